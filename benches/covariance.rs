@@ -0,0 +1,128 @@
+// Covariance and clustering inputs of the strategy engine at scale, against the previous per-pair Decimal computation:
+//     cargo bench --bench covariance
+use chrono::{DateTime, Duration, Utc};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use crypto_yield_farming_bot::data_ingestion::market::market_descriptor::MarketDescriptor;
+use crypto_yield_farming_bot::strategy::covariance::{self, ReturnMatrix};
+use crypto_yield_farming_bot::strategy::return_calculation_utils::{self, FillMethod};
+use crypto_yield_farming_bot::strategy::strategy_constants::CLUSTER_CORRELATION_THRESHOLD;
+use crypto_yield_farming_bot::strategy::types::MarketStateSlice;
+use ethers::types::Address;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::*;
+use std::hint::black_box;
+
+const OBSERVATIONS: usize = 10_000;
+const MARKET_COUNTS: [usize; 2] = [100, 150];
+const INTERVAL_MINUTES: i64 = 5;
+
+/// Markets whose index prices follow a shared factor plus their own noise, observed every 5 minutes
+fn market_slices(n_markets: usize) -> Vec<MarketStateSlice> {
+    let mut rng = StdRng::seed_from_u64(42);
+    let start = DateTime::<Utc>::from_timestamp(1_750_000_000, 0).unwrap();
+    let timestamps: Vec<DateTime<Utc>> = (0..OBSERVATIONS)
+        .map(|i| start + Duration::minutes(INTERVAL_MINUTES * i as i64))
+        .collect();
+    let factor: Vec<f64> = (0..OBSERVATIONS).map(|_| rng.random_range(-0.002..0.002)).collect();
+
+    (0..n_markets)
+        .map(|m| {
+            let beta = rng.random_range(0.0..1.5);
+            let mut price = rng.random_range(1.0..1000.0);
+            let index_prices: Vec<Decimal> = factor.iter()
+                .map(|f| {
+                    price *= 1.0 + beta * f + rng.random_range(-0.001..0.001);
+                    Decimal::from_f64(price).unwrap().round_dp(8)
+                })
+                .collect();
+            let market_address = Address::from_low_u64_be(m as u64 + 1);
+            MarketStateSlice {
+                market_address,
+                display_name: format!("M{}/USD [WETH - USDC]", m),
+                descriptor: MarketDescriptor {
+                    market_token: market_address,
+                    index_token: Address::from_low_u64_be(10_000 + m as u64),
+                    long_token: Address::from_low_u64_be(1),
+                    short_token: Address::from_low_u64_be(2),
+                    index_symbol: format!("M{}", m),
+                    long_symbol: "WETH".to_string(),
+                    short_symbol: "USDC".to_string(),
+                },
+                timestamps: timestamps.clone(),
+                fees_usd: vec![Decimal::ONE; OBSERVATIONS],
+                borrowing_fees_usd: vec![Decimal::ZERO; OBSERVATIONS],
+                trader_pnl_usd: vec![Decimal::ZERO; OBSERVATIONS],
+                gm_prices: vec![Decimal::ONE; OBSERVATIONS],
+                index_token_address: Address::from_low_u64_be(10_000 + m as u64),
+                index_token_symbol: format!("M{}", m),
+                index_prices,
+                index_token_timestamps: timestamps.clone(),
+                pnl_net: Decimal::ZERO,
+                pnl_long: Decimal::ZERO,
+                pnl_short: Decimal::ZERO,
+                oi_long: Decimal::from(3_000_000),
+                oi_short: Decimal::from(2_000_000),
+                oi_long_via_tokens: Decimal::from(3_000_000),
+                oi_short_via_tokens: Decimal::from(2_000_000),
+                oi_long_token_amount: Decimal::ZERO,
+                oi_short_token_amount: Decimal::ZERO,
+                pool_long_collateral_usd: Decimal::from(5_000_000),
+                pool_short_collateral_usd: Decimal::from(5_000_000),
+                pool_long_collateral_token_amount: Decimal::ZERO,
+                pool_short_collateral_token_amount: Decimal::ZERO,
+                impact_pool_usd: Decimal::ZERO,
+                impact_pool_token_amount: Decimal::ZERO,
+            }
+        })
+        .collect()
+}
+
+/// The previous path: Decimal returns and a Decimal sample covariance per market pair
+fn decimal_pairwise_covariance(market_slices: &[MarketStateSlice]) -> Vec<Vec<Decimal>> {
+    let series: Vec<(&[DateTime<Utc>], &[Decimal])> = market_slices.iter()
+        .map(|slice| (slice.index_token_timestamps.as_slice(), slice.index_prices.as_slice()))
+        .collect();
+    let returns = return_calculation_utils::aligned_returns(
+        &series,
+        Duration::minutes(INTERVAL_MINUTES),
+        FillMethod::ForwardFill,
+        Duration::minutes(3 * INTERVAL_MINUTES),
+    ).unwrap();
+    let n = Decimal::from(returns[0].len());
+    let means: Vec<Decimal> = returns.iter().map(|r| r.iter().sum::<Decimal>() / n).collect();
+    (0..returns.len())
+        .map(|i| (0..returns.len())
+            .map(|j| returns[i].iter().zip(&returns[j])
+                .map(|(x, y)| (x - means[i]) * (y - means[j]))
+                .sum::<Decimal>() / (n - Decimal::ONE))
+            .collect())
+        .collect()
+}
+
+fn bench_covariance(c: &mut Criterion) {
+    let mut group = c.benchmark_group("covariance");
+    group.sample_size(10);
+    for n_markets in MARKET_COUNTS {
+        let slices = market_slices(n_markets);
+        let mut return_matrix = ReturnMatrix::from_slices(&slices).unwrap();
+
+        group.bench_with_input(BenchmarkId::new("decimal_pairwise", n_markets), &slices, |b, slices| {
+            b.iter(|| decimal_pairwise_covariance(black_box(slices)))
+        });
+        group.bench_with_input(BenchmarkId::new("return_matrix_rebuild", n_markets), &slices, |b, slices| {
+            b.iter(|| return_matrix.rebuild(black_box(slices)))
+        });
+        group.bench_with_input(BenchmarkId::new("covariance_matrix", n_markets), &slices, |b, slices| {
+            b.iter(|| covariance::calculate_covariance_matrix(black_box(slices), &return_matrix))
+        });
+        group.bench_with_input(BenchmarkId::new("cluster_markets", n_markets), &slices, |b, _| {
+            b.iter(|| covariance::cluster_markets_by_correlation(black_box(&return_matrix), CLUSTER_CORRELATION_THRESHOLD))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_covariance);
+criterion_main!(benches);
@@ -0,0 +1,117 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+// Receives GMX deposit, withdrawal and shift execution/cancellation callbacks and re-emits them as a single
+// normalized event, so the bot learns of a request's outcome from one log instead of polling the request lists.
+// Callback signatures follow the GMX v2.2 callback receiver interfaces (request and event data as EventLogData).
+//
+// Build: solc --optimize --bin --abi contracts/GmxCallbackReceiver.sol -o contracts/out
+// Deploy: cargo run --bin deploy_gmx_callback
+
+library EventUtils {
+    struct EventLogData {
+        AddressItems addressItems;
+        UintItems uintItems;
+        IntItems intItems;
+        BoolItems boolItems;
+        Bytes32Items bytes32Items;
+        BytesItems bytesItems;
+        StringItems stringItems;
+    }
+
+    struct AddressItems { AddressKeyValue[] items; AddressArrayKeyValue[] arrayItems; }
+    struct UintItems { UintKeyValue[] items; UintArrayKeyValue[] arrayItems; }
+    struct IntItems { IntKeyValue[] items; IntArrayKeyValue[] arrayItems; }
+    struct BoolItems { BoolKeyValue[] items; BoolArrayKeyValue[] arrayItems; }
+    struct Bytes32Items { Bytes32KeyValue[] items; Bytes32ArrayKeyValue[] arrayItems; }
+    struct BytesItems { BytesKeyValue[] items; BytesArrayKeyValue[] arrayItems; }
+    struct StringItems { StringKeyValue[] items; StringArrayKeyValue[] arrayItems; }
+
+    struct AddressKeyValue { string key; address value; }
+    struct AddressArrayKeyValue { string key; address[] value; }
+    struct UintKeyValue { string key; uint256 value; }
+    struct UintArrayKeyValue { string key; uint256[] value; }
+    struct IntKeyValue { string key; int256 value; }
+    struct IntArrayKeyValue { string key; int256[] value; }
+    struct BoolKeyValue { string key; bool value; }
+    struct BoolArrayKeyValue { string key; bool[] value; }
+    struct Bytes32KeyValue { string key; bytes32 value; }
+    struct Bytes32ArrayKeyValue { string key; bytes32[] value; }
+    struct BytesKeyValue { string key; bytes value; }
+    struct BytesArrayKeyValue { string key; bytes[] value; }
+    struct StringKeyValue { string key; string value; }
+    struct StringArrayKeyValue { string key; string[] value; }
+}
+
+interface IRoleStore {
+    function hasRole(address account, bytes32 roleKey) external view returns (bool);
+}
+
+contract GmxCallbackReceiver {
+    uint8 public constant REQUEST_TYPE_DEPOSIT = 0;
+    uint8 public constant REQUEST_TYPE_WITHDRAWAL = 1;
+    uint8 public constant REQUEST_TYPE_SHIFT = 2;
+
+    // GMX handlers executing requests hold the CONTROLLER role
+    bytes32 public constant CONTROLLER = keccak256(abi.encode("CONTROLLER"));
+
+    IRoleStore public immutable roleStore;
+    address public immutable owner;
+
+    /// Emitted once per callback: executed is false when the request was cancelled
+    event RequestSettled(bytes32 indexed key, uint8 indexed requestType, bool executed);
+
+    error Unauthorized(address caller);
+
+    constructor(address _roleStore, address _owner) {
+        roleStore = IRoleStore(_roleStore);
+        owner = _owner;
+    }
+
+    modifier onlyController() {
+        if (!roleStore.hasRole(msg.sender, CONTROLLER)) {
+            revert Unauthorized(msg.sender);
+        }
+        _;
+    }
+
+    function afterDepositExecution(bytes32 key, EventUtils.EventLogData memory, EventUtils.EventLogData memory) external onlyController {
+        emit RequestSettled(key, REQUEST_TYPE_DEPOSIT, true);
+    }
+
+    function afterDepositCancellation(bytes32 key, EventUtils.EventLogData memory, EventUtils.EventLogData memory) external onlyController {
+        emit RequestSettled(key, REQUEST_TYPE_DEPOSIT, false);
+    }
+
+    function afterWithdrawalExecution(bytes32 key, EventUtils.EventLogData memory, EventUtils.EventLogData memory) external onlyController {
+        emit RequestSettled(key, REQUEST_TYPE_WITHDRAWAL, true);
+    }
+
+    function afterWithdrawalCancellation(bytes32 key, EventUtils.EventLogData memory, EventUtils.EventLogData memory) external onlyController {
+        emit RequestSettled(key, REQUEST_TYPE_WITHDRAWAL, false);
+    }
+
+    function afterShiftExecution(bytes32 key, EventUtils.EventLogData memory, EventUtils.EventLogData memory) external onlyController {
+        emit RequestSettled(key, REQUEST_TYPE_SHIFT, true);
+    }
+
+    function afterShiftCancellation(bytes32 key, EventUtils.EventLogData memory, EventUtils.EventLogData memory) external onlyController {
+        emit RequestSettled(key, REQUEST_TYPE_SHIFT, false);
+    }
+
+    /// Execution fee refunds routed to the callback contract are passed on to the owner
+    function refundExecutionFee(bytes32, EventUtils.EventLogData memory) external payable {
+        _forwardToOwner();
+    }
+
+    receive() external payable {
+        _forwardToOwner();
+    }
+
+    function _forwardToOwner() internal {
+        if (msg.value > 0) {
+            (bool success, ) = owner.call{ value: msg.value }("");
+            require(success, "Refund forwarding failed");
+        }
+    }
+}
@@ -1,11 +1,23 @@
+use ethers::abi::Abi;
 use eyre::Result;
 use reqwest::Client;
 use serde::Deserialize;
-use std::{fs::File, io::Write, path::Path};
-use tracing::{error, info, instrument};
+use std::{collections::BTreeSet, fs::File, io::Write, path::Path, time::Duration};
+use tracing::{error, info, warn, instrument};
 
 use crate::config::Config;
 
+/// Timeout for a single ABI request, a slow source must not hold up startup
+const ABI_FETCH_TIMEOUT_SECS: u64 = 15;
+
+/// ABIs the contract bindings are generated from, embedded in the binary so they are available without network access
+const EMBEDDED_ABIS: &[(&str, &str)] = &[
+    ("Reader", include_str!("../abis/Reader.json")),
+    ("DataStore", include_str!("../abis/DataStore.json")),
+    ("EventEmitter", include_str!("../abis/EventEmitter.json")),
+    ("ExchangeRouter", include_str!("../abis/ExchangeRouter.json")),
+];
+
 #[derive(Debug, Deserialize)]
 struct AbiV2Response {
     status: String,
@@ -13,13 +25,39 @@ struct AbiV2Response {
     result: String, // now holds the ABI JSON string
 }
 
-/// Fetches ABI for a given contract address from Etherscan V2 and saves to the specified path
-pub async fn fetch_abi_v2(
-    config: &Config,
-    contract_address: &str,
-    output_path: &Path,
-) -> Result<()> {
-    let client = Client::new();
+/// Embedded ABI JSON of a contract, None for contracts without one
+pub fn embedded_abi(name: &str) -> Option<&'static str> {
+    EMBEDDED_ABIS.iter().find(|(abi_name, _)| *abi_name == name).map(|(_, abi)| *abi)
+}
+
+/// Canonical signatures of an ABI's functions (outputs included) and events (indexed inputs marked),
+/// so parameter names, internal types and formatting don't count
+fn fragment_signatures(abi: &Abi) -> BTreeSet<String> {
+    let functions = abi.functions().map(|function| format!("function {}", function.signature()));
+    let events = abi.events().map(|event| {
+        let inputs: Vec<String> = event.inputs.iter()
+            .map(|input| if input.indexed { format!("{} indexed", input.kind) } else { input.kind.to_string() })
+            .collect();
+        format!("event {}({})", event.name, inputs.join(","))
+    });
+    functions.chain(events).collect()
+}
+
+/// Signatures of the embedded ABI's fragments that the fetched ABI lacks or declares differently. Embedded ABIs are
+/// trimmed to the fragments the bindings use, so fragments only present upstream don't count.
+pub fn embedded_fragment_mismatches(embedded_json: &str, fetched_json: &str) -> Result<Vec<String>> {
+    let embedded: Abi = serde_json::from_str(embedded_json)?;
+    let fetched: Abi = serde_json::from_str(fetched_json)?;
+    let fetched_signatures = fragment_signatures(&fetched);
+    Ok(fragment_signatures(&embedded)
+        .into_iter()
+        .filter(|signature| !fetched_signatures.contains(signature))
+        .collect())
+}
+
+/// Fetches ABI for a given contract address from Etherscan V2, validated as an ABI, returning its JSON
+pub async fn fetch_abi_v2(config: &Config, contract_address: &str) -> Result<String> {
+    let client = Client::builder().timeout(Duration::from_secs(ABI_FETCH_TIMEOUT_SECS)).build()?;
     let url = format!(
         "https://api.etherscan.io/v2/api?chainid={}&module=contract&action=getabi&address={}&apikey={}",
         config.chain_id, contract_address, config.etherscan_api_key
@@ -33,32 +71,104 @@ pub async fn fetch_abi_v2(
     }
 
     let abi_json_raw = response.result;
-    let abi_json = serde_json::from_str::<serde_json::Value>(&abi_json_raw)?
-        .to_string();
-
-    let mut file = File::create(output_path)?;
-    file.write_all(abi_json.as_bytes())?;
-
-    Ok(())
+    serde_json::from_str::<Abi>(&abi_json_raw)
+        .map_err(|e| eyre::eyre!("Fetched ABI for {} is not a valid ABI: {}", contract_address, e))?;
+    Ok(serde_json::from_str::<serde_json::Value>(&abi_json_raw)?.to_string())
 }
 
-/// Downloads all ABIs needed for the project if `REFRESH_ABIS=true` in .env
+/// Check the embedded ABIs for upstream updates if `REFETCH_ABIS=true` in .env (never with `ABI_OFFLINE=true`).
+/// The bindings are always generated from the embedded ABIs, so a fetch failing only loses the update check. A fetched
+/// ABI missing one of the embedded fragments, or declaring it differently, is saved to fetched_abis/ for review.
+/// Returns the names of the contracts whose ABI changed upstream.
 #[instrument(skip(config))]
-pub async fn fetch_all_abis(config: &Config) -> Result<()> {
+pub async fn fetch_all_abis(config: &Config) -> Result<Vec<&'static str>> {
     let abis = vec![
         ("Reader", config.gmx_reader),
         ("DataStore", config.gmx_datastore),
-        ("EventEmitter", config.gmx_eventemitter)
+        ("EventEmitter", config.gmx_eventemitter),
+        ("ExchangeRouter", config.gmx_exchangerouter),
     ];
 
-    if config.refetch_abis {
-        for (name, address) in abis {
-            let path = Path::new("fetched_abis").join(format!("{}.json", name));
-            fetch_abi_v2(config, &format!("{:?}", address), &path).await?;
-        }
-    } else {
+    if config.abi_offline {
+        info!("ABI_OFFLINE is true, using embedded ABIs without checking for updates");
+        return Ok(Vec::new());
+    }
+    if !config.refetch_abis {
         info!("REFETCH_ABIS is false, skipping ABI fetch");
+        return Ok(Vec::new());
     }
 
-    Ok(())
-}
\ No newline at end of file
+    let mut updated = Vec::new();
+    for (name, address) in abis {
+        let embedded = embedded_abi(name).ok_or_else(|| eyre::eyre!("No embedded ABI for {}", name))?;
+        let fetched = match fetch_abi_v2(config, &format!("{:?}", address)).await {
+            Ok(abi_json) => abi_json,
+            Err(e) => {
+                warn!(contract = name, error = ?e, "Failed to fetch ABI, using the embedded ABI");
+                continue;
+            }
+        };
+
+        let mismatches = embedded_fragment_mismatches(embedded, &fetched)
+            .map_err(|e| eyre::eyre!("Failed to compare the embedded {} ABI: {}", name, e))?;
+        if mismatches.is_empty() {
+            info!(contract = name, "Embedded ABI fragments match upstream");
+            continue;
+        }
+
+        let path = Path::new("fetched_abis").join(format!("{}.json", name));
+        std::fs::create_dir_all("fetched_abis")?;
+        let mut file = File::create(&path)?;
+        file.write_all(fetched.as_bytes())?;
+        warn!(
+            contract = name,
+            mismatches = ?mismatches,
+            path = %path.display(),
+            "ABI changed upstream, embedded ABI kept until the update is reviewed"
+        );
+        updated.push(name);
+    }
+
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UPSTREAM: &str = r#"[
+        {"type": "function", "name": "getUint", "stateMutability": "view",
+         "inputs": [{"name": "key", "type": "bytes32"}], "outputs": [{"name": "", "type": "uint256"}]},
+        {"type": "function", "name": "setUint", "stateMutability": "nonpayable",
+         "inputs": [{"name": "key", "type": "bytes32"}, {"name": "value", "type": "uint256"}], "outputs": [{"name": "", "type": "uint256"}]},
+        {"type": "event", "name": "EventLog1", "anonymous": false,
+         "inputs": [{"name": "eventName", "type": "string", "indexed": false}, {"name": "topic1", "type": "bytes32", "indexed": true}]}
+    ]"#;
+
+    #[test]
+    fn trimmed_embedded_abi_matches_full_upstream_abi() {
+        let embedded = r#"[
+            {"type": "function", "name": "getUint", "stateMutability": "view",
+             "inputs": [{"name": "k", "internalType": "bytes32", "type": "bytes32"}], "outputs": [{"name": "", "type": "uint256"}]}
+        ]"#;
+        assert!(embedded_fragment_mismatches(embedded, UPSTREAM).unwrap().is_empty());
+    }
+
+    #[test]
+    fn changed_or_missing_fragments_are_reported() {
+        let embedded = r#"[
+            {"type": "function", "name": "getUint", "stateMutability": "view",
+             "inputs": [{"name": "key", "type": "bytes32"}], "outputs": [{"name": "", "type": "int256"}]},
+            {"type": "function", "name": "getAddress", "stateMutability": "view",
+             "inputs": [{"name": "key", "type": "bytes32"}], "outputs": [{"name": "", "type": "address"}]},
+            {"type": "event", "name": "EventLog1", "anonymous": false,
+             "inputs": [{"name": "eventName", "type": "string", "indexed": false}, {"name": "topic1", "type": "bytes32", "indexed": false}]}
+        ]"#;
+        let mismatches = embedded_fragment_mismatches(embedded, UPSTREAM).unwrap();
+        assert_eq!(mismatches, vec![
+            "event EventLog1(string,bytes32)".to_string(),
+            "function getAddress(bytes32):(address)".to_string(),
+            "function getUint(bytes32):(int256)".to_string(),
+        ]);
+    }
+}
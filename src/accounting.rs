@@ -0,0 +1,389 @@
+use chrono::{DateTime, Utc};
+use eyre::Result;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use tracing::{debug, info, warn, instrument};
+
+use crate::db::db_manager::DbManager;
+use crate::db::models::markets::MarketModel;
+use crate::db::models::trades::{TradeModel, TradeActionType, TradeStatus};
+use crate::db::models::execution_costs::ExecutionVenue;
+use crate::db::models::rebalances::RebalanceCostModel;
+
+/// CSV column layout of an accounting export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Generic, // One row per asset leg with its USD value at execution (cost-basis ledger)
+    Koinly,  // Koinly universal import layout
+}
+
+impl ExportFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportFormat::Generic => "generic",
+            ExportFormat::Koinly => "koinly",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "generic" => Some(ExportFormat::Generic),
+            "koinly" => Some(ExportFormat::Koinly),
+            _ => None,
+        }
+    }
+}
+
+/// One asset leg of a settled trade. Deposits and withdrawals move two collateral tokens
+/// and are split into one entry per token so each leg carries its own cost basis.
+#[derive(Debug, Clone)]
+pub struct AccountingEntry {
+    pub timestamp: DateTime<Utc>,
+    pub venue: String,
+    pub action_type: TradeActionType,
+    pub sent_amount: Option<Decimal>,
+    pub sent_currency: Option<String>,
+    pub received_amount: Option<Decimal>,
+    pub received_currency: Option<String>,
+    pub usd_value: Option<Decimal>,
+    pub fee_usd: Decimal, // Gas + execution fee, attributed to the first leg of the trade
+    pub tx_hash: Option<String>,
+    pub description: String,
+}
+
+/// Build accounting entries for all settled trades created within the time range.
+/// Only the sent side of GM requests is recorded, so received amounts are estimated
+/// from GM prices, token prices and pool composition at execution.
+#[instrument(skip(db_manager), fields(on_close = true))]
+pub async fn build_trade_entries(
+    db_manager: &DbManager,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Vec<AccountingEntry>> {
+    let symbols: HashMap<i32, String> = db_manager.get_all_tokens().await?
+        .into_iter()
+        .map(|token| (token.id, token.symbol))
+        .collect();
+    let markets: HashMap<i32, MarketModel> = db_manager.get_all_markets().await?
+        .into_iter()
+        .map(|market| (market.id, market))
+        .collect();
+    // Costs are recorded when requests settle, which can be after the range ends
+    let fees = db_manager.get_execution_costs_by_tx_in_range(start, db_manager.clock.now()).await?;
+
+    let trades = db_manager.get_trades_in_range(start, end).await?;
+    let mut entries = Vec::with_capacity(trades.len());
+    for trade in trades.iter().filter(|t| t.status == TradeStatus::Settled.as_str()) {
+        let Some(action_type) = TradeActionType::parse(&trade.action_type) else {
+            warn!(trade_id = trade.id, action_type = %trade.action_type, "Skipping trade with unknown action type");
+            continue;
+        };
+        let market = trade.market_id.and_then(|id| markets.get(&id));
+        let mut trade_entries = match (action_type, market) {
+            (TradeActionType::GmDeposit, Some(market)) => deposit_entries(db_manager, trade, market, &symbols).await?,
+            (TradeActionType::GmWithdrawal, Some(market)) => withdrawal_entries(db_manager, trade, market, &symbols).await?,
+            (TradeActionType::GmShift, Some(market)) => {
+                let to_market = trade.to_market_id.and_then(|id| markets.get(&id));
+                shift_entries(db_manager, trade, market, to_market, &symbols).await?
+            }
+            (TradeActionType::ClaimRewards, _) => vec![AccountingEntry {
+                received_currency: Some("ARB".to_string()),
+                description: "GM pool incentive claim (claimed amount not recorded)".to_string(),
+                ..entry(trade, action_type)
+            }],
+            (TradeActionType::ClaimUiFees, _) => vec![AccountingEntry {
+                description: "GMX UI fee claim (claimed amounts not recorded)".to_string(),
+                ..entry(trade, action_type)
+            }],
+            // Moves collateral between our own accounts, not a disposal
+            (TradeActionType::BridgeCollateral, _) => continue,
+            (_, None) => {
+                warn!(trade_id = trade.id, market_id = ?trade.market_id, "Skipping trade with unknown market");
+                continue;
+            }
+        };
+
+        let fee_usd = trade.tx_hash.as_ref()
+            .and_then(|tx_hash| fees.get(tx_hash))
+            .copied()
+            .or(trade.gas_cost_usd)
+            .unwrap_or_default();
+        if let Some(first) = trade_entries.first_mut() {
+            first.fee_usd = fee_usd;
+        }
+        entries.extend(trade_entries);
+    }
+    debug!(trades = trades.len(), entries = entries.len(), "Built accounting entries");
+    Ok(entries)
+}
+
+/// Render entries as CSV in the given layout
+pub fn to_csv(entries: &[AccountingEntry], format: ExportFormat) -> String {
+    let mut lines = Vec::with_capacity(entries.len() + 1);
+    match format {
+        ExportFormat::Generic => {
+            lines.push("timestamp,venue,action,sent_amount,sent_currency,received_amount,received_currency,usd_value,fee_usd,tx_hash,description".to_string());
+            for e in entries {
+                lines.push(csv_row(&[
+                    e.timestamp.to_rfc3339(),
+                    e.venue.clone(),
+                    e.action_type.as_str().to_string(),
+                    format_amount(e.sent_amount),
+                    e.sent_currency.clone().unwrap_or_default(),
+                    format_amount(e.received_amount),
+                    e.received_currency.clone().unwrap_or_default(),
+                    format_usd(e.usd_value),
+                    format_usd(Some(e.fee_usd)),
+                    e.tx_hash.clone().unwrap_or_default(),
+                    e.description.clone(),
+                ]));
+            }
+        }
+        ExportFormat::Koinly => {
+            lines.push("Date,Sent Amount,Sent Currency,Received Amount,Received Currency,Fee Amount,Fee Currency,Net Worth Amount,Net Worth Currency,Label,Description,TxHash".to_string());
+            for e in entries {
+                let label = match e.action_type {
+                    TradeActionType::GmDeposit => "liquidity in",
+                    TradeActionType::GmWithdrawal => "liquidity out",
+                    TradeActionType::GmShift => "",
+                    TradeActionType::ClaimRewards => "reward",
+                    TradeActionType::ClaimUiFees => "cashback",
+                    TradeActionType::BridgeCollateral => "",
+                };
+                let has_fee = e.fee_usd > Decimal::ZERO;
+                lines.push(csv_row(&[
+                    e.timestamp.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+                    format_amount(e.sent_amount),
+                    e.sent_currency.clone().unwrap_or_default(),
+                    format_amount(e.received_amount),
+                    e.received_currency.clone().unwrap_or_default(),
+                    if has_fee { format_usd(Some(e.fee_usd)) } else { String::new() },
+                    if has_fee { "USD".to_string() } else { String::new() },
+                    format_usd(e.usd_value),
+                    if e.usd_value.is_some() { "USD".to_string() } else { String::new() },
+                    label.to_string(),
+                    format!("{} ({})", e.description, e.venue),
+                    e.tx_hash.clone().unwrap_or_default(),
+                ]));
+            }
+        }
+    }
+    lines.join("\n") + "\n"
+}
+
+// --- HELPERS ---
+
+/// Deposit: one entry per collateral token sent, GM received estimated from the leg's USD value
+async fn deposit_entries(
+    db_manager: &DbManager,
+    trade: &TradeModel,
+    market: &MarketModel,
+    symbols: &HashMap<i32, String>,
+) -> Result<Vec<AccountingEntry>> {
+    let gm_price = db_manager.get_gm_price_at(market.id, trade.created_at).await?.map(|(_, price)| price);
+    let mut entries = Vec::new();
+    for (token_id, amount) in [(market.long_token_id, trade.long_token_amount), (market.short_token_id, trade.short_token_amount)] {
+        let Some(amount) = amount.filter(|a| *a > Decimal::ZERO) else {
+            continue;
+        };
+        let price = db_manager.get_token_price_at_or_before(token_id, trade.created_at).await?;
+        let usd_value = price.map(|p| amount * p);
+        entries.push(AccountingEntry {
+            sent_amount: Some(amount),
+            sent_currency: Some(token_symbol(symbols, token_id)),
+            received_amount: divide(usd_value, gm_price),
+            received_currency: Some(gm_symbol(market, symbols)),
+            usd_value,
+            description: "GM deposit (GM received estimated from the GM price at execution)".to_string(),
+            ..entry(trade, TradeActionType::GmDeposit)
+        });
+    }
+    Ok(entries)
+}
+
+/// Withdrawal: GM sent is split across the collateral tokens by pool composition at execution
+async fn withdrawal_entries(
+    db_manager: &DbManager,
+    trade: &TradeModel,
+    market: &MarketModel,
+    symbols: &HashMap<i32, String>,
+) -> Result<Vec<AccountingEntry>> {
+    let gm_amount = trade.market_token_amount.unwrap_or_default();
+    let gm_price = db_manager.get_gm_price_at(market.id, trade.created_at).await?.map(|(_, price)| price);
+    let usd_value = gm_price.map(|p| gm_amount * p);
+
+    let composition = db_manager.get_pool_composition_at(market.id, trade.created_at).await?
+        .filter(|(long_usd, short_usd)| *long_usd + *short_usd > Decimal::ZERO);
+    let Some((long_usd, short_usd)) = composition else {
+        return Ok(vec![AccountingEntry {
+            sent_amount: Some(gm_amount),
+            sent_currency: Some(gm_symbol(market, symbols)),
+            usd_value,
+            description: "GM withdrawal (pool composition unknown, tokens received not estimated)".to_string(),
+            ..entry(trade, TradeActionType::GmWithdrawal)
+        }]);
+    };
+
+    let mut entries = Vec::new();
+    for (token_id, share) in [
+        (market.long_token_id, long_usd / (long_usd + short_usd)),
+        (market.short_token_id, short_usd / (long_usd + short_usd)),
+    ] {
+        if share.is_zero() {
+            continue;
+        }
+        let leg_usd = usd_value.map(|v| v * share);
+        let price = db_manager.get_token_price_at_or_before(token_id, trade.created_at).await?;
+        entries.push(AccountingEntry {
+            sent_amount: Some(gm_amount * share),
+            sent_currency: Some(gm_symbol(market, symbols)),
+            received_amount: divide(leg_usd, price),
+            received_currency: Some(token_symbol(symbols, token_id)),
+            usd_value: leg_usd,
+            description: "GM withdrawal (tokens received estimated from pool composition at execution)".to_string(),
+            ..entry(trade, TradeActionType::GmWithdrawal)
+        });
+    }
+    Ok(entries)
+}
+
+/// Shift: GM of one market swapped for GM of another at their prices at execution
+async fn shift_entries(
+    db_manager: &DbManager,
+    trade: &TradeModel,
+    from_market: &MarketModel,
+    to_market: Option<&MarketModel>,
+    symbols: &HashMap<i32, String>,
+) -> Result<Vec<AccountingEntry>> {
+    let gm_amount = trade.market_token_amount.unwrap_or_default();
+    let from_price = db_manager.get_gm_price_at(from_market.id, trade.created_at).await?.map(|(_, price)| price);
+    let usd_value = from_price.map(|p| gm_amount * p);
+    let (received_amount, received_currency) = match to_market {
+        Some(to_market) => {
+            let to_price = db_manager.get_gm_price_at(to_market.id, trade.created_at).await?.map(|(_, price)| price);
+            (divide(usd_value, to_price), Some(gm_symbol(to_market, symbols)))
+        }
+        None => (None, None),
+    };
+    Ok(vec![AccountingEntry {
+        sent_amount: Some(gm_amount),
+        sent_currency: Some(gm_symbol(from_market, symbols)),
+        received_amount,
+        received_currency,
+        usd_value,
+        description: "GM shift (GM received estimated from GM prices at execution)".to_string(),
+        ..entry(trade, TradeActionType::GmShift)
+    }])
+}
+
+/// Log each rebalance's net cost broken down by leg (swap slippage, GM price impact, execution fees, gas and hedge
+/// slippage) and the totals across the rebalances, so it shows where money leaks
+pub fn log_rebalance_cost_report(costs: &[RebalanceCostModel]) {
+    if costs.is_empty() {
+        info!("No rebalances to report");
+        return;
+    }
+
+    let rebalance_summary = costs.iter()
+        .map(|c| format!(
+            "#{} {} at {}{}: Total=${:.2} | SwapSlippage=${:.2}, GmImpact=${:.2}, ExecutionFees=${:.2}, Gas=${:.2}, HedgeSlippage=${:.2} | Txs={}, Orders={}{}",
+            c.rebalance_id,
+            c.source,
+            c.created_at.format("%Y-%m-%d %H:%M"),
+            if c.completed_at.is_none() { " (incomplete)" } else { "" },
+            c.total_usd(),
+            c.swap_slippage_usd,
+            c.gm_impact_usd,
+            c.execution_fee_usd,
+            c.gas_cost_usd,
+            c.hedge_slippage_usd,
+            c.tx_count,
+            c.order_count,
+            if c.unpriced_order_count > 0 { format!(" ({} unpriced)", c.unpriced_order_count) } else { String::new() },
+        ))
+        .collect::<Vec<_>>()
+        .join("\n  ");
+
+    let sum = |f: fn(&RebalanceCostModel) -> Decimal| costs.iter().map(f).sum::<Decimal>();
+    let total_usd = sum(RebalanceCostModel::total_usd);
+    let share = |amount: Decimal| if total_usd.is_zero() { Decimal::ZERO } else { amount / total_usd * Decimal::ONE_HUNDRED };
+    let leg_totals = [
+        ("SwapSlippage", sum(|c| c.swap_slippage_usd)),
+        ("GmImpact", sum(|c| c.gm_impact_usd)),
+        ("ExecutionFees", sum(|c| c.execution_fee_usd)),
+        ("Gas", sum(|c| c.gas_cost_usd)),
+        ("HedgeSlippage", sum(|c| c.hedge_slippage_usd)),
+    ];
+    let totals_summary = leg_totals.iter()
+        .map(|(leg, amount)| format!("{}=${:.2} ({:.1}%)", leg, amount, share(*amount)))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    info!(
+        rebalance_count = costs.len(),
+        total_usd = %total_usd.round_dp(2),
+        "Rebalance Costs:\n  {}\n\nTotal ${:.2}: {}",
+        rebalance_summary,
+        total_usd,
+        totals_summary
+    );
+}
+
+/// Entry with the trade's common fields and no asset legs
+fn entry(trade: &TradeModel, action_type: TradeActionType) -> AccountingEntry {
+    AccountingEntry {
+        timestamp: trade.created_at,
+        venue: ExecutionVenue::Gmx.as_str().to_string(),
+        action_type,
+        sent_amount: None,
+        sent_currency: None,
+        received_amount: None,
+        received_currency: None,
+        usd_value: None,
+        fee_usd: Decimal::ZERO,
+        tx_hash: trade.tx_hash.clone(),
+        description: String::new(),
+    }
+}
+
+fn token_symbol(symbols: &HashMap<i32, String>, token_id: i32) -> String {
+    symbols.get(&token_id).cloned().unwrap_or_else(|| format!("TOKEN_{}", token_id))
+}
+
+/// GM token symbol in the same format the wallet uses for market tokens
+fn gm_symbol(market: &MarketModel, symbols: &HashMap<i32, String>) -> String {
+    format!(
+        "GM_{}/USD_[{}-{}]",
+        token_symbol(symbols, market.index_token_id),
+        token_symbol(symbols, market.long_token_id),
+        token_symbol(symbols, market.short_token_id)
+    )
+}
+
+fn divide(value: Option<Decimal>, price: Option<Decimal>) -> Option<Decimal> {
+    match (value, price) {
+        (Some(value), Some(price)) if price > Decimal::ZERO => Some(value / price),
+        _ => None,
+    }
+}
+
+fn format_amount(amount: Option<Decimal>) -> String {
+    amount.map(|a| a.round_dp(18).normalize().to_string()).unwrap_or_default()
+}
+
+fn format_usd(amount: Option<Decimal>) -> String {
+    amount.map(|a| format!("{:.2}", a)).unwrap_or_default()
+}
+
+fn csv_row(fields: &[String]) -> String {
+    fields.iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
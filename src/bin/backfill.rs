@@ -0,0 +1,65 @@
+use dotenvy::dotenv;
+use tracing::{instrument, info};
+use std::time::Duration;
+
+use crypto_yield_farming_bot::logging;
+use crypto_yield_farming_bot::config;
+use crypto_yield_farming_bot::db::db_manager::DbManager;
+use crypto_yield_farming_bot::data_ingestion::backfill;
+use crypto_yield_farming_bot::data_ingestion::market::liquidations;
+use crypto_yield_farming_bot::gmx::event_fetcher::GmxEventFetcher;
+
+const USAGE: &str = "Usage: backfill [days]";
+
+const DEFAULT_BACKFILL_DAYS: i64 = 90;
+
+/// Pull historical hourly pool fees (GMX stats subgraph) and token prices (GMX API candles) for every tracked
+/// market and token, inserting them before the earliest collected data so the strategy has history immediately,
+/// and the liquidations of every tracked market (EventEmitter logs) over the same window.
+/// Markets and tokens must already be recorded by the data collector.
+#[instrument(name = "backfill_main")]
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    // Load environment variables from .env file
+    dotenv()?;
+
+    // Initialize logging
+    if let Err(e) = logging::init_logging(env!("CARGO_BIN_NAME").to_string()) {
+        eprintln!("Failed to initialize logging: {}", e);
+        return Err(e.into());
+    }
+
+    // Load configuration (including provider)
+    let cfg = config::Config::load().await;
+    info!(network_mode = %cfg.network_mode, "Configuration loaded and logging initialized");
+
+    // Parse arguments
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let days = match args.first() {
+        Some(arg) => arg.parse::<i64>()
+            .ok()
+            .filter(|days| *days > 0)
+            .ok_or_else(|| eyre::eyre!("Invalid backfill days: {}\n{}", arg, USAGE))?,
+        None => DEFAULT_BACKFILL_DAYS,
+    };
+
+    // Initialize db manager
+    let db = DbManager::init(&cfg).await?;
+    info!("Database manager initialized");
+
+    let since = db.clock.now() - chrono::Duration::days(days);
+    let prices_inserted = backfill::backfill_token_prices(&cfg, &db, since).await?;
+    let states_inserted = backfill::backfill_market_states(&cfg, &db, since).await?;
+    let mut event_fetcher = GmxEventFetcher::init(cfg.alchemy_provider.clone(), cfg.gmx_eventemitter, cfg.event_finality);
+    let liquidations_inserted = liquidations::sync_liquidations(&cfg, &db, &mut event_fetcher, since).await?;
+    info!(
+        days = days,
+        prices_inserted = prices_inserted,
+        states_inserted = states_inserted,
+        liquidations_inserted = liquidations_inserted,
+        "Historical backfill completed"
+    );
+
+    tokio::time::sleep(Duration::from_secs(1)).await; // Allow time for logging to flush
+    Ok(())
+}
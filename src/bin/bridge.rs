@@ -0,0 +1,76 @@
+use dotenvy::dotenv;
+use tracing::{instrument, info};
+use std::sync::Arc;
+use rust_decimal::Decimal;
+
+use crypto_yield_farming_bot::logging;
+use crypto_yield_farming_bot::config;
+use crypto_yield_farming_bot::wallet::WalletManager;
+use crypto_yield_farming_bot::db::db_manager::DbManager;
+use crypto_yield_farming_bot::hedging::dydx_client::DydxClient;
+use crypto_yield_farming_bot::bridging::{self, BridgeCollateralRequest, BridgeDirection};
+
+const USAGE: &str = "Usage: bridge <to-dydx <amount> | to-arbitrum <amount> | track>";
+
+/// Move USDC collateral between the Arbitrum wallet and the dYdX subaccount and wait for it to arrive,
+/// or (with `track`) finish tracking the transfers an earlier run left pending.
+#[instrument(name = "bridge_main")]
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    // Load environment variables from .env file
+    dotenv()?;
+
+    // Initialize logging
+    if let Err(e) = logging::init_logging(env!("CARGO_BIN_NAME").to_string()) {
+        eprintln!("Failed to initialize logging: {}", e);
+        return Err(e.into());
+    }
+
+    // Parse the command before connecting to anything
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let parse_amount = |s: Option<&String>| -> eyre::Result<Decimal> {
+        let s = s.ok_or_else(|| eyre::eyre!(USAGE))?;
+        s.parse::<Decimal>().map_err(|_| eyre::eyre!("Invalid amount: {}\n{}", s, USAGE))
+    };
+    let request = match args.first().map(String::as_str) {
+        Some("to-dydx") => Some((BridgeDirection::ArbitrumToDydx, parse_amount(args.get(1))?)),
+        Some("to-arbitrum") => Some((BridgeDirection::DydxToArbitrum, parse_amount(args.get(1))?)),
+        Some("track") => None,
+        _ => return Err(eyre::eyre!(USAGE)),
+    };
+
+    // Load configuration (including provider)
+    let cfg = config::Config::load().await;
+    info!(network_mode = %cfg.network_mode, "Configuration loaded and logging initialized");
+
+    // Initialize db manager
+    let db = DbManager::init(&cfg).await?;
+    let db = Arc::new(db);
+    info!("Database manager initialized");
+
+    // Initialize and load wallet manager
+    let wallet_manager = WalletManager::new(&cfg)?;
+    wallet_manager.load_tokens(&db).await?;
+    let wallet_manager = Arc::new(wallet_manager);
+    info!("Wallet manager initialized and tokens loaded");
+
+    let mut dydx_client = DydxClient::new(cfg.clone(), wallet_manager.clone(), db.clone()).await?;
+    info!("dYdX client initialized successfully");
+
+    match request {
+        Some((direction, amount)) => {
+            let request = BridgeCollateralRequest { direction, amount, rebalance_id: cfg.rebalance_id };
+            let transfer_id = bridging::bridge_collateral(&mut dydx_client, &db, &request).await?;
+            let status = bridging::wait_for_arrival(&mut dydx_client, &db, transfer_id).await?;
+            info!(bridge_transfer_id = transfer_id, status = status.as_str(), "Bridge transfer finished");
+        }
+        None => {
+            let pending = bridging::track_pending_bridge_transfers(&mut dydx_client, &db).await?;
+            info!(pending = pending, "Pending bridge transfers checked");
+        }
+    }
+
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await; // Allow time for logging to flush
+
+    Ok(())
+}
@@ -1,7 +1,11 @@
 use crypto_yield_farming_bot::config;
 use crypto_yield_farming_bot::logging;
+use crypto_yield_farming_bot::telemetry;
+use crypto_yield_farming_bot::redis_client::{self, ErrorSource};
+use crypto_yield_farming_bot::config::Config;
 use crypto_yield_farming_bot::gmx::event_fetcher::GmxEventFetcher;
-use crypto_yield_farming_bot::data_ingestion::token::token_registry;
+use crypto_yield_farming_bot::gmx::event_listener::GmxEventListener;
+use crypto_yield_farming_bot::data_ingestion::token::{token_registry, price_validator::PriceValidator};
 use crypto_yield_farming_bot::data_ingestion::market::market_registry;
 use crypto_yield_farming_bot::db::models::{
     tokens::RawTokenModel,
@@ -10,13 +14,14 @@ use crypto_yield_farming_bot::db::models::{
     market_states::RawMarketStateModel,
 };
 
-use tracing::{info, error, debug, instrument};
+use tracing::{info, info_span, warn, error, debug, instrument};
 use dotenvy::dotenv;
 use std::time::Duration;
 use std::sync::Arc;
 use tokio::time::interval;
+use tokio::sync::mpsc;
 use redis::AsyncCommands;
-use redis::streams::StreamMaxlen;
+use redis::aio::MultiplexedConnection;
 use chrono::Utc;
 
 
@@ -38,96 +43,118 @@ async fn main() -> eyre::Result<()> {
 
     // Initialize token registry
     let mut token_registry = token_registry::AssetTokenRegistry::new(&cfg);
+    token_registry.load_from_file()?;
+    token_registry.verify_decimals().await?;
+    if let Err(e) = token_registry.watch_file() {
+        warn!(?e, "Failed to watch asset token data file, changes to it need a restart");
+    }
     info!("Asset token registry initialized");
 
+    // Initialize price validator (anomalous prices are quarantined instead of stored)
+    let mut price_validator = PriceValidator::new(&cfg);
+
     // Initialize market registry
     let mut market_registry = market_registry::MarketRegistry::new(&cfg);
     info!("Market registry initialized");
 
     // Create Redis client
-    let redis_client = redis::Client::open("redis://redis:6379")?;
-    let mut redis_connection = redis_client.get_multiplexed_async_connection().await?;
-    info!("Redis connection established");
+    let redis_client = redis_client::create_client(&cfg)?;
+    let mut redis_connection = redis_client::connect_with_retry(&redis_client, &cfg).await?;
+
+    // Publish the data file's tokens, the recorder upserts them so markets referencing them can be recorded
+    for token_arc in token_registry.asset_tokens() {
+        let token = token_arc.read().await;
+        if let Ok(serialized) = serde_json::to_string(&RawTokenModel::from(&*token)) {
+            redis_client::publish_stream_entry(&mut redis_connection, "new_tokens", serialized, None).await?;
+        }
+    }
 
     // Initialize the GMX event fetcher
     let mut event_fetcher = GmxEventFetcher::init(
         Arc::clone(&cfg.alchemy_provider),
         cfg.gmx_eventemitter,
+        cfg.event_finality,
     );
     info!("GMX event fetcher initialized");
 
+    // Listen for MarketCreated events so new markets are picked up without waiting for the next cycle
+    let (market_created_tx, mut market_created_rx) = mpsc::unbounded_channel();
+    let market_listener = GmxEventListener::init_market_discovery(cfg.alchemy_ws_url.clone(), cfg.gmx_eventemitter, market_created_tx);
+    tokio::spawn(async move {
+        if let Err(e) = market_listener.start_listening().await {
+            error!(?e, "Market discovery listener stopped");
+        }
+    });
+
     // Periodically update markets and save to database
     let mut ticker = interval(Duration::from_secs(300));
     info!("Starting main data collection loop with 300s interval");
     
     loop {
-        ticker.tick().await;
+        tokio::select! {
+            _ = ticker.tick() => {}
+            Some(market_token) = market_created_rx.recv() => {
+                info!(market_token = %market_token, "MarketCreated event received, discovering new markets");
+                if let Err(e) = discover_new_markets(&cfg, &mut market_registry, &mut token_registry, &mut redis_connection).await {
+                    error!(?e, "Failed to discover newly created market, it will be picked up next cycle");
+                    redis_client::record_error(&mut redis_connection, ErrorSource::Rpc).await;
+                }
+                continue;
+            }
+        }
         info!("Data collection cycle started");
         let cycle_start = Utc::now();
-        
-        // Repopulate the market registry and get new tokens/markets
-        let (new_tokens, new_market_addresses) = match market_registry.repopulate(&cfg, &mut token_registry).await {
-            Ok(result) => result,
-            Err(e) => {
-                error!(?e, "Failed to repopulate market registry");
-                return Err(e);
-            }
-        };
 
-        // If we found new tokens or markets, send them to Redis streams
-        if !new_tokens.is_empty() || !new_market_addresses.is_empty() {
-            info!(
-                new_token_count = new_tokens.len(),
-                new_market_count = new_market_addresses.len(),
-                new_tokens = ?new_tokens.iter().map(|t| t.symbol.clone()).collect::<Vec<_>>(),
-                new_markets = ?new_market_addresses,
-                "Detected new tokens/markets"
-            );
-            
-            // Prepare and serialize new tokens directly from domain objects
-            if !new_tokens.is_empty() {
-                for token in &new_tokens {
-                    let raw_token_model = RawTokenModel::from(token);
-                    if let Ok(serialized) = serde_json::to_string(&raw_token_model) {
-                        let _: () = redis_connection.xadd_maxlen("new_tokens", StreamMaxlen::Approx(1000), "*", &[("data", serialized)]).await?;
+        // Each cycle is the root of its own trace, every entry published below carries its context
+        let cycle_span = info_span!(parent: None, "data_collection_cycle", cycle_start = %cycle_start);
+        let traceparent = telemetry::traceparent(&cycle_span);
+        
+        // Apply edits to the asset token data file (new tokens, oracle addresses) made since the last cycle
+        match token_registry.reload_if_changed().await {
+            Ok(Some(diff)) => {
+                for token in &diff.added {
+                    if let Ok(serialized) = serde_json::to_string(&RawTokenModel::from(token)) {
+                        redis_client::publish_stream_entry(&mut redis_connection, "new_tokens", serialized, traceparent.as_deref()).await?;
                     }
-                    debug!(
-                        token_address = %raw_token_model.address, 
-                        token_symbol = %raw_token_model.symbol,
-                        "New token model serialized and sent through Redis"
-                    );
                 }
-            }
-            
-            // Get full market data for new market addresses and prepare models
-            if !new_market_addresses.is_empty() {
-                for &market_address in &new_market_addresses {
-                    if let Some(market) = market_registry.get_market(&market_address) {
-                        let raw_market_model = RawMarketModel::from_async(market).await;
-                        if let Ok(serialized) = serde_json::to_string(&raw_market_model) {
-                            let _: () = redis_connection.xadd_maxlen("new_markets", StreamMaxlen::Approx(1000), "*", &[("data", serialized)]).await?;
-                        }
-                        debug!(
-                            market_address = %raw_market_model.address,
-                            "New market model serialized and sent through Redis"
-                        );
-                    }
+                if !diff.is_empty() {
+                    info!(
+                        added = ?diff.added.iter().map(|t| t.symbol.clone()).collect::<Vec<_>>(),
+                        oracle_updated = ?diff.oracle_updated.iter().map(|(symbol, _, _)| symbol.clone()).collect::<Vec<_>>(),
+                        "Asset token data file changes applied"
+                    );
                 }
             }
+            Ok(None) => {}
+            Err(e) => warn!(?e, "Failed to reload asset token data file, keeping the current tokens"),
+        }
+
+        // Repopulate the market registry and publish new tokens/markets
+        if let Err(e) = discover_new_markets(&cfg, &mut market_registry, &mut token_registry, &mut redis_connection).await {
+            redis_client::record_error(&mut redis_connection, ErrorSource::Rpc).await;
+            return Err(e);
         }
 
         // Fetch Asset Token price data from GMX
         if let Err(e) = token_registry.update_all_gmx_prices().await {
             error!(?e, "Failed to update asset token prices from GMX");
+            redis_client::record_error(&mut redis_connection, ErrorSource::Rpc).await;
             return Err(e);
         }
         debug!("Asset token prices updated from GMX");
 
+        // Fetch Chainlink oracle prices used to cross-check GMX prices
+        if let Err(e) = token_registry.update_all_oracle_prices(Arc::clone(&cfg)).await {
+            error!(?e, "Failed to update oracle prices, validating against previous observations only");
+            redis_client::record_error(&mut redis_connection, ErrorSource::Rpc).await;
+        }
+
         // Fetch GMX fees
         let fees_snapshot = match event_fetcher.fetch_fees().await {
             Ok(fees) => fees,
             Err(e) => {
                 error!(?e, "Failed to fetch GMX fees");
+                redis_client::record_error(&mut redis_connection, ErrorSource::Rpc).await;
                 return Err(e);
             }
         };
@@ -136,9 +163,28 @@ async fn main() -> eyre::Result<()> {
         // Update market data
         if let Err(e) = market_registry.update_all_market_data(Arc::clone(&cfg), &fees_snapshot).await {
             error!(?e, "Failed to update market data");
+            redis_client::record_error(&mut redis_connection, ErrorSource::Rpc).await;
             return Err(e);
         }
 
+        // Refresh halt/deprecation flags, a failed refresh keeps the previous flags
+        match market_registry.update_market_statuses(&cfg).await {
+            Ok(newly_deprecated) if !newly_deprecated.is_empty() => {
+                warn!(markets = ?newly_deprecated, "Markets newly halted or deprecated, positions will be wound down");
+            }
+            Ok(_) => {}
+            Err(e) => {
+                error!(?e, "Failed to update market statuses");
+                redis_client::record_error(&mut redis_connection, ErrorSource::Rpc).await;
+            }
+        }
+
+        // Refresh pool and open interest caps, states are recorded without caps when the read fails
+        if let Err(e) = market_registry.update_market_caps(&cfg).await {
+            error!(?e, "Failed to update market caps");
+            redis_client::record_error(&mut redis_connection, ErrorSource::Rpc).await;
+        }
+
         // Get token_price models and serialize directly
         let updated_tokens = token_registry.updated_tokens(cycle_start).await;
         let mut raw_token_prices = Vec::new();
@@ -148,7 +194,12 @@ async fn main() -> eyre::Result<()> {
                token.last_min_price_usd.is_some() && 
                token.last_max_price_usd.is_some() && 
                token.last_mid_price_usd.is_some() {
-                raw_token_prices.push(RawTokenPriceModel::from(&*token));
+                let mut raw_token_price = RawTokenPriceModel::from(&*token);
+                if let Some(anomaly) = price_validator.validate(&token) {
+                    raw_token_price.quarantine_reason = Some(anomaly.reason);
+                    raw_token_price.reference_price = anomaly.reference_price;
+                }
+                raw_token_prices.push(raw_token_price);
             }
         }
         info!(
@@ -193,10 +244,10 @@ async fn main() -> eyre::Result<()> {
         );
         
         for tp in serialized_token_prices {
-            let _: () = redis_connection.xadd_maxlen("token_prices", StreamMaxlen::Approx(1000), "*", &[("data", tp)]).await?;
+            redis_client::publish_stream_entry(&mut redis_connection, "token_prices", tp, traceparent.as_deref()).await?;
         }
         for ms in serialized_market_states {
-            let _: () = redis_connection.xadd_maxlen("market_states", StreamMaxlen::Approx(1000), "*", &[("data", ms)]).await?;
+            redis_client::publish_stream_entry(&mut redis_connection, "market_states", ms, traceparent.as_deref()).await?;
         }
 
         info!(
@@ -208,4 +259,67 @@ async fn main() -> eyre::Result<()> {
         // Zero out tracked fields for all markets at the end of the data collection loop
         market_registry.zero_all_tracked_fields();
     }
+}
+
+/// Repopulate the market registry and publish any new tokens and markets onto the `new_tokens` and `new_markets` Redis streams
+#[instrument(parent = None, skip(config, market_registry, token_registry, redis_connection), fields(on_close = true))]
+async fn discover_new_markets(
+    config: &Config,
+    market_registry: &mut market_registry::MarketRegistry,
+    token_registry: &mut token_registry::AssetTokenRegistry,
+    redis_connection: &mut MultiplexedConnection,
+) -> eyre::Result<()> {
+    // Repopulate the market registry (discovering tokens for any unknown index/collateral tokens) and get new tokens/markets
+    let (new_tokens, new_market_addresses) = match market_registry.repopulate(config, token_registry).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!(?e, "Failed to repopulate market registry");
+            return Err(e);
+        }
+    };
+
+    // If we found new tokens or markets, send them to Redis streams
+    if !new_tokens.is_empty() || !new_market_addresses.is_empty() {
+        let traceparent = telemetry::traceparent(&tracing::Span::current());
+        info!(
+            new_token_count = new_tokens.len(),
+            new_market_count = new_market_addresses.len(),
+            new_tokens = ?new_tokens.iter().map(|t| t.symbol.clone()).collect::<Vec<_>>(),
+            new_markets = ?new_market_addresses,
+            "Detected new tokens/markets"
+        );
+        
+        // Prepare and serialize new tokens directly from domain objects
+        if !new_tokens.is_empty() {
+            for token in &new_tokens {
+                let raw_token_model = RawTokenModel::from(token);
+                if let Ok(serialized) = serde_json::to_string(&raw_token_model) {
+                    redis_client::publish_stream_entry(redis_connection, "new_tokens", serialized, traceparent.as_deref()).await?;
+                }
+                debug!(
+                    token_address = %raw_token_model.address, 
+                    token_symbol = %raw_token_model.symbol,
+                    "New token model serialized and sent through Redis"
+                );
+            }
+        }
+        
+        // Get full market data for new market addresses and prepare models
+        if !new_market_addresses.is_empty() {
+            for &market_address in &new_market_addresses {
+                if let Some(market) = market_registry.get_market(&market_address) {
+                    let raw_market_model = RawMarketModel::from_async(market).await;
+                    if let Ok(serialized) = serde_json::to_string(&raw_market_model) {
+                        redis_client::publish_stream_entry(redis_connection, "new_markets", serialized, traceparent.as_deref()).await?;
+                    }
+                    debug!(
+                        market_address = %raw_market_model.address,
+                        "New market model serialized and sent through Redis"
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
 }
\ No newline at end of file
@@ -1,24 +1,29 @@
 use crypto_yield_farming_bot::config;
 use crypto_yield_farming_bot::logging;
+use crypto_yield_farming_bot::telemetry::{self, TRACEPARENT_FIELD};
+use crypto_yield_farming_bot::redis_client::{self, ErrorSource};
 use crypto_yield_farming_bot::db::{
     self,
     models::{
-        token_prices::RawTokenPriceModel,
+        token_prices::{RawTokenPriceModel, NewQuarantinedPriceModel},
         market_states::RawMarketStateModel,
         tokens::RawTokenModel,
         markets::RawMarketModel
     }
 };
 
-use tracing::{self, info, debug, error, warn, instrument};
+use tracing::{self, info, info_span, debug, error, warn, instrument, Instrument};
 use dotenvy::dotenv;
 use redis::AsyncCommands;
 use redis::streams::{StreamReadOptions, StreamReadReply};
 use futures::StreamExt;
 use std::collections::HashMap;
-use tokio::time::{sleep, Duration};
+use tokio::time::{sleep, interval, Duration};
 use tokio::sync::mpsc;
 
+const DATA_RETENTION_INTERVAL_SECS: u64 = 6 * 60 * 60; // Downsample and prune raw data every 6 hours
+const MARKET_OVERVIEW_REFRESH_INTERVAL_SECS: u64 = 5 * 60; // Refresh the market overview view once per collection cycle
+
 #[instrument(skip(token_prices_tx, market_states_tx, new_token_tx, new_market_tx, redis_connection), fields(stream_name, entry_count))]
 async fn process_stream_entries(
     stream_name: &str,
@@ -35,6 +40,11 @@ async fn process_stream_entries(
     
     for stream_id in stream_entries {
         let data = &stream_id.map;
+        // Trace context of the collection cycle that published the entry, continued when the data is recorded
+        let traceparent = match data.get(TRACEPARENT_FIELD) {
+            Some(redis::Value::BulkString(value)) => std::str::from_utf8(value).ok().map(str::to_string),
+            _ => None,
+        };
         // Use redis::Value::BulkString for the payload
         if let Some(redis::Value::BulkString(payload)) = data.get("data") {
             // Try to convert payload to string for printing
@@ -42,7 +52,8 @@ async fn process_stream_entries(
                 // Deserialize based on stream name
                 match stream_name {
                     "token_prices" => {
-                        if let Ok(raw_token_price_model) = serde_json::from_str::<RawTokenPriceModel>(text) {
+                        if let Ok(mut raw_token_price_model) = serde_json::from_str::<RawTokenPriceModel>(text) {
+                            raw_token_price_model.traceparent = traceparent.clone();
                             debug!(token_address = raw_token_price_model.token_address, "Deserialized token price");
                             if let Err(e) = token_prices_tx.send(raw_token_price_model).await {
                                 error!(error = ?e, "Token price channel closed");
@@ -53,7 +64,8 @@ async fn process_stream_entries(
                         }
                     },
                     "market_states" => {
-                        if let Ok(raw_market_state_model) = serde_json::from_str::<RawMarketStateModel>(text) {
+                        if let Ok(mut raw_market_state_model) = serde_json::from_str::<RawMarketStateModel>(text) {
+                            raw_market_state_model.traceparent = traceparent.clone();
                             debug!(market_address = raw_market_state_model.market_address, "Deserialized market state");
                             if let Err(e) = market_states_tx.send(raw_market_state_model).await {
                                 error!(error = ?e, "Market state channel closed");
@@ -121,11 +133,14 @@ async fn main() -> eyre::Result<()> {
     let mut db = db::db_manager::DbManager::init(&cfg).await?;
 
     // Create Redis client
-    let redis_client = redis::Client::open("redis://redis:6379")?;
-    let mut redis_connection = redis_client.get_multiplexed_async_connection().await?;
+    let redis_client = redis_client::create_client(&cfg)?;
+    let mut redis_connection = redis_client::connect_with_retry(&redis_client, &cfg).await?;
 
     // Clone Redis client for the spawned task
     let redis_client_for_task = redis_client.clone();
+
+    // Raw data older than the retention window is periodically downsampled to hourly rows
+    let raw_data_retention_days = cfg.raw_data_retention_days;
     
     // Create channels for batching
     let (token_prices_tx, mut token_prices_rx) = mpsc::channel::<RawTokenPriceModel>(1000);
@@ -151,6 +166,8 @@ async fn main() -> eyre::Result<()> {
         let mut new_token_batch = Vec::new();
         let mut new_market_batch = Vec::new();
         let mut message_stream = pubsub.on_message();
+        let mut retention_ticker = interval(Duration::from_secs(DATA_RETENTION_INTERVAL_SECS));
+        let mut overview_ticker = interval(Duration::from_secs(MARKET_OVERVIEW_REFRESH_INTERVAL_SECS));
 
         let mut markets_retry_bank: HashMap<String, (RawMarketModel, u32)> = HashMap::new();
         let mut token_prices_retry_bank: HashMap<String, (Vec<RawTokenPriceModel>, u32)> = HashMap::new();
@@ -167,9 +184,21 @@ async fn main() -> eyre::Result<()> {
             tokio::select! {
                 // Collect token prices
                 Some(raw_token_price) = token_prices_rx.recv() => {
-                    match db.convert_raw_token_price_to_new_token_price(raw_token_price.clone()).await {
+                    let record_span = info_span!("record_token_price", token_address = %raw_token_price.token_address);
+                    if let Some(traceparent) = &raw_token_price.traceparent {
+                        telemetry::set_parent(&record_span, traceparent);
+                    }
+                    match db.convert_raw_token_price_to_new_token_price(raw_token_price.clone()).instrument(record_span).await {
                         Ok(Some(token_price)) => {
-                            token_prices_batch.push(token_price);
+                            if let Some(reason) = raw_token_price.quarantine_reason.clone() {
+                                // Anomalous prices are kept out of token_prices so they can't poison covariance estimates
+                                let quarantined = NewQuarantinedPriceModel::from(&token_price, reason, raw_token_price.reference_price);
+                                if let Err(e) = db.insert_quarantined_price(&quarantined).await {
+                                    error!(error = ?e, token_address = raw_token_price.token_address, "Failed to insert quarantined token price");
+                                }
+                            } else {
+                                token_prices_batch.push(token_price);
+                            }
                             if waiting_for_flush {
                                 tokens_processed_since_signal += 1;
                             }
@@ -197,6 +226,7 @@ async fn main() -> eyre::Result<()> {
                     if token_prices_batch.len() >= 200 {
                         if let Err(e) = db.insert_token_prices(std::mem::take(&mut token_prices_batch)).await {
                             error!(error = ?e, "Failed to insert token prices batch");
+                            redis_client::record_error(&mut publish_connection, ErrorSource::Db).await;
                         } else {
                             info!("Flushed large token prices batch to database (safety flush)");
                         }
@@ -204,7 +234,16 @@ async fn main() -> eyre::Result<()> {
                 }
                 // Collect market states
                 Some(raw_market_state) = market_states_rx.recv() => {
-                    match db.convert_raw_market_state_to_new_market_state(raw_market_state.clone()).await {
+                    let record_span = info_span!("record_market_state", market_address = %raw_market_state.market_address);
+                    if let Some(traceparent) = &raw_market_state.traceparent {
+                        telemetry::set_parent(&record_span, traceparent);
+                    }
+                    // Record the market's halt/deprecation status (only written when it changes)
+                    if let Err(e) = db.set_market_deprecated(&raw_market_state.market_address, raw_market_state.deprecated).instrument(record_span.clone()).await {
+                        error!(error = ?e, market_address = %raw_market_state.market_address, "Failed to record market deprecation status");
+                        redis_client::record_error(&mut publish_connection, ErrorSource::Db).await;
+                    }
+                    match db.convert_raw_market_state_to_new_market_state(raw_market_state.clone()).instrument(record_span).await {
                         Ok(Some(market_state)) => {
                             market_states_batch.push(market_state);
                             if waiting_for_flush {
@@ -234,6 +273,7 @@ async fn main() -> eyre::Result<()> {
                     if market_states_batch.len() >= 200 {
                         if let Err(e) = db.insert_market_states(std::mem::take(&mut market_states_batch)).await {
                             error!(error = ?e, "Failed to insert market states batch");
+                            redis_client::record_error(&mut publish_connection, ErrorSource::Db).await;
                         } else {
                             info!("Flushed large market states batch to database (safety flush)");
                         }
@@ -273,6 +313,20 @@ async fn main() -> eyre::Result<()> {
                         }
                     }
                 }
+                // Periodic data retention (first tick fires immediately on startup)
+                _ = retention_ticker.tick() => {
+                    if let Err(e) = db.apply_data_retention(raw_data_retention_days).await {
+                        error!(error = ?e, "Failed to apply data retention");
+                        redis_client::record_error(&mut publish_connection, ErrorSource::Db).await;
+                    }
+                }
+                // Periodic market overview refresh, so dashboards and strategy filters read precomputed aggregates
+                _ = overview_ticker.tick() => {
+                    if let Err(e) = db.refresh_market_overview().await {
+                        error!(error = ?e, "Failed to refresh market overview");
+                        redis_client::record_error(&mut publish_connection, ErrorSource::Db).await;
+                    }
+                }
                 // PubSub signal - set coordination expectations
                 Some(message) = message_stream.next() => {
                     let channel: String = message.get_channel_name().to_string();
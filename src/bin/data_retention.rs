@@ -0,0 +1,46 @@
+use dotenvy::dotenv;
+use tracing::{instrument, info};
+
+use crypto_yield_farming_bot::logging;
+use crypto_yield_farming_bot::config;
+use crypto_yield_farming_bot::db::db_manager::DbManager;
+
+const USAGE: &str = "Usage: data_retention [retention_days]";
+
+/// Downsample token prices and market states older than the retention window into hourly rows and delete the raw rows.
+/// The data recorder also runs this on a schedule, this binary is for running it on demand.
+#[instrument(name = "data_retention_main")]
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    // Load environment variables from .env file
+    dotenv()?;
+
+    // Initialize logging
+    if let Err(e) = logging::init_logging(env!("CARGO_BIN_NAME").to_string()) {
+        eprintln!("Failed to initialize logging: {}", e);
+        return Err(e.into());
+    }
+
+    // Load configuration (including provider)
+    let cfg = config::Config::load().await;
+    info!(network_mode = %cfg.network_mode, "Configuration loaded and logging initialized");
+
+    // Parse arguments (defaults to the configured retention window)
+    let retention_days = match std::env::args().nth(1) {
+        Some(arg) => arg.parse::<i64>()
+            .ok()
+            .filter(|days| *days > 0)
+            .ok_or_else(|| eyre::eyre!("Invalid retention days: {}\n{}", arg, USAGE))?,
+        None => cfg.raw_data_retention_days,
+    };
+
+    // Initialize db manager
+    let db = DbManager::init(&cfg).await?;
+    info!("Database manager initialized");
+
+    db.apply_data_retention(retention_days).await?;
+
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await; // Allow time for logging to flush
+
+    Ok(())
+}
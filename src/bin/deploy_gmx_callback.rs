@@ -0,0 +1,50 @@
+use dotenvy::dotenv;
+use tracing::{instrument, info};
+use ethers::types::Address;
+
+use crypto_yield_farming_bot::logging;
+use crypto_yield_farming_bot::config;
+use crypto_yield_farming_bot::constants::GMX_ROLESTORE_ADDRESS_MAINNET;
+use crypto_yield_farming_bot::wallet::WalletManager;
+use crypto_yield_farming_bot::db::db_manager::DbManager;
+use crypto_yield_farming_bot::gmx::callback_receiver::{self, CALLBACK_RECEIVER_BYTECODE_PATH};
+
+const USAGE: &str = "Usage: deploy_gmx_callback [role_store_address] [bytecode_path]";
+
+#[instrument(name = "deploy_gmx_callback_main")]
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    // Load environment variables from .env file
+    dotenv()?;
+
+    // Initialize logging
+    if let Err(e) = logging::init_logging(env!("CARGO_BIN_NAME").to_string()) {
+        eprintln!("Failed to initialize logging: {}", e);
+        return Err(e.into());
+    }
+
+    // Load configuration (including provider)
+    let cfg = config::Config::load().await;
+    info!(network_mode = %cfg.network_mode, "Configuration loaded and logging initialized");
+
+    // The GMX RoleStore decides which callers may trigger callbacks, it has to be given explicitly off mainnet
+    let role_store: Address = match std::env::args().nth(1) {
+        Some(arg) => arg.parse().map_err(|_| eyre::eyre!("Invalid role store address: {}\n{}", arg, USAGE))?,
+        None if cfg.network_mode == "prod" => GMX_ROLESTORE_ADDRESS_MAINNET.parse()?,
+        None => return Err(eyre::eyre!("Role store address is required outside mainnet\n{}", USAGE)),
+    };
+    let bytecode_path = std::env::args().nth(2).unwrap_or_else(|| CALLBACK_RECEIVER_BYTECODE_PATH.to_string());
+    let bytecode = callback_receiver::load_bytecode(&bytecode_path)?;
+
+    // Initialize db manager (the deployment is registered as a bot transaction for the wallet watchdog)
+    let db = DbManager::init(&cfg).await?;
+    info!("Database manager initialized");
+
+    let wallet_manager = WalletManager::new(&cfg)?;
+    wallet_manager.register_transactions(&db);
+    info!(address = ?wallet_manager.address, role_store = ?role_store, "Deploying GMX callback receiver");
+    let callback_contract = callback_receiver::deploy_callback_receiver(&wallet_manager, role_store, bytecode).await?;
+    info!("Set GMX_CALLBACK_CONTRACT={:?} to route GM request callbacks through it", callback_contract);
+
+    Ok(())
+}
@@ -32,13 +32,13 @@ async fn main() -> Result<()> {
     info!("Database manager initialized");
 
     // Initialize and load wallet manager
-    let mut wallet_manager = WalletManager::new(&cfg)?;
+    let wallet_manager = WalletManager::new(&cfg)?;
     wallet_manager.load_tokens(&db).await?;
     let wallet_manager = Arc::new(wallet_manager);
     info!("Wallet manager initialized and tokens loaded");
 
     // Initialize dydx client
-    let mut dydx_client = DydxClient::new(cfg.clone(), wallet_manager.clone()).await?;
+    let mut dydx_client = DydxClient::new(cfg.clone(), wallet_manager.clone(), db.clone()).await?;
     info!("dYdX client initialized");
 
     // Set subaccount balance
@@ -62,7 +62,7 @@ async fn main() -> Result<()> {
         size = %size,
         "Placing dYdX market order to short ETH-USD perp"
     );
-    if let Err(e) = dydx_client.submit_perp_order(&token, size, side_is_buy).await {
+    if let Err(e) = dydx_client.submit_perp_order(&token, size, side_is_buy, cfg.rebalance_id).await {
         error!(error = %e, "Failed to submit dYdX market order");
     } else {
         info!("dYdX market order submitted successfully");
@@ -70,9 +70,16 @@ async fn main() -> Result<()> {
     
     dydx_client.wait_for_active_tasks().await; // Wait before closing position
 
+    // Resubmit the unfilled remainder of any partially filled orders
+    let retried = dydx_client.retry_partially_filled_orders().await?;
+    if retried > 0 {
+        info!(retried = retried, "Retried partially filled dYdX orders");
+        dydx_client.wait_for_active_tasks().await;
+    }
+
     // Close the position
     info!(token = %token, "Closing dYdX perp position");
-    if let Err(e) = dydx_client.reduce_perp_position(&token, None).await {
+    if let Err(e) = dydx_client.reduce_perp_position(&token, None, cfg.rebalance_id).await {
         error!(error = %e, "Failed to close dYdX perp position");
     } else {
         info!("dYdX perp position closed successfully");
@@ -31,13 +31,13 @@ async fn main() -> Result<()> {
     info!("Database manager initialized");
 
     // Initialize and load wallet manager
-    let mut wallet_manager = WalletManager::new(&cfg)?;
+    let wallet_manager = WalletManager::new(&cfg)?;
     wallet_manager.load_tokens(&db).await?;
     let wallet_manager = Arc::new(wallet_manager);
     info!("Wallet manager initialized and tokens loaded");
 
     // Initialize dydx client
-    let mut dydx_client = DydxClient::new(cfg.clone(), wallet_manager.clone()).await?;
+    let mut dydx_client = DydxClient::new(cfg.clone(), wallet_manager.clone(), db.clone()).await?;
     info!("dYdX client initialized successfully");
 
     // Use SkipGo to get route and msgs for a deposit from Arbitrum to dYdX
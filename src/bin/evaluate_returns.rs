@@ -0,0 +1,46 @@
+use dotenvy::dotenv;
+use tracing::{instrument, info};
+use std::sync::Arc;
+
+use crypto_yield_farming_bot::logging;
+use crypto_yield_farming_bot::config;
+use crypto_yield_farming_bot::db::db_manager::DbManager;
+use crypto_yield_farming_bot::strategy::evaluation;
+use crypto_yield_farming_bot::strategy::strategy_constants::RETURN_EVALUATION_REPORT_RUNS;
+use crypto_yield_farming_bot::reporting_currency::ReportingCurrency;
+
+#[instrument(name = "evaluate_returns_main")]
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    // Load environment variables from .env file
+    dotenv()?;
+
+    // Initialize logging
+    if let Err(e) = logging::init_logging(env!("CARGO_BIN_NAME").to_string()) {
+        eprintln!("Failed to initialize logging: {}", e);
+        return Err(e.into());
+    }
+
+    // Load configuration (including provider)
+    let cfg = config::Config::load().await;
+    info!(network_mode = %cfg.network_mode, "Configuration loaded and logging initialized");
+
+    // Initialize db manager
+    let db = DbManager::init(&cfg).await?;
+    let db = Arc::new(db);
+    info!("Database manager initialized");
+
+    // Resolve the currency realized returns are measured in
+    let reporting_currency = ReportingCurrency::load(&cfg, &db).await?;
+
+    // Evaluate every strategy run whose holding horizon has elapsed
+    evaluation::evaluate_expected_returns(db.clone(), &reporting_currency).await?;
+
+    // Report recent prediction error metrics
+    let metrics = db.get_recent_return_model_metrics(RETURN_EVALUATION_REPORT_RUNS).await?;
+    evaluation::log_metrics_report(&metrics);
+
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await; // Allow time for logging to flush
+
+    Ok(())
+}
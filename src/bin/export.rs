@@ -0,0 +1,78 @@
+use dotenvy::dotenv;
+use tracing::{instrument, info};
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crypto_yield_farming_bot::logging;
+use crypto_yield_farming_bot::config;
+use crypto_yield_farming_bot::db::db_manager::DbManager;
+use crypto_yield_farming_bot::accounting::{self, ExportFormat};
+
+const USAGE: &str = "Usage: export trades [--format generic|koinly] [--from YYYY-MM-DD] [--to YYYY-MM-DD] [--out <path>]";
+
+#[instrument(name = "export_main")]
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    // Load environment variables from .env file
+    dotenv()?;
+
+    // Initialize logging
+    if let Err(e) = logging::init_logging(env!("CARGO_BIN_NAME").to_string()) {
+        eprintln!("Failed to initialize logging: {}", e);
+        return Err(e.into());
+    }
+
+    // Load configuration (including provider)
+    let cfg = config::Config::load().await;
+    info!(network_mode = %cfg.network_mode, "Configuration loaded and logging initialized");
+
+    // Initialize db manager
+    let db = DbManager::init(&cfg).await?;
+    info!("Database manager initialized");
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) != Some("trades") {
+        return Err(eyre::eyre!(USAGE));
+    }
+
+    // Parse options (dates are UTC days, both ends inclusive)
+    let parse_date = |s: &String| NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|_| eyre::eyre!("Invalid date: {}\n{}", s, USAGE));
+    let mut format = ExportFormat::Generic;
+    let mut start = DateTime::<Utc>::UNIX_EPOCH;
+    let mut end = db.clock.now();
+    let mut out_path = None;
+    let mut options = args[1..].iter();
+    while let Some(option) = options.next() {
+        let value = options.next().ok_or_else(|| eyre::eyre!("Missing value for {}\n{}", option, USAGE))?;
+        match option.as_str() {
+            "--format" => format = ExportFormat::parse(value).ok_or_else(|| eyre::eyre!("Invalid format: {}\n{}", value, USAGE))?,
+            "--from" => start = parse_date(value)?.and_hms_opt(0, 0, 0).unwrap().and_utc(),
+            "--to" => end = parse_date(value)?.and_hms_opt(23, 59, 59).unwrap().and_utc(),
+            "--out" => out_path = Some(value.clone()),
+            _ => return Err(eyre::eyre!("Unknown option: {}\n{}", option, USAGE)),
+        }
+    }
+    if start > end {
+        return Err(eyre::eyre!("--from must not be after --to"));
+    }
+    let out_path = out_path.unwrap_or_else(|| format!(
+        "trades_{}_{}_{}.csv",
+        format.as_str(),
+        start.format("%Y%m%d"),
+        end.format("%Y%m%d")
+    ));
+
+    let entries = accounting::build_trade_entries(&db, start, end).await?;
+    std::fs::write(&out_path, accounting::to_csv(&entries, format))?;
+    info!(
+        format = format.as_str(),
+        from = %start,
+        to = %end,
+        rows = entries.len(),
+        path = %out_path,
+        "Trade export written"
+    );
+
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await; // Allow time for logging to flush
+
+    Ok(())
+}
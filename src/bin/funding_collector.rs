@@ -0,0 +1,97 @@
+use dotenvy::dotenv;
+use tracing::{instrument, info, error};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::interval;
+
+use crypto_yield_farming_bot::logging;
+use crypto_yield_farming_bot::config;
+use crypto_yield_farming_bot::wallet::WalletManager;
+use crypto_yield_farming_bot::db::db_manager::DbManager;
+use crypto_yield_farming_bot::hedging::dydx_client::DydxClient;
+use crypto_yield_farming_bot::data_ingestion::market::{incentives, liquidations};
+use crypto_yield_farming_bot::gmx::event_fetcher::GmxEventFetcher;
+
+const USAGE: &str = "Usage: funding_collector [backfill <days>]";
+
+const FUNDING_SYNC_INTERVAL_SECS: u64 = 3600; // dYdX funding is paid hourly
+const DEFAULT_BACKFILL_DAYS: i64 = 7; // History pulled for tickers with nothing stored yet
+
+/// Periodically store dYdX hourly funding rates for every perp hedging one of our tokens,
+/// along with GM pool incentive emissions (both are carry the strategy engine adds to fee returns)
+/// and GMX liquidations (the risk model's liquidation intensity).
+/// `backfill <days>` pulls the historical funding endpoint once over the given window and exits.
+#[instrument(name = "funding_collector_main")]
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    // Load environment variables from .env file
+    dotenv()?;
+
+    // Initialize logging
+    if let Err(e) = logging::init_logging(env!("CARGO_BIN_NAME").to_string()) {
+        eprintln!("Failed to initialize logging: {}", e);
+        return Err(e.into());
+    }
+
+    // Load configuration (including provider)
+    let cfg = config::Config::load().await;
+    info!(network_mode = %cfg.network_mode, "Configuration loaded and logging initialized");
+
+    // Parse arguments
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let backfill_days = match args.first().map(String::as_str) {
+        Some("backfill") => {
+            let arg = args.get(1).ok_or_else(|| eyre::eyre!(USAGE))?;
+            Some(arg.parse::<i64>()
+                .ok()
+                .filter(|days| *days > 0)
+                .ok_or_else(|| eyre::eyre!("Invalid backfill days: {}\n{}", arg, USAGE))?)
+        }
+        Some(_) => return Err(eyre::eyre!(USAGE)),
+        None => None,
+    };
+
+    // Initialize db manager
+    let db = DbManager::init(&cfg).await?;
+    let db = Arc::new(db);
+    info!("Database manager initialized");
+
+    // Initialize and load wallet manager (its tokens determine which perps are tracked)
+    let wallet_manager = WalletManager::new(&cfg)?;
+    wallet_manager.load_tokens(&db).await?;
+    let wallet_manager = Arc::new(wallet_manager);
+    info!("Wallet manager initialized");
+
+    // Initialize dydx client
+    let dydx_client = DydxClient::new(cfg.clone(), wallet_manager.clone(), db.clone()).await?;
+    info!("dYdX client initialized");
+
+    if let Some(days) = backfill_days {
+        let since = db.clock.now() - chrono::Duration::days(days);
+        let inserted = dydx_client.sync_funding_rates(since).await?;
+        info!(days = days, inserted = inserted, "Funding rate backfill completed");
+        tokio::time::sleep(Duration::from_secs(1)).await; // Allow time for logging to flush
+        return Ok(());
+    }
+
+    let mut event_fetcher = GmxEventFetcher::init(cfg.alchemy_provider.clone(), cfg.gmx_eventemitter, cfg.event_finality);
+    let mut ticker = interval(Duration::from_secs(FUNDING_SYNC_INTERVAL_SECS));
+    info!(interval_secs = FUNDING_SYNC_INTERVAL_SECS, "Starting funding rate, incentive and liquidation collection loop");
+    loop {
+        ticker.tick().await;
+        // Pick up newly listed markets so their perps are tracked without a restart
+        if let Err(e) = wallet_manager.refresh(&db).await {
+            error!(error = ?e, "Failed to refresh wallet tokens");
+        }
+        let since = db.clock.now() - chrono::Duration::days(DEFAULT_BACKFILL_DAYS);
+        if let Err(e) = dydx_client.sync_funding_rates(since).await {
+            error!(error = ?e, "Failed to sync funding rates");
+        }
+        if let Err(e) = incentives::record_market_incentives(&cfg, &db).await {
+            error!(error = ?e, "Failed to record market incentives");
+        }
+        if let Err(e) = liquidations::sync_liquidations(&cfg, &db, &mut event_fetcher, since).await {
+            error!(error = ?e, "Failed to sync liquidations");
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use dotenvy::dotenv;
+use tracing::{instrument, info};
+use std::collections::HashMap;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::FromPrimitive;
+
+use crypto_yield_farming_bot::logging;
+use crypto_yield_farming_bot::config;
+use crypto_yield_farming_bot::db::db_manager::DbManager;
+use crypto_yield_farming_bot::gm_token_txs::{gas_profile, fee_buffer};
+use crypto_yield_farming_bot::gm_token_txs::gm_tx_manager::MAX_FEE_PER_GAS_BUFFER;
+
+const USAGE: &str = "Usage: gas_profile [lookback_days]";
+const DEFAULT_LOOKBACK_DAYS: i64 = 30;
+
+#[instrument(name = "gas_profile_main")]
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    // Load environment variables from .env file
+    dotenv()?;
+
+    // Initialize logging
+    if let Err(e) = logging::init_logging(env!("CARGO_BIN_NAME").to_string()) {
+        eprintln!("Failed to initialize logging: {}", e);
+        return Err(e.into());
+    }
+
+    // Load configuration (including provider)
+    let cfg = config::Config::load().await;
+    info!(network_mode = %cfg.network_mode, "Configuration loaded and logging initialized");
+
+    // Initialize db manager
+    let db = DbManager::init(&cfg).await?;
+    info!("Database manager initialized");
+
+    let lookback_days = match std::env::args().nth(1) {
+        Some(arg) => arg.parse::<i64>().map_err(|_| eyre::eyre!("Invalid lookback days: {}\n{}", arg, USAGE))?,
+        None => DEFAULT_LOOKBACK_DAYS,
+    };
+    let since = db.clock.now() - chrono::Duration::days(lookback_days);
+
+    // Report gas usage and execution fee utilization per action type and market
+    let summaries = db.get_gas_profile_summary_since(since).await?;
+    let display_names = db.get_market_display_names().await?;
+    let market_names: HashMap<i32, String> = db.market_id_map.iter()
+        .map(|(address, id)| (*id, display_names.get(address).cloned().unwrap_or_else(|| format!("{:?}", address))))
+        .collect();
+    info!(lookback_days = lookback_days, "Gas profiles loaded");
+
+    // Buffer each action type would be quoted with right now (tuned, or the default)
+    let mut current_buffers: HashMap<String, Decimal> = HashMap::new();
+    for summary in &summaries {
+        if !current_buffers.contains_key(&summary.action_type) {
+            let buffer = fee_buffer::max_fee_per_gas_buffer(&cfg, &db, &summary.action_type, Decimal::from_f64(MAX_FEE_PER_GAS_BUFFER).unwrap()).await?;
+            current_buffers.insert(summary.action_type.clone(), buffer);
+        }
+    }
+    gas_profile::log_gas_profile_report(&summaries, &market_names, &current_buffers);
+
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await; // Allow time for logging to flush
+
+    Ok(())
+}
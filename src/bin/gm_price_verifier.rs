@@ -0,0 +1,43 @@
+use dotenvy::dotenv;
+use tracing::{instrument, info};
+use std::sync::Arc;
+
+use crypto_yield_farming_bot::logging;
+use crypto_yield_farming_bot::config;
+use crypto_yield_farming_bot::db::db_manager::DbManager;
+use crypto_yield_farming_bot::data_ingestion::gm_price_verifier::GmPriceVerifier;
+
+/// Periodically re-read a random sample of recorded GM prices on-chain at the block they were collected at
+/// (through ARCHIVE_RPC_URL) and report the ones deviating beyond GM_PRICE_VERIFY_TOLERANCE_PCT.
+/// With `once`, verify a single sample and exit.
+#[instrument(name = "gm_price_verifier_main")]
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    // Load environment variables from .env file
+    dotenv()?;
+
+    // Initialize logging
+    if let Err(e) = logging::init_logging(env!("CARGO_BIN_NAME").to_string()) {
+        eprintln!("Failed to initialize logging: {}", e);
+        return Err(e.into());
+    }
+
+    // Load configuration (including provider)
+    let cfg = config::Config::load().await;
+    info!(network_mode = %cfg.network_mode, "Configuration loaded and logging initialized");
+
+    // Initialize db manager
+    let db = DbManager::init(&cfg).await?;
+    let db = Arc::new(db);
+    info!("Database manager initialized");
+
+    let verifier = GmPriceVerifier::init(cfg.clone(), db.clone())?;
+    if std::env::args().nth(1).as_deref() == Some("once") {
+        verifier.verify_sample().await?;
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await; // Allow time for logging to flush
+    } else {
+        verifier.run().await;
+    }
+
+    Ok(())
+}
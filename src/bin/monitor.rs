@@ -0,0 +1,86 @@
+use dotenvy::dotenv;
+use std::time::{Duration, Instant};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use tokio::sync::mpsc::error::TryRecvError;
+
+use crypto_yield_farming_bot::config;
+use crypto_yield_farming_bot::redis_client;
+use crypto_yield_farming_bot::wallet::WalletManager;
+use crypto_yield_farming_bot::db::db_manager::DbManager;
+use crypto_yield_farming_bot::db::subscriber::DbChannel;
+use crypto_yield_farming_bot::monitor::{self, MonitorSnapshot};
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5); // Polling interval when database notifications are unavailable
+const IDLE_REFRESH_INTERVAL: Duration = Duration::from_secs(60); // Keeps wallet values and ages current between notifications
+const INPUT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    // Load environment variables from .env file
+    dotenv()?;
+
+    // Console logging would draw over the terminal UI, so logging is not initialized here
+
+    // Load configuration (including provider)
+    let cfg = config::Config::load().await;
+
+    // Initialize db manager
+    let db = DbManager::init(&cfg).await?;
+
+    // Initialize and load wallet manager
+    let wallet_manager = WalletManager::new(&cfg)?;
+    wallet_manager.load_tokens(&db).await?;
+
+    // Error counters live in Redis, the monitor still runs without them
+    let mut redis_connection = match redis_client::create_client(&cfg) {
+        Ok(client) => client.get_multiplexed_async_connection().await.ok(),
+        Err(_) => None,
+    };
+
+    // Refresh when new market states, runs or trades land rather than polling, falling back to polling once the
+    // subscription fails (the sender is dropped)
+    let (changed_tx, mut changed_rx) = tokio::sync::mpsc::unbounded_channel::<()>();
+    if let Ok(mut subscriber) = db.subscribe(&DbChannel::ALL).await {
+        tokio::spawn(async move {
+            while subscriber.recv().await.is_ok() && changed_tx.send(()).is_ok() {}
+        });
+    }
+    let mut refresh_interval = IDLE_REFRESH_INTERVAL;
+
+    let mut terminal = ratatui::init();
+    let result: eyre::Result<()> = async {
+        let mut snapshot = MonitorSnapshot::default();
+        let mut last_refresh: Option<Instant> = None;
+        loop {
+            let mut changed = false;
+            loop {
+                match changed_rx.try_recv() {
+                    Ok(()) => changed = true,
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        refresh_interval = REFRESH_INTERVAL;
+                        break;
+                    }
+                }
+            }
+            if changed || last_refresh.is_none_or(|at| at.elapsed() >= refresh_interval) {
+                // Refresh token prices so current weights are valued at the latest mid prices
+                let _ = wallet_manager.refresh(&db).await;
+                snapshot = MonitorSnapshot::load(&db, &wallet_manager, redis_connection.as_mut()).await;
+                last_refresh = Some(Instant::now());
+            }
+            terminal.draw(|frame| monitor::render(frame, &snapshot))?;
+
+            if event::poll(INPUT_POLL_INTERVAL)? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press && matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }.await;
+    ratatui::restore();
+
+    result
+}
@@ -0,0 +1,43 @@
+use dotenvy::dotenv;
+use tracing::{instrument, info};
+use std::sync::Arc;
+
+use crypto_yield_farming_bot::logging;
+use crypto_yield_farming_bot::config;
+use crypto_yield_farming_bot::redis_client;
+use crypto_yield_farming_bot::db::db_manager::DbManager;
+use crypto_yield_farming_bot::pipeline_watchdog::PipelineWatchdog;
+
+/// Watch the token_prices and market_states data streams, alerting and recording a pipeline_health row when either
+/// goes without a new entry for longer than its threshold (TOKEN_PRICES_STALE_AFTER_MINS, MARKET_STATES_STALE_AFTER_MINS)
+/// and recording again once it recovers.
+#[instrument(name = "pipeline_watchdog_main")]
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    // Load environment variables from .env file
+    dotenv()?;
+
+    // Initialize logging
+    if let Err(e) = logging::init_logging(env!("CARGO_BIN_NAME").to_string()) {
+        eprintln!("Failed to initialize logging: {}", e);
+        return Err(e.into());
+    }
+
+    // Load configuration (including provider)
+    let cfg = config::Config::load().await;
+    info!(network_mode = %cfg.network_mode, "Configuration loaded and logging initialized");
+
+    // Initialize db manager
+    let db = DbManager::init(&cfg).await?;
+    let db = Arc::new(db);
+    info!("Database manager initialized");
+
+    // Connect to Redis, on a connection of its own since stream reads block it
+    let redis_client = redis_client::create_client(&cfg)?;
+    let redis_connection = redis_client::connect_with_retry(&redis_client, &cfg).await?;
+
+    let mut watchdog = PipelineWatchdog::init(&cfg, db.clone(), redis_connection).await?;
+    watchdog.run().await;
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use dotenvy::dotenv;
+use tracing::{instrument, info};
+use std::collections::HashMap;
+
+use crypto_yield_farming_bot::logging;
+use crypto_yield_farming_bot::config;
+use crypto_yield_farming_bot::db::db_manager::DbManager;
+use crypto_yield_farming_bot::db::models::pending_plans::PlanStatus;
+
+const USAGE: &str = "Usage: plan <list | show <plan_id> | approve <plan_id> [action_id...] | reject <plan_id> [action_id...]>";
+
+#[instrument(name = "plan_main")]
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    // Load environment variables from .env file
+    dotenv()?;
+
+    // Initialize logging
+    if let Err(e) = logging::init_logging(env!("CARGO_BIN_NAME").to_string()) {
+        eprintln!("Failed to initialize logging: {}", e);
+        return Err(e.into());
+    }
+
+    // Load configuration (including provider)
+    let cfg = config::Config::load().await;
+    info!(network_mode = %cfg.network_mode, "Configuration loaded and logging initialized");
+
+    // Initialize db manager
+    let db = DbManager::init(&cfg).await?;
+    info!("Database manager initialized");
+
+    // Expire stale plans before showing or deciding anything
+    db.expire_stale_plans().await?;
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command = args.first().map(String::as_str).unwrap_or("list");
+    let parse_id = |s: &String| s.parse::<i32>().map_err(|_| eyre::eyre!("Invalid id: {}\n{}", s, USAGE));
+
+    match command {
+        "list" => {
+            let plans = db.get_undecided_plans().await?;
+            if plans.is_empty() {
+                info!("No plans awaiting approval");
+            }
+            for plan in plans {
+                info!(plan_id = plan.id, created_at = %plan.created_at, expires_at = %plan.expires_at, "Plan awaiting approval");
+            }
+        }
+        "show" => {
+            let plan_id = parse_id(args.get(1).ok_or_else(|| eyre::eyre!(USAGE))?)?;
+            let (plan, actions) = db.get_pending_plan(plan_id).await?
+                .ok_or_else(|| eyre::eyre!("Plan {} not found", plan_id))?;
+            let display_names = db.get_market_display_names().await?;
+            let names_by_id: HashMap<i32, String> = db.market_id_map.iter()
+                .map(|(address, id)| (*id, display_names.get(address).cloned().unwrap_or_else(|| format!("{:?}", address))))
+                .collect();
+            let action_summary = actions.iter()
+                .map(|a| format!(
+                    "#{} {}: Weight={:.2}%, Return={:.5}bps [{}]{}",
+                    a.id,
+                    names_by_id.get(&a.market_id).cloned().unwrap_or_else(|| a.market_id.to_string()),
+                    a.target_weight * rust_decimal::Decimal::from(100),
+                    a.expected_return.unwrap_or_default() * rust_decimal::Decimal::from(10000),
+                    a.status,
+                    a.notes.as_ref().map(|n| format!(" - {}", n)).unwrap_or_default()
+                ))
+                .collect::<Vec<_>>()
+                .join("\n  ");
+            info!(
+                plan_id = plan.id,
+                status = %plan.status,
+                expires_at = %plan.expires_at,
+                "Plan actions:\n  {}",
+                action_summary
+            );
+        }
+        "approve" | "reject" => {
+            let plan_id = parse_id(args.get(1).ok_or_else(|| eyre::eyre!(USAGE))?)?;
+            let action_ids = args[2..].iter().map(parse_id).collect::<eyre::Result<Vec<i32>>>()?;
+            let action_ids = if action_ids.is_empty() { None } else { Some(action_ids) };
+            let status = if command == "approve" { PlanStatus::Approved } else { PlanStatus::Rejected };
+            let updated = db.decide_pending_plan(plan_id, action_ids, status).await
+                .map_err(|e| eyre::eyre!("Failed to decide plan {} (it may be expired or already decided): {}", plan_id, e))?;
+            info!(plan_id = plan_id, decision = status.as_str(), updated_actions = updated, "Plan decision recorded");
+        }
+        _ => return Err(eyre::eyre!(USAGE)),
+    }
+
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await; // Allow time for logging to flush
+
+    Ok(())
+}
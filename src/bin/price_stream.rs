@@ -0,0 +1,211 @@
+use dotenvy::dotenv;
+use redis::AsyncCommands;
+use redis::streams::{StreamReadOptions, StreamReadReply};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use std::time::Duration;
+use tracing::{instrument, info, debug, warn, error};
+
+use crypto_yield_farming_bot::logging;
+use crypto_yield_farming_bot::config;
+use crypto_yield_farming_bot::redis_client;
+use crypto_yield_farming_bot::db::db_manager::DbManager;
+use crypto_yield_farming_bot::db::subscriber::{DbChannel, DbEvent, DbSubscriber};
+
+/// Data streams relayed to clients, as published by the data collector
+const STREAMS: [&str; 2] = ["token_prices", "market_states"];
+/// Database notifications relayed to clients, as the `strategy_runs` and `trades` streams
+const DB_CHANNELS: [DbChannel; 2] = [DbChannel::StrategyRuns, DbChannel::Trades];
+const STREAM_PATH: &str = "/stream";
+const CLIENT_BUFFER_SIZE: usize = 1024; // Entries a slow client may fall behind by before it skips ahead
+const HEARTBEAT_INTERVAL_SECS: u64 = 15; // Keeps idle connections open through proxies
+const MAX_REQUEST_HEAD_BYTES: usize = 8 * 1024;
+const REQUEST_HEAD_TIMEOUT_SECS: u64 = 10; // An idle or slow client is dropped instead of holding its task
+
+/// A stream entry as sent to clients: the stream name is the SSE event name, the entry ID the event ID
+#[derive(Debug, Clone)]
+struct StreamEvent {
+    stream: String,
+    id: String,
+    data: String,
+}
+
+/// Serve newly collected token prices and market states as Server-Sent Events on `GET /stream`, relayed from the
+/// Redis data streams as the collector publishes them (before the recorder has written them to Postgres), so
+/// consumers get push updates without polling the database. Strategy runs and trade status changes of the configured
+/// account are relayed from the database's notifications as the `strategy_runs` and `trades` streams.
+/// `GET /stream?streams=token_prices` limits the events to the listed streams. Events carry the collector's (or the
+/// notification's) JSON payload.
+#[instrument(name = "price_stream_main")]
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    // Load environment variables from .env file
+    dotenv()?;
+
+    // Initialize logging
+    if let Err(e) = logging::init_logging(env!("CARGO_BIN_NAME").to_string()) {
+        eprintln!("Failed to initialize logging: {}", e);
+        return Err(e.into());
+    }
+
+    // Load configuration (including provider)
+    let cfg = config::Config::load().await;
+    info!(network_mode = %cfg.network_mode, "Configuration loaded and logging initialized");
+
+    // Connect to Redis, on a connection of its own since stream reads block it
+    let redis_client = redis_client::create_client(&cfg)?;
+    let redis_connection = redis_client::connect_with_retry(&redis_client, &cfg).await?;
+
+    // Relay new stream entries to every connected client
+    let (events_tx, _) = broadcast::channel::<StreamEvent>(CLIENT_BUFFER_SIZE);
+    let relay_tx = events_tx.clone();
+    tokio::spawn(async move {
+        if let Err(e) = relay_stream_entries(redis_connection, relay_tx).await {
+            error!(error = ?e, "Stream relay stopped");
+            std::process::exit(1);
+        }
+    });
+
+    // Relay new strategy runs and trade status changes as they are recorded
+    let db = DbManager::init(&cfg).await?;
+    let subscriber = db.subscribe(&DB_CHANNELS).await?;
+    let relay_tx = events_tx.clone();
+    tokio::spawn(async move {
+        if let Err(e) = relay_db_notifications(subscriber, relay_tx).await {
+            error!(error = ?e, "Database notification relay stopped");
+            std::process::exit(1);
+        }
+    });
+
+    let listener = TcpListener::bind(&cfg.price_stream_addr).await?;
+    info!(addr = %cfg.price_stream_addr, path = STREAM_PATH, "Price stream server listening");
+    loop {
+        let (socket, peer) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                warn!(error = ?e, "Failed to accept connection");
+                continue;
+            }
+        };
+        let events_rx = events_tx.subscribe();
+        tokio::spawn(async move {
+            match serve_client(socket, events_rx).await {
+                Ok(()) => debug!(peer = %peer, "Client disconnected"),
+                Err(e) => debug!(peer = %peer, error = ?e, "Client connection closed"),
+            }
+        });
+    }
+}
+
+/// Read new entries of the data streams forever, starting from the latest, and broadcast them
+async fn relay_stream_entries(mut redis_connection: redis::aio::MultiplexedConnection, events_tx: broadcast::Sender<StreamEvent>) -> eyre::Result<()> {
+    let stream_options = StreamReadOptions::default().block(0).count(100);
+    let mut last_ids: Vec<String> = STREAMS.iter().map(|_| "$".to_string()).collect();
+
+    info!("Starting Redis stream relay");
+    loop {
+        let reply: StreamReadReply = redis_connection.xread_options(&STREAMS, &last_ids, &stream_options).await?;
+        for stream_key in reply.keys {
+            let Some(index) = STREAMS.iter().position(|stream| *stream == stream_key.key) else {
+                continue;
+            };
+            for entry in stream_key.ids {
+                last_ids[index] = entry.id.clone();
+                let Some(redis::Value::BulkString(payload)) = entry.map.get("data") else {
+                    continue;
+                };
+                let Ok(data) = String::from_utf8(payload.clone()) else {
+                    warn!(stream = %stream_key.key, id = %entry.id, "Stream entry payload is not UTF-8, skipped");
+                    continue;
+                };
+                // No receivers just means no client is connected
+                let _ = events_tx.send(StreamEvent { stream: stream_key.key.clone(), id: entry.id.clone(), data });
+            }
+        }
+    }
+}
+
+/// Broadcast database notifications forever, the run or trade ID (and trade status) as the event ID
+async fn relay_db_notifications(mut subscriber: DbSubscriber, events_tx: broadcast::Sender<StreamEvent>) -> eyre::Result<()> {
+    info!("Starting database notification relay");
+    loop {
+        let event = match subscriber.recv().await? {
+            DbEvent::StrategyRunInserted(run) => StreamEvent {
+                stream: "strategy_runs".to_string(),
+                id: run.id.to_string(),
+                data: serde_json::to_string(&run)?,
+            },
+            DbEvent::TradeChanged(trade) => StreamEvent {
+                stream: "trades".to_string(),
+                id: format!("{}-{}", trade.id, trade.status),
+                data: serde_json::to_string(&trade)?,
+            },
+            DbEvent::MarketStatesInserted(_) | DbEvent::Reconnected => continue,
+        };
+        // No receivers just means no client is connected
+        let _ = events_tx.send(event);
+    }
+}
+
+/// Answer one HTTP request: stream events for `GET /stream` until the client goes away, 404 otherwise
+async fn serve_client(mut socket: TcpStream, mut events_rx: broadcast::Receiver<StreamEvent>) -> eyre::Result<()> {
+    let head = tokio::time::timeout(Duration::from_secs(REQUEST_HEAD_TIMEOUT_SECS), read_request_head(&mut socket)).await
+        .map_err(|_| eyre::eyre!("Timed out reading the request head"))??;
+    let request_line = head.lines().next().unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or_default(), parts.next().unwrap_or_default());
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    if method != "GET" || path != STREAM_PATH {
+        socket.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").await?;
+        return Ok(());
+    }
+
+    // Streams the client asked for, all of them unless listed
+    let requested: Vec<String> = query.split('&')
+        .filter_map(|param| param.strip_prefix("streams="))
+        .flat_map(|streams| streams.split(',').map(str::to_string))
+        .collect();
+    let wanted = |stream: &str| requested.is_empty() || requested.iter().any(|r| r == stream);
+
+    socket.write_all(
+        b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\nAccess-Control-Allow-Origin: *\r\n\r\n"
+    ).await?;
+    socket.write_all(format!("retry: {}\n\n", HEARTBEAT_INTERVAL_SECS * 1000).as_bytes()).await?;
+
+    let mut heartbeat = tokio::time::interval(Duration::from_secs(HEARTBEAT_INTERVAL_SECS));
+    loop {
+        tokio::select! {
+            event = events_rx.recv() => match event {
+                Ok(event) if wanted(&event.stream) => {
+                    let message = format!("id: {}\nevent: {}\ndata: {}\n\n", event.id, event.stream, event.data);
+                    socket.write_all(message.as_bytes()).await?;
+                }
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!(skipped = skipped, "Client fell behind, skipping ahead");
+                    socket.write_all(format!(": skipped {} events\n\n", skipped).as_bytes()).await?;
+                }
+                Err(broadcast::error::RecvError::Closed) => return Ok(()),
+            },
+            _ = heartbeat.tick() => socket.write_all(b": heartbeat\n\n").await?,
+        }
+    }
+}
+
+/// Read the request line and headers, up to the blank line ending them
+async fn read_request_head(socket: &mut TcpStream) -> eyre::Result<String> {
+    let mut head = Vec::new();
+    let mut buffer = [0u8; 1024];
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+        if head.len() > MAX_REQUEST_HEAD_BYTES {
+            return Err(eyre::eyre!("Request head too large"));
+        }
+        let read = socket.read(&mut buffer).await?;
+        if read == 0 {
+            return Err(eyre::eyre!("Connection closed before the request was complete"));
+        }
+        head.extend_from_slice(&buffer[..read]);
+    }
+    Ok(String::from_utf8_lossy(&head).into_owned())
+}
@@ -0,0 +1,78 @@
+use dotenvy::dotenv;
+use tracing::{instrument, info, warn};
+use std::sync::Arc;
+use rust_decimal::Decimal;
+
+use crypto_yield_farming_bot::logging;
+use crypto_yield_farming_bot::config::{self, dynamic::DynamicConfig};
+use crypto_yield_farming_bot::wallet::WalletManager;
+use crypto_yield_farming_bot::db::db_manager::DbManager;
+use crypto_yield_farming_bot::gm_token_txs::plan_executor::GmPlanExecutor;
+use crypto_yield_farming_bot::strategy::raise_cash;
+
+const USAGE: &str = "Usage: raise_cash <amount_usd> [--execute]";
+
+/// Plan the GM withdrawals raising a cash amount for a scheduled withdrawal from the fund, trimming the positions
+/// with the lowest expected return and exit cost first, without a full strategy run. The plan is only logged unless
+/// `--execute` is given.
+#[instrument(name = "raise_cash_main")]
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    // Load environment variables from .env file
+    dotenv()?;
+
+    // Initialize logging
+    if let Err(e) = logging::init_logging(env!("CARGO_BIN_NAME").to_string()) {
+        eprintln!("Failed to initialize logging: {}", e);
+        return Err(e.into());
+    }
+
+    // Parse the command before connecting to anything
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let amount_usd = match args.first() {
+        Some(s) => s.parse::<Decimal>().map_err(|_| eyre::eyre!("Invalid amount: {}\n{}", s, USAGE))?,
+        None => return Err(eyre::eyre!(USAGE)),
+    };
+    let execute = match args.get(1).map(String::as_str) {
+        Some("--execute") => true,
+        Some(other) => return Err(eyre::eyre!("Unknown argument: {}\n{}", other, USAGE)),
+        None => false,
+    };
+
+    // Load configuration (including provider)
+    let cfg = config::Config::load().await;
+    info!(network_mode = %cfg.network_mode, account_id = %cfg.account_id, "Configuration loaded and logging initialized");
+
+    // Initialize db manager
+    let db = DbManager::init(&cfg).await?;
+    let db = Arc::new(db);
+    info!("Database manager initialized");
+
+    let dynamic_config = DynamicConfig::load(&cfg, db.clone()).await?;
+    info!("Dynamic config loaded");
+
+    // Initialize and load wallet manager
+    let wallet_manager = WalletManager::new(&cfg)?;
+    wallet_manager.load_tokens(&db).await?;
+    let wallet_manager = Arc::new(wallet_manager);
+    info!("Wallet manager initialized and tokens loaded");
+
+    let params = dynamic_config.params().await;
+    let plan = raise_cash::plan_raise_cash(&cfg, &db, &params, &wallet_manager, amount_usd).await?;
+
+    if !execute || plan.trims.is_empty() {
+        info!("Cash raise planned, not executed");
+    } else if params.safe_mode {
+        warn!("Safe mode enabled, cash raise not executed");
+    } else if cfg.approval_mode {
+        warn!(trim_count = plan.trims.len(), "Approval mode enabled, cash raise withdrawals must be executed manually");
+    } else {
+        let plan_executor = GmPlanExecutor::new(cfg.clone(), wallet_manager.clone(), db.clone(), dynamic_config.clone());
+        let submitted = raise_cash::execute_cash_raise(&plan_executor, &plan, cfg.rebalance_id).await;
+        info!(submitted = submitted, planned = plan.trims.len(), raised_usd = %plan.raised_usd(), "Cash raise finished");
+    }
+
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await; // Allow time for logging to flush
+
+    Ok(())
+}
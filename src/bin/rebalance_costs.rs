@@ -0,0 +1,63 @@
+use dotenvy::dotenv;
+use tracing::{instrument, info};
+
+use crypto_yield_farming_bot::logging;
+use crypto_yield_farming_bot::config;
+use crypto_yield_farming_bot::db::db_manager::DbManager;
+use crypto_yield_farming_bot::accounting;
+
+const USAGE: &str = "Usage: rebalance_costs [lookback_days] | rebalance_costs start <source> | rebalance_costs complete <rebalance_id>";
+const DEFAULT_LOOKBACK_DAYS: i64 = 7;
+
+/// Report the net cost of recent rebalances broken down by leg. `start` opens a rebalance for legs run by their own
+/// binaries (run them with REBALANCE_ID set to the printed ID) and `complete` closes it.
+#[instrument(name = "rebalance_costs_main")]
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    // Load environment variables from .env file
+    dotenv()?;
+
+    // Initialize logging
+    if let Err(e) = logging::init_logging(env!("CARGO_BIN_NAME").to_string()) {
+        eprintln!("Failed to initialize logging: {}", e);
+        return Err(e.into());
+    }
+
+    // Load configuration (including provider)
+    let cfg = config::Config::load().await;
+    info!(network_mode = %cfg.network_mode, account_id = %cfg.account_id, "Configuration loaded and logging initialized");
+
+    // Initialize db manager
+    let db = DbManager::init(&cfg).await?;
+    info!("Database manager initialized");
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("start") => {
+            let source = args.get(1).ok_or_else(|| eyre::eyre!(USAGE))?;
+            let rebalance_id = db.begin_rebalance(source).await?;
+            println!("{}", rebalance_id);
+        }
+        Some("complete") => {
+            let rebalance_id = match args.get(1) {
+                Some(arg) => arg.parse::<i32>().map_err(|_| eyre::eyre!("Invalid rebalance ID: {}\n{}", arg, USAGE))?,
+                None => return Err(eyre::eyre!(USAGE)),
+            };
+            db.complete_rebalance(rebalance_id).await?;
+        }
+        arg => {
+            let lookback_days = match arg {
+                Some(arg) => arg.parse::<i64>().map_err(|_| eyre::eyre!("Invalid lookback days: {}\n{}", arg, USAGE))?,
+                None => DEFAULT_LOOKBACK_DAYS,
+            };
+            let since = db.clock.now() - chrono::Duration::days(lookback_days);
+            let costs = db.get_rebalance_costs_since(since).await?;
+            info!(lookback_days = lookback_days, "Rebalance costs loaded");
+            accounting::log_rebalance_cost_report(&costs);
+        }
+    }
+
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await; // Allow time for logging to flush
+
+    Ok(())
+}
@@ -1,10 +1,14 @@
 use dotenvy::dotenv;
 use tracing::{instrument, info};
+use chrono::Utc;
+use std::sync::Arc;
 
 use crypto_yield_farming_bot::logging;
 use crypto_yield_farming_bot::config;
 use crypto_yield_farming_bot::wallet::WalletManager;
 use crypto_yield_farming_bot::db::db_manager::DbManager;
+use crypto_yield_farming_bot::reporting_currency::ReportingCurrency;
+use crypto_yield_farming_bot::hedging::{dydx_client::DydxClient, hyperliquid_client::HyperliquidClient, hedge_venue::{self, PerpVenue}};
 
 #[instrument(name = "trading_bot_main")]
 #[tokio::main]
@@ -24,16 +28,37 @@ async fn main() -> eyre::Result<()> {
 
     // Initialize db manager
     let db = DbManager::init(&cfg).await?;
+    let db = Arc::new(db);
     info!("Database manager initialized");
 
     // Initialize and load wallet manager
-    let mut wallet_manager = WalletManager::new(&cfg)?;
+    let wallet_manager = WalletManager::new(&cfg)?;
     wallet_manager.load_tokens(&db).await?;
+    let wallet_manager = Arc::new(wallet_manager);
     info!(address = ?wallet_manager.address, "Wallet manager initialized");
 
     // Log wallet token balances
     wallet_manager.log_all_balances(false).await?;
-    
+
+    // Log total wallet value in the reporting currency
+    let reporting_currency = ReportingCurrency::load(&cfg, &db).await?;
+    let total_value_usd = wallet_manager.get_total_value_usd().await?;
+    let total_value = reporting_currency.convert_usd_at(&db, total_value_usd, Utc::now()).await?;
+    info!(
+        total_value_usd = %total_value_usd.round_dp(2),
+        "Total wallet value: {:.4} {}",
+        total_value,
+        reporting_currency.symbol
+    );
+
+    // Log open perp hedge positions, consolidated across venues
+    let dydx_client = DydxClient::new(cfg.clone(), wallet_manager.clone(), db.clone()).await?;
+    let mut hedge_venues: Vec<Box<dyn PerpVenue>> = vec![Box::new(dydx_client)];
+    if cfg.hyperliquid_enabled {
+        hedge_venues.push(Box::new(HyperliquidClient::new(cfg.clone(), wallet_manager.clone(), db.clone())?));
+    }
+    hedge_venue::log_consolidated_positions(&hedge_venue::get_consolidated_positions(&hedge_venues).await?);
+
     tokio::time::sleep(std::time::Duration::from_secs(1)).await; // Allow time for logging to flush
 
     Ok(())
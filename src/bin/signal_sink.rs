@@ -0,0 +1,152 @@
+use dotenvy::dotenv;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{instrument, info, debug, warn};
+
+use crypto_yield_farming_bot::logging;
+use crypto_yield_farming_bot::config::{self, Config};
+use crypto_yield_farming_bot::db::db_manager::DbManager;
+use crypto_yield_farming_bot::signal_sink;
+
+const SIGNALS_PATH: &str = "/signals";
+const SIGNATURE_HEADER: &str = "x-signature";
+const MAX_REQUEST_HEAD_BYTES: usize = 8 * 1024;
+const MAX_BODY_BYTES: usize = 64 * 1024;
+const REQUEST_READ_TIMEOUT_SECS: u64 = 10; // Covers the head and body together, so a trickling client can't hold its task
+
+/// Accept signed external strategy signals on `POST /signals` and queue them for the strategy engine. The body is a
+/// JSON payload of per-market expected return overrides and vetoes, signed with personal_sign by a signer listed in
+/// EXTERNAL_SIGNAL_TRUST, the signature sent in the `X-Signature` header. Each trusted signer's expected returns are
+/// blended with the engine's own estimates by its trust weight, and any of its vetoes keep the market unallocated,
+/// until the payload expires.
+#[instrument(name = "signal_sink_main")]
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    // Load environment variables from .env file
+    dotenv()?;
+
+    // Initialize logging
+    if let Err(e) = logging::init_logging(env!("CARGO_BIN_NAME").to_string()) {
+        eprintln!("Failed to initialize logging: {}", e);
+        return Err(e.into());
+    }
+
+    // Load configuration (including provider)
+    let cfg = Arc::new(config::Config::load().await);
+    info!(network_mode = %cfg.network_mode, "Configuration loaded and logging initialized");
+    if cfg.external_signal_trust.is_empty() {
+        warn!("No trusted signers in EXTERNAL_SIGNAL_TRUST, every payload will be rejected");
+    }
+
+    // Initialize db manager
+    let db = DbManager::init(&cfg).await?;
+    let db = Arc::new(db);
+    info!("Database manager initialized");
+
+    let listener = TcpListener::bind(&cfg.signal_sink_addr).await?;
+    info!(addr = %cfg.signal_sink_addr, path = SIGNALS_PATH, trusted_signers = cfg.external_signal_trust.len(), "Signal sink listening");
+    loop {
+        let (socket, peer) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                warn!(error = ?e, "Failed to accept connection");
+                continue;
+            }
+        };
+        let (cfg, db) = (cfg.clone(), db.clone());
+        tokio::spawn(async move {
+            if let Err(e) = serve_client(&cfg, &db, socket).await {
+                debug!(peer = %peer, error = ?e, "Client connection closed");
+            }
+        });
+    }
+}
+
+/// Answer one HTTP request: queue the payload of `POST /signals`, 404 otherwise
+async fn serve_client(config: &Config, db: &DbManager, mut socket: TcpStream) -> eyre::Result<()> {
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(REQUEST_READ_TIMEOUT_SECS);
+    let Ok(read) = tokio::time::timeout_at(deadline, read_request_head(&mut socket)).await else {
+        return respond(&mut socket, "408 Request Timeout", "{\"error\":\"request timed out\"}").await;
+    };
+    let (head, mut body) = read?;
+    let mut lines = head.lines();
+    let mut parts = lines.next().unwrap_or_default().split_whitespace();
+    let (method, path) = (parts.next().unwrap_or_default(), parts.next().unwrap_or_default());
+    if method != "POST" || path != SIGNALS_PATH {
+        return respond(&mut socket, "404 Not Found", "{\"error\":\"not found\"}").await;
+    }
+
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+        .collect();
+    let header = |name: &str| headers.iter().find(|(header, _)| header == name).map(|(_, value)| value.as_str());
+    let Some(signature) = header(SIGNATURE_HEADER) else {
+        return respond(&mut socket, "401 Unauthorized", "{\"error\":\"missing X-Signature header\"}").await;
+    };
+    let Some(content_length) = header("content-length").and_then(|length| length.parse::<usize>().ok()) else {
+        return respond(&mut socket, "411 Length Required", "{\"error\":\"missing Content-Length header\"}").await;
+    };
+    if content_length > MAX_BODY_BYTES {
+        return respond(&mut socket, "413 Payload Too Large", "{\"error\":\"payload too large\"}").await;
+    }
+
+    // The head read may already hold part of the body
+    let mut buffer = [0u8; 4096];
+    while body.len() < content_length {
+        let Ok(read) = tokio::time::timeout_at(deadline, socket.read(&mut buffer)).await else {
+            return respond(&mut socket, "408 Request Timeout", "{\"error\":\"request timed out\"}").await;
+        };
+        let read = read?;
+        if read == 0 {
+            return Err(eyre::eyre!("Connection closed before the body was complete"));
+        }
+        body.extend_from_slice(&buffer[..read]);
+    }
+    body.truncate(content_length);
+
+    match signal_sink::accept_payload(config, db, &body, signature).await {
+        Ok((signer, count)) => {
+            let response = serde_json::json!({ "signer": format!("{:?}", signer), "accepted": count });
+            respond(&mut socket, "202 Accepted", &response.to_string()).await
+        }
+        Err(e) => {
+            warn!(error = %e, "External signal payload rejected");
+            let response = serde_json::json!({ "error": e.to_string() });
+            respond(&mut socket, e.status(), &response.to_string()).await
+        }
+    }
+}
+
+async fn respond(socket: &mut TcpStream, status: &str, body: &str) -> eyre::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    socket.write_all(response.as_bytes()).await?;
+    Ok(())
+}
+
+/// Read the request line and headers, up to the blank line ending them. Returns the head and any body bytes read past it.
+async fn read_request_head(socket: &mut TcpStream) -> eyre::Result<(String, Vec<u8>)> {
+    let mut head = Vec::new();
+    let mut buffer = [0u8; 1024];
+    loop {
+        if let Some(end) = head.windows(4).position(|window| window == b"\r\n\r\n") {
+            let body = head.split_off(end + 4);
+            return Ok((String::from_utf8_lossy(&head).into_owned(), body));
+        }
+        if head.len() > MAX_REQUEST_HEAD_BYTES {
+            return Err(eyre::eyre!("Request head too large"));
+        }
+        let read = socket.read(&mut buffer).await?;
+        if read == 0 {
+            return Err(eyre::eyre!("Connection closed before the request was complete"));
+        }
+        head.extend_from_slice(&buffer[..read]);
+    }
+}
@@ -8,10 +8,11 @@ use rust_decimal::Decimal;
 use rust_decimal::prelude::*;
 
 use crypto_yield_farming_bot::logging;
-use crypto_yield_farming_bot::config;
+use crypto_yield_farming_bot::config::{self, dynamic::DynamicConfig};
 use crypto_yield_farming_bot::wallet::WalletManager;
 use crypto_yield_farming_bot::db::db_manager::DbManager;
 use crypto_yield_farming_bot::spot_swap::swap_manager::SwapManager;
+use crypto_yield_farming_bot::spot_swap::twap;
 use crypto_yield_farming_bot::spot_swap::types::SwapRequest;
 
 #[tokio::main]
@@ -31,10 +32,12 @@ async fn main() -> Result<()> {
 
     // Initialize db manager
     let db = DbManager::init(&cfg).await?;
+    let db = Arc::new(db);
+    let dynamic_config = DynamicConfig::load(&cfg, db.clone()).await?;
     info!("Database manager initialized");
 
     // Initialize and load wallet manager
-    let mut wallet_manager = WalletManager::new(&cfg)?;
+    let wallet_manager = WalletManager::new(&cfg)?;
     wallet_manager.load_tokens(&db).await?;
     let wallet_manager = Arc::new(wallet_manager);
     info!("Wallet manager initialized and tokens loaded");
@@ -43,7 +46,7 @@ async fn main() -> Result<()> {
     wallet_manager.log_all_balances(false).await?;
 
     // Initialize Spot Swap Manager
-    let swap_manager = SwapManager::new(&cfg, wallet_manager.clone());
+    let swap_manager = SwapManager::new(&cfg, wallet_manager.clone(), db.clone(), dynamic_config);
     info!("Spot Swap Manager initialized");
 
     // Example usage of Spot Swap Manager Swap
@@ -52,9 +55,10 @@ async fn main() -> Result<()> {
         to_token_address: Address::from_str(crypto_yield_farming_bot::constants::NATIVE_ADDRESS).unwrap(), // NATIVE ETH
         amount: Decimal::from_f64(0.0001).unwrap(), // SELL 0001 WETH
         side: "SELL".to_string(), // Selling WETH for NATIVE ETH
+        rebalance_id: cfg.rebalance_id,
     };
     info!("Executing swap request: {:?}", swap_request);
-    if let Err(e) = swap_manager.execute_swap(&swap_request).await {
+    if let Err(e) = twap::execute_twap_swap(&cfg, &wallet_manager, &swap_manager, &db, &swap_request).await {
         error!(error = ?e, "Failed to execute swap request");
         return Err(e.into());
     }
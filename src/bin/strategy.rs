@@ -0,0 +1,85 @@
+use dotenvy::dotenv;
+use tracing::{instrument, info};
+use std::collections::HashMap;
+
+use crypto_yield_farming_bot::logging;
+use crypto_yield_farming_bot::config;
+use crypto_yield_farming_bot::db::db_manager::DbManager;
+
+const USAGE: &str = "Usage: strategy inspect <run_id>";
+
+#[instrument(name = "strategy_main")]
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    // Load environment variables from .env file
+    dotenv()?;
+
+    // Initialize logging
+    if let Err(e) = logging::init_logging(env!("CARGO_BIN_NAME").to_string()) {
+        eprintln!("Failed to initialize logging: {}", e);
+        return Err(e.into());
+    }
+
+    // Load configuration (including provider)
+    let cfg = config::Config::load().await;
+    info!(network_mode = %cfg.network_mode, "Configuration loaded and logging initialized");
+
+    // Initialize db manager
+    let db = DbManager::init(&cfg).await?;
+    info!("Database manager initialized");
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("inspect") => {
+            let run_id_arg = args.get(1).ok_or_else(|| eyre::eyre!(USAGE))?;
+            let run_id = run_id_arg.parse::<i32>().map_err(|_| eyre::eyre!("Invalid run id: {}\n{}", run_id_arg, USAGE))?;
+            let run = db.get_strategy_run(run_id).await?
+                .ok_or_else(|| eyre::eyre!("Strategy run {} not found", run_id))?;
+            let outputs: HashMap<i32, (rust_decimal::Decimal, rust_decimal::Decimal)> = db.get_strategy_run_markets(run_id).await?
+                .into_iter()
+                .map(|m| (m.market_id, (m.expected_return_bps, m.target_weight)))
+                .collect();
+            let inputs = db.get_strategy_run_inputs(run_id).await?;
+
+            let display_names = db.get_market_display_names().await?;
+            let names_by_id: HashMap<i32, String> = db.market_id_map.iter()
+                .map(|(address, id)| (*id, display_names.get(address).cloned().unwrap_or_else(|| format!("{:?}", address))))
+                .collect();
+            let format_opt = |v: Option<String>| v.unwrap_or_else(|| "N/A".to_string());
+
+            let input_summary = inputs.iter()
+                .map(|i| {
+                    let (expected_return_bps, target_weight) = outputs.get(&i.market_id).copied().unwrap_or_default();
+                    format!(
+                        "{}: Rows={} (index prices {}), Range={} -> {}, MeanFeeRate={:.5}bps, Volatility={:.5}bps, LastIndexPrice={}, PoolValue={:.2} USD, DataQuality={}, Return={:.5}bps, Weight={:.2}%",
+                        names_by_id.get(&i.market_id).cloned().unwrap_or_else(|| i.market_id.to_string()),
+                        i.market_row_count,
+                        i.index_price_row_count,
+                        format_opt(i.first_timestamp.map(|t| t.to_string())),
+                        format_opt(i.last_timestamp.map(|t| t.to_string())),
+                        i.mean_fee_rate * rust_decimal::Decimal::from(10000),
+                        i.volatility * rust_decimal::Decimal::from(10000),
+                        format_opt(i.last_index_price.map(|p| p.to_string())),
+                        i.pool_value_usd,
+                        format_opt(i.data_quality.map(|q| format!("{:.2}", q))),
+                        expected_return_bps,
+                        target_weight * rust_decimal::Decimal::from(100),
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n  ");
+            info!(
+                run_id = run.id,
+                created_at = %run.created_at,
+                market_count = inputs.len(),
+                "Strategy run inputs:\n  {}",
+                if inputs.is_empty() { "N/A (run recorded before input digests were stored)".to_string() } else { input_summary }
+            );
+        }
+        _ => return Err(eyre::eyre!(USAGE)),
+    }
+
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await; // Allow time for logging to flush
+
+    Ok(())
+}
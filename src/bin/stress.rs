@@ -0,0 +1,63 @@
+use dotenvy::dotenv;
+use tracing::{instrument, info};
+use std::sync::Arc;
+
+use crypto_yield_farming_bot::logging;
+use crypto_yield_farming_bot::config;
+use crypto_yield_farming_bot::wallet::WalletManager;
+use crypto_yield_farming_bot::db::db_manager::DbManager;
+use crypto_yield_farming_bot::stress::{self, StressScenario};
+use crypto_yield_farming_bot::hedging::{dydx_client::DydxClient, hyperliquid_client::HyperliquidClient, hedge_venue::PerpVenue};
+
+const USAGE: &str = "Usage: stress [ASSET=PERCENT ...] (e.g. stress ETH=-30 BTC=-30 *=-50, defaults to a market-wide crash)";
+
+#[instrument(name = "stress_main")]
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    // Load environment variables from .env file
+    dotenv()?;
+
+    // Initialize logging
+    if let Err(e) = logging::init_logging(env!("CARGO_BIN_NAME").to_string()) {
+        eprintln!("Failed to initialize logging: {}", e);
+        return Err(e.into());
+    }
+
+    // Parse the scenario before connecting to anything
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let scenario = if args.is_empty() {
+        StressScenario::crash()
+    } else {
+        StressScenario::parse(&args).map_err(|e| eyre::eyre!("{}\n{}", e, USAGE))?
+    };
+
+    // Load configuration (including provider)
+    let cfg = config::Config::load().await;
+    info!(network_mode = %cfg.network_mode, "Configuration loaded and logging initialized");
+
+    // Initialize db manager
+    let db = DbManager::init(&cfg).await?;
+    let db = Arc::new(db);
+    info!("Database manager initialized");
+
+    // Initialize and load wallet manager
+    let wallet_manager = WalletManager::new(&cfg)?;
+    wallet_manager.load_tokens(&db).await?;
+    let wallet_manager = Arc::new(wallet_manager);
+    info!(address = ?wallet_manager.address, "Wallet manager initialized");
+
+    // Perp venues holding the hedges
+    let dydx_client = DydxClient::new(cfg.clone(), wallet_manager.clone(), db.clone()).await?;
+    let mut hedge_venues: Vec<Box<dyn PerpVenue>> = vec![Box::new(dydx_client)];
+    if cfg.hyperliquid_enabled {
+        hedge_venues.push(Box::new(HyperliquidClient::new(cfg.clone(), wallet_manager.clone(), db.clone())?));
+    }
+
+    // Shock the current holdings and report each one's contribution
+    let report = stress::stress_portfolio(&wallet_manager, &db, &hedge_venues, scenario).await?;
+    stress::log_stress_report(&report);
+
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await; // Allow time for logging to flush
+
+    Ok(())
+}
@@ -1,13 +1,24 @@
 use dotenvy::dotenv;
-use tracing::{instrument, info};
+use tracing::{instrument, info, debug, warn, error};
 use std::sync::Arc;
+use std::collections::HashSet;
 
 use crypto_yield_farming_bot::logging;
-use crypto_yield_farming_bot::config;
+use crypto_yield_farming_bot::telemetry;
+use crypto_yield_farming_bot::config::{self, dynamic::DynamicConfig};
 use crypto_yield_farming_bot::wallet::WalletManager;
 use crypto_yield_farming_bot::db::db_manager::DbManager;
-use crypto_yield_farming_bot::hedging::dydx_client::DydxClient;
-use crypto_yield_farming_bot::strategy::engine;
+use crypto_yield_farming_bot::hedging::{dydx_client::DydxClient, hyperliquid_client::HyperliquidClient, hedge_venue::{self, PerpVenue}};
+use crypto_yield_farming_bot::strategy::{engine, market_universe, freshness, pnl_model::ReturnEnsemble, approval, wind_down, utilization_guard, composition_drift, weight_smoothing, holding_period, deposit_capacity, withdrawal_liquidity, trade_size, rebalance, gm_costs, benchmark, fee_budget::FeeBudgetStatus, types::PortfolioSnapshot};
+use crypto_yield_farming_bot::constants::ARB_TOKEN_ADDRESS;
+use crypto_yield_farming_bot::gmx::callback_receiver::{GmxCallbackListener, SettledRequests};
+use crypto_yield_farming_bot::spot_swap::{gas_reserve, dust, swap_manager::SwapManager};
+use crypto_yield_farming_bot::gm_token_txs::{
+    gm_tx_manager::GmTxManager,
+    plan_executor::GmPlanExecutor,
+    types::{GmTxRequest, GmClaimRewardsRequest, GmClaimUiFeesRequest},
+};
+use crypto_yield_farming_bot::db::models::strategy_runs::{NewStrategyRunModel, NewStrategyRunMarketModel, NewStrategyRunInputModel};
 
 #[instrument(name = "trading_bot_main")]
 #[tokio::main]
@@ -23,32 +34,264 @@ async fn main() -> eyre::Result<()> {
 
     // Load configuration (including provider)
     let cfg = config::Config::load().await;
-    info!(network_mode = %cfg.network_mode, "Configuration loaded and logging initialized");
+    info!(network_mode = %cfg.network_mode, account_id = %cfg.account_id, "Configuration loaded and logging initialized");
 
     // Initialize db manager
     let db = DbManager::init(&cfg).await?;
     let db = Arc::new(db);
     info!("Database manager initialized");
 
+    // Load runtime config overrides and keep them fresh while the run is in progress
+    let dynamic_config = DynamicConfig::load(&cfg, db.clone()).await?;
+    dynamic_config.spawn_watcher();
+    if dynamic_config.safe_mode().await {
+        warn!("Safe mode enabled, skipping trading run");
+        return Ok(());
+    }
+    info!("Dynamic config loaded");
+
     // Initialize and load wallet manager
-    let mut wallet_manager = WalletManager::new(&cfg)?;
+    let wallet_manager = WalletManager::new(&cfg)?;
     wallet_manager.load_tokens(&db).await?;
     let wallet_manager = Arc::new(wallet_manager);
     info!("Wallet manager initialized");
 
-    // Initialize dydx client
-    let dydx_client = DydxClient::new(cfg.clone(), wallet_manager.clone()).await?;
-    let dydx_client = Arc::new(dydx_client);
+    // Initialize hedge venues in order of preference, dYdX first
+    let dydx_client = DydxClient::new(cfg.clone(), wallet_manager.clone(), db.clone()).await?;
+    let mut hedge_venues: Vec<Box<dyn PerpVenue>> = vec![Box::new(dydx_client)];
     info!("dYdX client initialized");
 
-    // Run strategy engine
-    let portfolio_data = engine::run_strategy_engine(db.clone(), dydx_client.clone()).await?;
+    // Hyperliquid is the fallback hedge venue for tokens dYdX doesn't list
+    if cfg.hyperliquid_enabled {
+        let hyperliquid_client = HyperliquidClient::new(cfg.clone(), wallet_manager.clone(), db.clone())?;
+        hedge_venues.push(Box::new(hyperliquid_client));
+        info!("Hyperliquid client initialized");
+    }
+
+    // Keep enough native ETH for gas before any other action runs
+    if cfg.approval_mode {
+        if let Some(top_up) = gas_reserve::plan_gas_reserve_top_up(&cfg, &wallet_manager).await? {
+            warn!(amount = %top_up.amount, "Approval mode enabled, native gas reserve is low and must be replenished manually");
+        }
+    } else {
+        let swap_manager = SwapManager::new(&cfg, wallet_manager.clone(), db.clone(), dynamic_config.clone());
+        if let Err(e) = gas_reserve::ensure_gas_reserve(&cfg, &wallet_manager, &swap_manager).await {
+            warn!(error = ?e, "Failed to replenish native gas reserve");
+        }
+
+        // Sweep small leftover asset balances into the base stablecoin
+        match dust::consolidate_dust(&cfg, &wallet_manager, &swap_manager).await {
+            Ok(swept) if swept > 0 => info!(swept = swept, "Dust balances consolidated"),
+            Ok(_) => {}
+            Err(e) => warn!(error = ?e, "Failed to consolidate dust balances"),
+        }
+    }
+
+    // Finish any plan a previous run was interrupted in, before planning new actions against stale holdings
+    let mut plan_executor = GmPlanExecutor::new(cfg.clone(), wallet_manager.clone(), db.clone(), dynamic_config.clone());
+
+    // With a callback receiver configured, dependencies settle on its callback events rather than on request list polling
+    let settled_requests = SettledRequests::new();
+    if let Some(callback_listener) = GmxCallbackListener::from_config(&cfg, settled_requests.clone()) {
+        tokio::spawn(async move {
+            if let Err(e) = callback_listener.start_listening().await {
+                error!(?e, "GMX callback listener stopped");
+            }
+        });
+        plan_executor = plan_executor.with_callbacks(settled_requests);
+    }
+    if cfg.approval_mode {
+        let incomplete_plans = db.get_incomplete_execution_plans().await?;
+        if !incomplete_plans.is_empty() {
+            warn!(plan_count = incomplete_plans.len(), "Approval mode enabled, interrupted execution plans must be resumed manually");
+        }
+    } else {
+        let resumed = plan_executor.resume_incomplete_plans().await?;
+        if resumed > 0 {
+            info!(resumed = resumed, "Interrupted execution plans resumed");
+        }
+    }
+
+    // Wind down positions in markets GMX has halted or deprecated, exits are not discretionary so they bypass the fee budget
+    let deprecated_exits = wind_down::plan_deprecated_market_exits(&cfg, &db, &wallet_manager).await?;
+    if !deprecated_exits.is_empty() {
+        if cfg.approval_mode {
+            warn!(exit_count = deprecated_exits.len(), "Approval mode enabled, deprecated market exits must be executed manually");
+        } else {
+            wind_down::execute_deprecated_market_exits(&plan_executor, &deprecated_exits, cfg.rebalance_id).await;
+        }
+    }
+
+    // Claim accrued pool incentives so they can be redeployed
+    if cfg.gmx_rewards_distributor.is_some() && !cfg.approval_mode {
+        let gm_tx_manager = GmTxManager::new(cfg.clone(), wallet_manager.clone(), db.clone());
+        let claim_request = GmClaimRewardsRequest { tokens: vec![ARB_TOKEN_ADDRESS.parse()?] };
+        if let Err(e) = gm_tx_manager.execute_transaction(&GmTxRequest::ClaimRewards(claim_request), cfg.rebalance_id).await {
+            warn!(error = ?e, "Failed to claim pool incentive rewards");
+        }
+    }
+
+    // Claim UI fees accrued to the wallet as UI fee receiver of its own GM requests
+    if cfg.gmx_ui_fee_receiver == Some(wallet_manager.address) && !cfg.approval_mode {
+        let gm_tx_manager = GmTxManager::new(cfg.clone(), wallet_manager.clone(), db.clone());
+        let claim_request = GmClaimUiFeesRequest { markets: wallet_manager.tokens().market_tokens.keys().copied().collect() };
+        if let Err(e) = gm_tx_manager.execute_transaction(&GmTxRequest::ClaimUiFees(claim_request), cfg.rebalance_id).await {
+            warn!(error = ?e, "Failed to claim GMX UI fees");
+        }
+    }
+
+    // Refresh tokens and prices before planning, exits and claims above may have run for a while
+    wallet_manager.refresh(&db).await?;
+
+    // Abort rather than plan against stale data when the ingestion or funding pipeline has stalled
+    let hedge_markets = hedge_venue::load_hedge_markets(&cfg, &hedge_venues).await?;
+    freshness::ensure_data_fresh(&cfg, &db, &hedge_markets).await?;
+
+    // Link this run's trace to the collection cycles that produced the data it plans from
+    let input_traces = db.get_latest_ingest_traceparents().await?;
+    let linked = telemetry::add_links(&tracing::Span::current(), input_traces.iter().map(String::as_str));
+    debug!(linked = linked, "Run trace linked to input data collection cycles");
+
+    // With a market universe configured, plan only its markets against its capital bucket, the other universes'
+    // positions are left to their own runs
+    let universe = match &cfg.market_universe {
+        Some(name) => Some(market_universe::load_universe(&db, &wallet_manager, name).await?),
+        None => None,
+    };
+
+    // Run strategy engine, penalizing turnover away from current GM holdings
+    let held_portfolio = PortfolioSnapshot::load(&wallet_manager, &db).await?;
+    let current_portfolio = match &universe {
+        Some(universe) => universe.scope_portfolio(&held_portfolio),
+        None => held_portfolio.clone(),
+    };
+    let ensemble = ReturnEnsemble::from_config(&cfg);
+    let mut params = dynamic_config.params().await;
+    let mut portfolio_data = engine::run_strategy_engine(db.clone(), &hedge_markets, &ensemble, &params, Some(&current_portfolio), universe.as_ref()).await?;
+
+    // Plan with the minimum trade size the weights were allocated under, it may have been relaxed for a small portfolio
+    params.min_trade_size_usd = portfolio_data.constraints.min_trade_size_usd;
+
+    // Smooth the targets across runs so a jump in the optimizer output doesn't whipsaw the portfolio
+    let smoothed = weight_smoothing::apply_weight_smoothing(&cfg, &db, &mut portfolio_data, &current_portfolio).await?;
+    if smoothed > 0 {
+        info!(smoothed = smoothed, "Target weights smoothed across runs");
+    }
+
+    // Don't cut positions still within their minimum holding period or re-enter markets still in their cooldown
+    let holding_periods = holding_period::track_holding_periods(&db, &held_portfolio).await?;
+    let held_back = holding_period::apply_holding_rules(&cfg, &holding_periods, db.clock.now(), &mut portfolio_data, &current_portfolio);
+    if held_back > 0 {
+        info!(held_back = held_back, "Target weights held back by holding rules");
+    }
+
+    // Measure the plan's Sharpe ratio against the current hurdle rate (USDC lending APR unless fixed in config)
+    portfolio_data.risk_free_rate_apr = benchmark::load_risk_free_rate_apr(&cfg).await;
+
+    // Record the held positions' collateral splits and trim positions whose split has drifted past the threshold
+    let exposures = composition_drift::track_position_exposures(&db, &current_portfolio).await?;
+    let drift_trimmed = composition_drift::apply_collateral_drift_rebalance(&cfg, &exposures, &mut portfolio_data, &current_portfolio);
+    if drift_trimmed > 0 {
+        info!(trimmed = drift_trimmed, "Positions trimmed to restore their collateral split");
+    }
+
+    // Don't add to pools too utilized to withdraw from promptly
+    let constrained = utilization_guard::apply_utilization_ceiling(&cfg, &db, &mut portfolio_data, Some(&current_portfolio)).await?;
+    if constrained > 0 {
+        info!(constrained = constrained, "Deposits constrained by the utilization ceiling");
+    }
+
+    // Don't plan deposits past the pools' max pool amounts, GMX would revert them
+    let capacity_capped = deposit_capacity::apply_deposit_capacity_cap(&db, &mut portfolio_data, &current_portfolio).await?;
+    if capacity_capped > 0 {
+        info!(capped = capacity_capped, "Deposits capped by GMX pool caps");
+    }
+
+    // Don't plan withdrawals larger than the pools can currently pay out
+    let capped = withdrawal_liquidity::apply_withdrawal_liquidity_cap(&cfg, &db, &mut portfolio_data, &current_portfolio).await?;
+    if capped > 0 {
+        info!(capped = capped, "Withdrawals capped by pool liquidity");
+    }
+
+    // Drop trades too small to be worth their execution fees
+    let dropped = trade_size::apply_min_trade_size(&params, &mut portfolio_data, &current_portfolio);
+    if dropped > 0 {
+        info!(dropped = dropped, "Trades below the minimum trade size dropped");
+    }
+
+    // Turn the weight changes into GM moves, shifting between same-collateral markets instead of round trips where possible.
+    // Each move is recorded in the plan notes so the plan shows how it would be executed.
+    let rebalance_plan = rebalance::plan_rebalance(&params, &mut portfolio_data, &current_portfolio, &wallet_manager).await?;
+    if !rebalance_plan.is_empty() {
+        info!(
+            gm_requests = ?rebalance_plan.gm_requests(),
+            deposits = ?rebalance_plan.deposits,
+            "Rebalance moves planned"
+        );
+        let costs = gm_costs::estimate_rebalance_costs(&cfg, &db, &rebalance_plan, &mut portfolio_data).await?;
+        info!(
+            fees_usd = %costs.fees_usd().round_dp(2),
+            price_impact_usd = %costs.price_impact_usd().round_dp(2),
+            "Estimated GMX deposit and withdrawal costs"
+        );
+    }
     
     // Log basic diagnostics
     info!("Strategy engine completed with {} markets", portfolio_data.market_addresses.len());
     portfolio_data.log_portfolio_data();
+
+    // Record the run so its expected returns can later be evaluated against realized returns and the benchmark
+    let run_summary = NewStrategyRunModel::from_portfolio_data(&portfolio_data);
+    let run_markets = NewStrategyRunMarketModel::from_portfolio_data(&portfolio_data, &db.market_id_map);
+    let run_inputs = NewStrategyRunInputModel::from_portfolio_data(&portfolio_data, &db.market_id_map);
+    db.insert_strategy_run(&run_summary, &run_markets, &run_inputs).await?;
+
+    // Defer the plan once the execution fee budget is spent
+    let fee_budget_status = FeeBudgetStatus::load(&cfg, &db).await?;
+    fee_budget_status.log_remaining();
+    if fee_budget_status.is_exhausted() {
+        warn!("Execution fee budget exhausted, deferring plan until budget is available");
+    } else {
+        // In approval mode, hold the plan until an operator approves it (whole plan or per action) or it expires,
+        // and execute the moves of the approved markets only
+        let requests = if cfg.approval_mode {
+            let plan_id = approval::submit_plan_for_approval(&cfg, db.clone(), &portfolio_data).await?;
+            match approval::wait_for_plan_approval(db.clone(), plan_id, std::time::Duration::from_secs(15)).await? {
+                Some(approved_actions) => {
+                    let approved_markets: HashSet<_> = db.market_id_map.iter()
+                        .filter(|(_, id)| approved_actions.iter().any(|action| action.market_id == **id))
+                        .map(|(address, _)| *address)
+                        .collect();
+                    let requests = rebalance_plan.gm_requests_within(&approved_markets);
+                    info!(
+                        plan_id = plan_id,
+                        approved_actions = approved_actions.len(),
+                        requests = requests.len(),
+                        "Approved plan actions released for execution"
+                    );
+                    requests
+                }
+                None => {
+                    info!(plan_id = plan_id, "Plan was not approved, nothing to execute");
+                    Vec::new()
+                }
+            }
+        } else {
+            rebalance_plan.gm_requests()
+        };
+
+        // Deposits are left to a later run, sized from the tokens held once these requests have settled
+        if requests.is_empty() {
+            debug!("No shifts or withdrawals to execute");
+        } else if params.safe_mode {
+            warn!(request_count = requests.len(), "Safe mode enabled, rebalance not executed");
+        } else {
+            rebalance::execute_rebalance(&plan_executor, &requests, cfg.rebalance_id).await;
+        }
+    }
     
     tokio::time::sleep(std::time::Duration::from_secs(3)).await; // Allow time for logging to flush
+    logging::shutdown_tracing();
 
     Ok(())
 }
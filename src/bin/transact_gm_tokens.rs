@@ -4,6 +4,7 @@ use tracing::{info, error};
 use ethers::types::{Address};
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 use rust_decimal::Decimal;
 use rust_decimal::prelude::*;
 
@@ -11,8 +12,10 @@ use crypto_yield_farming_bot::logging;
 use crypto_yield_farming_bot::config;
 use crypto_yield_farming_bot::wallet::WalletManager;
 use crypto_yield_farming_bot::db::db_manager::DbManager;
+use crypto_yield_farming_bot::gmx::callback_receiver::{GmxCallbackListener, SettledRequests};
 use crypto_yield_farming_bot::gm_token_txs::{
     gm_tx_manager::GmTxManager,
+    order_monitor::GmOrderMonitor,
     types::{
         GmDepositRequest,
         GmWithdrawalRequest,
@@ -43,7 +46,7 @@ async fn main() -> Result<()> {
     info!("Database manager initialized");
 
     // Initialize and load wallet manager
-    let mut wallet_manager = WalletManager::new(&cfg)?;
+    let wallet_manager = WalletManager::new(&cfg)?;
     wallet_manager.load_tokens(&db).await?;
     let wallet_manager = Arc::new(wallet_manager);
     info!("Wallet manager initialized and tokens loaded");
@@ -55,11 +58,27 @@ async fn main() -> Result<()> {
     let gm_tx_manager = GmTxManager::new(cfg.clone(), wallet_manager.clone(), db.clone());
     info!("GM Transaction Manager initialized");
 
+    // Start GM order monitor to cancel requests that keepers fail to execute in time
+    let mut gm_order_monitor = GmOrderMonitor::new(cfg.clone(), wallet_manager.clone(), db.clone());
+    let settled_requests = SettledRequests::new();
+    if let Some(callback_listener) = GmxCallbackListener::from_config(&cfg, settled_requests.clone()) {
+        tokio::spawn(async move {
+            if let Err(e) = callback_listener.start_listening().await {
+                error!(?e, "GMX callback listener stopped");
+            }
+        });
+        gm_order_monitor = gm_order_monitor.with_callbacks(settled_requests);
+    }
+    let gm_order_monitor = Arc::new(gm_order_monitor);
+    tokio::spawn(gm_order_monitor.run(Duration::from_secs(30)));
+
     // Example usage of GM Transaction Manager Deposit
     let deposit_request = GmDepositRequest {
         market: Address::from_str("0x70d95587d40A2caf56bd97485aB3Eec10Bee6336").unwrap(), // ETH/USD [ETH - USDC]
         long_amount: Decimal::zero(), // 0 WETH
         short_amount: Decimal::from_f64(0.5).unwrap(), // 0.5 USDC
+        initial_long_token: None,
+        initial_short_token: None,
     };
 
     // Estimate amount out from deposit
@@ -71,7 +90,7 @@ async fn main() -> Result<()> {
 
     // Execute deposit
     info!("Executing deposit request: {:?}", deposit_request);
-    if let Err(e) = gm_tx_manager.execute_transaction(&GmTxRequest::Deposit(deposit_request)).await {
+    if let Err(e) = gm_tx_manager.execute_transaction(&GmTxRequest::Deposit(deposit_request), cfg.rebalance_id).await {
         error!(error = ?e, "Failed to execute deposit request");
         return Err(e.into());
     }
@@ -97,7 +116,7 @@ async fn main() -> Result<()> {
         amount: eth_usd_gm_token_balance, // Use all the balance gained from the deposit
     };
     info!("Executing shift request: {:?}", shift_request);
-    if let Err(e) = gm_tx_manager.execute_transaction(&GmTxRequest::Shift(shift_request)).await {
+    if let Err(e) = gm_tx_manager.execute_transaction(&GmTxRequest::Shift(shift_request), cfg.rebalance_id).await {
         error!(error = ?e, "Failed to execute shift request");
         return Err(e.into());
     }
@@ -135,7 +154,7 @@ async fn main() -> Result<()> {
 
     // Execute withdrawal
     info!("Executing withdrawal request: {:?}", withdrawal_request);
-    if let Err(e) = gm_tx_manager.execute_transaction(&GmTxRequest::Withdrawal(withdrawal_request)).await {
+    if let Err(e) = gm_tx_manager.execute_transaction(&GmTxRequest::Withdrawal(withdrawal_request), cfg.rebalance_id).await {
         error!(error = ?e, "Failed to execute withdrawal request");
         return Err(e.into());
     }
@@ -0,0 +1,40 @@
+use dotenvy::dotenv;
+use tracing::{instrument, info};
+use std::sync::Arc;
+
+use crypto_yield_farming_bot::logging;
+use crypto_yield_farming_bot::config;
+use crypto_yield_farming_bot::wallet::WalletManager;
+use crypto_yield_farming_bot::db::db_manager::DbManager;
+use crypto_yield_farming_bot::wallet_watchdog::WalletWatchdog;
+
+/// Watch the execution wallet for outgoing transactions the bot didn't send, alerting on each one and
+/// (with WALLET_WATCHDOG_SAFE_MODE) switching the system into safe mode.
+#[instrument(name = "wallet_watchdog_main")]
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    // Load environment variables from .env file
+    dotenv()?;
+
+    // Initialize logging
+    if let Err(e) = logging::init_logging(env!("CARGO_BIN_NAME").to_string()) {
+        eprintln!("Failed to initialize logging: {}", e);
+        return Err(e.into());
+    }
+
+    // Load configuration (including provider)
+    let cfg = config::Config::load().await;
+    info!(network_mode = %cfg.network_mode, "Configuration loaded and logging initialized");
+
+    // Initialize db manager
+    let db = DbManager::init(&cfg).await?;
+    let db = Arc::new(db);
+    info!("Database manager initialized");
+
+    // Only the wallet address is needed, the watchdog never sends
+    let wallet_manager = WalletManager::new(&cfg)?;
+    let mut watchdog = WalletWatchdog::init(cfg.clone(), db.clone(), wallet_manager.address).await?;
+    watchdog.run().await;
+
+    Ok(())
+}
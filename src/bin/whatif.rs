@@ -0,0 +1,189 @@
+use dotenvy::dotenv;
+use ethers::types::Address;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{instrument, info, warn};
+
+use crypto_yield_farming_bot::logging;
+use crypto_yield_farming_bot::config::{self, dynamic::DynamicConfig};
+use crypto_yield_farming_bot::wallet::WalletManager;
+use crypto_yield_farming_bot::db::db_manager::DbManager;
+use crypto_yield_farming_bot::db::models::trades::TradeActionType;
+use crypto_yield_farming_bot::hedging::{dydx_client::DydxClient, hyperliquid_client::HyperliquidClient, hedge_venue::{self, PerpVenue}};
+use crypto_yield_farming_bot::strategy::{engine, market_universe, pnl_model::ReturnEnsemble, utilization_guard, deposit_capacity, withdrawal_liquidity, trade_size, rebalance, gm_costs, benchmark, types::PortfolioSnapshot};
+
+const USAGE: &str = "Usage: whatif --weights <weights.json> (JSON object of market address to target weight, e.g. {\"0x70d9...\": 0.4})";
+
+/// Target weights by market from a JSON file, weights given as fractions of the portfolio
+fn load_weights(path: &str) -> eyre::Result<HashMap<Address, Decimal>> {
+    let contents = std::fs::read_to_string(path).map_err(|e| eyre::eyre!("Failed to read {}: {}", path, e))?;
+    let raw: HashMap<String, f64> = serde_json::from_str(&contents).map_err(|e| eyre::eyre!("Invalid weights file {}: {}", path, e))?;
+    let mut weights = HashMap::new();
+    for (market, weight) in raw {
+        let address: Address = market.parse().map_err(|_| eyre::eyre!("Invalid market address: {}", market))?;
+        let weight = Decimal::from_f64(weight).filter(|w| *w >= Decimal::ZERO)
+            .ok_or_else(|| eyre::eyre!("Invalid weight {} for market {}", weight, market))?;
+        weights.insert(address, weight);
+    }
+    let total: Decimal = weights.values().sum();
+    if total > Decimal::ONE {
+        return Err(eyre::eyre!("Weights sum to {}, more than the whole portfolio", total));
+    }
+    Ok(weights)
+}
+
+#[instrument(name = "whatif_main")]
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    // Load environment variables from .env file
+    dotenv()?;
+
+    // Initialize logging
+    if let Err(e) = logging::init_logging(env!("CARGO_BIN_NAME").to_string()) {
+        eprintln!("Failed to initialize logging: {}", e);
+        return Err(e.into());
+    }
+
+    // Parse the target weights before connecting to anything
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let weights_path = match args.as_slice() {
+        [flag, path] if flag == "--weights" => path.clone(),
+        _ => return Err(eyre::eyre!(USAGE)),
+    };
+    let target_weights = load_weights(&weights_path)?;
+
+    // Load configuration (including provider)
+    let cfg = config::Config::load().await;
+    info!(network_mode = %cfg.network_mode, "Configuration loaded and logging initialized");
+
+    // Initialize db manager
+    let db = DbManager::init(&cfg).await?;
+    let db = Arc::new(db);
+    info!("Database manager initialized");
+
+    // Plan with the same parameters the trading bot would use
+    let dynamic_config = DynamicConfig::load(&cfg, db.clone()).await?;
+    info!("Dynamic config loaded");
+
+    // Initialize and load wallet manager
+    let wallet_manager = WalletManager::new(&cfg)?;
+    wallet_manager.load_tokens(&db).await?;
+    let wallet_manager = Arc::new(wallet_manager);
+    info!("Wallet manager initialized");
+
+    // Hedge venues decide which markets are in the strategy universe
+    let dydx_client = DydxClient::new(cfg.clone(), wallet_manager.clone(), db.clone()).await?;
+    let mut hedge_venues: Vec<Box<dyn PerpVenue>> = vec![Box::new(dydx_client)];
+    if cfg.hyperliquid_enabled {
+        hedge_venues.push(Box::new(HyperliquidClient::new(cfg.clone(), wallet_manager.clone(), db.clone())?));
+    }
+    let hedge_markets = hedge_venue::load_hedge_markets(&cfg, &hedge_venues).await?;
+
+    // Run the strategy engine for the expected returns and covariances, then replace its weights with the manual ones
+    let universe = match &cfg.market_universe {
+        Some(name) => Some(market_universe::load_universe(&db, &wallet_manager, name).await?),
+        None => None,
+    };
+    let held_portfolio = PortfolioSnapshot::load(&wallet_manager, &db).await?;
+    let current_portfolio = match &universe {
+        Some(universe) => universe.scope_portfolio(&held_portfolio),
+        None => held_portfolio,
+    };
+    let ensemble = ReturnEnsemble::from_config(&cfg);
+    let mut params = dynamic_config.params().await;
+    let mut portfolio_data = engine::run_strategy_engine(db.clone(), &hedge_markets, &ensemble, &params, Some(&current_portfolio), universe.as_ref()).await?;
+    params.min_trade_size_usd = portfolio_data.constraints.min_trade_size_usd;
+    portfolio_data.risk_free_rate_apr = benchmark::load_risk_free_rate_apr(&cfg).await;
+
+    let unknown: Vec<&Address> = target_weights.keys().filter(|address| portfolio_data.get_market_index(**address).is_none()).collect();
+    if !unknown.is_empty() {
+        return Err(eyre::eyre!("Markets not in the strategy universe: {:?}", unknown));
+    }
+    let engine_weights = portfolio_data.weights.clone();
+    for (i, address) in portfolio_data.market_addresses.clone().iter().enumerate() {
+        portfolio_data.weights[i] = target_weights.get(address).copied().unwrap_or(Decimal::ZERO);
+    }
+    let (engine_return, engine_volatility, engine_sharpe) = {
+        let mut engine_data = portfolio_data.clone();
+        engine_data.weights = engine_weights;
+        engine_data.portfolio_metrics()
+    };
+
+    // Apply the same guards the trading bot applies, so the preview shows what would actually be executed
+    let constrained = utilization_guard::apply_utilization_ceiling(&cfg, &db, &mut portfolio_data, Some(&current_portfolio)).await?;
+    let capacity_capped = deposit_capacity::apply_deposit_capacity_cap(&db, &mut portfolio_data, &current_portfolio).await?;
+    let liquidity_capped = withdrawal_liquidity::apply_withdrawal_liquidity_cap(&cfg, &db, &mut portfolio_data, &current_portfolio).await?;
+    let dropped = trade_size::apply_min_trade_size(&params, &mut portfolio_data, &current_portfolio);
+    if constrained + capacity_capped + liquidity_capped + dropped > 0 {
+        warn!(
+            utilization_constrained = constrained,
+            capacity_capped = capacity_capped,
+            liquidity_capped = liquidity_capped,
+            dropped = dropped,
+            "Manual weights adjusted by the planning guards, see the plan notes"
+        );
+    }
+
+    let rebalance_plan = rebalance::plan_rebalance(&params, &mut portfolio_data, &current_portfolio, &wallet_manager).await?;
+    let gm_costs = gm_costs::estimate_rebalance_costs(&cfg, &db, &rebalance_plan, &mut portfolio_data).await?;
+
+    // Estimate execution fees from the recent median fee of each action type, in ETH
+    let since = db.clock.now() - chrono::Duration::hours(cfg.gas_baseline_window_hours);
+    let action_counts = [
+        (TradeActionType::GmShift, rebalance_plan.shifts.len()),
+        (TradeActionType::GmWithdrawal, rebalance_plan.withdrawals.len()),
+        (TradeActionType::GmDeposit, rebalance_plan.deposits.len()),
+    ];
+    let mut estimated_fee = Decimal::ZERO;
+    let mut unestimated = Vec::new();
+    for (action_type, count) in action_counts.iter().filter(|(_, count)| *count > 0) {
+        match db.get_median_execution_fee_since(action_type.as_str(), since).await? {
+            (Some(median_fee), _) => estimated_fee += median_fee * Decimal::from(*count),
+            (None, _) => unestimated.push(action_type.as_str()),
+        }
+    }
+    if !unestimated.is_empty() {
+        warn!(action_types = ?unestimated, "No recent execution fee samples, these actions are left out of the cost estimate");
+    }
+    let eth_price = wallet_manager.native_token().last_mid_price_usd;
+
+    // Report the plan without recording a strategy run or executing anything
+    let display_names: HashMap<Address, String> = portfolio_data.market_addresses.iter().copied()
+        .zip(portfolio_data.display_names.iter().cloned())
+        .collect();
+    let name = |address: &Address| display_names.get(address).cloned().unwrap_or_else(|| format!("{:?}", address));
+    let actions = rebalance_plan.shifts.iter()
+        .map(|s| format!("Shift {} -> {}: {} GM", name(&s.from_market), name(&s.to_market), s.amount))
+        .chain(rebalance_plan.withdrawals.iter().map(|w| format!("Withdraw {}: {} GM", name(&w.market), w.amount)))
+        .chain(rebalance_plan.deposits.iter().map(|d| format!("Deposit {}: ${}", name(&d.market), d.value_usd.round_dp(2))))
+        .collect::<Vec<_>>();
+    let notes = portfolio_data.market_addresses.iter()
+        .filter_map(|address| portfolio_data.get_notes(*address).map(|notes| format!("{}: {}", name(address), notes)))
+        .collect::<Vec<_>>();
+
+    portfolio_data.log_portfolio_data();
+    let (portfolio_return, portfolio_volatility, portfolio_sharpe) = portfolio_data.portfolio_metrics();
+    let bps = Decimal::from_f64(10000.0).unwrap();
+    info!(
+        "What-If Plan (portfolio ${}):\n  {}\n\nNotes:\n  {}\n\nEstimated Execution Fees: {} ETH (${})\nEstimated GMX Fees: ${}\nEstimated Price Impact: ${}\n\nManual vs Engine Allocation:\n  Expected Return: {:.5}bps vs {:.5}bps\n  Volatility: {:.5}bps vs {:.5}bps\n  Sharpe Ratio (excess): {:.3} vs {:.3}",
+        current_portfolio.total_value_usd.round_dp(2),
+        if actions.is_empty() { "No actions".to_string() } else { actions.join("\n  ") },
+        if notes.is_empty() { "None".to_string() } else { notes.join("\n  ") },
+        estimated_fee.round_dp(6),
+        (estimated_fee * eth_price).round_dp(2),
+        gm_costs.fees_usd().round_dp(2),
+        gm_costs.price_impact_usd().round_dp(2),
+        portfolio_return * bps,
+        engine_return * bps,
+        portfolio_volatility * bps,
+        engine_volatility * bps,
+        portfolio_sharpe,
+        engine_sharpe
+    );
+
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await; // Allow time for logging to flush
+
+    Ok(())
+}
@@ -0,0 +1,212 @@
+// Moves USDC collateral between the Arbitrum wallet and the dYdX trading subaccount through SkipGo routes (CCTP),
+// recording each transfer in bridge_transfers and tracking it until the funds arrive on the destination chain.
+// Deposits land on the dYdX chain account and are then moved into the subaccount; withdrawals leave the subaccount
+// before they are bridged.
+use eyre::Result;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::*;
+use std::time::Duration;
+use tracing::{info, warn, error, instrument};
+
+use crate::db::db_manager::DbManager;
+use crate::db::models::bridge_transfers::{BridgeTransferModel, NewBridgeTransferModel, BridgeTransferStatus};
+use crate::db::models::execution_costs::{NewExecutionCostModel, ExecutionVenue};
+use crate::db::models::trades::TradeActionType;
+use crate::hedging::dydx_client::DydxClient;
+use crate::hedging::skip_go;
+use crate::strategy::compliance;
+
+/// Slippage tolerance passed to SkipGo routes, CCTP transfers are 1:1 less a fixed fee
+const BRIDGE_SLIPPAGE_TOLERANCE_PCT: f64 = 1.0;
+/// Interval at which a pending transfer's route status is checked
+const BRIDGE_POLL_INTERVAL_SECS: u64 = 30;
+/// A transfer still pending after this many times its estimated duration is left to later tracking
+const BRIDGE_TIMEOUT_MULTIPLIER: u64 = 5;
+/// Lower bound on the wait for routes with a short (or unknown) estimated duration
+const MIN_BRIDGE_TIMEOUT_SECS: u64 = 15 * 60;
+/// USDC left on the dYdX chain account when moving arrived funds into the subaccount, pays the chain's transaction fees
+const DYDX_FEE_RESERVE_USDC: f64 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BridgeDirection {
+    ArbitrumToDydx,
+    DydxToArbitrum,
+}
+
+impl BridgeDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BridgeDirection::ArbitrumToDydx => "ArbitrumToDydx",
+            BridgeDirection::DydxToArbitrum => "DydxToArbitrum",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "ArbitrumToDydx" => Some(BridgeDirection::ArbitrumToDydx),
+            "DydxToArbitrum" => Some(BridgeDirection::DydxToArbitrum),
+            _ => None,
+        }
+    }
+}
+
+/// Move `amount` USDC (sent on the source chain, route fees included) in the given direction
+#[derive(Debug, Clone)]
+pub struct BridgeCollateralRequest {
+    pub direction: BridgeDirection,
+    pub amount: Decimal,
+    pub rebalance_id: Option<i32>, // Rebalance the transfer's costs are recorded against, None for a standalone transfer
+}
+
+/// Send a bridge transfer and record it as pending, returning the transfer ID. The funds are not available on the
+/// destination until the transfer is tracked to arrival (see `wait_for_arrival` and `track_pending_bridge_transfers`).
+#[instrument(skip(dydx_client, db), fields(on_close = true))]
+pub async fn bridge_collateral(dydx_client: &mut DydxClient, db: &DbManager, request: &BridgeCollateralRequest) -> Result<i32> {
+    if request.amount <= Decimal::ZERO {
+        return Err(eyre::eyre!("Bridge amount must be positive, got {}", request.amount));
+    }
+    // USDC is valued at par, the route moves it 1:1 less fees
+    if let Some(violation) = compliance::check_bridge_collateral(db, "USDC", request.amount).await? {
+        warn!(rule_id = %violation.rule_id, "{}", violation);
+        return Err(violation.into());
+    }
+
+    let slippage_tolerance_pct = Decimal::from_f64(BRIDGE_SLIPPAGE_TOLERANCE_PCT).unwrap();
+    let transfer = match request.direction {
+        BridgeDirection::ArbitrumToDydx => {
+            dydx_client.dydx_deposit(Some(request.amount), None, false, Some(slippage_tolerance_pct)).await?
+        }
+        BridgeDirection::DydxToArbitrum => {
+            // Only the chain account's USDC can be bridged, so it has to leave the subaccount first
+            dydx_client.withdraw_from_subaccount(request.amount).await?;
+            dydx_client.dydx_withdrawal(Some(request.amount), None, false, Some(slippage_tolerance_pct)).await?
+        }
+    };
+    if dydx_client.is_sandbox() {
+        return Err(eyre::eyre!("dYdX sandbox mode: bridge transfer of {} validated but not sent", transfer.amount));
+    }
+    let (source_tx_hash, source_chain_id) = transfer.submitted_txs.first().cloned()
+        .ok_or_else(|| eyre::eyre!("SkipGo route sent no transactions"))?;
+
+    let id = db.insert_bridge_transfer(&NewBridgeTransferModel {
+        direction: request.direction.as_str().to_string(),
+        amount: transfer.amount,
+        expected_amount_out: transfer.expected_amount_out,
+        source_chain_id,
+        source_tx_hash: source_tx_hash.clone(),
+        estimated_duration_secs: transfer.estimated_time_secs as i64,
+    }).await?;
+
+    if transfer.gas_cost_usd > Decimal::ZERO {
+        let cost = NewExecutionCostModel {
+            venue: ExecutionVenue::SkipGo.as_str().to_string(),
+            action_type: TradeActionType::BridgeCollateral.as_str().to_string(),
+            tx_hash: Some(source_tx_hash.clone()),
+            gas_cost_usd: transfer.gas_cost_usd,
+            execution_fee_usd: Decimal::ZERO,
+            slippage_usd: Decimal::ZERO,
+        };
+        if let Err(e) = db.insert_execution_cost(&cost, request.rebalance_id).await {
+            error!(error = ?e, tx_hash = %source_tx_hash, "Failed to record bridge execution cost");
+        }
+    }
+
+    info!(
+        bridge_transfer_id = id,
+        direction = request.direction.as_str(),
+        amount = %transfer.amount,
+        expected_amount_out = %transfer.expected_amount_out,
+        estimated_time_secs = transfer.estimated_time_secs,
+        source_tx_hash = %source_tx_hash,
+        "Bridge transfer sent"
+    );
+    Ok(id)
+}
+
+/// Check a pending transfer's route once, completing it when the funds have arrived: deposits are moved into the
+/// subaccount before they count as arrived. Returns the transfer's status after the check.
+#[instrument(skip(dydx_client, db, transfer), fields(bridge_transfer_id = transfer.id))]
+pub async fn check_bridge_transfer(dydx_client: &mut DydxClient, db: &DbManager, transfer: &BridgeTransferModel) -> Result<BridgeTransferStatus> {
+    let status = BridgeTransferStatus::parse(&transfer.status)
+        .ok_or_else(|| eyre::eyre!("Unknown bridge transfer status: {}", transfer.status))?;
+    if status != BridgeTransferStatus::Pending {
+        return Ok(status);
+    }
+    let direction = BridgeDirection::parse(&transfer.direction)
+        .ok_or_else(|| eyre::eyre!("Unknown bridge direction: {}", transfer.direction))?;
+
+    let response = skip_go::get_transaction_status(skip_go::SkipGoGetTransactionStatusRequest {
+        tx_hash: transfer.source_tx_hash.clone(),
+        chain_id: transfer.source_chain_id.clone(),
+    }).await?;
+    match response.state {
+        skip_go::SkipGoTransactionState::StateSubmitted | skip_go::SkipGoTransactionState::StatePending => {
+            let elapsed_secs = (db.clock.now() - transfer.created_at).num_seconds().max(0) as u64;
+            if elapsed_secs > timeout_secs(transfer) {
+                warn!(elapsed_secs = elapsed_secs, estimated_duration_secs = transfer.estimated_duration_secs, "Bridge transfer pending far beyond its estimated duration");
+            }
+            Ok(BridgeTransferStatus::Pending)
+        }
+        skip_go::SkipGoTransactionState::StateCompletedSuccess => {
+            if direction == BridgeDirection::ArbitrumToDydx {
+                let available = dydx_client.get_dydx_usdc_balance().await? - Decimal::from_f64(DYDX_FEE_RESERVE_USDC).unwrap();
+                let amount = transfer.expected_amount_out.min(available);
+                if amount > Decimal::ZERO {
+                    dydx_client.deposit_to_subaccount(amount).await?;
+                }
+            }
+            db.update_bridge_transfer_status(transfer.id, BridgeTransferStatus::Arrived, None).await?;
+            info!(direction = %transfer.direction, amount = %transfer.amount, "Bridge transfer arrived");
+            Ok(BridgeTransferStatus::Arrived)
+        }
+        state => {
+            let reason = format!("SkipGo route ended in {:?}: {}", state, response.error);
+            error!(direction = %transfer.direction, amount = %transfer.amount, source_tx_hash = %transfer.source_tx_hash, state = ?state, "Bridge transfer failed");
+            db.update_bridge_transfer_status(transfer.id, BridgeTransferStatus::Failed, Some(reason)).await?;
+            Ok(BridgeTransferStatus::Failed)
+        }
+    }
+}
+
+/// Poll a transfer until its funds arrive or its route fails. A transfer still pending past its timeout is
+/// returned as pending and left for `track_pending_bridge_transfers`.
+#[instrument(skip(dydx_client, db), fields(on_close = true))]
+pub async fn wait_for_arrival(dydx_client: &mut DydxClient, db: &DbManager, transfer_id: i32) -> Result<BridgeTransferStatus> {
+    loop {
+        let transfer = db.get_bridge_transfer(transfer_id).await?
+            .ok_or_else(|| eyre::eyre!("Bridge transfer {} not found", transfer_id))?;
+        let status = check_bridge_transfer(dydx_client, db, &transfer).await?;
+        if status != BridgeTransferStatus::Pending {
+            return Ok(status);
+        }
+        let elapsed_secs = (db.clock.now() - transfer.created_at).num_seconds().max(0) as u64;
+        if elapsed_secs > timeout_secs(&transfer) {
+            warn!(bridge_transfer_id = transfer_id, elapsed_secs = elapsed_secs, "Bridge transfer still pending, leaving it for later tracking");
+            return Ok(status);
+        }
+        tokio::time::sleep(Duration::from_secs(BRIDGE_POLL_INTERVAL_SECS)).await;
+    }
+}
+
+/// Check every pending transfer once, e.g. those an interrupted run was waiting on.
+/// Returns the number still pending.
+#[instrument(skip(dydx_client, db))]
+pub async fn track_pending_bridge_transfers(dydx_client: &mut DydxClient, db: &DbManager) -> Result<usize> {
+    let mut pending = 0;
+    for transfer in db.get_pending_bridge_transfers().await? {
+        match check_bridge_transfer(dydx_client, db, &transfer).await {
+            Ok(BridgeTransferStatus::Pending) => pending += 1,
+            Ok(_) => {}
+            Err(e) => {
+                error!(error = ?e, bridge_transfer_id = transfer.id, "Failed to check bridge transfer");
+                pending += 1;
+            }
+        }
+    }
+    Ok(pending)
+}
+
+/// How long a transfer may stay pending before it is reported as overdue
+fn timeout_secs(transfer: &BridgeTransferModel) -> u64 {
+    (transfer.estimated_duration_secs.max(0) as u64 * BRIDGE_TIMEOUT_MULTIPLIER).max(MIN_BRIDGE_TIMEOUT_SECS)
+}
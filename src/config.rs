@@ -1,161 +0,0 @@
-use std::env;
-use std::sync::Arc;
-use ethers::providers::{Provider, Http};
-use ethers::types::Address;
-use std::sync::Once;
-
-use crate::constants;
-
-static INIT_CRYPTO: Once = Once::new();
-
-#[derive(Debug)]
-pub struct Config {
-    pub alchemy_provider: Arc<Provider<Http>>,
-    pub alchemy_ws_url: String,
-    pub wallet_private_key: String,
-    pub wallet_mnemonic: String,
-    pub network_mode: String,
-    pub execution_mode: String,
-    pub chain_id: u64,
-    pub gmx_datastore: Address,
-    pub gmx_reader: Address,
-    pub gmx_eventemitter: Address,
-    pub gmx_exchangerouter: Address,
-    pub gmx_baserouter: Address,
-    pub gmx_depositvault: Address,
-    pub gmx_withdrawalvault: Address,
-    pub gmx_shiftvault: Address, 
-    pub etherscan_api_key: String,
-    pub refetch_abis: bool,
-    pub database_url: String,
-    pub zerox_api_key: String,
-}
-
-impl Config {
-    pub async fn load() -> Arc<Self> {
-        // Load network mode env var and validate it
-        let network_mode = env::var("NETWORK_MODE").expect("Missing NETWORK_MODE environment variable");
-        if network_mode != "test" && network_mode != "prod" {
-            panic!("NETWORK_MODE must be either 'test' or 'prod'");
-        }
-        
-        // Load execution mode (paper or live)
-        let execution_mode = env::var("EXECUTION_MODE").unwrap_or_else(|_| "paper".to_string());
-        if execution_mode != "paper" && execution_mode != "live" {
-            panic!("EXECUTION_MODE must be either 'paper' or 'live'");
-        }
-
-        // Load alchemy RPC HTTP URL based on network mode, create ethers provider
-        let alchemy_rpc_url = match network_mode.as_str() {
-            "test" => env::var("ALCHEMY_RPC_URL_TEST").expect("Missing ALCHEMY_RPC_URL_TEST"),
-            "prod" => env::var("ALCHEMY_RPC_URL_PROD").expect("Missing ALCHEMY_RPC_URL_PROD"),
-            _ => panic!("Invalid NETWORK_MODE"),
-        };
-
-        let provider = Provider::<Http>::try_from(alchemy_rpc_url)
-            .expect("Failed to create Alchemy provider");
-
-        // Load alchemy WebSocket URL based on network mode, create ethers provider
-        let alchemy_ws_url = match network_mode.as_str() {
-            "test" => env::var("ALCHEMY_WS_URL_TEST").expect("Missing ALCHEMY_WS_URL_TEST"),
-            "prod" => env::var("ALCHEMY_WS_URL_PROD").expect("Missing ALCHEMY_WS_URL_PROD"),
-            _ => panic!("Invalid NETWORK_MODE"),
-        };
-
-        // Load wallet private key based on network mode
-        let wallet_private_key = match network_mode.as_str() {
-            "test" => env::var("WALLET_PRIVATE_KEY_TEST").expect("Missing WALLET_PRIVATE_KEY_TEST"),
-            "prod" => env::var("WALLET_PRIVATE_KEY_PROD").expect("Missing WALLET_PRIVATE_KEY_PROD"),
-            _ => panic!("Invalid NETWORK_MODE"),
-        };
-
-        // Load wallet mnemonic based on network mode
-        let wallet_mnemonic = match network_mode.as_str() {
-            "test" => env::var("WALLET_MNEMONIC_TEST").expect("Missing WALLET_MNEMONIC_TEST"),
-            "prod" => env::var("WALLET_MNEMONIC_PROD").expect("Missing WALLET_MNEMONIC_PROD"),
-            _ => panic!("Invalid NETWORK_MODE"),
-        };
-
-        // Load chain ID based on network mode
-        let chain_id = match network_mode.as_str() {
-            "test" => constants::ARBITRUM_SEPOLIA_CHAIN_ID,
-            "prod" => constants::ARBITRUM_MAINNET_CHAIN_ID,
-            _ => panic!("Invalid NETWORK_MODE"),
-        };
-
-        // Load GMX contract addresses based on network mode
-        let (
-            gmx_datastore, gmx_reader, gmx_eventemitter, gmx_exchangerouter, 
-            gmx_baserouter, gmx_depositvault, gmx_withdrawalvault, gmx_shiftvault
-        ) = match network_mode.as_str() {
-            "test" => {
-                
-                (   
-                    constants::GMX_DATASTORE_ADDRESS_SEPOLIA, 
-                    constants::GMX_READER_ADDRESS_SEPOLIA,
-                    constants::GMX_EVENTEMITTER_ADDRESS_SEPOLIA,
-                    constants::GMX_EXCHANGEROUTER_ADDRESS_SEPOLIA,
-                    constants::GMX_BASEROUTER_ADDRESS_SEPOLIA,
-                    constants::GMX_DEPOSITVAULT_ADDRESS_SEPOLIA,
-                    constants::GMX_WITHDRAWALVAULT_ADDRESS_SEPOLIA,
-                    constants::GMX_SHIFTVAULT_ADDRESS_SEPOLIA,
-                ) // No shift vault on Sepolia, using same address as mainnet for placeholder
-        },
-            "prod" => (
-                constants::GMX_DATASTORE_ADDRESS_MAINNET, 
-                constants::GMX_READER_ADDRESS_MAINNET,
-                constants::GMX_EVENTEMITTER_ADDRESS_MAINNET,
-                constants::GMX_EXCHANGEROUTER_ADDRESS_MAINNET,
-                constants::GMX_BASEROUTER_ADDRESS_MAINNET,
-                constants::GMX_DEPOSITVAULT_ADDRESS_MAINNET,
-                constants::GMX_WITHDRAWALVAULT_ADDRESS_MAINNET,
-                constants::GMX_SHIFTVAULT_ADDRESS_MAINNET, 
-            ),
-            _ => panic!("Invalid NETWORK_MODE"),
-        };
-
-        // Load Etherscan API key, refetch ABIs flag
-        let etherscan_api_key = env::var("ETHERSCAN_API_KEY").expect("Missing ETHERSCAN_API_KEY");
-        let refetch_abis = env::var("REFETCH_ABIS")
-            .map(|v| v.parse().unwrap_or(false))
-            .unwrap_or(false);
-
-        // Load database URL
-        let database_url = env::var("DATABASE_URL").expect("Missing DATABASE_URL");
-
-        // Load 0x API key
-        let zerox_api_key = env::var("ZEROX_API_KEY").expect("Missing ZEROX_API_KEY");
-
-        let config = Config {
-            alchemy_provider: Arc::new(provider),
-            alchemy_ws_url,
-            wallet_private_key,
-            wallet_mnemonic,
-            network_mode,
-            execution_mode,
-            chain_id,
-            gmx_datastore: gmx_datastore.parse().expect("Invalid GMX DataStore address"),
-            gmx_reader: gmx_reader.parse().expect("Invalid GMX Reader address"),
-            gmx_eventemitter: gmx_eventemitter.parse().expect("Invalid GMX EventEmitter address"),
-            gmx_exchangerouter: gmx_exchangerouter.parse().expect("Invalid GMX ExchangeRouter address"),
-            gmx_baserouter: gmx_baserouter.parse().expect("Invalid GMX BaseRouter address"),
-            gmx_depositvault: gmx_depositvault.parse().expect("Invalid GMX DepositVault address"),
-            gmx_withdrawalvault: gmx_withdrawalvault.parse().expect("Invalid GMX WithdrawalVault address"),
-            gmx_shiftvault: gmx_shiftvault.parse().expect("Invalid GMX ShiftVault address"),
-            etherscan_api_key,
-            refetch_abis,
-            database_url,
-            zerox_api_key,
-        };
-        
-        Arc::new(config)
-    }
-}
-
-pub fn init_crypto_provider() {
-    INIT_CRYPTO.call_once(|| {
-        rustls::crypto::aws_lc_rs::default_provider()
-            .install_default()
-            .expect("Failed to install AWS-LC-RS as default TLS provider");
-    });
-}
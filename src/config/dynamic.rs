@@ -0,0 +1,221 @@
+use std::sync::Arc;
+use std::time::Duration;
+use eyre::Result;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::*;
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn, instrument};
+
+use super::Config;
+use crate::db::db_manager::DbManager;
+use crate::strategy::allocator::AllocatorMode;
+use crate::strategy::strategy_constants::{ALLOCATOR_RISK_AVERSION, ALLOCATOR_TURNOVER_PENALTY};
+
+const DEFAULT_SWAP_SLIPPAGE_TOLERANCE_PCT: f64 = 0.5;
+
+/// Parameters that can be changed at runtime through the config_overrides table, keyed by field name
+#[derive(Debug, Clone, PartialEq)]
+pub struct DynamicParams {
+    pub allocator_mode: AllocatorMode,     // Mean-variance or market-neutral hedged carry allocation
+    pub allocator_risk_aversion: f64,      // Mean-variance risk aversion when holdings are known
+    pub allocator_turnover_penalty: f64,   // Penalty on turnover away from current holdings
+    pub allocator_kelly_fraction: Option<f64>, // Fractional-Kelly sizing, None deploys the full mean-variance weights
+    pub risk_horizon_hours: i64,           // Holding period the covariance is scaled to
+    pub implied_vol_weight: Option<f64>,   // Weight of Deribit implied volatility in the majors' variance, None for realized only
+    pub min_trade_size_usd: Decimal,       // Rebalance threshold, smaller trades are dropped from the plan
+    pub swap_slippage_tolerance_pct: Decimal, // Spot swap slippage tolerance in percent (e.g. 0.5 for 0.5%)
+    pub safe_mode: bool,                   // Halt all transacting, in-flight plans stop before their next wave
+}
+
+impl DynamicParams {
+    /// Values used while no override is set, from the environment config and strategy constants
+    pub fn defaults(config: &Config) -> Self {
+        Self {
+            allocator_mode: config.allocator_mode,
+            allocator_risk_aversion: ALLOCATOR_RISK_AVERSION,
+            allocator_turnover_penalty: ALLOCATOR_TURNOVER_PENALTY,
+            allocator_kelly_fraction: config.allocator_kelly_fraction,
+            risk_horizon_hours: config.risk_horizon_hours,
+            implied_vol_weight: config.implied_vol_weight,
+            min_trade_size_usd: config.min_trade_size_usd,
+            swap_slippage_tolerance_pct: Decimal::from_f64(DEFAULT_SWAP_SLIPPAGE_TOLERANCE_PCT).unwrap(),
+            safe_mode: false,
+        }
+    }
+
+    /// Apply an override value to the parameter with the given name
+    fn apply(&mut self, key: &str, value: &str) -> Result<()> {
+        let value = value.trim();
+        match key {
+            "allocator_mode" => self.allocator_mode = parse_allocator_mode(value)?,
+            "allocator_risk_aversion" => self.allocator_risk_aversion = parse_non_negative_f64(value)?,
+            "allocator_turnover_penalty" => self.allocator_turnover_penalty = parse_non_negative_f64(value)?,
+            "allocator_kelly_fraction" => self.allocator_kelly_fraction = parse_kelly_fraction(value)?,
+            "risk_horizon_hours" => {
+                let hours: i64 = value.parse()?;
+                if hours <= 0 {
+                    return Err(eyre::eyre!("must be a positive number of hours"));
+                }
+                self.risk_horizon_hours = hours;
+            }
+            "implied_vol_weight" => self.implied_vol_weight = parse_implied_vol_weight(value)?,
+            "min_trade_size_usd" => self.min_trade_size_usd = parse_non_negative_decimal(value)?,
+            "swap_slippage_tolerance_pct" => {
+                let slippage = parse_non_negative_decimal(value)?;
+                if slippage.is_zero() || slippage > Decimal::from(50) {
+                    return Err(eyre::eyre!("slippage tolerance must be within (0, 50] percent"));
+                }
+                self.swap_slippage_tolerance_pct = slippage;
+            }
+            "safe_mode" => self.safe_mode = match value.to_lowercase().as_str() {
+                "true" | "1" | "on" => true,
+                "false" | "0" | "off" => false,
+                _ => return Err(eyre::eyre!("expected true or false")),
+            },
+            _ => return Err(eyre::eyre!("unknown parameter")),
+        }
+        Ok(())
+    }
+
+    /// Parameter names and display values, for change logging
+    fn fields(&self) -> [(&'static str, String); 9] {
+        [
+            ("allocator_mode", self.allocator_mode.as_str().to_string()),
+            ("allocator_risk_aversion", self.allocator_risk_aversion.to_string()),
+            ("allocator_turnover_penalty", self.allocator_turnover_penalty.to_string()),
+            ("allocator_kelly_fraction", self.allocator_kelly_fraction.map_or_else(|| "off".to_string(), |f| f.to_string())),
+            ("risk_horizon_hours", self.risk_horizon_hours.to_string()),
+            ("implied_vol_weight", self.implied_vol_weight.map_or_else(|| "off".to_string(), |w| w.to_string())),
+            ("min_trade_size_usd", self.min_trade_size_usd.to_string()),
+            ("swap_slippage_tolerance_pct", self.swap_slippage_tolerance_pct.to_string()),
+            ("safe_mode", self.safe_mode.to_string()),
+        ]
+    }
+}
+
+/// Shared handle to the runtime parameters. Every refresh rebuilds them from the defaults plus the current
+/// overrides, so deleting an override reverts the parameter. Invalid or unknown overrides are logged and ignored.
+pub struct DynamicConfig {
+    defaults: DynamicParams,
+    current: RwLock<DynamicParams>,
+    db_manager: Arc<DbManager>,
+    refresh_interval: Duration,
+}
+
+impl DynamicConfig {
+    /// Build the handle and apply the overrides currently in the database
+    pub async fn load(config: &Config, db_manager: Arc<DbManager>) -> Result<Arc<Self>> {
+        let defaults = DynamicParams::defaults(config);
+        let dynamic_config = Arc::new(Self {
+            current: RwLock::new(defaults.clone()),
+            defaults,
+            db_manager,
+            refresh_interval: Duration::from_secs(config.config_refresh_interval_secs),
+        });
+        dynamic_config.refresh().await?;
+        Ok(dynamic_config)
+    }
+
+    /// Snapshot of the current parameters
+    pub async fn params(&self) -> DynamicParams {
+        self.current.read().await.clone()
+    }
+
+    pub async fn safe_mode(&self) -> bool {
+        self.current.read().await.safe_mode
+    }
+
+    /// Reload the overrides, logging every parameter whose value changed. Returns the number of changes.
+    #[instrument(skip(self))]
+    pub async fn refresh(&self) -> Result<usize> {
+        let overrides = self.db_manager.get_config_overrides().await?;
+        let mut params = self.defaults.clone();
+        for config_override in &overrides {
+            if let Err(e) = params.apply(&config_override.key, &config_override.value) {
+                warn!(
+                    key = %config_override.key,
+                    value = %config_override.value,
+                    error = %e,
+                    "Ignoring invalid config override"
+                );
+            }
+        }
+
+        let mut current = self.current.write().await;
+        let mut changed = 0;
+        for ((name, old_value), (_, new_value)) in current.fields().into_iter().zip(params.fields()) {
+            if old_value != new_value {
+                info!(parameter = name, old_value = %old_value, new_value = %new_value, "Config parameter changed");
+                changed += 1;
+            }
+        }
+        *current = params;
+        debug!(overrides = overrides.len(), changed = changed, "Config overrides refreshed");
+        Ok(changed)
+    }
+
+    /// Refresh the overrides every CONFIG_REFRESH_INTERVAL_SECS in the background, keeping the last good
+    /// parameters when the database can't be reached
+    pub fn spawn_watcher(self: &Arc<Self>) -> JoinHandle<()> {
+        let dynamic_config = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(dynamic_config.refresh_interval);
+            interval.tick().await; // First tick completes immediately, overrides were just loaded
+            loop {
+                interval.tick().await;
+                if let Err(e) = dynamic_config.refresh().await {
+                    warn!(error = ?e, "Failed to refresh config overrides, keeping current parameters");
+                }
+            }
+        })
+    }
+}
+
+fn parse_non_negative_f64(value: &str) -> Result<f64> {
+    let parsed: f64 = value.parse()?;
+    if !parsed.is_finite() || parsed < 0.0 {
+        return Err(eyre::eyre!("must be a non-negative number"));
+    }
+    Ok(parsed)
+}
+
+/// Allocator mode, mean_variance or hedged_carry
+pub fn parse_allocator_mode(value: &str) -> Result<AllocatorMode> {
+    match value.to_lowercase().as_str() {
+        "mean_variance" => Ok(AllocatorMode::MeanVariance),
+        "hedged_carry" => Ok(AllocatorMode::HedgedCarry),
+        _ => Err(eyre::eyre!("expected mean_variance or hedged_carry")),
+    }
+}
+
+/// Kelly fraction in (0, 1], or off to deploy the full mean-variance weights
+pub fn parse_kelly_fraction(value: &str) -> Result<Option<f64>> {
+    if value.eq_ignore_ascii_case("off") || value.eq_ignore_ascii_case("none") {
+        return Ok(None);
+    }
+    let parsed: f64 = value.parse()?;
+    if !parsed.is_finite() || parsed <= 0.0 || parsed > 1.0 {
+        return Err(eyre::eyre!("must be within (0, 1] or off"));
+    }
+    Ok(Some(parsed))
+}
+
+pub fn parse_implied_vol_weight(value: &str) -> Result<Option<f64>> {
+    if value.eq_ignore_ascii_case("off") || value.eq_ignore_ascii_case("none") {
+        return Ok(None);
+    }
+    let parsed: f64 = value.parse()?;
+    if !parsed.is_finite() || !(0.0..=1.0).contains(&parsed) {
+        return Err(eyre::eyre!("must be within [0, 1] or off"));
+    }
+    Ok(Some(parsed))
+}
+
+fn parse_non_negative_decimal(value: &str) -> Result<Decimal> {
+    let parsed = Decimal::from_str(value)?;
+    if parsed.is_sign_negative() {
+        return Err(eyre::eyre!("must be a non-negative decimal"));
+    }
+    Ok(parsed)
+}
@@ -0,0 +1,117 @@
+use std::env;
+use std::fmt;
+use std::fs::File;
+use std::io::Read;
+use std::os::fd::FromRawFd;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use ethers::signers::LocalWallet;
+use eyre::Result;
+use tracing::{info, instrument};
+
+use crate::logging;
+
+/// Environment variable the passphrase is read from with WALLET_KEYSTORE_PASSPHRASE_SOURCE=env
+pub const KEYSTORE_PASSPHRASE_ENV: &str = "WALLET_KEYSTORE_PASSPHRASE";
+
+/// Where the keystore passphrase comes from, selected with WALLET_KEYSTORE_PASSPHRASE_SOURCE
+#[derive(Debug, Clone, PartialEq)]
+pub enum PassphraseSource {
+    Prompt,   // Asked for on the terminal at startup
+    Env,      // WALLET_KEYSTORE_PASSPHRASE
+    Fd(i32),  // Read to EOF from an inherited file descriptor, e.g. fd:3 with `3<passphrase_file`
+}
+
+impl FromStr for PassphraseSource {
+    type Err = eyre::Report;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "prompt" => Ok(PassphraseSource::Prompt),
+            "env" => Ok(PassphraseSource::Env),
+            _ => match value.strip_prefix("fd:").map(str::parse::<i32>) {
+                Some(Ok(fd)) if fd >= 0 => Ok(PassphraseSource::Fd(fd)),
+                _ => Err(eyre::eyre!("expected prompt, env or fd:<n>")),
+            },
+        }
+    }
+}
+
+/// Web3 keystore v3 file (as written by geth, cast wallet or MetaMask exports) holding the wallet key
+pub struct EncryptedKeystore {
+    pub path: PathBuf,
+    pub passphrase_source: PassphraseSource,
+    unlocked: OnceLock<LocalWallet>,
+    fd_passphrase: OnceLock<Result<String, String>>, // A descriptor can only be read once, retries reuse its outcome
+}
+
+/// The account's wallet key: a plaintext private key from the secrets backend, or an encrypted keystore
+/// unlocked with a passphrase the first time the wallet is needed
+pub enum WalletKey {
+    PrivateKey(String),
+    Keystore(EncryptedKeystore),
+}
+
+impl WalletKey {
+    pub fn keystore(path: PathBuf, passphrase_source: PassphraseSource) -> Self {
+        WalletKey::Keystore(EncryptedKeystore { path, passphrase_source, unlocked: OnceLock::new(), fd_passphrase: OnceLock::new() })
+    }
+
+    /// The wallet for this key. A keystore is decrypted once per process, later calls reuse the unlocked wallet
+    /// so the passphrase is only asked for (or read from its descriptor) once.
+    pub fn wallet(&self) -> Result<LocalWallet> {
+        match self {
+            WalletKey::PrivateKey(private_key) => Ok(LocalWallet::from_str(private_key)?),
+            WalletKey::Keystore(keystore) => {
+                if let Some(wallet) = keystore.unlocked.get() {
+                    return Ok(wallet.clone());
+                }
+                let wallet = keystore.unlock()?;
+                Ok(keystore.unlocked.get_or_init(|| wallet).clone())
+            }
+        }
+    }
+}
+
+impl EncryptedKeystore {
+    #[instrument(skip(self), fields(path = %self.path.display()))]
+    fn unlock(&self) -> Result<LocalWallet> {
+        let passphrase = match self.passphrase_source {
+            PassphraseSource::Prompt => rpassword::prompt_password(format!("Passphrase for {}: ", self.path.display()))?,
+            PassphraseSource::Env => env::var(KEYSTORE_PASSPHRASE_ENV)
+                .map_err(|_| eyre::eyre!("Missing {}", KEYSTORE_PASSPHRASE_ENV))?,
+            PassphraseSource::Fd(fd) => self.fd_passphrase
+                .get_or_init(|| read_passphrase_fd(fd).map_err(|e| format!("Failed to read passphrase from fd {}: {}", fd, e)))
+                .clone()
+                .map_err(|e| eyre::eyre!(e))?,
+        };
+        logging::register_secret(&passphrase);
+
+        let wallet = LocalWallet::decrypt_keystore(&self.path, passphrase)
+            .map_err(|e| eyre::eyre!("Failed to unlock keystore {}: {}", self.path.display(), e))?;
+        info!("Wallet keystore unlocked");
+        Ok(wallet)
+    }
+}
+
+/// Read the passphrase from a descriptor handed over by the parent process, closing it once read
+fn read_passphrase_fd(fd: i32) -> std::io::Result<String> {
+    let mut file = unsafe { File::from_raw_fd(fd) };
+    let mut passphrase = String::new();
+    file.read_to_string(&mut passphrase)?;
+    Ok(passphrase.trim_end_matches(['\r', '\n']).to_string())
+}
+
+// Never print key material, only where it comes from
+impl fmt::Debug for WalletKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WalletKey::PrivateKey(_) => write!(f, "PrivateKey(<redacted>)"),
+            WalletKey::Keystore(keystore) => f.debug_struct("Keystore")
+                .field("path", &keystore.path)
+                .field("passphrase_source", &keystore.passphrase_source)
+                .finish(),
+        }
+    }
+}
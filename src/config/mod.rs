@@ -0,0 +1,936 @@
+pub mod secrets;
+pub mod dynamic;
+pub mod keystore;
+
+use std::env;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use ethers::providers::{Provider, Http};
+use ethers::types::Address;
+use rust_decimal::Decimal;
+use std::sync::Once;
+
+use crate::constants;
+use crate::logging;
+use crate::gmx::event_fetcher::EventFinality;
+use crate::strategy::allocator::AllocatorMode;
+use crate::strategy::strategy_constants::DEFAULT_RETURN_SIGNAL_WEIGHTS;
+use secrets::SecretsManager;
+use keystore::{PassphraseSource, WalletKey};
+
+static INIT_CRYPTO: Once = Once::new();
+
+#[derive(Debug)]
+pub struct Config {
+    pub alchemy_provider: Arc<Provider<Http>>,
+    pub alchemy_ws_url: String,
+    pub wallet_key: WalletKey, // Plaintext private key or encrypted keystore, unlocked when the wallet is built
+    pub wallet_mnemonic: String,
+    pub network_mode: String,
+    pub execution_mode: String,
+    pub account_id: String, // Portfolio account this process trades, scopes wallets, plans, trades and overrides
+    pub chain_id: u64,
+    pub gmx_datastore: Address,
+    pub gmx_reader: Address,
+    pub gmx_eventemitter: Address,
+    pub gmx_exchangerouter: Address,
+    pub gmx_baserouter: Address,
+    pub gmx_depositvault: Address,
+    pub gmx_withdrawalvault: Address,
+    pub gmx_shiftvault: Address, 
+    pub event_finality: EventFinality, // Depth at which GMX event logs are ingested, shallower blocks may still be reorged
+    pub wnt_address: Address,
+    pub gmx_rewards_distributor: Option<Address>,
+    pub gmx_ui_fee_receiver: Option<Address>, // UI fee receiver named on GM requests, UI fees are only claimable when it is the wallet itself
+    pub gmx_callback_contract: Option<Address>,
+    pub gmx_callback_gas_limit: u64,
+    pub etherscan_api_key: String,
+    pub refetch_abis: bool,
+    pub abi_offline: bool,
+    pub database_url: String,
+    pub database_read_url: Option<String>,
+    pub secrets: Arc<SecretsManager>,
+    pub redis_url: String,
+    pub redis_username: Option<String>,
+    pub redis_password: Option<String>,
+    pub redis_db: Option<i64>,
+    pub redis_tls_insecure: bool,
+    pub redis_connect_max_retries: u32,
+    pub price_stream_addr: String, // Address the price stream server listens on for Server-Sent Events clients
+    pub token_prices_stale_after_mins: u64, // Pipeline watchdog alerts when no token_prices entry arrives for this long
+    pub market_states_stale_after_mins: u64, // Pipeline watchdog alerts when no market_states entry arrives for this long
+    pub zerox_api_key: String,
+    pub gm_order_timeout_secs: u64,
+    pub plan_failure_policy: String,
+    pub plan_max_concurrent_actions: usize,
+    pub plan_duplicate_window_secs: u64,
+    pub approval_mode: bool,
+    pub plan_approval_ttl_secs: u64,
+    pub reporting_currency: String,
+    pub raw_data_retention_days: i64,
+    pub max_price_deviation_pct: Decimal,
+    pub max_oracle_deviation_pct: Decimal,
+    pub max_market_data_age_secs: i64,
+    pub max_funding_data_age_secs: i64,
+    pub daily_fee_budget_usd: Option<Decimal>,
+    pub monthly_fee_budget_usd: Option<Decimal>,
+    pub wallet_watchdog_poll_secs: u64,
+    pub wallet_watchdog_safe_mode: bool,
+    pub archive_rpc_url: Option<String>,
+    pub gm_price_verify_interval_secs: u64,
+    pub gm_price_verify_sample_size: i64,
+    pub gm_price_verify_tolerance_pct: Decimal,
+    pub permit_approvals: bool,
+    pub sanctions_deny_list: HashSet<Address>,
+    pub sanctions_oracle_screening: bool,
+    pub gas_spike_multiplier: Decimal,
+    pub gas_baseline_window_hours: i64,
+    pub gas_max_deferral_secs: u64,
+    pub gas_deferrable_actions: Vec<String>,
+    pub gas_fee_buffer_autotune: bool,
+    pub gas_fee_buffer_min: Decimal,
+    pub gas_fee_buffer_max: Decimal,
+    pub gas_fee_buffer_quantile: f64,
+    pub gas_fee_buffer_lookback_days: i64,
+    pub max_deposit_utilization: Decimal,
+    pub gas_reserve_min_native: Decimal,
+    pub gas_reserve_target_native: Decimal,
+    pub gas_reserve_source_token: Option<Address>,
+    pub allocator_mode: AllocatorMode,
+    pub market_universe: Option<String>, // Universe (market_universes table) trading runs allocate, None for every market
+    pub rebalance_id: Option<i32>, // Rebalance (rebalances table) the legs this process records belong to, None unless set
+    pub allocator_kelly_fraction: Option<f64>,
+    pub risk_horizon_hours: i64,
+    pub implied_vol_weight: Option<f64>,
+    pub min_trade_size_usd: Decimal,
+    pub min_swap_size_usd: Decimal,
+    pub dust_threshold_usd: Decimal,
+    pub twap_min_notional_usd: Decimal,
+    pub twap_slices: u32,
+    pub twap_duration_mins: u64,
+    pub twap_max_price_move_pct: Decimal,
+    pub base_stablecoin: Option<Address>,
+    pub risk_free_rate_apr: Option<Decimal>,
+    pub aave_pool_address: Option<Address>,
+    pub dydx_config_path: Option<String>,
+    pub dydx_sandbox: bool,
+    pub hyperliquid_enabled: bool,
+    pub hyperliquid_api_url: String,
+    pub hedge_min_volume_usd: Decimal,
+    pub hedge_rebalance_band_pct: Decimal,
+    pub hedge_rebalance_target_band_pct: Decimal,
+    pub hedge_band_vol_multiple: Option<Decimal>,
+    pub collateral_drift_threshold: Option<Decimal>,
+    pub weight_smoothing_alpha: Option<Decimal>,
+    pub weight_drift_band: Decimal,
+    pub weight_drift_bands: HashMap<Address, Decimal>,
+    pub min_holding_hours: i64,
+    pub min_holding_hours_by_market: HashMap<Address, i64>,
+    pub reentry_cooldown_hours: i64,
+    pub reentry_cooldown_hours_by_market: HashMap<Address, i64>,
+    pub return_ensemble_combiner: String,
+    pub return_signal_weights: HashMap<String, Decimal>,
+    pub signal_sink_addr: String, // Address the signal sink listens on for signed external signal payloads
+    pub external_signal_trust: HashMap<Address, Decimal>, // Trust weight of each accepted external signal signer
+    pub config_refresh_interval_secs: u64,
+    pub gmx_subgraph_url: String,
+    pub gmx_api_url: String,
+    pub paraswap_api_url: Option<String>,
+    pub paraswap_api_key: Option<String>,
+    pub paraswap_partner: Option<String>,
+    pub paraswap_partner_address: Option<Address>,
+    pub paraswap_partner_fee_bps: u32,
+    pub paraswap_requests_per_sec: u32,
+    pub oneinch_api_key: Option<String>,
+}
+
+impl Config {
+    pub async fn load() -> Arc<Self> {
+        // Load network mode env var and validate it
+        let network_mode = env::var("NETWORK_MODE").expect("Missing NETWORK_MODE environment variable");
+        if network_mode != "test" && network_mode != "prod" {
+            panic!("NETWORK_MODE must be either 'test' or 'prod'");
+        }
+        
+        // Load execution mode (paper or live)
+        let execution_mode = env::var("EXECUTION_MODE").unwrap_or_else(|_| "paper".to_string());
+        if execution_mode != "paper" && execution_mode != "live" {
+            panic!("EXECUTION_MODE must be either 'paper' or 'live'");
+        }
+
+        // Load portfolio account, each account is an independent mandate with its own wallet sharing this deployment
+        let account_id = env::var("ACCOUNT_ID").unwrap_or_else(|_| constants::DEFAULT_ACCOUNT_ID.to_string());
+        if account_id.is_empty() || !account_id.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_') {
+            panic!("ACCOUNT_ID must be non-empty lowercase letters, digits and underscores");
+        }
+        // Accounts other than the default read their wallet secrets with the account as suffix, e.g. WALLET_PRIVATE_KEY_PROD_AGGRESSIVE
+        let account_secret_suffix = if account_id == constants::DEFAULT_ACCOUNT_ID {
+            String::new()
+        } else {
+            format!("_{}", account_id.to_uppercase())
+        };
+
+        // Load secrets provider (env, Vault or AWS Secrets Manager) for the wallet key, mnemonic and database password
+        let secrets = Arc::new(SecretsManager::from_env());
+        if secrets.supports_rotation() {
+            secrets.refresh().await.expect("Failed to fetch secrets from secrets backend");
+        }
+
+        // Load alchemy RPC HTTP URL based on network mode, create ethers provider
+        let alchemy_rpc_url = match network_mode.as_str() {
+            "test" => env::var("ALCHEMY_RPC_URL_TEST").expect("Missing ALCHEMY_RPC_URL_TEST"),
+            "prod" => env::var("ALCHEMY_RPC_URL_PROD").expect("Missing ALCHEMY_RPC_URL_PROD"),
+            _ => panic!("Invalid NETWORK_MODE"),
+        };
+
+        let provider = Provider::<Http>::try_from(alchemy_rpc_url)
+            .expect("Failed to create Alchemy provider");
+
+        // Load alchemy WebSocket URL based on network mode, create ethers provider
+        let alchemy_ws_url = match network_mode.as_str() {
+            "test" => env::var("ALCHEMY_WS_URL_TEST").expect("Missing ALCHEMY_WS_URL_TEST"),
+            "prod" => env::var("ALCHEMY_WS_URL_PROD").expect("Missing ALCHEMY_WS_URL_PROD"),
+            _ => panic!("Invalid NETWORK_MODE"),
+        };
+
+        // Load the account's wallet key based on network mode: an encrypted keystore file when its path is set,
+        // unlocked with a passphrase from WALLET_KEYSTORE_PASSPHRASE_SOURCE (prompt, env or fd:<n>), the private key otherwise
+        let wallet_keystore_path_name = match network_mode.as_str() {
+            "test" => format!("WALLET_KEYSTORE_PATH_TEST{}", account_secret_suffix),
+            "prod" => format!("WALLET_KEYSTORE_PATH_PROD{}", account_secret_suffix),
+            _ => panic!("Invalid NETWORK_MODE"),
+        };
+        let wallet_key = match env::var(&wallet_keystore_path_name) {
+            Ok(path) => {
+                let path = std::path::PathBuf::from(path);
+                if !path.is_file() {
+                    panic!("{} must point to a keystore file", wallet_keystore_path_name);
+                }
+                let passphrase_source: PassphraseSource = env::var("WALLET_KEYSTORE_PASSPHRASE_SOURCE")
+                    .map(|v| v.parse().expect("WALLET_KEYSTORE_PASSPHRASE_SOURCE must be prompt, env or fd:<n>"))
+                    .unwrap_or(PassphraseSource::Prompt);
+                WalletKey::keystore(path, passphrase_source)
+            }
+            Err(_) => {
+                let wallet_private_key_name = match network_mode.as_str() {
+                    "test" => format!("WALLET_PRIVATE_KEY_TEST{}", account_secret_suffix),
+                    "prod" => format!("WALLET_PRIVATE_KEY_PROD{}", account_secret_suffix),
+                    _ => panic!("Invalid NETWORK_MODE"),
+                };
+                let wallet_private_key = secrets.require(&wallet_private_key_name).await
+                    .unwrap_or_else(|_| panic!("Missing {} (or {})", wallet_private_key_name, wallet_keystore_path_name));
+                WalletKey::PrivateKey(wallet_private_key)
+            }
+        };
+
+        // Load the account's wallet mnemonic (also the dYdX wallet) based on network mode
+        let wallet_mnemonic_name = match network_mode.as_str() {
+            "test" => format!("WALLET_MNEMONIC_TEST{}", account_secret_suffix),
+            "prod" => format!("WALLET_MNEMONIC_PROD{}", account_secret_suffix),
+            _ => panic!("Invalid NETWORK_MODE"),
+        };
+        let wallet_mnemonic = secrets.require(&wallet_mnemonic_name).await
+            .unwrap_or_else(|_| panic!("Missing {}", wallet_mnemonic_name));
+
+        // Load chain ID based on network mode
+        let chain_id = match network_mode.as_str() {
+            "test" => constants::ARBITRUM_SEPOLIA_CHAIN_ID,
+            "prod" => constants::ARBITRUM_MAINNET_CHAIN_ID,
+            _ => panic!("Invalid NETWORK_MODE"),
+        };
+
+        // Load GMX contract addresses based on network mode
+        let (
+            gmx_datastore, gmx_reader, gmx_eventemitter, gmx_exchangerouter, 
+            gmx_baserouter, gmx_depositvault, gmx_withdrawalvault, gmx_shiftvault
+        ) = match network_mode.as_str() {
+            "test" => {
+                
+                (   
+                    constants::GMX_DATASTORE_ADDRESS_SEPOLIA, 
+                    constants::GMX_READER_ADDRESS_SEPOLIA,
+                    constants::GMX_EVENTEMITTER_ADDRESS_SEPOLIA,
+                    constants::GMX_EXCHANGEROUTER_ADDRESS_SEPOLIA,
+                    constants::GMX_BASEROUTER_ADDRESS_SEPOLIA,
+                    constants::GMX_DEPOSITVAULT_ADDRESS_SEPOLIA,
+                    constants::GMX_WITHDRAWALVAULT_ADDRESS_SEPOLIA,
+                    constants::GMX_SHIFTVAULT_ADDRESS_SEPOLIA,
+                ) // No shift vault on Sepolia, using same address as mainnet for placeholder
+        },
+            "prod" => (
+                constants::GMX_DATASTORE_ADDRESS_MAINNET, 
+                constants::GMX_READER_ADDRESS_MAINNET,
+                constants::GMX_EVENTEMITTER_ADDRESS_MAINNET,
+                constants::GMX_EXCHANGEROUTER_ADDRESS_MAINNET,
+                constants::GMX_BASEROUTER_ADDRESS_MAINNET,
+                constants::GMX_DEPOSITVAULT_ADDRESS_MAINNET,
+                constants::GMX_WITHDRAWALVAULT_ADDRESS_MAINNET,
+                constants::GMX_SHIFTVAULT_ADDRESS_MAINNET, 
+            ),
+            _ => panic!("Invalid NETWORK_MODE"),
+        };
+
+        // Load event finality: GMX fee events are ingested once they are EVENT_FINALITY blocks deep (default 20), or
+        // "finalized" to wait for Arbitrum's finalized block (settled on L1, roughly 15 minutes behind)
+        let event_finality = match env::var("EVENT_FINALITY") {
+            Ok(v) if v == "finalized" => EventFinality::Finalized,
+            Ok(v) => EventFinality::Confirmations(v.parse().expect("EVENT_FINALITY must be finalized or a number of blocks")),
+            Err(_) => EventFinality::Confirmations(constants::DEFAULT_EVENT_CONFIRMATION_BLOCKS),
+        };
+
+        // Load wrapped native token address based on network mode
+        let wnt_address = match network_mode.as_str() {
+            "test" => constants::WNT_ADDRESS_SEPOLIA,
+            "prod" => constants::WNT_ADDRESS,
+            _ => panic!("Invalid NETWORK_MODE"),
+        };
+
+        // Load optional GM incentives rewards distributor (reward claims are skipped without it)
+        let gmx_rewards_distributor = env::var("GMX_REWARDS_DISTRIBUTOR")
+            .ok()
+            .map(|v| v.parse().expect("Invalid GMX_REWARDS_DISTRIBUTOR address"));
+
+        // Load optional GMX UI fee receiver (zero address without it): set to the wallet's own address, the UI fees
+        // charged on its requests accrue to the wallet and are claimed back
+        let gmx_ui_fee_receiver = env::var("GMX_UI_FEE_RECEIVER")
+            .ok()
+            .map(|v| v.parse().expect("Invalid GMX_UI_FEE_RECEIVER address"));
+
+        // Load optional GMX callback receiver (deployed with deploy_gmx_callback): when set, GM requests name it as their
+        // callback contract and request completion is detected from its events instead of polling the request lists
+        let gmx_callback_contract = env::var("GMX_CALLBACK_CONTRACT")
+            .ok()
+            .map(|v| v.parse().expect("Invalid GMX_CALLBACK_CONTRACT address"));
+        let gmx_callback_gas_limit = env::var("GMX_CALLBACK_GAS_LIMIT")
+            .map(|v| v.parse().expect("GMX_CALLBACK_GAS_LIMIT must be a positive integer"))
+            .unwrap_or(100_000);
+
+        // Load Etherscan API key, refetch ABIs flag and offline flag (never contact the ABI source, use the embedded ABIs)
+        let etherscan_api_key = env::var("ETHERSCAN_API_KEY").expect("Missing ETHERSCAN_API_KEY");
+        let refetch_abis = env::var("REFETCH_ABIS")
+            .map(|v| v.parse().unwrap_or(false))
+            .unwrap_or(false);
+        let abi_offline = env::var("ABI_OFFLINE")
+            .map(|v| v.parse().unwrap_or(false))
+            .unwrap_or(false);
+
+        // Load database URL and optional read replica URL (heavy read-only queries are routed to the replica)
+        // The password is taken from the secrets provider when it has one
+        let mut database_url = env::var("DATABASE_URL").expect("Missing DATABASE_URL");
+        let mut database_read_url = env::var("DATABASE_READ_URL").ok();
+        if let Some(password) = secrets.get(secrets::DATABASE_PASSWORD_SECRET).await.expect("Failed to fetch database password") {
+            database_url = secrets::with_database_password(&database_url, &password).expect("Invalid DATABASE_URL");
+            database_read_url = database_read_url
+                .map(|url| secrets::with_database_password(&url, &password).expect("Invalid DATABASE_READ_URL"));
+        }
+
+        // Load Redis URL (rediss:// for TLS) with optional credentials, database index and TLS/connection retry options
+        let redis_url = env::var("REDIS_URL").unwrap_or_else(|_| "redis://redis:6379".to_string());
+        let redis_username = env::var("REDIS_USERNAME").ok();
+        let redis_password = env::var("REDIS_PASSWORD").ok();
+        let redis_db = env::var("REDIS_DB")
+            .ok()
+            .map(|v| v.parse().expect("REDIS_DB must be a non-negative integer"));
+        let redis_tls_insecure = env::var("REDIS_TLS_INSECURE")
+            .map(|v| v.parse().unwrap_or(false))
+            .unwrap_or(false);
+        let redis_connect_max_retries = env::var("REDIS_CONNECT_MAX_RETRIES")
+            .map(|v| v.parse().expect("REDIS_CONNECT_MAX_RETRIES must be a positive integer"))
+            .unwrap_or(10);
+
+        // Load address of the price stream server, pushing newly collected prices and market states to clients
+        let price_stream_addr = env::var("PRICE_STREAM_ADDR").unwrap_or_else(|_| "127.0.0.1:8090".to_string());
+        price_stream_addr.parse::<std::net::SocketAddr>().expect("PRICE_STREAM_ADDR must be a socket address, e.g. 0.0.0.0:8090");
+
+        // Load pipeline staleness thresholds, data collection publishes to both streams every 5 minutes
+        let token_prices_stale_after_mins: u64 = env::var("TOKEN_PRICES_STALE_AFTER_MINS")
+            .map(|v| v.parse().expect("TOKEN_PRICES_STALE_AFTER_MINS must be a positive integer"))
+            .unwrap_or(15);
+        let market_states_stale_after_mins: u64 = env::var("MARKET_STATES_STALE_AFTER_MINS")
+            .map(|v| v.parse().expect("MARKET_STATES_STALE_AFTER_MINS must be a positive integer"))
+            .unwrap_or(15);
+        if token_prices_stale_after_mins == 0 || market_states_stale_after_mins == 0 {
+            panic!("TOKEN_PRICES_STALE_AFTER_MINS and MARKET_STATES_STALE_AFTER_MINS must be positive");
+        }
+
+        // Load 0x API key
+        let zerox_api_key = env::var("ZEROX_API_KEY").expect("Missing ZEROX_API_KEY");
+
+        // Load timeout after which unexecuted GM deposits/withdrawals/shifts are cancelled
+        let gm_order_timeout_secs = env::var("GM_ORDER_TIMEOUT_SECS")
+            .map(|v| v.parse().expect("GM_ORDER_TIMEOUT_SECS must be a positive integer"))
+            .unwrap_or(600);
+
+        // Load what happens when a plan action fails after the actions it depends on executed: compensate rolls the
+        // stranded actions back (withdraw what a deposit minted, redeposit what a withdrawal returned), replan leaves
+        // the intermediate holdings for the next strategy run to plan from
+        let plan_failure_policy = env::var("PLAN_FAILURE_POLICY").unwrap_or_else(|_| "compensate".to_string());
+        if plan_failure_policy != "compensate" && plan_failure_policy != "replan" {
+            panic!("PLAN_FAILURE_POLICY must be either 'compensate' or 'replan'");
+        }
+
+        // Load max number of independent plan actions (disjoint spent and produced tokens) executed at once
+        let plan_max_concurrent_actions = env::var("PLAN_MAX_CONCURRENT_ACTIONS")
+            .map(|v| v.parse().expect("PLAN_MAX_CONCURRENT_ACTIONS must be a positive integer"))
+            .unwrap_or(4);
+        if plan_max_concurrent_actions == 0 {
+            panic!("PLAN_MAX_CONCURRENT_ACTIONS must be at least 1");
+        }
+
+        // Load window in which a plan identical to an earlier one (same source, actions and amounts) is refused, guarding
+        // against double execution by a misfiring scheduler or two instances running at once (0 disables the check)
+        let plan_duplicate_window_secs = env::var("PLAN_DUPLICATE_WINDOW_SECS")
+            .map(|v| v.parse().expect("PLAN_DUPLICATE_WINDOW_SECS must be a non-negative integer"))
+            .unwrap_or(3600);
+
+        // Load optional 1inch API key (1inch swap quotes are skipped without it)
+        let oneinch_api_key = env::var("ONEINCH_API_KEY").ok();
+
+        // Load approval mode flag (plans wait for operator approval before execution) and plan expiry
+        let approval_mode = env::var("APPROVAL_MODE")
+            .map(|v| v.parse().unwrap_or(false))
+            .unwrap_or(false);
+        let plan_approval_ttl_secs = env::var("PLAN_APPROVAL_TTL_SECS")
+            .map(|v| v.parse().expect("PLAN_APPROVAL_TTL_SECS must be a positive integer"))
+            .unwrap_or(3600);
+
+        // Load reporting currency valuations and performance metrics are expressed in (USD or a tracked token symbol)
+        let reporting_currency = env::var("REPORTING_CURRENCY")
+            .map(|v| v.to_uppercase())
+            .unwrap_or_else(|_| "USD".to_string());
+
+        // Load number of days raw token prices and market states are kept before being downsampled to hourly rows
+        let raw_data_retention_days = env::var("RAW_DATA_RETENTION_DAYS")
+            .map(|v| v.parse().expect("RAW_DATA_RETENTION_DAYS must be a positive integer"))
+            .unwrap_or(30);
+
+        // Load price anomaly thresholds (% move from the previous observation / from the Chainlink oracle) past which prices are quarantined
+        let max_price_deviation_pct = env::var("MAX_PRICE_DEVIATION_PCT")
+            .map(|v| v.parse().expect("MAX_PRICE_DEVIATION_PCT must be a decimal percentage"))
+            .unwrap_or(Decimal::from(25));
+        let max_oracle_deviation_pct = env::var("MAX_ORACLE_DEVIATION_PCT")
+            .map(|v| v.parse().expect("MAX_ORACLE_DEVIATION_PCT must be a decimal percentage"))
+            .unwrap_or(Decimal::from(10));
+
+        // Load maximum age of the newest market state / token price and of the newest dYdX funding rate before a run is aborted
+        let max_market_data_age_secs = env::var("MAX_MARKET_DATA_AGE_SECS")
+            .map(|v| v.parse().expect("MAX_MARKET_DATA_AGE_SECS must be a positive integer"))
+            .unwrap_or(15 * 60); // Data collection runs every 5 minutes
+        let max_funding_data_age_secs = env::var("MAX_FUNDING_DATA_AGE_SECS")
+            .map(|v| v.parse().expect("MAX_FUNDING_DATA_AGE_SECS must be a positive integer"))
+            .unwrap_or(3 * 3600); // Funding is collected hourly
+
+        // Load optional daily/monthly gas + execution fee budgets (unset means unlimited)
+        let daily_fee_budget_usd = env::var("DAILY_FEE_BUDGET_USD")
+            .ok()
+            .map(|v| v.parse().expect("DAILY_FEE_BUDGET_USD must be a decimal USD amount"));
+        let monthly_fee_budget_usd = env::var("MONTHLY_FEE_BUDGET_USD")
+            .ok()
+            .map(|v| v.parse().expect("MONTHLY_FEE_BUDGET_USD must be a decimal USD amount"));
+
+        // Load wallet watchdog settings: how often the wallet nonce is checked for transactions the bot didn't send,
+        // and whether such a transaction also switches the system into safe mode
+        let wallet_watchdog_poll_secs = env::var("WALLET_WATCHDOG_POLL_SECS")
+            .map(|v| v.parse().expect("WALLET_WATCHDOG_POLL_SECS must be a positive integer"))
+            .unwrap_or(30);
+        let wallet_watchdog_safe_mode = env::var("WALLET_WATCHDOG_SAFE_MODE")
+            .map(|v| v.parse().unwrap_or(false))
+            .unwrap_or(false);
+
+        // Load GM price verification: a random sample of recorded GM prices is periodically re-read on-chain at the
+        // block they were collected at (needs an archive node, the main RPC is used when no archive RPC is set)
+        let archive_rpc_url = env::var("ARCHIVE_RPC_URL").ok();
+        let gm_price_verify_interval_secs = env::var("GM_PRICE_VERIFY_INTERVAL_SECS")
+            .map(|v| v.parse().expect("GM_PRICE_VERIFY_INTERVAL_SECS must be a positive integer"))
+            .unwrap_or(3600);
+        let gm_price_verify_sample_size = env::var("GM_PRICE_VERIFY_SAMPLE_SIZE")
+            .map(|v| v.parse().expect("GM_PRICE_VERIFY_SAMPLE_SIZE must be a positive integer"))
+            .unwrap_or(10);
+        let gm_price_verify_tolerance_pct = env::var("GM_PRICE_VERIFY_TOLERANCE_PCT")
+            .map(|v| v.parse().expect("GM_PRICE_VERIFY_TOLERANCE_PCT must be a decimal percentage"))
+            .unwrap_or(Decimal::new(5, 1));
+
+        // Load permit approvals: grant token allowances by signature bundled with the trade (EIP-2612 for GM deposits,
+        // withdrawals and shifts, Permit2 for 0x swaps) instead of separate approve transactions where possible
+        let permit_approvals = env::var("PERMIT_APPROVALS")
+            .map(|v| v.parse().unwrap_or(false))
+            .unwrap_or(false);
+
+        // Load sanctions screening: swaps are refused when the swap contract, spender or any reported route contract
+        // is on the deny-list (comma-separated addresses), or flagged by the Chainalysis sanctions oracle when enabled
+        let sanctions_deny_list = env::var("SANCTIONS_DENY_LIST")
+            .unwrap_or_default()
+            .split(',')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse().expect("SANCTIONS_DENY_LIST must be comma-separated addresses"))
+            .collect();
+        let sanctions_oracle_screening = env::var("SANCTIONS_ORACLE_SCREENING")
+            .map(|v| v.parse().unwrap_or(false))
+            .unwrap_or(false);
+
+        // Load gas spike protection: execution fees above multiplier x the rolling median defer the configured
+        // (non-urgent) GM action types until fees normalize or the max deferral passes
+        let gas_spike_multiplier = env::var("GAS_SPIKE_MULTIPLIER")
+            .map(|v| v.parse().expect("GAS_SPIKE_MULTIPLIER must be a decimal multiplier"))
+            .unwrap_or(Decimal::from(2));
+        let gas_baseline_window_hours = env::var("GAS_BASELINE_WINDOW_HOURS")
+            .map(|v| v.parse().expect("GAS_BASELINE_WINDOW_HOURS must be a positive integer"))
+            .unwrap_or(24);
+        let gas_max_deferral_secs = env::var("GAS_MAX_DEFERRAL_SECS")
+            .map(|v| v.parse().expect("GAS_MAX_DEFERRAL_SECS must be a non-negative integer"))
+            .unwrap_or(3600);
+        let gas_deferrable_actions = env::var("GAS_DEFERRABLE_ACTIONS")
+            .unwrap_or_else(|_| "GmDeposit,GmShift".to_string())
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        // Load max fee per gas buffer autotuning: once enough GM requests have recorded base fees, each action type's
+        // buffer is the quantile of the buffer its requests actually needed over the lookback, kept within the bounds
+        let gas_fee_buffer_autotune = env::var("GAS_FEE_BUFFER_AUTOTUNE")
+            .map(|v| v.parse().unwrap_or(true))
+            .unwrap_or(true);
+        let gas_fee_buffer_min: Decimal = env::var("GAS_FEE_BUFFER_MIN")
+            .map(|v| v.parse().expect("GAS_FEE_BUFFER_MIN must be a decimal multiplier"))
+            .unwrap_or(Decimal::new(102, 2));
+        let gas_fee_buffer_max: Decimal = env::var("GAS_FEE_BUFFER_MAX")
+            .map(|v| v.parse().expect("GAS_FEE_BUFFER_MAX must be a decimal multiplier"))
+            .unwrap_or(Decimal::new(13, 1));
+        if gas_fee_buffer_min < Decimal::ONE || gas_fee_buffer_max < gas_fee_buffer_min {
+            panic!("GAS_FEE_BUFFER_MIN must be at least 1 and GAS_FEE_BUFFER_MAX at least GAS_FEE_BUFFER_MIN");
+        }
+        let gas_fee_buffer_quantile: f64 = env::var("GAS_FEE_BUFFER_QUANTILE")
+            .map(|v| v.parse().expect("GAS_FEE_BUFFER_QUANTILE must be a decimal between 0 and 1"))
+            .unwrap_or(0.99);
+        if !(0.0..=1.0).contains(&gas_fee_buffer_quantile) {
+            panic!("GAS_FEE_BUFFER_QUANTILE must be between 0 and 1");
+        }
+        let gas_fee_buffer_lookback_days = env::var("GAS_FEE_BUFFER_LOOKBACK_DAYS")
+            .map(|v| v.parse().expect("GAS_FEE_BUFFER_LOOKBACK_DAYS must be a positive integer"))
+            .unwrap_or(14);
+
+        // Load utilization ceiling (fraction of pool liquidity reserved by open interest) above which deposits are blocked
+        let max_deposit_utilization = env::var("MAX_DEPOSIT_UTILIZATION")
+            .map(|v| v.parse().expect("MAX_DEPOSIT_UTILIZATION must be a decimal fraction"))
+            .unwrap_or(Decimal::new(9, 1));
+
+        // Load gas reserve policy: below the minimum native balance, unwrap WETH (or buy ETH with the source token)
+        // back up to the target before any other action runs
+        let gas_reserve_min_native = env::var("GAS_RESERVE_MIN_NATIVE")
+            .map(|v| v.parse().expect("GAS_RESERVE_MIN_NATIVE must be a decimal ETH amount"))
+            .unwrap_or(Decimal::new(5, 3));
+        let gas_reserve_target_native = env::var("GAS_RESERVE_TARGET_NATIVE")
+            .map(|v| v.parse().expect("GAS_RESERVE_TARGET_NATIVE must be a decimal ETH amount"))
+            .unwrap_or(Decimal::new(2, 2));
+        if gas_reserve_target_native < gas_reserve_min_native {
+            panic!("GAS_RESERVE_TARGET_NATIVE must not be below GAS_RESERVE_MIN_NATIVE");
+        }
+        let gas_reserve_source_token = env::var("GAS_RESERVE_SOURCE_TOKEN")
+            .ok()
+            .map(|v| v.parse().expect("Invalid GAS_RESERVE_SOURCE_TOKEN"));
+
+        // Load allocator mode: mean_variance trades expected LP return against variance, hedged_carry keeps every
+        // non-stable market fully hedged and allocates by net carry per unit of residual basis risk
+        let allocator_mode = env::var("ALLOCATOR_MODE")
+            .map(|v| dynamic::parse_allocator_mode(&v).expect("ALLOCATOR_MODE must be mean_variance or hedged_carry"))
+            .unwrap_or(AllocatorMode::MeanVariance);
+
+        // Load market universe: runs allocate only the universe's markets, with its capital fraction of the GM holdings,
+        // leaving positions of the other universes to their own runs
+        let market_universe = env::var("MARKET_UNIVERSE").ok().filter(|v| !v.is_empty());
+
+        // Load rebalance ID: a leg run by its own binary (spot swap, hedge order, GM request) is recorded against this
+        // rebalance so its costs show in the rebalance's breakdown
+        let rebalance_id = env::var("REBALANCE_ID")
+            .ok()
+            .filter(|v| !v.is_empty())
+            .map(|v| v.trim().parse::<i32>().expect("REBALANCE_ID must be a rebalance ID"));
+
+        // Load allocator sizing mode: a Kelly fraction in (0, 1] caps each market's weight at that fraction of its Kelly
+        // bet (edge over variance), leaving the rest undeployed; unset or off deploys the full mean-variance weights
+        let allocator_kelly_fraction = env::var("ALLOCATOR_KELLY_FRACTION")
+            .ok()
+            .and_then(|v| dynamic::parse_kelly_fraction(&v).expect("ALLOCATOR_KELLY_FRACTION must be within (0, 1] or off"));
+
+        // Load risk horizon: variances are measured at several horizons and the covariance is scaled to this holding
+        // period from the nearest one, instead of from the 5 minute sampling interval. An implied volatility weight in
+        // [0, 1] blends Deribit DVOL into the variance of BTC and ETH markets (unset uses realized volatility only)
+        let risk_horizon_hours = env::var("RISK_HORIZON_HOURS")
+            .map(|v| v.trim().parse::<i64>().expect("RISK_HORIZON_HOURS must be whole hours"))
+            .unwrap_or(7 * 24);
+        if risk_horizon_hours <= 0 {
+            panic!("RISK_HORIZON_HOURS must be positive");
+        }
+        let implied_vol_weight = env::var("IMPLIED_VOL_WEIGHT")
+            .ok()
+            .and_then(|v| dynamic::parse_implied_vol_weight(&v).expect("IMPLIED_VOL_WEIGHT must be within [0, 1] or off"));
+
+        // Load minimum trade sizes: plan actions moving less than the trade minimum are dropped, and balances worth
+        // less than the swap minimum are not worth the gas to sweep
+        let min_trade_size_usd = env::var("MIN_TRADE_SIZE_USD")
+            .map(|v| v.parse().expect("MIN_TRADE_SIZE_USD must be a decimal USD amount"))
+            .unwrap_or(Decimal::from(25));
+        let min_swap_size_usd = env::var("MIN_SWAP_SIZE_USD")
+            .map(|v| v.parse().expect("MIN_SWAP_SIZE_USD must be a decimal USD amount"))
+            .unwrap_or(Decimal::ONE);
+
+        // Load dust consolidation: asset balances worth less than the threshold are swept into the base stablecoin
+        // (defaults to USDC on mainnet, sweeping is disabled when no base stablecoin is set)
+        let dust_threshold_usd = env::var("DUST_THRESHOLD_USD")
+            .map(|v| v.parse().expect("DUST_THRESHOLD_USD must be a decimal USD amount"))
+            .unwrap_or(Decimal::from(10));
+        let base_stablecoin = match env::var("BASE_STABLECOIN") {
+            Ok(v) => Some(v.parse().expect("Invalid BASE_STABLECOIN")),
+            Err(_) if network_mode == "prod" => Some(constants::USDC_ADDRESS.parse().expect("Invalid USDC address")),
+            Err(_) => None,
+        };
+
+        // Load TWAP execution: spot swaps worth at least the notional are split into slices spread evenly over the
+        // duration, remaining slices are aborted once the quoted price moves past the limit from the first slice's fill
+        let twap_min_notional_usd = env::var("TWAP_MIN_NOTIONAL_USD")
+            .map(|v| v.parse().expect("TWAP_MIN_NOTIONAL_USD must be a decimal USD amount"))
+            .unwrap_or(Decimal::from(10000));
+        let twap_slices = env::var("TWAP_SLICES")
+            .map(|v| v.parse().expect("TWAP_SLICES must be a positive integer"))
+            .unwrap_or(5);
+        let twap_duration_mins = env::var("TWAP_DURATION_MINS")
+            .map(|v| v.parse().expect("TWAP_DURATION_MINS must be a positive integer"))
+            .unwrap_or(10);
+        let twap_max_price_move_pct = env::var("TWAP_MAX_PRICE_MOVE_PCT")
+            .map(|v| v.parse().expect("TWAP_MAX_PRICE_MOVE_PCT must be a decimal percentage"))
+            .unwrap_or(Decimal::ONE);
+        if twap_slices == 0 {
+            panic!("TWAP_SLICES must be a positive integer");
+        }
+
+        // Load risk-free (hurdle) rate: a fixed APR when set, otherwise the Aave supply APR of the base stablecoin
+        // (Aave v3 pool defaults to mainnet, without either the rate is zero)
+        let risk_free_rate_apr = env::var("RISK_FREE_RATE_APR")
+            .ok()
+            .map(|v| v.parse().expect("RISK_FREE_RATE_APR must be a decimal fraction (e.g. 0.05 for 5%)"));
+        let aave_pool_address = match env::var("AAVE_POOL_ADDRESS") {
+            Ok(v) => Some(v.parse().expect("Invalid AAVE_POOL_ADDRESS")),
+            Err(_) if network_mode == "prod" => Some(constants::AAVE_V3_POOL_ADDRESS.parse().expect("Invalid Aave pool address")),
+            Err(_) => None,
+        };
+
+        // Load dYdX client: the node and indexer endpoints default to the network mode's (mainnet or testnet) config file,
+        // and in sandbox mode orders and transfers are signed and simulated against the node but never broadcast
+        let dydx_config_path = env::var("DYDX_CONFIG_PATH").ok();
+        let dydx_sandbox = env::var("DYDX_SANDBOX")
+            .map(|v| v.parse().expect("DYDX_SANDBOX must be true or false"))
+            .unwrap_or(false);
+
+        // Load Hyperliquid hedging: when enabled, tokens dYdX does not list (or lists with too little volume) are hedged on
+        // Hyperliquid perps, signed with the wallet key
+        let hyperliquid_enabled = env::var("HYPERLIQUID_ENABLED")
+            .map(|v| v.parse().unwrap_or(false))
+            .unwrap_or(false);
+        let hyperliquid_api_url = env::var("HYPERLIQUID_API_URL").unwrap_or_else(|_| match network_mode.as_str() {
+            "prod" => "https://api.hyperliquid.xyz".to_string(),
+            _ => "https://api.hyperliquid-testnet.xyz".to_string(),
+        });
+        let hedge_min_volume_usd = env::var("HEDGE_MIN_VOLUME_USD")
+            .map(|v| v.parse().expect("HEDGE_MIN_VOLUME_USD must be a decimal USD amount"))
+            .unwrap_or(Decimal::from(1_000_000));
+
+        // Load hedge delta bands (fractions of the position's notional): a hedge is only re-targeted once it drifts past
+        // the rebalance band, and then only back to the edge of the tighter target band
+        let hedge_rebalance_band_pct = env::var("HEDGE_REBALANCE_BAND_PCT")
+            .map(|v| v.parse().expect("HEDGE_REBALANCE_BAND_PCT must be a decimal fraction"))
+            .unwrap_or(Decimal::new(2, 2));
+        let hedge_rebalance_target_band_pct = env::var("HEDGE_REBALANCE_TARGET_BAND_PCT")
+            .map(|v| v.parse().expect("HEDGE_REBALANCE_TARGET_BAND_PCT must be a decimal fraction"))
+            .unwrap_or(Decimal::new(5, 3));
+        if hedge_rebalance_target_band_pct >= hedge_rebalance_band_pct {
+            panic!("HEDGE_REBALANCE_TARGET_BAND_PCT must be below HEDGE_REBALANCE_BAND_PCT");
+        }
+        // A volatility multiple widens both bands (keeping their ratio) to that many standard deviations of the index
+        // price move until the hedge is next checked, so volatile tokens aren't re-hedged on noise (unset keeps them fixed)
+        let hedge_band_vol_multiple = env::var("HEDGE_BAND_VOL_MULTIPLE")
+            .ok()
+            .map(|v| v.parse::<Decimal>().expect("HEDGE_BAND_VOL_MULTIPLE must be a decimal multiple"));
+        if hedge_band_vol_multiple.is_some_and(|multiple| multiple <= Decimal::ZERO) {
+            panic!("HEDGE_BAND_VOL_MULTIPLE must be positive");
+        }
+
+        // Load collateral drift threshold: how far a held market's long token share may move from where it was when the
+        // position was last traded before the planner withdraws to restore the split (unset disables drift rebalancing)
+        let collateral_drift_threshold = env::var("COLLATERAL_DRIFT_THRESHOLD")
+            .ok()
+            .map(|v| v.parse::<Decimal>().expect("COLLATERAL_DRIFT_THRESHOLD must be a decimal fraction (e.g. 0.1 for 10 points)"));
+        if collateral_drift_threshold.is_some_and(|threshold| threshold <= Decimal::ZERO || threshold >= Decimal::ONE) {
+            panic!("COLLATERAL_DRIFT_THRESHOLD must be between 0 and 1");
+        }
+
+        // Load target weight smoothing: an EMA weight in (0, 1] moves each target only that fraction of the way from
+        // the previous run's target to the optimizer output (unset leaves the output as is), and targets within a
+        // market's drift band of its current weight keep the current weight. The band defaults to WEIGHT_DRIFT_BAND
+        // (0 disables it) and WEIGHT_DRIFT_BANDS sets it per market as address=band pairs.
+        let weight_smoothing_alpha = env::var("WEIGHT_SMOOTHING_ALPHA")
+            .ok()
+            .map(|v| v.parse::<Decimal>().expect("WEIGHT_SMOOTHING_ALPHA must be a decimal fraction"));
+        if weight_smoothing_alpha.is_some_and(|alpha| alpha <= Decimal::ZERO || alpha > Decimal::ONE) {
+            panic!("WEIGHT_SMOOTHING_ALPHA must be within (0, 1]");
+        }
+        let parse_band = |name: &str, value: &str| {
+            let band = value.trim().parse::<Decimal>().unwrap_or_else(|_| panic!("{} must be decimal weight fractions", name));
+            if band < Decimal::ZERO || band >= Decimal::ONE {
+                panic!("{} must be between 0 and 1", name);
+            }
+            band
+        };
+        let weight_drift_band = env::var("WEIGHT_DRIFT_BAND")
+            .map(|v| parse_band("WEIGHT_DRIFT_BAND", &v))
+            .unwrap_or(Decimal::ZERO);
+        let weight_drift_bands = env::var("WEIGHT_DRIFT_BANDS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(|pair| {
+                let (market, band) = pair.split_once('=').expect("WEIGHT_DRIFT_BANDS must be comma-separated market=band pairs");
+                (market.trim().parse().expect("Invalid market address in WEIGHT_DRIFT_BANDS"), parse_band("WEIGHT_DRIFT_BANDS", band))
+            })
+            .collect();
+
+        // Load holding rules: a position entered less than MIN_HOLDING_HOURS ago isn't reduced, and a market exited
+        // less than REENTRY_COOLDOWN_HOURS ago isn't re-entered, unless a risk guard overrides (0 disables either).
+        // MIN_HOLDING_HOURS_BY_MARKET and REENTRY_COOLDOWN_HOURS_BY_MARKET set them per market as address=hours pairs.
+        let parse_hours = |name: &str, value: &str| {
+            let hours = value.trim().parse::<i64>().unwrap_or_else(|_| panic!("{} must be whole hours", name));
+            if hours < 0 {
+                panic!("{} must not be negative", name);
+            }
+            hours
+        };
+        let parse_market_hours = |name: &str| -> HashMap<Address, i64> {
+            env::var(name)
+                .unwrap_or_default()
+                .split(',')
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .map(|pair| {
+                    let (market, hours) = pair.split_once('=').unwrap_or_else(|| panic!("{} must be comma-separated market=hours pairs", name));
+                    (market.trim().parse().unwrap_or_else(|_| panic!("Invalid market address in {}", name)), parse_hours(name, hours))
+                })
+                .collect()
+        };
+        let min_holding_hours = env::var("MIN_HOLDING_HOURS")
+            .map(|v| parse_hours("MIN_HOLDING_HOURS", &v))
+            .unwrap_or(0);
+        let min_holding_hours_by_market = parse_market_hours("MIN_HOLDING_HOURS_BY_MARKET");
+        let reentry_cooldown_hours = env::var("REENTRY_COOLDOWN_HOURS")
+            .map(|v| parse_hours("REENTRY_COOLDOWN_HOURS", &v))
+            .unwrap_or(0);
+        let reentry_cooldown_hours_by_market = parse_market_hours("REENTRY_COOLDOWN_HOURS_BY_MARKET");
+
+        // Load expected return ensemble: signal weights (name=weight pairs) and how signal estimates are combined,
+        // either a confidence-scaled weighted average or stacking (weights fitted walk-forward against realized returns)
+        let return_ensemble_combiner = env::var("RETURN_ENSEMBLE_COMBINER").unwrap_or_else(|_| "weighted_average".to_string());
+        if return_ensemble_combiner != "weighted_average" && return_ensemble_combiner != "stacking" {
+            panic!("RETURN_ENSEMBLE_COMBINER must be either 'weighted_average' or 'stacking'");
+        }
+        let return_signal_weights = env::var("RETURN_SIGNAL_WEIGHTS")
+            .unwrap_or_else(|_| DEFAULT_RETURN_SIGNAL_WEIGHTS.to_string())
+            .split(',')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(|pair| {
+                let (name, weight) = pair.split_once('=').expect("RETURN_SIGNAL_WEIGHTS must be comma-separated name=weight pairs");
+                (name.trim().to_string(), weight.trim().parse().expect("RETURN_SIGNAL_WEIGHTS weights must be decimals"))
+            })
+            .collect();
+
+        // Load the external signal sink: signers whose payloads are accepted (address=trust pairs) and where the sink
+        // listens. A signer's trust in [0, 1] is the weight of its expected returns against the internal estimate.
+        let signal_sink_addr = env::var("SIGNAL_SINK_ADDR").unwrap_or_else(|_| "127.0.0.1:8091".to_string());
+        signal_sink_addr.parse::<std::net::SocketAddr>().expect("SIGNAL_SINK_ADDR must be a socket address, e.g. 0.0.0.0:8091");
+        let external_signal_trust = env::var("EXTERNAL_SIGNAL_TRUST")
+            .unwrap_or_default()
+            .split(',')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(|pair| {
+                let (signer, trust) = pair.split_once('=').expect("EXTERNAL_SIGNAL_TRUST must be comma-separated signer=trust pairs");
+                let trust: Decimal = trust.trim().parse().expect("EXTERNAL_SIGNAL_TRUST trust weights must be decimals");
+                if trust < Decimal::ZERO || trust > Decimal::ONE {
+                    panic!("EXTERNAL_SIGNAL_TRUST trust weights must be between 0 and 1");
+                }
+                (signer.trim().parse().expect("Invalid signer address in EXTERNAL_SIGNAL_TRUST"), trust)
+            })
+            .collect();
+
+        // Load how often running components reload config_overrides from the database
+        let config_refresh_interval_secs = env::var("CONFIG_REFRESH_INTERVAL_SECS")
+            .map(|v| v.parse().expect("CONFIG_REFRESH_INTERVAL_SECS must be a positive integer"))
+            .unwrap_or(30);
+        if config_refresh_interval_secs == 0 {
+            panic!("CONFIG_REFRESH_INTERVAL_SECS must be a positive integer");
+        }
+
+        // Load GMX synthetics stats subgraph URL (The Graph or Subsquid deployment) used for historical backfills
+        let gmx_subgraph_url = env::var("GMX_SUBGRAPH_URL").unwrap_or_else(|_| constants::GMX_STATS_SUBGRAPH_ENDPOINT.to_string());
+
+        // Load GMX REST API base URL (token list, ticker prices, incentives and candles)
+        let gmx_api_url = env::var("GMX_API_URL").unwrap_or_else(|_| constants::GMX_API_URL.to_string());
+
+        // Load optional ParaSwap API URL override, API key, partner fee settings and client-side rate limit
+        let paraswap_api_url = env::var("PARASWAP_API_URL").ok();
+        let paraswap_api_key = env::var("PARASWAP_API_KEY").ok();
+        let paraswap_partner = env::var("PARASWAP_PARTNER").ok();
+        let paraswap_partner_address = env::var("PARASWAP_PARTNER_ADDRESS")
+            .ok()
+            .map(|v| v.parse().expect("Invalid PARASWAP_PARTNER_ADDRESS"));
+        let paraswap_partner_fee_bps = env::var("PARASWAP_PARTNER_FEE_BPS")
+            .map(|v| v.parse().expect("PARASWAP_PARTNER_FEE_BPS must be a non-negative integer"))
+            .unwrap_or(0);
+        if paraswap_partner_fee_bps > 0 && paraswap_partner_address.is_none() {
+            panic!("PARASWAP_PARTNER_ADDRESS is required when PARASWAP_PARTNER_FEE_BPS is set");
+        }
+        let paraswap_requests_per_sec = env::var("PARASWAP_REQUESTS_PER_SEC")
+            .map(|v| v.parse().expect("PARASWAP_REQUESTS_PER_SEC must be a positive integer"))
+            .unwrap_or(1);
+
+        // Mask API keys loaded from the environment in logs, e.g. in a debug dump of the config
+        // (values fetched through the secrets manager are registered as they are fetched)
+        for api_key in [Some(&etherscan_api_key), Some(&zerox_api_key), redis_password.as_ref(), paraswap_api_key.as_ref()].into_iter().flatten() {
+            logging::register_secret(api_key);
+        }
+
+        let config = Config {
+            alchemy_provider: Arc::new(provider),
+            alchemy_ws_url,
+            wallet_key,
+            wallet_mnemonic,
+            network_mode,
+            execution_mode,
+            account_id,
+            chain_id,
+            gmx_datastore: gmx_datastore.parse().expect("Invalid GMX DataStore address"),
+            gmx_reader: gmx_reader.parse().expect("Invalid GMX Reader address"),
+            gmx_eventemitter: gmx_eventemitter.parse().expect("Invalid GMX EventEmitter address"),
+            gmx_exchangerouter: gmx_exchangerouter.parse().expect("Invalid GMX ExchangeRouter address"),
+            gmx_baserouter: gmx_baserouter.parse().expect("Invalid GMX BaseRouter address"),
+            gmx_depositvault: gmx_depositvault.parse().expect("Invalid GMX DepositVault address"),
+            gmx_withdrawalvault: gmx_withdrawalvault.parse().expect("Invalid GMX WithdrawalVault address"),
+            gmx_shiftvault: gmx_shiftvault.parse().expect("Invalid GMX ShiftVault address"),
+            event_finality,
+            wnt_address: wnt_address.parse().expect("Invalid WNT address"),
+            gmx_rewards_distributor,
+            gmx_ui_fee_receiver,
+            gmx_callback_contract,
+            gmx_callback_gas_limit,
+            etherscan_api_key,
+            refetch_abis,
+            abi_offline,
+            database_url,
+            database_read_url,
+            secrets,
+            redis_url,
+            redis_username,
+            redis_password,
+            redis_db,
+            redis_tls_insecure,
+            redis_connect_max_retries,
+            price_stream_addr,
+            token_prices_stale_after_mins,
+            market_states_stale_after_mins,
+            zerox_api_key,
+            gm_order_timeout_secs,
+            plan_failure_policy,
+            plan_max_concurrent_actions,
+            plan_duplicate_window_secs,
+            approval_mode,
+            plan_approval_ttl_secs,
+            reporting_currency,
+            raw_data_retention_days,
+            max_price_deviation_pct,
+            max_oracle_deviation_pct,
+            max_market_data_age_secs,
+            max_funding_data_age_secs,
+            daily_fee_budget_usd,
+            monthly_fee_budget_usd,
+            wallet_watchdog_poll_secs,
+            wallet_watchdog_safe_mode,
+            archive_rpc_url,
+            gm_price_verify_interval_secs,
+            gm_price_verify_sample_size,
+            gm_price_verify_tolerance_pct,
+            permit_approvals,
+            sanctions_deny_list,
+            sanctions_oracle_screening,
+            gas_spike_multiplier,
+            gas_baseline_window_hours,
+            gas_max_deferral_secs,
+            gas_deferrable_actions,
+            gas_fee_buffer_autotune,
+            gas_fee_buffer_min,
+            gas_fee_buffer_max,
+            gas_fee_buffer_quantile,
+            gas_fee_buffer_lookback_days,
+            max_deposit_utilization,
+            gas_reserve_min_native,
+            gas_reserve_target_native,
+            gas_reserve_source_token,
+            allocator_mode,
+            market_universe,
+            rebalance_id,
+            allocator_kelly_fraction,
+            risk_horizon_hours,
+            implied_vol_weight,
+            min_trade_size_usd,
+            min_swap_size_usd,
+            dust_threshold_usd,
+            twap_min_notional_usd,
+            twap_slices,
+            twap_duration_mins,
+            twap_max_price_move_pct,
+            base_stablecoin,
+            risk_free_rate_apr,
+            aave_pool_address,
+            dydx_config_path,
+            dydx_sandbox,
+            hyperliquid_enabled,
+            hyperliquid_api_url,
+            hedge_min_volume_usd,
+            hedge_rebalance_band_pct,
+            hedge_rebalance_target_band_pct,
+            hedge_band_vol_multiple,
+            collateral_drift_threshold,
+            weight_smoothing_alpha,
+            weight_drift_band,
+            weight_drift_bands,
+            min_holding_hours,
+            min_holding_hours_by_market,
+            reentry_cooldown_hours,
+            reentry_cooldown_hours_by_market,
+            return_ensemble_combiner,
+            return_signal_weights,
+            signal_sink_addr,
+            external_signal_trust,
+            config_refresh_interval_secs,
+            gmx_subgraph_url,
+            gmx_api_url,
+            paraswap_api_url,
+            paraswap_api_key,
+            paraswap_partner,
+            paraswap_partner_address,
+            paraswap_partner_fee_bps,
+            paraswap_requests_per_sec,
+            oneinch_api_key,
+        };
+        
+        Arc::new(config)
+    }
+}
+
+pub fn init_crypto_provider() {
+    INIT_CRYPTO.call_once(|| {
+        rustls::crypto::aws_lc_rs::default_provider()
+            .install_default()
+            .expect("Failed to install AWS-LC-RS as default TLS provider");
+    });
+}
@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::time::{Duration, Instant};
+use eyre::Result;
+use serde::Deserialize;
+use tokio::sync::RwLock;
+use tracing::{debug, info, instrument};
+
+use crate::logging;
+
+/// Secret holding the database password, substituted into the database URLs when set
+pub const DATABASE_PASSWORD_SECRET: &str = "DATABASE_PASSWORD";
+
+/// Where secrets are fetched from, selected with SECRETS_BACKEND
+#[derive(Clone)]
+pub enum SecretsBackend {
+    Env,                                                                // Environment variables (.env)
+    Vault { address: String, token: String, mount: String, path: String }, // HashiCorp Vault KV v2 secret
+    AwsSecretsManager { secret_id: String },                            // AWS Secrets Manager secret holding a JSON object
+}
+
+impl SecretsBackend {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SecretsBackend::Env => "env",
+            SecretsBackend::Vault { .. } => "vault",
+            SecretsBackend::AwsSecretsManager { .. } => "aws",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct VaultKvResponse {
+    data: VaultKvData,
+}
+
+#[derive(Deserialize)]
+struct VaultKvData {
+    data: HashMap<String, String>,
+}
+
+struct CachedSecrets {
+    values: HashMap<String, String>,
+    fetched_at: Instant,
+}
+
+/// Fetches secrets from the configured backend. Vault and AWS secrets are a single key/value bundle keyed by
+/// the same names as the environment variables they replace, cached for the TTL and refetched once it expires
+/// so rotated values are picked up. Names missing from the bundle fall back to the environment.
+pub struct SecretsManager {
+    backend: SecretsBackend,
+    cache_ttl: Duration,
+    cache: RwLock<Option<CachedSecrets>>,
+}
+
+impl SecretsManager {
+    /// Build the secrets manager from SECRETS_BACKEND (env, vault or aws) and the selected backend's settings
+    pub fn from_env() -> Self {
+        let backend = match env::var("SECRETS_BACKEND").unwrap_or_else(|_| "env".to_string()).as_str() {
+            "env" => SecretsBackend::Env,
+            "vault" => SecretsBackend::Vault {
+                address: env::var("VAULT_ADDR").expect("Missing VAULT_ADDR"),
+                token: env::var("VAULT_TOKEN").expect("Missing VAULT_TOKEN"),
+                mount: env::var("VAULT_MOUNT").unwrap_or_else(|_| "secret".to_string()),
+                path: env::var("VAULT_SECRET_PATH").expect("Missing VAULT_SECRET_PATH"),
+            },
+            "aws" => SecretsBackend::AwsSecretsManager {
+                secret_id: env::var("AWS_SECRET_ID").expect("Missing AWS_SECRET_ID"),
+            },
+            _ => panic!("SECRETS_BACKEND must be one of 'env', 'vault' or 'aws'"),
+        };
+        let cache_ttl_secs: u64 = env::var("SECRETS_CACHE_TTL_SECS")
+            .map(|v| v.parse().expect("SECRETS_CACHE_TTL_SECS must be a positive integer"))
+            .unwrap_or(300);
+
+        Self {
+            backend,
+            cache_ttl: Duration::from_secs(cache_ttl_secs),
+            cache: RwLock::new(None),
+        }
+    }
+
+    pub fn backend(&self) -> &SecretsBackend {
+        &self.backend
+    }
+
+    pub fn cache_ttl(&self) -> Duration {
+        self.cache_ttl
+    }
+
+    /// Whether secret values can change while the process runs (anything but the environment)
+    pub fn supports_rotation(&self) -> bool {
+        !matches!(self.backend, SecretsBackend::Env)
+    }
+
+    /// Get a secret by name, or None if neither the backend nor the environment has it.
+    /// Values are registered with logging so they are masked wherever they would be logged.
+    pub async fn get(&self, name: &str) -> Result<Option<String>> {
+        let value = self.lookup(name).await?;
+        if let Some(value) = &value {
+            logging::register_secret(value);
+        }
+        Ok(value)
+    }
+
+    async fn lookup(&self, name: &str) -> Result<Option<String>> {
+        if let SecretsBackend::Env = self.backend {
+            return Ok(env::var(name).ok());
+        }
+
+        {
+            let cache = self.cache.read().await;
+            if let Some(cached) = cache.as_ref().filter(|cached| cached.fetched_at.elapsed() < self.cache_ttl) {
+                return Ok(cached.values.get(name).cloned().or_else(|| env::var(name).ok()));
+            }
+        }
+
+        let values = self.refresh().await?;
+        Ok(values.get(name).cloned().or_else(|| env::var(name).ok()))
+    }
+
+    /// Get a secret by name, failing if it is not set anywhere
+    pub async fn require(&self, name: &str) -> Result<String> {
+        self.get(name).await?
+            .ok_or_else(|| eyre::eyre!("Missing secret {} (secrets backend: {})", name, self.backend.as_str()))
+    }
+
+    /// Refetch the secret bundle from the backend, replacing the cached values
+    #[instrument(skip(self), fields(backend = self.backend.as_str()))]
+    pub async fn refresh(&self) -> Result<HashMap<String, String>> {
+        let values = match &self.backend {
+            SecretsBackend::Env => HashMap::new(),
+            SecretsBackend::Vault { address, token, mount, path } => fetch_vault_secret(address, token, mount, path).await?,
+            SecretsBackend::AwsSecretsManager { secret_id } => fetch_aws_secret(secret_id).await?,
+        };
+        debug!(secret_count = values.len(), "Fetched secrets from backend");
+
+        *self.cache.write().await = Some(CachedSecrets {
+            values: values.clone(),
+            fetched_at: Instant::now(),
+        });
+        Ok(values)
+    }
+}
+
+impl fmt::Debug for SecretsManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecretsManager")
+            .field("backend", &self.backend.as_str())
+            .field("cache_ttl", &self.cache_ttl)
+            .finish()
+    }
+}
+
+/// Replace the password in a database URL
+pub fn with_database_password(database_url: &str, password: &str) -> Result<String> {
+    let mut url = url::Url::parse(database_url)?;
+    url.set_password(Some(password))
+        .map_err(|_| eyre::eyre!("Database URL cannot carry a password"))?;
+    Ok(url.to_string())
+}
+
+/// Read a KV v2 secret from Vault
+async fn fetch_vault_secret(address: &str, token: &str, mount: &str, path: &str) -> Result<HashMap<String, String>> {
+    let url = format!("{}/v1/{}/data/{}", address.trim_end_matches('/'), mount, path);
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()?;
+    let response: VaultKvResponse = client
+        .get(&url)
+        .header("X-Vault-Token", token)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    info!(mount = mount, path = path, "Loaded secrets from Vault");
+    Ok(response.data.data)
+}
+
+/// Read a JSON key/value secret from AWS Secrets Manager, credentials and region come from the default AWS chain
+async fn fetch_aws_secret(secret_id: &str) -> Result<HashMap<String, String>> {
+    let aws_config = aws_config::defaults(aws_config::BehaviorVersion::latest()).load().await;
+    let client = aws_sdk_secretsmanager::Client::new(&aws_config);
+    let output = client.get_secret_value()
+        .secret_id(secret_id)
+        .send()
+        .await?;
+    let secret_string = output.secret_string()
+        .ok_or_else(|| eyre::eyre!("AWS secret {} has no string value", secret_id))?;
+    info!(secret_id = secret_id, "Loaded secrets from AWS Secrets Manager");
+    Ok(serde_json::from_str(secret_string)?)
+}
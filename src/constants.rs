@@ -11,6 +11,7 @@ pub const GMX_BASEROUTER_ADDRESS_MAINNET: &str = "0x7452c558d45f8afC8c83dAe62C3f
 pub const GMX_DEPOSITVAULT_ADDRESS_MAINNET: &str = "0xF89e77e8Dc11691C9e8757e84aaFbCD8A67d7A55";
 pub const GMX_WITHDRAWALVAULT_ADDRESS_MAINNET: &str = "0x0628D46b5D145f183AdB6Ef1f2c97eD1C4701C55";
 pub const GMX_SHIFTVAULT_ADDRESS_MAINNET: &str = "0xfe99609C4AA83ff6816b64563Bdffd7fa68753Ab";
+pub const GMX_ROLESTORE_ADDRESS_MAINNET: &str = "0x3c3d99FD298f679DBC2CEcd132b4eC4d0F5e6e72";
 
 pub const GMX_DATASTORE_ADDRESS_SEPOLIA: &str = "0xCF4c2C4c53157BcC01A596e3788fFF69cBBCD201";
 pub const GMX_READER_ADDRESS_SEPOLIA: &str = "0x4750376b9378294138Cf7B7D69a2d243f4940f71";
@@ -21,15 +22,47 @@ pub const GMX_DEPOSITVAULT_ADDRESS_SEPOLIA: &str = "0x809Ea82C394beB993c2b6B0d73
 pub const GMX_WITHDRAWALVAULT_ADDRESS_SEPOLIA: &str = "0x7601c9dBbDCf1f5ED1E7Adba4EFd9f2cADa037A5";
 pub const GMX_SHIFTVAULT_ADDRESS_SEPOLIA: &str = "0xfe99609C4AA83ff6816b64563Bdffd7fa68753Ab"; // No shift vault on Sepolia, using same address as mainnet for placeholder
 
-// GMX REST API
-pub const GMX_API_PRICES_ENDPOINT: &str = "https://arbitrum-api.gmxinfra.io/prices/tickers";
-pub const GMX_SUPPORTED_TOKENS_ENDPOINT: &str = "https://arbitrum-api.gmxinfra.io/tokens";
+// GMX REST API (default base URL, endpoint paths are appended to it)
+pub const GMX_API_URL: &str = "https://arbitrum-api.gmxinfra.io";
+pub const GMX_API_PRICES_PATH: &str = "/prices/tickers";
+pub const GMX_SUPPORTED_TOKENS_PATH: &str = "/tokens";
+pub const GMX_INCENTIVES_PATH: &str = "/incentives";
+pub const GMX_CANDLES_PATH: &str = "/prices/candles";
+
+// Deribit public API, DVOL implied volatility indices of the majors
+pub const DERIBIT_API_URL: &str = "https://www.deribit.com";
+pub const DERIBIT_VOLATILITY_INDEX_PATH: &str = "/api/v2/public/get_volatility_index_data";
+
+// GMX synthetics stats subgraph (hourly collected pool fees), default source for historical backfill
+pub const GMX_STATS_SUBGRAPH_ENDPOINT: &str = "https://subgraph.satsuma-prod.com/3b2ced13c8d9/gmx/synthetics-arbitrum-stats/api";
+
+// ARB token (default GM pool incentive reward token)
+pub const ARB_TOKEN_ADDRESS: &str = "0x912CE59144191C1204E64559FE8253a0e49E6548";
+
+// USDC (native) on Arbitrum, default base stablecoin for dust consolidation
+pub const USDC_ADDRESS: &str = "0xaf88d065e77c8cC2239327C5EDb3A432268e5831";
+
+// Aave v3 Pool on Arbitrum, USDC supply APR is the default risk-free (hurdle) rate
+pub const AAVE_V3_POOL_ADDRESS: &str = "0x794a61358D6845594F94dc1DB02A252b5b4814aD";
+
+// Uniswap Permit2 (same address on every chain), spender of standing approvals for signature-based transfers
+pub const PERMIT2_ADDRESS: &str = "0x000000000022D473030F116dDEE9F6B43aC78BA3";
+
+// Chainalysis sanctions oracle (same address on Arbitrum and Ethereum mainnet)
+pub const CHAINALYSIS_SANCTIONS_ORACLE_ADDRESS: &str = "0x40C57923924B5c5c5455c48D93317139ADDaC8fb";
 
 // GMX Decimals
 pub const GMX_DECIMALS: u8 = 30; // GMX prices are returned with 30 decimals
 
+// Event ingestion
+pub const DEFAULT_EVENT_CONFIRMATION_BLOCKS: u64 = 20; // Blocks an event log must be buried under before it's ingested (~5s on Arbitrum)
+
 // WNT (Wrapped Native Token) Address
 pub const WNT_ADDRESS: &str = "0x82aF49447D8a07e3bd95BD0d56f35241523fBab1"; // WETH on Arbitrum
+pub const WNT_ADDRESS_SEPOLIA: &str = "0x980B62Da83eFf3D4576C647993b0c1D7faf17c73"; // WETH on Arbitrum Sepolia
 
 // Native Token Address
 pub const NATIVE_ADDRESS: &str = "0xEeeeeEeeeEeEeeEeEeEeeEEEeeeeEeeeeeeeEEeE"; // ETH on Arbitrum
+
+// Portfolio Accounts
+pub const DEFAULT_ACCOUNT_ID: &str = "default"; // Account used when ACCOUNT_ID is unset, keeps single-portfolio setups unchanged
@@ -0,0 +1,111 @@
+use chrono::{DateTime, Utc};
+use ethers::types::Address;
+use eyre::Result;
+use reqwest::Client;
+use std::str::FromStr;
+use tracing::{info, warn, instrument};
+
+use crate::config::Config;
+use crate::db::db_manager::DbManager;
+use crate::db::models::market_states::NewBackfilledMarketStateModel;
+use crate::db::models::token_prices::NewTokenPriceModel;
+use crate::gmx::history;
+
+/// Source recorded on market states backfilled from the GMX stats subgraph
+pub const MARKET_STATE_BACKFILL_SOURCE: &str = "gmx_subgraph";
+/// Source recorded on token prices backfilled from GMX API candles
+pub const TOKEN_PRICE_BACKFILL_SOURCE: &str = "gmx_candles";
+
+const BACKFILL_REQUEST_TIMEOUT_SECS: u64 = 30;
+
+/// Backfill hourly pool fees for every tracked market from `since` up to the market's earliest stored state,
+/// so history that was already collected is never duplicated. Only fees are available historically; PnL, pool
+/// composition and GM price stay empty on backfilled states. Returns the number of states inserted.
+#[instrument(skip(config, db_manager), fields(on_close = true))]
+pub async fn backfill_market_states(config: &Config, db_manager: &DbManager, since: DateTime<Utc>) -> Result<u64> {
+    if config.network_mode != "prod" {
+        warn!("Historical market data is only available on mainnet, skipping market state backfill");
+        return Ok(0);
+    }
+    let client = backfill_client()?;
+    let now = db_manager.clock.now();
+    let earliest_states = db_manager.get_earliest_market_state_timestamps().await?;
+
+    let mut inserted = 0;
+    for (market, market_id) in &db_manager.market_id_map {
+        let end = earliest_states.get(market).copied().unwrap_or(now);
+        if end <= since {
+            continue;
+        }
+        let hourly_fees = match history::fetch_hourly_market_fees(&client, &config.gmx_subgraph_url, *market, since, end).await {
+            Ok(fees) => fees,
+            Err(e) => {
+                warn!(market = ?market, error = ?e, "Failed to fetch historical market fees, skipping market");
+                continue;
+            }
+        };
+        let states: Vec<NewBackfilledMarketStateModel> = hourly_fees.into_iter()
+            .map(|fees| NewBackfilledMarketStateModel {
+                market_id: *market_id,
+                timestamp: fees.timestamp,
+                fees_total: fees.fees_usd,
+            })
+            .collect();
+        let market_inserted = db_manager.insert_backfilled_market_states(&states, MARKET_STATE_BACKFILL_SOURCE).await?;
+        info!(market = ?market, inserted = market_inserted, until = %end, "Market states backfilled");
+        inserted += market_inserted;
+    }
+    Ok(inserted)
+}
+
+/// Backfill hourly prices for every token from `since` up to the token's earliest stored price, using the candle
+/// close as the min, max and mid price (historical oracle spreads aren't available). Returns the number of prices inserted.
+#[instrument(skip(config, db_manager), fields(on_close = true))]
+pub async fn backfill_token_prices(config: &Config, db_manager: &DbManager, since: DateTime<Utc>) -> Result<u64> {
+    if config.network_mode != "prod" {
+        warn!("Historical prices are only available on mainnet, skipping token price backfill");
+        return Ok(0);
+    }
+    let client = backfill_client()?;
+    let now = db_manager.clock.now();
+    let earliest_prices = db_manager.get_earliest_token_price_timestamps().await?;
+
+    let mut inserted = 0;
+    for token in db_manager.get_all_tokens().await? {
+        let Ok(address) = Address::from_str(&token.address) else {
+            continue;
+        };
+        let end = earliest_prices.get(&address).copied().unwrap_or(now);
+        if end <= since {
+            continue;
+        }
+        let candles = match history::fetch_hourly_price_candles(&client, &config.gmx_api_url, &token.symbol, since, now).await {
+            Ok(candles) => candles,
+            Err(e) => {
+                warn!(token = %token.symbol, error = ?e, "Failed to fetch historical prices, skipping token");
+                continue;
+            }
+        };
+        let prices: Vec<NewTokenPriceModel> = candles.into_iter()
+            .filter(|candle| candle.timestamp < end)
+            .map(|candle| NewTokenPriceModel {
+                token_id: token.id,
+                timestamp: candle.timestamp,
+                min_price: candle.close,
+                max_price: candle.close,
+                mid_price: candle.close,
+                traceparent: None,
+            })
+            .collect();
+        let token_inserted = db_manager.insert_backfilled_token_prices(&prices, TOKEN_PRICE_BACKFILL_SOURCE).await?;
+        info!(token = %token.symbol, inserted = token_inserted, until = %end, "Token prices backfilled");
+        inserted += token_inserted;
+    }
+    Ok(inserted)
+}
+
+fn backfill_client() -> Result<Client> {
+    Ok(Client::builder()
+        .timeout(std::time::Duration::from_secs(BACKFILL_REQUEST_TIMEOUT_SECS))
+        .build()?)
+}
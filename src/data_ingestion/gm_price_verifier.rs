@@ -0,0 +1,163 @@
+// Independent check on the GM prices recorded by the collector: a random sample of recorded rows is re-read on-chain
+// with Reader.getMarketTokenPrice at the block each row was collected at, from the token prices recorded with it.
+// The on-chain read doesn't go through our ingestion pipeline, so a deviation points at a silent ingestion bug.
+use ethers::prelude::*;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use std::time::Duration;
+use eyre::Result;
+use tracing::{info, debug, warn, error, instrument};
+
+use crate::config::Config;
+use crate::constants::GMX_DECIMALS;
+use crate::db::db_manager::DbManager;
+use crate::db::models::market_states::RecordedGmPriceModel;
+use crate::gmx::reader::{self, PnlFactorType};
+use crate::gmx::reader_utils::{MarketProps, MarketPrices, PriceProps};
+use super::market::market_utils::i256_to_decimal_scaled;
+
+/// How far back rows are sampled from
+const SAMPLE_WINDOW_DAYS: i64 = 7;
+
+pub struct GmPriceVerifier {
+    config: Arc<Config>,
+    db: Arc<DbManager>,
+    provider: Arc<Provider<Http>>, // Archive provider, the historical reads need state of past blocks
+}
+
+impl GmPriceVerifier {
+    #[instrument(skip(config, db))]
+    pub fn init(config: Arc<Config>, db: Arc<DbManager>) -> Result<Self> {
+        let provider = match &config.archive_rpc_url {
+            Some(url) => Arc::new(Provider::<Http>::try_from(url.as_str())?),
+            None => {
+                warn!("ARCHIVE_RPC_URL not set, verifying GM prices through the main RPC (old blocks need archive state)");
+                Arc::clone(&config.alchemy_provider)
+            }
+        };
+        info!(
+            sample_size = config.gm_price_verify_sample_size,
+            tolerance_pct = %config.gm_price_verify_tolerance_pct,
+            "Initializing GM price verifier"
+        );
+        Ok(Self { config, db, provider })
+    }
+
+    // Verify a sample forever (to be called in long-running background task)
+    #[instrument(skip(self))]
+    pub async fn run(&self) {
+        let mut ticker = tokio::time::interval(Duration::from_secs(self.config.gm_price_verify_interval_secs));
+        info!(interval_secs = self.config.gm_price_verify_interval_secs, "Starting GM price verifier");
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.verify_sample().await {
+                error!(?e, "GM price verification failed");
+            }
+        }
+    }
+
+    /// Re-read a random sample of recorded GM prices on-chain, returning how many deviate beyond the tolerance
+    #[instrument(skip(self))]
+    pub async fn verify_sample(&self) -> Result<usize> {
+        let since = self.db.clock.now() - chrono::Duration::days(SAMPLE_WINDOW_DAYS);
+        let sample = self.db.get_recorded_gm_price_sample(since, self.config.gm_price_verify_sample_size).await?;
+        let tolerance = self.config.gm_price_verify_tolerance_pct / Decimal::ONE_HUNDRED;
+
+        let mut verified = 0;
+        let mut discrepancies = 0;
+        for row in &sample {
+            let (on_chain_min, on_chain_max) = match self.read_on_chain_price(row).await {
+                Ok(prices) => prices,
+                Err(e) => {
+                    // A failed read says nothing about the recorded price, skip the row rather than count it
+                    warn!(error = ?e, market_state_id = row.market_state_id, "Failed to read GM price on-chain");
+                    continue;
+                }
+            };
+            verified += 1;
+
+            let deviation_min = relative_deviation(row.gm_price_min, on_chain_min);
+            let deviation_max = relative_deviation(row.gm_price_max, on_chain_max);
+            if deviation_min > tolerance || deviation_max > tolerance {
+                discrepancies += 1;
+                error!(
+                    market_state_id = row.market_state_id,
+                    market = %row.market_address,
+                    timestamp = %row.timestamp,
+                    recorded_min = %row.gm_price_min,
+                    on_chain_min = %on_chain_min,
+                    recorded_max = %row.gm_price_max,
+                    on_chain_max = %on_chain_max,
+                    deviation_pct = %(deviation_min.max(deviation_max) * Decimal::ONE_HUNDRED).round_dp(4),
+                    "Recorded GM price deviates from on-chain price"
+                );
+            } else {
+                debug!(market_state_id = row.market_state_id, market = %row.market_address, "Recorded GM price verified");
+            }
+        }
+
+        info!(sampled = sample.len(), verified = verified, discrepancies = discrepancies, "GM price verification finished");
+        Ok(discrepancies)
+    }
+
+    /// GM min and max price read on-chain at the block the row was collected at, from the row's recorded token prices
+    async fn read_on_chain_price(&self, row: &RecordedGmPriceModel) -> Result<(Decimal, Decimal)> {
+        let block = self.block_at(row.timestamp.timestamp() as u64).await?;
+        let market_props = MarketProps {
+            market_token: row.market_address.parse()?,
+            index_token: row.index_token_address.parse()?,
+            long_token: row.long_token_address.parse()?,
+            short_token: row.short_token_address.parse()?,
+        };
+        let market_prices = MarketPrices { // GMX stores prices as [usd_price / 10^(token_decimals)] * 10^GMX_DECIMALS
+            index_token_price: price_props(row.index_min_price, row.index_max_price, row.index_token_decimals)?,
+            long_token_price: price_props(row.long_min_price, row.long_max_price, row.long_token_decimals)?,
+            short_token_price: price_props(row.short_min_price, row.short_max_price, row.short_token_decimals)?,
+        };
+
+        // Collected prices use the deposit PnL factor, as in the collector
+        let (min, max) = tokio::try_join!(
+            reader::get_market_token_price_at_block(
+                &self.config, self.provider.clone(), market_props.clone(), market_prices.clone(), PnlFactorType::Deposit, false, block
+            ),
+            reader::get_market_token_price_at_block(
+                &self.config, self.provider.clone(), market_props, market_prices, PnlFactorType::Deposit, true, block
+            ),
+        )?;
+        Ok((i256_to_decimal_scaled(min), i256_to_decimal_scaled(max)))
+    }
+
+    /// Latest block mined at or before the given unix timestamp, by binary search over block timestamps
+    async fn block_at(&self, timestamp: u64) -> Result<u64> {
+        let mut low = 0;
+        let mut high = self.provider.get_block_number().await?.as_u64();
+        while low < high {
+            let mid = (low + high).div_ceil(2);
+            let block = self.provider.get_block(mid).await?
+                .ok_or_else(|| eyre::eyre!("Block {} not found", mid))?;
+            if block.timestamp.as_u64() <= timestamp {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        Ok(low)
+    }
+}
+
+/// Token USD prices as GMX price props
+fn price_props(min_price: Decimal, max_price: Decimal, decimals: i32) -> Result<PriceProps> {
+    let price_decimals = GMX_DECIMALS as u32 - decimals as u32;
+    let to_u256 = |price: Decimal| -> Result<U256> {
+        Ok(ethers::utils::parse_units(price.round_dp(price_decimals).to_string(), price_decimals)?.into())
+    };
+    Ok(PriceProps { min: to_u256(min_price)?, max: to_u256(max_price)? })
+}
+
+/// Deviation of a recorded value from the reference value, relative to the reference
+fn relative_deviation(recorded: Decimal, reference: Decimal) -> Decimal {
+    if reference.is_zero() {
+        return if recorded.is_zero() { Decimal::ZERO } else { Decimal::ONE };
+    }
+    ((recorded - reference) / reference).abs()
+}
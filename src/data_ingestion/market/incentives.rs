@@ -0,0 +1,47 @@
+use eyre::Result;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use tracing::{info, warn, instrument};
+
+use crate::config::Config;
+use crate::db::db_manager::DbManager;
+use crate::db::models::market_incentives::NewMarketIncentiveModel;
+use crate::gmx::incentives;
+
+/// Fetch current GM pool incentive emissions and store them valued at the latest reward token prices.
+/// Returns the number of markets with incentives recorded.
+#[instrument(skip(config, db_manager), fields(on_close = true))]
+pub async fn record_market_incentives(config: &Config, db_manager: &DbManager) -> Result<usize> {
+    let market_incentives = incentives::fetch_lp_incentives(config).await?;
+    if market_incentives.is_empty() {
+        return Ok(0);
+    }
+
+    let latest_prices: HashMap<i32, Decimal> = db_manager.get_latest_token_prices().await?
+        .into_iter()
+        .map(|p| (p.token_id, p.mid_price))
+        .collect();
+
+    let mut models = Vec::with_capacity(market_incentives.len());
+    for incentive in &market_incentives {
+        let Some(market_id) = db_manager.market_id_map.get(&incentive.market).copied() else {
+            continue; // Market not tracked
+        };
+        let Some(reward_price) = db_manager.token_id_map.get(&incentive.reward_token).and_then(|id| latest_prices.get(id)) else {
+            warn!(reward_token = ?incentive.reward_token, "No price recorded for incentive reward token, skipping");
+            continue;
+        };
+        let period_hours = Decimal::from(incentive.period_secs) / Decimal::from(3600);
+        models.push(NewMarketIncentiveModel {
+            market_id,
+            reward_token: format!("{:?}", incentive.reward_token),
+            reward_amount: incentive.reward_amount,
+            period_secs: incentive.period_secs as i32,
+            reward_usd_per_hour: incentive.reward_amount * reward_price / period_hours,
+        });
+    }
+
+    db_manager.insert_market_incentives(&models).await?;
+    info!(market_count = models.len(), "Market incentives recorded");
+    Ok(models.len())
+}
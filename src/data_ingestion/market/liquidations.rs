@@ -0,0 +1,95 @@
+use chrono::{DateTime, Utc};
+use ethers::providers::Middleware;
+use ethers::types::BlockNumber;
+use eyre::Result;
+use std::collections::HashMap;
+use tracing::{info, debug, instrument};
+
+use crate::config::Config;
+use crate::db::db_manager::DbManager;
+use crate::db::models::liquidations::NewLiquidationModel;
+use crate::gmx::event_fetcher::GmxEventFetcher;
+use super::market_utils::u256_to_decimal_scaled;
+
+/// Record GMX liquidations of tracked markets up to the latest final block (see EVENT_FINALITY). The scan resumes after the fetcher's last
+/// block, or on a fresh fetcher after the latest recorded liquidation, never reaching back before `since`.
+/// Returns the number of liquidations inserted.
+#[instrument(skip(config, db_manager, fetcher), fields(on_close = true))]
+pub async fn sync_liquidations(
+    config: &Config,
+    db_manager: &DbManager,
+    fetcher: &mut GmxEventFetcher,
+    since: DateTime<Utc>,
+) -> Result<usize> {
+    let latest_block = fetcher.safe_block().await?;
+    let from_block = match fetcher.get_last_block_fetched() {
+        Some(block) => block + 1,
+        None => {
+            let since_block = block_at_or_after(config, since, latest_block).await?;
+            let recorded_block = db_manager.get_latest_liquidation_block().await?.map(|block| block + 1);
+            recorded_block.map_or(since_block, |block| block.max(since_block))
+        }
+    };
+    if from_block > latest_block {
+        return Ok(0);
+    }
+
+    let liquidations = fetcher.fetch_liquidations(from_block, latest_block).await?;
+    let mut block_times: HashMap<u64, DateTime<Utc>> = HashMap::new();
+    let mut models = Vec::with_capacity(liquidations.len());
+    for liquidation in &liquidations {
+        let Some(market_id) = db_manager.market_id_map.get(&liquidation.market).copied() else {
+            continue; // Market not tracked
+        };
+        let timestamp = match block_times.get(&liquidation.block_number) {
+            Some(timestamp) => *timestamp,
+            None => {
+                let timestamp = block_time(config, liquidation.block_number).await?;
+                block_times.insert(liquidation.block_number, timestamp);
+                timestamp
+            }
+        };
+        models.push(NewLiquidationModel {
+            market_id,
+            block_number: liquidation.block_number as i64,
+            tx_hash: format!("{:?}", liquidation.tx_hash),
+            log_index: liquidation.log_index as i32,
+            timestamp,
+            is_long: liquidation.is_long,
+            size_usd: u256_to_decimal_scaled(liquidation.size_delta_usd),
+        });
+    }
+
+    let inserted = db_manager.insert_liquidations(&models).await?;
+    fetcher.set_last_block_fetched(latest_block);
+    info!(
+        from_block = from_block,
+        to_block = latest_block,
+        found = liquidations.len(),
+        inserted = inserted,
+        "Liquidations synced"
+    );
+    Ok(inserted as usize)
+}
+
+/// First block mined at or after the given time, by binary search over block timestamps
+async fn block_at_or_after(config: &Config, time: DateTime<Utc>, latest_block: u64) -> Result<u64> {
+    let (mut low, mut high) = (0u64, latest_block);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if block_time(config, mid).await? < time {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    debug!(time = %time, block = low, "Resolved block for time");
+    Ok(low)
+}
+
+async fn block_time(config: &Config, block_number: u64) -> Result<DateTime<Utc>> {
+    let block = config.alchemy_provider.get_block(BlockNumber::Number(block_number.into())).await?
+        .ok_or_else(|| eyre::eyre!("Block {} not found", block_number))?;
+    DateTime::<Utc>::from_timestamp(block.timestamp.as_u64() as i64, 0)
+        .ok_or_else(|| eyre::eyre!("Invalid timestamp for block {}", block_number))
+}
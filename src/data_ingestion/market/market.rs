@@ -10,6 +10,7 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use crate::config::Config;
+use crate::clock::Clock;
 use crate::gmx::{
     reader_utils::{MarketPrices, MarketProps, MarketInfo, MarketPoolValueInfoProps},
     reader,
@@ -18,6 +19,7 @@ use crate::gmx::{
     multicall::BatchMarketData,
 };
 use crate::data_ingestion::token::token::AssetToken;
+use super::market_descriptor::{MarketDescriptor, format_display_name};
 use super::market_utils::{
     self,
     i256_to_decimal_scaled, 
@@ -35,16 +37,19 @@ pub struct Market {
     pub short_token: Arc<RwLock<AssetToken>>,
     pub borrowing_factor_per_second: Option<market_utils::BorrowingFactorPerSecond>,
     pub has_supply: bool, // Indicates if the market has supply (won't for swap or deprecated markets), default is true
+    pub deprecated: bool, // Market is disabled or closed to deposits by GMX, should be wound down, default is false
     pub pnl: Option<market_utils::Pnl>,
     pub token_pool: Option<market_utils::TokenPool>,
     pub gm_token_price: Option<market_utils::GmTokenPrice>,
     pub open_interest: Option<market_utils::OpenInterest>,
+    pub caps: Option<market_utils::MarketCaps>, // Max pool amounts and max open interest read from the datastore
     pub current_utilization: Option<Decimal>,
     pub volume: market_utils::Volume,
     pub cumulative_fees: market_utils::CumulativeFees,
 
     // Timestamp of the last market data update
     pub updated_at: Option<SystemTime>,  
+    pub clock: Arc<dyn Clock>,
 }
 
 impl fmt::Display for Market {
@@ -52,17 +57,27 @@ impl fmt::Display for Market {
         let index = self.index_token.try_read().map(|t| t.symbol.clone()).unwrap_or("?".to_string());
         let long = self.long_token.try_read().map(|t| t.symbol.clone()).unwrap_or("?".to_string());
         let short = self.short_token.try_read().map(|t| t.symbol.clone()).unwrap_or("?".to_string());
-        write!(
-            f,
-            "{}/USD [{} - {}]",
-            index,
-            long,
-            short,
-        )
+        write!(f, "{}", format_display_name(&index, &long, &short))
     }
 }
 
 impl Market {
+    /// The market's tokens and canonical names
+    pub async fn descriptor(&self) -> MarketDescriptor {
+        let index_token = self.index_token.read().await;
+        let long_token = self.long_token.read().await;
+        let short_token = self.short_token.read().await;
+        MarketDescriptor {
+            market_token: self.market_token,
+            index_token: index_token.address,
+            long_token: long_token.address,
+            short_token: short_token.address,
+            index_symbol: index_token.symbol.clone(),
+            long_symbol: long_token.symbol.clone(),
+            short_symbol: short_token.symbol.clone(),
+        }
+    }
+
     // Construct a MarketProps struct from the latest price data
     pub async fn market_props(&self) -> MarketProps {
         MarketProps {
@@ -407,7 +422,7 @@ impl Market {
         self.process_volume_and_fees(&index_token, &long_token, &short_token, market_fees).await?;
 
         // Update the timestamp of the last update
-        self.updated_at = Some(SystemTime::now());
+        self.updated_at = Some(self.clock.system_time());
 
         Ok(())
     }
@@ -444,10 +459,14 @@ impl Market {
         }
         let trading_volume_usd = u256_to_decimal_scaled(market_fees.trading_volume);
         self.volume.trading += trading_volume_usd;
+        self.volume.taker_buy += u256_to_decimal_scaled(market_fees.taker_buy_volume);
+        self.volume.taker_sell += u256_to_decimal_scaled(market_fees.taker_sell_volume);
 
         debug!(
             swap_volume = %swap_volume_total,
             trading_volume = %trading_volume_usd,
+            taker_buy_volume = %self.volume.taker_buy,
+            taker_sell_volume = %self.volume.taker_sell,
             "Volume data updated"
         );
 
@@ -485,6 +504,20 @@ impl Market {
         Ok(())
     }
 
+    /// Set the market's pool and open interest caps from their raw datastore values
+    pub async fn set_caps(&mut self, caps: &datastore::MarketCaps) {
+        let long_decimals = self.long_token.read().await.decimals;
+        let short_decimals = self.short_token.read().await.decimals;
+        self.caps = Some(
+            market_utils::MarketCaps {
+                max_pool_amount_long: u256_to_decimal_scaled_decimals(caps.max_pool_amount_long, long_decimals),
+                max_pool_amount_short: u256_to_decimal_scaled_decimals(caps.max_pool_amount_short, short_decimals),
+                max_open_interest_long: u256_to_decimal_scaled(caps.max_open_interest_long),
+                max_open_interest_short: u256_to_decimal_scaled(caps.max_open_interest_short),
+            }
+        );
+    }
+
     /// Zero out tracked fields (for each data collection cycle).
     pub fn zero_out_tracked_fields(&mut self) {
         self.borrowing_factor_per_second = None;
@@ -492,6 +525,7 @@ impl Market {
         self.token_pool = None;
         self.gm_token_price = None;
         self.open_interest = None;
+        self.caps = None;
         self.current_utilization = None;
         self.volume = market_utils::Volume::new();
         self.cumulative_fees = market_utils::CumulativeFees::new();
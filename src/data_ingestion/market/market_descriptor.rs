@@ -0,0 +1,49 @@
+use ethers::types::Address;
+use std::fmt;
+
+/// Identity of a GMX market: its tokens and the canonical names derived from them. Built by the market registry from
+/// its tokens and by the database from the markets and tokens tables, so logs, reports and dashboards name a market
+/// identically wherever the name comes from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MarketDescriptor {
+    pub market_token: Address,
+    pub index_token: Address,
+    pub long_token: Address,
+    pub short_token: Address,
+    pub index_symbol: String,
+    pub long_symbol: String,
+    pub short_symbol: String,
+}
+
+impl MarketDescriptor {
+    /// Canonical display name, e.g. "ETH/USD [WETH - USDC]"
+    pub fn display_name(&self) -> String {
+        format_display_name(&self.index_symbol, &self.long_symbol, &self.short_symbol)
+    }
+
+    /// Index ticker the market tracks, e.g. "ETH/USD"
+    pub fn ticker(&self) -> String {
+        format!("{}/USD", self.index_symbol)
+    }
+
+    /// Long and short collateral token symbols
+    pub fn collateral_symbols(&self) -> (&str, &str) {
+        (&self.long_symbol, &self.short_symbol)
+    }
+
+    /// Whether both sides of the pool are the same token (e.g. BTC/USD [WBTC - WBTC])
+    pub fn is_single_token(&self) -> bool {
+        self.long_token == self.short_token
+    }
+}
+
+impl fmt::Display for MarketDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.display_name())
+    }
+}
+
+/// The one place market display names are formatted
+pub fn format_display_name(index_symbol: &str, long_symbol: &str, short_symbol: &str) -> String {
+    format!("{}/USD [{} - {}]", index_symbol, long_symbol, short_symbol)
+}
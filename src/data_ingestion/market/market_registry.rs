@@ -12,27 +12,37 @@ use std::time::SystemTime;
 use chrono::{DateTime, Utc};
 
 use crate::config::Config;
+use crate::clock::{Clock, system_clock};
 use crate::data_ingestion::token::{token::AssetToken, token_registry::AssetTokenRegistry};
 use crate::gmx::{
     reader_utils::MarketProps,
     reader,
     event_listener_utils::MarketFees,
     multicall::fetch_all_market_data_batch,
+    datastore::{get_market_status_flags_batch, get_market_caps_batch},
 };
 use super::market::Market;
+use super::market_descriptor::format_display_name;
 use super::market_utils;
 
 pub struct MarketRegistry {
     markets: HashMap<Address, Market>,
     network_mode: String, 
+    clock: Arc<dyn Clock>,
 }
 
 impl MarketRegistry {
-    #[instrument(skip(config), fields(network_mode = %config.network_mode))]
     pub fn new(config: &Config) -> Self {
+        Self::with_clock(config, system_clock())
+    }
+
+    /// Create a registry whose markets timestamp their updates with the given clock
+    #[instrument(skip(config, clock), fields(network_mode = %config.network_mode))]
+    pub fn with_clock(config: &Config, clock: Arc<dyn Clock>) -> Self {
         Self { 
             markets: HashMap::new(), 
-            network_mode: config.network_mode.clone() 
+            network_mode: config.network_mode.clone(),
+            clock,
         }
     }
 
@@ -64,14 +74,17 @@ impl MarketRegistry {
                 short_token: short,
                 borrowing_factor_per_second: None,
                 has_supply: true, // Default to true
+                deprecated: false,
                 pnl: None,
                 token_pool: None,
                 gm_token_price: None,
                 open_interest: None,
+                caps: None,
                 current_utilization: None,
                 volume:  market_utils::Volume::new(),
                 cumulative_fees:  market_utils::CumulativeFees::new(),
                 updated_at: None,
+                clock: Arc::clone(&self.clock),
             };
             self.markets.insert(props.market_token, market);
             debug!("Market inserted successfully");
@@ -124,9 +137,18 @@ impl MarketRegistry {
         asset_token_registry: &mut AssetTokenRegistry, 
     ) -> eyre::Result<(Vec<AssetToken>, Vec<Address>)> {
         debug!("Repopulating market registry");
-        let new_tokens = asset_token_registry.update_tracked_tokens().await?;
+        let mut new_tokens = asset_token_registry.update_tracked_tokens().await?;
         
         let market_props_list = self.fetch_markets_with_retry(config).await?;
+
+        // Fill in metadata on-chain for any market tokens the GMX API has not listed yet
+        let referenced_tokens: Vec<Address> = market_props_list.iter()
+            .flat_map(|props| [props.index_token, props.long_token, props.short_token])
+            .collect();
+        match asset_token_registry.enrich_missing_tokens(config, &referenced_tokens).await {
+            Ok(enriched_tokens) => new_tokens.extend(enriched_tokens),
+            Err(e) => warn!(error = ?e, "Failed to enrich missing tokens from on-chain metadata"),
+        }
         let mut new_market_addresses = Vec::new();
         
         for props in &market_props_list {
@@ -187,6 +209,12 @@ impl MarketRegistry {
         self.markets.values().filter(|m| m.has_supply)
     }
 
+    // Returns an iterator over all markets that GMX has halted or closed to deposits
+    #[instrument(skip(self))]
+    pub fn deprecated_markets(&self) -> impl Iterator<Item = &Market> {
+        self.markets.values().filter(|m| m.deprecated)
+    }
+
     // Returns an iterator over all markets that have been updated since a given timestamp
     #[instrument(skip(self, updated_at_threshold))]
     pub fn updated_markets(&self, updated_at_threshold: DateTime<Utc>) -> impl Iterator<Item = &Market> {
@@ -295,6 +323,65 @@ impl MarketRegistry {
         Ok(())
     }
 
+    /// Refresh halt/deprecation flags for all markets from the datastore.
+    /// Returns the addresses of markets that became deprecated since the last refresh.
+    #[instrument(skip(self, config), fields(on_close = true))]
+    pub async fn update_market_statuses(&mut self, config: &Config) -> Result<Vec<Address>> {
+        let mut market_props_list = Vec::with_capacity(self.markets.len());
+        for market in self.markets.values() {
+            market_props_list.push(market.market_props().await);
+        }
+        let status_flags = get_market_status_flags_batch(config, &market_props_list).await?;
+
+        let mut newly_deprecated = Vec::new();
+        for market in self.markets.values_mut() {
+            let Some(flags) = status_flags.get(&market.market_token) else {
+                continue;
+            };
+            let deprecated = flags.is_deprecated();
+            if deprecated && !market.deprecated {
+                warn!(
+                    market = %market,
+                    is_disabled = flags.is_disabled,
+                    deposits_disabled = flags.deposits_disabled,
+                    "Market halted or deprecated by GMX"
+                );
+                newly_deprecated.push(market.market_token);
+            } else if !deprecated && market.deprecated {
+                info!(market = %market, "Market re-enabled by GMX");
+            }
+            market.deprecated = deprecated;
+        }
+
+        info!(
+            market_count = status_flags.len(),
+            deprecated_count = self.deprecated_markets().count(),
+            newly_deprecated_count = newly_deprecated.len(),
+            "Market statuses updated"
+        );
+        Ok(newly_deprecated)
+    }
+
+    /// Refresh max pool amount and max open interest caps for all markets from the datastore,
+    /// recorded with the market states so the planner can size deposits within them
+    #[instrument(skip(self, config), fields(on_close = true))]
+    pub async fn update_market_caps(&mut self, config: &Config) -> Result<()> {
+        let mut market_props_list = Vec::with_capacity(self.markets.len());
+        for market in self.markets.values() {
+            market_props_list.push(market.market_props().await);
+        }
+        let caps = get_market_caps_batch(config, &market_props_list).await?;
+
+        for market in self.markets.values_mut() {
+            if let Some(market_caps) = caps.get(&market.market_token) {
+                market.set_caps(market_caps).await;
+            }
+        }
+
+        info!(market_count = caps.len(), "Market caps updated");
+        Ok(())
+    }
+
     #[instrument(skip(self), fields(on_close = true))]
     pub async fn save_markets_to_file(&self) -> eyre::Result<()> {
         debug!("Saving markets to file");
@@ -302,12 +389,7 @@ impl MarketRegistry {
             let index_token = market.index_token.read().await;
             let long_token = market.long_token.read().await;
             let short_token = market.short_token.read().await;
-            let description = format!(
-                "{}/USD [{} - {}]",
-                index_token.symbol,
-                long_token.symbol,
-                short_token.symbol
-            );
+            let description = format_display_name(&index_token.symbol, &long_token.symbol, &short_token.symbol);
             let market_address = to_checksum(&market.market_token, None);
             let token_json = |token: &AssetToken| json!({
                 "symbol": token.symbol,
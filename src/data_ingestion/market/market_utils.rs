@@ -78,10 +78,20 @@ pub struct OpenInterest {
     pub short_via_tokens: Decimal,
 }
 
+#[derive(Debug, Clone, Copy)]
+pub struct MarketCaps {
+    pub max_pool_amount_long: Decimal,  // In long tokens
+    pub max_pool_amount_short: Decimal, // In short tokens
+    pub max_open_interest_long: Decimal,  // In USD
+    pub max_open_interest_short: Decimal, // In USD
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Volume {
     pub trading: Decimal,
     pub swap: Decimal,
+    pub taker_buy: Decimal,  // Long increases and short decreases, in USD
+    pub taker_sell: Decimal, // Short increases and long decreases, in USD
 }
 
 impl Volume {
@@ -89,7 +99,18 @@ impl Volume {
         Self {
             trading: Decimal::ZERO,
             swap: Decimal::ZERO,
+            taker_buy: Decimal::ZERO,
+            taker_sell: Decimal::ZERO,
+        }
+    }
+
+    /// Net taker flow as a share of taker volume, from -1 (all sells) to 1 (all buys), None without position changes
+    pub fn taker_flow_imbalance(&self) -> Option<Decimal> {
+        let total = self.taker_buy + self.taker_sell;
+        if total.is_zero() {
+            return None;
         }
+        Some((self.taker_buy - self.taker_sell) / total)
     }
 }
 
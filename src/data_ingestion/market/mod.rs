@@ -1,3 +1,6 @@
 pub mod market;
 pub mod market_registry;
-pub mod market_utils;
\ No newline at end of file
+pub mod market_utils;
+pub mod incentives;
+pub mod liquidations;
+pub mod market_descriptor;
\ No newline at end of file
@@ -1,2 +1,4 @@
 pub mod token;
-pub mod market;
\ No newline at end of file
+pub mod market;
+pub mod backfill;
+pub mod gm_price_verifier;
\ No newline at end of file
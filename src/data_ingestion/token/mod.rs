@@ -1,3 +1,4 @@
 pub mod token;
 pub mod token_registry;
-pub mod oracle;
\ No newline at end of file
+pub mod oracle;
+pub mod price_validator;
\ No newline at end of file
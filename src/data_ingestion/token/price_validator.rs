@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use ethers::types::Address;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::*;
+use tracing::warn;
+
+use crate::config::Config;
+use super::token::AssetToken;
+
+/// Jumps from the previous observation rejected in a row before the new level becomes the reference,
+/// so a real move on a token without an oracle is only quarantined for a few ticks
+const MAX_CONSECUTIVE_ANOMALIES: u32 = 5;
+
+/// Reason a price observation was rejected, with the price it was compared against
+#[derive(Debug, Clone)]
+pub struct PriceAnomaly {
+    pub reason: String,
+    pub reference_price: Option<Decimal>,
+}
+
+/// Flags token prices that are non-positive, disagree with the token's Chainlink oracle, or (for tokens
+/// without an oracle price) jump too far from the previous accepted observation, so they are quarantined
+/// instead of stored
+#[derive(Debug)]
+pub struct PriceValidator {
+    max_price_deviation: Decimal,
+    max_oracle_deviation: Decimal,
+    last_accepted: HashMap<Address, Decimal>,
+    consecutive_anomalies: HashMap<Address, u32>,
+}
+
+impl PriceValidator {
+    pub fn new(config: &Config) -> Self {
+        Self::with_limits(config.max_price_deviation_pct, config.max_oracle_deviation_pct)
+    }
+
+    fn with_limits(max_price_deviation_pct: Decimal, max_oracle_deviation_pct: Decimal) -> Self {
+        Self {
+            max_price_deviation: max_price_deviation_pct / Decimal::from(100),
+            max_oracle_deviation: max_oracle_deviation_pct / Decimal::from(100),
+            last_accepted: HashMap::new(),
+            consecutive_anomalies: HashMap::new(),
+        }
+    }
+
+    /// Validate the token's latest mid price. Accepted prices become the reference for the next observation.
+    pub fn validate(&mut self, token: &AssetToken) -> Option<PriceAnomaly> {
+        let price = token.last_mid_price_usd?;
+        let anomaly = self.check(token, price);
+        match &anomaly {
+            Some(anomaly) => {
+                *self.consecutive_anomalies.entry(token.address).or_default() += 1;
+                warn!(
+                    symbol = %token.symbol,
+                    address = %token.address,
+                    price = %price,
+                    reference_price = ?anomaly.reference_price,
+                    reason = %anomaly.reason,
+                    "Price anomaly detected, quarantining observation"
+                );
+            }
+            None => {
+                self.consecutive_anomalies.remove(&token.address);
+                self.last_accepted.insert(token.address, price);
+            }
+        }
+        anomaly
+    }
+
+    fn check(&self, token: &AssetToken, price: Decimal) -> Option<PriceAnomaly> {
+        if price <= Decimal::ZERO {
+            return Some(PriceAnomaly {
+                reason: "non-positive price".to_string(),
+                reference_price: self.last_accepted.get(&token.address).copied(),
+            });
+        }
+
+        // An oracle that agrees confirms the price, however far it moved since the previous observation
+        let oracle_price = token.oracle.as_ref()
+            .and_then(|oracle| oracle.price)
+            .and_then(Decimal::from_f64)
+            .filter(|p| *p > Decimal::ZERO);
+        if let Some(oracle_price) = oracle_price {
+            let deviation = relative_deviation(price, oracle_price);
+            if deviation > self.max_oracle_deviation {
+                return Some(PriceAnomaly {
+                    reason: format!("{:.2}% deviation from oracle price", deviation * Decimal::from(100)),
+                    reference_price: Some(oracle_price),
+                });
+            }
+            return None;
+        }
+
+        // Without an oracle, a level that keeps deviating from the reference for long enough is taken as real
+        let consecutive_anomalies = self.consecutive_anomalies.get(&token.address).copied().unwrap_or(0);
+        if let Some(&previous) = self.last_accepted.get(&token.address) {
+            let deviation = relative_deviation(price, previous);
+            if deviation > self.max_price_deviation && consecutive_anomalies < MAX_CONSECUTIVE_ANOMALIES {
+                return Some(PriceAnomaly {
+                    reason: format!("{:.2}% move from previous observation", deviation * Decimal::from(100)),
+                    reference_price: Some(previous),
+                });
+            }
+        }
+
+        None
+    }
+}
+
+fn relative_deviation(price: Decimal, reference: Decimal) -> Decimal {
+    ((price - reference) / reference).abs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_ingestion::token::oracle::Oracle;
+
+    fn token(price: Decimal, oracle_price: Option<f64>) -> AssetToken {
+        let oracle = oracle_price.map(|oracle_price| {
+            let mut oracle = Oracle::new_single(Address::zero());
+            oracle.price = Some(oracle_price);
+            oracle
+        });
+        AssetToken {
+            symbol: "WETH".to_string(),
+            address: Address::repeat_byte(1),
+            mainnet_address: None,
+            decimals: 18,
+            is_synthetic: false,
+            oracle,
+            last_min_price: None,
+            last_max_price: None,
+            last_min_price_usd: Some(price),
+            last_max_price_usd: Some(price),
+            last_mid_price_usd: Some(price),
+            updated_at: None,
+        }
+    }
+
+    fn validator() -> PriceValidator {
+        PriceValidator::with_limits(Decimal::from(20), Decimal::from(2))
+    }
+
+    #[test]
+    fn oracle_confirmed_regime_shift_is_accepted() {
+        let mut validator = validator();
+        assert!(validator.validate(&token(Decimal::from(100), Some(100.0))).is_none());
+
+        // A 50% move the oracle agrees with is accepted and becomes the new reference
+        assert!(validator.validate(&token(Decimal::from(150), Some(150.5))).is_none());
+        assert!(validator.validate(&token(Decimal::from(151), None)).is_none());
+    }
+
+    #[test]
+    fn oracle_disagreement_is_quarantined() {
+        let mut validator = validator();
+        assert!(validator.validate(&token(Decimal::from(100), Some(100.0))).is_none());
+
+        let anomaly = validator.validate(&token(Decimal::from(105), Some(100.0))).expect("price off the oracle");
+        assert_eq!(anomaly.reference_price, Some(Decimal::from(100)));
+        assert!(validator.validate(&token(Decimal::from(150), Some(100.0))).is_some());
+    }
+
+    #[test]
+    fn unconfirmed_jump_becomes_reference_after_consecutive_anomalies() {
+        let mut validator = validator();
+        assert!(validator.validate(&token(Decimal::from(100), None)).is_none());
+
+        for _ in 0..MAX_CONSECUTIVE_ANOMALIES {
+            let anomaly = validator.validate(&token(Decimal::from(150), None)).expect("jump from the previous price");
+            assert_eq!(anomaly.reference_price, Some(Decimal::from(100)));
+        }
+        assert!(validator.validate(&token(Decimal::from(150), None)).is_none());
+        assert!(validator.validate(&token(Decimal::from(152), None)).is_none());
+    }
+
+    #[test]
+    fn accepted_price_resets_the_anomaly_count() {
+        let mut validator = validator();
+        assert!(validator.validate(&token(Decimal::from(100), None)).is_none());
+        assert!(validator.validate(&token(Decimal::from(150), None)).is_some());
+        assert!(validator.validate(&token(Decimal::from(101), None)).is_none());
+
+        for _ in 0..MAX_CONSECUTIVE_ANOMALIES {
+            assert!(validator.validate(&token(Decimal::from(150), None)).is_some());
+        }
+    }
+
+    #[test]
+    fn non_positive_price_is_always_quarantined() {
+        let mut validator = validator();
+        assert!(validator.validate(&token(Decimal::from(0), Some(100.0))).is_some());
+        assert!(validator.validate(&token(Decimal::from(-1), None)).is_some());
+    }
+}
@@ -1,12 +1,18 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::fs;
+use std::path::Path;
 use std::str::FromStr;
 use std::time::SystemTime;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, mpsc};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher, EventKind};
 use rust_decimal::Decimal;
+use ethers::providers::{Provider, Http};
 use ethers::types::{Address, U256};
 use ethers::utils;
+use ethers::abi::Token;
+use ethers::contract::{abigen, Multicall};
 use eyre::{Result, eyre};
 use serde_json::Value;
 use reqwest::Client;
@@ -15,21 +21,135 @@ use chrono::{DateTime, Utc};
 
 use super::token::AssetToken;
 use super::oracle::Oracle;
-use crate::constants::{GMX_API_PRICES_ENDPOINT, GMX_SUPPORTED_TOKENS_ENDPOINT, GMX_DECIMALS};
+use crate::constants::{GMX_API_PRICES_PATH, GMX_SUPPORTED_TOKENS_PATH, GMX_DECIMALS};
 use crate::config::Config;
+use crate::clock::{Clock, system_clock};
+
+abigen!(
+    ERC20Metadata,
+    r#"[
+        function symbol() external view returns (string)
+        function decimals() external view returns (uint8)
+    ]"#
+);
+
+/// A token whose configured decimals disagree with its contract's `decimals()`
+#[derive(Debug, Clone)]
+pub struct DecimalsMismatch {
+    pub address: Address,
+    pub symbol: String,
+    pub decimals: u8,          // Configured (data file, GMX API or database)
+    pub on_chain_decimals: u8, // Returned by the token contract
+}
+
+impl fmt::Display for DecimalsMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}): {} decimals configured, {} on-chain", self.symbol, utils::to_checksum(&self.address, None), self.decimals, self.on_chain_decimals)
+    }
+}
+
+/// Check tokens' configured (address, symbol, decimals) against their contracts' `decimals()` and `symbol()` via
+/// multicall, returning the decimals mismatches. Symbol mismatches are only logged, the data file often uses GMX's
+/// names (e.g. ETH for WETH). Tokens whose metadata can't be read are skipped.
+#[instrument(skip(provider, tokens), fields(token_count = tokens.len()))]
+pub async fn check_token_decimals(provider: Arc<Provider<Http>>, tokens: &[(Address, String, u8)]) -> Result<Vec<DecimalsMismatch>> {
+    if tokens.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut multicall = Multicall::new(provider.clone(), None).await?;
+    for (address, _, _) in tokens {
+        let erc20 = ERC20Metadata::new(*address, provider.clone());
+        multicall.add_call(erc20.symbol(), true);
+        multicall.add_call(erc20.decimals(), true);
+    }
+    let results = multicall.call_raw().await?;
+
+    let mut mismatches = Vec::new();
+    for (i, (address, symbol, decimals)) in tokens.iter().enumerate() {
+        let on_chain_decimals = match results.get(i * 2 + 1) {
+            Some(Ok(Token::Uint(on_chain_decimals))) if *on_chain_decimals <= U256::from(u8::MAX) => on_chain_decimals.as_u32() as u8,
+            _ => {
+                debug!(address = %address, symbol = %symbol, "Token decimals not readable on-chain, not checked");
+                continue;
+            }
+        };
+        if let Some(Ok(Token::String(on_chain_symbol))) = results.get(i * 2) {
+            if !on_chain_symbol.eq_ignore_ascii_case(symbol) {
+                debug!(address = %address, symbol = %symbol, on_chain_symbol = %on_chain_symbol, "Token symbol differs from on-chain symbol");
+            }
+        }
+        if on_chain_decimals != *decimals {
+            mismatches.push(DecimalsMismatch {
+                address: *address,
+                symbol: symbol.clone(),
+                decimals: *decimals,
+                on_chain_decimals,
+            });
+        }
+    }
+    debug!(checked = tokens.len(), mismatches = mismatches.len(), "Token decimals checked on-chain");
+    Ok(mismatches)
+}
+
+/// Tokens with a contract to check decimals against (synthetic index tokens have none)
+fn decimals_to_check<'a>(tokens: impl IntoIterator<Item = &'a AssetToken>) -> Vec<(Address, String, u8)> {
+    tokens.into_iter()
+        .filter(|token| !token.is_synthetic)
+        .map(|token| (token.address, token.symbol.clone(), token.decimals))
+        .collect()
+}
 
 #[derive(Debug)]
 pub struct AssetTokenRegistry {
     asset_tokens: HashMap<Address, Arc<RwLock<AssetToken>>>,
+    provider: Arc<Provider<Http>>,
     network_mode: String, // "prod" or "test"
+    gmx_api_url: String,
+    clock: Arc<dyn Clock>,
+    file_watch: Option<TokenFileWatch>,
+}
+
+/// Watcher on the asset token data file, signalling each change until the registry reloads the file
+struct TokenFileWatch {
+    _watcher: RecommendedWatcher, // Stops watching when dropped
+    changes: mpsc::UnboundedReceiver<()>,
+}
+
+impl fmt::Debug for TokenFileWatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenFileWatch").finish_non_exhaustive()
+    }
+}
+
+/// Changes applied to the registry by reloading the asset token data file
+#[derive(Debug, Clone, Default)]
+pub struct TokenFileDiff {
+    pub added: Vec<AssetToken>,
+    pub oracle_updated: Vec<(String, Vec<Address>, Vec<Address>)>, // Symbol, previous feeds and new feeds
+    pub not_in_file: Vec<String>, // Symbols of registry tokens missing from the file, kept as markets may reference them
+}
+
+impl TokenFileDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.oracle_updated.is_empty()
+    }
 }
 
 impl AssetTokenRegistry {
-    #[instrument(skip(config), fields(network_mode = %config.network_mode))]
     pub fn new(config: &Config) -> Self {
+        Self::with_clock(config, system_clock())
+    }
+
+    /// Create a registry whose token prices are timestamped with the given clock
+    #[instrument(skip(config, clock), fields(network_mode = %config.network_mode))]
+    pub fn with_clock(config: &Config, clock: Arc<dyn Clock>) -> Self {
         Self {
             asset_tokens: HashMap::new(),
+            provider: config.alchemy_provider.clone(),
             network_mode: config.network_mode.clone(),
+            gmx_api_url: config.gmx_api_url.clone(),
+            clock,
+            file_watch: None,
         }
     }
 
@@ -78,32 +198,183 @@ impl AssetTokenRegistry {
         updated_tokens.into_iter()
     }
 
+    /// Path of the asset token data file for the network mode
+    fn token_file_path(&self) -> &'static str {
+        match self.network_mode.as_str() {
+            "test" => "data/testnet_asset_token_data.json",
+            "prod" => "data/asset_token_data.json",
+            _ => panic!("Invalid NETWORK_MODE"),
+        }
+    }
+
     #[instrument(skip(self), fields(on_close = true))]
     pub fn load_from_file(&mut self) -> Result<()> {
-        let path = match self.network_mode.as_str() {
-            "test" => "data/testnet_asset_token_data.json".to_string(),
-            "prod" => "data/asset_token_data.json".to_string(),
-            _ => panic!("Invalid NETWORK_MODE"),
+        let tokens = self.read_token_file()?;
+        let loaded_count = tokens.len();
+        for asset_token in tokens {
+            debug!(
+                symbol = %asset_token.symbol,
+                address = %asset_token.address,
+                decimals = asset_token.decimals,
+                is_synthetic = asset_token.is_synthetic,
+                "Loaded token"
+            );
+            self.asset_tokens.insert(asset_token.address, Arc::new(RwLock::new(asset_token)));
+        }
+        info!(loaded_count = loaded_count, "Asset tokens loaded from file");
+        Ok(())
+    }
+
+    /// Check the loaded tokens' decimals against the chain. Wrong decimals in the data file mis-scale every USD figure
+    /// of the token, so any mismatch is an error to fix in the file before starting.
+    #[instrument(skip(self), fields(on_close = true))]
+    pub async fn verify_decimals(&self) -> Result<()> {
+        let mut tokens = Vec::with_capacity(self.asset_tokens.len());
+        for token in self.asset_tokens.values() {
+            tokens.push(token.read().await.clone());
+        }
+        let mismatches = check_token_decimals(self.provider.clone(), &decimals_to_check(&tokens)).await?;
+        if !mismatches.is_empty() {
+            let details = mismatches.iter().map(|mismatch| mismatch.to_string()).collect::<Vec<_>>().join("; ");
+            return Err(eyre!("Asset token decimals don't match the chain, fix {}: {}", self.token_file_path(), details));
+        }
+        info!(checked = tokens.len(), "Asset token decimals match the chain");
+        Ok(())
+    }
+
+    /// Addresses of tokens new to the registry whose decimals don't match the chain, kept out of the registry until
+    /// their source is fixed. When the check itself fails the tokens are let through unverified.
+    async fn quarantined_tokens(&self, tokens: &[AssetToken]) -> HashSet<Address> {
+        match check_token_decimals(self.provider.clone(), &decimals_to_check(tokens)).await {
+            Ok(mismatches) => mismatches.into_iter()
+                .map(|mismatch| {
+                    error!(token = %mismatch, "Token decimals don't match the chain, token quarantined");
+                    mismatch.address
+                })
+                .collect(),
+            Err(e) => {
+                warn!(error = ?e, "Failed to check token decimals on-chain, tokens added unverified");
+                HashSet::new()
+            }
+        }
+    }
+
+    /// Watch the asset token data file, so edits (e.g. a new oracle address) are picked up by `reload_if_changed`
+    /// without a restart. The directory is watched rather than the file, editors often replace the file on save.
+    #[instrument(skip(self))]
+    pub fn watch_file(&mut self) -> Result<()> {
+        let path = Path::new(self.token_file_path());
+        let file_name = path.file_name().map(|name| name.to_os_string());
+        let (changes_tx, changes) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| match event {
+            Ok(event) if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+                && event.paths.iter().any(|changed| changed.file_name().map(|name| name.to_os_string()) == file_name) => {
+                let _ = changes_tx.send(());
+            }
+            Ok(_) => {}
+            Err(e) => warn!(error = ?e, "Asset token data file watch error"),
+        })?;
+        watcher.watch(path.parent().unwrap_or(Path::new(".")), RecursiveMode::NonRecursive)?;
+        self.file_watch = Some(TokenFileWatch { _watcher: watcher, changes });
+        info!(file = %path.display(), "Watching asset token data file for changes");
+        Ok(())
+    }
+
+    /// Reload the asset token data file if it changed since the last check. Returns the applied diff, None when the
+    /// file is unchanged or not watched.
+    pub async fn reload_if_changed(&mut self) -> Result<Option<TokenFileDiff>> {
+        let Some(file_watch) = &mut self.file_watch else {
+            return Ok(None);
         };
+        // A single save often fires several events, one reload covers them all
+        let mut changed = false;
+        while file_watch.changes.try_recv().is_ok() {
+            changed = true;
+        }
+        if !changed {
+            return Ok(None);
+        }
+        self.reload_from_file().await.map(Some)
+    }
+
+    /// Re-read the asset token data file and apply it in place: tokens new to the registry are added (unless their
+    /// decimals don't match the chain) and tokens whose oracle feeds changed get the new feeds (their oracle price is
+    /// fetched again on the next update). Tokens missing from the file are kept, markets may still reference them.
+    #[instrument(skip(self), fields(on_close = true))]
+    pub async fn reload_from_file(&mut self) -> Result<TokenFileDiff> {
+        let tokens = self.read_token_file()?;
+        let mut diff = TokenFileDiff::default();
+        let in_file: Vec<Address> = tokens.iter().map(|token| token.address).collect();
+        let new_tokens: Vec<AssetToken> = tokens.iter()
+            .filter(|token| !self.asset_tokens.contains_key(&token.address))
+            .cloned()
+            .collect();
+        let quarantined = self.quarantined_tokens(&new_tokens).await;
+
+        for asset_token in tokens {
+            let Some(existing) = self.asset_tokens.get(&asset_token.address) else {
+                if quarantined.contains(&asset_token.address) {
+                    continue;
+                }
+                info!(symbol = %asset_token.symbol, address = %asset_token.address, "Token added from data file");
+                self.asset_tokens.insert(asset_token.address, Arc::new(RwLock::new(asset_token.clone())));
+                diff.added.push(asset_token);
+                continue;
+            };
+            let mut existing = existing.write().await;
+            let previous_feeds = oracle_feeds(&existing.oracle);
+            let new_feeds = oracle_feeds(&asset_token.oracle);
+            if previous_feeds != new_feeds {
+                info!(
+                    symbol = %existing.symbol,
+                    previous_feeds = ?previous_feeds,
+                    new_feeds = ?new_feeds,
+                    "Token oracle updated from data file"
+                );
+                existing.oracle = asset_token.oracle;
+                diff.oracle_updated.push((existing.symbol.clone(), previous_feeds, new_feeds));
+            }
+        }
+
+        for (address, token) in &self.asset_tokens {
+            if !in_file.contains(address) {
+                diff.not_in_file.push(token.read().await.symbol.clone());
+            }
+        }
+        if !diff.not_in_file.is_empty() {
+            debug!(tokens = ?diff.not_in_file, "Registry tokens missing from data file kept");
+        }
+        info!(
+            added = diff.added.len(),
+            oracle_updated = diff.oracle_updated.len(),
+            not_in_file = diff.not_in_file.len(),
+            "Asset token data file reloaded"
+        );
+        Ok(diff)
+    }
+
+    /// Parse every token of the asset token data file for the network mode
+    fn read_token_file(&self) -> Result<Vec<AssetToken>> {
+        let path = self.token_file_path();
         info!(file = %path, "Loading asset tokens from file");
-        let file_content = fs::read_to_string(&path)?;
+        let file_content = fs::read_to_string(path)?;
         let json_data: Value = serde_json::from_str(&file_content)?;
         let tokens = json_data.get("tokens").ok_or_else(|| eyre!("Tokens not found in JSON data"))?;
 
-        let mut loaded_count = 0;
+        let mut asset_tokens = Vec::new();
         for token in tokens.as_array().unwrap_or(&vec![]) {
-            let symbol = token["symbol"].as_str().expect("Token must have a symbol").to_string();
-            let decimals = token["decimals"].as_u64().expect("Token must have decimals") as u8;
+            let symbol = token["symbol"].as_str().ok_or_else(|| eyre!("Token must have a symbol"))?.to_string();
+            let decimals = token["decimals"].as_u64().ok_or_else(|| eyre!("Token {} must have decimals", symbol))? as u8;
             let is_synthetic = token.get("synthetic").map_or(false, |v| v.as_bool().unwrap_or(false));
             
             // Handle both mainnet and testnet addresses
             let address = if self.network_mode == "prod" {
-                Address::from_str(token["address"].as_str().expect("Token must have an address"))?
+                Address::from_str(token["address"].as_str().ok_or_else(|| eyre!("Token {} must have an address", symbol))?)?
             } else {
-                Address::from_str(token["testnetAddress"].as_str().expect("Token must have a testnet address"))?
+                Address::from_str(token["testnetAddress"].as_str().ok_or_else(|| eyre!("Token {} must have a testnet address", symbol))?)?
             };
             let mainnet_address = if self.network_mode == "test" {
-                Some(Address::from_str(token["mainnetAddress"].as_str().expect("Token must have a mainnet address"))?)
+                Some(Address::from_str(token["mainnetAddress"].as_str().ok_or_else(|| eyre!("Token {} must have a mainnet address", symbol))?)?)
             } else {
                 None
             };
@@ -122,8 +393,8 @@ impl AssetTokenRegistry {
                 None
             };
 
-            let asset_token = AssetToken {
-                symbol: symbol.clone(),
+            asset_tokens.push(AssetToken {
+                symbol,
                 address,
                 mainnet_address,
                 decimals,
@@ -135,19 +406,9 @@ impl AssetTokenRegistry {
                 last_max_price_usd: None,
                 last_mid_price_usd: None,
                 updated_at: None,
-            };
-            self.asset_tokens.insert(address, Arc::new(RwLock::new(asset_token)));
-            loaded_count += 1;
-            debug!(
-                symbol = %symbol,
-                address = %address,
-                decimals = decimals,
-                is_synthetic = is_synthetic,
-                "Loaded token"
-            );
+            });
         }
-        info!(loaded_count = loaded_count, "Asset tokens loaded from file");
-        Ok(())
+        Ok(asset_tokens)
     }
 
     #[instrument(skip(self), fields(on_close = true))]
@@ -164,11 +425,11 @@ impl AssetTokenRegistry {
             eyre!("Error parsing the 'tokens' field from API response")
         )?;
 
-        let mut new_tokens = Vec::new();
+        let mut new_tokens: Vec<AssetToken> = Vec::new();
         for token in tokens_arr {
             let address = Address::from_str(token["address"].as_str().expect("Token must have an address"))?;
-            // If the token isn't already in the registry, add it and add it to new tokens
-            if !self.asset_tokens.contains_key(&address) {
+            // If the token isn't already in the registry, add it to new tokens
+            if !self.asset_tokens.contains_key(&address) && !new_tokens.iter().any(|t| t.address == address) {
                 let symbol = token["symbol"].as_str().expect("Token must have a symbol").to_string();
                 let decimals = token["decimals"].as_u64().expect("Token must have decimals") as u8;
                 let is_synthetic = token.get("synthetic").map_or(false, |v| v.as_bool().unwrap_or(false));
@@ -187,30 +448,125 @@ impl AssetTokenRegistry {
                     last_mid_price_usd: None,
                     updated_at: None,
                 };
-                self.asset_tokens.insert(address, Arc::new(RwLock::new(new_token.clone())));
                 new_tokens.push(new_token);
-                debug!(
-                    symbol = %symbol,
-                    address = %address,
-                    "Added new tracked token"
-                );
             }
         }
 
+        // Keep tokens whose API decimals don't match the chain out of the registry
+        let quarantined = self.quarantined_tokens(&new_tokens).await;
+        new_tokens.retain(|token| !quarantined.contains(&token.address));
+
         // If no new tokens were found, return early
         if new_tokens.is_empty() {
             debug!("No new supported tokens found");
             return Ok(Vec::new());
         }
 
+        for new_token in &new_tokens {
+            self.asset_tokens.insert(new_token.address, Arc::new(RwLock::new(new_token.clone())));
+            debug!(
+                symbol = %new_token.symbol,
+                address = %new_token.address,
+                "Added new tracked token"
+            );
+        }
+
         // Write the new tokens to json file
+        self.append_tokens_to_file(&new_tokens)?;
+        info!(
+            new_token_count = new_tokens.len(),
+            "Added new tokens to registry and updated data file"
+        );
+        Ok(new_tokens)
+    }                  
+
+    /// On-chain fallback for tokens referenced by market props but missing from the registry (the GMX API and
+    /// data file can lag new listings): reads `symbol()`/`decimals()` via multicall, adds the tokens to the registry
+    /// and data file, and returns them so they are persisted to the DB with the other new tokens.
+    /// Addresses without ERC20 metadata (e.g. synthetic index tokens) are skipped.
+    #[instrument(skip(self, config, token_addresses), fields(on_close = true))]
+    pub async fn enrich_missing_tokens(&mut self, config: &Config, token_addresses: &[Address]) -> Result<Vec<AssetToken>> {
+        // Testnet tokens need a mainnet address for pricing, which cannot be discovered on-chain
+        if self.network_mode == "test" {
+            debug!("Skipping on-chain token enrichment in test mode");
+            return Ok(Vec::new());
+        }
+
+        let mut missing: Vec<Address> = token_addresses.iter()
+            .filter(|a| !a.is_zero() && !self.asset_tokens.contains_key(a))
+            .cloned()
+            .collect();
+        missing.sort();
+        missing.dedup();
+        if missing.is_empty() {
+            return Ok(Vec::new());
+        }
+        debug!(missing_count = missing.len(), "Fetching on-chain metadata for tokens missing from registry");
+
+        let mut multicall = Multicall::new(config.alchemy_provider.clone(), None).await?;
+        for address in &missing {
+            let erc20 = ERC20Metadata::new(*address, config.alchemy_provider.clone());
+            multicall.add_call(erc20.symbol(), true);
+            multicall.add_call(erc20.decimals(), true);
+        }
+        let results = multicall.call_raw().await?;
+
+        let mut new_tokens = Vec::new();
+        for (i, address) in missing.iter().enumerate() {
+            let symbol = match results.get(i * 2) {
+                Some(Ok(Token::String(symbol))) if !symbol.is_empty() => symbol.clone(),
+                _ => {
+                    warn!(address = %address, "Failed to read token symbol on-chain, skipping");
+                    continue;
+                }
+            };
+            let decimals = match results.get(i * 2 + 1) {
+                Some(Ok(Token::Uint(decimals))) if *decimals <= U256::from(GMX_DECIMALS) => decimals.as_u32() as u8,
+                _ => {
+                    warn!(address = %address, symbol = %symbol, "Failed to read token decimals on-chain, skipping");
+                    continue;
+                }
+            };
+
+            let new_token = AssetToken {
+                symbol: symbol.clone(),
+                address: *address,
+                mainnet_address: None,
+                decimals,
+                is_synthetic: false, // Synthetic tokens have no contract to read metadata from
+                oracle: None,
+                last_min_price: None,
+                last_max_price: None,
+                last_min_price_usd: None,
+                last_max_price_usd: None,
+                last_mid_price_usd: None,
+                updated_at: None,
+            };
+            self.asset_tokens.insert(*address, Arc::new(RwLock::new(new_token.clone())));
+            new_tokens.push(new_token);
+            info!(
+                symbol = %symbol,
+                address = %address,
+                decimals = decimals,
+                "Added token from on-chain metadata"
+            );
+        }
+
+        if !new_tokens.is_empty() {
+            self.append_tokens_to_file(&new_tokens)?;
+        }
+        Ok(new_tokens)
+    }
+
+    /// Append new tokens to the mainnet asset token data file
+    fn append_tokens_to_file(&self, new_tokens: &[AssetToken]) -> Result<()> {
         let path = "data/asset_token_data.json".to_string();
         let existing_file_content = fs::read_to_string(&path)?;
         let mut existing_json_data: Value = serde_json::from_str(&existing_file_content)?;
         let tokens_arr: &mut Vec<Value> = existing_json_data["tokens"].as_array_mut().ok_or_else(|| 
             eyre!("Error parsing the 'tokens' field from existing JSON data")
         )?;
-        for token in &new_tokens {
+        for token in new_tokens {
             let new_token_json = serde_json::json!({
                 "symbol": token.symbol,
                 "address": utils::to_checksum(&token.address, None),
@@ -220,12 +576,8 @@ impl AssetTokenRegistry {
             tokens_arr.push(new_token_json);
         }
         fs::write(&path, serde_json::to_string_pretty(&existing_json_data)?)?;
-        info!(
-            new_token_count = new_tokens.len(),
-            "Added new tokens to registry and updated data file"
-        );
-        Ok(new_tokens)
-    }                  
+        Ok(())
+    }
 
     #[instrument(skip(self), fields(on_close = true))]
     pub async fn update_all_gmx_prices(&mut self) -> Result<()> {
@@ -256,7 +608,7 @@ impl AssetTokenRegistry {
                             token.last_min_price_usd = Some(min_price_usd);
                             token.last_max_price_usd = Some(max_price_usd);
                             token.last_mid_price_usd = Some((min_price_usd + max_price_usd) / Decimal::from(2));
-                            token.updated_at = Some(SystemTime::now());
+                            token.updated_at = Some(self.clock.system_time());
                             updated_count += 1;
                             debug!(
                                 symbol = %token.symbol,
@@ -288,7 +640,7 @@ impl AssetTokenRegistry {
                                 token_guard.last_min_price_usd = Some(min_price_usd);
                                 token_guard.last_max_price_usd = Some(max_price_usd);
                                 token_guard.last_mid_price_usd = Some((min_price_usd + max_price_usd) / Decimal::from(2));
-                                token_guard.updated_at = Some(SystemTime::now());
+                                token_guard.updated_at = Some(self.clock.system_time());
                                 updated_count += 1;
                                 debug!(
                                     symbol = %token_guard.symbol,
@@ -403,7 +755,7 @@ impl AssetTokenRegistry {
             .timeout(std::time::Duration::from_secs(10))
             .build()?;
         let res = client
-            .get(GMX_SUPPORTED_TOKENS_ENDPOINT)
+            .get(format!("{}{}", self.gmx_api_url, GMX_SUPPORTED_TOKENS_PATH))
             .send()
             .await?
             .error_for_status()?;
@@ -448,10 +800,15 @@ impl AssetTokenRegistry {
             .timeout(std::time::Duration::from_secs(10))
             .build()?;
         let res = client
-            .get(GMX_API_PRICES_ENDPOINT)
+            .get(format!("{}{}", self.gmx_api_url, GMX_API_PRICES_PATH))
             .send()
             .await?
             .error_for_status()?;
         res.json().await.map_err(Into::into)
     }
+}
+
+/// Aggregator addresses of a token's oracle, in feed order
+fn oracle_feeds(oracle: &Option<Oracle>) -> Vec<Address> {
+    oracle.as_ref().map_or_else(Vec::new, |oracle| oracle.feeds.iter().map(|feed| feed.aggregator).collect())
 }
\ No newline at end of file
@@ -1,14 +1,76 @@
+use sqlx::Executor;
 use sqlx::postgres::{
+    PgConnectOptions,
     PgPool,
     PgPoolOptions,
 };
 use eyre::Result;
+use tracing::{info, warn};
 
 use crate::config::Config;
+use crate::config::secrets::{self, DATABASE_PASSWORD_SECRET};
 
 pub async fn create_pool(config: &Config) -> Result<PgPool, sqlx::Error> {
     PgPoolOptions::new()
         .max_connections(5) 
         .connect(&config.database_url)
         .await
+}
+
+/// Create a read-only pool against the configured read replica, if any
+pub async fn create_read_pool(config: &Config) -> Result<Option<PgPool>, sqlx::Error> {
+    let Some(database_read_url) = &config.database_read_url else {
+        return Ok(None);
+    };
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .after_connect(|conn, _meta| Box::pin(async move {
+            conn.execute("SET SESSION CHARACTERISTICS AS TRANSACTION READ ONLY").await?;
+            Ok(())
+        }))
+        .connect(database_read_url)
+        .await?;
+    Ok(Some(pool))
+}
+
+/// Keep the pools' connect options in step with a rotating database password: the secret is refetched every
+/// cache TTL and new connections use the latest password, existing connections are left open.
+/// Does nothing when secrets come from the environment.
+pub fn spawn_password_rotation(config: &Config, pool: PgPool, read_pool: Option<PgPool>) {
+    if !config.secrets.supports_rotation() {
+        return;
+    }
+    let secrets_manager = config.secrets.clone();
+    let database_url = config.database_url.clone();
+    let database_read_url = config.database_read_url.clone();
+
+    tokio::spawn(async move {
+        let mut current_password = secrets_manager.get(DATABASE_PASSWORD_SECRET).await.ok().flatten();
+        loop {
+            tokio::time::sleep(secrets_manager.cache_ttl()).await;
+            let password = match secrets_manager.refresh().await {
+                Ok(values) => values.get(DATABASE_PASSWORD_SECRET).cloned(),
+                Err(e) => {
+                    warn!(error = ?e, "Failed to refresh database password, keeping current credentials");
+                    continue;
+                }
+            };
+            let Some(password) = password.filter(|password| current_password.as_ref() != Some(password)) else {
+                continue;
+            };
+
+            let mut targets = vec![(&pool, database_url.as_str())];
+            if let (Some(read_pool), Some(read_url)) = (&read_pool, &database_read_url) {
+                targets.push((read_pool, read_url.as_str()));
+            }
+            for (target_pool, url) in targets {
+                match secrets::with_database_password(url, &password).and_then(|url| Ok(url.parse::<PgConnectOptions>()?)) {
+                    Ok(options) => target_pool.set_connect_options(options),
+                    Err(e) => warn!(error = ?e, "Failed to apply rotated database password"),
+                }
+            }
+            info!("Database password rotated, new connections use the updated credentials");
+            current_password = Some(password);
+        }
+    });
 }
\ No newline at end of file
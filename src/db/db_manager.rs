@@ -1,44 +1,118 @@
 use sqlx::PgPool;
 use std::collections::HashMap;
-use ethers::types::Address;
+use ethers::types::{Address, H256};
 use std::sync::Arc;
 use std::str::FromStr;
-use tokio::sync::RwLock;
-use tracing::{info, debug, instrument};
-use chrono::{DateTime, Utc};
+use tokio::sync::{RwLock, broadcast};
+use tracing::{info, debug, warn, instrument};
+use chrono::{DateTime, DurationRound, Utc};
 use rust_decimal::Decimal;
 
 use super::connection;
 use super::schema;
+use super::subscriber::{DbSubscriber, DbChannel};
 use super::queries::{
     tokens as tokens_queries,
     markets as markets_queries,
     token_prices as token_prices_queries,
     market_states as market_states_queries,
+    trades as trades_queries,
+    pending_plans as pending_plans_queries,
+    strategy_runs as strategy_runs_queries,
+    orders as orders_queries,
+    execution_costs as execution_costs_queries,
+    funding_rates as funding_rates_queries,
+    market_incentives as market_incentives_queries,
+    execution_plans as execution_plans_queries,
+    config_overrides as config_overrides_queries,
+    compliance_rules as compliance_rules_queries,
+    wallet_transactions as wallet_transactions_queries,
+    market_overview as market_overview_queries,
+    spot_swaps as spot_swaps_queries,
+    bridge_transfers as bridge_transfers_queries,
+    position_exposures as position_exposures_queries,
+    pipeline_health as pipeline_health_queries,
+    liquidations as liquidations_queries,
+    market_holding_periods as market_holding_periods_queries,
+    external_signals as external_signals_queries,
+    market_universes as market_universes_queries,
+    rebalances as rebalances_queries,
 };
 use super::models::{
     tokens::{TokenModel, NewTokenModel, RawTokenModel},
     markets::{MarketModel, NewMarketModel, RawMarketModel},
-    token_prices::{TokenPriceModel, NewTokenPriceModel, RawTokenPriceModel},
-    market_states::{MarketStateModel, NewMarketStateModel, RawMarketStateModel},
+    token_prices::{TokenPriceModel, NewTokenPriceModel, RawTokenPriceModel, NewQuarantinedPriceModel},
+    market_states::{MarketStateModel, MarketCapsModel, NewMarketStateModel, NewBackfilledMarketStateModel, RawMarketStateModel, RecordedGmPriceModel},
+    trades::{TradeModel, NewTradeModel, TradeStatus},
+    pending_plans::{PendingPlanModel, PendingPlanActionModel, NewPendingPlanActionModel, PlanStatus},
+    strategy_runs::{StrategyRunModel, NewStrategyRunModel, StrategyRunMarketModel, NewStrategyRunMarketModel, StrategyRunInputModel, NewStrategyRunInputModel, ReturnModelMetricsModel, NewReturnModelMetricsModel},
+    orders::{OrderModel, NewOrderModel, HedgeOrderStatus},
+    execution_costs::{NewExecutionCostModel, ExecutionSpendModel, NewGasPriceSampleModel, NewGasProfileModel, GasProfileSummaryModel},
+    funding_rates::NewFundingRateModel,
+    market_incentives::NewMarketIncentiveModel,
+    execution_plans::{ExecutionPlanModel, ExecutionPlanActionModel, NewExecutionPlanActionModel, ExecutionStatus, PlanCreation},
+    config_overrides::ConfigOverrideModel,
+    compliance_rules::ComplianceRuleModel,
+    market_universes::MarketUniverseModel,
+    wallet_transactions::{WalletTransactionModel, NewWalletTransactionModel},
+    market_overview::MarketOverviewModel,
+    spot_swaps::NewSpotSwapModel,
+    bridge_transfers::{BridgeTransferModel, NewBridgeTransferModel, BridgeTransferStatus},
+    position_exposures::{PositionExposureModel, NewPositionExposureModel},
+    pipeline_health::NewPipelineHealthModel,
+    liquidations::{NewLiquidationModel, LiquidationTotalsModel},
+    market_holding_periods::MarketHoldingPeriodModel,
+    external_signals::{NewExternalSignalModel, ExternalSignalModel},
+    rebalances::RebalanceCostModel,
 };
 use crate::config::Config;
+use crate::clock::{Clock, system_clock};
 use crate::data_ingestion::token::token::AssetToken;
 use crate::data_ingestion::market::market::Market;
+use crate::data_ingestion::market::market_descriptor::MarketDescriptor;
 use crate::strategy::types::MarketStateSlice;
 
+/// Pending invalidations beyond this are collapsed, a lagging receiver just refreshes once
+const ID_MAP_INVALIDATION_CAPACITY: usize = 16;
+
+/// Published when new tokens or markets are inserted, so cached ID maps are reloaded on their next lookup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdMapInvalidation {
+    Tokens,
+    Markets,
+}
+
 pub struct DbManager {
     pub pool: PgPool,
+    pub read_pool: PgPool, // Read replica for heavy read-only queries, same as `pool` when no replica is configured
     pub token_id_map: HashMap<Address, i32>,
     pub market_id_map: HashMap<Address, i32>,
+    pub clock: Arc<dyn Clock>,
+    pub account_id: String, // Portfolio account trades, plans, runs and overrides are scoped to
+    id_map_invalidation_tx: broadcast::Sender<IdMapInvalidation>,
+    id_map_invalidation_rx: broadcast::Receiver<IdMapInvalidation>,
 }
 
 impl DbManager {
     /// Creates a new database connection and initializes the schema
-    #[instrument(skip(config), fields(on_close = true))]
     pub async fn init(config: &Config) -> Result<Self, sqlx::Error> {
+        Self::init_with_clock(config, system_clock()).await
+    }
+
+    /// Same as `init`, but timestamps and time windows are taken from the given clock
+    #[instrument(skip(config, clock), fields(on_close = true))]
+    pub async fn init_with_clock(config: &Config, clock: Arc<dyn Clock>) -> Result<Self, sqlx::Error> {
         debug!("Initializing database manager");
         let pool = connection::create_pool(config).await?;
+        let replica_pool = connection::create_read_pool(config).await?;
+        connection::spawn_password_rotation(config, pool.clone(), replica_pool.clone());
+        let read_pool = match replica_pool {
+            Some(read_pool) => {
+                info!("Read replica configured, routing read-only history and report queries to it");
+                read_pool
+            }
+            None => pool.clone(),
+        };
 
         // Ensure schema is initialized (creates tables if needed)
         schema::init_schema(&pool).await?;
@@ -47,6 +121,15 @@ impl DbManager {
         // Load ID maps
         let token_id_map = tokens_queries::get_token_id_map(&pool).await?;
         let market_id_map = markets_queries::get_market_id_map(&pool).await?;
+        store_market_display_names(&pool).await?;
+
+        // A configured rebalance must exist, legs recorded against a missing one would fail after they were executed
+        if let Some(rebalance_id) = config.rebalance_id {
+            if !rebalances_queries::rebalance_exists(&pool, &config.account_id, rebalance_id).await? {
+                return Err(sqlx::Error::Protocol(format!("Rebalance {} not found for account {}", rebalance_id, config.account_id)));
+            }
+            info!(rebalance_id = rebalance_id, "Recording legs against the configured rebalance");
+        }
 
         info!(
             token_count = token_id_map.len(),
@@ -54,13 +137,24 @@ impl DbManager {
             "Database manager initialized with existing entities"
         );
 
+        let (id_map_invalidation_tx, id_map_invalidation_rx) = broadcast::channel(ID_MAP_INVALIDATION_CAPACITY);
         Ok(Self {
             pool,
+            read_pool,
             token_id_map,
             market_id_map,
+            clock,
+            account_id: config.account_id.clone(),
+            id_map_invalidation_tx,
+            id_map_invalidation_rx,
         })
     }
 
+    /// Subscribe to the database's notifications on the given channels, scoped to this manager's account
+    pub async fn subscribe(&self, channels: &[DbChannel]) -> Result<DbSubscriber, sqlx::Error> {
+        DbSubscriber::connect(&self.pool, &self.account_id, channels).await
+    }
+
     /// Internal method to refresh ID maps from the database
     #[instrument(skip(self))]
     pub async fn refresh_id_maps(&mut self) -> Result<(), sqlx::Error> {
@@ -86,6 +180,57 @@ impl DbManager {
         Ok(())
     }
 
+    /// Subscribe to ID map invalidations published when this manager inserts new tokens or markets
+    pub fn subscribe_id_map_invalidations(&self) -> broadcast::Receiver<IdMapInvalidation> {
+        self.id_map_invalidation_tx.subscribe()
+    }
+
+    /// Publish an ID map invalidation, caches are reloaded on their next lookup
+    pub fn invalidate_id_maps(&self, invalidation: IdMapInvalidation) {
+        // Sending only fails without receivers, and the manager always holds one
+        let _ = self.id_map_invalidation_tx.send(invalidation);
+    }
+
+    /// Reload the ID maps if an invalidation was published since the last lookup, returning whether they were reloaded
+    async fn sync_id_maps(&mut self) -> Result<bool, sqlx::Error> {
+        let mut invalidated = false;
+        loop {
+            match self.id_map_invalidation_rx.try_recv() {
+                Ok(_) | Err(broadcast::error::TryRecvError::Lagged(_)) => invalidated = true,
+                Err(_) => break,
+            }
+        }
+        if invalidated {
+            self.refresh_id_maps().await?;
+        }
+        Ok(invalidated)
+    }
+
+    /// Token ID by address from the cached map. A miss reloads the maps unless `refreshed` says they were
+    /// already reloaded during the current call, so a batch of unknown addresses costs a single reload.
+    async fn lookup_token_id(&mut self, address: &Address, refreshed: &mut bool) -> Result<Option<i32>, sqlx::Error> {
+        if let Some(&id) = self.token_id_map.get(address) {
+            return Ok(Some(id));
+        }
+        if !*refreshed {
+            self.refresh_id_maps().await?;
+            *refreshed = true;
+        }
+        Ok(self.token_id_map.get(address).copied())
+    }
+
+    /// Market ID by market token address from the cached map, reloading the maps at most once per call like `lookup_token_id`
+    async fn lookup_market_id(&mut self, address: &Address, refreshed: &mut bool) -> Result<Option<i32>, sqlx::Error> {
+        if let Some(&id) = self.market_id_map.get(address) {
+            return Ok(Some(id));
+        }
+        if !*refreshed {
+            self.refresh_id_maps().await?;
+            *refreshed = true;
+        }
+        Ok(self.market_id_map.get(address).copied())
+    }
+
     /// Prepare token price models from a list of AssetToken objects
     #[instrument(skip(self, tokens_iter))]
     pub async fn prepare_token_prices<I>(&mut self, tokens_iter: I) -> Result<(Vec<NewTokenPriceModel>, Vec<AssetToken>), sqlx::Error>
@@ -93,7 +238,7 @@ impl DbManager {
         I: IntoIterator<Item = Arc<RwLock<AssetToken>>>,
     {
         debug!("Preparing token price models");
-        self.refresh_id_maps().await?;
+        let mut refreshed = self.sync_id_maps().await?;
 
         let mut token_prices = Vec::new();
         let mut failed_tokens = Vec::new();
@@ -101,7 +246,7 @@ impl DbManager {
         for token_arc in tokens_iter {
             count += 1;
             let token = token_arc.read().await;
-            if self.token_id_map.contains_key(&token.address) {
+            if self.lookup_token_id(&token.address, &mut refreshed).await?.is_some() {
                 let new_token_price = NewTokenPriceModel::from(&*token, &self.token_id_map);
                 token_prices.push(new_token_price);
             } else {
@@ -138,6 +283,20 @@ impl DbManager {
         Ok(())
     }
 
+    /// Store a price that failed ingestion validation instead of inserting it into token_prices
+    #[instrument(skip(self, price), fields(token_id = price.token_id))]
+    pub async fn insert_quarantined_price(&self, price: &NewQuarantinedPriceModel) -> Result<(), sqlx::Error> {
+        token_prices_queries::insert_quarantined_price(&self.pool, price).await?;
+        warn!(
+            token_id = price.token_id,
+            mid_price = %price.mid_price,
+            reference_price = ?price.reference_price,
+            reason = %price.reason,
+            "Token price quarantined"
+        );
+        Ok(())
+    }
+
     /// Prepare market state models from a list of Market objects
     #[instrument(skip(self, markets_iter))]
     pub async fn prepare_market_states<'a, I>(&mut self, markets_iter: I) -> Result<(Vec<NewMarketStateModel>, Vec<Market>), sqlx::Error>
@@ -145,14 +304,14 @@ impl DbManager {
         I: IntoIterator<Item = &'a Market>,
     {
         debug!("Preparing market state models");
-        self.refresh_id_maps().await?;
+        let mut refreshed = self.sync_id_maps().await?;
 
         let mut market_states = Vec::new();
         let mut failed_markets = Vec::new();
         let mut count = 0;
         for market in markets_iter {
             count += 1;
-            if self.market_id_map.contains_key(&market.market_token) {
+            if self.lookup_market_id(&market.market_token, &mut refreshed).await?.is_some() {
                 let new_market_state = NewMarketStateModel::from(market, &self.market_id_map);
                 market_states.push(new_market_state);
             } else {
@@ -192,7 +351,7 @@ impl DbManager {
     #[instrument(skip(self, tokens))]
     pub async fn prepare_new_tokens(&mut self, tokens: &[AssetToken]) -> Result<Vec<NewTokenModel>, sqlx::Error> {
         debug!("Preparing new token models");
-        self.refresh_id_maps().await?;
+        self.sync_id_maps().await?;
 
         let new_tokens: Vec<NewTokenModel> = tokens
             .iter()
@@ -212,14 +371,14 @@ impl DbManager {
     #[instrument(skip(self, markets))]
     pub async fn prepare_new_markets(&mut self, markets: &[&Market]) -> Result<(Vec<NewMarketModel>, Vec<Market>), sqlx::Error> {
         debug!("Preparing new market models");
-        self.refresh_id_maps().await?;
+        let mut refreshed = self.sync_id_maps().await?;
 
         let mut new_markets = Vec::new();
         let mut failed_markets = Vec::new();
         for market in markets {
-            if self.token_id_map.contains_key(&market.index_token.read().await.address) &&
-               self.token_id_map.contains_key(&market.long_token.read().await.address) &&
-               self.token_id_map.contains_key(&market.short_token.read().await.address) {     
+            if self.lookup_token_id(&market.index_token.read().await.address, &mut refreshed).await?.is_some() &&
+               self.lookup_token_id(&market.long_token.read().await.address, &mut refreshed).await?.is_some() &&
+               self.lookup_token_id(&market.short_token.read().await.address, &mut refreshed).await?.is_some() {     
                 let new_market = NewMarketModel::from_async(market, &self.token_id_map).await;
                 new_markets.push(new_market);
             } else {
@@ -286,6 +445,9 @@ impl DbManager {
             skipped = skipped_count,
             "Token insertion completed"
         );
+        if inserted_count > 0 {
+            self.invalidate_id_maps(IdMapInvalidation::Tokens);
+        }
         Ok(())
     }
 
@@ -337,16 +499,56 @@ impl DbManager {
             skipped = skipped_count,
             "Market insertion completed"
         );
+        if inserted_count > 0 {
+            store_market_display_names(&self.pool).await?;
+            self.invalidate_id_maps(IdMapInvalidation::Markets);
+        }
         Ok(())
     }
 
-    /// Get display names for all markets by joining with token information
+    /// Get the descriptor (tokens and canonical names) of every market
+    #[instrument(skip(self), fields(on_close = true))]
+    pub async fn get_market_descriptors(&self) -> Result<HashMap<Address, MarketDescriptor>, sqlx::Error> {
+        let descriptors: HashMap<Address, MarketDescriptor> = markets_queries::get_market_descriptors(&self.read_pool).await?
+            .into_values()
+            .map(|descriptor| (descriptor.market_token, descriptor))
+            .collect();
+        debug!(count = descriptors.len(), "Market descriptors fetched");
+        Ok(descriptors)
+    }
+
+    /// Get the canonical display names of all markets
     #[instrument(skip(self), fields(on_close = true))]
     pub async fn get_market_display_names(&self) -> Result<HashMap<Address, String>, sqlx::Error> {
-        debug!("Fetching market display names");
-        let display_names = market_states_queries::get_market_display_names(&self.pool).await?;
-        debug!(count = display_names.len(), "Market display names fetched");
-        Ok(display_names)
+        Ok(self.get_market_descriptors().await?
+            .into_iter()
+            .map(|(address, descriptor)| (address, descriptor.display_name()))
+            .collect())
+    }
+
+    /// Recompute the market overview (latest state, trailing fee APRs and GM price change per market)
+    #[instrument(skip(self), fields(on_close = true))]
+    pub async fn refresh_market_overview(&self) -> Result<(), sqlx::Error> {
+        market_overview_queries::refresh_market_overview(&self.pool).await?;
+        debug!("Market overview refreshed");
+        Ok(())
+    }
+
+    /// Get the market overview as of its last refresh, highest 7d fee APR first
+    #[instrument(skip(self))]
+    pub async fn get_market_overview(&self) -> Result<Vec<MarketOverviewModel>, sqlx::Error> {
+        let overview = market_overview_queries::get_market_overview(&self.read_pool).await?;
+        debug!(count = overview.len(), "Market overview fetched");
+        Ok(overview)
+    }
+
+    /// Get one market's overview as of the last refresh, None if it has no recorded state
+    #[instrument(skip(self))]
+    pub async fn get_market_overview_for_market(&self, market_address: Address) -> Result<Option<MarketOverviewModel>, sqlx::Error> {
+        let Some(&market_id) = self.market_id_map.get(&market_address) else {
+            return Ok(None);
+        };
+        market_overview_queries::get_market_overview_for_market(&self.read_pool, market_id).await
     }
 
     /// Fetch full history for each market and construct MarketStateSlice objects
@@ -357,19 +559,27 @@ impl DbManager {
     ) -> Result<Vec<MarketStateSlice>, sqlx::Error> {
         let mut slices = Vec::new();
 
-        // Get display names for all markets upfront
-        let display_names = self.get_market_display_names().await?;
-
-        // Fetch all market states and token prices concurrently
-        let (states_by_market, prices_by_token) = tokio::try_join!(
-            market_states_queries::get_all_market_states_in_range(&self.pool, start, end),
-            token_prices_queries::get_all_token_prices_in_range(&self.pool, start, end),
+        // Fetch every market's history, token prices, display names, index tokens and token info concurrently
+        // with set-based queries, then group in memory so no per-market queries are issued
+        let (states_by_market, prices_by_token, descriptors, market_index_tokens, tokens, deprecated_market_ids) = tokio::try_join!(
+            market_states_queries::get_all_market_states_in_range(&self.read_pool, start, end),
+            token_prices_queries::get_all_token_prices_in_range(&self.read_pool, start, end),
+            markets_queries::get_market_descriptors(&self.read_pool),
+            markets_queries::get_all_market_index_tokens(&self.read_pool),
+            tokens_queries::get_all_tokens(&self.read_pool),
+            markets_queries::get_deprecated_market_ids(&self.read_pool),
         )?;
-
-        // Get market-to-index-token mapping
-        let market_index_tokens = markets_queries::get_all_market_index_tokens(&self.pool).await?;
+        let tokens_by_id: HashMap<i32, TokenModel> = tokens.into_iter()
+            .map(|token| (token.id, token))
+            .collect();
 
         for (address, market_id) in &self.market_id_map {
+            // Deprecated markets are excluded from allocation, existing positions are wound down separately
+            if deprecated_market_ids.contains(market_id) {
+                debug!(market_address = %address, "Skipping deprecated market");
+                continue;
+            }
+
             let history = match states_by_market.get(market_id) {
                 Some(h) if !h.is_empty() => h,
                 _ => continue,
@@ -388,8 +598,8 @@ impl DbManager {
             };
 
             // Get token info
-            let index_token = match tokens_queries::get_token_by_id(&self.pool, index_token_id).await? {
-                Some(token) => token,
+            let index_token = match tokens_by_id.get(&index_token_id) {
+                Some(token) => token.clone(),
                 None => continue,
             };
 
@@ -400,12 +610,17 @@ impl DbManager {
             let index_token_prices: Vec<Decimal> = index_token_prices_objects.iter().map(|p| p.mid_price).collect();
             let index_token_timestamps: Vec<DateTime<Utc>> = index_token_prices_objects.iter().map(|p| p.timestamp).collect();
 
-            let display_name = display_names.get(address).cloned()
-                .unwrap_or_else(|| format!("{}/USD [Unknown]", address));
+            let Some(descriptor) = descriptors.get(market_id).cloned() else {
+                continue;
+            };
             
             // --- HISTORICAL DATA ---
             let timestamps = history.iter().map(|x| x.timestamp).collect();
             let fees_usd = history.iter().map(|x| x.fees_total.unwrap_or_default()).collect();
+            let borrowing_fees_usd = history.iter().map(|x| x.fees_borrowing.unwrap_or_default()).collect();
+            // Backfilled states carry fees only, hold PnL and GM price at the nearest recorded value rather than zero
+            let trader_pnl_usd = fill_missing(history.iter().map(|x| x.pnl_net).collect());
+            let gm_prices = fill_missing(history.iter().map(|x| x.gm_price_mid).collect());
 
             // --- CURRENT STATE ---
             let last_state = history.last().unwrap(); // Safe since is_empty() was checked above
@@ -444,9 +659,13 @@ impl DbManager {
 
             slices.push(MarketStateSlice {
                 market_address: *address,
-                display_name,
+                display_name: descriptor.display_name(),
+                descriptor,
                 timestamps,
                 fees_usd,
+                borrowing_fees_usd,
+                trader_pnl_usd,
+                gm_prices,
                 index_token_address,
                 index_token_symbol,
                 index_prices: index_token_prices,
@@ -495,6 +714,13 @@ impl DbManager {
         Ok(tokens)
     }
 
+    /// Fetch the mid price of a token at or before the given time
+    #[instrument(skip(self))]
+    pub async fn get_token_price_at_or_before(&self, token_id: i32, at: DateTime<Utc>) -> Result<Option<Decimal>, sqlx::Error> {
+        let price = token_prices_queries::get_token_price_at_or_before(&self.read_pool, token_id, at).await?;
+        Ok(price.map(|p| p.mid_price))
+    }
+
     /// Fetch most recent market state for all markets
     #[instrument(skip(self))]
     pub async fn get_latest_market_states(&self) -> Result<Vec<MarketStateModel>, sqlx::Error> {
@@ -503,6 +729,37 @@ impl DbManager {
         Ok(states)
     }
 
+    /// Fetch the latest pool caps recorded for every market, keyed by market ID
+    #[instrument(skip(self))]
+    pub async fn get_latest_market_caps(&self) -> Result<HashMap<i32, MarketCapsModel>, sqlx::Error> {
+        let caps: HashMap<i32, MarketCapsModel> = market_states_queries::get_latest_market_caps_for_all_markets(&self.pool).await?
+            .into_iter()
+            .map(|caps| (caps.market_id, caps))
+            .collect();
+        debug!(count = caps.len(), "Fetched latest market caps");
+        Ok(caps)
+    }
+
+    /// Fetch a random sample of collected GM prices since the given time, with the token prices they were computed from
+    #[instrument(skip(self))]
+    pub async fn get_recorded_gm_price_sample(&self, since: DateTime<Utc>, limit: i64) -> Result<Vec<RecordedGmPriceModel>, sqlx::Error> {
+        let sample = market_states_queries::get_recorded_gm_price_sample(&self.read_pool, since, limit).await?;
+        debug!(count = sample.len(), "Fetched recorded GM price sample");
+        Ok(sample)
+    }
+
+    /// Fetch the latest GM mid price of every market as of the given time, with the time each price was recorded
+    #[instrument(skip(self))]
+    pub async fn get_latest_gm_prices_as_of(&self, as_of: DateTime<Utc>) -> Result<HashMap<Address, (Decimal, DateTime<Utc>)>, sqlx::Error> {
+        let prices: HashMap<Address, (Decimal, DateTime<Utc>)> = market_states_queries::get_latest_gm_prices_as_of(&self.pool, as_of)
+            .await?
+            .into_iter()
+            .filter_map(|(address, price, timestamp)| Some((Address::from_str(&address).ok()?, (price, timestamp))))
+            .collect();
+        debug!(count = prices.len(), as_of = %as_of, "Fetched latest GM prices");
+        Ok(prices)
+    }
+
     /// Fetch all asset tokens
     #[instrument(skip(self))]
     pub async fn get_all_asset_tokens(&self) -> Result<Vec<(Address, String, u8, Decimal)>, sqlx::Error> {
@@ -560,6 +817,778 @@ impl DbManager {
         Ok(price_props)
     }
 
+    /// Insert a new trade record, as a leg of the given rebalance
+    #[instrument(skip(self, trade), fields(action_type = %trade.action_type, status = %trade.status))]
+    pub async fn insert_trade(&self, trade: &NewTradeModel, rebalance_id: Option<i32>) -> Result<i32, sqlx::Error> {
+        let id = trades_queries::insert_trade(&self.pool, &self.account_id, rebalance_id, trade).await?;
+        debug!(trade_id = id, "Trade inserted");
+        Ok(id)
+    }
+
+    /// Update the status of a trade
+    #[instrument(skip(self))]
+    pub async fn update_trade_status(&self, trade_id: i32, status: TradeStatus, cancel_tx_hash: Option<String>) -> Result<(), sqlx::Error> {
+        trades_queries::update_trade_status(&self.pool, trade_id, status.as_str(), cancel_tx_hash.as_deref()).await?;
+        debug!(trade_id = trade_id, status = status.as_str(), "Trade status updated");
+        Ok(())
+    }
+
+    /// Record the execution fee refunded to a trade after keeper execution
+    #[instrument(skip(self))]
+    pub async fn update_trade_execution_fee_refund(&self, trade_id: i32, execution_fee_refund: Decimal) -> Result<(), sqlx::Error> {
+        trades_queries::update_trade_execution_fee_refund(&self.pool, trade_id, execution_fee_refund).await?;
+        debug!(trade_id = trade_id, execution_fee_refund = %execution_fee_refund, "Trade execution fee refund recorded");
+        Ok(())
+    }
+
+    /// Fetch all trades still waiting for keeper execution
+    #[instrument(skip(self))]
+    pub async fn get_pending_trades(&self) -> Result<Vec<TradeModel>, sqlx::Error> {
+        let trades = trades_queries::get_trades_by_status(&self.pool, &self.account_id, TradeStatus::Pending.as_str()).await?;
+        debug!(count = trades.len(), "Fetched pending trades");
+        Ok(trades)
+    }
+
+    /// Fetch all trades created within a time range
+    #[instrument(skip(self))]
+    pub async fn get_trades_in_range(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<TradeModel>, sqlx::Error> {
+        let trades = trades_queries::get_trades_in_range(&self.read_pool, &self.account_id, start, end).await?;
+        debug!(count = trades.len(), "Fetched trades in range");
+        Ok(trades)
+    }
+
+    /// Fetch the most recently created trades, newest first
+    #[instrument(skip(self))]
+    pub async fn get_recent_trades(&self, limit: i64) -> Result<Vec<TradeModel>, sqlx::Error> {
+        let trades = trades_queries::get_recent_trades(&self.read_pool, &self.account_id, limit).await?;
+        debug!(count = trades.len(), "Fetched recent trades");
+        Ok(trades)
+    }
+
+    /// Fetch the most recent trade of a type in a market created since the given time
+    #[instrument(skip(self))]
+    pub async fn get_latest_trade_since(&self, action_type: &str, market_id: i32, since: DateTime<Utc>) -> Result<Option<TradeModel>, sqlx::Error> {
+        let trade = trades_queries::get_latest_trade_since(&self.pool, &self.account_id, action_type, market_id, since).await?;
+        debug!(found = trade.is_some(), "Fetched latest trade since");
+        Ok(trade)
+    }
+
+    /// Fetch when the last settled trade touching a market was last updated, if any
+    #[instrument(skip(self))]
+    pub async fn get_last_settled_trade_time(&self, market_id: i32) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+        trades_queries::get_last_settled_trade_time(&self.pool, &self.account_id, market_id).await
+    }
+
+    /// Sum the notional of this account's trades created since the given time
+    #[instrument(skip(self))]
+    pub async fn get_trade_notional_since(&self, since: DateTime<Utc>) -> Result<Decimal, sqlx::Error> {
+        trades_queries::get_trade_notional_since(&self.pool, &self.account_id, since).await
+    }
+
+    /// Write a proposed plan to the pending plans table for operator approval
+    #[instrument(skip(self, actions), fields(action_count = actions.len()))]
+    pub async fn create_pending_plan(&self, actions: &[NewPendingPlanActionModel], expires_at: DateTime<Utc>) -> Result<i32, sqlx::Error> {
+        let plan_id = pending_plans_queries::insert_pending_plan(&self.pool, &self.account_id, expires_at, actions).await?;
+        info!(plan_id = plan_id, expires_at = %expires_at, "Pending plan created");
+        Ok(plan_id)
+    }
+
+    /// Fetch a pending plan and its actions
+    #[instrument(skip(self))]
+    pub async fn get_pending_plan(&self, plan_id: i32) -> Result<Option<(PendingPlanModel, Vec<PendingPlanActionModel>)>, sqlx::Error> {
+        let plan = match pending_plans_queries::get_pending_plan(&self.pool, &self.account_id, plan_id).await? {
+            Some(plan) => plan,
+            None => return Ok(None),
+        };
+        let actions = pending_plans_queries::get_plan_actions(&self.pool, plan_id).await?;
+        debug!(plan_id = plan_id, status = %plan.status, action_count = actions.len(), "Fetched pending plan");
+        Ok(Some((plan, actions)))
+    }
+
+    /// Fetch all plans still waiting for a decision
+    #[instrument(skip(self))]
+    pub async fn get_undecided_plans(&self) -> Result<Vec<PendingPlanModel>, sqlx::Error> {
+        let plans = pending_plans_queries::get_undecided_plans(&self.pool, &self.account_id).await?;
+        debug!(count = plans.len(), "Fetched undecided plans");
+        Ok(plans)
+    }
+
+    /// Approve or reject a whole plan (`action_ids` = None) or only the given actions
+    #[instrument(skip(self))]
+    pub async fn decide_pending_plan(&self, plan_id: i32, action_ids: Option<Vec<i32>>, status: PlanStatus) -> Result<u64, sqlx::Error> {
+        let updated = pending_plans_queries::decide_plan_actions(&self.pool, &self.account_id, plan_id, action_ids.as_deref(), status).await?;
+        info!(plan_id = plan_id, status = status.as_str(), updated = updated, "Pending plan actions decided");
+        Ok(updated)
+    }
+
+    /// Expire undecided plans that are past their expiry time
+    #[instrument(skip(self))]
+    pub async fn expire_stale_plans(&self) -> Result<u64, sqlx::Error> {
+        let expired = pending_plans_queries::expire_stale_plans(&self.pool, &self.account_id).await?;
+        if expired > 0 {
+            info!(expired = expired, "Expired stale pending plans");
+        }
+        Ok(expired)
+    }
+
+    /// Record the portfolio summary, per-market output and input digests of a strategy engine run
+    #[instrument(skip(self, run, markets, inputs), fields(market_count = markets.len()))]
+    pub async fn insert_strategy_run(&self, run: &NewStrategyRunModel, markets: &[NewStrategyRunMarketModel], inputs: &[NewStrategyRunInputModel]) -> Result<i32, sqlx::Error> {
+        let run_id = strategy_runs_queries::insert_strategy_run(&self.pool, &self.account_id, run, markets, inputs).await?;
+        info!(run_id = run_id, "Strategy run recorded");
+        Ok(run_id)
+    }
+
+    /// Fetch a strategy run by ID
+    #[instrument(skip(self))]
+    pub async fn get_strategy_run(&self, run_id: i32) -> Result<Option<StrategyRunModel>, sqlx::Error> {
+        strategy_runs_queries::get_run(&self.read_pool, &self.account_id, run_id).await
+    }
+
+    /// Fetch the most recently recorded strategy run
+    #[instrument(skip(self))]
+    pub async fn get_latest_strategy_run(&self) -> Result<Option<StrategyRunModel>, sqlx::Error> {
+        strategy_runs_queries::get_latest_run(&self.read_pool, &self.account_id).await
+    }
+
+    /// Fetch the most recently recorded strategy run over a market universe (None for runs over every market)
+    #[instrument(skip(self))]
+    pub async fn get_latest_universe_strategy_run(&self, universe: Option<&str>) -> Result<Option<StrategyRunModel>, sqlx::Error> {
+        strategy_runs_queries::get_latest_universe_run(&self.read_pool, &self.account_id, universe).await
+    }
+
+    /// Fetch the per-market input digests recorded with a strategy run
+    #[instrument(skip(self))]
+    pub async fn get_strategy_run_inputs(&self, run_id: i32) -> Result<Vec<StrategyRunInputModel>, sqlx::Error> {
+        let inputs = strategy_runs_queries::get_run_inputs(&self.read_pool, run_id).await?;
+        debug!(run_id = run_id, count = inputs.len(), "Fetched strategy run inputs");
+        Ok(inputs)
+    }
+
+    /// Fetch strategy runs created before the cutoff that have no return model metrics yet
+    #[instrument(skip(self))]
+    pub async fn get_unevaluated_strategy_runs(&self, created_before: DateTime<Utc>) -> Result<Vec<StrategyRunModel>, sqlx::Error> {
+        let runs = strategy_runs_queries::get_unevaluated_runs(&self.pool, &self.account_id, created_before).await?;
+        debug!(count = runs.len(), "Fetched unevaluated strategy runs");
+        Ok(runs)
+    }
+
+    /// Fetch the per-market output of a strategy run
+    #[instrument(skip(self))]
+    pub async fn get_strategy_run_markets(&self, run_id: i32) -> Result<Vec<StrategyRunMarketModel>, sqlx::Error> {
+        let markets = strategy_runs_queries::get_run_markets(&self.read_pool, run_id).await?;
+        debug!(run_id = run_id, count = markets.len(), "Fetched strategy run markets");
+        Ok(markets)
+    }
+
+    /// Fetch the latest GM token mid price (and its timestamp) of a market at or before the given time
+    #[instrument(skip(self))]
+    pub async fn get_gm_price_at(&self, market_id: i32, at: DateTime<Utc>) -> Result<Option<(DateTime<Utc>, Decimal)>, sqlx::Error> {
+        strategy_runs_queries::get_gm_price_at(&self.read_pool, market_id, at).await
+    }
+
+    /// Fetch the pool composition (long and short token USD value) of a market at or before the given time
+    #[instrument(skip(self))]
+    pub async fn get_pool_composition_at(&self, market_id: i32, at: DateTime<Utc>) -> Result<Option<(Decimal, Decimal)>, sqlx::Error> {
+        strategy_runs_queries::get_pool_composition_at(&self.read_pool, market_id, at).await
+    }
+
+    /// Store the expected return prediction error metrics of a strategy run
+    #[instrument(skip(self, metrics), fields(run_id = metrics.run_id))]
+    pub async fn insert_return_model_metrics(&self, metrics: &NewReturnModelMetricsModel) -> Result<(), sqlx::Error> {
+        strategy_runs_queries::insert_return_model_metrics(&self.pool, metrics).await?;
+        debug!("Return model metrics inserted");
+        Ok(())
+    }
+
+    /// Fetch the most recent return model metrics, newest run first
+    #[instrument(skip(self))]
+    pub async fn get_recent_return_model_metrics(&self, limit: i64) -> Result<Vec<ReturnModelMetricsModel>, sqlx::Error> {
+        let metrics = strategy_runs_queries::get_recent_return_model_metrics(&self.read_pool, limit).await?;
+        debug!(count = metrics.len(), "Fetched recent return model metrics");
+        Ok(metrics)
+    }
+
+    /// Insert a new hedge order record, as a leg of the given rebalance
+    #[instrument(skip(self, order), fields(venue = %order.venue, ticker = %order.ticker, client_id = order.client_id))]
+    pub async fn insert_order(&self, order: &NewOrderModel, rebalance_id: Option<i32>) -> Result<i32, sqlx::Error> {
+        let id = orders_queries::insert_order(&self.pool, &self.account_id, rebalance_id, order).await?;
+        debug!(order_id = id, "Order inserted");
+        Ok(id)
+    }
+
+    /// Record the average price a hedge order filled at
+    #[instrument(skip(self))]
+    pub async fn update_order_fill_price(&self, order_id: i32, average_fill_price: Decimal) -> Result<(), sqlx::Error> {
+        orders_queries::update_order_fill_price(&self.pool, order_id, average_fill_price).await?;
+        debug!(order_id = order_id, average_fill_price = %average_fill_price, "Order fill price recorded");
+        Ok(())
+    }
+
+    /// Update the status and filled size of a hedge order
+    #[instrument(skip(self))]
+    pub async fn update_order_state(&self, order_id: i32, status: HedgeOrderStatus, filled_size: Decimal, venue_order_id: Option<String>) -> Result<(), sqlx::Error> {
+        orders_queries::update_order_state(&self.pool, order_id, status.as_str(), filled_size, venue_order_id.as_deref()).await?;
+        debug!(order_id = order_id, status = status.as_str(), filled_size = %filled_size, "Order state updated");
+        Ok(())
+    }
+
+    /// Fetch all hedge orders on a venue that are still outstanding, optionally for a single ticker
+    #[instrument(skip(self))]
+    pub async fn get_open_orders(&self, venue: &str, ticker: Option<&str>) -> Result<Vec<OrderModel>, sqlx::Error> {
+        let orders = orders_queries::get_orders_by_status(&self.pool, &self.account_id, venue, &HedgeOrderStatus::open_statuses(), ticker).await?;
+        debug!(count = orders.len(), "Fetched open orders");
+        Ok(orders)
+    }
+
+    /// Fetch all hedge orders on a venue with a partially filled remainder awaiting retry
+    #[instrument(skip(self))]
+    pub async fn get_partially_filled_orders(&self, venue: &str) -> Result<Vec<OrderModel>, sqlx::Error> {
+        let orders = orders_queries::get_orders_by_status(&self.pool, &self.account_id, venue, &[HedgeOrderStatus::PartiallyFilled.as_str()], None).await?;
+        debug!(count = orders.len(), "Fetched partially filled orders");
+        Ok(orders)
+    }
+
+    /// Downsample raw token prices and market states older than the retention window into hourly rows,
+    /// then delete the raw rows. Only whole hours are downsampled.
+    #[instrument(skip(self), fields(on_close = true))]
+    pub async fn apply_data_retention(&self, retention_days: i64) -> Result<(), sqlx::Error> {
+        let cutoff = (self.clock.now() - chrono::Duration::days(retention_days))
+            .duration_trunc(chrono::Duration::hours(1))
+            .map_err(|e| sqlx::Error::Protocol(format!("Invalid retention cutoff: {}", e)))?;
+        debug!(cutoff = %cutoff, "Applying data retention");
+
+        let (token_prices_aggregated, token_prices_deleted) =
+            token_prices_queries::downsample_token_prices_before(&self.pool, cutoff).await?;
+        let (market_states_aggregated, market_states_deleted) =
+            market_states_queries::downsample_market_states_before(&self.pool, cutoff).await?;
+
+        info!(
+            cutoff = %cutoff,
+            token_prices_aggregated,
+            token_prices_deleted,
+            market_states_aggregated,
+            market_states_deleted,
+            "Data retention applied"
+        );
+        Ok(())
+    }
+
+    /// Record gas and execution fees paid for an on-chain action, as a leg of the given rebalance
+    #[instrument(skip(self, cost), fields(venue = %cost.venue, action_type = %cost.action_type))]
+    pub async fn insert_execution_cost(&self, cost: &NewExecutionCostModel, rebalance_id: Option<i32>) -> Result<i32, sqlx::Error> {
+        let id = execution_costs_queries::insert_execution_cost(&self.pool, &self.account_id, rebalance_id, cost).await?;
+        debug!(execution_cost_id = id, total_cost_usd = %(cost.gas_cost_usd + cost.execution_fee_usd), "Execution cost recorded");
+        Ok(id)
+    }
+
+    /// Record the slippage of a transaction whose cost was recorded before its fill was known
+    #[instrument(skip(self))]
+    pub async fn update_execution_slippage(&self, tx_hash: &str, slippage_usd: Decimal) -> Result<(), sqlx::Error> {
+        let updated = execution_costs_queries::update_execution_slippage(&self.pool, tx_hash, slippage_usd).await?;
+        if updated == 0 {
+            warn!(tx_hash = tx_hash, "No execution cost recorded for transaction, slippage not recorded");
+        }
+        debug!(tx_hash = tx_hash, slippage_usd = %slippage_usd, "Execution slippage recorded");
+        Ok(())
+    }
+
+    /// Fetch cumulative execution spend per venue and action type since the given time
+    #[instrument(skip(self))]
+    pub async fn get_execution_spend_since(&self, since: DateTime<Utc>) -> Result<Vec<ExecutionSpendModel>, sqlx::Error> {
+        let spend = execution_costs_queries::get_execution_spend_since(&self.pool, &self.account_id, since).await?;
+        debug!(count = spend.len(), "Fetched execution spend");
+        Ok(spend)
+    }
+
+    /// Record the aggregate of a spot swap across its TWAP slices, as a leg of the given rebalance
+    #[instrument(skip(self, swap), fields(side = %swap.side, status = %swap.status))]
+    pub async fn insert_spot_swap(&self, swap: &NewSpotSwapModel, rebalance_id: Option<i32>) -> Result<i32, sqlx::Error> {
+        let id = spot_swaps_queries::insert_spot_swap(&self.pool, &self.account_id, rebalance_id, swap).await?;
+        debug!(spot_swap_id = id, slices_filled = swap.slices_filled, slices_planned = swap.slices_planned, "Spot swap recorded");
+        Ok(id)
+    }
+
+    /// Start a rebalance, returning the ID its trades, swaps, orders and execution costs are recorded with
+    #[instrument(skip(self))]
+    pub async fn begin_rebalance(&self, source: &str) -> Result<i32, sqlx::Error> {
+        let id = rebalances_queries::insert_rebalance(&self.pool, &self.account_id, source).await?;
+        info!(rebalance_id = id, "Rebalance started");
+        Ok(id)
+    }
+
+    /// Complete a rebalance
+    #[instrument(skip(self))]
+    pub async fn complete_rebalance(&self, rebalance_id: i32) -> Result<(), sqlx::Error> {
+        rebalances_queries::complete_rebalance(&self.pool, rebalance_id).await?;
+        info!(rebalance_id = rebalance_id, "Rebalance completed");
+        Ok(())
+    }
+
+    /// Fetch the cost breakdown of the rebalances started since the given time, newest first
+    #[instrument(skip(self))]
+    pub async fn get_rebalance_costs_since(&self, since: DateTime<Utc>) -> Result<Vec<RebalanceCostModel>, sqlx::Error> {
+        let costs = rebalances_queries::get_rebalance_costs_since(&self.read_pool, &self.account_id, since).await?;
+        debug!(count = costs.len(), "Fetched rebalance costs");
+        Ok(costs)
+    }
+
+    /// Record a bridge transfer sent on its source chain, pending until the funds arrive
+    #[instrument(skip(self, transfer), fields(direction = %transfer.direction, amount = %transfer.amount))]
+    pub async fn insert_bridge_transfer(&self, transfer: &NewBridgeTransferModel) -> Result<i32, sqlx::Error> {
+        let id = bridge_transfers_queries::insert_bridge_transfer(&self.pool, &self.account_id, transfer).await?;
+        debug!(bridge_transfer_id = id, source_tx_hash = %transfer.source_tx_hash, "Bridge transfer recorded");
+        Ok(id)
+    }
+
+    /// Update a bridge transfer's status
+    #[instrument(skip(self))]
+    pub async fn update_bridge_transfer_status(&self, id: i32, status: BridgeTransferStatus, error: Option<String>) -> Result<(), sqlx::Error> {
+        bridge_transfers_queries::update_bridge_transfer_status(&self.pool, id, status.as_str(), error.as_deref()).await?;
+        debug!(bridge_transfer_id = id, status = status.as_str(), "Bridge transfer status updated");
+        Ok(())
+    }
+
+    /// Fetch a bridge transfer by ID
+    #[instrument(skip(self))]
+    pub async fn get_bridge_transfer(&self, id: i32) -> Result<Option<BridgeTransferModel>, sqlx::Error> {
+        bridge_transfers_queries::get_bridge_transfer(&self.pool, &self.account_id, id).await
+    }
+
+    /// Fetch the bridge transfers whose funds have not arrived yet
+    #[instrument(skip(self))]
+    pub async fn get_pending_bridge_transfers(&self) -> Result<Vec<BridgeTransferModel>, sqlx::Error> {
+        let transfers = bridge_transfers_queries::get_pending_bridge_transfers(&self.pool, &self.account_id).await?;
+        debug!(count = transfers.len(), "Fetched pending bridge transfers");
+        Ok(transfers)
+    }
+
+    /// Record the collateral exposures of the held GM positions
+    #[instrument(skip(self, exposures), fields(count = exposures.len()))]
+    pub async fn insert_position_exposures(&self, exposures: &[NewPositionExposureModel]) -> Result<(), sqlx::Error> {
+        position_exposures_queries::insert_position_exposures(&self.pool, &self.account_id, exposures).await?;
+        debug!("Position exposures recorded");
+        Ok(())
+    }
+
+    /// Fetch the holding period state of every market, keyed by market ID
+    #[instrument(skip(self))]
+    pub async fn get_market_holding_periods(&self) -> Result<HashMap<i32, MarketHoldingPeriodModel>, sqlx::Error> {
+        let periods: HashMap<i32, MarketHoldingPeriodModel> = market_holding_periods_queries::get_market_holding_periods(&self.pool, &self.account_id).await?
+            .into_iter()
+            .map(|period| (period.market_id, period))
+            .collect();
+        debug!(count = periods.len(), "Fetched market holding periods");
+        Ok(periods)
+    }
+
+    /// Record markets entered or exited since the holding period state was last updated
+    #[instrument(skip(self, periods), fields(count = periods.len()))]
+    pub async fn upsert_market_holding_periods(&self, periods: &[MarketHoldingPeriodModel]) -> Result<(), sqlx::Error> {
+        market_holding_periods_queries::upsert_market_holding_periods(&self.pool, &self.account_id, periods).await?;
+        debug!("Market holding periods updated");
+        Ok(())
+    }
+
+    /// Fetch the latest recorded exposure of every market, keyed by market ID
+    #[instrument(skip(self))]
+    pub async fn get_latest_position_exposures(&self) -> Result<HashMap<i32, PositionExposureModel>, sqlx::Error> {
+        let exposures: HashMap<i32, PositionExposureModel> = position_exposures_queries::get_latest_position_exposures(&self.pool, &self.account_id).await?
+            .into_iter()
+            .map(|exposure| (exposure.market_id, exposure))
+            .collect();
+        debug!(count = exposures.len(), "Fetched latest position exposures");
+        Ok(exposures)
+    }
+
+    /// Record an execution fee estimate sample
+    #[instrument(skip(self, sample), fields(action_type = %sample.action_type))]
+    pub async fn insert_gas_price_sample(&self, sample: &NewGasPriceSampleModel) -> Result<(), sqlx::Error> {
+        execution_costs_queries::insert_gas_price_sample(&self.pool, sample).await?;
+        debug!(gas_price = %sample.gas_price, execution_fee = %sample.execution_fee, "Gas price sample recorded");
+        Ok(())
+    }
+
+    /// Record a data stream going stale or recovering
+    #[instrument(skip(self, health), fields(stream = %health.stream, status = %health.status))]
+    pub async fn insert_pipeline_health(&self, health: &NewPipelineHealthModel) -> Result<(), sqlx::Error> {
+        pipeline_health_queries::insert_pipeline_health(&self.pool, health).await?;
+        debug!(last_entry_at = ?health.last_entry_at, "Pipeline health recorded");
+        Ok(())
+    }
+
+    /// Get the median estimated execution fee for an action type since the given time, with the sample count
+    #[instrument(skip(self))]
+    pub async fn get_median_execution_fee_since(&self, action_type: &str, since: DateTime<Utc>) -> Result<(Option<Decimal>, i64), sqlx::Error> {
+        execution_costs_queries::get_median_execution_fee_since(&self.read_pool, action_type, since).await
+    }
+
+    /// Get the total execution cost per transaction hash for costs recorded within a time range
+    #[instrument(skip(self))]
+    pub async fn get_execution_costs_by_tx_in_range(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<HashMap<String, Decimal>, sqlx::Error> {
+        let costs = execution_costs_queries::get_execution_costs_by_tx_in_range(&self.read_pool, start, end).await?;
+        debug!(count = costs.len(), "Fetched execution costs by transaction");
+        Ok(costs)
+    }
+
+    /// Get the quantile of the max fee per gas buffer an action type's request transactions needed since the given time,
+    /// with the number of transactions it was computed from
+    #[instrument(skip(self))]
+    pub async fn get_required_fee_buffer_since(&self, action_type: &str, since: DateTime<Utc>, quantile: f64) -> Result<(Option<Decimal>, i64), sqlx::Error> {
+        let (required_fee_buffer, sample_count) = execution_costs_queries::get_required_fee_buffer_since(&self.read_pool, action_type, since, quantile).await?;
+        debug!(required_fee_buffer = ?required_fee_buffer, sample_count = sample_count, "Fetched required fee buffer");
+        Ok((required_fee_buffer, sample_count))
+    }
+
+    /// Record the gas profile of a GM request creation transaction
+    #[instrument(skip(self, profile), fields(action_type = %profile.action_type, tx_hash = %profile.tx_hash))]
+    pub async fn insert_gas_profile(&self, profile: &NewGasProfileModel) -> Result<i32, sqlx::Error> {
+        let id = execution_costs_queries::insert_gas_profile(&self.pool, profile).await?;
+        debug!(gas_profile_id = id, gas_used = %profile.gas_used, execution_fee = %profile.execution_fee, "Gas profile recorded");
+        Ok(id)
+    }
+
+    /// Record the keeper execution and execution fee refund of a GM request
+    #[instrument(skip(self))]
+    pub async fn update_gas_profile_refund(
+        &self,
+        tx_hash: &str,
+        keeper_tx_hash: &str,
+        keeper_gas_price: Decimal,
+        execution_fee_refund: Decimal,
+    ) -> Result<(), sqlx::Error> {
+        let updated = execution_costs_queries::update_gas_profile_refund(&self.pool, tx_hash, keeper_tx_hash, keeper_gas_price, execution_fee_refund).await?;
+        debug!(updated = updated, "Gas profile refund recorded");
+        Ok(())
+    }
+
+    /// Net the refunded share of a GM request's execution fee out of its recorded execution cost
+    #[instrument(skip(self))]
+    pub async fn apply_execution_fee_refund(&self, tx_hash: &str, refunded_fraction: Decimal) -> Result<(), sqlx::Error> {
+        let updated = execution_costs_queries::apply_execution_fee_refund(&self.pool, tx_hash, refunded_fraction).await?;
+        debug!(updated = updated, "Execution fee refund netted from execution cost");
+        Ok(())
+    }
+
+    /// Get gas usage and execution fee utilization percentiles per action type and market since the given time
+    #[instrument(skip(self))]
+    pub async fn get_gas_profile_summary_since(&self, since: DateTime<Utc>) -> Result<Vec<GasProfileSummaryModel>, sqlx::Error> {
+        let summary = execution_costs_queries::get_gas_profile_summary_since(&self.read_pool, since).await?;
+        debug!(count = summary.len(), "Fetched gas profile summary");
+        Ok(summary)
+    }
+
+    /// Store dYdX funding rates, skipping ones already recorded
+    #[instrument(skip(self, rates), fields(count = rates.len()))]
+    pub async fn insert_funding_rates(&self, rates: &[NewFundingRateModel]) -> Result<u64, sqlx::Error> {
+        let inserted = funding_rates_queries::insert_funding_rates(&self.pool, rates).await?;
+        debug!(inserted = inserted, "Funding rates inserted");
+        Ok(inserted)
+    }
+
+    /// Get the most recent stored funding timestamp for a dYdX ticker
+    #[instrument(skip(self))]
+    pub async fn get_latest_funding_rate_timestamp(&self, ticker: &str) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+        funding_rates_queries::get_latest_funding_rate_timestamp(&self.pool, ticker).await
+    }
+
+    /// Get the mean hourly funding rate per dYdX ticker since the given time
+    #[instrument(skip(self))]
+    pub async fn get_average_funding_rates_since(&self, since: DateTime<Utc>) -> Result<HashMap<String, Decimal>, sqlx::Error> {
+        let rates = funding_rates_queries::get_average_funding_rates_since(&self.read_pool, since).await?;
+        debug!(count = rates.len(), "Fetched average funding rates");
+        Ok(rates)
+    }
+
+    /// Store a snapshot of GM pool incentive emissions
+    #[instrument(skip(self, incentives), fields(count = incentives.len()))]
+    pub async fn insert_market_incentives(&self, incentives: &[NewMarketIncentiveModel]) -> Result<(), sqlx::Error> {
+        market_incentives_queries::insert_market_incentives(&self.pool, incentives).await?;
+        debug!("Market incentives inserted");
+        Ok(())
+    }
+
+    /// Get the latest incentive emission rate (USD per hour) per market recorded since the given time
+    #[instrument(skip(self))]
+    pub async fn get_market_incentive_rates_since(&self, since: DateTime<Utc>) -> Result<HashMap<Address, Decimal>, sqlx::Error> {
+        let rates_by_id = market_incentives_queries::get_latest_incentive_rates_since(&self.read_pool, since).await?;
+        let rates: HashMap<Address, Decimal> = self.market_id_map.iter()
+            .filter_map(|(address, id)| rates_by_id.get(id).map(|rate| (*address, *rate)))
+            .collect();
+        debug!(count = rates.len(), "Fetched market incentive rates");
+        Ok(rates)
+    }
+
+    /// Insert liquidations, skipping ones already recorded. Returns the number inserted.
+    #[instrument(skip(self, liquidations), fields(count = liquidations.len()))]
+    pub async fn insert_liquidations(&self, liquidations: &[NewLiquidationModel]) -> Result<u64, sqlx::Error> {
+        let inserted = liquidations_queries::insert_liquidations(&self.pool, liquidations).await?;
+        debug!(inserted = inserted, "Liquidations inserted");
+        Ok(inserted)
+    }
+
+    /// Get the highest block a liquidation was recorded at, where ingestion resumes
+    #[instrument(skip(self))]
+    pub async fn get_latest_liquidation_block(&self) -> Result<Option<u64>, sqlx::Error> {
+        let block = liquidations_queries::get_latest_liquidation_block(&self.pool).await?;
+        Ok(block.map(|block| block as u64))
+    }
+
+    /// Get per-market liquidation totals since the given time
+    #[instrument(skip(self))]
+    pub async fn get_liquidation_totals_since(&self, since: DateTime<Utc>) -> Result<HashMap<Address, LiquidationTotalsModel>, sqlx::Error> {
+        let totals_by_id: HashMap<i32, LiquidationTotalsModel> = liquidations_queries::get_liquidation_totals_since(&self.read_pool, since).await?
+            .into_iter()
+            .map(|totals| (totals.market_id, totals))
+            .collect();
+        let totals: HashMap<Address, LiquidationTotalsModel> = self.market_id_map.iter()
+            .filter_map(|(address, id)| totals_by_id.get(id).map(|totals| (*address, totals.clone())))
+            .collect();
+        debug!(count = totals.len(), "Fetched liquidation totals");
+        Ok(totals)
+    }
+
+    /// Insert the signals of one signed payload. Returns false when the payload was already recorded.
+    #[instrument(skip(self, signals), fields(count = signals.len()))]
+    pub async fn insert_external_signals(&self, signals: &[NewExternalSignalModel]) -> Result<bool, sqlx::Error> {
+        let inserted = external_signals_queries::insert_external_signals(&self.pool, signals).await?;
+        debug!(inserted = inserted, "External signals inserted");
+        Ok(inserted)
+    }
+
+    /// Fetch each signer's latest active signal of every tracked market, keyed by market address
+    #[instrument(skip(self))]
+    pub async fn get_active_external_signals(&self) -> Result<HashMap<Address, Vec<ExternalSignalModel>>, sqlx::Error> {
+        let address_by_id: HashMap<i32, Address> = self.market_id_map.iter().map(|(address, id)| (*id, *address)).collect();
+        let mut signals: HashMap<Address, Vec<ExternalSignalModel>> = HashMap::new();
+        for signal in external_signals_queries::get_active_external_signals(&self.read_pool, self.clock.now()).await? {
+            if let Some(address) = address_by_id.get(&signal.market_id) {
+                signals.entry(*address).or_default().push(signal);
+            }
+        }
+        debug!(markets = signals.len(), "Fetched active external signals");
+        Ok(signals)
+    }
+
+    /// Fetch every runtime configuration override of this account
+    #[instrument(skip(self))]
+    pub async fn get_config_overrides(&self) -> Result<Vec<ConfigOverrideModel>, sqlx::Error> {
+        let overrides = config_overrides_queries::get_config_overrides(&self.pool, &self.account_id).await?;
+        debug!(count = overrides.len(), "Fetched config overrides");
+        Ok(overrides)
+    }
+
+    /// Fetch the enabled compliance rules of this account
+    #[instrument(skip(self))]
+    pub async fn get_compliance_rules(&self) -> Result<Vec<ComplianceRuleModel>, sqlx::Error> {
+        let rules = compliance_rules_queries::get_enabled_compliance_rules(&self.pool, &self.account_id).await?;
+        debug!(count = rules.len(), "Fetched compliance rules");
+        Ok(rules)
+    }
+
+    /// Fetch the enabled market universes of this account
+    #[instrument(skip(self))]
+    pub async fn get_market_universes(&self) -> Result<Vec<MarketUniverseModel>, sqlx::Error> {
+        let universes = market_universes_queries::get_enabled_market_universes(&self.pool, &self.account_id).await?;
+        debug!(count = universes.len(), "Fetched market universes");
+        Ok(universes)
+    }
+
+    /// Set a runtime configuration override, picked up by running components on their next refresh
+    #[instrument(skip(self))]
+    pub async fn set_config_override(&self, key: &str, value: &str) -> Result<(), sqlx::Error> {
+        config_overrides_queries::upsert_config_override(&self.pool, &self.account_id, key, value).await?;
+        debug!("Config override set");
+        Ok(())
+    }
+
+    /// Remove a runtime configuration override, returning whether one existed
+    #[instrument(skip(self))]
+    pub async fn delete_config_override(&self, key: &str) -> Result<bool, sqlx::Error> {
+        let deleted = config_overrides_queries::delete_config_override(&self.pool, &self.account_id, key).await?;
+        debug!(deleted = deleted, "Config override removed");
+        Ok(deleted)
+    }
+
+    /// Register a transaction sent by the bot's wallet, so the wallet watchdog can tell it from external ones
+    #[instrument(skip(self))]
+    pub async fn record_wallet_transaction(&self, wallet_address: Address, tx_hash: H256, nonce: u64) -> Result<(), sqlx::Error> {
+        let tx = NewWalletTransactionModel {
+            wallet_address: format!("{:?}", wallet_address),
+            tx_hash: format!("{:?}", tx_hash),
+            nonce: nonce as i64,
+        };
+        wallet_transactions_queries::insert_wallet_transaction(&self.pool, &tx).await?;
+        debug!("Wallet transaction registered");
+        Ok(())
+    }
+
+    /// Fetch the registered transactions of a wallet with nonces in [from_nonce, to_nonce)
+    #[instrument(skip(self))]
+    pub async fn get_wallet_transactions_by_nonce(&self, wallet_address: Address, from_nonce: u64, to_nonce: u64) -> Result<Vec<WalletTransactionModel>, sqlx::Error> {
+        let transactions = wallet_transactions_queries::get_wallet_transactions_by_nonce(
+            &self.pool,
+            &format!("{:?}", wallet_address),
+            from_nonce as i64,
+            to_nonce as i64,
+        ).await?;
+        debug!(count = transactions.len(), "Fetched registered wallet transactions");
+        Ok(transactions)
+    }
+
+    /// Fetch the most recent market state for a single market
+    #[instrument(skip(self, market_address))]
+    pub async fn get_latest_market_state(&self, market_address: Address) -> Result<Option<MarketStateModel>, sqlx::Error> {
+        let market_id = self.market_id_map.get(&market_address)
+            .ok_or_else(|| sqlx::Error::RowNotFound)?;
+        let state = market_states_queries::get_latest_market_state_for_market(&self.pool, *market_id).await?;
+        debug!(market = ?market_address, found = state.is_some(), "Fetched latest market state");
+        Ok(state)
+    }
+
+    /// Persist an execution plan of a rebalance and its ordered actions, all starting as planned, unless a plan with the
+    /// same hash was created since `duplicate_since`
+    #[instrument(skip(self, actions), fields(action_count = actions.len()))]
+    pub async fn create_execution_plan(
+        &self,
+        source: &str,
+        traceparent: Option<&str>,
+        plan_hash: &str,
+        duplicate_since: Option<DateTime<Utc>>,
+        rebalance_id: Option<i32>,
+        actions: &[NewExecutionPlanActionModel],
+    ) -> Result<PlanCreation, sqlx::Error> {
+        let creation = execution_plans_queries::insert_execution_plan(&self.pool, &self.account_id, source, traceparent, plan_hash, duplicate_since, rebalance_id, actions).await?;
+        match &creation {
+            PlanCreation::Created(plan_id) => info!(plan_id = plan_id, "Execution plan created"),
+            PlanCreation::Duplicate { plan_id, created_at } => warn!(duplicate_of = plan_id, created_at = %created_at, "Identical execution plan already created, not creating another"),
+        }
+        Ok(creation)
+    }
+
+    /// Fetch all execution plans that have not run to completion
+    #[instrument(skip(self))]
+    pub async fn get_incomplete_execution_plans(&self) -> Result<Vec<ExecutionPlanModel>, sqlx::Error> {
+        let plans = execution_plans_queries::get_incomplete_execution_plans(&self.pool, &self.account_id).await?;
+        debug!(count = plans.len(), "Fetched incomplete execution plans");
+        Ok(plans)
+    }
+
+    /// Fetch the actions of an execution plan in execution order
+    #[instrument(skip(self))]
+    pub async fn get_execution_plan_actions(&self, plan_id: i32) -> Result<Vec<ExecutionPlanActionModel>, sqlx::Error> {
+        let actions = execution_plans_queries::get_execution_plan_actions(&self.pool, &self.account_id, plan_id).await?;
+        debug!(count = actions.len(), "Fetched execution plan actions");
+        Ok(actions)
+    }
+
+    /// Mark an execution plan action as submitted, recording the balance it spends from
+    #[instrument(skip(self))]
+    pub async fn mark_execution_action_submitted(&self, action_id: i32, spent_balance_before: Decimal) -> Result<(), sqlx::Error> {
+        execution_plans_queries::mark_action_submitted(&self.pool, action_id, spent_balance_before).await?;
+        debug!(action_id = action_id, "Execution plan action submitted");
+        Ok(())
+    }
+
+    /// Update the status of an execution plan action, optionally recording an error
+    #[instrument(skip(self))]
+    pub async fn update_execution_action_status(&self, action_id: i32, status: ExecutionStatus, error: Option<String>) -> Result<(), sqlx::Error> {
+        execution_plans_queries::update_action_status(&self.pool, action_id, status.as_str(), error.as_deref()).await?;
+        debug!(action_id = action_id, status = status.as_str(), "Execution plan action status updated");
+        Ok(())
+    }
+
+    /// Mark an execution plan as completed
+    #[instrument(skip(self))]
+    pub async fn complete_execution_plan(&self, plan_id: i32) -> Result<(), sqlx::Error> {
+        execution_plans_queries::complete_execution_plan(&self.pool, plan_id).await?;
+        info!(plan_id = plan_id, "Execution plan completed");
+        Ok(())
+    }
+
+    /// Fetch the timestamps of the latest ingested market state and token price, for pipeline health checks
+    #[instrument(skip(self))]
+    pub async fn get_latest_ingest_timestamps(&self) -> Result<(Option<DateTime<Utc>>, Option<DateTime<Utc>>), sqlx::Error> {
+        let market_state_timestamp = market_states_queries::get_latest_market_state_timestamp(&self.read_pool).await?;
+        let token_price_timestamp = token_prices_queries::get_latest_token_price_timestamp(&self.read_pool).await?;
+        debug!(market_state_timestamp = ?market_state_timestamp, token_price_timestamp = ?token_price_timestamp, "Fetched latest ingest timestamps");
+        Ok((market_state_timestamp, token_price_timestamp))
+    }
+
+    /// Fetch the trace contexts of the collection cycles behind the latest market state and token price of each
+    /// market and token, so a strategy run can link its trace to the data it plans from
+    #[instrument(skip(self))]
+    pub async fn get_latest_ingest_traceparents(&self) -> Result<Vec<String>, sqlx::Error> {
+        let mut traceparents = market_states_queries::get_latest_market_state_traceparents(&self.read_pool).await?;
+        traceparents.extend(token_prices_queries::get_latest_token_price_traceparents(&self.read_pool).await?);
+        traceparents.sort_unstable();
+        traceparents.dedup();
+        debug!(count = traceparents.len(), "Fetched latest ingest trace contexts");
+        Ok(traceparents)
+    }
+
+    /// Store historical market states pulled from a backfill source, returning the number inserted
+    #[instrument(skip(self, states), fields(count = states.len()))]
+    pub async fn insert_backfilled_market_states(&self, states: &[NewBackfilledMarketStateModel], source: &str) -> Result<u64, sqlx::Error> {
+        let inserted = market_states_queries::insert_backfilled_market_states(&self.pool, states, source).await?;
+        debug!(inserted = inserted, "Backfilled market states inserted");
+        Ok(inserted)
+    }
+
+    /// Store historical token prices pulled from a backfill source, returning the number inserted
+    #[instrument(skip(self, prices), fields(count = prices.len()))]
+    pub async fn insert_backfilled_token_prices(&self, prices: &[NewTokenPriceModel], source: &str) -> Result<u64, sqlx::Error> {
+        let inserted = token_prices_queries::insert_backfilled_token_prices(&self.pool, prices, source).await?;
+        debug!(inserted = inserted, "Backfilled token prices inserted");
+        Ok(inserted)
+    }
+
+    /// Fetch the earliest stored market state timestamp per market, backfills stop there to avoid overlapping data
+    #[instrument(skip(self))]
+    pub async fn get_earliest_market_state_timestamps(&self) -> Result<HashMap<Address, DateTime<Utc>>, sqlx::Error> {
+        let timestamps_by_id = market_states_queries::get_earliest_market_state_timestamps(&self.read_pool).await?;
+        let timestamps: HashMap<Address, DateTime<Utc>> = self.market_id_map.iter()
+            .filter_map(|(address, id)| timestamps_by_id.get(id).map(|timestamp| (*address, *timestamp)))
+            .collect();
+        debug!(count = timestamps.len(), "Fetched earliest market state timestamps");
+        Ok(timestamps)
+    }
+
+    /// Fetch the earliest stored token price timestamp per token, backfills stop there to avoid overlapping data
+    #[instrument(skip(self))]
+    pub async fn get_earliest_token_price_timestamps(&self) -> Result<HashMap<Address, DateTime<Utc>>, sqlx::Error> {
+        let timestamps_by_id = token_prices_queries::get_earliest_token_price_timestamps(&self.read_pool).await?;
+        let timestamps: HashMap<Address, DateTime<Utc>> = self.token_id_map.iter()
+            .filter_map(|(address, id)| timestamps_by_id.get(id).map(|timestamp| (*address, *timestamp)))
+            .collect();
+        debug!(count = timestamps.len(), "Fetched earliest token price timestamps");
+        Ok(timestamps)
+    }
+
+    /// Record a market's halt/deprecation status, logging when it changes
+    #[instrument(skip(self))]
+    pub async fn set_market_deprecated(&self, market_address: &str, deprecated: bool) -> Result<(), sqlx::Error> {
+        let changed = markets_queries::set_market_deprecated(&self.pool, market_address, deprecated).await?;
+        if changed && deprecated {
+            warn!(market_address = %market_address, "Market marked deprecated, excluded from allocation until re-enabled");
+        } else if changed {
+            info!(market_address = %market_address, "Market deprecation cleared");
+        }
+        Ok(())
+    }
+
+    /// Get the addresses of all markets currently flagged as deprecated
+    #[instrument(skip(self))]
+    pub async fn get_deprecated_markets(&self) -> Result<Vec<Address>, sqlx::Error> {
+        let deprecated_ids = markets_queries::get_deprecated_market_ids(&self.pool).await?;
+        let deprecated_markets: Vec<Address> = self.market_id_map.iter()
+            .filter(|(_, id)| deprecated_ids.contains(id))
+            .map(|(address, _)| *address)
+            .collect();
+        debug!(count = deprecated_markets.len(), "Fetched deprecated markets");
+        Ok(deprecated_markets)
+    }
+
     /// Convert raw token model to new token model
     #[instrument(skip(self, raw_token))]
     pub async fn convert_raw_token_to_new_token(&mut self, raw_token: RawTokenModel) -> Result<NewTokenModel, sqlx::Error> {
@@ -574,8 +1603,6 @@ impl DbManager {
     /// Convert raw market model to new market model
     #[instrument(skip(self, raw_market))]
     pub async fn convert_raw_market_to_new_market(&mut self, raw_market: RawMarketModel) -> Result<Option<NewMarketModel>, sqlx::Error> {
-        self.refresh_id_maps().await?;
-
         // Parse addresses
         let index_token_address = raw_market.index_token_address.parse::<Address>()
             .map_err(|_| sqlx::Error::Decode("Invalid index token address".into()))?;
@@ -585,11 +1612,11 @@ impl DbManager {
             .map_err(|_| sqlx::Error::Decode("Invalid short token address".into()))?;
 
         // Check if all required token IDs exist
-        if let (Some(&index_token_id), Some(&long_token_id), Some(&short_token_id)) = (
-            self.token_id_map.get(&index_token_address),
-            self.token_id_map.get(&long_token_address),
-            self.token_id_map.get(&short_token_address)
-        ) {
+        let mut refreshed = self.sync_id_maps().await?;
+        let index_token_id = self.lookup_token_id(&index_token_address, &mut refreshed).await?;
+        let long_token_id = self.lookup_token_id(&long_token_address, &mut refreshed).await?;
+        let short_token_id = self.lookup_token_id(&short_token_address, &mut refreshed).await?;
+        if let (Some(index_token_id), Some(long_token_id), Some(short_token_id)) = (index_token_id, long_token_id, short_token_id) {
             Ok(Some(NewMarketModel {
                 address: raw_market.address,
                 index_token_id,
@@ -598,9 +1625,9 @@ impl DbManager {
             }))
         } else {
             debug!(
-                index_exists = self.token_id_map.contains_key(&index_token_address),
-                long_exists = self.token_id_map.contains_key(&long_token_address),
-                short_exists = self.token_id_map.contains_key(&short_token_address),
+                index_exists = index_token_id.is_some(),
+                long_exists = long_token_id.is_some(),
+                short_exists = short_token_id.is_some(),
                 "Cannot convert raw market - missing token IDs"
             );
             Ok(None)
@@ -610,20 +1637,20 @@ impl DbManager {
     /// Convert raw token price model to new token price model
     #[instrument(skip(self, raw_token_price))]
     pub async fn convert_raw_token_price_to_new_token_price(&mut self, raw_token_price: RawTokenPriceModel) -> Result<Option<NewTokenPriceModel>, sqlx::Error> {
-        self.refresh_id_maps().await?;
-
         // Parse token address
         let token_address = raw_token_price.token_address.parse::<Address>()
             .map_err(|_| sqlx::Error::Decode("Invalid token address".into()))?;
 
         // Check if token ID exists
-        if let Some(&token_id) = self.token_id_map.get(&token_address) {
+        let mut refreshed = self.sync_id_maps().await?;
+        if let Some(token_id) = self.lookup_token_id(&token_address, &mut refreshed).await? {
             Ok(Some(NewTokenPriceModel {
                 token_id,
                 timestamp: raw_token_price.timestamp,
                 min_price: raw_token_price.min_price,
                 max_price: raw_token_price.max_price,
                 mid_price: raw_token_price.mid_price,
+                traceparent: raw_token_price.traceparent,
             }))
         } else {
             debug!(
@@ -637,14 +1664,13 @@ impl DbManager {
     /// Convert raw market state model to new market state model
     #[instrument(skip(self, raw_market_state))]
     pub async fn convert_raw_market_state_to_new_market_state(&mut self, raw_market_state: RawMarketStateModel) -> Result<Option<NewMarketStateModel>, sqlx::Error> {
-        self.refresh_id_maps().await?;
-
         // Parse market address
         let market_address = raw_market_state.market_address.parse::<Address>()
             .map_err(|_| sqlx::Error::Decode("Invalid market address".into()))?;
 
         // Check if market ID exists
-        if let Some(&market_id) = self.market_id_map.get(&market_address) {
+        let mut refreshed = self.sync_id_maps().await?;
+        if let Some(market_id) = self.lookup_market_id(&market_address, &mut refreshed).await? {
             Ok(Some(NewMarketStateModel {
                 market_id,
                 timestamp: raw_market_state.timestamp,
@@ -676,6 +1702,14 @@ impl DbManager {
                 fees_swap: raw_market_state.fees_swap,
                 fees_borrowing: raw_market_state.fees_borrowing,
                 fees_total: raw_market_state.fees_total,
+                max_pool_amount_long: raw_market_state.max_pool_amount_long,
+                max_pool_amount_short: raw_market_state.max_pool_amount_short,
+                max_open_interest_long: raw_market_state.max_open_interest_long,
+                max_open_interest_short: raw_market_state.max_open_interest_short,
+                taker_buy_volume: raw_market_state.taker_buy_volume,
+                taker_sell_volume: raw_market_state.taker_sell_volume,
+                taker_flow_imbalance: raw_market_state.taker_flow_imbalance,
+                traceparent: raw_market_state.traceparent,
             }))
         } else {
             debug!(
@@ -685,4 +1719,30 @@ impl DbManager {
             Ok(None)
         }
     }
+}
+
+/// Fill gaps with the previous recorded value, leading gaps with the first recorded value (zero when nothing is recorded)
+fn fill_missing(values: Vec<Option<Decimal>>) -> Vec<Decimal> {
+    let mut last = values.iter().flatten().next().copied().unwrap_or_default();
+    values.into_iter()
+        .map(|value| {
+            if let Some(value) = value {
+                last = value;
+            }
+            last
+        })
+        .collect()
+}
+
+/// Store each market's canonical display name on its row, for SQL consumers (market_overview, dashboards) to join
+async fn store_market_display_names(pool: &PgPool) -> Result<(), sqlx::Error> {
+    let names: Vec<(i32, String)> = markets_queries::get_market_descriptors(pool).await?
+        .into_iter()
+        .map(|(market_id, descriptor)| (market_id, descriptor.display_name()))
+        .collect();
+    let renamed = markets_queries::set_market_display_names(pool, &names).await?;
+    if renamed > 0 {
+        debug!(renamed = renamed, "Market display names stored");
+    }
+    Ok(())
 }
\ No newline at end of file
@@ -3,3 +3,4 @@ pub mod schema;
 pub mod models;
 pub mod queries;
 pub mod db_manager;
+pub mod subscriber;
@@ -0,0 +1,56 @@
+use rust_decimal::Decimal;
+use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+
+/// Progress of a USDC transfer between the Arbitrum wallet and the dYdX chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BridgeTransferStatus {
+    Pending, // Sent on the source chain, funds not yet delivered
+    Arrived, // Funds delivered on the destination chain
+    Failed,  // Route abandoned or errored, funds may need recovering by hand
+}
+
+impl BridgeTransferStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BridgeTransferStatus::Pending => "Pending",
+            BridgeTransferStatus::Arrived => "Arrived",
+            BridgeTransferStatus::Failed => "Failed",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "Pending" => Some(BridgeTransferStatus::Pending),
+            "Arrived" => Some(BridgeTransferStatus::Arrived),
+            "Failed" => Some(BridgeTransferStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct BridgeTransferModel {
+    pub id: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub direction: String,
+    pub amount: Decimal,
+    pub expected_amount_out: Decimal,
+    pub source_chain_id: String,
+    pub source_tx_hash: String,
+    pub estimated_duration_secs: i64,
+    pub status: String,
+    pub arrived_at: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewBridgeTransferModel {
+    pub direction: String,
+    pub amount: Decimal,
+    pub expected_amount_out: Decimal,
+    pub source_chain_id: String,
+    pub source_tx_hash: String,
+    pub estimated_duration_secs: i64,
+}
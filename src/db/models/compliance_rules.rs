@@ -0,0 +1,10 @@
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ComplianceRuleModel {
+    pub rule_id: String,
+    pub rule_type: String,
+    pub value: String,
+    pub updated_at: DateTime<Utc>,
+}
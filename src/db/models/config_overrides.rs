@@ -0,0 +1,9 @@
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ConfigOverrideModel {
+    pub key: String,
+    pub value: String,
+    pub updated_at: DateTime<Utc>,
+}
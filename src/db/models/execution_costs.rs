@@ -0,0 +1,94 @@
+use rust_decimal::Decimal;
+use sqlx::FromRow;
+
+/// Venue an execution cost was paid on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionVenue {
+    Gmx,
+    ParaSwap,
+    Weth, // Wrapping / unwrapping native ETH
+    SkipGo, // Bridging collateral to and from dYdX
+}
+
+impl ExecutionVenue {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExecutionVenue::Gmx => "gmx",
+            ExecutionVenue::ParaSwap => "paraswap",
+            ExecutionVenue::Weth => "weth",
+            ExecutionVenue::SkipGo => "skip_go",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "gmx" => Some(ExecutionVenue::Gmx),
+            "paraswap" => Some(ExecutionVenue::ParaSwap),
+            "weth" => Some(ExecutionVenue::Weth),
+            "skip_go" => Some(ExecutionVenue::SkipGo),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NewExecutionCostModel {
+    pub venue: String,
+    pub action_type: String,
+    pub tx_hash: Option<String>,
+    pub gas_cost_usd: Decimal,
+    pub execution_fee_usd: Decimal,
+    pub slippage_usd: Decimal, // Value lost versus mid prices, not part of the fee budget
+}
+
+/// Cumulative execution spend for one venue and action type
+#[derive(Debug, Clone, FromRow)]
+pub struct ExecutionSpendModel {
+    pub venue: String,
+    pub action_type: String,
+    pub tx_count: i64,
+    pub total_cost_usd: Decimal,
+}
+
+/// Execution fee estimate sampled before a GM request, used to build the rolling fee baseline
+#[derive(Debug, Clone)]
+pub struct NewGasPriceSampleModel {
+    pub action_type: String,
+    pub gas_price: Decimal,
+    pub execution_fee: Decimal,
+}
+
+/// On-chain gas usage of a GM request creation transaction and its keeper execution fee
+#[derive(Debug, Clone)]
+pub struct NewGasProfileModel {
+    pub action_type: String,
+    pub market_id: Option<i32>,
+    pub tx_hash: String,
+    pub gas_used: Decimal,
+    pub l1_gas_used: Option<Decimal>,
+    pub network_gas_price: Decimal,
+    pub effective_gas_price: Decimal,
+    pub max_fee_per_gas_buffer: Decimal,
+    pub estimated_gas_limit: Decimal,
+    pub execution_fee: Decimal,
+    pub quote_base_fee: Option<Decimal>,     // Latest block base fee when the fee was estimated (wei)
+    pub inclusion_base_fee: Option<Decimal>, // Base fee of the block the request was included in (wei)
+}
+
+/// Empirical gas usage and execution fee utilization for one action type and market
+#[derive(Debug, Clone, FromRow)]
+pub struct GasProfileSummaryModel {
+    pub action_type: String,
+    pub market_id: Option<i32>,
+    pub tx_count: i64,
+    pub refunded_count: i64,
+    pub avg_gas_used: Option<Decimal>,
+    pub avg_l1_gas_share: Option<Decimal>,               // Share of gas_used spent on L1 data
+    pub p50_inclusion_price_ratio: Option<Decimal>,      // Effective gas price / network gas price at estimate
+    pub p95_inclusion_price_ratio: Option<Decimal>,
+    pub p50_fee_utilization: Option<Decimal>,            // (execution fee - refund) / execution fee
+    pub p95_fee_utilization: Option<Decimal>,
+    pub p95_required_fee_buffer: Option<Decimal>,        // Keeper cost / (estimated gas limit * network gas price)
+    pub p95_base_fee_movement: Option<Decimal>,          // Inclusion block base fee / base fee at estimate
+    pub p95_keeper_gas_limit_utilization: Option<Decimal>, // Implied keeper gas / estimated gas limit
+}
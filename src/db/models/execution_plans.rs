@@ -0,0 +1,185 @@
+use rust_decimal::Decimal;
+use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+use ethers::types::Address;
+use ethers::utils::keccak256;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::db::models::trades::TradeActionType;
+use crate::gm_token_txs::types::{GmTxRequest, GmDepositRequest, GmWithdrawalRequest, GmShiftRequest};
+
+/// Execution status of a plan action, persisted around submission so an interrupted plan can be resumed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionStatus {
+    Planned,    // Not yet sent
+    Submitted,  // About to be or already sent, outcome unknown until confirmed
+    Confirmed,  // Transaction landed (keeper execution is tracked on the trade)
+    Failed,
+    Compensated, // Executed, then rolled back by a compensation plan after a dependent action failed
+}
+
+impl ExecutionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExecutionStatus::Planned => "Planned",
+            ExecutionStatus::Submitted => "Submitted",
+            ExecutionStatus::Confirmed => "Confirmed",
+            ExecutionStatus::Failed => "Failed",
+            ExecutionStatus::Compensated => "Compensated",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "Planned" => Some(ExecutionStatus::Planned),
+            "Submitted" => Some(ExecutionStatus::Submitted),
+            "Confirmed" => Some(ExecutionStatus::Confirmed),
+            "Failed" => Some(ExecutionStatus::Failed),
+            "Compensated" => Some(ExecutionStatus::Compensated),
+            _ => None,
+        }
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, ExecutionStatus::Confirmed | ExecutionStatus::Failed | ExecutionStatus::Compensated)
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ExecutionPlanModel {
+    pub id: i32,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub source: String,
+    pub traceparent: Option<String>, // Trace context of the run that created the plan
+    pub plan_hash: Option<String>,
+    pub rebalance_id: Option<i32>, // Rebalance the plan's legs are recorded against, also when it is resumed
+}
+
+/// Outcome of persisting a new plan
+#[derive(Debug, Clone)]
+pub enum PlanCreation {
+    Created(i32),
+    Duplicate { plan_id: i32, created_at: DateTime<Utc> }, // An identical plan was created within the duplicate window
+}
+
+/// Deterministic content hash of a plan: its source and its actions with their amounts, independent of action order.
+/// Amounts are normalized so equal values hash equally whatever their scale.
+pub fn plan_hash(source: &str, actions: &[NewExecutionPlanActionModel]) -> String {
+    let amount = |value: Option<Decimal>| value.map(|v| v.normalize().to_string()).unwrap_or_default();
+    let mut lines: Vec<String> = actions.iter()
+        .map(|action| format!(
+            "{}|{}|{}|{}|{}|{}|{}|{}",
+            action.action_type,
+            action.market_id,
+            action.to_market_id.map(|id| id.to_string()).unwrap_or_default(),
+            amount(action.long_token_amount),
+            amount(action.short_token_amount),
+            amount(action.market_token_amount),
+            action.initial_long_token.as_deref().unwrap_or_default().to_lowercase(),
+            action.initial_short_token.as_deref().unwrap_or_default().to_lowercase(),
+        ))
+        .collect();
+    lines.sort();
+    format!("0x{}", hex::encode(keccak256(format!("{}\n{}", source, lines.join("\n")))))
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ExecutionPlanActionModel {
+    pub id: i32,
+    pub plan_id: i32,
+    pub seq: i32,
+    pub updated_at: DateTime<Utc>,
+    pub action_type: String,
+    pub status: String,
+    pub market_id: i32,
+    pub to_market_id: Option<i32>,
+    pub long_token_amount: Option<Decimal>,
+    pub short_token_amount: Option<Decimal>,
+    pub market_token_amount: Option<Decimal>,
+    pub spent_balance_before: Option<Decimal>, // Balance of the token the action spends, read just before submission
+    pub submitted_at: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+    pub initial_long_token: Option<String>,  // Token swapped into the long token through GMX markets, for deposits
+    pub initial_short_token: Option<String>, // Token swapped into the short token through GMX markets, for deposits
+}
+
+impl ExecutionPlanActionModel {
+    /// Rebuild the GM request this action was created from
+    pub fn to_request(&self, market_id_map: &HashMap<Address, i32>) -> Option<GmTxRequest> {
+        let market_address = |id: i32| market_id_map.iter().find(|(_, market_id)| **market_id == id).map(|(address, _)| *address);
+        let market = market_address(self.market_id)?;
+        match TradeActionType::parse(&self.action_type)? {
+            TradeActionType::GmDeposit => Some(GmTxRequest::Deposit(GmDepositRequest {
+                market,
+                long_amount: self.long_token_amount.unwrap_or(Decimal::ZERO),
+                short_amount: self.short_token_amount.unwrap_or(Decimal::ZERO),
+                initial_long_token: self.initial_long_token.as_deref().map(Address::from_str).transpose().ok()?,
+                initial_short_token: self.initial_short_token.as_deref().map(Address::from_str).transpose().ok()?,
+            })),
+            TradeActionType::GmWithdrawal => Some(GmTxRequest::Withdrawal(GmWithdrawalRequest {
+                market,
+                amount: self.market_token_amount?,
+            })),
+            TradeActionType::GmShift => Some(GmTxRequest::Shift(GmShiftRequest {
+                from_market: market,
+                to_market: market_address(self.to_market_id?)?,
+                amount: self.market_token_amount?,
+            })),
+            TradeActionType::ClaimRewards | TradeActionType::ClaimUiFees | TradeActionType::BridgeCollateral => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NewExecutionPlanActionModel {
+    pub action_type: String,
+    pub market_id: i32,
+    pub to_market_id: Option<i32>,
+    pub long_token_amount: Option<Decimal>,
+    pub short_token_amount: Option<Decimal>,
+    pub market_token_amount: Option<Decimal>,
+    pub initial_long_token: Option<String>,
+    pub initial_short_token: Option<String>,
+}
+
+impl NewExecutionPlanActionModel {
+    /// Build a plan action from a GM request, None for requests that are not persisted in plans (reward claims)
+    /// or that reference markets missing from the ID map
+    pub fn from_request(request: &GmTxRequest, market_id_map: &HashMap<Address, i32>) -> Option<Self> {
+        match request {
+            GmTxRequest::Deposit(deposit) => Some(Self {
+                action_type: TradeActionType::GmDeposit.as_str().to_string(),
+                market_id: *market_id_map.get(&deposit.market)?,
+                to_market_id: None,
+                long_token_amount: Some(deposit.long_amount),
+                short_token_amount: Some(deposit.short_amount),
+                market_token_amount: None,
+                initial_long_token: deposit.initial_long_token.map(|token| format!("{:?}", token)),
+                initial_short_token: deposit.initial_short_token.map(|token| format!("{:?}", token)),
+            }),
+            GmTxRequest::Withdrawal(withdrawal) => Some(Self {
+                action_type: TradeActionType::GmWithdrawal.as_str().to_string(),
+                market_id: *market_id_map.get(&withdrawal.market)?,
+                to_market_id: None,
+                long_token_amount: None,
+                short_token_amount: None,
+                market_token_amount: Some(withdrawal.amount),
+                initial_long_token: None,
+                initial_short_token: None,
+            }),
+            GmTxRequest::Shift(shift) => Some(Self {
+                action_type: TradeActionType::GmShift.as_str().to_string(),
+                market_id: *market_id_map.get(&shift.from_market)?,
+                to_market_id: Some(*market_id_map.get(&shift.to_market)?),
+                long_token_amount: None,
+                short_token_amount: None,
+                market_token_amount: Some(shift.amount),
+                initial_long_token: None,
+                initial_short_token: None,
+            }),
+            GmTxRequest::ClaimRewards(_) | GmTxRequest::ClaimUiFees(_) => None,
+        }
+    }
+}
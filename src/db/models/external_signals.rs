@@ -0,0 +1,25 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::FromRow;
+
+#[derive(Debug, Clone)]
+pub struct NewExternalSignalModel {
+    pub signer: String,
+    pub market_id: i32,
+    pub expected_return_apr: Option<Decimal>,
+    pub veto: bool,
+    pub issued_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub payload_hash: String,
+}
+
+/// A signer's latest unexpired signal for one market
+#[derive(Debug, Clone, FromRow)]
+pub struct ExternalSignalModel {
+    pub signer: String,
+    pub market_id: i32,
+    pub expected_return_apr: Option<Decimal>,
+    pub veto: bool,
+    pub issued_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
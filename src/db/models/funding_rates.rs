@@ -0,0 +1,18 @@
+use rust_decimal::Decimal;
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct FundingRateModel {
+    pub id: i32,
+    pub ticker: String,
+    pub effective_at: DateTime<Utc>,
+    pub rate: Decimal,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewFundingRateModel {
+    pub ticker: String,
+    pub effective_at: DateTime<Utc>,
+    pub rate: Decimal,
+}
\ No newline at end of file
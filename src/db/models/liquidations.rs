@@ -0,0 +1,24 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::FromRow;
+
+#[derive(Debug, Clone)]
+pub struct NewLiquidationModel {
+    pub market_id: i32,
+    pub block_number: i64,
+    pub tx_hash: String,
+    pub log_index: i32,
+    pub timestamp: DateTime<Utc>,
+    pub is_long: bool,
+    pub size_usd: Decimal,
+}
+
+/// Liquidations of one market over a window
+#[derive(Debug, Clone, FromRow)]
+pub struct LiquidationTotalsModel {
+    pub market_id: i32,
+    pub liquidation_count: i64,
+    pub liquidated_usd: Decimal,
+    pub long_liquidated_usd: Decimal,
+    pub max_hourly_liquidated_usd: Decimal, // Largest amount liquidated within a single hour, the cascade size
+}
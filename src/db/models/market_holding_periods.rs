@@ -0,0 +1,21 @@
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+/// Planner state of one market: when the account last entered and exited it
+#[derive(Debug, Clone, FromRow)]
+pub struct MarketHoldingPeriodModel {
+    pub market_id: i32,
+    pub entered_at: Option<DateTime<Utc>>,
+    pub exited_at: Option<DateTime<Utc>>,
+}
+
+impl MarketHoldingPeriodModel {
+    /// Whether the market was held when the state was last updated
+    pub fn is_held(&self) -> bool {
+        match (self.entered_at, self.exited_at) {
+            (Some(entered_at), Some(exited_at)) => entered_at > exited_at,
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use rust_decimal::Decimal;
+
+#[derive(Debug, Clone)]
+pub struct NewMarketIncentiveModel {
+    pub market_id: i32,
+    pub reward_token: String,
+    pub reward_amount: Decimal,
+    pub period_secs: i32,
+    pub reward_usd_per_hour: Decimal,
+}
\ No newline at end of file
@@ -0,0 +1,20 @@
+use rust_decimal::Decimal;
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+/// Row of the market_overview materialized view, as of its last refresh
+#[derive(Debug, Clone, FromRow)]
+pub struct MarketOverviewModel {
+    pub market_id: i32,
+    pub market_address: String,
+    pub display_name: String,
+    pub deprecated: bool,
+    pub latest_timestamp: DateTime<Utc>,
+    pub gm_price_mid: Option<Decimal>,
+    pub gm_price_change_24h: Option<Decimal>, // Fractional change of the GM mid price over 24h, None without a day of history
+    pub utilization: Option<Decimal>,
+    pub open_interest_usd: Option<Decimal>,
+    pub pool_value_usd: Option<Decimal>,
+    pub fee_apr_24h: Option<Decimal>, // Trailing fees annualized over the current pool value
+    pub fee_apr_7d: Option<Decimal>,
+}
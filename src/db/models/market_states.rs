@@ -43,6 +43,44 @@ pub struct MarketStateModel {
     pub fees_total: Option<Decimal>,
 }
 
+/// A collected GM price with the market's tokens and the token prices recorded at or before it,
+/// everything needed to recompute the price on-chain
+#[derive(Debug, Clone, FromRow)]
+pub struct RecordedGmPriceModel {
+    pub market_state_id: i32,
+    pub timestamp: DateTime<Utc>,
+    pub gm_price_min: Decimal,
+    pub gm_price_max: Decimal,
+    pub market_address: String,
+    pub index_token_address: String,
+    pub long_token_address: String,
+    pub short_token_address: String,
+    pub index_token_decimals: i32,
+    pub long_token_decimals: i32,
+    pub short_token_decimals: i32,
+    pub index_min_price: Decimal,
+    pub index_max_price: Decimal,
+    pub long_min_price: Decimal,
+    pub long_max_price: Decimal,
+    pub short_min_price: Decimal,
+    pub short_max_price: Decimal,
+}
+
+/// A market's pool composition with the pool caps recorded alongside it, what deposit capacity is sized from
+#[derive(Debug, Clone, FromRow)]
+pub struct MarketCapsModel {
+    pub market_id: i32,
+    pub timestamp: DateTime<Utc>,
+    pub pool_long_amount: Option<Decimal>,
+    pub pool_short_amount: Option<Decimal>,
+    pub pool_long_token_usd: Option<Decimal>,
+    pub pool_short_token_usd: Option<Decimal>,
+    pub max_pool_amount_long: Decimal,
+    pub max_pool_amount_short: Decimal,
+    pub max_open_interest_long: Option<Decimal>,
+    pub max_open_interest_short: Option<Decimal>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RawMarketStateModel {
     pub market_address: String,
@@ -75,6 +113,24 @@ pub struct RawMarketStateModel {
     pub fees_swap: Option<Decimal>,
     pub fees_borrowing: Option<Decimal>,
     pub fees_total: Option<Decimal>,
+    #[serde(default)]
+    pub max_pool_amount_long: Option<Decimal>,
+    #[serde(default)]
+    pub max_pool_amount_short: Option<Decimal>,
+    #[serde(default)]
+    pub max_open_interest_long: Option<Decimal>,
+    #[serde(default)]
+    pub max_open_interest_short: Option<Decimal>,
+    #[serde(default)]
+    pub taker_buy_volume: Option<Decimal>,
+    #[serde(default)]
+    pub taker_sell_volume: Option<Decimal>,
+    #[serde(default)]
+    pub taker_flow_imbalance: Option<Decimal>,
+    #[serde(default)]
+    pub deprecated: bool, // Market status flag, recorded on the markets table rather than per state
+    #[serde(skip)]
+    pub traceparent: Option<String>, // Trace context of the collection cycle, taken from the stream entry by the recorder
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -109,6 +165,22 @@ pub struct NewMarketStateModel {
     pub fees_swap: Option<Decimal>,
     pub fees_borrowing: Option<Decimal>,
     pub fees_total: Option<Decimal>,
+    pub max_pool_amount_long: Option<Decimal>,
+    pub max_pool_amount_short: Option<Decimal>,
+    pub max_open_interest_long: Option<Decimal>,
+    pub max_open_interest_short: Option<Decimal>,
+    pub taker_buy_volume: Option<Decimal>,
+    pub taker_sell_volume: Option<Decimal>,
+    pub taker_flow_imbalance: Option<Decimal>,
+    pub traceparent: Option<String>, // Trace context of the collection cycle the state was recorded from
+}
+
+/// Historical market state from a backfill source, holding only the fields the source provides
+#[derive(Debug, Clone)]
+pub struct NewBackfilledMarketStateModel {
+    pub market_id: i32,
+    pub timestamp: DateTime<Utc>,
+    pub fees_total: Decimal, // Fees accrued to the pool since the previous state
 }
 
 impl RawMarketStateModel {
@@ -146,6 +218,15 @@ impl RawMarketStateModel {
             fees_swap: Some(market.cumulative_fees.swap_fees),
             fees_borrowing: Some(market.cumulative_fees.borrowing_fees),
             fees_total: Some(market.cumulative_fees.total_fees),
+            max_pool_amount_long: market.caps.map(|caps| caps.max_pool_amount_long),
+            max_pool_amount_short: market.caps.map(|caps| caps.max_pool_amount_short),
+            max_open_interest_long: market.caps.map(|caps| caps.max_open_interest_long),
+            max_open_interest_short: market.caps.map(|caps| caps.max_open_interest_short),
+            taker_buy_volume: Some(market.volume.taker_buy),
+            taker_sell_volume: Some(market.volume.taker_sell),
+            taker_flow_imbalance: market.volume.taker_flow_imbalance(),
+            deprecated: market.deprecated,
+            traceparent: None,
         }
     }
 }
@@ -186,6 +267,14 @@ impl NewMarketStateModel {
             fees_swap: Some(market.cumulative_fees.swap_fees),
             fees_borrowing: Some(market.cumulative_fees.borrowing_fees),
             fees_total: Some(market.cumulative_fees.total_fees),
+            max_pool_amount_long: market.caps.map(|caps| caps.max_pool_amount_long),
+            max_pool_amount_short: market.caps.map(|caps| caps.max_pool_amount_short),
+            max_open_interest_long: market.caps.map(|caps| caps.max_open_interest_long),
+            max_open_interest_short: market.caps.map(|caps| caps.max_open_interest_short),
+            taker_buy_volume: Some(market.volume.taker_buy),
+            taker_sell_volume: Some(market.volume.taker_sell),
+            taker_flow_imbalance: market.volume.taker_flow_imbalance(),
+            traceparent: None,
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,11 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct MarketUniverseModel {
+    pub universe: String,
+    pub markets: Vec<String>,
+    pub capital_fraction: Decimal,
+    pub updated_at: DateTime<Utc>,
+}
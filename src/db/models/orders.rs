@@ -0,0 +1,86 @@
+use rust_decimal::Decimal;
+use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+
+/// Lifecycle status of a perp hedge order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HedgeOrderStatus {
+    Submitted,       // Placed on the venue, not yet seen by the indexer
+    Open,            // Resting / being matched
+    Filled,
+    PartiallyFilled, // Cancelled or expired with part of the size unfilled, remainder awaiting retry
+    Retried,         // Unfilled remainder resubmitted as a child order
+    Cancelled,
+    Expired,
+}
+
+impl HedgeOrderStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HedgeOrderStatus::Submitted => "Submitted",
+            HedgeOrderStatus::Open => "Open",
+            HedgeOrderStatus::Filled => "Filled",
+            HedgeOrderStatus::PartiallyFilled => "PartiallyFilled",
+            HedgeOrderStatus::Retried => "Retried",
+            HedgeOrderStatus::Cancelled => "Cancelled",
+            HedgeOrderStatus::Expired => "Expired",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "Submitted" => Some(HedgeOrderStatus::Submitted),
+            "Open" => Some(HedgeOrderStatus::Open),
+            "Filled" => Some(HedgeOrderStatus::Filled),
+            "PartiallyFilled" => Some(HedgeOrderStatus::PartiallyFilled),
+            "Retried" => Some(HedgeOrderStatus::Retried),
+            "Cancelled" => Some(HedgeOrderStatus::Cancelled),
+            "Expired" => Some(HedgeOrderStatus::Expired),
+            _ => None,
+        }
+    }
+
+    /// Statuses in which the order still represents an outstanding hedge adjustment
+    pub fn open_statuses() -> [&'static str; 3] {
+        [
+            HedgeOrderStatus::Submitted.as_str(),
+            HedgeOrderStatus::Open.as_str(),
+            HedgeOrderStatus::PartiallyFilled.as_str(),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct OrderModel {
+    pub id: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub venue: String,
+    pub ticker: String,
+    pub side: String,
+    pub size: Decimal,
+    pub filled_size: Decimal,
+    pub reduce_only: bool,
+    pub client_id: i64,
+    pub good_til_block: Option<i64>,
+    pub venue_order_id: Option<String>,
+    pub tx_hash: Option<String>,
+    pub status: String,
+    pub parent_order_id: Option<i32>,
+    pub rebalance_id: Option<i32>, // Rebalance the order is a leg of, a retry of the order belongs to it too
+}
+
+#[derive(Debug, Clone)]
+pub struct NewOrderModel {
+    pub venue: String,
+    pub ticker: String,
+    pub side: String,
+    pub size: Decimal,
+    pub reduce_only: bool,
+    pub client_id: i64,
+    pub good_til_block: Option<i64>,
+    pub tx_hash: Option<String>,
+    pub status: String,
+    pub parent_order_id: Option<i32>,
+    pub reference_price: Option<Decimal>, // Mid price of the hedged asset when the order was placed
+}
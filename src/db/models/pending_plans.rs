@@ -0,0 +1,83 @@
+use rust_decimal::Decimal;
+use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+use ethers::types::Address;
+use std::collections::HashMap;
+
+use crate::strategy::types::PortfolioData;
+
+/// Approval status shared by pending plans and their individual actions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanStatus {
+    Pending,
+    Approved,
+    Rejected,
+    Expired,
+}
+
+impl PlanStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PlanStatus::Pending => "Pending",
+            PlanStatus::Approved => "Approved",
+            PlanStatus::Rejected => "Rejected",
+            PlanStatus::Expired => "Expired",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "Pending" => Some(PlanStatus::Pending),
+            "Approved" => Some(PlanStatus::Approved),
+            "Rejected" => Some(PlanStatus::Rejected),
+            "Expired" => Some(PlanStatus::Expired),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct PendingPlanModel {
+    pub id: i32,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub status: String,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct PendingPlanActionModel {
+    pub id: i32,
+    pub plan_id: i32,
+    pub market_id: i32,
+    pub target_weight: Decimal,
+    pub expected_return: Option<Decimal>,
+    pub status: String,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewPendingPlanActionModel {
+    pub market_id: i32,
+    pub target_weight: Decimal,
+    pub expected_return: Option<Decimal>,
+    pub notes: Option<String>, // Constraints applied by the planner (e.g. utilization ceiling)
+}
+
+impl NewPendingPlanActionModel {
+    /// Build one proposed action per market in the portfolio (markets missing from the ID map are skipped)
+    pub fn from_portfolio_data(portfolio_data: &PortfolioData, market_id_map: &HashMap<Address, i32>) -> Vec<Self> {
+        portfolio_data.market_addresses.iter()
+            .enumerate()
+            .filter_map(|(i, address)| {
+                let market_id = *market_id_map.get(address)?;
+                Some(Self {
+                    market_id,
+                    target_weight: portfolio_data.weights[i],
+                    expected_return: Some(portfolio_data.expected_returns[i]),
+                    notes: portfolio_data.get_notes(*address),
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,10 @@
+use chrono::{DateTime, Utc};
+
+/// Health transition of a data stream, recorded by the pipeline watchdog
+#[derive(Debug, Clone)]
+pub struct NewPipelineHealthModel {
+    pub stream: String,
+    pub status: String,
+    pub last_entry_at: Option<DateTime<Utc>>,
+    pub stale_after_secs: i32,
+}
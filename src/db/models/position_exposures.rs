@@ -0,0 +1,26 @@
+use rust_decimal::Decimal;
+use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+
+/// A held GM position's collateral exposure as recorded by a trading run
+#[derive(Debug, Clone, FromRow)]
+pub struct PositionExposureModel {
+    pub id: i32,
+    pub market_id: i32,
+    pub recorded_at: DateTime<Utc>,
+    pub gm_value_usd: Decimal,
+    pub long_token_usd: Decimal,
+    pub short_token_usd: Decimal,
+    pub long_share: Decimal,
+    pub entry_long_share: Decimal,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewPositionExposureModel {
+    pub market_id: i32,
+    pub gm_value_usd: Decimal,
+    pub long_token_usd: Decimal,
+    pub short_token_usd: Decimal,
+    pub long_share: Decimal,
+    pub entry_long_share: Decimal,
+}
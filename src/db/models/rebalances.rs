@@ -0,0 +1,27 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::FromRow;
+
+/// Net cost of one rebalance across its legs. Costs are positive when money leaked, slippage can be negative when a
+/// leg filled better than mid.
+#[derive(Debug, Clone, FromRow)]
+pub struct RebalanceCostModel {
+    pub rebalance_id: i32,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub source: String,
+    pub swap_slippage_usd: Decimal,  // Spot swap output below mid
+    pub gm_impact_usd: Decimal,      // Estimated GMX price impact and pool fees of the GM requests
+    pub execution_fee_usd: Decimal,  // GMX keeper execution fees, net of refunds
+    pub gas_cost_usd: Decimal,
+    pub hedge_slippage_usd: Decimal, // Hedge fills past the mid price at placement
+    pub tx_count: i64,               // Transactions with a recorded execution cost
+    pub order_count: i64,            // Hedge orders placed
+    pub unpriced_order_count: i64,   // Filled hedge orders without a reference or fill price, left out of the hedge slippage
+}
+
+impl RebalanceCostModel {
+    pub fn total_usd(&self) -> Decimal {
+        self.swap_slippage_usd + self.gm_impact_usd + self.execution_fee_usd + self.gas_cost_usd + self.hedge_slippage_usd
+    }
+}
@@ -0,0 +1,44 @@
+use rust_decimal::Decimal;
+
+/// Outcome of a logical spot swap, which may have been executed in several TWAP slices
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpotSwapStatus {
+    Completed, // Every slice filled
+    Aborted,   // Remaining slices skipped after the price moved past the limit
+    Failed,    // A slice failed to execute
+}
+
+impl SpotSwapStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SpotSwapStatus::Completed => "Completed",
+            SpotSwapStatus::Aborted => "Aborted",
+            SpotSwapStatus::Failed => "Failed",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "Completed" => Some(SpotSwapStatus::Completed),
+            "Aborted" => Some(SpotSwapStatus::Aborted),
+            "Failed" => Some(SpotSwapStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NewSpotSwapModel {
+    pub from_token_address: String,
+    pub to_token_address: String,
+    pub side: String,
+    pub requested_amount: Decimal,
+    pub from_amount: Decimal,
+    pub to_amount: Decimal,
+    pub slices_planned: i32,
+    pub slices_filled: i32,
+    pub status: String,
+    pub gas_cost_usd: Decimal,
+    pub tx_hashes: Vec<String>,
+    pub screened_contracts: Vec<String>,
+}
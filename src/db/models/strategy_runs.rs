@@ -0,0 +1,168 @@
+use rust_decimal::Decimal;
+use rust_decimal::prelude::*;
+use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+use ethers::types::Address;
+use std::collections::HashMap;
+
+use crate::strategy::types::PortfolioData;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct StrategyRunModel {
+    pub id: i32,
+    pub created_at: DateTime<Utc>,
+    pub risk_free_rate_apr: Option<Decimal>,
+    pub expected_return_bps: Option<Decimal>,
+    pub volatility_bps: Option<Decimal>,
+    pub sharpe_ratio: Option<Decimal>,
+    pub relaxed_constraints: Option<String>,
+    pub universe: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewStrategyRunModel {
+    pub risk_free_rate_apr: Decimal,
+    pub expected_return_bps: Decimal,
+    pub volatility_bps: Decimal,
+    pub sharpe_ratio: Decimal, // Excess-return Sharpe ratio per timestep
+    pub relaxed_constraints: Option<String>, // Constraints relaxed by the feasibility check, None when all held
+    pub universe: Option<String>,            // Market universe the run allocated, None for every market
+}
+
+impl NewStrategyRunModel {
+    /// Portfolio-level summary of the run at the target weights
+    pub fn from_portfolio_data(portfolio_data: &PortfolioData) -> Self {
+        let (expected_return, volatility, sharpe_ratio) = portfolio_data.portfolio_metrics();
+        Self {
+            risk_free_rate_apr: portfolio_data.risk_free_rate_apr,
+            expected_return_bps: expected_return * Decimal::from_f64(10000.0).unwrap(),
+            volatility_bps: volatility * Decimal::from_f64(10000.0).unwrap(),
+            sharpe_ratio,
+            relaxed_constraints: portfolio_data.relaxed_constraints_summary(),
+            universe: portfolio_data.universe.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct StrategyRunMarketModel {
+    pub id: i32,
+    pub run_id: i32,
+    pub market_id: i32,
+    pub expected_return_bps: Decimal,
+    pub target_weight: Decimal,
+    pub raw_target_weight: Option<Decimal>,
+    pub smoothed_target_weight: Option<Decimal>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewStrategyRunMarketModel {
+    pub market_id: i32,
+    pub expected_return_bps: Decimal,
+    pub target_weight: Decimal,                 // Final target after smoothing and the planning guards
+    pub raw_target_weight: Option<Decimal>,     // Optimizer output, None when the smoothing layer didn't run
+    pub smoothed_target_weight: Option<Decimal>, // Target after cross-run smoothing, before the planning guards
+}
+
+impl NewStrategyRunMarketModel {
+    /// Build one row per market in the portfolio (markets missing from the ID map are skipped)
+    pub fn from_portfolio_data(portfolio_data: &PortfolioData, market_id_map: &HashMap<Address, i32>) -> Vec<Self> {
+        portfolio_data.market_addresses.iter()
+            .enumerate()
+            .filter_map(|(i, address)| {
+                let market_id = *market_id_map.get(address)?;
+                Some(Self {
+                    market_id,
+                    expected_return_bps: portfolio_data.expected_returns[i] * Decimal::from_f64(10000.0).unwrap(),
+                    target_weight: portfolio_data.weights[i],
+                    raw_target_weight: portfolio_data.raw_weights.as_ref().map(|weights| weights[i]),
+                    smoothed_target_weight: portfolio_data.smoothed_weights.as_ref().map(|weights| weights[i]),
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct StrategyRunInputModel {
+    pub id: i32,
+    pub run_id: i32,
+    pub market_id: i32,
+    pub market_row_count: i32,
+    pub index_price_row_count: i32,
+    pub first_timestamp: Option<DateTime<Utc>>,
+    pub last_timestamp: Option<DateTime<Utc>>,
+    pub mean_fee_rate: Decimal,
+    pub volatility: Decimal,
+    pub last_index_price: Option<Decimal>,
+    pub pool_value_usd: Decimal,
+    pub data_quality: Option<Decimal>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewStrategyRunInputModel {
+    pub market_id: i32,
+    pub market_row_count: i32,
+    pub index_price_row_count: i32,
+    pub first_timestamp: Option<DateTime<Utc>>,
+    pub last_timestamp: Option<DateTime<Utc>>,
+    pub mean_fee_rate: Decimal,
+    pub volatility: Decimal,
+    pub last_index_price: Option<Decimal>,
+    pub pool_value_usd: Decimal,
+    pub data_quality: Decimal,
+}
+
+impl NewStrategyRunInputModel {
+    /// Build one input digest row per market in the portfolio (markets missing from the ID map are skipped)
+    pub fn from_portfolio_data(portfolio_data: &PortfolioData, market_id_map: &HashMap<Address, i32>) -> Vec<Self> {
+        portfolio_data.market_addresses.iter()
+            .zip(portfolio_data.input_digests.iter())
+            .filter_map(|(address, digest)| {
+                let market_id = *market_id_map.get(address)?;
+                Some(Self {
+                    market_id,
+                    market_row_count: digest.market_row_count as i32,
+                    index_price_row_count: digest.index_price_row_count as i32,
+                    first_timestamp: digest.first_timestamp,
+                    last_timestamp: digest.last_timestamp,
+                    mean_fee_rate: digest.mean_fee_rate,
+                    volatility: digest.volatility,
+                    last_index_price: digest.last_index_price,
+                    pool_value_usd: digest.pool_value_usd,
+                    data_quality: digest.data_quality,
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ReturnModelMetricsModel {
+    pub id: i32,
+    pub run_id: i32,
+    pub evaluated_at: DateTime<Utc>,
+    pub horizon_hours: i32,
+    pub market_count: i32,
+    pub bias_bps: Decimal,
+    pub mae_bps: Decimal,
+    pub rank_correlation: Option<Decimal>,
+    pub reporting_currency: String,
+    pub portfolio_return_bps: Option<Decimal>,
+    pub benchmark_return_bps: Option<Decimal>,
+    pub alpha_bps: Option<Decimal>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewReturnModelMetricsModel {
+    pub run_id: i32,
+    pub horizon_hours: i32,
+    pub market_count: i32,
+    pub bias_bps: Decimal,
+    pub mae_bps: Decimal,
+    pub rank_correlation: Option<Decimal>,
+    pub reporting_currency: String,
+    pub portfolio_return_bps: Option<Decimal>, // Realized return of the target weights over the horizon
+    pub benchmark_return_bps: Option<Decimal>, // Return of holding the base stablecoin in Aave over the horizon
+    pub alpha_bps: Option<Decimal>,            // Portfolio return minus benchmark return
+}
@@ -25,6 +25,12 @@ pub struct RawTokenPriceModel {
     pub min_price: Decimal,
     pub max_price: Decimal,
     pub mid_price: Decimal,
+    #[serde(default)]
+    pub quarantine_reason: Option<String>, // Set by ingestion validation, quarantined prices are not stored in token_prices
+    #[serde(default)]
+    pub reference_price: Option<Decimal>, // Price the observation was validated against
+    #[serde(skip)]
+    pub traceparent: Option<String>, // Trace context of the collection cycle, taken from the stream entry by the recorder
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -34,6 +40,7 @@ pub struct NewTokenPriceModel {
     pub min_price: Decimal,
     pub max_price: Decimal,
     pub mid_price: Decimal,
+    pub traceparent: Option<String>, // Trace context of the collection cycle the price was recorded from
 }
 
 impl RawTokenPriceModel {
@@ -46,6 +53,9 @@ impl RawTokenPriceModel {
             min_price: token.last_min_price_usd.unwrap(),
             max_price: token.last_max_price_usd.unwrap(),
             mid_price: token.last_mid_price_usd.unwrap(),
+            quarantine_reason: None,
+            reference_price: None,
+            traceparent: None,
         }
     }
 }
@@ -61,6 +71,32 @@ impl NewTokenPriceModel {
             min_price: token.last_min_price_usd.unwrap(),
             max_price: token.last_max_price_usd.unwrap(),
             mid_price: token.last_mid_price_usd.unwrap(),
+            traceparent: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NewQuarantinedPriceModel {
+    pub token_id: i32,
+    pub timestamp: chrono::DateTime<Utc>,
+    pub min_price: Decimal,
+    pub max_price: Decimal,
+    pub mid_price: Decimal,
+    pub reference_price: Option<Decimal>,
+    pub reason: String,
+}
+
+impl NewQuarantinedPriceModel {
+    pub fn from(token_price: &NewTokenPriceModel, reason: String, reference_price: Option<Decimal>) -> Self {
+        Self {
+            token_id: token_price.token_id,
+            timestamp: token_price.timestamp,
+            min_price: token_price.min_price,
+            max_price: token_price.max_price,
+            mid_price: token_price.mid_price,
+            reference_price,
+            reason,
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,120 @@
+use rust_decimal::Decimal;
+use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeActionType {
+    GmDeposit,
+    GmWithdrawal,
+    GmShift,
+    ClaimRewards,
+    ClaimUiFees,      // UI fees accrued to the wallet as UI fee receiver
+    BridgeCollateral, // USDC moved between the Arbitrum wallet and the dYdX subaccount
+}
+
+impl TradeActionType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TradeActionType::GmDeposit => "GmDeposit",
+            TradeActionType::GmWithdrawal => "GmWithdrawal",
+            TradeActionType::GmShift => "GmShift",
+            TradeActionType::ClaimRewards => "ClaimRewards",
+            TradeActionType::ClaimUiFees => "ClaimUiFees",
+            TradeActionType::BridgeCollateral => "BridgeCollateral",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "GmDeposit" => Some(TradeActionType::GmDeposit),
+            "GmWithdrawal" => Some(TradeActionType::GmWithdrawal),
+            "GmShift" => Some(TradeActionType::GmShift),
+            "ClaimRewards" => Some(TradeActionType::ClaimRewards),
+            "ClaimUiFees" => Some(TradeActionType::ClaimUiFees),
+            "BridgeCollateral" => Some(TradeActionType::BridgeCollateral),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeStatus {
+    Pending,    // Created on-chain, waiting for keeper execution
+    Settled,    // No longer pending in the DataStore (executed by a keeper)
+    Cancelled,  // Cancelled by us after timing out
+    Failed,
+    Blocked,    // Never submitted, a compliance rule blocked it
+}
+
+impl TradeStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TradeStatus::Pending => "Pending",
+            TradeStatus::Settled => "Settled",
+            TradeStatus::Cancelled => "Cancelled",
+            TradeStatus::Failed => "Failed",
+            TradeStatus::Blocked => "Blocked",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "Pending" => Some(TradeStatus::Pending),
+            "Settled" => Some(TradeStatus::Settled),
+            "Cancelled" => Some(TradeStatus::Cancelled),
+            "Failed" => Some(TradeStatus::Failed),
+            "Blocked" => Some(TradeStatus::Blocked),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct TradeModel {
+    pub id: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub action_type: String,
+    pub status: String,
+    pub market_id: Option<i32>,
+    pub to_market_id: Option<i32>,
+    pub long_token_amount: Option<Decimal>,
+    pub short_token_amount: Option<Decimal>,
+    pub market_token_amount: Option<Decimal>,
+    pub tx_hash: Option<String>,
+    pub order_key: Option<String>,
+    pub cancel_tx_hash: Option<String>,
+    pub execution_fee: Option<Decimal>,
+    pub gas_used: Option<Decimal>,
+    pub gas_price: Option<Decimal>,
+    pub gas_cost_usd: Option<Decimal>,
+    pub execution_fee_refund: Option<Decimal>, // Set once the keeper's refund has been reconciled
+    pub notional_usd: Option<Decimal>,
+    pub compliance_rule_id: Option<String>,
+}
+
+impl TradeModel {
+    /// Execution fee paid net of the keeper's refund, the full fee while no refund has been reconciled
+    pub fn net_execution_fee(&self) -> Option<Decimal> {
+        self.execution_fee.map(|fee| fee - self.execution_fee_refund.unwrap_or_default())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NewTradeModel {
+    pub action_type: String,
+    pub status: String,
+    pub market_id: Option<i32>,
+    pub to_market_id: Option<i32>,
+    pub long_token_amount: Option<Decimal>,
+    pub short_token_amount: Option<Decimal>,
+    pub market_token_amount: Option<Decimal>,
+    pub tx_hash: Option<String>,
+    pub order_key: Option<String>,
+    pub execution_fee: Option<Decimal>,
+    pub gas_used: Option<Decimal>,
+    pub gas_price: Option<Decimal>,
+    pub gas_cost_usd: Option<Decimal>,
+    pub notional_usd: Option<Decimal>,
+    pub compliance_rule_id: Option<String>,
+}
@@ -0,0 +1,18 @@
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct WalletTransactionModel {
+    pub id: i32,
+    pub wallet_address: String,
+    pub tx_hash: String,
+    pub nonce: i64,
+    pub sent_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewWalletTransactionModel {
+    pub wallet_address: String,
+    pub tx_hash: String,
+    pub nonce: i64,
+}
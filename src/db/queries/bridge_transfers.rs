@@ -0,0 +1,71 @@
+use sqlx::{PgPool, Row};
+
+use crate::db::models::bridge_transfers::{BridgeTransferModel, NewBridgeTransferModel, BridgeTransferStatus};
+
+const BRIDGE_TRANSFER_COLUMNS: &str = r#"
+    id, created_at, updated_at, direction, amount, expected_amount_out, source_chain_id, source_tx_hash,
+    estimated_duration_secs, status, arrived_at, error
+"#;
+
+/// Insert a bridge transfer as pending, returning its ID
+pub async fn insert_bridge_transfer(pool: &PgPool, account_id: &str, transfer: &NewBridgeTransferModel) -> Result<i32, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        INSERT INTO bridge_transfers (direction, amount, expected_amount_out, source_chain_id, source_tx_hash, estimated_duration_secs, status, account_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING id
+        "#
+    )
+    .bind(&transfer.direction)
+    .bind(transfer.amount)
+    .bind(transfer.expected_amount_out)
+    .bind(&transfer.source_chain_id)
+    .bind(&transfer.source_tx_hash)
+    .bind(transfer.estimated_duration_secs)
+    .bind(BridgeTransferStatus::Pending.as_str())
+    .bind(account_id)
+    .fetch_one(pool)
+    .await?;
+    Ok(row.get(0))
+}
+
+/// Update a bridge transfer's status, stamping the arrival time when it arrived
+pub async fn update_bridge_transfer_status(pool: &PgPool, id: i32, status: &str, error: Option<&str>) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE bridge_transfers
+        SET status = $2,
+            error = $3,
+            arrived_at = CASE WHEN $2 = 'Arrived' THEN now() ELSE arrived_at END,
+            updated_at = now()
+        WHERE id = $1
+        "#
+    )
+    .bind(id)
+    .bind(status)
+    .bind(error)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Fetch one of the account's bridge transfers by ID
+pub async fn get_bridge_transfer(pool: &PgPool, account_id: &str, id: i32) -> Result<Option<BridgeTransferModel>, sqlx::Error> {
+    let query = format!("SELECT {} FROM bridge_transfers WHERE id = $1 AND account_id = $2", BRIDGE_TRANSFER_COLUMNS);
+    sqlx::query_as::<_, BridgeTransferModel>(&query)
+        .bind(id)
+        .bind(account_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Fetch every bridge transfer of the account whose funds have not arrived yet, oldest first
+pub async fn get_pending_bridge_transfers(pool: &PgPool, account_id: &str) -> Result<Vec<BridgeTransferModel>, sqlx::Error> {
+    let query = format!("SELECT {} FROM bridge_transfers WHERE account_id = $1 AND status = $2 ORDER BY created_at ASC", BRIDGE_TRANSFER_COLUMNS);
+    sqlx::query_as::<_, BridgeTransferModel>(&query)
+        .bind(account_id)
+        .bind(BridgeTransferStatus::Pending.as_str())
+        .fetch_all(pool)
+        .await
+}
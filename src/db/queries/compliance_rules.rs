@@ -0,0 +1,18 @@
+use sqlx::PgPool;
+
+use crate::db::models::compliance_rules::ComplianceRuleModel;
+
+/// Get the enabled compliance rules of an account
+pub async fn get_enabled_compliance_rules(pool: &PgPool, account_id: &str) -> Result<Vec<ComplianceRuleModel>, sqlx::Error> {
+    sqlx::query_as::<_, ComplianceRuleModel>(
+        r#"
+        SELECT rule_id, rule_type, value, updated_at
+        FROM compliance_rules
+        WHERE account_id = $1 AND enabled
+        ORDER BY rule_id
+        "#
+    )
+    .bind(account_id)
+    .fetch_all(pool)
+    .await
+}
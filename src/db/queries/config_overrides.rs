@@ -0,0 +1,50 @@
+use sqlx::PgPool;
+
+use crate::db::models::config_overrides::ConfigOverrideModel;
+
+/// Get every configuration override of an account
+pub async fn get_config_overrides(pool: &PgPool, account_id: &str) -> Result<Vec<ConfigOverrideModel>, sqlx::Error> {
+    sqlx::query_as::<_, ConfigOverrideModel>(
+        r#"
+        SELECT key, value, updated_at
+        FROM config_overrides
+        WHERE account_id = $1
+        ORDER BY key
+        "#
+    )
+    .bind(account_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Set an account's configuration override, replacing any existing value for the key
+pub async fn upsert_config_override(pool: &PgPool, account_id: &str, key: &str, value: &str) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO config_overrides (account_id, key, value, updated_at)
+        VALUES ($1, $2, $3, now())
+        ON CONFLICT (account_id, key) DO UPDATE SET value = EXCLUDED.value, updated_at = now()
+        "#
+    )
+    .bind(account_id)
+    .bind(key)
+    .bind(value)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Remove an account's configuration override so the parameter reverts to its default, returning whether one existed
+pub async fn delete_config_override(pool: &PgPool, account_id: &str, key: &str) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM config_overrides
+        WHERE account_id = $1 AND key = $2
+        "#
+    )
+    .bind(account_id)
+    .bind(key)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
@@ -0,0 +1,273 @@
+use sqlx::{PgPool, Row};
+use chrono::{DateTime, Utc};
+
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+use crate::db::models::execution_costs::{NewExecutionCostModel, ExecutionSpendModel, NewGasPriceSampleModel, NewGasProfileModel, GasProfileSummaryModel};
+
+/// Insert a single execution cost record for an account, returning its ID
+pub async fn insert_execution_cost(pool: &PgPool, account_id: &str, rebalance_id: Option<i32>, cost: &NewExecutionCostModel) -> Result<i32, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        INSERT INTO execution_costs (venue, action_type, tx_hash, gas_cost_usd, execution_fee_usd, total_cost_usd, account_id, slippage_usd, rebalance_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        RETURNING id
+        "#
+    )
+    .bind(&cost.venue)
+    .bind(&cost.action_type)
+    .bind(&cost.tx_hash)
+    .bind(cost.gas_cost_usd)
+    .bind(cost.execution_fee_usd)
+    .bind(cost.gas_cost_usd + cost.execution_fee_usd)
+    .bind(account_id)
+    .bind(cost.slippage_usd)
+    .bind(rebalance_id)
+    .fetch_one(pool)
+    .await?;
+    Ok(row.get(0))
+}
+
+/// Net a refunded share of the execution fee out of the costs recorded for a transaction, returning the rows updated.
+/// The refund is valued at the same price as the fee it came out of.
+pub async fn apply_execution_fee_refund(pool: &PgPool, tx_hash: &str, refunded_fraction: Decimal) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE execution_costs
+        SET execution_fee_refund_usd = execution_fee_usd * $2,
+            total_cost_usd = gas_cost_usd + execution_fee_usd - execution_fee_usd * $2
+        WHERE tx_hash = $1 AND execution_fee_usd > 0
+        "#
+    )
+    .bind(tx_hash)
+    .bind(refunded_fraction)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Record the slippage of the costs recorded for a transaction once its fill is known, returning the rows updated
+pub async fn update_execution_slippage(pool: &PgPool, tx_hash: &str, slippage_usd: Decimal) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE execution_costs
+        SET slippage_usd = $2
+        WHERE tx_hash = $1
+        "#
+    )
+    .bind(tx_hash)
+    .bind(slippage_usd)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Fetch an account's cumulative execution spend per venue and action type since the given time
+pub async fn get_execution_spend_since(pool: &PgPool, account_id: &str, since: DateTime<Utc>) -> Result<Vec<ExecutionSpendModel>, sqlx::Error> {
+    sqlx::query_as::<_, ExecutionSpendModel>(
+        r#"
+        SELECT venue, action_type, COUNT(*) AS tx_count, SUM(total_cost_usd) AS total_cost_usd
+        FROM execution_costs
+        WHERE account_id = $1 AND created_at >= $2
+        GROUP BY venue, action_type
+        ORDER BY venue, action_type
+        "#
+    )
+    .bind(account_id)
+    .bind(since)
+    .fetch_all(pool)
+    .await
+}
+
+/// Fetch total execution cost per transaction hash for costs recorded within a time range
+pub async fn get_execution_costs_by_tx_in_range(
+    pool: &PgPool,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<HashMap<String, Decimal>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT tx_hash, SUM(total_cost_usd) AS total_cost_usd
+        FROM execution_costs
+        WHERE tx_hash IS NOT NULL AND created_at >= $1 AND created_at <= $2
+        GROUP BY tx_hash
+        "#
+    )
+    .bind(start)
+    .bind(end)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.into_iter().map(|row| (row.get(0), row.get(1))).collect())
+}
+
+/// Insert an execution fee estimate sample
+pub async fn insert_gas_price_sample(pool: &PgPool, sample: &NewGasPriceSampleModel) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO gas_price_samples (action_type, gas_price, execution_fee)
+        VALUES ($1, $2, $3)
+        "#
+    )
+    .bind(&sample.action_type)
+    .bind(sample.gas_price)
+    .bind(sample.execution_fee)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Fetch the median estimated execution fee for an action type since the given time, with the sample count
+pub async fn get_median_execution_fee_since(
+    pool: &PgPool,
+    action_type: &str,
+    since: DateTime<Utc>,
+) -> Result<(Option<Decimal>, i64), sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT
+            (percentile_cont(0.5) WITHIN GROUP (ORDER BY execution_fee))::NUMERIC AS median_execution_fee,
+            COUNT(*) AS sample_count
+        FROM gas_price_samples
+        WHERE action_type = $1 AND sampled_at >= $2
+        "#
+    )
+    .bind(action_type)
+    .bind(since)
+    .fetch_one(pool)
+    .await?;
+    Ok((row.get(0), row.get(1)))
+}
+
+
+/// Insert a gas profile for a GM request creation transaction, returning its ID
+pub async fn insert_gas_profile(pool: &PgPool, profile: &NewGasProfileModel) -> Result<i32, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        INSERT INTO gas_profiles (
+            action_type, market_id, tx_hash, gas_used, l1_gas_used, network_gas_price, effective_gas_price,
+            max_fee_per_gas_buffer, estimated_gas_limit, execution_fee, quote_base_fee, inclusion_base_fee
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        RETURNING id
+        "#
+    )
+    .bind(&profile.action_type)
+    .bind(profile.market_id)
+    .bind(&profile.tx_hash)
+    .bind(profile.gas_used)
+    .bind(profile.l1_gas_used)
+    .bind(profile.network_gas_price)
+    .bind(profile.effective_gas_price)
+    .bind(profile.max_fee_per_gas_buffer)
+    .bind(profile.estimated_gas_limit)
+    .bind(profile.execution_fee)
+    .bind(profile.quote_base_fee)
+    .bind(profile.inclusion_base_fee)
+    .fetch_one(pool)
+    .await?;
+    Ok(row.get(0))
+}
+
+/// Record the keeper execution and execution fee refund for the gas profile of a request creation transaction
+pub async fn update_gas_profile_refund(
+    pool: &PgPool,
+    tx_hash: &str,
+    keeper_tx_hash: &str,
+    keeper_gas_price: Decimal,
+    execution_fee_refund: Decimal,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE gas_profiles
+        SET keeper_tx_hash = $2, keeper_gas_price = $3, execution_fee_refund = $4
+        WHERE tx_hash = $1
+        "#
+    )
+    .bind(tx_hash)
+    .bind(keeper_tx_hash)
+    .bind(keeper_gas_price)
+    .bind(execution_fee_refund)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Fetch gas usage and execution fee utilization percentiles per action type and market since the given time
+pub async fn get_gas_profile_summary_since(pool: &PgPool, since: DateTime<Utc>) -> Result<Vec<GasProfileSummaryModel>, sqlx::Error> {
+    sqlx::query_as::<_, GasProfileSummaryModel>(
+        r#"
+        WITH profiles AS (
+            SELECT
+                action_type,
+                market_id,
+                gas_used,
+                execution_fee_refund,
+                l1_gas_used / NULLIF(gas_used, 0) AS l1_gas_share,
+                effective_gas_price / NULLIF(network_gas_price, 0) AS inclusion_price_ratio,
+                (execution_fee - execution_fee_refund) / NULLIF(execution_fee, 0) AS fee_utilization,
+                (execution_fee - execution_fee_refund) * 1e18 / NULLIF(estimated_gas_limit * network_gas_price, 0) AS required_fee_buffer,
+                (execution_fee - execution_fee_refund) * 1e18 / NULLIF(keeper_gas_price * estimated_gas_limit, 0) AS keeper_gas_limit_utilization,
+                inclusion_base_fee / NULLIF(quote_base_fee, 0) AS base_fee_movement
+            FROM gas_profiles
+            WHERE created_at >= $1
+        )
+        SELECT
+            action_type,
+            market_id,
+            COUNT(*) AS tx_count,
+            COUNT(execution_fee_refund) AS refunded_count,
+            AVG(gas_used) AS avg_gas_used,
+            AVG(l1_gas_share) AS avg_l1_gas_share,
+            (percentile_cont(0.5) WITHIN GROUP (ORDER BY inclusion_price_ratio))::NUMERIC AS p50_inclusion_price_ratio,
+            (percentile_cont(0.95) WITHIN GROUP (ORDER BY inclusion_price_ratio))::NUMERIC AS p95_inclusion_price_ratio,
+            (percentile_cont(0.5) WITHIN GROUP (ORDER BY fee_utilization))::NUMERIC AS p50_fee_utilization,
+            (percentile_cont(0.95) WITHIN GROUP (ORDER BY fee_utilization))::NUMERIC AS p95_fee_utilization,
+            (percentile_cont(0.95) WITHIN GROUP (ORDER BY required_fee_buffer))::NUMERIC AS p95_required_fee_buffer,
+            (percentile_cont(0.95) WITHIN GROUP (ORDER BY keeper_gas_limit_utilization))::NUMERIC AS p95_keeper_gas_limit_utilization,
+            (percentile_cont(0.95) WITHIN GROUP (ORDER BY base_fee_movement))::NUMERIC AS p95_base_fee_movement
+        FROM profiles
+        GROUP BY action_type, market_id
+        ORDER BY action_type, market_id
+        "#
+    )
+    .bind(since)
+    .fetch_all(pool)
+    .await
+}
+
+/// Quantile of the max fee per gas buffer request transactions of an action type actually needed since the given
+/// time, with the number of transactions it was computed from. A transaction needed the larger of the base fee
+/// movement between estimate and inclusion, the effective gas price over the quoted one, and (once refunded) the
+/// keeper execution cost over the estimated gas limit at the quoted price. Only transactions with recorded base
+/// fees count.
+pub async fn get_required_fee_buffer_since(
+    pool: &PgPool,
+    action_type: &str,
+    since: DateTime<Utc>,
+    quantile: f64,
+) -> Result<(Option<Decimal>, i64), sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        WITH required AS (
+            SELECT GREATEST(
+                inclusion_base_fee / NULLIF(quote_base_fee, 0),
+                effective_gas_price / NULLIF(network_gas_price, 0),
+                (execution_fee - execution_fee_refund) * 1e18 / NULLIF(estimated_gas_limit * network_gas_price, 0)
+            ) AS required_fee_buffer
+            FROM gas_profiles
+            WHERE action_type = $1 AND created_at >= $2 AND quote_base_fee IS NOT NULL AND inclusion_base_fee IS NOT NULL
+        )
+        SELECT
+            (percentile_cont($3) WITHIN GROUP (ORDER BY required_fee_buffer))::NUMERIC AS required_fee_buffer,
+            COUNT(required_fee_buffer) AS sample_count
+        FROM required
+        "#
+    )
+    .bind(action_type)
+    .bind(since)
+    .bind(quantile)
+    .fetch_one(pool)
+    .await?;
+    Ok((row.get(0), row.get(1)))
+}
\ No newline at end of file
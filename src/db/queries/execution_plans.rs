@@ -0,0 +1,188 @@
+use sqlx::{PgPool, Row};
+use rust_decimal::Decimal;
+use chrono::{DateTime, Utc};
+
+use crate::db::models::execution_plans::{
+    ExecutionPlanModel,
+    ExecutionPlanActionModel,
+    NewExecutionPlanActionModel,
+    ExecutionStatus,
+    PlanCreation,
+};
+
+const ACTION_COLUMNS: &str = r#"
+    id, plan_id, seq, updated_at, action_type, status, market_id, to_market_id,
+    long_token_amount, short_token_amount, market_token_amount,
+    spent_balance_before, submitted_at, error, initial_long_token, initial_short_token
+"#;
+
+/// Insert an account's plan and its ordered actions in a single transaction, returning the plan ID.
+/// With `duplicate_since`, no plan is inserted when the account created one with the same hash since then: the check
+/// holds an advisory lock on the hash until the transaction ends, so concurrent instances can't both pass it.
+pub async fn insert_execution_plan(
+    pool: &PgPool,
+    account_id: &str,
+    source: &str,
+    traceparent: Option<&str>,
+    plan_hash: &str,
+    duplicate_since: Option<DateTime<Utc>>,
+    rebalance_id: Option<i32>,
+    actions: &[NewExecutionPlanActionModel],
+) -> Result<PlanCreation, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    if let Some(since) = duplicate_since {
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
+            .bind(plan_hash)
+            .execute(&mut *tx)
+            .await?;
+        let duplicate = sqlx::query(
+            "SELECT id, created_at FROM execution_plans WHERE account_id = $1 AND plan_hash = $2 AND created_at >= $3 ORDER BY created_at DESC LIMIT 1"
+        )
+        .bind(account_id)
+        .bind(plan_hash)
+        .bind(since)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if let Some(row) = duplicate {
+            tx.rollback().await?;
+            return Ok(PlanCreation::Duplicate { plan_id: row.get(0), created_at: row.get(1) });
+        }
+    }
+
+    let row = sqlx::query(
+        r#"
+        INSERT INTO execution_plans (source, traceparent, plan_hash, rebalance_id, account_id)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id
+        "#
+    )
+    .bind(source)
+    .bind(traceparent)
+    .bind(plan_hash)
+    .bind(rebalance_id)
+    .bind(account_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    let plan_id: i32 = row.get(0);
+
+    for (seq, action) in actions.iter().enumerate() {
+        sqlx::query(
+            r#"
+            INSERT INTO execution_plan_actions (
+                plan_id,
+                seq,
+                action_type,
+                status,
+                market_id,
+                to_market_id,
+                long_token_amount,
+                short_token_amount,
+                market_token_amount,
+                initial_long_token,
+                initial_short_token
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            "#
+        )
+        .bind(plan_id)
+        .bind(seq as i32)
+        .bind(&action.action_type)
+        .bind(ExecutionStatus::Planned.as_str())
+        .bind(action.market_id)
+        .bind(action.to_market_id)
+        .bind(action.long_token_amount)
+        .bind(action.short_token_amount)
+        .bind(action.market_token_amount)
+        .bind(&action.initial_long_token)
+        .bind(&action.initial_short_token)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(PlanCreation::Created(plan_id))
+}
+
+/// Fetch all of an account's plans that have not run to completion, oldest first
+pub async fn get_incomplete_execution_plans(pool: &PgPool, account_id: &str) -> Result<Vec<ExecutionPlanModel>, sqlx::Error> {
+    sqlx::query_as::<_, ExecutionPlanModel>(
+        "SELECT id, created_at, completed_at, source, traceparent, plan_hash, rebalance_id FROM execution_plans WHERE account_id = $1 AND completed_at IS NULL ORDER BY created_at ASC"
+    )
+    .bind(account_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Fetch all actions of an account's plan in execution order
+pub async fn get_execution_plan_actions(pool: &PgPool, account_id: &str, plan_id: i32) -> Result<Vec<ExecutionPlanActionModel>, sqlx::Error> {
+    let query = format!(
+        "SELECT {} FROM execution_plan_actions WHERE plan_id IN (SELECT id FROM execution_plans WHERE id = $1 AND account_id = $2) ORDER BY seq ASC",
+        ACTION_COLUMNS
+    );
+    sqlx::query_as::<_, ExecutionPlanActionModel>(&query)
+        .bind(plan_id)
+        .bind(account_id)
+        .fetch_all(pool)
+        .await
+}
+
+/// Mark an action as submitted, recording the balance it spends from so the outcome can be verified after a crash
+pub async fn mark_action_submitted(
+    pool: &PgPool,
+    id: i32,
+    spent_balance_before: Decimal,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE execution_plan_actions
+        SET status = $2,
+            spent_balance_before = $3,
+            submitted_at = now(),
+            updated_at = now()
+        WHERE id = $1
+        "#
+    )
+    .bind(id)
+    .bind(ExecutionStatus::Submitted.as_str())
+    .bind(spent_balance_before)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Update the status of an action, optionally recording an error
+pub async fn update_action_status(
+    pool: &PgPool,
+    id: i32,
+    status: &str,
+    error: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE execution_plan_actions
+        SET status = $2,
+            error = COALESCE($3, error),
+            updated_at = now()
+        WHERE id = $1
+        "#
+    )
+    .bind(id)
+    .bind(status)
+    .bind(error)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Mark a plan as completed
+pub async fn complete_execution_plan(pool: &PgPool, plan_id: i32) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE execution_plans SET completed_at = now() WHERE id = $1")
+        .bind(plan_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
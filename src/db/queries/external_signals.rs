@@ -0,0 +1,50 @@
+use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+
+use crate::db::models::external_signals::{NewExternalSignalModel, ExternalSignalModel};
+
+/// Insert the signals of one payload in a single transaction. Returns false (inserting nothing) when the payload was
+/// already recorded, i.e. it is a replay.
+pub async fn insert_external_signals(pool: &PgPool, signals: &[NewExternalSignalModel]) -> Result<bool, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    for signal in signals {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO external_signals (signer, market_id, expected_return_apr, veto, issued_at, expires_at, payload_hash)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (payload_hash, market_id) DO NOTHING
+            "#
+        )
+        .bind(&signal.signer)
+        .bind(signal.market_id)
+        .bind(signal.expected_return_apr)
+        .bind(signal.veto)
+        .bind(signal.issued_at)
+        .bind(signal.expires_at)
+        .bind(&signal.payload_hash)
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            tx.rollback().await?;
+            return Ok(false);
+        }
+    }
+    tx.commit().await?;
+    Ok(true)
+}
+
+/// Get each signer's latest signal for each market, among the signals not expired at the given time
+pub async fn get_active_external_signals(pool: &PgPool, at: DateTime<Utc>) -> Result<Vec<ExternalSignalModel>, sqlx::Error> {
+    sqlx::query_as::<_, ExternalSignalModel>(
+        r#"
+        SELECT DISTINCT ON (signer, market_id)
+            signer, market_id, expected_return_apr, veto, issued_at, expires_at
+        FROM external_signals
+        WHERE expires_at > $1 AND issued_at <= $1
+        ORDER BY signer, market_id, issued_at DESC, id DESC
+        "#
+    )
+    .bind(at)
+    .fetch_all(pool)
+    .await
+}
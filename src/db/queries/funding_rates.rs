@@ -0,0 +1,57 @@
+use sqlx::{PgPool, Row};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+use crate::db::models::funding_rates::NewFundingRateModel;
+
+/// Insert funding rates, skipping ones already stored. Returns the number of rows inserted.
+pub async fn insert_funding_rates(pool: &PgPool, rates: &[NewFundingRateModel]) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let mut inserted = 0;
+    for rate in rates {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO funding_rates (ticker, effective_at, rate)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (ticker, effective_at) DO NOTHING
+            "#
+        )
+        .bind(&rate.ticker)
+        .bind(rate.effective_at)
+        .bind(rate.rate)
+        .execute(&mut *tx)
+        .await?;
+        inserted += result.rows_affected();
+    }
+    tx.commit().await?;
+    Ok(inserted)
+}
+
+/// Get the most recent stored funding timestamp for a ticker
+pub async fn get_latest_funding_rate_timestamp(pool: &PgPool, ticker: &str) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    let row = sqlx::query("SELECT MAX(effective_at) FROM funding_rates WHERE ticker = $1")
+        .bind(ticker)
+        .fetch_one(pool)
+        .await?;
+    Ok(row.get(0))
+}
+
+/// Get the mean hourly funding rate per ticker since the given time
+pub async fn get_average_funding_rates_since(pool: &PgPool, since: DateTime<Utc>) -> Result<HashMap<String, Decimal>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT ticker, AVG(rate) AS avg_rate
+        FROM funding_rates
+        WHERE effective_at >= $1
+        GROUP BY ticker
+        "#
+    )
+    .bind(since)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter()
+        .map(|row| (row.get::<String, _>(0), row.get::<Decimal, _>(1)))
+        .collect())
+}
\ No newline at end of file
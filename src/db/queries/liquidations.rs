@@ -0,0 +1,66 @@
+use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+
+use crate::db::models::liquidations::{NewLiquidationModel, LiquidationTotalsModel};
+
+/// Insert liquidations in a single transaction, skipping ones already recorded. Returns the number inserted.
+pub async fn insert_liquidations(pool: &PgPool, liquidations: &[NewLiquidationModel]) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let mut inserted = 0;
+    for liquidation in liquidations {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO liquidations (market_id, block_number, tx_hash, log_index, timestamp, is_long, size_usd)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (tx_hash, log_index) DO NOTHING
+            "#
+        )
+        .bind(liquidation.market_id)
+        .bind(liquidation.block_number)
+        .bind(&liquidation.tx_hash)
+        .bind(liquidation.log_index)
+        .bind(liquidation.timestamp)
+        .bind(liquidation.is_long)
+        .bind(liquidation.size_usd)
+        .execute(&mut *tx)
+        .await?;
+        inserted += result.rows_affected();
+    }
+    tx.commit().await?;
+    Ok(inserted)
+}
+
+/// Get the highest block a liquidation was recorded at
+pub async fn get_latest_liquidation_block(pool: &PgPool) -> Result<Option<i64>, sqlx::Error> {
+    sqlx::query_scalar::<_, Option<i64>>(
+        r#"
+        SELECT MAX(block_number)
+        FROM liquidations
+        "#
+    )
+    .fetch_one(pool)
+    .await
+}
+
+/// Get per-market liquidation totals since the given time, with the largest single-hour total
+pub async fn get_liquidation_totals_since(pool: &PgPool, since: DateTime<Utc>) -> Result<Vec<LiquidationTotalsModel>, sqlx::Error> {
+    sqlx::query_as::<_, LiquidationTotalsModel>(
+        r#"
+        WITH hourly AS (
+            SELECT market_id, date_trunc('hour', timestamp) AS hour, COUNT(*) AS liquidation_count,
+                SUM(size_usd) AS liquidated_usd,
+                SUM(CASE WHEN is_long THEN size_usd ELSE 0 END) AS long_liquidated_usd
+            FROM liquidations
+            WHERE timestamp >= $1
+            GROUP BY market_id, hour
+        )
+        SELECT market_id, SUM(liquidation_count)::BIGINT AS liquidation_count, SUM(liquidated_usd) AS liquidated_usd,
+            SUM(long_liquidated_usd) AS long_liquidated_usd, MAX(liquidated_usd) AS max_hourly_liquidated_usd
+        FROM hourly
+        GROUP BY market_id
+        "#
+    )
+    .bind(since)
+    .fetch_all(pool)
+    .await
+}
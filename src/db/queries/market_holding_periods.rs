@@ -0,0 +1,40 @@
+use sqlx::PgPool;
+
+use crate::db::models::market_holding_periods::MarketHoldingPeriodModel;
+
+/// Get the holding period state of every market of an account
+pub async fn get_market_holding_periods(pool: &PgPool, account_id: &str) -> Result<Vec<MarketHoldingPeriodModel>, sqlx::Error> {
+    sqlx::query_as::<_, MarketHoldingPeriodModel>(
+        r#"
+        SELECT market_id, entered_at, exited_at
+        FROM market_holding_periods
+        WHERE account_id = $1
+        "#
+    )
+    .bind(account_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Set the holding period state of an account's markets in a single transaction
+pub async fn upsert_market_holding_periods(pool: &PgPool, account_id: &str, periods: &[MarketHoldingPeriodModel]) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    for period in periods {
+        sqlx::query(
+            r#"
+            INSERT INTO market_holding_periods (account_id, market_id, entered_at, exited_at, updated_at)
+            VALUES ($1, $2, $3, $4, now())
+            ON CONFLICT (account_id, market_id) DO UPDATE
+            SET entered_at = EXCLUDED.entered_at, exited_at = EXCLUDED.exited_at, updated_at = now()
+            "#
+        )
+        .bind(account_id)
+        .bind(period.market_id)
+        .bind(period.entered_at)
+        .bind(period.exited_at)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
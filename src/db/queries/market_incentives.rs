@@ -0,0 +1,47 @@
+use sqlx::{PgPool, Row};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+use crate::db::models::market_incentives::NewMarketIncentiveModel;
+
+/// Insert a snapshot of market incentive emissions in a single transaction
+pub async fn insert_market_incentives(pool: &PgPool, incentives: &[NewMarketIncentiveModel]) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    for incentive in incentives {
+        sqlx::query(
+            r#"
+            INSERT INTO market_incentives (market_id, reward_token, reward_amount, period_secs, reward_usd_per_hour)
+            VALUES ($1, $2, $3, $4, $5)
+            "#
+        )
+        .bind(incentive.market_id)
+        .bind(&incentive.reward_token)
+        .bind(incentive.reward_amount)
+        .bind(incentive.period_secs)
+        .bind(incentive.reward_usd_per_hour)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Get the most recent incentive emission rate (USD per hour) per market recorded since the given time
+pub async fn get_latest_incentive_rates_since(pool: &PgPool, since: DateTime<Utc>) -> Result<HashMap<i32, Decimal>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT DISTINCT ON (market_id) market_id, reward_usd_per_hour
+        FROM market_incentives
+        WHERE recorded_at >= $1
+        ORDER BY market_id, recorded_at DESC
+        "#
+    )
+    .bind(since)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter()
+        .map(|row| (row.get::<i32, _>(0), row.get::<Decimal, _>(1)))
+        .collect())
+}
\ No newline at end of file
@@ -0,0 +1,42 @@
+use sqlx::PgPool;
+
+use crate::db::models::market_overview::MarketOverviewModel;
+
+/// Recompute the market overview view, concurrently so dashboard and strategy reads aren't blocked
+pub async fn refresh_market_overview(pool: &PgPool) -> Result<(), sqlx::Error> {
+    sqlx::query("REFRESH MATERIALIZED VIEW CONCURRENTLY market_overview")
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Fetch the overview of every market, highest 7d fee APR first
+pub async fn get_market_overview(pool: &PgPool) -> Result<Vec<MarketOverviewModel>, sqlx::Error> {
+    sqlx::query_as::<_, MarketOverviewModel>(
+        r#"
+        SELECT
+            market_id, market_address, display_name, deprecated, latest_timestamp, gm_price_mid,
+            gm_price_change_24h, utilization, open_interest_usd, pool_value_usd, fee_apr_24h, fee_apr_7d
+        FROM market_overview
+        ORDER BY fee_apr_7d DESC NULLS LAST
+        "#
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Fetch the overview of one market, None if it has no recorded state yet
+pub async fn get_market_overview_for_market(pool: &PgPool, market_id: i32) -> Result<Option<MarketOverviewModel>, sqlx::Error> {
+    sqlx::query_as::<_, MarketOverviewModel>(
+        r#"
+        SELECT
+            market_id, market_address, display_name, deprecated, latest_timestamp, gm_price_mid,
+            gm_price_change_24h, utilization, open_interest_usd, pool_value_usd, fee_apr_24h, fee_apr_7d
+        FROM market_overview
+        WHERE market_id = $1
+        "#
+    )
+    .bind(market_id)
+    .fetch_optional(pool)
+    .await
+}
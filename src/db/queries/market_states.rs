@@ -1,17 +1,16 @@
 use sqlx::{PgPool, Row};
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
-use ethers::types::Address;
 use rust_decimal::Decimal;
 
-use crate::db::models::market_states::{NewMarketStateModel, MarketStateModel};
+use crate::db::models::market_states::{NewMarketStateModel, NewBackfilledMarketStateModel, MarketStateModel, MarketCapsModel, RecordedGmPriceModel};
 
 /// Insert a single market state record
 pub async fn insert_market_state(
     pool: &PgPool,
     new_state: &NewMarketStateModel,
 ) -> Result<(), sqlx::Error> {
-    sqlx::query!(
+    sqlx::query(
         r#"
         INSERT INTO market_states (
             market_id,
@@ -43,45 +42,62 @@ pub async fn insert_market_state(
             fees_liquidation,
             fees_swap,
             fees_borrowing,
-            fees_total
+            fees_total,
+            max_pool_amount_long,
+            max_pool_amount_short,
+            max_open_interest_long,
+            max_open_interest_short,
+            taker_buy_volume,
+            taker_sell_volume,
+            taker_flow_imbalance,
+            traceparent
         )
         VALUES (
             $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, 
             $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, 
-            $25, $26, $27, $28, $29, $30
+            $25, $26, $27, $28, $29, $30, $31, $32, $33, $34, $35,
+            $36, $37, $38
         )
-        "#,
-        new_state.market_id,
-        new_state.timestamp,
-        new_state.borrowing_factor_long,
-        new_state.borrowing_factor_short,
-        new_state.pnl_long,
-        new_state.pnl_short,
-        new_state.pnl_net,
-        new_state.gm_price_min,
-        new_state.gm_price_max,
-        new_state.gm_price_mid,
-        new_state.pool_long_amount,
-        new_state.pool_short_amount,
-        new_state.pool_impact_amount,
-        new_state.pool_long_token_usd,
-        new_state.pool_short_token_usd,
-        new_state.pool_impact_token_usd,
-        new_state.open_interest_long,
-        new_state.open_interest_short,
-        new_state.open_interest_long_amount,
-        new_state.open_interest_short_amount,
-        new_state.open_interest_long_via_tokens,
-        new_state.open_interest_short_via_tokens,
-        new_state.utilization,
-        new_state.swap_volume,
-        new_state.trading_volume,
-        new_state.fees_position,
-        new_state.fees_liquidation,
-        new_state.fees_swap,
-        new_state.fees_borrowing,
-        new_state.fees_total
+        "#
     )
+    .bind(new_state.market_id)
+    .bind(new_state.timestamp)
+    .bind(new_state.borrowing_factor_long)
+    .bind(new_state.borrowing_factor_short)
+    .bind(new_state.pnl_long)
+    .bind(new_state.pnl_short)
+    .bind(new_state.pnl_net)
+    .bind(new_state.gm_price_min)
+    .bind(new_state.gm_price_max)
+    .bind(new_state.gm_price_mid)
+    .bind(new_state.pool_long_amount)
+    .bind(new_state.pool_short_amount)
+    .bind(new_state.pool_impact_amount)
+    .bind(new_state.pool_long_token_usd)
+    .bind(new_state.pool_short_token_usd)
+    .bind(new_state.pool_impact_token_usd)
+    .bind(new_state.open_interest_long)
+    .bind(new_state.open_interest_short)
+    .bind(new_state.open_interest_long_amount)
+    .bind(new_state.open_interest_short_amount)
+    .bind(new_state.open_interest_long_via_tokens)
+    .bind(new_state.open_interest_short_via_tokens)
+    .bind(new_state.utilization)
+    .bind(new_state.swap_volume)
+    .bind(new_state.trading_volume)
+    .bind(new_state.fees_position)
+    .bind(new_state.fees_liquidation)
+    .bind(new_state.fees_swap)
+    .bind(new_state.fees_borrowing)
+    .bind(new_state.fees_total)
+    .bind(new_state.max_pool_amount_long)
+    .bind(new_state.max_pool_amount_short)
+    .bind(new_state.max_open_interest_long)
+    .bind(new_state.max_open_interest_short)
+    .bind(new_state.taker_buy_volume)
+    .bind(new_state.taker_sell_volume)
+    .bind(new_state.taker_flow_imbalance)
+    .bind(&new_state.traceparent)
     .execute(pool)
     .await?;
 
@@ -175,6 +191,7 @@ pub async fn get_market_state_history_in_range(
 }
 
 /// Fetch all market states across all markets in a time range
+/// (hourly aggregates for downsampled history, raw observations for recent data)
 pub async fn get_all_market_states_in_range(
     pool: &PgPool,
     start: DateTime<Utc>,
@@ -183,6 +200,21 @@ pub async fn get_all_market_states_in_range(
     // Use query() instead of query_as!() for binary protocol
     let rows = sqlx::query(
         r#"
+        SELECT
+            id, market_id, timestamp,
+            borrowing_factor_long, borrowing_factor_short,
+            pnl_long, pnl_short, pnl_net,
+            gm_price_min, gm_price_max, gm_price_mid,
+            pool_long_amount, pool_short_amount, pool_impact_amount,
+            pool_long_token_usd, pool_short_token_usd, pool_impact_token_usd,
+            open_interest_long, open_interest_short,
+            open_interest_long_amount, open_interest_short_amount,
+            open_interest_long_via_tokens, open_interest_short_via_tokens,
+            utilization, swap_volume, trading_volume,
+            fees_position, fees_liquidation, fees_swap, fees_borrowing, fees_total
+        FROM market_states_hourly
+        WHERE timestamp >= $1 AND timestamp <= $2
+        UNION ALL
         SELECT
             id, market_id, timestamp,
             borrowing_factor_long, borrowing_factor_short,
@@ -249,40 +281,6 @@ pub async fn get_all_market_states_in_range(
     Ok(result)
 }
 
-/// Get market display names by joining markets and tokens tables
-pub async fn get_market_display_names(pool: &PgPool) -> Result<HashMap<Address, String>, sqlx::Error> {
-    let rows = sqlx::query!(
-        r#"
-        SELECT 
-            m.address as market_address,
-            it.symbol as index_token_symbol,
-            lt.symbol as long_token_symbol,
-            st.symbol as short_token_symbol
-        FROM markets m
-        JOIN tokens it ON m.index_token_id = it.id
-        JOIN tokens lt ON m.long_token_id = lt.id
-        JOIN tokens st ON m.short_token_id = st.id
-        "#
-    )
-    .fetch_all(pool)
-    .await?;
-
-    let display_names = rows.into_iter()
-        .filter_map(|row| {
-            let addr = row.market_address.parse::<Address>().ok()?;
-            let display_name = format!(
-                "{}/USD [{} - {}]", 
-                row.index_token_symbol, 
-                row.long_token_symbol, 
-                row.short_token_symbol
-            );
-            Some((addr, display_name))
-        })
-        .collect();
-
-    Ok(display_names)
-}
-
 /// Fetch latest market state for a specific market
 pub async fn get_latest_market_state_for_market(pool: &PgPool, market_id: i32) -> Result<Option<MarketStateModel>, sqlx::Error> {
     sqlx::query_as!(
@@ -328,6 +326,39 @@ pub async fn get_latest_market_states_for_all_markets(pool: &PgPool) -> Result<V
     .await
 }
 
+/// Fetch the latest market state recorded with pool caps for all markets
+pub async fn get_latest_market_caps_for_all_markets(pool: &PgPool) -> Result<Vec<MarketCapsModel>, sqlx::Error> {
+    sqlx::query_as::<_, MarketCapsModel>(
+        r#"
+        SELECT DISTINCT ON (market_id)
+            market_id, timestamp, pool_long_amount, pool_short_amount, pool_long_token_usd, pool_short_token_usd,
+            max_pool_amount_long, max_pool_amount_short, max_open_interest_long, max_open_interest_short
+        FROM market_states
+        WHERE max_pool_amount_long IS NOT NULL AND max_pool_amount_short IS NOT NULL
+        ORDER BY market_id, timestamp DESC
+        "#
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Fetch the latest GM mid price of every market recorded at or before the given time, with its timestamp
+pub async fn get_latest_gm_prices_as_of(pool: &PgPool, as_of: DateTime<Utc>) -> Result<Vec<(String, Decimal, DateTime<Utc>)>, sqlx::Error> {
+    sqlx::query_as::<_, (String, Decimal, DateTime<Utc>)>(
+        r#"
+        SELECT DISTINCT ON (ms.market_id)
+            m.address, ms.gm_price_mid, ms.timestamp
+        FROM market_states ms
+        JOIN markets m ON ms.market_id = m.id
+        WHERE ms.timestamp <= $1 AND ms.gm_price_mid IS NOT NULL
+        ORDER BY ms.market_id, ms.timestamp DESC
+        "#
+    )
+    .bind(as_of)
+    .fetch_all(pool)
+    .await
+}
+
 /// Fetch all market tokens
 pub async fn get_all_market_tokens(pool: &PgPool) -> Result<Vec<(String, String, Decimal, String, String, String)>, sqlx::Error> {
     let rows = sqlx::query!(
@@ -370,3 +401,191 @@ pub async fn get_all_market_tokens(pool: &PgPool) -> Result<Vec<(String, String,
         .collect();
     Ok(market_tokens)
 }
+
+/// Downsample raw market states older than the cutoff into hourly rows and delete the raw rows,
+/// returning the number of hourly rows written and raw rows deleted.
+/// Rates are averaged, per-interval volumes and fees are summed (so hourly fee totals are preserved),
+/// taker flow imbalance is recomputed from the summed taker volumes, and all other fields keep the last observation of the hour.
+pub async fn downsample_market_states_before(pool: &PgPool, cutoff: DateTime<Utc>) -> Result<(u64, u64), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let aggregated = sqlx::query(
+        r#"
+        INSERT INTO market_states_hourly (
+            market_id, timestamp,
+            borrowing_factor_long, borrowing_factor_short,
+            pnl_long, pnl_short, pnl_net,
+            gm_price_min, gm_price_max, gm_price_mid,
+            pool_long_amount, pool_short_amount, pool_impact_amount,
+            pool_long_token_usd, pool_short_token_usd, pool_impact_token_usd,
+            open_interest_long, open_interest_short,
+            open_interest_long_amount, open_interest_short_amount,
+            open_interest_long_via_tokens, open_interest_short_via_tokens,
+            utilization, swap_volume, trading_volume,
+            fees_position, fees_liquidation, fees_swap, fees_borrowing, fees_total,
+            taker_buy_volume, taker_sell_volume, taker_flow_imbalance,
+            sample_count
+        )
+        SELECT
+            market_id,
+            date_trunc('hour', timestamp) AS bucket,
+            AVG(borrowing_factor_long),
+            AVG(borrowing_factor_short),
+            (array_agg(pnl_long ORDER BY timestamp DESC))[1],
+            (array_agg(pnl_short ORDER BY timestamp DESC))[1],
+            (array_agg(pnl_net ORDER BY timestamp DESC))[1],
+            (array_agg(gm_price_min ORDER BY timestamp DESC))[1],
+            (array_agg(gm_price_max ORDER BY timestamp DESC))[1],
+            (array_agg(gm_price_mid ORDER BY timestamp DESC))[1],
+            (array_agg(pool_long_amount ORDER BY timestamp DESC))[1],
+            (array_agg(pool_short_amount ORDER BY timestamp DESC))[1],
+            (array_agg(pool_impact_amount ORDER BY timestamp DESC))[1],
+            (array_agg(pool_long_token_usd ORDER BY timestamp DESC))[1],
+            (array_agg(pool_short_token_usd ORDER BY timestamp DESC))[1],
+            (array_agg(pool_impact_token_usd ORDER BY timestamp DESC))[1],
+            (array_agg(open_interest_long ORDER BY timestamp DESC))[1],
+            (array_agg(open_interest_short ORDER BY timestamp DESC))[1],
+            (array_agg(open_interest_long_amount ORDER BY timestamp DESC))[1],
+            (array_agg(open_interest_short_amount ORDER BY timestamp DESC))[1],
+            (array_agg(open_interest_long_via_tokens ORDER BY timestamp DESC))[1],
+            (array_agg(open_interest_short_via_tokens ORDER BY timestamp DESC))[1],
+            AVG(utilization),
+            SUM(swap_volume),
+            SUM(trading_volume),
+            SUM(fees_position),
+            SUM(fees_liquidation),
+            SUM(fees_swap),
+            SUM(fees_borrowing),
+            SUM(fees_total),
+            SUM(taker_buy_volume),
+            SUM(taker_sell_volume),
+            (SUM(taker_buy_volume) - SUM(taker_sell_volume)) / NULLIF(SUM(taker_buy_volume) + SUM(taker_sell_volume), 0),
+            COUNT(*)
+        FROM market_states
+        WHERE timestamp < $1
+        GROUP BY market_id, bucket
+        ON CONFLICT (market_id, timestamp) DO NOTHING
+        "#
+    )
+    .bind(cutoff)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    let deleted = sqlx::query("DELETE FROM market_states WHERE timestamp < $1")
+        .bind(cutoff)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    tx.commit().await?;
+    Ok((aggregated, deleted))
+}
+
+/// Fetch the timestamp of the most recent market state across all markets
+pub async fn get_latest_market_state_timestamp(pool: &PgPool) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    let row = sqlx::query("SELECT MAX(timestamp) FROM market_states")
+        .fetch_one(pool)
+        .await?;
+    Ok(row.get(0))
+}
+
+
+/// Insert backfilled market states tagged with their source in a single transaction, returning the number inserted
+pub async fn insert_backfilled_market_states(
+    pool: &PgPool,
+    states: &[NewBackfilledMarketStateModel],
+    source: &str,
+) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let mut inserted = 0;
+    for state in states {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO market_states (market_id, timestamp, fees_total, source)
+            VALUES ($1, $2, $3, $4)
+            "#
+        )
+        .bind(state.market_id)
+        .bind(state.timestamp)
+        .bind(state.fees_total)
+        .bind(source)
+        .execute(&mut *tx)
+        .await?;
+        inserted += result.rows_affected();
+    }
+    tx.commit().await?;
+    Ok(inserted)
+}
+
+/// Get the earliest stored market state timestamp per market ID
+pub async fn get_earliest_market_state_timestamps(pool: &PgPool) -> Result<HashMap<i32, DateTime<Utc>>, sqlx::Error> {
+    let rows = sqlx::query("SELECT market_id, MIN(timestamp) FROM market_states GROUP BY market_id")
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.into_iter()
+        .map(|row| (row.get::<i32, _>(0), row.get::<DateTime<Utc>, _>(1)))
+        .collect())
+}
+
+/// Distinct trace contexts of the latest state recorded for each market
+pub async fn get_latest_market_state_traceparents(pool: &PgPool) -> Result<Vec<String>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT DISTINCT traceparent
+        FROM (
+            SELECT DISTINCT ON (market_id) traceparent
+            FROM market_states
+            ORDER BY market_id, timestamp DESC
+        ) latest
+        WHERE traceparent IS NOT NULL
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(|row| row.get("traceparent")).collect())
+}
+
+/// Fetch a random sample of collected GM prices since the given time, with the token prices recorded at or before each
+pub async fn get_recorded_gm_price_sample(pool: &PgPool, since: DateTime<Utc>, limit: i64) -> Result<Vec<RecordedGmPriceModel>, sqlx::Error> {
+    sqlx::query_as::<_, RecordedGmPriceModel>(
+        r#"
+        SELECT
+            ms.id AS market_state_id, ms.timestamp, ms.gm_price_min, ms.gm_price_max,
+            m.address AS market_address,
+            it.address AS index_token_address, lt.address AS long_token_address, st.address AS short_token_address,
+            it.decimals AS index_token_decimals, lt.decimals AS long_token_decimals, st.decimals AS short_token_decimals,
+            ip.min_price AS index_min_price, ip.max_price AS index_max_price,
+            lp.min_price AS long_min_price, lp.max_price AS long_max_price,
+            sp.min_price AS short_min_price, sp.max_price AS short_max_price
+        FROM (
+            SELECT id, market_id, timestamp, gm_price_min, gm_price_max
+            FROM market_states
+            WHERE source = 'collector' AND timestamp >= $1 AND gm_price_min IS NOT NULL AND gm_price_max IS NOT NULL
+            ORDER BY random()
+            LIMIT $2
+        ) ms
+        JOIN markets m ON m.id = ms.market_id
+        JOIN tokens it ON it.id = m.index_token_id
+        JOIN tokens lt ON lt.id = m.long_token_id
+        JOIN tokens st ON st.id = m.short_token_id
+        JOIN LATERAL (
+            SELECT min_price, max_price FROM token_prices
+            WHERE token_id = it.id AND timestamp <= ms.timestamp ORDER BY timestamp DESC LIMIT 1
+        ) ip ON true
+        JOIN LATERAL (
+            SELECT min_price, max_price FROM token_prices
+            WHERE token_id = lt.id AND timestamp <= ms.timestamp ORDER BY timestamp DESC LIMIT 1
+        ) lp ON true
+        JOIN LATERAL (
+            SELECT min_price, max_price FROM token_prices
+            WHERE token_id = st.id AND timestamp <= ms.timestamp ORDER BY timestamp DESC LIMIT 1
+        ) sp ON true
+        "#
+    )
+    .bind(since)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
\ No newline at end of file
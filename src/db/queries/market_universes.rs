@@ -0,0 +1,18 @@
+use sqlx::PgPool;
+
+use crate::db::models::market_universes::MarketUniverseModel;
+
+/// Get the enabled market universes of an account
+pub async fn get_enabled_market_universes(pool: &PgPool, account_id: &str) -> Result<Vec<MarketUniverseModel>, sqlx::Error> {
+    sqlx::query_as::<_, MarketUniverseModel>(
+        r#"
+        SELECT universe, markets, capital_fraction, updated_at
+        FROM market_universes
+        WHERE account_id = $1 AND enabled
+        ORDER BY universe
+        "#
+    )
+    .bind(account_id)
+    .fetch_all(pool)
+    .await
+}
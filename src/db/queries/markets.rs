@@ -1,7 +1,9 @@
 use sqlx::{PgPool, Error};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use sqlx::Row;
 use ethers::types::Address;
 use crate::db::models::markets::{MarketModel, NewMarketModel};
+use crate::data_ingestion::market::market_descriptor::MarketDescriptor;
 
 /// Fetch a market by its database ID
 pub async fn get_market_by_id(pool: &PgPool, id: i32) -> Result<Option<MarketModel>, Error> {
@@ -101,4 +103,88 @@ pub async fn get_all_market_index_tokens(pool: &PgPool) -> Result<HashMap<i32, i
         .collect();
     
     Ok(map)
+}
+
+/// Set or clear the deprecated flag for a market, returning true if the status changed
+pub async fn set_market_deprecated(pool: &PgPool, address: &str, deprecated: bool) -> Result<bool, Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE markets
+        SET deprecated_at = CASE WHEN $2 THEN NOW() ELSE NULL END
+        WHERE address = $1 AND (deprecated_at IS NOT NULL) <> $2
+        "#
+    )
+    .bind(address)
+    .bind(deprecated)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Get the IDs of all markets currently flagged as deprecated
+pub async fn get_deprecated_market_ids(pool: &PgPool) -> Result<HashSet<i32>, Error> {
+    let rows = sqlx::query("SELECT id FROM markets WHERE deprecated_at IS NOT NULL")
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows.into_iter().map(|row| row.get::<i32, _>(0)).collect())
+}
+
+/// Get the descriptor of every market from its tokens, keyed by market ID
+pub async fn get_market_descriptors(pool: &PgPool) -> Result<HashMap<i32, MarketDescriptor>, Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            m.id,
+            m.address,
+            it.address AS index_token_address, it.symbol AS index_token_symbol,
+            lt.address AS long_token_address, lt.symbol AS long_token_symbol,
+            st.address AS short_token_address, st.symbol AS short_token_symbol
+        FROM markets m
+        JOIN tokens it ON m.index_token_id = it.id
+        JOIN tokens lt ON m.long_token_id = lt.id
+        JOIN tokens st ON m.short_token_id = st.id
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let parse = |value: String| value.parse::<Address>().map_err(|_| Error::Decode(format!("Invalid address {}", value).into()));
+    let mut descriptors = HashMap::new();
+    for row in rows {
+        descriptors.insert(row.get::<i32, _>("id"), MarketDescriptor {
+            market_token: parse(row.get("address"))?,
+            index_token: parse(row.get("index_token_address"))?,
+            long_token: parse(row.get("long_token_address"))?,
+            short_token: parse(row.get("short_token_address"))?,
+            index_symbol: row.get("index_token_symbol"),
+            long_symbol: row.get("long_token_symbol"),
+            short_symbol: row.get("short_token_symbol"),
+        });
+    }
+    Ok(descriptors)
+}
+
+/// Store the canonical display names of markets (ID, name), leaving names already up to date untouched.
+/// Returns the number of markets renamed.
+pub async fn set_market_display_names(pool: &PgPool, names: &[(i32, String)]) -> Result<u64, Error> {
+    let mut tx = pool.begin().await?;
+    let mut updated = 0;
+    for (market_id, display_name) in names {
+        let result = sqlx::query(
+            r#"
+            UPDATE markets
+            SET display_name = $2
+            WHERE id = $1 AND display_name IS DISTINCT FROM $2
+            "#
+        )
+        .bind(market_id)
+        .bind(display_name)
+        .execute(&mut *tx)
+        .await?;
+        updated += result.rows_affected();
+    }
+    tx.commit().await?;
+    Ok(updated)
 }
\ No newline at end of file
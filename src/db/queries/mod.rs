@@ -1,4 +1,25 @@
 pub mod tokens;
 pub mod token_prices;
 pub mod markets;
-pub mod market_states;
\ No newline at end of file
+pub mod market_states;
+pub mod trades;
+pub mod pending_plans;
+pub mod strategy_runs;
+pub mod orders;
+pub mod execution_costs;
+pub mod funding_rates;
+pub mod market_incentives;
+pub mod execution_plans;
+pub mod config_overrides;
+pub mod wallet_transactions;
+pub mod market_overview;
+pub mod spot_swaps;
+pub mod bridge_transfers;
+pub mod position_exposures;
+pub mod compliance_rules;
+pub mod pipeline_health;
+pub mod liquidations;
+pub mod market_holding_periods;
+pub mod external_signals;
+pub mod market_universes;
+pub mod rebalances;
\ No newline at end of file
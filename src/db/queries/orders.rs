@@ -0,0 +1,118 @@
+use sqlx::{PgPool, Row};
+use rust_decimal::Decimal;
+
+use crate::db::models::orders::{OrderModel, NewOrderModel};
+
+const ORDER_COLUMNS: &str = r#"
+    id, created_at, updated_at, venue, ticker, side, size, filled_size, reduce_only,
+    client_id, good_til_block, venue_order_id, tx_hash, status, parent_order_id, rebalance_id
+"#;
+
+/// Insert a single order record, returning its ID
+pub async fn insert_order(pool: &PgPool, account_id: &str, rebalance_id: Option<i32>, order: &NewOrderModel) -> Result<i32, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        INSERT INTO orders (
+            venue,
+            ticker,
+            side,
+            size,
+            reduce_only,
+            client_id,
+            good_til_block,
+            tx_hash,
+            status,
+            parent_order_id,
+            reference_price,
+            rebalance_id,
+            account_id
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+        RETURNING id
+        "#
+    )
+    .bind(&order.venue)
+    .bind(&order.ticker)
+    .bind(&order.side)
+    .bind(order.size)
+    .bind(order.reduce_only)
+    .bind(order.client_id)
+    .bind(order.good_til_block)
+    .bind(&order.tx_hash)
+    .bind(&order.status)
+    .bind(order.parent_order_id)
+    .bind(order.reference_price)
+    .bind(rebalance_id)
+    .bind(account_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.get(0))
+}
+
+/// Record the size weighted average fill price of an order
+pub async fn update_order_fill_price(pool: &PgPool, id: i32, average_fill_price: Decimal) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE orders
+        SET average_fill_price = $2,
+            updated_at = now()
+        WHERE id = $1
+        "#
+    )
+    .bind(id)
+    .bind(average_fill_price)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Update the status and fill state of an order, keeping the existing venue order ID when none is given
+pub async fn update_order_state(
+    pool: &PgPool,
+    id: i32,
+    status: &str,
+    filled_size: Decimal,
+    venue_order_id: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE orders
+        SET status = $2,
+            filled_size = $3,
+            venue_order_id = COALESCE($4, venue_order_id),
+            updated_at = now()
+        WHERE id = $1
+        "#
+    )
+    .bind(id)
+    .bind(status)
+    .bind(filled_size)
+    .bind(venue_order_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Fetch all of the account's orders with one of the given statuses on a venue, optionally for a single ticker
+pub async fn get_orders_by_status(
+    pool: &PgPool,
+    account_id: &str,
+    venue: &str,
+    statuses: &[&str],
+    ticker: Option<&str>,
+) -> Result<Vec<OrderModel>, sqlx::Error> {
+    let query = format!(
+        "SELECT {} FROM orders WHERE account_id = $1 AND venue = $2 AND status = ANY($3) AND ($4::TEXT IS NULL OR ticker = $4) ORDER BY created_at ASC",
+        ORDER_COLUMNS
+    );
+    let statuses: Vec<String> = statuses.iter().map(|s| s.to_string()).collect();
+    sqlx::query_as::<_, OrderModel>(&query)
+        .bind(account_id)
+        .bind(venue)
+        .bind(statuses)
+        .bind(ticker)
+        .fetch_all(pool)
+        .await
+}
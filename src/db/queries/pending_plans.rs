@@ -0,0 +1,194 @@
+use sqlx::{PgPool, Row};
+use chrono::{DateTime, Utc};
+
+use crate::db::models::pending_plans::{
+    PendingPlanModel,
+    PendingPlanActionModel,
+    NewPendingPlanActionModel,
+    PlanStatus,
+};
+
+/// Insert an account's plan and all of its proposed actions in a single transaction, returning the plan ID
+pub async fn insert_pending_plan(
+    pool: &PgPool,
+    account_id: &str,
+    expires_at: DateTime<Utc>,
+    actions: &[NewPendingPlanActionModel],
+) -> Result<i32, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let row = sqlx::query(
+        r#"
+        INSERT INTO pending_plans (expires_at, status, account_id)
+        VALUES ($1, $2, $3)
+        RETURNING id
+        "#
+    )
+    .bind(expires_at)
+    .bind(PlanStatus::Pending.as_str())
+    .bind(account_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    let plan_id: i32 = row.get(0);
+
+    for action in actions {
+        sqlx::query(
+            r#"
+            INSERT INTO pending_plan_actions (plan_id, market_id, target_weight, expected_return, status, notes)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#
+        )
+        .bind(plan_id)
+        .bind(action.market_id)
+        .bind(action.target_weight)
+        .bind(action.expected_return)
+        .bind(PlanStatus::Pending.as_str())
+        .bind(&action.notes)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(plan_id)
+}
+
+/// Fetch an account's plan by its ID
+pub async fn get_pending_plan(pool: &PgPool, account_id: &str, plan_id: i32) -> Result<Option<PendingPlanModel>, sqlx::Error> {
+    sqlx::query_as::<_, PendingPlanModel>(
+        "SELECT id, created_at, expires_at, decided_at, status FROM pending_plans WHERE id = $1 AND account_id = $2"
+    )
+    .bind(plan_id)
+    .bind(account_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Fetch all of an account's plans that are still waiting for a decision
+pub async fn get_undecided_plans(pool: &PgPool, account_id: &str) -> Result<Vec<PendingPlanModel>, sqlx::Error> {
+    sqlx::query_as::<_, PendingPlanModel>(
+        "SELECT id, created_at, expires_at, decided_at, status FROM pending_plans WHERE account_id = $1 AND status = $2 ORDER BY created_at ASC"
+    )
+    .bind(account_id)
+    .bind(PlanStatus::Pending.as_str())
+    .fetch_all(pool)
+    .await
+}
+
+/// Fetch all actions belonging to a plan
+pub async fn get_plan_actions(pool: &PgPool, plan_id: i32) -> Result<Vec<PendingPlanActionModel>, sqlx::Error> {
+    sqlx::query_as::<_, PendingPlanActionModel>(
+        r#"
+        SELECT id, plan_id, market_id, target_weight, expected_return, status, notes
+        FROM pending_plan_actions
+        WHERE plan_id = $1
+        ORDER BY target_weight DESC, id ASC
+        "#
+    )
+    .bind(plan_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Set the status of still-pending actions of an account's plan (all of them when `action_ids` is None),
+/// then settle the plan itself once no actions are left pending.
+/// Returns the number of actions updated.
+pub async fn decide_plan_actions(
+    pool: &PgPool,
+    account_id: &str,
+    plan_id: i32,
+    action_ids: Option<&[i32]>,
+    status: PlanStatus,
+) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    // Only the account's undecided plans that have not expired can be decided
+    let plan_open = sqlx::query(
+        "SELECT 1 FROM pending_plans WHERE id = $1 AND status = $2 AND expires_at > now() AND account_id = $3 FOR UPDATE"
+    )
+    .bind(plan_id)
+    .bind(PlanStatus::Pending.as_str())
+    .bind(account_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    if plan_open.is_none() {
+        return Err(sqlx::Error::RowNotFound);
+    }
+
+    let updated = match action_ids {
+        Some(ids) => sqlx::query(
+            "UPDATE pending_plan_actions SET status = $3 WHERE plan_id = $1 AND id = ANY($2) AND status = $4"
+        )
+        .bind(plan_id)
+        .bind(ids)
+        .bind(status.as_str())
+        .bind(PlanStatus::Pending.as_str())
+        .execute(&mut *tx)
+        .await?
+        .rows_affected(),
+        None => sqlx::query(
+            "UPDATE pending_plan_actions SET status = $2 WHERE plan_id = $1 AND status = $3"
+        )
+        .bind(plan_id)
+        .bind(status.as_str())
+        .bind(PlanStatus::Pending.as_str())
+        .execute(&mut *tx)
+        .await?
+        .rows_affected(),
+    };
+
+    // Plan is approved once every action is decided and at least one was approved, otherwise rejected
+    sqlx::query(
+        r#"
+        UPDATE pending_plans p
+        SET status = CASE
+                WHEN EXISTS (SELECT 1 FROM pending_plan_actions a WHERE a.plan_id = p.id AND a.status = $2) THEN $2
+                ELSE $3
+            END,
+            decided_at = now()
+        WHERE p.id = $1
+          AND NOT EXISTS (SELECT 1 FROM pending_plan_actions a WHERE a.plan_id = p.id AND a.status = $4)
+        "#
+    )
+    .bind(plan_id)
+    .bind(PlanStatus::Approved.as_str())
+    .bind(PlanStatus::Rejected.as_str())
+    .bind(PlanStatus::Pending.as_str())
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(updated)
+}
+
+/// Expire all of an account's undecided plans (and their undecided actions) past their expiry time,
+/// returning the number of plans expired
+pub async fn expire_stale_plans(pool: &PgPool, account_id: &str) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"
+        UPDATE pending_plan_actions a
+        SET status = $1
+        FROM pending_plans p
+        WHERE a.plan_id = p.id AND p.status = $2 AND p.expires_at <= now() AND a.status = $2 AND p.account_id = $3
+        "#
+    )
+    .bind(PlanStatus::Expired.as_str())
+    .bind(PlanStatus::Pending.as_str())
+    .bind(account_id)
+    .execute(&mut *tx)
+    .await?;
+
+    let expired = sqlx::query(
+        "UPDATE pending_plans SET status = $1, decided_at = now() WHERE status = $2 AND expires_at <= now() AND account_id = $3"
+    )
+    .bind(PlanStatus::Expired.as_str())
+    .bind(PlanStatus::Pending.as_str())
+    .bind(account_id)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    tx.commit().await?;
+    Ok(expired)
+}
@@ -0,0 +1,20 @@
+use sqlx::PgPool;
+
+use crate::db::models::pipeline_health::NewPipelineHealthModel;
+
+/// Insert a data stream health transition
+pub async fn insert_pipeline_health(pool: &PgPool, health: &NewPipelineHealthModel) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO pipeline_health (stream, status, last_entry_at, stale_after_secs)
+        VALUES ($1, $2, $3, $4)
+        "#
+    )
+    .bind(&health.stream)
+    .bind(&health.status)
+    .bind(health.last_entry_at)
+    .bind(health.stale_after_secs)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
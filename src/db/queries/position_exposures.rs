@@ -0,0 +1,43 @@
+use sqlx::PgPool;
+
+use crate::db::models::position_exposures::{PositionExposureModel, NewPositionExposureModel};
+
+/// Insert the exposures recorded by one of an account's runs in a single transaction
+pub async fn insert_position_exposures(pool: &PgPool, account_id: &str, exposures: &[NewPositionExposureModel]) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    for exposure in exposures {
+        sqlx::query(
+            r#"
+            INSERT INTO position_exposures (market_id, gm_value_usd, long_token_usd, short_token_usd, long_share, entry_long_share, account_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#
+        )
+        .bind(exposure.market_id)
+        .bind(exposure.gm_value_usd)
+        .bind(exposure.long_token_usd)
+        .bind(exposure.short_token_usd)
+        .bind(exposure.long_share)
+        .bind(exposure.entry_long_share)
+        .bind(account_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Fetch the latest exposure an account recorded for every market
+pub async fn get_latest_position_exposures(pool: &PgPool, account_id: &str) -> Result<Vec<PositionExposureModel>, sqlx::Error> {
+    sqlx::query_as::<_, PositionExposureModel>(
+        r#"
+        SELECT DISTINCT ON (market_id)
+            id, market_id, recorded_at, gm_value_usd, long_token_usd, short_token_usd, long_share, entry_long_share
+        FROM position_exposures
+        WHERE account_id = $1
+        ORDER BY market_id, recorded_at DESC
+        "#
+    )
+    .bind(account_id)
+    .fetch_all(pool)
+    .await
+}
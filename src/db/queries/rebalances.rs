@@ -0,0 +1,108 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row};
+
+use crate::db::models::rebalances::RebalanceCostModel;
+
+/// Insert a rebalance for an account, returning its ID
+pub async fn insert_rebalance(pool: &PgPool, account_id: &str, source: &str) -> Result<i32, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        INSERT INTO rebalances (source, account_id)
+        VALUES ($1, $2)
+        RETURNING id
+        "#
+    )
+    .bind(source)
+    .bind(account_id)
+    .fetch_one(pool)
+    .await?;
+    Ok(row.get(0))
+}
+
+/// Stamp a rebalance as completed, a rebalance completed earlier keeps its completion time
+pub async fn complete_rebalance(pool: &PgPool, id: i32) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE rebalances
+        SET completed_at = now()
+        WHERE id = $1 AND completed_at IS NULL
+        "#
+    )
+    .bind(id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Whether a rebalance exists for an account
+pub async fn rebalance_exists(pool: &PgPool, account_id: &str, id: i32) -> Result<bool, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT EXISTS (SELECT 1 FROM rebalances WHERE id = $1 AND account_id = $2)
+        "#
+    )
+    .bind(id)
+    .bind(account_id)
+    .fetch_one(pool)
+    .await?;
+    Ok(row.get(0))
+}
+
+/// Cost breakdown of an account's rebalances started since the given time, newest first. Slippage of GMX costs is
+/// the GM requests' estimated price impact, of other venues the spot swaps' output below mid. Hedge slippage is the
+/// filled size times the fill price's distance from the reference price, against the order's side.
+pub async fn get_rebalance_costs_since(pool: &PgPool, account_id: &str, since: DateTime<Utc>) -> Result<Vec<RebalanceCostModel>, sqlx::Error> {
+    sqlx::query_as::<_, RebalanceCostModel>(
+        r#"
+        WITH leg_costs AS (
+            SELECT
+                rebalance_id,
+                SUM(slippage_usd) FILTER (WHERE venue <> 'gmx') AS swap_slippage_usd,
+                SUM(slippage_usd) FILTER (WHERE venue = 'gmx') AS gm_impact_usd,
+                SUM(execution_fee_usd - execution_fee_refund_usd) AS execution_fee_usd,
+                SUM(gas_cost_usd) AS gas_cost_usd,
+                COUNT(*) AS tx_count
+            FROM execution_costs
+            WHERE rebalance_id IS NOT NULL
+            GROUP BY rebalance_id
+        ),
+        hedge_costs AS (
+            SELECT
+                rebalance_id,
+                SUM(
+                    CASE WHEN side = 'BUY' THEN average_fill_price - reference_price ELSE reference_price - average_fill_price END
+                    * filled_size
+                ) AS hedge_slippage_usd,
+                COUNT(*) AS order_count,
+                COUNT(*) FILTER (
+                    WHERE filled_size > 0 AND (average_fill_price IS NULL OR reference_price IS NULL)
+                ) AS unpriced_order_count
+            FROM orders
+            WHERE rebalance_id IS NOT NULL
+            GROUP BY rebalance_id
+        )
+        SELECT
+            r.id AS rebalance_id,
+            r.created_at,
+            r.completed_at,
+            r.source,
+            COALESCE(l.swap_slippage_usd, 0) AS swap_slippage_usd,
+            COALESCE(l.gm_impact_usd, 0) AS gm_impact_usd,
+            COALESCE(l.execution_fee_usd, 0) AS execution_fee_usd,
+            COALESCE(l.gas_cost_usd, 0) AS gas_cost_usd,
+            COALESCE(h.hedge_slippage_usd, 0) AS hedge_slippage_usd,
+            COALESCE(l.tx_count, 0) AS tx_count,
+            COALESCE(h.order_count, 0) AS order_count,
+            COALESCE(h.unpriced_order_count, 0) AS unpriced_order_count
+        FROM rebalances r
+        LEFT JOIN leg_costs l ON l.rebalance_id = r.id
+        LEFT JOIN hedge_costs h ON h.rebalance_id = r.id
+        WHERE r.account_id = $1 AND r.created_at >= $2
+        ORDER BY r.created_at DESC
+        "#
+    )
+    .bind(account_id)
+    .bind(since)
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,37 @@
+use sqlx::{PgPool, Row};
+use rust_decimal::Decimal;
+
+use crate::db::models::spot_swaps::NewSpotSwapModel;
+
+/// Insert the aggregate record of a spot swap, returning its ID
+pub async fn insert_spot_swap(pool: &PgPool, account_id: &str, rebalance_id: Option<i32>, swap: &NewSpotSwapModel) -> Result<i32, sqlx::Error> {
+    let average_rate = if swap.from_amount > Decimal::ZERO { Some(swap.to_amount / swap.from_amount) } else { None };
+    let row = sqlx::query(
+        r#"
+        INSERT INTO spot_swaps (
+            from_token_address, to_token_address, side, requested_amount, from_amount, to_amount, average_rate,
+            slices_planned, slices_filled, status, gas_cost_usd, tx_hashes, screened_contracts, rebalance_id, account_id
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+        RETURNING id
+        "#
+    )
+    .bind(&swap.from_token_address)
+    .bind(&swap.to_token_address)
+    .bind(&swap.side)
+    .bind(swap.requested_amount)
+    .bind(swap.from_amount)
+    .bind(swap.to_amount)
+    .bind(average_rate)
+    .bind(swap.slices_planned)
+    .bind(swap.slices_filled)
+    .bind(&swap.status)
+    .bind(swap.gas_cost_usd)
+    .bind(&swap.tx_hashes)
+    .bind(&swap.screened_contracts)
+    .bind(rebalance_id)
+    .bind(account_id)
+    .fetch_one(pool)
+    .await?;
+    Ok(row.get(0))
+}
@@ -0,0 +1,260 @@
+use sqlx::{PgPool, Row};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+
+use crate::db::models::strategy_runs::{
+    StrategyRunModel,
+    NewStrategyRunModel,
+    StrategyRunMarketModel,
+    NewStrategyRunMarketModel,
+    StrategyRunInputModel,
+    NewStrategyRunInputModel,
+    ReturnModelMetricsModel,
+    NewReturnModelMetricsModel,
+};
+
+const RUN_COLUMNS: &str = "id, created_at, risk_free_rate_apr, expected_return_bps, volatility_bps, sharpe_ratio, relaxed_constraints, universe";
+
+/// Insert an account's strategy run with its per-market outputs and input digests in a single transaction, returning the run ID
+pub async fn insert_strategy_run(
+    pool: &PgPool,
+    account_id: &str,
+    run: &NewStrategyRunModel,
+    markets: &[NewStrategyRunMarketModel],
+    inputs: &[NewStrategyRunInputModel],
+) -> Result<i32, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let row = sqlx::query(
+        r#"
+        INSERT INTO strategy_runs (risk_free_rate_apr, expected_return_bps, volatility_bps, sharpe_ratio, relaxed_constraints, universe, account_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING id
+        "#
+    )
+    .bind(run.risk_free_rate_apr)
+    .bind(run.expected_return_bps)
+    .bind(run.volatility_bps)
+    .bind(run.sharpe_ratio)
+    .bind(&run.relaxed_constraints)
+    .bind(&run.universe)
+    .bind(account_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    let run_id: i32 = row.get(0);
+
+    for market in markets {
+        sqlx::query(
+            r#"
+            INSERT INTO strategy_run_markets (run_id, market_id, expected_return_bps, target_weight, raw_target_weight, smoothed_target_weight)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#
+        )
+        .bind(run_id)
+        .bind(market.market_id)
+        .bind(market.expected_return_bps)
+        .bind(market.target_weight)
+        .bind(market.raw_target_weight)
+        .bind(market.smoothed_target_weight)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    for input in inputs {
+        sqlx::query(
+            r#"
+            INSERT INTO strategy_run_inputs (
+                run_id, market_id, market_row_count, index_price_row_count, first_timestamp, last_timestamp,
+                mean_fee_rate, volatility, last_index_price, pool_value_usd, data_quality
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            "#
+        )
+        .bind(run_id)
+        .bind(input.market_id)
+        .bind(input.market_row_count)
+        .bind(input.index_price_row_count)
+        .bind(input.first_timestamp)
+        .bind(input.last_timestamp)
+        .bind(input.mean_fee_rate)
+        .bind(input.volatility)
+        .bind(input.last_index_price)
+        .bind(input.pool_value_usd)
+        .bind(input.data_quality)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(run_id)
+}
+
+/// Fetch a single run of an account by ID
+pub async fn get_run(pool: &PgPool, account_id: &str, run_id: i32) -> Result<Option<StrategyRunModel>, sqlx::Error> {
+    sqlx::query_as::<_, StrategyRunModel>(&format!("SELECT {} FROM strategy_runs WHERE id = $1 AND account_id = $2", RUN_COLUMNS))
+        .bind(run_id)
+        .bind(account_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Fetch an account's most recently recorded run
+pub async fn get_latest_run(pool: &PgPool, account_id: &str) -> Result<Option<StrategyRunModel>, sqlx::Error> {
+    sqlx::query_as::<_, StrategyRunModel>(&format!("SELECT {} FROM strategy_runs WHERE account_id = $1 ORDER BY created_at DESC LIMIT 1", RUN_COLUMNS))
+        .bind(account_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Fetch an account's most recently recorded run over a market universe (None for runs over every market)
+pub async fn get_latest_universe_run(pool: &PgPool, account_id: &str, universe: Option<&str>) -> Result<Option<StrategyRunModel>, sqlx::Error> {
+    sqlx::query_as::<_, StrategyRunModel>(&format!(
+        "SELECT {} FROM strategy_runs WHERE account_id = $1 AND universe IS NOT DISTINCT FROM $2 ORDER BY created_at DESC LIMIT 1",
+        RUN_COLUMNS
+    ))
+        .bind(account_id)
+        .bind(universe)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Fetch the per-market input digests of a run
+pub async fn get_run_inputs(pool: &PgPool, run_id: i32) -> Result<Vec<StrategyRunInputModel>, sqlx::Error> {
+    sqlx::query_as::<_, StrategyRunInputModel>(
+        r#"
+        SELECT id, run_id, market_id, market_row_count, index_price_row_count, first_timestamp, last_timestamp,
+               mean_fee_rate, volatility, last_index_price, pool_value_usd, data_quality
+        FROM strategy_run_inputs
+        WHERE run_id = $1
+        ORDER BY market_id
+        "#
+    )
+    .bind(run_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Fetch an account's runs created before the cutoff that have not been evaluated yet
+pub async fn get_unevaluated_runs(pool: &PgPool, account_id: &str, created_before: DateTime<Utc>) -> Result<Vec<StrategyRunModel>, sqlx::Error> {
+    sqlx::query_as::<_, StrategyRunModel>(&format!(
+        r#"
+        SELECT {}
+        FROM strategy_runs r
+        WHERE r.created_at <= $1
+          AND r.account_id = $2
+          AND NOT EXISTS (SELECT 1 FROM return_model_metrics m WHERE m.run_id = r.id)
+        ORDER BY r.created_at ASC
+        "#,
+        RUN_COLUMNS
+    ))
+    .bind(created_before)
+    .bind(account_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Fetch the per-market outputs of a run
+pub async fn get_run_markets(pool: &PgPool, run_id: i32) -> Result<Vec<StrategyRunMarketModel>, sqlx::Error> {
+    sqlx::query_as::<_, StrategyRunMarketModel>(
+        r#"
+        SELECT id, run_id, market_id, expected_return_bps, target_weight, raw_target_weight, smoothed_target_weight
+        FROM strategy_run_markets
+        WHERE run_id = $1
+        "#
+    )
+    .bind(run_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Fetch the most recent GM token mid price of a market at or before the given time
+pub async fn get_gm_price_at(
+    pool: &PgPool,
+    market_id: i32,
+    at: DateTime<Utc>,
+) -> Result<Option<(DateTime<Utc>, Decimal)>, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT timestamp, gm_price_mid
+        FROM market_states
+        WHERE market_id = $1 AND timestamp <= $2 AND gm_price_mid IS NOT NULL
+        ORDER BY timestamp DESC
+        LIMIT 1
+        "#
+    )
+    .bind(market_id)
+    .bind(at)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|r| (r.get(0), r.get(1))))
+}
+
+/// Fetch the pool composition (long and short token USD value) of a market at or before the given time
+pub async fn get_pool_composition_at(
+    pool: &PgPool,
+    market_id: i32,
+    at: DateTime<Utc>,
+) -> Result<Option<(Decimal, Decimal)>, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT pool_long_token_usd, pool_short_token_usd
+        FROM market_states
+        WHERE market_id = $1 AND timestamp <= $2
+            AND pool_long_token_usd IS NOT NULL AND pool_short_token_usd IS NOT NULL
+        ORDER BY timestamp DESC
+        LIMIT 1
+        "#
+    )
+    .bind(market_id)
+    .bind(at)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|r| (r.get(0), r.get(1))))
+}
+
+/// Insert prediction error metrics for a run
+pub async fn insert_return_model_metrics(pool: &PgPool, metrics: &NewReturnModelMetricsModel) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO return_model_metrics (
+            run_id, horizon_hours, market_count, bias_bps, mae_bps, rank_correlation, reporting_currency,
+            portfolio_return_bps, benchmark_return_bps, alpha_bps
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        ON CONFLICT (run_id) DO NOTHING
+        "#
+    )
+    .bind(metrics.run_id)
+    .bind(metrics.horizon_hours)
+    .bind(metrics.market_count)
+    .bind(metrics.bias_bps)
+    .bind(metrics.mae_bps)
+    .bind(metrics.rank_correlation)
+    .bind(&metrics.reporting_currency)
+    .bind(metrics.portfolio_return_bps)
+    .bind(metrics.benchmark_return_bps)
+    .bind(metrics.alpha_bps)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Fetch the most recently evaluated metrics, newest first
+pub async fn get_recent_return_model_metrics(pool: &PgPool, limit: i64) -> Result<Vec<ReturnModelMetricsModel>, sqlx::Error> {
+    sqlx::query_as::<_, ReturnModelMetricsModel>(
+        r#"
+        SELECT m.id, m.run_id, m.evaluated_at, m.horizon_hours, m.market_count, m.bias_bps, m.mae_bps, m.rank_correlation, m.reporting_currency,
+               m.portfolio_return_bps, m.benchmark_return_bps, m.alpha_bps
+        FROM return_model_metrics m
+        JOIN strategy_runs r ON r.id = m.run_id
+        ORDER BY r.created_at DESC
+        LIMIT $1
+        "#
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
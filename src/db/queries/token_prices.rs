@@ -3,25 +3,49 @@ use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use std::collections::HashMap;
 
-use crate::db::models::token_prices::{NewTokenPriceModel, TokenPriceModel};
+use crate::db::models::token_prices::{NewTokenPriceModel, TokenPriceModel, NewQuarantinedPriceModel};
 
 /// Insert a single token price record
 pub async fn insert_token_price(
     pool: &PgPool,
     new_price: &NewTokenPriceModel,
 ) -> Result<(), sqlx::Error> {
-    sqlx::query!(
+    sqlx::query(
         r#"
-        INSERT INTO token_prices (token_id, timestamp, min_price, max_price, mid_price)
-        VALUES ($1, $2, $3, $4, $5)
-        "#,
-        new_price.token_id,
-        new_price.timestamp,
-        new_price.min_price,
-        new_price.max_price,
-        new_price.mid_price,
+        INSERT INTO token_prices (token_id, timestamp, min_price, max_price, mid_price, traceparent)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        "#
+    )
+    .bind(new_price.token_id)
+    .bind(new_price.timestamp)
+    .bind(new_price.min_price)
+    .bind(new_price.max_price)
+    .bind(new_price.mid_price)
+    .bind(&new_price.traceparent)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
 
+/// Insert a price that failed ingestion validation into the quarantine table
+pub async fn insert_quarantined_price(
+    pool: &PgPool,
+    price: &NewQuarantinedPriceModel,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO quarantined_prices (token_id, timestamp, min_price, max_price, mid_price, reference_price, reason)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        "#
     )
+    .bind(price.token_id)
+    .bind(price.timestamp)
+    .bind(price.min_price)
+    .bind(price.max_price)
+    .bind(price.mid_price)
+    .bind(price.reference_price)
+    .bind(&price.reason)
     .execute(pool)
     .await?;
 
@@ -96,6 +120,7 @@ pub async fn get_token_price_history_in_range(
 }
 
 /// Fetch all token prices across all tokens in a time range
+/// (hourly aggregates for downsampled history, raw observations for recent data)
 pub async fn get_all_token_prices_in_range(
     pool: &PgPool,
     start: DateTime<Utc>,
@@ -105,6 +130,10 @@ pub async fn get_all_token_prices_in_range(
     let rows = sqlx::query(
         r#"
         SELECT id, token_id, timestamp, min_price, max_price, mid_price
+        FROM token_prices_hourly
+        WHERE timestamp >= $1 AND timestamp <= $2
+        UNION ALL
+        SELECT id, token_id, timestamp, min_price, max_price, mid_price
         FROM token_prices
         WHERE timestamp >= $1 AND timestamp <= $2
         ORDER BY token_id, timestamp
@@ -150,6 +179,27 @@ pub async fn get_latest_token_price_for_token(pool: &PgPool, token_id: i32) -> R
     .await
 }
 
+/// Fetch the most recent token price for a specific token at or before a given timestamp
+pub async fn get_token_price_at_or_before(
+    pool: &PgPool,
+    token_id: i32,
+    timestamp: DateTime<Utc>,
+) -> Result<Option<TokenPriceModel>, sqlx::Error> {
+    sqlx::query_as::<_, TokenPriceModel>(
+        r#"
+        SELECT id, token_id, timestamp, min_price, max_price, mid_price
+        FROM token_prices
+        WHERE token_id = $1 AND timestamp <= $2
+        ORDER BY timestamp DESC
+        LIMIT 1
+        "#
+    )
+    .bind(token_id)
+    .bind(timestamp)
+    .fetch_optional(pool)
+    .await
+}
+
 /// Fetch the latest token prices for all tokens
 pub async fn get_latest_token_prices_for_all_tokens(pool: &PgPool) -> Result<Vec<TokenPriceModel>, sqlx::Error> {
     sqlx::query_as!(
@@ -239,4 +289,116 @@ pub async fn get_latest_price_props_for_market(
     } else {
         Ok(None)
     }
-}
\ No newline at end of file
+}
+
+/// Downsample raw token prices older than the cutoff into hourly OHLC rows and delete the raw rows,
+/// returning the number of hourly rows written and raw rows deleted
+pub async fn downsample_token_prices_before(pool: &PgPool, cutoff: DateTime<Utc>) -> Result<(u64, u64), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let aggregated = sqlx::query(
+        r#"
+        INSERT INTO token_prices_hourly (
+            token_id, timestamp,
+            open_price, high_price, low_price, close_price,
+            min_price, max_price, mid_price,
+            sample_count
+        )
+        SELECT
+            token_id,
+            date_trunc('hour', timestamp) AS bucket,
+            (array_agg(mid_price ORDER BY timestamp ASC))[1],
+            MAX(mid_price),
+            MIN(mid_price),
+            (array_agg(mid_price ORDER BY timestamp DESC))[1],
+            MIN(min_price),
+            MAX(max_price),
+            (array_agg(mid_price ORDER BY timestamp DESC))[1],
+            COUNT(*)
+        FROM token_prices
+        WHERE timestamp < $1
+        GROUP BY token_id, bucket
+        ON CONFLICT (token_id, timestamp) DO NOTHING
+        "#
+    )
+    .bind(cutoff)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    let deleted = sqlx::query("DELETE FROM token_prices WHERE timestamp < $1")
+        .bind(cutoff)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    tx.commit().await?;
+    Ok((aggregated, deleted))
+}
+
+/// Fetch the timestamp of the most recent token price across all tokens
+pub async fn get_latest_token_price_timestamp(pool: &PgPool) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    let row = sqlx::query("SELECT MAX(timestamp) FROM token_prices")
+        .fetch_one(pool)
+        .await?;
+    Ok(row.get(0))
+}
+
+
+/// Insert backfilled token prices tagged with their source in a single transaction, returning the number inserted
+pub async fn insert_backfilled_token_prices(
+    pool: &PgPool,
+    prices: &[NewTokenPriceModel],
+    source: &str,
+) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let mut inserted = 0;
+    for price in prices {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO token_prices (token_id, timestamp, min_price, max_price, mid_price, source)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#
+        )
+        .bind(price.token_id)
+        .bind(price.timestamp)
+        .bind(price.min_price)
+        .bind(price.max_price)
+        .bind(price.mid_price)
+        .bind(source)
+        .execute(&mut *tx)
+        .await?;
+        inserted += result.rows_affected();
+    }
+    tx.commit().await?;
+    Ok(inserted)
+}
+
+/// Get the earliest stored token price timestamp per token ID
+pub async fn get_earliest_token_price_timestamps(pool: &PgPool) -> Result<HashMap<i32, DateTime<Utc>>, sqlx::Error> {
+    let rows = sqlx::query("SELECT token_id, MIN(timestamp) FROM token_prices GROUP BY token_id")
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.into_iter()
+        .map(|row| (row.get::<i32, _>(0), row.get::<DateTime<Utc>, _>(1)))
+        .collect())
+}
+
+/// Distinct trace contexts of the latest price recorded for each token
+pub async fn get_latest_token_price_traceparents(pool: &PgPool) -> Result<Vec<String>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT DISTINCT traceparent
+        FROM (
+            SELECT DISTINCT ON (token_id) traceparent
+            FROM token_prices
+            ORDER BY token_id, timestamp DESC
+        ) latest
+        WHERE traceparent IS NOT NULL
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(|row| row.get("traceparent")).collect())
+}
@@ -0,0 +1,187 @@
+use sqlx::{PgPool, Row};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+
+use crate::db::models::trades::{TradeModel, NewTradeModel};
+
+const TRADE_COLUMNS: &str = r#"
+    id, created_at, updated_at, action_type, status, market_id, to_market_id,
+    long_token_amount, short_token_amount, market_token_amount,
+    tx_hash, order_key, cancel_tx_hash,
+    execution_fee, gas_used, gas_price, gas_cost_usd, execution_fee_refund,
+    notional_usd, compliance_rule_id
+"#;
+
+/// Insert a single trade record for an account, returning its ID
+pub async fn insert_trade(pool: &PgPool, account_id: &str, rebalance_id: Option<i32>, trade: &NewTradeModel) -> Result<i32, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        INSERT INTO trades (
+            action_type,
+            status,
+            market_id,
+            to_market_id,
+            long_token_amount,
+            short_token_amount,
+            market_token_amount,
+            tx_hash,
+            order_key,
+            execution_fee,
+            gas_used,
+            gas_price,
+            gas_cost_usd,
+            notional_usd,
+            compliance_rule_id,
+            account_id,
+            rebalance_id
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+        RETURNING id
+        "#
+    )
+    .bind(&trade.action_type)
+    .bind(&trade.status)
+    .bind(trade.market_id)
+    .bind(trade.to_market_id)
+    .bind(trade.long_token_amount)
+    .bind(trade.short_token_amount)
+    .bind(trade.market_token_amount)
+    .bind(&trade.tx_hash)
+    .bind(&trade.order_key)
+    .bind(trade.execution_fee)
+    .bind(trade.gas_used)
+    .bind(trade.gas_price)
+    .bind(trade.gas_cost_usd)
+    .bind(trade.notional_usd)
+    .bind(&trade.compliance_rule_id)
+    .bind(account_id)
+    .bind(rebalance_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.get(0))
+}
+
+/// Update the status of a trade, optionally recording the cancellation transaction hash
+pub async fn update_trade_status(
+    pool: &PgPool,
+    id: i32,
+    status: &str,
+    cancel_tx_hash: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE trades
+        SET status = $2,
+            cancel_tx_hash = COALESCE($3, cancel_tx_hash),
+            updated_at = now()
+        WHERE id = $1
+        "#
+    )
+    .bind(id)
+    .bind(status)
+    .bind(cancel_tx_hash)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Record the execution fee refunded to a trade after keeper execution
+pub async fn update_trade_execution_fee_refund(pool: &PgPool, id: i32, execution_fee_refund: Decimal) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE trades SET execution_fee_refund = $2 WHERE id = $1")
+        .bind(id)
+        .bind(execution_fee_refund)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Fetch all of an account's trades with the given status
+pub async fn get_trades_by_status(pool: &PgPool, account_id: &str, status: &str) -> Result<Vec<TradeModel>, sqlx::Error> {
+    sqlx::query_as::<_, TradeModel>(
+        &format!("SELECT {} FROM trades WHERE account_id = $1 AND status = $2 ORDER BY created_at ASC", TRADE_COLUMNS)
+    )
+    .bind(account_id)
+    .bind(status)
+    .fetch_all(pool)
+    .await
+}
+
+/// Fetch all of an account's trades created within a time range
+pub async fn get_trades_in_range(
+    pool: &PgPool,
+    account_id: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Vec<TradeModel>, sqlx::Error> {
+    sqlx::query_as::<_, TradeModel>(
+        &format!("SELECT {} FROM trades WHERE account_id = $1 AND created_at >= $2 AND created_at <= $3 ORDER BY created_at ASC", TRADE_COLUMNS)
+    )
+    .bind(account_id)
+    .bind(start)
+    .bind(end)
+    .fetch_all(pool)
+    .await
+}
+
+/// Fetch an account's most recent trade of a type in a market created at or after the given time
+pub async fn get_latest_trade_since(
+    pool: &PgPool,
+    account_id: &str,
+    action_type: &str,
+    market_id: i32,
+    since: DateTime<Utc>,
+) -> Result<Option<TradeModel>, sqlx::Error> {
+    sqlx::query_as::<_, TradeModel>(
+        &format!(
+            "SELECT {} FROM trades WHERE account_id = $1 AND action_type = $2 AND market_id = $3 AND created_at >= $4 ORDER BY created_at DESC LIMIT 1",
+            TRADE_COLUMNS
+        )
+    )
+    .bind(account_id)
+    .bind(action_type)
+    .bind(market_id)
+    .bind(since)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Fetch when an account's most recent settled trade touching a market (as source or shift destination) was last updated
+pub async fn get_last_settled_trade_time(pool: &PgPool, account_id: &str, market_id: i32) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    let row = sqlx::query(
+        "SELECT MAX(updated_at) FROM trades WHERE account_id = $1 AND status = 'Settled' AND (market_id = $2 OR to_market_id = $2)"
+    )
+    .bind(account_id)
+    .bind(market_id)
+    .fetch_one(pool)
+    .await?;
+    Ok(row.get(0))
+}
+
+/// Sum the notional of an account's trades created since the given time, blocked and failed requests excluded
+pub async fn get_trade_notional_since(pool: &PgPool, account_id: &str, since: DateTime<Utc>) -> Result<Decimal, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT COALESCE(SUM(notional_usd), 0)
+        FROM trades
+        WHERE account_id = $1 AND created_at >= $2 AND status NOT IN ('Blocked', 'Failed')
+        "#
+    )
+    .bind(account_id)
+    .bind(since)
+    .fetch_one(pool)
+    .await?;
+    Ok(row.get(0))
+}
+
+/// Fetch an account's most recently created trades, newest first
+pub async fn get_recent_trades(pool: &PgPool, account_id: &str, limit: i64) -> Result<Vec<TradeModel>, sqlx::Error> {
+    sqlx::query_as::<_, TradeModel>(
+        &format!("SELECT {} FROM trades WHERE account_id = $1 ORDER BY created_at DESC LIMIT $2", TRADE_COLUMNS)
+    )
+    .bind(account_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
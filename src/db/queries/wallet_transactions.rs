@@ -0,0 +1,42 @@
+use sqlx::PgPool;
+
+use crate::db::models::wallet_transactions::{WalletTransactionModel, NewWalletTransactionModel};
+
+/// Register a transaction sent by the bot, ignoring repeats of the same hash
+pub async fn insert_wallet_transaction(pool: &PgPool, tx: &NewWalletTransactionModel) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO wallet_transactions (wallet_address, tx_hash, nonce)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (tx_hash) DO NOTHING
+        "#
+    )
+    .bind(&tx.wallet_address)
+    .bind(&tx.tx_hash)
+    .bind(tx.nonce)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Get the registered transactions of a wallet with nonces in [from_nonce, to_nonce)
+pub async fn get_wallet_transactions_by_nonce(
+    pool: &PgPool,
+    wallet_address: &str,
+    from_nonce: i64,
+    to_nonce: i64,
+) -> Result<Vec<WalletTransactionModel>, sqlx::Error> {
+    sqlx::query_as::<_, WalletTransactionModel>(
+        r#"
+        SELECT id, wallet_address, tx_hash, nonce, sent_at
+        FROM wallet_transactions
+        WHERE wallet_address = $1 AND nonce >= $2 AND nonce < $3
+        ORDER BY nonce, sent_at
+        "#
+    )
+    .bind(wallet_address)
+    .bind(from_nonce)
+    .bind(to_nonce)
+    .fetch_all(pool)
+    .await
+}
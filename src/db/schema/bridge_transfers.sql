@@ -0,0 +1,23 @@
+CREATE TABLE IF NOT EXISTS bridge_transfers (
+    id SERIAL PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+    direction TEXT NOT NULL, -- ArbitrumToDydx or DydxToArbitrum
+    amount NUMERIC NOT NULL, -- USDC sent from the source chain, including bridge fees
+    expected_amount_out NUMERIC NOT NULL, -- USDC the route quoted to deliver
+    source_chain_id TEXT NOT NULL,
+    source_tx_hash TEXT NOT NULL, -- Transaction the route is tracked by
+    estimated_duration_secs BIGINT NOT NULL,
+
+    status TEXT NOT NULL, -- Pending until the funds arrive, then Arrived or Failed
+    arrived_at TIMESTAMPTZ,
+    error TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_bridge_transfers_status ON bridge_transfers (status);
+
+-- Portfolio account the row belongs to, accounts share the deployment but never each other's state
+ALTER TABLE bridge_transfers ADD COLUMN IF NOT EXISTS account_id TEXT NOT NULL DEFAULT 'default';
+
+CREATE INDEX IF NOT EXISTS idx_bridge_transfers_account_status ON bridge_transfers (account_id, status);
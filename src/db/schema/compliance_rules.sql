@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS compliance_rules (
+    account_id TEXT NOT NULL DEFAULT 'default',
+    rule_id TEXT NOT NULL, -- Operator chosen name, recorded on the trades the rule blocks
+    rule_type TEXT NOT NULL, -- MaxTradeNotional, MaxDailyTurnover, BannedToken, MaxHedgeLeverage or TradingWindow
+    value TEXT NOT NULL, -- USD amount, token symbol, leverage or UTC hour window (e.g. 8-20), by rule type
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (account_id, rule_id)
+);
\ No newline at end of file
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS config_overrides (
+    key TEXT PRIMARY KEY, -- Dynamic parameter name, e.g. allocator_risk_aversion
+    value TEXT NOT NULL, -- Parsed by the parameter's type, invalid values are ignored
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+-- Overrides are per portfolio account, each account tunes its own strategy parameters
+ALTER TABLE config_overrides ADD COLUMN IF NOT EXISTS account_id TEXT NOT NULL DEFAULT 'default';
+ALTER TABLE config_overrides DROP CONSTRAINT IF EXISTS config_overrides_pkey;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_config_overrides_account_key
+ON config_overrides(account_id, key);
@@ -0,0 +1,53 @@
+-- NOTIFY events for consumers reacting to new data without polling (see db::subscriber::DbSubscriber).
+-- Payloads are JSON, small enough to stay far below the 8000 byte NOTIFY limit; consumers re-read the rows they need.
+
+-- market_states_inserted: one event per insert statement, {"count": rows, "timestamp": latest state timestamp}
+CREATE OR REPLACE FUNCTION notify_market_states_inserted() RETURNS trigger AS $$
+BEGIN
+    PERFORM pg_notify('market_states_inserted', (
+        SELECT json_build_object('count', COUNT(*), 'timestamp', MAX(timestamp))::text FROM inserted_market_states
+    ));
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+-- strategy_runs_inserted: {"id": run ID, "account_id": account, "created_at": run time}
+CREATE OR REPLACE FUNCTION notify_strategy_run_inserted() RETURNS trigger AS $$
+BEGIN
+    PERFORM pg_notify('strategy_runs_inserted', json_build_object(
+        'id', NEW.id, 'account_id', NEW.account_id, 'created_at', NEW.created_at
+    )::text);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+-- trades_changed: a trade was recorded or its status changed, {"id", "account_id", "action_type", "status"}
+CREATE OR REPLACE FUNCTION notify_trade_changed() RETURNS trigger AS $$
+BEGIN
+    PERFORM pg_notify('trades_changed', json_build_object(
+        'id', NEW.id, 'account_id', NEW.account_id, 'action_type', NEW.action_type, 'status', NEW.status
+    )::text);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Triggers are only created when missing, so concurrently starting services don't race on dropping them
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_trigger WHERE tgname = 'market_states_inserted_notify') THEN
+        CREATE TRIGGER market_states_inserted_notify
+        AFTER INSERT ON market_states
+        REFERENCING NEW TABLE AS inserted_market_states
+        FOR EACH STATEMENT EXECUTE FUNCTION notify_market_states_inserted();
+    END IF;
+    IF NOT EXISTS (SELECT 1 FROM pg_trigger WHERE tgname = 'strategy_runs_inserted_notify') THEN
+        CREATE TRIGGER strategy_runs_inserted_notify
+        AFTER INSERT ON strategy_runs
+        FOR EACH ROW EXECUTE FUNCTION notify_strategy_run_inserted();
+    END IF;
+    IF NOT EXISTS (SELECT 1 FROM pg_trigger WHERE tgname = 'trades_changed_notify') THEN
+        CREATE TRIGGER trades_changed_notify
+        AFTER INSERT OR UPDATE OF status ON trades
+        FOR EACH ROW EXECUTE FUNCTION notify_trade_changed();
+    END IF;
+END $$;
\ No newline at end of file
@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS execution_costs (
+    id SERIAL PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+    venue TEXT NOT NULL,
+    action_type TEXT NOT NULL,
+    tx_hash TEXT,
+
+    gas_cost_usd NUMERIC NOT NULL,
+    execution_fee_usd NUMERIC NOT NULL DEFAULT 0, -- Keeper execution fee paid upfront (GMX requests)
+    total_cost_usd NUMERIC NOT NULL
+);
+
+-- USD value of the execution fee refunded after keeper execution, already netted out of total_cost_usd
+ALTER TABLE execution_costs ADD COLUMN IF NOT EXISTS execution_fee_refund_usd NUMERIC NOT NULL DEFAULT 0;
+
+-- Portfolio account the row belongs to, accounts share the deployment but never each other's state
+ALTER TABLE execution_costs ADD COLUMN IF NOT EXISTS account_id TEXT NOT NULL DEFAULT 'default';
+
+-- USD value lost versus mid prices: spot swap output below mid, or estimated GMX price impact and pool fees of a GM request
+ALTER TABLE execution_costs ADD COLUMN IF NOT EXISTS slippage_usd NUMERIC NOT NULL DEFAULT 0;
\ No newline at end of file
@@ -0,0 +1,45 @@
+CREATE TABLE IF NOT EXISTS execution_plans (
+    id SERIAL PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    completed_at TIMESTAMPTZ,
+    source TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS execution_plan_actions (
+    id SERIAL PRIMARY KEY,
+    plan_id INTEGER NOT NULL REFERENCES execution_plans(id),
+    seq INTEGER NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+    action_type TEXT NOT NULL,
+    status TEXT NOT NULL,
+
+    market_id INTEGER NOT NULL REFERENCES markets(id),
+    to_market_id INTEGER REFERENCES markets(id),
+
+    long_token_amount NUMERIC,
+    short_token_amount NUMERIC,
+    market_token_amount NUMERIC,
+
+    spent_balance_before NUMERIC,
+    submitted_at TIMESTAMPTZ,
+    error TEXT
+);
+
+ALTER TABLE execution_plan_actions ADD COLUMN IF NOT EXISTS initial_long_token TEXT;
+ALTER TABLE execution_plan_actions ADD COLUMN IF NOT EXISTS initial_short_token TEXT;
+
+-- W3C trace context of the run that created the plan, a resumed plan links back to it
+ALTER TABLE execution_plans ADD COLUMN IF NOT EXISTS traceparent TEXT;
+
+-- Content hash of the plan (source and sorted actions with amounts), an identical plan isn't executed twice in a window
+ALTER TABLE execution_plans ADD COLUMN IF NOT EXISTS plan_hash TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_execution_plans_hash
+ON execution_plans(plan_hash, created_at);
+
+CREATE INDEX IF NOT EXISTS idx_execution_plan_actions_plan
+ON execution_plan_actions(plan_id, seq);
+
+-- Portfolio account the row belongs to, accounts share the deployment but never each other's state
+ALTER TABLE execution_plans ADD COLUMN IF NOT EXISTS account_id TEXT NOT NULL DEFAULT 'default';
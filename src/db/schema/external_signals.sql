@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS external_signals (
+    id SERIAL PRIMARY KEY,
+    signer TEXT NOT NULL, -- Address that signed the payload, weighted by its configured trust
+    market_id INTEGER NOT NULL REFERENCES markets(id),
+    expected_return_apr NUMERIC, -- Annualized expected LP return override, NULL for a veto-only signal
+    veto BOOLEAN NOT NULL DEFAULT FALSE, -- Exclude the market from allocation while active
+    issued_at TIMESTAMPTZ NOT NULL, -- Signer's timestamp of the payload
+    expires_at TIMESTAMPTZ NOT NULL, -- Ignored by the strategy engine from then on
+    payload_hash TEXT NOT NULL, -- Keccak-256 of the signed payload body, a replayed payload is rejected
+    received_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (payload_hash, market_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_external_signals_expires_at
+ON external_signals(expires_at);
\ No newline at end of file
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS funding_rates (
+    id SERIAL PRIMARY KEY,
+    ticker TEXT NOT NULL, -- dYdX perpetual market ticker (e.g. ETH-USD)
+    effective_at TIMESTAMPTZ NOT NULL,
+    rate NUMERIC NOT NULL, -- Hourly funding rate, positive means longs pay shorts
+    UNIQUE (ticker, effective_at)
+);
\ No newline at end of file
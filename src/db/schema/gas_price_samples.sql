@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS gas_price_samples (
+    id SERIAL PRIMARY KEY,
+    sampled_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    action_type TEXT NOT NULL, -- GM request type the execution fee was estimated for
+    gas_price NUMERIC NOT NULL, -- Buffered gas price (wei)
+    execution_fee NUMERIC NOT NULL -- Estimated keeper execution fee (native token)
+);
+
+CREATE INDEX IF NOT EXISTS idx_gas_price_samples_action_sampled_at
+ON gas_price_samples(action_type, sampled_at);
\ No newline at end of file
@@ -0,0 +1,30 @@
+CREATE TABLE IF NOT EXISTS gas_profiles (
+    id SERIAL PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+    action_type TEXT NOT NULL,
+    market_id INTEGER REFERENCES markets(id),
+    tx_hash TEXT NOT NULL UNIQUE, -- Request creation transaction
+
+    -- Request creation transaction (paid by the wallet)
+    gas_used NUMERIC NOT NULL,
+    l1_gas_used NUMERIC, -- Arbitrum L1 data component of gas_used (gasUsedForL1), NULL on chains without it
+    network_gas_price NUMERIC NOT NULL, -- Gas price quoted by the node when the fee was estimated (wei)
+    effective_gas_price NUMERIC NOT NULL, -- Gas price actually paid (wei)
+
+    -- Keeper execution fee
+    max_fee_per_gas_buffer NUMERIC NOT NULL, -- Buffer applied to the network gas price for the estimate
+    estimated_gas_limit NUMERIC NOT NULL, -- Keeper gas limit the execution fee was estimated for
+    execution_fee NUMERIC NOT NULL, -- Paid upfront (native token)
+    execution_fee_refund NUMERIC, -- Refunded once executed (native token), NULL until observed
+    keeper_tx_hash TEXT,
+    keeper_gas_price NUMERIC -- Effective gas price of the keeper execution transaction (wei)
+);
+
+CREATE INDEX IF NOT EXISTS idx_gas_profiles_action_created_at
+ON gas_profiles(action_type, created_at);
+
+-- Base fee of the latest block when the fee was estimated and of the block the request was included in (wei),
+-- their ratio is the base fee movement the max fee per gas buffer has to absorb
+ALTER TABLE gas_profiles ADD COLUMN IF NOT EXISTS quote_base_fee NUMERIC;
+ALTER TABLE gas_profiles ADD COLUMN IF NOT EXISTS inclusion_base_fee NUMERIC;
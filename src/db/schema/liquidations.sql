@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS liquidations (
+    id SERIAL PRIMARY KEY,
+    market_id INTEGER NOT NULL REFERENCES markets(id),
+    block_number BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    log_index INTEGER NOT NULL,
+    timestamp TIMESTAMPTZ NOT NULL, -- Block time of the liquidation
+    is_long BOOLEAN NOT NULL,
+    size_usd NUMERIC NOT NULL, -- Position size closed by the liquidation
+    UNIQUE (tx_hash, log_index)
+);
+
+CREATE INDEX IF NOT EXISTS idx_liquidations_market_timestamp
+ON liquidations(market_id, timestamp);
\ No newline at end of file
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS market_holding_periods (
+    account_id TEXT NOT NULL DEFAULT 'default',
+    market_id INTEGER NOT NULL REFERENCES markets(id),
+    entered_at TIMESTAMPTZ, -- When the current (or last) position was first seen held
+    exited_at TIMESTAMPTZ, -- When the last position was first seen fully exited, before entered_at while held
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (account_id, market_id)
+);
\ No newline at end of file
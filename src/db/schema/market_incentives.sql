@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS market_incentives (
+    id SERIAL PRIMARY KEY,
+    market_id INTEGER NOT NULL REFERENCES markets(id),
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    reward_token TEXT NOT NULL, -- Reward token address
+    reward_amount NUMERIC NOT NULL, -- Reward tokens distributed to the pool over the period
+    period_secs INTEGER NOT NULL,
+    reward_usd_per_hour NUMERIC NOT NULL -- Emission rate valued at the latest reward token price
+);
+
+CREATE INDEX IF NOT EXISTS idx_market_incentives_market_recorded_at
+ON market_incentives(market_id, recorded_at);
\ No newline at end of file
@@ -0,0 +1,60 @@
+-- Latest state per market with trailing fee APRs and GM price change, for dashboards and coarse market filters.
+-- Refreshed periodically by the data recorder (see refresh_market_overview), reads never touch market_states directly.
+-- Fee windows include downsampled hourly rows, so the 7d APR stays whole when raw data is retained for less than a week.
+
+-- Views created before display names were stored on markets format their own, recreate them to use the stored name
+DO $$
+BEGIN
+    IF EXISTS (SELECT 1 FROM pg_matviews WHERE matviewname = 'market_overview' AND definition NOT LIKE '%m.display_name%') THEN
+        DROP MATERIALIZED VIEW market_overview;
+    END IF;
+END $$;
+
+CREATE MATERIALIZED VIEW IF NOT EXISTS market_overview AS
+WITH latest AS (
+    SELECT DISTINCT ON (market_id)
+        market_id, timestamp, gm_price_mid, utilization,
+        open_interest_long, open_interest_short,
+        pool_long_token_usd + pool_short_token_usd - pool_impact_token_usd AS pool_value_usd
+    FROM market_states
+    ORDER BY market_id, timestamp DESC
+),
+price_24h_ago AS (
+    SELECT DISTINCT ON (market_id) market_id, gm_price_mid
+    FROM market_states
+    WHERE timestamp <= now() - INTERVAL '24 hours'
+    ORDER BY market_id, timestamp DESC
+),
+fees AS (
+    SELECT
+        market_id,
+        SUM(fees_total) FILTER (WHERE timestamp > now() - INTERVAL '24 hours') AS fees_24h_usd,
+        SUM(fees_total) AS fees_7d_usd
+    FROM (
+        SELECT market_id, timestamp, fees_total FROM market_states
+        UNION ALL
+        SELECT market_id, timestamp, fees_total FROM market_states_hourly
+    ) states
+    WHERE timestamp > now() - INTERVAL '7 days'
+    GROUP BY market_id
+)
+SELECT
+    m.id AS market_id,
+    m.address AS market_address,
+    COALESCE(m.display_name, m.address) AS display_name,
+    m.deprecated_at IS NOT NULL AS deprecated,
+    l.timestamp AS latest_timestamp,
+    l.gm_price_mid,
+    CASE WHEN p.gm_price_mid > 0 THEN l.gm_price_mid / p.gm_price_mid - 1 END AS gm_price_change_24h,
+    l.utilization,
+    l.open_interest_long + l.open_interest_short AS open_interest_usd,
+    l.pool_value_usd,
+    CASE WHEN l.pool_value_usd > 0 THEN COALESCE(f.fees_24h_usd, 0) / l.pool_value_usd * 365 END AS fee_apr_24h,
+    CASE WHEN l.pool_value_usd > 0 THEN COALESCE(f.fees_7d_usd, 0) / l.pool_value_usd * 365 / 7 END AS fee_apr_7d
+FROM latest l
+JOIN markets m ON m.id = l.market_id
+LEFT JOIN price_24h_ago p ON p.market_id = l.market_id
+LEFT JOIN fees f ON f.market_id = l.market_id;
+
+-- Unique index lets the view be refreshed concurrently, without blocking readers
+CREATE UNIQUE INDEX IF NOT EXISTS idx_market_overview_market ON market_overview(market_id);
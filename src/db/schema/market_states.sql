@@ -38,4 +38,22 @@ CREATE TABLE IF NOT EXISTS market_states (
     fees_swap NUMERIC,
     fees_borrowing NUMERIC,
     fees_total NUMERIC
-);
\ No newline at end of file
+);
+
+-- Where the row came from: collector (live ingestion) or a historical backfill source
+ALTER TABLE market_states ADD COLUMN IF NOT EXISTS source TEXT NOT NULL DEFAULT 'collector';
+
+-- W3C trace context of the collection cycle that produced the row, links strategy runs back to the data they used
+ALTER TABLE market_states ADD COLUMN IF NOT EXISTS traceparent TEXT;
+
+-- Pool and open interest caps read from the GMX datastore (pool caps in token units, open interest caps in USD)
+ALTER TABLE market_states ADD COLUMN IF NOT EXISTS max_pool_amount_long NUMERIC;
+ALTER TABLE market_states ADD COLUMN IF NOT EXISTS max_pool_amount_short NUMERIC;
+ALTER TABLE market_states ADD COLUMN IF NOT EXISTS max_open_interest_long NUMERIC;
+ALTER TABLE market_states ADD COLUMN IF NOT EXISTS max_open_interest_short NUMERIC;
+
+-- Taker flow over the collection interval from position changes in USD (buys are long increases and short decreases),
+-- imbalance is (buy - sell) / (buy + sell), null when no positions changed
+ALTER TABLE market_states ADD COLUMN IF NOT EXISTS taker_buy_volume NUMERIC;
+ALTER TABLE market_states ADD COLUMN IF NOT EXISTS taker_sell_volume NUMERIC;
+ALTER TABLE market_states ADD COLUMN IF NOT EXISTS taker_flow_imbalance NUMERIC;
\ No newline at end of file
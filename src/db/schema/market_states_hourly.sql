@@ -0,0 +1,50 @@
+CREATE TABLE IF NOT EXISTS market_states_hourly (
+    id SERIAL PRIMARY KEY,
+    market_id INTEGER NOT NULL REFERENCES markets(id),
+    timestamp TIMESTAMPTZ NOT NULL, -- Start of the hour
+
+    borrowing_factor_long NUMERIC,
+    borrowing_factor_short NUMERIC,
+
+    pnl_long NUMERIC,
+    pnl_short NUMERIC,
+    pnl_net NUMERIC,
+
+    gm_price_min NUMERIC,
+    gm_price_max NUMERIC,
+    gm_price_mid NUMERIC,
+
+    pool_long_amount NUMERIC,
+    pool_short_amount NUMERIC,
+    pool_impact_amount NUMERIC,
+    pool_long_token_usd NUMERIC,
+    pool_short_token_usd NUMERIC,
+    pool_impact_token_usd NUMERIC,
+
+    open_interest_long NUMERIC,
+    open_interest_short NUMERIC,
+    open_interest_long_amount NUMERIC,
+    open_interest_short_amount NUMERIC,
+    open_interest_long_via_tokens NUMERIC,
+    open_interest_short_via_tokens NUMERIC,
+
+    utilization NUMERIC,
+
+    swap_volume NUMERIC,
+    trading_volume NUMERIC,
+
+    fees_position NUMERIC,
+    fees_liquidation NUMERIC,
+    fees_swap NUMERIC,
+    fees_borrowing NUMERIC,
+    fees_total NUMERIC,
+
+    sample_count INTEGER NOT NULL,
+
+    UNIQUE (market_id, timestamp)
+);
+
+-- Taker flow summed over the hour, imbalance recomputed from the sums
+ALTER TABLE market_states_hourly ADD COLUMN IF NOT EXISTS taker_buy_volume NUMERIC;
+ALTER TABLE market_states_hourly ADD COLUMN IF NOT EXISTS taker_sell_volume NUMERIC;
+ALTER TABLE market_states_hourly ADD COLUMN IF NOT EXISTS taker_flow_imbalance NUMERIC;
\ No newline at end of file
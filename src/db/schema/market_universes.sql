@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS market_universes (
+    account_id TEXT NOT NULL DEFAULT 'default',
+    universe TEXT NOT NULL, -- Operator chosen name (e.g. core, extended, experimental), selected with MARKET_UNIVERSE
+    markets TEXT[] NOT NULL, -- Index token symbols (BTC selects every BTC pool) or market token addresses
+    capital_fraction NUMERIC NOT NULL, -- Share of the account's GM holdings the universe's runs allocate
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (account_id, universe)
+);
\ No newline at end of file
@@ -4,4 +4,9 @@ CREATE TABLE IF NOT EXISTS markets (
     index_token_id INTEGER NOT NULL REFERENCES tokens(id),
     long_token_id INTEGER NOT NULL REFERENCES tokens(id),
     short_token_id INTEGER NOT NULL REFERENCES tokens(id)
-);
\ No newline at end of file
+);
+
+ALTER TABLE markets ADD COLUMN IF NOT EXISTS deprecated_at TIMESTAMPTZ;
+
+-- Canonical display name (see MarketDescriptor), kept up to date by the database manager for SQL consumers
+ALTER TABLE markets ADD COLUMN IF NOT EXISTS display_name TEXT;
\ No newline at end of file
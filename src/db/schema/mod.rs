@@ -9,6 +9,33 @@ pub async fn init_schema(pool: &PgPool) -> Result<(), sqlx::Error> {
     pool.execute(include_str!("markets.sql")).await?;
     pool.execute(include_str!("token_prices.sql")).await?;
     pool.execute(include_str!("market_states.sql")).await?;
+    pool.execute(include_str!("token_prices_hourly.sql")).await?;
+    pool.execute(include_str!("market_states_hourly.sql")).await?;
+    pool.execute(include_str!("trades.sql")).await?;
+    pool.execute(include_str!("pending_plans.sql")).await?;
+    pool.execute(include_str!("strategy_runs.sql")).await?;
+    pool.execute(include_str!("orders.sql")).await?;
+    pool.execute(include_str!("execution_costs.sql")).await?;
+    pool.execute(include_str!("quarantined_prices.sql")).await?;
+    pool.execute(include_str!("funding_rates.sql")).await?;
+    pool.execute(include_str!("gas_price_samples.sql")).await?;
+    pool.execute(include_str!("market_incentives.sql")).await?;
+    pool.execute(include_str!("execution_plans.sql")).await?;
+    pool.execute(include_str!("gas_profiles.sql")).await?;
+    pool.execute(include_str!("config_overrides.sql")).await?;
+    pool.execute(include_str!("wallet_transactions.sql")).await?;
+    pool.execute(include_str!("market_overview.sql")).await?;
+    pool.execute(include_str!("spot_swaps.sql")).await?;
+    pool.execute(include_str!("rebalances.sql")).await?;
+    pool.execute(include_str!("bridge_transfers.sql")).await?;
+    pool.execute(include_str!("position_exposures.sql")).await?;
+    pool.execute(include_str!("compliance_rules.sql")).await?;
+    pool.execute(include_str!("pipeline_health.sql")).await?;
+    pool.execute(include_str!("liquidations.sql")).await?;
+    pool.execute(include_str!("market_holding_periods.sql")).await?;
+    pool.execute(include_str!("external_signals.sql")).await?;
+    pool.execute(include_str!("market_universes.sql")).await?;
+    pool.execute(include_str!("db_notifications.sql")).await?;
 
     // Create indices on timestamp for performance
     sqlx::query(
@@ -29,5 +56,50 @@ pub async fn init_schema(pool: &PgPool) -> Result<(), sqlx::Error> {
     .execute(pool)
     .await?;
 
+    sqlx::query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_trades_status 
+        ON trades(status);
+        "#
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_strategy_run_markets_run 
+        ON strategy_run_markets(run_id);
+        "#
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_strategy_run_inputs_run 
+        ON strategy_run_inputs(run_id);
+        "#
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_orders_ticker_status 
+        ON orders(ticker, status);
+        "#
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_execution_costs_created_at 
+        ON execution_costs(created_at);
+        "#
+    )
+    .execute(pool)
+    .await?;
+
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,27 @@
+CREATE TABLE IF NOT EXISTS orders (
+    id SERIAL PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    venue TEXT NOT NULL,
+    ticker TEXT NOT NULL,
+    side TEXT NOT NULL,
+    size NUMERIC NOT NULL,
+    filled_size NUMERIC NOT NULL DEFAULT 0,
+    reduce_only BOOLEAN NOT NULL,
+    client_id BIGINT NOT NULL,
+    good_til_block BIGINT,
+    venue_order_id TEXT,
+    tx_hash TEXT,
+    status TEXT NOT NULL,
+    parent_order_id INTEGER REFERENCES orders(id)
+);
+
+-- Mid price when the order was placed and size weighted average fill price, their gap is the hedge slippage
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS reference_price NUMERIC;
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS average_fill_price NUMERIC;
+
+-- Portfolio account the row belongs to, accounts share the deployment but never each other's state
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS account_id TEXT NOT NULL DEFAULT 'default';
+
+CREATE INDEX IF NOT EXISTS idx_orders_account_venue_status
+ON orders(account_id, venue, status);
@@ -0,0 +1,22 @@
+CREATE TABLE IF NOT EXISTS pending_plans (
+    id SERIAL PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    decided_at TIMESTAMPTZ,
+    status TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS pending_plan_actions (
+    id SERIAL PRIMARY KEY,
+    plan_id INTEGER NOT NULL REFERENCES pending_plans(id),
+    market_id INTEGER NOT NULL REFERENCES markets(id),
+    target_weight NUMERIC NOT NULL,
+    expected_return NUMERIC,
+    status TEXT NOT NULL,
+    notes TEXT
+);
+
+ALTER TABLE pending_plan_actions ADD COLUMN IF NOT EXISTS notes TEXT;
+
+-- Portfolio account the row belongs to, accounts share the deployment but never each other's state
+ALTER TABLE pending_plans ADD COLUMN IF NOT EXISTS account_id TEXT NOT NULL DEFAULT 'default';
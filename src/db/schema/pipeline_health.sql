@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS pipeline_health (
+    id SERIAL PRIMARY KEY,
+    stream TEXT NOT NULL, -- Redis data stream, token_prices or market_states
+    status TEXT NOT NULL, -- stale when the stream stopped receiving entries, recovered once they resume
+    last_entry_at TIMESTAMPTZ, -- Time of the latest entry seen, NULL when the stream has none
+    stale_after_secs INTEGER NOT NULL, -- Threshold the stream was checked against
+    detected_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_pipeline_health_stream_detected_at
+ON pipeline_health(stream, detected_at);
\ No newline at end of file
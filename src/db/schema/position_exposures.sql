@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS position_exposures (
+    id SERIAL PRIMARY KEY,
+    market_id INTEGER NOT NULL REFERENCES markets(id),
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+    gm_value_usd NUMERIC NOT NULL, -- Value of the GM held
+    long_token_usd NUMERIC NOT NULL, -- Share of the held value backed by the pool's long token
+    short_token_usd NUMERIC NOT NULL, -- Share of the held value backed by the pool's short token
+    long_share NUMERIC NOT NULL, -- Long token share of the pool when recorded
+    entry_long_share NUMERIC NOT NULL -- Long token share when the position was last traded, drift is measured from it
+);
+
+CREATE INDEX IF NOT EXISTS idx_position_exposures_market_recorded_at ON position_exposures (market_id, recorded_at);
+
+-- Portfolio account the row belongs to, accounts share the deployment but never each other's state.
+-- These rows are the account's portfolio snapshots, there is no separate portfolio_snapshots table to scope.
+ALTER TABLE position_exposures ADD COLUMN IF NOT EXISTS account_id TEXT NOT NULL DEFAULT 'default';
\ No newline at end of file
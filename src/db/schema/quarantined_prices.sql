@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS quarantined_prices (
+    id SERIAL PRIMARY KEY,
+    token_id INTEGER NOT NULL REFERENCES tokens(id),
+    timestamp TIMESTAMPTZ NOT NULL,
+    min_price NUMERIC NOT NULL,
+    max_price NUMERIC NOT NULL,
+    mid_price NUMERIC NOT NULL,
+    reference_price NUMERIC,
+    reason TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
\ No newline at end of file
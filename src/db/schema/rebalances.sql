@@ -0,0 +1,25 @@
+CREATE TABLE IF NOT EXISTS rebalances (
+    id SERIAL PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    completed_at TIMESTAMPTZ,
+    source TEXT NOT NULL, -- What started the move, e.g. the execution plan source or "manual"
+    account_id TEXT NOT NULL DEFAULT 'default'
+);
+
+CREATE INDEX IF NOT EXISTS idx_rebalances_account_created
+ON rebalances(account_id, created_at);
+
+-- Rebalance each leg (GM request, spot swap, hedge order, execution cost) belongs to, NULL for standalone actions
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS rebalance_id INTEGER REFERENCES rebalances(id);
+ALTER TABLE spot_swaps ADD COLUMN IF NOT EXISTS rebalance_id INTEGER REFERENCES rebalances(id);
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS rebalance_id INTEGER REFERENCES rebalances(id);
+ALTER TABLE execution_costs ADD COLUMN IF NOT EXISTS rebalance_id INTEGER REFERENCES rebalances(id);
+
+-- Rebalance an execution plan's legs are recorded against, a resumed plan keeps recording into it
+ALTER TABLE execution_plans ADD COLUMN IF NOT EXISTS rebalance_id INTEGER REFERENCES rebalances(id);
+
+CREATE INDEX IF NOT EXISTS idx_execution_costs_rebalance
+ON execution_costs(rebalance_id);
+
+CREATE INDEX IF NOT EXISTS idx_orders_rebalance
+ON orders(rebalance_id);
\ No newline at end of file
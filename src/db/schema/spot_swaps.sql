@@ -0,0 +1,25 @@
+CREATE TABLE IF NOT EXISTS spot_swaps (
+    id SERIAL PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+    from_token_address TEXT NOT NULL,
+    to_token_address TEXT NOT NULL,
+    side TEXT NOT NULL, -- "BUY" or "SELL"
+    requested_amount NUMERIC NOT NULL, -- In to_token when buying, from_token when selling
+
+    from_amount NUMERIC NOT NULL, -- Total from_token spent across slices
+    to_amount NUMERIC NOT NULL, -- Total to_token received across slices
+    average_rate NUMERIC, -- to_amount / from_amount, NULL when nothing filled
+
+    slices_planned INTEGER NOT NULL,
+    slices_filled INTEGER NOT NULL,
+    status TEXT NOT NULL, -- Completed, Aborted (price moved past the limit) or Failed
+    gas_cost_usd NUMERIC NOT NULL,
+    tx_hashes TEXT[] NOT NULL DEFAULT '{}'
+);
+
+-- Counterparty contracts (swap contract, spender, route) screened clean against the sanctions deny-list/oracle
+ALTER TABLE spot_swaps ADD COLUMN IF NOT EXISTS screened_contracts TEXT[] NOT NULL DEFAULT '{}';
+
+-- Portfolio account the row belongs to, accounts share the deployment but never each other's state
+ALTER TABLE spot_swaps ADD COLUMN IF NOT EXISTS account_id TEXT NOT NULL DEFAULT 'default';
@@ -0,0 +1,66 @@
+CREATE TABLE IF NOT EXISTS strategy_runs (
+    id SERIAL PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE TABLE IF NOT EXISTS strategy_run_markets (
+    id SERIAL PRIMARY KEY,
+    run_id INTEGER NOT NULL REFERENCES strategy_runs(id),
+    market_id INTEGER NOT NULL REFERENCES markets(id),
+    expected_return_bps NUMERIC NOT NULL,
+    target_weight NUMERIC NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS strategy_run_inputs (
+    id SERIAL PRIMARY KEY,
+    run_id INTEGER NOT NULL REFERENCES strategy_runs(id),
+    market_id INTEGER NOT NULL REFERENCES markets(id),
+    market_row_count INTEGER NOT NULL,
+    index_price_row_count INTEGER NOT NULL,
+    first_timestamp TIMESTAMPTZ,
+    last_timestamp TIMESTAMPTZ,
+    mean_fee_rate NUMERIC NOT NULL, -- Mean fees per timestep relative to pool value
+    volatility NUMERIC NOT NULL,
+    last_index_price NUMERIC,
+    pool_value_usd NUMERIC NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS return_model_metrics (
+    id SERIAL PRIMARY KEY,
+    run_id INTEGER NOT NULL UNIQUE REFERENCES strategy_runs(id),
+    evaluated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    horizon_hours INTEGER NOT NULL,
+    market_count INTEGER NOT NULL,
+    bias_bps NUMERIC NOT NULL,
+    mae_bps NUMERIC NOT NULL,
+    rank_correlation NUMERIC
+);
+
+ALTER TABLE return_model_metrics ADD COLUMN IF NOT EXISTS reporting_currency TEXT NOT NULL DEFAULT 'USD';
+
+-- Portfolio-level expectations of the run, Sharpe ratio in excess of the risk-free (hurdle) rate
+ALTER TABLE strategy_runs ADD COLUMN IF NOT EXISTS risk_free_rate_apr NUMERIC;
+ALTER TABLE strategy_runs ADD COLUMN IF NOT EXISTS expected_return_bps NUMERIC;
+ALTER TABLE strategy_runs ADD COLUMN IF NOT EXISTS volatility_bps NUMERIC;
+ALTER TABLE strategy_runs ADD COLUMN IF NOT EXISTS sharpe_ratio NUMERIC;
+
+-- Realized portfolio return against holding the base stablecoin in Aave at the run's risk-free rate
+ALTER TABLE return_model_metrics ADD COLUMN IF NOT EXISTS portfolio_return_bps NUMERIC;
+ALTER TABLE return_model_metrics ADD COLUMN IF NOT EXISTS benchmark_return_bps NUMERIC;
+ALTER TABLE return_model_metrics ADD COLUMN IF NOT EXISTS alpha_bps NUMERIC;
+
+-- Constraints the feasibility check relaxed before allocating, in relaxation order
+ALTER TABLE strategy_runs ADD COLUMN IF NOT EXISTS relaxed_constraints TEXT;
+
+-- Data quality score (coverage, gaps, price anomalies) the market's expected return and variance were discounted by
+ALTER TABLE strategy_run_inputs ADD COLUMN IF NOT EXISTS data_quality NUMERIC;
+
+-- Portfolio account the row belongs to, accounts share the deployment but never each other's state
+ALTER TABLE strategy_runs ADD COLUMN IF NOT EXISTS account_id TEXT NOT NULL DEFAULT 'default';
+
+-- Optimizer output and the target after cross-run smoothing, target_weight being the final target the plan was built from
+ALTER TABLE strategy_run_markets ADD COLUMN IF NOT EXISTS raw_target_weight NUMERIC;
+ALTER TABLE strategy_run_markets ADD COLUMN IF NOT EXISTS smoothed_target_weight NUMERIC;
+
+-- Market universe the run allocated, NULL for runs over every market
+ALTER TABLE strategy_runs ADD COLUMN IF NOT EXISTS universe TEXT;
\ No newline at end of file
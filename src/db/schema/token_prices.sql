@@ -5,4 +5,10 @@ CREATE TABLE IF NOT EXISTS token_prices (
     min_price NUMERIC NOT NULL,
     max_price NUMERIC NOT NULL,
     mid_price NUMERIC NOT NULL
-);
\ No newline at end of file
+);
+
+-- Where the row came from: collector (live ingestion) or a historical backfill source
+ALTER TABLE token_prices ADD COLUMN IF NOT EXISTS source TEXT NOT NULL DEFAULT 'collector';
+
+-- W3C trace context of the collection cycle that produced the row, links strategy runs back to the data they used
+ALTER TABLE token_prices ADD COLUMN IF NOT EXISTS traceparent TEXT;
\ No newline at end of file
@@ -0,0 +1,18 @@
+CREATE TABLE IF NOT EXISTS token_prices_hourly (
+    id SERIAL PRIMARY KEY,
+    token_id INTEGER NOT NULL REFERENCES tokens(id),
+    timestamp TIMESTAMPTZ NOT NULL, -- Start of the hour
+
+    open_price NUMERIC NOT NULL,
+    high_price NUMERIC NOT NULL,
+    low_price NUMERIC NOT NULL,
+    close_price NUMERIC NOT NULL,
+
+    min_price NUMERIC NOT NULL,
+    max_price NUMERIC NOT NULL,
+    mid_price NUMERIC NOT NULL, -- Same as close_price, kept so hourly rows read like raw rows
+
+    sample_count INTEGER NOT NULL,
+
+    UNIQUE (token_id, timestamp)
+);
@@ -0,0 +1,35 @@
+CREATE TABLE IF NOT EXISTS trades (
+    id SERIAL PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+    action_type TEXT NOT NULL,
+    status TEXT NOT NULL,
+
+    market_id INTEGER REFERENCES markets(id),
+    to_market_id INTEGER REFERENCES markets(id),
+
+    long_token_amount NUMERIC,
+    short_token_amount NUMERIC,
+    market_token_amount NUMERIC,
+
+    tx_hash TEXT,
+    order_key TEXT,
+    cancel_tx_hash TEXT,
+
+    execution_fee NUMERIC,
+    gas_used NUMERIC,
+    gas_price NUMERIC,
+    gas_cost_usd NUMERIC
+);
+
+-- Unused execution fee GMX refunded after keeper execution, the execution fee actually paid is execution_fee minus this
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS execution_fee_refund NUMERIC;
+
+-- Portfolio account the row belongs to, accounts share the deployment but never each other's state
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS account_id TEXT NOT NULL DEFAULT 'default';
+
+-- USD value of the request when it was created, summed into the daily turnover checked by the compliance rules
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS notional_usd NUMERIC;
+-- Compliance rule that blocked the request, set on Blocked trades only
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS compliance_rule_id TEXT;
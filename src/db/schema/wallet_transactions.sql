@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS wallet_transactions (
+    id SERIAL PRIMARY KEY,
+    wallet_address TEXT NOT NULL, -- Sending wallet
+    tx_hash TEXT NOT NULL UNIQUE,
+    nonce BIGINT NOT NULL, -- Wallet nonce the transaction was signed with
+    sent_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_wallet_transactions_wallet_nonce
+ON wallet_transactions(wallet_address, nonce);
@@ -0,0 +1,128 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::{PgListener, PgPool};
+use tracing::{debug, info, warn};
+
+/// Channels the database notifies on (see schema/db_notifications.sql)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DbChannel {
+    MarketStates, // Market states inserted by the recorder
+    StrategyRuns, // Strategy run recorded
+    Trades,       // Trade recorded or its status changed
+}
+
+impl DbChannel {
+    pub const ALL: [DbChannel; 3] = [DbChannel::MarketStates, DbChannel::StrategyRuns, DbChannel::Trades];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DbChannel::MarketStates => "market_states_inserted",
+            DbChannel::StrategyRuns => "strategy_runs_inserted",
+            DbChannel::Trades => "trades_changed",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        DbChannel::ALL.into_iter().find(|channel| channel.as_str() == s)
+    }
+}
+
+/// An insert statement into market_states
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketStatesInserted {
+    pub count: i64,
+    pub timestamp: Option<DateTime<Utc>>, // Latest state timestamp among the inserted rows
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyRunInserted {
+    pub id: i32,
+    pub account_id: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeChanged {
+    pub id: i32,
+    pub account_id: String,
+    pub action_type: String,
+    pub status: String,
+}
+
+/// A notification received by a subscriber
+#[derive(Debug, Clone)]
+pub enum DbEvent {
+    MarketStatesInserted(MarketStatesInserted),
+    StrategyRunInserted(StrategyRunInserted),
+    TradeChanged(TradeChanged),
+    Reconnected, // The connection dropped and is re-established on the next receive, notifications sent meanwhile are lost
+}
+
+impl DbEvent {
+    /// Channel the event arrived on, None for reconnects
+    pub fn channel(&self) -> Option<DbChannel> {
+        match self {
+            DbEvent::MarketStatesInserted(_) => Some(DbChannel::MarketStates),
+            DbEvent::StrategyRunInserted(_) => Some(DbChannel::StrategyRuns),
+            DbEvent::TradeChanged(_) => Some(DbChannel::Trades),
+            DbEvent::Reconnected => None,
+        }
+    }
+}
+
+/// Receives the database's NOTIFY events on a dedicated connection so consumers (the price stream, the monitor)
+/// react to new data instead of polling. Runs and trades of other accounts are filtered out. After a
+/// `DbEvent::Reconnected` consumers should re-read whatever they show, the events in between are not replayed.
+pub struct DbSubscriber {
+    listener: PgListener,
+    account_id: String,
+}
+
+impl DbSubscriber {
+    /// Listen on the channels over a connection of the given pool, which must be the primary: replicas don't
+    /// deliver notifications
+    pub async fn connect(pool: &PgPool, account_id: &str, channels: &[DbChannel]) -> Result<Self, sqlx::Error> {
+        let mut listener = PgListener::connect_with(pool).await?;
+        listener.listen_all(channels.iter().map(DbChannel::as_str)).await?;
+        info!(channels = ?channels, account_id = account_id, "Subscribed to database notifications");
+        Ok(Self { listener, account_id: account_id.to_string() })
+    }
+
+    /// Wait for the next event. Malformed payloads and other accounts' events are skipped.
+    pub async fn recv(&mut self) -> Result<DbEvent, sqlx::Error> {
+        loop {
+            // None means the connection was lost, the next call reconnects and re-listens
+            let Some(notification) = self.listener.try_recv().await? else {
+                warn!("Database notification connection lost, reconnecting");
+                return Ok(DbEvent::Reconnected);
+            };
+
+            let channel = notification.channel();
+            let payload = notification.payload();
+            let event = match DbChannel::parse(channel) {
+                Some(DbChannel::MarketStates) => serde_json::from_str(payload).map(DbEvent::MarketStatesInserted),
+                Some(DbChannel::StrategyRuns) => serde_json::from_str(payload).map(DbEvent::StrategyRunInserted),
+                Some(DbChannel::Trades) => serde_json::from_str(payload).map(DbEvent::TradeChanged),
+                None => continue,
+            };
+            let event = match event {
+                Ok(event) => event,
+                Err(e) => {
+                    warn!(channel = channel, payload = payload, error = %e, "Malformed database notification skipped");
+                    continue;
+                }
+            };
+
+            let account_id = match &event {
+                DbEvent::StrategyRunInserted(run) => Some(&run.account_id),
+                DbEvent::TradeChanged(trade) => Some(&trade.account_id),
+                _ => None,
+            };
+            if account_id.is_some_and(|account_id| *account_id != self.account_id) {
+                continue;
+            }
+            debug!(channel = channel, ?event, "Database notification received");
+            return Ok(event);
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use eyre::Result;
+use rust_decimal::Decimal;
+use tracing::{debug, instrument};
+
+use crate::config::Config;
+use crate::db::db_manager::DbManager;
+
+/// Minimum number of request transactions with recorded base fees before an action type's buffer is tuned
+pub const FEE_BUFFER_MIN_SAMPLES: i64 = 20;
+
+/// Gas price a GM request's execution fee was quoted at, carried to the gas profile of the request transaction
+#[derive(Debug, Clone, Copy)]
+pub struct GasQuote {
+    pub network_gas_price: Decimal,      // Gas price quoted by the node (wei)
+    pub max_fee_per_gas_buffer: Decimal, // Buffer applied to it for the max fee per gas and execution fee
+    pub base_fee: Option<Decimal>,       // Latest block base fee at the quote (wei), None when the node doesn't report it
+}
+
+/// Max fee per gas buffer for an action type: the configured quantile of the buffer its request transactions
+/// actually needed over the lookback (base fee movement until inclusion, and keeper cost once refunded), kept within
+/// the configured bounds. Falls back to the default when autotuning is off or there are too few samples.
+#[instrument(skip(config, db_manager), fields(on_close = true))]
+pub async fn max_fee_per_gas_buffer(config: &Config, db_manager: &DbManager, action_type: &str, default_buffer: Decimal) -> Result<Decimal> {
+    if !config.gas_fee_buffer_autotune {
+        return Ok(default_buffer);
+    }
+
+    let since = db_manager.clock.now() - chrono::Duration::days(config.gas_fee_buffer_lookback_days);
+    let (required_buffer, sample_count) = db_manager.get_required_fee_buffer_since(action_type, since, config.gas_fee_buffer_quantile).await?;
+    let buffer = match required_buffer {
+        Some(required_buffer) if sample_count >= FEE_BUFFER_MIN_SAMPLES => {
+            required_buffer.clamp(config.gas_fee_buffer_min, config.gas_fee_buffer_max).round_dp(4)
+        }
+        _ => default_buffer,
+    };
+    debug!(required_buffer = ?required_buffer, sample_count = sample_count, buffer = %buffer, "Max fee per gas buffer tuned");
+    Ok(buffer)
+}
@@ -0,0 +1,56 @@
+use eyre::Result;
+use rust_decimal::Decimal;
+use tracing::{debug, instrument};
+
+use crate::config::Config;
+use crate::db::db_manager::DbManager;
+use crate::db::models::execution_costs::NewGasPriceSampleModel;
+
+pub const GAS_BASELINE_MIN_SAMPLES: i64 = 5; // Below this many samples in the window there is no baseline to compare against
+pub const GAS_SPIKE_POLL_INTERVAL_SECS: u64 = 60;
+
+/// Outcome of comparing an execution fee estimate against the rolling baseline
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExecutionFeeCheck {
+    Normal { baseline: Decimal },
+    InsufficientBaseline { sample_count: i64 },
+    Spiked { baseline: Decimal, ratio: Decimal },
+}
+
+/// Whether the action type is non-urgent and may be deferred during a fee spike
+pub fn is_deferrable(config: &Config, action_type: &str) -> bool {
+    config.gas_deferrable_actions.iter().any(|a| a == action_type)
+}
+
+/// Compare an execution fee estimate against the rolling median for the same action type, then record it as a sample
+#[instrument(skip(config, db_manager), fields(on_close = true))]
+pub async fn check_execution_fee(
+    config: &Config,
+    db_manager: &DbManager,
+    action_type: &str,
+    gas_price: Decimal,
+    execution_fee: Decimal,
+) -> Result<ExecutionFeeCheck> {
+    let since = db_manager.clock.now() - chrono::Duration::hours(config.gas_baseline_window_hours);
+    let (baseline, sample_count) = db_manager.get_median_execution_fee_since(action_type, since).await?;
+
+    db_manager.insert_gas_price_sample(&NewGasPriceSampleModel {
+        action_type: action_type.to_string(),
+        gas_price,
+        execution_fee,
+    }).await?;
+
+    let check = match baseline {
+        Some(baseline) if sample_count >= GAS_BASELINE_MIN_SAMPLES && baseline > Decimal::ZERO => {
+            let ratio = execution_fee / baseline;
+            if ratio > config.gas_spike_multiplier {
+                ExecutionFeeCheck::Spiked { baseline, ratio }
+            } else {
+                ExecutionFeeCheck::Normal { baseline }
+            }
+        }
+        _ => ExecutionFeeCheck::InsufficientBaseline { sample_count },
+    };
+    debug!(?check, execution_fee = %execution_fee, "Execution fee checked against baseline");
+    Ok(check)
+}
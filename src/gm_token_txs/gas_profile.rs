@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+use rust_decimal::Decimal;
+use tracing::info;
+
+use crate::db::models::execution_costs::GasProfileSummaryModel;
+
+/// Minimum number of refunded requests before a fee buffer is suggested for an action type
+pub const MIN_REFUNDED_SAMPLES: i64 = 10;
+
+/// Suggested max fee per gas buffer: high enough that 95% of request transactions are included at the quoted
+/// price, 95% absorb the base fee movement until inclusion, and 95% of keeper executions are covered by the
+/// execution fee. None without enough refunded samples.
+pub fn suggest_fee_buffer(summaries: &[&GasProfileSummaryModel]) -> Option<Decimal> {
+    let refunded_count: i64 = summaries.iter().map(|s| s.refunded_count).sum();
+    if refunded_count < MIN_REFUNDED_SAMPLES {
+        return None;
+    }
+    summaries.iter()
+        .flat_map(|s| [s.p95_inclusion_price_ratio, s.p95_required_fee_buffer, s.p95_base_fee_movement])
+        .flatten()
+        .max()
+        .map(|buffer| buffer.max(Decimal::ONE).round_dp(2))
+}
+
+/// Log gas usage and execution fee utilization per action type and market, with a suggested buffer per action type
+/// next to the buffer currently in use for it
+pub fn log_gas_profile_report(summaries: &[GasProfileSummaryModel], market_names: &HashMap<i32, String>, current_buffers: &HashMap<String, Decimal>) {
+    if summaries.is_empty() {
+        info!("No GM gas profiles to report");
+        return;
+    }
+
+    let format_opt = |v: Option<Decimal>, dp: u32| v.map(|v| v.round_dp(dp).to_string()).unwrap_or_else(|| "n/a".to_string());
+    let profile_summary = summaries.iter()
+        .map(|s| format!(
+            "{} {}: Txs={} (refunded {}), AvgGas={}, L1Share={}, InclusionPriceRatio p50={} p95={}, FeeUtilization p50={} p95={}, RequiredBuffer p95={}, KeeperGasLimitUtilization p95={}, BaseFeeMovement p95={}",
+            s.action_type,
+            s.market_id.map(|id| market_names.get(&id).cloned().unwrap_or_else(|| id.to_string())).unwrap_or_else(|| "n/a".to_string()),
+            s.tx_count,
+            s.refunded_count,
+            format_opt(s.avg_gas_used, 0),
+            format_opt(s.avg_l1_gas_share, 3),
+            format_opt(s.p50_inclusion_price_ratio, 3),
+            format_opt(s.p95_inclusion_price_ratio, 3),
+            format_opt(s.p50_fee_utilization, 3),
+            format_opt(s.p95_fee_utilization, 3),
+            format_opt(s.p95_required_fee_buffer, 3),
+            format_opt(s.p95_keeper_gas_limit_utilization, 3),
+            format_opt(s.p95_base_fee_movement, 3),
+        ))
+        .collect::<Vec<_>>()
+        .join("\n  ");
+
+    let mut by_action_type: HashMap<&str, Vec<&GasProfileSummaryModel>> = HashMap::new();
+    for summary in summaries {
+        by_action_type.entry(summary.action_type.as_str()).or_default().push(summary);
+    }
+    let mut action_types: Vec<&str> = by_action_type.keys().copied().collect();
+    action_types.sort();
+    let suggestions = action_types.iter()
+        .map(|action_type| format!(
+            "{}: {} (current {})",
+            action_type,
+            suggest_fee_buffer(&by_action_type[action_type])
+                .map(|buffer| buffer.to_string())
+                .unwrap_or_else(|| format!("n/a (fewer than {} refunded requests)", MIN_REFUNDED_SAMPLES)),
+            format_opt(current_buffers.get(*action_type).copied(), 4)
+        ))
+        .collect::<Vec<_>>()
+        .join("\n  ");
+
+    info!(
+        "GM Gas Profile:\n  {}\n\nSuggested max fee per gas buffer:\n  {}",
+        profile_summary,
+        suggestions
+    );
+}
@@ -1,5 +1,5 @@
 use eyre::Result;
-use tracing::{debug, info, instrument};
+use tracing::{debug, info, warn, error, instrument};
 use std::sync::Arc;
 use ethers::prelude::*;
 use rust_decimal::Decimal;
@@ -7,30 +7,40 @@ use rust_decimal::prelude::*;
 
 use crate::config::Config;
 use crate::constants::GMX_DECIMALS;
-use crate::wallet::WalletManager;
+use crate::wallet::{WalletManager, TokenInfo};
 use crate::db::db_manager::DbManager;
+use crate::db::models::trades::{NewTradeModel, TradeActionType, TradeStatus};
+use crate::db::models::execution_costs::{NewExecutionCostModel, ExecutionVenue, NewGasProfileModel};
+use crate::strategy::{compliance, deposit_capacity, fee_budget, utilization_guard, withdrawal_liquidity};
 use crate::gmx::{
     exchange_router_utils,
     exchange_router,
     datastore,
     reader,
     reader_utils,
+    incentives,
 };
+use super::gas_guard::{self, ExecutionFeeCheck, GAS_SPIKE_POLL_INTERVAL_SECS};
+use super::fee_buffer::{self, GasQuote};
+use super::swap_path;
 use super::types::{
     GmTxRequest, 
     GmDepositRequest, 
     GmWithdrawalRequest, 
     GmShiftRequest,
+    GmClaimRewardsRequest,
+    GmClaimUiFeesRequest,
     GmAmountOutResponse,
 };
 
-const MAX_FEE_PER_GAS_BUFFER: f64 = 1.1; // 10% above the current gas price
+pub const MAX_FEE_PER_GAS_BUFFER: f64 = 1.1; // 10% above the current gas price, until an action type's buffer is tuned
 
 pub struct GmTxManager {
     config: Arc<Config>,
     wallet_manager: Arc<WalletManager>,
     db_manager: Arc<DbManager>,
-    max_fee_per_gas_buffer: Decimal,
+    default_fee_buffer: Decimal,
+    submission_lock: tokio::sync::Mutex<()>, // Serializes request creation transactions from the wallet
 }
 
 impl GmTxManager {
@@ -40,18 +50,59 @@ impl GmTxManager {
             config,
             wallet_manager,
             db_manager,
-            max_fee_per_gas_buffer: Decimal::from_f64(MAX_FEE_PER_GAS_BUFFER).unwrap(),
+            default_fee_buffer: Decimal::from_f64(MAX_FEE_PER_GAS_BUFFER).unwrap(),
+            submission_lock: tokio::sync::Mutex::new(()),
         }
     }
 
-    /// Execute a GM transaction request
+    /// Execute a GM transaction request, recording it as a leg of the given rebalance
     #[instrument(skip(self))]
-    pub async fn execute_transaction(&self, request: &GmTxRequest) -> Result<()> {
+    pub async fn execute_transaction(&self, request: &GmTxRequest, rebalance_id: Option<i32>) -> Result<()> {
+        // Compliance rules come first and apply to every request, exits included
+        if let Some(violation) = compliance::check_gm_request(&self.db_manager, &self.wallet_manager, request).await? {
+            self.record_blocked_trade(request, &violation, rebalance_id).await;
+            return Err(violation.into());
+        }
+
+        // Deposits and shifts are discretionary, withdrawals are always allowed so positions can be exited
+        // unless the pool can't pay them out, in which case they would only revert
         match request {
-            GmTxRequest::Deposit(deposit_request) => self.execute_deposit(deposit_request).await,
-            GmTxRequest::Withdrawal(withdrawal_request) => self.execute_withdrawal(withdrawal_request).await,
-            GmTxRequest::Shift(shift_request) => self.execute_shift(shift_request).await,
+            GmTxRequest::Deposit(deposit_request) => {
+                fee_budget::ensure_fee_budget_available(&self.config, &self.db_manager, "GM deposit").await?;
+                utilization_guard::ensure_deposit_within_utilization(&self.config, &self.db_manager, deposit_request.market).await?;
+                deposit_capacity::ensure_deposit_within_capacity(&self.db_manager, deposit_request).await?;
+            }
+            GmTxRequest::Shift(_) => fee_budget::ensure_fee_budget_available(&self.config, &self.db_manager, "GM shift").await?,
+            GmTxRequest::ClaimRewards(_) => fee_budget::ensure_fee_budget_available(&self.config, &self.db_manager, "reward claim").await?,
+            GmTxRequest::ClaimUiFees(_) => fee_budget::ensure_fee_budget_available(&self.config, &self.db_manager, "UI fee claim").await?,
+            GmTxRequest::Withdrawal(withdrawal_request) => {
+                withdrawal_liquidity::ensure_withdrawal_within_liquidity(&self.config, &self.db_manager, withdrawal_request.market, withdrawal_request.amount).await?;
+            }
+        }
+
+        // Hold non-urgent requests while keeper execution fees are spiking
+        self.wait_for_normal_execution_fee(request).await?;
+
+        let result = match request {
+            GmTxRequest::Deposit(deposit_request) => self.execute_deposit(deposit_request, rebalance_id).await,
+            GmTxRequest::Withdrawal(withdrawal_request) => self.execute_withdrawal(withdrawal_request, rebalance_id).await,
+            GmTxRequest::Shift(shift_request) => self.execute_shift(shift_request, rebalance_id).await,
+            GmTxRequest::ClaimRewards(claim_request) => self.execute_claim_rewards(claim_request, rebalance_id).await,
+            GmTxRequest::ClaimUiFees(claim_request) => self.execute_claim_ui_fees(claim_request, rebalance_id).await,
+        };
+        if result.is_err() {
+            // The request's gas limit may have changed on-chain (e.g. execution fee too low), re-read it next time
+            let gas_limit_constant = match request {
+                GmTxRequest::Deposit(_) => Some("DEPOSIT_GAS_LIMIT"),
+                GmTxRequest::Withdrawal(_) => Some("WITHDRAWAL_GAS_LIMIT"),
+                GmTxRequest::Shift(_) => Some("SHIFT_GAS_LIMIT"),
+                GmTxRequest::ClaimRewards(_) | GmTxRequest::ClaimUiFees(_) => None,
+            };
+            if let Some(name) = gas_limit_constant {
+                datastore::invalidate_cached_constant(datastore::constant_key(name));
+            }
         }
+        result
     }
 
     #[instrument(skip(self))]
@@ -60,6 +111,8 @@ impl GmTxManager {
             GmTxRequest::Deposit(deposit_request) => self.get_deposit_amount_out(deposit_request).await,
             GmTxRequest::Withdrawal(withdrawal_request) => self.get_withdrawal_amount_out(withdrawal_request).await,
             GmTxRequest::Shift(_) => Err(eyre::eyre!("Amount out estimation for Shift requests is not supported")),
+            GmTxRequest::ClaimRewards(_) => Err(eyre::eyre!("Amount out estimation for ClaimRewards requests is not supported")),
+            GmTxRequest::ClaimUiFees(_) => Err(eyre::eyre!("Amount out estimation for ClaimUiFees requests is not supported")),
         }
     }
 
@@ -67,20 +120,21 @@ impl GmTxManager {
 
     /// Execute a GM deposit request
     #[instrument(skip(self, request))]
-    async fn execute_deposit(&self, request: &GmDepositRequest) -> Result<()> {
+    async fn execute_deposit(&self, request: &GmDepositRequest, rebalance_id: Option<i32>) -> Result<()> {
         // Validate request
         let log_string = self.validate_deposit_request(&request).await?;
 
-        // Get pre-deposit balances
-        let market_token_info = self.wallet_manager.market_tokens.get(&request.market)
+        // Get pre-deposit balances (of the tokens sent, which are swapped into the long/short tokens when they differ)
+        let market_token_info = self.wallet_manager.market_token(&request.market)
             .ok_or_else(|| eyre::eyre!("Market token not found: {}", request.market))?;
-        let long_token_info = self.wallet_manager.asset_tokens.get(&market_token_info.long_token_address)
-            .ok_or_else(|| eyre::eyre!("Long token not found: {}", market_token_info.long_token_address))?;
-        let short_token_info = self.wallet_manager.asset_tokens.get(&market_token_info.short_token_address)
-            .ok_or_else(|| eyre::eyre!("Short token not found: {}", market_token_info.short_token_address))?;
+        let (initial_long_token, initial_short_token) = request.initial_tokens(market_token_info.long_token_address, market_token_info.short_token_address);
+        let long_token_info = self.wallet_manager.asset_token(&initial_long_token)
+            .ok_or_else(|| eyre::eyre!("Long token not found: {}", initial_long_token))?;
+        let short_token_info = self.wallet_manager.asset_token(&initial_short_token)
+            .ok_or_else(|| eyre::eyre!("Short token not found: {}", initial_short_token))?;
         let initial_market_token_balance = self.wallet_manager.get_token_balance(market_token_info.address).await?;
-        let initial_long_token_balance = self.wallet_manager.get_token_balance(market_token_info.long_token_address).await?;
-        let initial_short_token_balance = self.wallet_manager.get_token_balance(market_token_info.short_token_address).await?;
+        let initial_long_token_balance = self.wallet_manager.get_token_balance(initial_long_token).await?;
+        let initial_short_token_balance = self.wallet_manager.get_token_balance(initial_short_token).await?;
         let initial_native_token_balance = self.wallet_manager.get_native_balance().await?;
 
         info!(
@@ -93,7 +147,7 @@ impl GmTxManager {
         );
 
         // Get execution fee
-        let (execution_fee, gas_limit, gas_price) = self.calculate_execution_fee(GmTxRequest::Deposit(request.clone())).await?;
+        let (execution_fee, gas_limit, max_fee_per_gas, gas_quote) = self.calculate_execution_fee(GmTxRequest::Deposit(request.clone())).await?;
 
         // Verify funds for deposit
         if initial_long_token_balance < request.long_amount {
@@ -113,19 +167,27 @@ impl GmTxManager {
             ));
         }
 
+        // Estimate the price impact before the tokens leave the wallet
+        let price_impact_usd = self.estimate_price_impact_usd(&GmTxRequest::Deposit(request.clone())).await;
+
         // Create deposit params
         let (deposit_params, initial_long_amount, initial_short_amount) = self.create_deposit_params(request, execution_fee)?;
 
         // Execute deposit
-        let (tx_hash, receipt) = exchange_router::create_deposit(
-            &self.config, 
-            &self.wallet_manager, 
-            deposit_params, 
-            initial_long_amount, 
-            initial_short_amount, 
-            gas_limit, 
-            gas_price
-        ).await?;
+        let (tx_hash, receipt) = {
+            // One submission at a time so concurrently executed plan actions don't race for the wallet nonce
+            let _submission_guard = self.submission_lock.lock().await;
+            exchange_router::create_deposit(
+                &self.config, 
+                &self.wallet_manager, 
+                self.db_manager.clock.as_ref(),
+                deposit_params, 
+                initial_long_amount, 
+                initial_short_amount, 
+                gas_limit, 
+                max_fee_per_gas
+            ).await?
+        };
         let gas_used = self.u256_to_decimal(receipt.gas_used.unwrap_or(U256::zero()), 0)?;
         let gas_price = self.u256_to_decimal(receipt.effective_gas_price.unwrap_or(U256::zero()), 18)?;
         info!(
@@ -134,15 +196,42 @@ impl GmTxManager {
             gas_used = ?gas_used,
             gas_price = ?gas_price,
             gas_cost = ?gas_used * gas_price,
-            gas_cost_usd = ?gas_used * gas_price * self.wallet_manager.native_token.last_mid_price_usd,
+            gas_cost_usd = ?gas_used * gas_price * self.wallet_manager.native_token().last_mid_price_usd,
             "{} Deposit Executed Successfully",
             log_string,
         );
+        self.record_trade(
+            NewTradeModel {
+                action_type: TradeActionType::GmDeposit.as_str().to_string(),
+                status: TradeStatus::Pending.as_str().to_string(),
+                market_id: self.db_manager.market_id_map.get(&request.market).copied(),
+                to_market_id: None,
+                long_token_amount: Some(request.long_amount),
+                short_token_amount: Some(request.short_amount),
+                market_token_amount: None,
+                tx_hash: None,
+                order_key: None,
+                execution_fee: None,
+                gas_used: None,
+                gas_price: None,
+                gas_cost_usd: None,
+                notional_usd: compliance::request_notional_usd(&self.wallet_manager, &GmTxRequest::Deposit(request.clone())).ok(),
+                compliance_rule_id: None,
+            },
+            tx_hash,
+            &receipt,
+            "DepositCreated",
+            execution_fee,
+            gas_limit,
+            gas_quote,
+            price_impact_usd,
+            rebalance_id,
+        ).await;
 
         // Get post-deposit balances
         let final_market_token_balance = self.wallet_manager.get_token_balance(market_token_info.address).await?;
-        let final_long_token_balance = self.wallet_manager.get_token_balance(market_token_info.long_token_address).await?;
-        let final_short_token_balance = self.wallet_manager.get_token_balance(market_token_info.short_token_address).await?;
+        let final_long_token_balance = self.wallet_manager.get_token_balance(initial_long_token).await?;
+        let final_short_token_balance = self.wallet_manager.get_token_balance(initial_short_token).await?;
         let final_native_token_balance = self.wallet_manager.get_native_balance().await?;
 
         let market_token_delta = final_market_token_balance - initial_market_token_balance;
@@ -155,16 +244,18 @@ impl GmTxManager {
             final_long_token_balance = ?final_long_token_balance,
             final_short_token_balance = ?final_short_token_balance,
             final_native_token_balance = ?final_native_token_balance,
-            "{} Deposit Completed \n {}{} {} ({:.2} USD) | {}{} {} ({:.2} USD) | {}{} {} ({:.2} USD) | {}{} NATIVE ({:.4} USD)",
-            log_string,
-            if market_token_delta.is_sign_positive() { "+" } else { "" }, market_token_delta, 
-            market_token_info.symbol, market_token_delta * market_token_info.last_mid_price_usd,
-            if long_token_delta.is_sign_positive() { "+" } else { "" }, long_token_delta, 
-            long_token_info.symbol, long_token_delta * long_token_info.last_mid_price_usd,
-            if short_token_delta.is_sign_positive() { "+" } else { "" }, short_token_delta, 
-            short_token_info.symbol, short_token_delta * short_token_info.last_mid_price_usd,
-            if native_token_delta.is_sign_positive() { "+" } else { "" }, native_token_delta, 
-            native_token_delta * self.wallet_manager.native_token.last_mid_price_usd
+            balance_changes = %format!(
+                "{}{} {} ({:.2} USD) | {}{} {} ({:.2} USD) | {}{} {} ({:.2} USD) | {}{} NATIVE ({:.4} USD)",
+                if market_token_delta.is_sign_positive() { "+" } else { "" }, market_token_delta,
+                market_token_info.symbol, market_token_delta * market_token_info.last_mid_price_usd,
+                if long_token_delta.is_sign_positive() { "+" } else { "" }, long_token_delta,
+                long_token_info.symbol, long_token_delta * long_token_info.last_mid_price_usd,
+                if short_token_delta.is_sign_positive() { "+" } else { "" }, short_token_delta,
+                short_token_info.symbol, short_token_delta * short_token_info.last_mid_price_usd,
+                if native_token_delta.is_sign_positive() { "+" } else { "" }, native_token_delta,
+                native_token_delta * self.wallet_manager.native_token().last_mid_price_usd
+            ),
+            "{} Deposit Completed", log_string
         );
 
         Ok(())
@@ -172,16 +263,16 @@ impl GmTxManager {
 
     /// Execute a GM withdrawal request
     #[instrument(skip(self, request))]
-    async fn execute_withdrawal(&self, request: &GmWithdrawalRequest) -> Result<()> {
+    async fn execute_withdrawal(&self, request: &GmWithdrawalRequest, rebalance_id: Option<i32>) -> Result<()> {
         // Validate request
         let log_string = self.validate_withdrawal_request(&request).await?;
 
         // Get pre-withdrawal balances
-        let market_token_info = self.wallet_manager.market_tokens.get(&request.market)
+        let market_token_info = self.wallet_manager.market_token(&request.market)
             .ok_or_else(|| eyre::eyre!("Market token not found: {}", request.market))?;
-        let long_token_info = self.wallet_manager.asset_tokens.get(&market_token_info.long_token_address)
+        let long_token_info = self.wallet_manager.asset_token(&market_token_info.long_token_address)
             .ok_or_else(|| eyre::eyre!("Long token not found: {}", market_token_info.long_token_address))?;
-        let short_token_info = self.wallet_manager.asset_tokens.get(&market_token_info.short_token_address)
+        let short_token_info = self.wallet_manager.asset_token(&market_token_info.short_token_address)
             .ok_or_else(|| eyre::eyre!("Short token not found: {}", market_token_info.short_token_address))?;
         let initial_market_token_balance = self.wallet_manager.get_token_balance(market_token_info.address).await?;
         let initial_long_token_balance = self.wallet_manager.get_token_balance(market_token_info.long_token_address).await?;
@@ -198,7 +289,7 @@ impl GmTxManager {
         );
 
         // Get execution fee
-        let (execution_fee, gas_limit, gas_price) = self.calculate_execution_fee(GmTxRequest::Withdrawal(request.clone())).await?;
+        let (execution_fee, gas_limit, max_fee_per_gas, gas_quote) = self.calculate_execution_fee(GmTxRequest::Withdrawal(request.clone())).await?;
 
         // Verify funds for withdrawal
         if initial_market_token_balance < request.amount {
@@ -213,18 +304,25 @@ impl GmTxManager {
             ));
         }
 
+        // Estimate the price impact before the GM tokens leave the wallet
+        let price_impact_usd = self.estimate_price_impact_usd(&GmTxRequest::Withdrawal(request.clone())).await;
+
         // Create withdrawal params
         let (withdrawal_params, market_token_amount) = self.create_withdrawal_params(request, execution_fee)?;
 
         // Execute withdrawal
-        let (tx_hash, receipt) = exchange_router::create_withdrawal(
-            &self.config, 
-            &self.wallet_manager, 
-            withdrawal_params, 
-            market_token_amount, 
-            gas_limit, 
-            gas_price
-        ).await?;
+        let (tx_hash, receipt) = {
+            let _submission_guard = self.submission_lock.lock().await;
+            exchange_router::create_withdrawal(
+                &self.config, 
+                &self.wallet_manager, 
+                self.db_manager.clock.as_ref(),
+                withdrawal_params, 
+                market_token_amount, 
+                gas_limit, 
+                max_fee_per_gas
+            ).await?
+        };
         let gas_used = self.u256_to_decimal(receipt.gas_used.unwrap_or(U256::zero()), 0)?;
         let gas_price = self.u256_to_decimal(receipt.effective_gas_price.unwrap_or(U256::zero()), 18)?;
         info!(
@@ -233,10 +331,37 @@ impl GmTxManager {
             gas_used = ?gas_used,
             gas_price = ?gas_price,
             gas_cost = ?gas_used * gas_price,
-            gas_cost_usd = ?gas_used * gas_price * self.wallet_manager.native_token.last_mid_price_usd,
+            gas_cost_usd = ?gas_used * gas_price * self.wallet_manager.native_token().last_mid_price_usd,
             "{} Withdrawal Executed Successfully",
             log_string,
         );
+        self.record_trade(
+            NewTradeModel {
+                action_type: TradeActionType::GmWithdrawal.as_str().to_string(),
+                status: TradeStatus::Pending.as_str().to_string(),
+                market_id: self.db_manager.market_id_map.get(&request.market).copied(),
+                to_market_id: None,
+                long_token_amount: None,
+                short_token_amount: None,
+                market_token_amount: Some(request.amount),
+                tx_hash: None,
+                order_key: None,
+                execution_fee: None,
+                gas_used: None,
+                gas_price: None,
+                gas_cost_usd: None,
+                notional_usd: compliance::request_notional_usd(&self.wallet_manager, &GmTxRequest::Withdrawal(request.clone())).ok(),
+                compliance_rule_id: None,
+            },
+            tx_hash,
+            &receipt,
+            "WithdrawalCreated",
+            execution_fee,
+            gas_limit,
+            gas_quote,
+            price_impact_usd,
+            rebalance_id,
+        ).await;
 
         // Get post-withdrawal balances
         let final_market_token_balance = self.wallet_manager.get_token_balance(market_token_info.address).await?;
@@ -254,16 +379,18 @@ impl GmTxManager {
             final_long_token_balance = ?final_long_token_balance,
             final_short_token_balance = ?final_short_token_balance,
             final_native_token_balance = ?final_native_token_balance,
-            "{} Withdrawal Completed \n {}{} {} ({:.2} USD) | {}{} {} ({:.2} USD) | {}{} {} ({:.2} USD) | {}{} NATIVE ({:.4} USD)",
-            log_string,
-            if market_token_delta.is_sign_positive() { "+" } else { "" }, market_token_delta,
-            market_token_info.symbol, market_token_delta * market_token_info.last_mid_price_usd,
-            if long_token_delta.is_sign_positive() { "+" } else { "" }, long_token_delta,
-            long_token_info.symbol, long_token_delta * long_token_info.last_mid_price_usd,
-            if short_token_delta.is_sign_positive() { "+" } else { "" }, short_token_delta,
-            short_token_info.symbol, short_token_delta * short_token_info.last_mid_price_usd,
-            if native_token_delta.is_sign_positive() { "+" } else { "" }, native_token_delta,
-            native_token_delta * self.wallet_manager.native_token.last_mid_price_usd
+            balance_changes = %format!(
+                "{}{} {} ({:.2} USD) | {}{} {} ({:.2} USD) | {}{} {} ({:.2} USD) | {}{} NATIVE ({:.4} USD)",
+                if market_token_delta.is_sign_positive() { "+" } else { "" }, market_token_delta,
+                market_token_info.symbol, market_token_delta * market_token_info.last_mid_price_usd,
+                if long_token_delta.is_sign_positive() { "+" } else { "" }, long_token_delta,
+                long_token_info.symbol, long_token_delta * long_token_info.last_mid_price_usd,
+                if short_token_delta.is_sign_positive() { "+" } else { "" }, short_token_delta,
+                short_token_info.symbol, short_token_delta * short_token_info.last_mid_price_usd,
+                if native_token_delta.is_sign_positive() { "+" } else { "" }, native_token_delta,
+                native_token_delta * self.wallet_manager.native_token().last_mid_price_usd
+            ),
+            "{} Withdrawal Completed", log_string
         );
 
         Ok(())
@@ -271,14 +398,14 @@ impl GmTxManager {
 
     /// Execute a GM shift request
     #[instrument(skip(self, request))]
-    async fn execute_shift(&self, request: &GmShiftRequest) -> Result<()> {
+    async fn execute_shift(&self, request: &GmShiftRequest, rebalance_id: Option<i32>) -> Result<()> {
         // Validate request
         let log_string = self.validate_shift_request(&request).await?;
 
         // Get pre-shift balances
-        let from_market_info = self.wallet_manager.market_tokens.get(&request.from_market)
+        let from_market_info = self.wallet_manager.market_token(&request.from_market)
             .ok_or_else(|| eyre::eyre!("From market token not found: {}", request.from_market))?;
-        let to_market_info = self.wallet_manager.market_tokens.get(&request.to_market)
+        let to_market_info = self.wallet_manager.market_token(&request.to_market)
             .ok_or_else(|| eyre::eyre!("To market token not found: {}", request.to_market))?;
         let initial_from_market_balance = self.wallet_manager.get_token_balance(from_market_info.address).await?;
         let initial_to_market_balance = self.wallet_manager.get_token_balance(to_market_info.address).await?;
@@ -293,7 +420,7 @@ impl GmTxManager {
         );
 
         // Get execution fee
-        let (execution_fee, gas_limit, gas_price) = self.calculate_execution_fee(GmTxRequest::Shift(request.clone())).await?;
+        let (execution_fee, gas_limit, max_fee_per_gas, gas_quote) = self.calculate_execution_fee(GmTxRequest::Shift(request.clone())).await?;
 
         // Verify funds for shift
         if initial_from_market_balance < request.amount {
@@ -312,14 +439,18 @@ impl GmTxManager {
         let (shift_params, from_market_amount) = self.create_shift_params(request, execution_fee)?;
 
         // Execute shift
-        let (tx_hash, receipt) = exchange_router::create_shift(
-            &self.config, 
-            &self.wallet_manager, 
-            shift_params, 
-            from_market_amount, 
-            gas_limit, 
-            gas_price
-        ).await?;
+        let (tx_hash, receipt) = {
+            let _submission_guard = self.submission_lock.lock().await;
+            exchange_router::create_shift(
+                &self.config, 
+                &self.wallet_manager, 
+                self.db_manager.clock.as_ref(),
+                shift_params, 
+                from_market_amount, 
+                gas_limit, 
+                max_fee_per_gas
+            ).await?
+        };
         let gas_used = self.u256_to_decimal(receipt.gas_used.unwrap_or(U256::zero()), 0)?;
         let gas_price = self.u256_to_decimal(receipt.effective_gas_price.unwrap_or(U256::zero()), 18)?;
         info!(
@@ -328,10 +459,37 @@ impl GmTxManager {
             gas_used = ?gas_used,
             gas_price = ?gas_price,
             gas_cost = ?gas_used * gas_price,
-            gas_cost_usd = ?gas_used * gas_price * self.wallet_manager.native_token.last_mid_price_usd,
+            gas_cost_usd = ?gas_used * gas_price * self.wallet_manager.native_token().last_mid_price_usd,
             "{} Shift Executed Successfully",
             log_string,
         );
+        self.record_trade(
+            NewTradeModel {
+                action_type: TradeActionType::GmShift.as_str().to_string(),
+                status: TradeStatus::Pending.as_str().to_string(),
+                market_id: self.db_manager.market_id_map.get(&request.from_market).copied(),
+                to_market_id: self.db_manager.market_id_map.get(&request.to_market).copied(),
+                long_token_amount: None,
+                short_token_amount: None,
+                market_token_amount: Some(request.amount),
+                tx_hash: None,
+                order_key: None,
+                execution_fee: None,
+                gas_used: None,
+                gas_price: None,
+                gas_cost_usd: None,
+                notional_usd: compliance::request_notional_usd(&self.wallet_manager, &GmTxRequest::Shift(request.clone())).ok(),
+                compliance_rule_id: None,
+            },
+            tx_hash,
+            &receipt,
+            "ShiftCreated",
+            execution_fee,
+            gas_limit,
+            gas_quote,
+            Decimal::ZERO, // GMX has no amount out estimate for shifts
+            rebalance_id,
+        ).await;
 
         // Get post-shift balances
         let final_from_market_balance = self.wallet_manager.get_token_balance(from_market_info.address).await?;
@@ -346,19 +504,199 @@ impl GmTxManager {
             final_from_market_balance = ?final_from_market_balance,
             final_to_market_balance = ?final_to_market_balance,
             final_native_token_balance = ?final_native_token_balance,
-            "{} Shift Completed \n {}{} {} ({:.2} USD) | {}{} {} ({:.2} USD) | {}{} NATIVE ({:.4} USD)",
-            log_string,
-            if from_market_delta.is_sign_positive() { "+" } else { "" }, from_market_delta,
-            from_market_info.symbol, from_market_delta * from_market_info.last_mid_price_usd,
-            if to_market_delta.is_sign_positive() { "+" } else { "" }, to_market_delta,
-            to_market_info.symbol, to_market_delta * to_market_info.last_mid_price_usd,
-            if native_token_delta.is_sign_positive() { "+" } else { "" }, native_token_delta,
-            native_token_delta * self.wallet_manager.native_token.last_mid_price_usd
+            balance_changes = %format!(
+                "{}{} {} ({:.2} USD) | {}{} {} ({:.2} USD) | {}{} NATIVE ({:.4} USD)",
+                if from_market_delta.is_sign_positive() { "+" } else { "" }, from_market_delta,
+                from_market_info.symbol, from_market_delta * from_market_info.last_mid_price_usd,
+                if to_market_delta.is_sign_positive() { "+" } else { "" }, to_market_delta,
+                to_market_info.symbol, to_market_delta * to_market_info.last_mid_price_usd,
+                if native_token_delta.is_sign_positive() { "+" } else { "" }, native_token_delta,
+                native_token_delta * self.wallet_manager.native_token().last_mid_price_usd
+            ),
+            "{} Shift Completed", log_string
         );
 
         Ok(())
     }
     
+    /// Claim accrued pool incentive rewards, recorded as an already settled trade since no keeper is involved
+    #[instrument(skip(self, request))]
+    async fn execute_claim_rewards(&self, request: &GmClaimRewardsRequest, rebalance_id: Option<i32>) -> Result<()> {
+        if request.tokens.is_empty() {
+            return Err(eyre::eyre!("No reward tokens to claim"));
+        }
+
+        // Skip the transaction when nothing has accrued
+        let claimable = incentives::get_claimable_rewards(&self.config, &self.wallet_manager, &request.tokens).await?;
+        if claimable.iter().all(|amount| amount.is_zero()) {
+            info!(tokens = ?request.tokens, "No accrued rewards to claim");
+            return Ok(());
+        }
+
+        let (tx_hash, receipt) = {
+            let _submission_guard = self.submission_lock.lock().await;
+            incentives::claim_rewards(&self.config, &self.wallet_manager, request.tokens.clone()).await?
+        };
+        let gas_used = self.u256_to_decimal(receipt.gas_used.unwrap_or(U256::zero()), 0)?;
+        let gas_price = self.u256_to_decimal(receipt.effective_gas_price.unwrap_or(U256::zero()), 18)?;
+        let gas_cost_usd = gas_used * gas_price * self.wallet_manager.native_token().last_mid_price_usd;
+        let claimed_summary = request.tokens.iter()
+            .zip(claimable.iter())
+            .map(|(token, amount)| {
+                let (symbol, decimals) = self.wallet_manager.token(token)
+                    .map(|t| (t.symbol, t.decimals))
+                    .unwrap_or_else(|| (format!("{:?}", token), 18));
+                format!("{} {}", self.u256_to_decimal(*amount, decimals).unwrap_or_default(), symbol)
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        info!(
+            tx_hash = ?tx_hash,
+            gas_used = ?gas_used,
+            gas_cost_usd = ?gas_cost_usd,
+            "REWARD CLAIM | Claimed {} |",
+            claimed_summary
+        );
+
+        let trade = NewTradeModel {
+            action_type: TradeActionType::ClaimRewards.as_str().to_string(),
+            status: TradeStatus::Settled.as_str().to_string(),
+            market_id: None,
+            to_market_id: None,
+            long_token_amount: None,
+            short_token_amount: None,
+            market_token_amount: None,
+            tx_hash: Some(format!("{:?}", tx_hash)),
+            order_key: None,
+            execution_fee: None,
+            gas_used: Some(gas_used),
+            gas_price: Some(gas_price),
+            gas_cost_usd: Some(gas_cost_usd),
+            notional_usd: Some(Decimal::ZERO),
+            compliance_rule_id: None,
+        };
+        if let Err(e) = self.db_manager.insert_trade(&trade, rebalance_id).await {
+            error!(error = ?e, tx_hash = ?tx_hash, "Failed to record reward claim");
+        }
+        let execution_cost = NewExecutionCostModel {
+            venue: ExecutionVenue::Gmx.as_str().to_string(),
+            action_type: trade.action_type.clone(),
+            tx_hash: trade.tx_hash.clone(),
+            gas_cost_usd,
+            execution_fee_usd: Decimal::ZERO,
+            slippage_usd: Decimal::ZERO,
+        };
+        if let Err(e) = self.db_manager.insert_execution_cost(&execution_cost, rebalance_id).await {
+            error!(error = ?e, tx_hash = ?tx_hash, "Failed to record execution cost");
+        }
+
+        Ok(())
+    }
+
+    /// Claim the UI fees accrued to the wallet in the long and short tokens of the given markets, when the wallet is
+    /// the configured UI fee receiver
+    #[instrument(skip(self, request))]
+    async fn execute_claim_ui_fees(&self, request: &GmClaimUiFeesRequest, rebalance_id: Option<i32>) -> Result<()> {
+        if self.config.gmx_ui_fee_receiver != Some(self.wallet_manager.address) {
+            return Err(eyre::eyre!("UI fees are only claimable when the wallet is the configured UI fee receiver"));
+        }
+        let mut pairs = Vec::with_capacity(request.markets.len() * 2);
+        for market in &request.markets {
+            let market_token_info = self.wallet_manager.market_token(market)
+                .ok_or_else(|| eyre::eyre!("Market token not found: {}", market))?;
+            pairs.push((*market, market_token_info.long_token_address));
+            if market_token_info.short_token_address != market_token_info.long_token_address {
+                pairs.push((*market, market_token_info.short_token_address));
+            }
+        }
+
+        // Skip the transaction when nothing has accrued
+        let claimable = datastore::get_claimable_ui_fees_batch(&self.config, self.wallet_manager.address, &pairs).await?;
+        let pairs: Vec<(Address, Address)> = pairs.into_iter()
+            .filter(|pair| claimable.get(pair).is_some_and(|amount| !amount.is_zero()))
+            .collect();
+        if pairs.is_empty() {
+            if datastore::get_ui_fee_factor(&self.config, self.wallet_manager.address).await?.is_zero() {
+                debug!("No UI fee factor set for the wallet, no UI fees accrue");
+            }
+            info!(market_count = request.markets.len(), "No accrued UI fees to claim");
+            return Ok(());
+        }
+
+        let (markets, tokens): (Vec<Address>, Vec<Address>) = pairs.iter().copied().unzip();
+        let (tx_hash, receipt) = {
+            let _submission_guard = self.submission_lock.lock().await;
+            exchange_router::claim_ui_fees(&self.config, &self.wallet_manager, markets, tokens).await?
+        };
+        let gas_used = self.u256_to_decimal(receipt.gas_used.unwrap_or(U256::zero()), 0)?;
+        let gas_price = self.u256_to_decimal(receipt.effective_gas_price.unwrap_or(U256::zero()), 18)?;
+        let gas_cost_usd = gas_used * gas_price * self.wallet_manager.native_token().last_mid_price_usd;
+
+        // Sum the claimed amounts per token, the same token accrues in several markets
+        let mut claimed: Vec<(Address, U256)> = Vec::new();
+        for (market, token) in &pairs {
+            let amount = claimable[&(*market, *token)];
+            match claimed.iter_mut().find(|(claimed_token, _)| claimed_token == token) {
+                Some((_, total)) => *total += amount,
+                None => claimed.push((*token, amount)),
+            }
+        }
+        let mut claimed_usd = Decimal::ZERO;
+        let claimed_summary = claimed.iter()
+            .map(|(token, amount)| {
+                let (symbol, decimals, price) = self.wallet_manager.token(token)
+                    .map(|t| (t.symbol, t.decimals, t.last_mid_price_usd))
+                    .unwrap_or_else(|| (format!("{:?}", token), 18, Decimal::ZERO));
+                let amount = self.u256_to_decimal(*amount, decimals).unwrap_or_default();
+                claimed_usd += amount * price;
+                format!("{} {}", amount, symbol)
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        info!(
+            tx_hash = ?tx_hash,
+            gas_used = ?gas_used,
+            gas_cost_usd = ?gas_cost_usd,
+            claimed_usd = %claimed_usd.round_dp(2),
+            "UI FEE CLAIM | Claimed {} |",
+            claimed_summary
+        );
+
+        let trade = NewTradeModel {
+            action_type: TradeActionType::ClaimUiFees.as_str().to_string(),
+            status: TradeStatus::Settled.as_str().to_string(),
+            market_id: None,
+            to_market_id: None,
+            long_token_amount: None,
+            short_token_amount: None,
+            market_token_amount: None,
+            tx_hash: Some(format!("{:?}", tx_hash)),
+            order_key: None,
+            execution_fee: None,
+            gas_used: Some(gas_used),
+            gas_price: Some(gas_price),
+            gas_cost_usd: Some(gas_cost_usd),
+            notional_usd: Some(Decimal::ZERO),
+            compliance_rule_id: None,
+        };
+        if let Err(e) = self.db_manager.insert_trade(&trade, rebalance_id).await {
+            error!(error = ?e, tx_hash = ?tx_hash, "Failed to record UI fee claim");
+        }
+        let execution_cost = NewExecutionCostModel {
+            venue: ExecutionVenue::Gmx.as_str().to_string(),
+            action_type: trade.action_type.clone(),
+            tx_hash: trade.tx_hash.clone(),
+            gas_cost_usd,
+            execution_fee_usd: Decimal::ZERO,
+            slippage_usd: Decimal::ZERO,
+        };
+        if let Err(e) = self.db_manager.insert_execution_cost(&execution_cost, rebalance_id).await {
+            error!(error = ?e, tx_hash = ?tx_hash, "Failed to record execution cost");
+        }
+
+        Ok(())
+    }
+
     /// Validate the GM deposit request, create log string
     #[instrument(skip(self, request))]
     async fn validate_deposit_request(&self, request: &GmDepositRequest) -> Result<String> {
@@ -366,19 +704,26 @@ impl GmTxManager {
         if request.long_amount.is_zero() && request.short_amount.is_zero() {
             return Err(eyre::eyre!("Both long and short amounts cannot be zero"));
         }
-        let market_token_info = self.wallet_manager.market_tokens.get(&request.market)
+        let market_token_info = self.wallet_manager.market_token(&request.market)
             .ok_or_else(|| eyre::eyre!("Market token not found: {}", request.market))?;
-        let long_token_info = self.wallet_manager.asset_tokens.get(&market_token_info.long_token_address)
-            .ok_or_else(|| eyre::eyre!("Long token not found: {}", market_token_info.long_token_address))?;
-        let short_token_info = self.wallet_manager.asset_tokens.get(&market_token_info.short_token_address)
-            .ok_or_else(|| eyre::eyre!("Short token not found: {}", market_token_info.short_token_address))?;
+        let (initial_long_token, long_token_swap_path, initial_short_token, short_token_swap_path) = self.get_deposit_swap_paths(request)?;
+        let long_token_info = self.wallet_manager.asset_token(&initial_long_token)
+            .ok_or_else(|| eyre::eyre!("Long token not found: {}", initial_long_token))?;
+        let short_token_info = self.wallet_manager.asset_token(&initial_short_token)
+            .ok_or_else(|| eyre::eyre!("Short token not found: {}", initial_short_token))?;
+        let swaps_initial_token = request.initial_long_token.is_some() || request.initial_short_token.is_some();
+        if swaps_initial_token && initial_long_token == initial_short_token && !request.long_amount.is_zero() && !request.short_amount.is_zero() {
+            return Err(eyre::eyre!("Long and short amounts cannot both be sent as {}, send the total on one side", long_token_info.symbol));
+        }
 
         // Create log string
+        let swap_count = long_token_swap_path.len() + short_token_swap_path.len();
         let log_string = format!(
-            "GM DEPOSIT REQUEST | Deposit {} {} and {} {} into {} |",
+            "GM DEPOSIT REQUEST | Deposit {} {} and {} {} into {}{} |",
             request.long_amount, long_token_info.symbol,
             request.short_amount, short_token_info.symbol,
-            market_token_info.symbol
+            market_token_info.symbol,
+            if swap_count > 0 { format!(" via {} GMX swap(s)", swap_count) } else { String::new() }
         );
         Ok(log_string)
     }
@@ -390,7 +735,7 @@ impl GmTxManager {
         if request.amount.is_zero() {
             return Err(eyre::eyre!("Withdrawal amount cannot be zero"));
         }
-        let market_token_info = self.wallet_manager.market_tokens.get(&request.market)
+        let market_token_info = self.wallet_manager.market_token(&request.market)
             .ok_or_else(|| eyre::eyre!("Market token not found: {}", request.market))?;
         
         // Create log string
@@ -411,9 +756,9 @@ impl GmTxManager {
         if request.from_market == request.to_market {
             return Err(eyre::eyre!("From and to markets cannot be the same"));
         }
-        let from_market_info = self.wallet_manager.market_tokens.get(&request.from_market)
+        let from_market_info = self.wallet_manager.market_token(&request.from_market)
             .ok_or_else(|| eyre::eyre!("From market token not found: {}", request.from_market))?;
-        let to_market_info = self.wallet_manager.market_tokens.get(&request.to_market)
+        let to_market_info = self.wallet_manager.market_token(&request.to_market)
             .ok_or_else(|| eyre::eyre!("To market token not found: {}", request.to_market))?;
         
         // Create log string
@@ -424,21 +769,92 @@ impl GmTxManager {
         Ok(log_string)
     }             
 
-    /// Calculates the execution fee for a GM transaction
+    /// Defer deferrable requests while the estimated execution fee is above the spike threshold,
+    /// proceeding once fees normalize or the max deferral passes. Every estimate is recorded towards the baseline.
+    #[instrument(skip(self, request))]
+    async fn wait_for_normal_execution_fee(&self, request: &GmTxRequest) -> Result<()> {
+        let action_type = match request {
+            GmTxRequest::Deposit(_) => TradeActionType::GmDeposit,
+            GmTxRequest::Withdrawal(_) => TradeActionType::GmWithdrawal,
+            GmTxRequest::Shift(_) => TradeActionType::GmShift,
+            GmTxRequest::ClaimRewards(_) | GmTxRequest::ClaimUiFees(_) => return Ok(()), // Claims pay no keeper execution fee
+        }.as_str();
+        let deferrable = gas_guard::is_deferrable(&self.config, action_type);
+        let deadline = self.db_manager.clock.now() + chrono::Duration::seconds(self.config.gas_max_deferral_secs as i64);
+
+        loop {
+            let (execution_fee, _, gas_price, _) = self.calculate_execution_fee(request.clone()).await?;
+            let check = gas_guard::check_execution_fee(
+                &self.config,
+                &self.db_manager,
+                action_type,
+                self.u256_to_decimal(gas_price, 0)?,
+                self.u256_to_decimal(execution_fee, 18)?,
+            ).await?;
+
+            let ExecutionFeeCheck::Spiked { baseline, ratio } = check else {
+                return Ok(());
+            };
+            if !deferrable {
+                warn!(action_type = action_type, baseline = %baseline, ratio = %ratio.round_dp(2), "Execution fee spiking, proceeding since action is not deferrable");
+                return Ok(());
+            }
+            if self.db_manager.clock.now() >= deadline {
+                warn!(action_type = action_type, baseline = %baseline, ratio = %ratio.round_dp(2), "Execution fee still spiking after max deferral, proceeding");
+                return Ok(());
+            }
+            info!(
+                action_type = action_type,
+                baseline = %baseline,
+                ratio = %ratio.round_dp(2),
+                deadline = %deadline,
+                "Execution fee spiking, deferring request for {}s",
+                GAS_SPIKE_POLL_INTERVAL_SECS
+            );
+            tokio::time::sleep(std::time::Duration::from_secs(GAS_SPIKE_POLL_INTERVAL_SECS)).await;
+        }
+    }
+
+    /// Calculates the execution fee for a GM transaction, as (execution fee, gas limit, max fee per gas, gas quote)
     #[instrument(skip(self))]
-    async fn calculate_execution_fee(&self, gm_transaction_type: GmTxRequest) -> Result<(U256, U256, U256)> {
+    async fn calculate_execution_fee(&self, gm_transaction_type: GmTxRequest) -> Result<(U256, U256, U256, GasQuote)> {
         debug!(?gm_transaction_type, "Calculating execution fee");
+
+        // Deposits sending held tokens through GMX swap paths pay for every swap on execution
+        let swaps_count = match &gm_transaction_type {
+            GmTxRequest::Deposit(deposit_request) => {
+                let (_, long_token_swap_path, _, short_token_swap_path) = self.get_deposit_swap_paths(deposit_request)?;
+                U256::from(long_token_swap_path.len() + short_token_swap_path.len())
+            }
+            _ => U256::zero(),
+        };
         let estimated_gas_limit = match gm_transaction_type {
+            GmTxRequest::Deposit(_) if !swaps_count.is_zero() => {
+                datastore::get_deposit_gas_limit(&self.config).await? + datastore::estimate_execute_gas_limit_per_swap(&self.config).await? * swaps_count
+            }
             GmTxRequest::Deposit(_) => datastore::get_deposit_gas_limit(&self.config).await?,
             GmTxRequest::Withdrawal(_) => datastore::get_withdrawal_gas_limit(&self.config).await?,
             GmTxRequest::Shift(_) => datastore::get_shift_gas_limit(&self.config).await?,
+            GmTxRequest::ClaimRewards(_) | GmTxRequest::ClaimUiFees(_) => return Err(eyre::eyre!("Claims have no keeper execution fee")),
+        };
+        // Keepers forward the callback gas limit to the callback contract, so it is paid for in the execution fee
+        let (callback_contract, callback_gas_limit) = self.callback_settings();
+        let estimated_gas_limit = if callback_contract.is_zero() {
+            estimated_gas_limit
+        } else {
+            let max_callback_gas_limit = datastore::get_max_callback_gas_limit(&self.config).await?;
+            if callback_gas_limit > max_callback_gas_limit {
+                return Err(eyre::eyre!("Callback gas limit {} exceeds the GMX maximum of {}", callback_gas_limit, max_callback_gas_limit));
+            }
+            estimated_gas_limit + callback_gas_limit
         };
         debug!(?estimated_gas_limit, "Estimated total gas limit for deposit");
 
         let oracle_price_count = match gm_transaction_type {
-            GmTxRequest::Deposit(_) => datastore::estimate_deposit_oracle_price_count(U256::zero()),
+            GmTxRequest::Deposit(_) => datastore::estimate_deposit_oracle_price_count(swaps_count),
             GmTxRequest::Withdrawal(_) => datastore::estimate_withdrawal_oracle_price_count(U256::zero()),
             GmTxRequest::Shift(_) => datastore::estimate_shift_oracle_price_count(U256::zero()),
+            GmTxRequest::ClaimRewards(_) | GmTxRequest::ClaimUiFees(_) => return Err(eyre::eyre!("Claims have no keeper execution fee")),
         };
         let adjusted_gas_limit = datastore::adjust_gas_limit_for_estimate(
             &self.config,
@@ -447,45 +863,246 @@ impl GmTxManager {
         ).await?;
         debug!(?adjusted_gas_limit, "Adjusted gas limit for estimate");
 
-        let gas_price_dec = self.u256_to_decimal(self.wallet_manager.signer.provider().get_gas_price().await?, 0)?;
-        let gas_price_dec_with_buf = gas_price_dec * self.max_fee_per_gas_buffer;
+        // Buffer tuned to the base fee movement this action type's requests have had to absorb
+        let action_type = match gm_transaction_type {
+            GmTxRequest::Deposit(_) => TradeActionType::GmDeposit,
+            GmTxRequest::Withdrawal(_) => TradeActionType::GmWithdrawal,
+            GmTxRequest::Shift(_) => TradeActionType::GmShift,
+            GmTxRequest::ClaimRewards(_) => TradeActionType::ClaimRewards,
+            GmTxRequest::ClaimUiFees(_) => TradeActionType::ClaimUiFees,
+        }.as_str();
+        let max_fee_per_gas_buffer = fee_buffer::max_fee_per_gas_buffer(&self.config, &self.db_manager, action_type, self.default_fee_buffer).await?;
+
+        let provider = self.wallet_manager.signer.provider();
+        let gas_price_dec = self.u256_to_decimal(provider.get_gas_price().await?, 0)?;
+        let base_fee = provider.get_block(BlockNumber::Latest).await?
+            .and_then(|block| block.base_fee_per_gas)
+            .and_then(|base_fee| self.u256_to_decimal(base_fee, 0).ok());
+        let gas_price_dec_with_buf = gas_price_dec * max_fee_per_gas_buffer;
         let gas_price = self.decimal_to_u256(gas_price_dec_with_buf, 0)?;
-        debug!(?gas_price, "Gas price with buffer");
+        debug!(?gas_price, %max_fee_per_gas_buffer, "Gas price with buffer");
 
         let execution_fee = adjusted_gas_limit * gas_price;
         debug!(?execution_fee, "Calculated execution fee for deposit");
 
-        Ok((execution_fee, adjusted_gas_limit, gas_price))
+        let gas_quote = GasQuote {
+            network_gas_price: gas_price_dec,
+            max_fee_per_gas_buffer,
+            base_fee,
+        };
+        Ok((execution_fee, adjusted_gas_limit, gas_price, gas_quote))
+    }
+
+    /// Record a request a compliance rule blocked as a Blocked trade carrying the rule's ID.
+    /// Failures are logged rather than returned, the request is refused either way.
+    async fn record_blocked_trade(&self, request: &GmTxRequest, violation: &compliance::ComplianceViolation, rebalance_id: Option<i32>) {
+        let market_id = |market: &Address| self.db_manager.market_id_map.get(market).copied();
+        let (action_type, market_id, to_market_id, long_token_amount, short_token_amount, market_token_amount) = match request {
+            GmTxRequest::Deposit(deposit) => (TradeActionType::GmDeposit, market_id(&deposit.market), None, Some(deposit.long_amount), Some(deposit.short_amount), None),
+            GmTxRequest::Withdrawal(withdrawal) => (TradeActionType::GmWithdrawal, market_id(&withdrawal.market), None, None, None, Some(withdrawal.amount)),
+            GmTxRequest::Shift(shift) => (TradeActionType::GmShift, market_id(&shift.from_market), market_id(&shift.to_market), None, None, Some(shift.amount)),
+            GmTxRequest::ClaimRewards(_) => (TradeActionType::ClaimRewards, None, None, None, None, None),
+            GmTxRequest::ClaimUiFees(_) => (TradeActionType::ClaimUiFees, None, None, None, None, None),
+        };
+        let trade = NewTradeModel {
+            action_type: action_type.as_str().to_string(),
+            status: TradeStatus::Blocked.as_str().to_string(),
+            market_id,
+            to_market_id,
+            long_token_amount,
+            short_token_amount,
+            market_token_amount,
+            tx_hash: None,
+            order_key: None,
+            execution_fee: None,
+            gas_used: None,
+            gas_price: None,
+            gas_cost_usd: None,
+            notional_usd: compliance::request_notional_usd(&self.wallet_manager, request).ok(),
+            compliance_rule_id: Some(violation.rule_id.clone()),
+        };
+        warn!(rule_id = %violation.rule_id, action_type = action_type.as_str(), "{}", violation);
+        if let Err(e) = self.db_manager.insert_trade(&trade, rebalance_id).await {
+            error!(error = ?e, rule_id = %violation.rule_id, "Failed to record blocked trade");
+        }
+    }
+
+    /// Value a deposit or withdrawal is estimated to lose to GMX price impact and pool fees: its value at mid prices
+    /// minus the estimated amount out at mid prices. Zero when the estimate fails, the request goes ahead regardless.
+    async fn estimate_price_impact_usd(&self, request: &GmTxRequest) -> Decimal {
+        let notional_usd = match compliance::request_notional_usd(&self.wallet_manager, request) {
+            Ok(notional_usd) => notional_usd,
+            Err(e) => {
+                warn!(error = ?e, "Failed to value GM request, price impact not estimated");
+                return Decimal::ZERO;
+            }
+        };
+        let token_price = |token: &Address| self.wallet_manager.token(token).map(|info| info.last_mid_price_usd).unwrap_or_default();
+        let amount_out_usd = match (request, self.get_transaction_amount_out(request).await) {
+            (GmTxRequest::Deposit(deposit), Ok(GmAmountOutResponse::Deposit { amount_out })) => {
+                amount_out * self.wallet_manager.market_token(&deposit.market).map(|info| info.last_mid_price_usd).unwrap_or_default()
+            }
+            (GmTxRequest::Withdrawal(withdrawal), Ok(GmAmountOutResponse::Withdrawal { long_amount_out, short_amount_out })) => {
+                let Some(market_token_info) = self.wallet_manager.market_token(&withdrawal.market) else {
+                    return Decimal::ZERO;
+                };
+                long_amount_out * token_price(&market_token_info.long_token_address)
+                    + short_amount_out * token_price(&market_token_info.short_token_address)
+            }
+            (_, Err(e)) => {
+                warn!(error = ?e, "Failed to estimate GM request amount out, price impact not estimated");
+                return Decimal::ZERO;
+            }
+            _ => return Decimal::ZERO,
+        };
+        let price_impact_usd = notional_usd - amount_out_usd;
+        debug!(notional_usd = %notional_usd, amount_out_usd = %amount_out_usd, price_impact_usd = %price_impact_usd, "GM request price impact estimated");
+        price_impact_usd
+    }
+
+    /// Record a created GM request in the trades table so its keeper execution can be monitored.
+    /// Failures are logged rather than returned since the on-chain request has already been created.
+    async fn record_trade(
+        &self,
+        mut trade: NewTradeModel,
+        tx_hash: TxHash,
+        receipt: &TransactionReceipt,
+        created_event_name: &str,
+        execution_fee: U256,
+        gas_limit: U256,
+        gas_quote: GasQuote,
+        price_impact_usd: Decimal,
+        rebalance_id: Option<i32>,
+    ) {
+        let order_key = exchange_router::get_request_key_from_receipt(&self.config, receipt, created_event_name);
+        if order_key.is_none() {
+            error!(tx_hash = ?tx_hash, event = created_event_name, "Request key not found in receipt, order cannot be monitored");
+        }
+        let gas_used = self.u256_to_decimal(receipt.gas_used.unwrap_or(U256::zero()), 0).unwrap_or_default();
+        let gas_price = self.u256_to_decimal(receipt.effective_gas_price.unwrap_or(U256::zero()), 18).unwrap_or_default();
+
+        trade.tx_hash = Some(format!("{:?}", tx_hash));
+        trade.order_key = order_key.map(|key| format!("{:?}", key));
+        trade.execution_fee = self.u256_to_decimal(execution_fee, 18).ok();
+        trade.gas_used = Some(gas_used);
+        trade.gas_price = Some(gas_price);
+        trade.gas_cost_usd = Some(gas_used * gas_price * self.wallet_manager.native_token().last_mid_price_usd);
+
+        match self.db_manager.insert_trade(&trade, rebalance_id).await {
+            Ok(trade_id) => debug!(trade_id = trade_id, order_key = ?trade.order_key, "Trade recorded"),
+            Err(e) => error!(error = ?e, tx_hash = ?tx_hash, "Failed to record trade"),
+        }
+
+        // Count gas and the full execution fee against the fee budget, the refund is netted out once the order monitor
+        // reconciles it after keeper execution
+        let execution_cost = NewExecutionCostModel {
+            venue: ExecutionVenue::Gmx.as_str().to_string(),
+            action_type: trade.action_type.clone(),
+            tx_hash: trade.tx_hash.clone(),
+            gas_cost_usd: trade.gas_cost_usd.unwrap_or_default(),
+            execution_fee_usd: trade.execution_fee.unwrap_or_default() * self.wallet_manager.native_token().last_mid_price_usd,
+            slippage_usd: price_impact_usd,
+        };
+        if let Err(e) = self.db_manager.insert_execution_cost(&execution_cost, rebalance_id).await {
+            error!(error = ?e, tx_hash = ?tx_hash, "Failed to record execution cost");
+        }
+
+        // Profile gas usage so the fee buffer and keeper gas estimates can be tuned from empirical data
+        let inclusion_base_fee = match receipt.block_number {
+            Some(block_number) => self.wallet_manager.signer.provider().get_block(block_number).await.ok().flatten()
+                .and_then(|block| block.base_fee_per_gas)
+                .and_then(|base_fee| self.u256_to_decimal(base_fee, 0).ok()),
+            None => None,
+        };
+        let gas_profile = NewGasProfileModel {
+            action_type: trade.action_type.clone(),
+            market_id: trade.market_id,
+            tx_hash: format!("{:?}", tx_hash),
+            gas_used,
+            l1_gas_used: receipt.other.get_deserialized::<U256>("gasUsedForL1")
+                .and_then(|l1_gas_used| l1_gas_used.ok())
+                .and_then(|l1_gas_used| self.u256_to_decimal(l1_gas_used, 0).ok()),
+            network_gas_price: gas_quote.network_gas_price,
+            effective_gas_price: self.u256_to_decimal(receipt.effective_gas_price.unwrap_or(U256::zero()), 0).unwrap_or_default(),
+            max_fee_per_gas_buffer: gas_quote.max_fee_per_gas_buffer,
+            estimated_gas_limit: self.u256_to_decimal(gas_limit, 0).unwrap_or_default(),
+            execution_fee: trade.execution_fee.unwrap_or_default(),
+            quote_base_fee: gas_quote.base_fee,
+            inclusion_base_fee,
+        };
+        if let Err(e) = self.db_manager.insert_gas_profile(&gas_profile).await {
+            error!(error = ?e, tx_hash = ?tx_hash, "Failed to record gas profile");
+        }
+    }
+
+    /// Tokens a deposit sends and the GMX swap paths turning them into the market's long and short tokens,
+    /// as (initial long token, long token swap path, initial short token, short token swap path)
+    fn get_deposit_swap_paths(&self, request: &GmDepositRequest) -> Result<(Address, Vec<Address>, Address, Vec<Address>)> {
+        let market_token_info = self.wallet_manager.market_token(&request.market)
+            .ok_or_else(|| eyre::eyre!("Market token not found: {}", request.market))?;
+        let (initial_long_token, initial_short_token) = request.initial_tokens(market_token_info.long_token_address, market_token_info.short_token_address);
+        let tokens = self.wallet_manager.tokens();
+        let long_token_swap_path = swap_path::find_swap_path(&tokens, initial_long_token, market_token_info.long_token_address, request.market)
+            .ok_or_else(|| eyre::eyre!("No GMX swap path from {} to long token {}", initial_long_token, market_token_info.long_token_address))?;
+        let short_token_swap_path = swap_path::find_swap_path(&tokens, initial_short_token, market_token_info.short_token_address, request.market)
+            .ok_or_else(|| eyre::eyre!("No GMX swap path from {} to short token {}", initial_short_token, market_token_info.short_token_address))?;
+        Ok((initial_long_token, long_token_swap_path, initial_short_token, short_token_swap_path))
+    }
+
+    /// Convert an amount of one token into another at their last mid prices
+    fn convert_at_mid_price(&self, amount: Decimal, from_token: Address, to_token_info: &TokenInfo) -> Result<Decimal> {
+        if from_token == to_token_info.address {
+            return Ok(amount);
+        }
+        let from_token_info = self.wallet_manager.asset_token(&from_token)
+            .ok_or_else(|| eyre::eyre!("Token not found: {}", from_token))?;
+        if to_token_info.last_mid_price_usd.is_zero() {
+            return Err(eyre::eyre!("No price for token {}", to_token_info.symbol));
+        }
+        Ok((amount * from_token_info.last_mid_price_usd / to_token_info.last_mid_price_usd).round_dp(to_token_info.decimals as u32))
+    }
+
+    /// Callback contract and gas limit GM requests are created with, zero when no callback receiver is configured
+    fn callback_settings(&self) -> (Address, U256) {
+        match self.config.gmx_callback_contract {
+            Some(callback_contract) => (callback_contract, U256::from(self.config.gmx_callback_gas_limit)),
+            None => (Address::zero(), U256::zero()),
+        }
+    }
+
+    /// UI fee receiver GM requests are created with, zero when none is configured
+    fn ui_fee_receiver(&self) -> Address {
+        self.config.gmx_ui_fee_receiver.unwrap_or(Address::zero())
     }
 
     /// Creates GM deposit params from the given request
     fn create_deposit_params(&self, request: &GmDepositRequest, execution_fee: U256) -> Result<(exchange_router_utils::CreateDepositParams, U256, U256)> {
-        let market_token_info = self.wallet_manager.market_tokens.get(&request.market)
-            .ok_or_else(|| eyre::eyre!("Market token not found: {}", request.market))?;
-        let long_token_decimals = self.wallet_manager.asset_tokens.get(&market_token_info.long_token_address)
-            .ok_or_else(|| eyre::eyre!("Long token not found: {}", market_token_info.long_token_address))?
+        let (initial_long_token, long_token_swap_path, initial_short_token, short_token_swap_path) = self.get_deposit_swap_paths(request)?;
+        let long_token_decimals = self.wallet_manager.asset_token(&initial_long_token)
+            .ok_or_else(|| eyre::eyre!("Long token not found: {}", initial_long_token))?
             .decimals;
-        let short_token_decimals = self.wallet_manager.asset_tokens.get(&market_token_info.short_token_address)
-            .ok_or_else(|| eyre::eyre!("Short token not found: {}", market_token_info.short_token_address))?
+        let short_token_decimals = self.wallet_manager.asset_token(&initial_short_token)
+            .ok_or_else(|| eyre::eyre!("Short token not found: {}", initial_short_token))?
             .decimals;
         let initial_long_amount = self.decimal_to_u256(request.long_amount, long_token_decimals)?;
         let initial_short_amount = self.decimal_to_u256(request.short_amount, short_token_decimals)?;
+        let (callback_contract, callback_gas_limit) = self.callback_settings();
 
         let deposit_params = exchange_router_utils::CreateDepositParams {
             addresses: exchange_router_utils::CreateDepositParamsAddresses {
                 receiver: self.wallet_manager.address,
-                callback_contract: Address::zero(),
-                ui_fee_receiver: Address::zero(),
+                callback_contract,
+                ui_fee_receiver: self.ui_fee_receiver(),
                 market: request.market,
-                initial_long_token: market_token_info.long_token_address,
-                initial_short_token: market_token_info.short_token_address,
-                long_token_swap_path: vec![], 
-                short_token_swap_path: vec![],
+                initial_long_token,
+                initial_short_token,
+                long_token_swap_path,
+                short_token_swap_path,
             },
             min_market_tokens: U256::zero(),
             should_unwrap_native_token: false,
             execution_fee,
-            callback_gas_limit: U256::zero(),
+            callback_gas_limit,
             data_list: vec![],
         };
         Ok((deposit_params, initial_long_amount, initial_short_amount))
@@ -494,12 +1111,13 @@ impl GmTxManager {
     /// Creates GM withdrawal params from the given request
     fn create_withdrawal_params(&self, request: &GmWithdrawalRequest, execution_fee: U256) -> Result<(exchange_router_utils::CreateWithdrawalParams, U256)> {
         let market_token_amount = self.decimal_to_u256(request.amount, 18)?; // Always 18 decimals for GM market tokens
+        let (callback_contract, callback_gas_limit) = self.callback_settings();
 
         let withdrawal_params = exchange_router_utils::CreateWithdrawalParams {
             addresses: exchange_router_utils::CreateWithdrawalParamsAddresses {
                 receiver: self.wallet_manager.address,
-                callback_contract: Address::zero(),
-                ui_fee_receiver: Address::zero(),
+                callback_contract,
+                ui_fee_receiver: self.ui_fee_receiver(),
                 market: request.market,
                 long_token_swap_path: vec![], 
                 short_token_swap_path: vec![],
@@ -508,7 +1126,7 @@ impl GmTxManager {
             min_short_token_amount: U256::zero(),
             should_unwrap_native_token: false,
             execution_fee,
-            callback_gas_limit: U256::zero(),
+            callback_gas_limit,
             data_list: vec![],
         };
         Ok((withdrawal_params, market_token_amount))
@@ -517,18 +1135,19 @@ impl GmTxManager {
     /// Creates GM shift params from the given request
     fn create_shift_params(&self, request: &GmShiftRequest, execution_fee: U256) -> Result<(exchange_router_utils::CreateShiftParams, U256)> {
         let from_market_amount = self.decimal_to_u256(request.amount, 18)?; // Always 18 decimals for GM market tokens  
+        let (callback_contract, callback_gas_limit) = self.callback_settings();
         
         let shift_params = exchange_router_utils::CreateShiftParams {
             addresses: exchange_router_utils::CreateShiftParamsAddresses {
                 receiver: self.wallet_manager.address,
-                callback_contract: Address::zero(),
-                ui_fee_receiver: Address::zero(),
+                callback_contract,
+                ui_fee_receiver: self.ui_fee_receiver(),
                 from_market: request.from_market,
                 to_market: request.to_market,
             },
             min_market_tokens: U256::zero(),
             execution_fee,
-            callback_gas_limit: U256::zero(),
+            callback_gas_limit,
             data_list: vec![],
         };
         Ok((shift_params, from_market_amount))
@@ -541,13 +1160,13 @@ impl GmTxManager {
         let _ = self.validate_deposit_request(&request).await?;
 
         // Get token infos
-        let market_token_info = self.wallet_manager.market_tokens.get(&request.market)
+        let market_token_info = self.wallet_manager.market_token(&request.market)
             .ok_or_else(|| eyre::eyre!("Market token not found: {}", request.market))?;
-        let index_token_info = self.wallet_manager.asset_tokens.get(&market_token_info.index_token_address)
+        let index_token_info = self.wallet_manager.asset_token(&market_token_info.index_token_address)
             .ok_or_else(|| eyre::eyre!("Index token not found: {}", market_token_info.index_token_address))?;
-        let long_token_info = self.wallet_manager.asset_tokens.get(&market_token_info.long_token_address)
+        let long_token_info = self.wallet_manager.asset_token(&market_token_info.long_token_address)
             .ok_or_else(|| eyre::eyre!("Long token not found: {}", market_token_info.long_token_address))?;
-        let short_token_info = self.wallet_manager.asset_tokens.get(&market_token_info.short_token_address)
+        let short_token_info = self.wallet_manager.asset_token(&market_token_info.short_token_address)
             .ok_or_else(|| eyre::eyre!("Short token not found: {}", market_token_info.short_token_address))?;
         
         let market_props = reader_utils::MarketProps {
@@ -576,10 +1195,14 @@ impl GmTxManager {
             },
         };
 
-        let long_token_amout = self.decimal_to_u256(request.long_amount, long_token_info.decimals)?;
-        let short_token_amount = self.decimal_to_u256(request.short_amount, short_token_info.decimals)?;
+        // Amounts sent through a swap path are converted at mid prices, ignoring the swaps' price impact and fees
+        let (initial_long_token, initial_short_token) = request.initial_tokens(long_token_info.address, short_token_info.address);
+        let long_amount = self.convert_at_mid_price(request.long_amount, initial_long_token, &long_token_info)?;
+        let short_amount = self.convert_at_mid_price(request.short_amount, initial_short_token, &short_token_info)?;
+        let long_token_amout = self.decimal_to_u256(long_amount, long_token_info.decimals)?;
+        let short_token_amount = self.decimal_to_u256(short_amount, short_token_info.decimals)?;
 
-        let ui_fee_receiver = Address::zero();
+        let ui_fee_receiver = self.ui_fee_receiver();
         let swap_pricing_type = reader_utils::SwapPricingType::Deposit;
         let include_virtual_inventory_impact = true;
 
@@ -607,13 +1230,13 @@ impl GmTxManager {
         let _ = self.validate_withdrawal_request(&request).await?;
 
         // Get token infos
-        let market_token_info = self.wallet_manager.market_tokens.get(&request.market)
+        let market_token_info = self.wallet_manager.market_token(&request.market)
             .ok_or_else(|| eyre::eyre!("Market token not found: {}", request.market))?;
-        let index_token_info = self.wallet_manager.asset_tokens.get(&market_token_info.index_token_address)
+        let index_token_info = self.wallet_manager.asset_token(&market_token_info.index_token_address)
             .ok_or_else(|| eyre::eyre!("Index token not found: {}", market_token_info.index_token_address))?;
-        let long_token_info = self.wallet_manager.asset_tokens.get(&market_token_info.long_token_address)
+        let long_token_info = self.wallet_manager.asset_token(&market_token_info.long_token_address)
             .ok_or_else(|| eyre::eyre!("Long token not found: {}", market_token_info.long_token_address))?;
-        let short_token_info = self.wallet_manager.asset_tokens.get(&market_token_info.short_token_address)
+        let short_token_info = self.wallet_manager.asset_token(&market_token_info.short_token_address)
             .ok_or_else(|| eyre::eyre!("Short token not found: {}", market_token_info.short_token_address))?;
         
         let market_props = reader_utils::MarketProps {
@@ -644,7 +1267,7 @@ impl GmTxManager {
 
         let market_token_amount = self.decimal_to_u256(request.amount, 18)?; // Always 18 decimals for GM market tokens
 
-        let ui_fee_receiver = Address::zero();
+        let ui_fee_receiver = self.ui_fee_receiver();
         let swap_pricing_type = reader_utils::SwapPricingType::Withdrawal;
 
         // Get token amounts out
@@ -1,2 +1,9 @@
 pub mod gm_tx_manager;
-pub mod types;
\ No newline at end of file
+pub mod order_monitor;
+pub mod types;
+pub mod gas_guard;
+pub mod plan_executor;
+pub mod plan_graph;
+pub mod gas_profile;
+pub mod swap_path;
+pub mod fee_buffer;
\ No newline at end of file
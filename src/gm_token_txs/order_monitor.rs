@@ -0,0 +1,196 @@
+use eyre::Result;
+use tracing::{debug, info, warn, error, instrument};
+use std::sync::Arc;
+use std::str::FromStr;
+use std::time::Duration;
+use ethers::providers::Middleware;
+use ethers::types::H256;
+use rust_decimal::Decimal;
+
+use crate::config::Config;
+use crate::wallet::WalletManager;
+use crate::db::db_manager::DbManager;
+use crate::db::models::trades::{TradeModel, TradeActionType, TradeStatus};
+use crate::data_ingestion::market::market_utils::u256_to_decimal_scaled_decimals;
+use crate::gmx::{
+    datastore,
+    exchange_router,
+    callback_receiver::{SettledRequests, CallbackOutcome},
+};
+
+/// Watches created GM deposits/withdrawals/shifts and cancels any that keepers have not executed
+/// within the configured timeout, so the collateral does not stay locked in the vaults.
+/// With a callback receiver configured, outcomes come from its callback events and the request lists
+/// are only read once a request reaches the timeout (in case its callback was missed while reconnecting).
+pub struct GmOrderMonitor {
+    config: Arc<Config>,
+    wallet_manager: Arc<WalletManager>,
+    db_manager: Arc<DbManager>,
+    order_timeout: chrono::Duration,
+    settled_requests: Option<SettledRequests>,
+}
+
+impl GmOrderMonitor {
+    pub fn new(config: Arc<Config>, wallet_manager: Arc<WalletManager>, db_manager: Arc<DbManager>) -> Self {
+        let order_timeout = chrono::Duration::seconds(config.gm_order_timeout_secs as i64);
+        Self {
+            config,
+            wallet_manager,
+            db_manager,
+            order_timeout,
+            settled_requests: None,
+        }
+    }
+
+    /// Detect request completion from callback events instead of polling the request lists
+    pub fn with_callbacks(mut self, settled_requests: SettledRequests) -> Self {
+        self.settled_requests = Some(settled_requests);
+        self
+    }
+
+    /// Callback outcomes the monitor reads from, None when completion is detected by polling
+    pub fn settled_requests(&self) -> Option<&SettledRequests> {
+        self.settled_requests.as_ref()
+    }
+
+    /// Poll pending orders forever at the given interval
+    pub async fn run(self: Arc<Self>, poll_interval: Duration) {
+        info!(
+            poll_interval_secs = poll_interval.as_secs(),
+            order_timeout_secs = self.order_timeout.num_seconds(),
+            "Starting GM order monitor"
+        );
+        loop {
+            if let Err(e) = self.check_pending_orders().await {
+                error!(error = ?e, "Failed to check pending GM orders");
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    /// Check every pending GM trade once: mark executed ones as settled and cancel timed out ones
+    #[instrument(skip(self), fields(on_close = true))]
+    pub async fn check_pending_orders(&self) -> Result<()> {
+        let pending_trades = self.db_manager.get_pending_trades().await?;
+        debug!(count = pending_trades.len(), "Checking pending GM orders");
+
+        for trade in pending_trades {
+            if let Err(e) = self.check_pending_order(&trade).await {
+                error!(trade_id = trade.id, error = ?e, "Failed to check pending GM order");
+            }
+        }
+        Ok(())
+    }
+
+    #[instrument(skip(self, trade), fields(trade_id = trade.id, action_type = %trade.action_type))]
+    async fn check_pending_order(&self, trade: &TradeModel) -> Result<()> {
+        let action_type = TradeActionType::parse(&trade.action_type)
+            .ok_or_else(|| eyre::eyre!("Unknown trade action type: {}", trade.action_type))?;
+        let order_key = match trade.order_key.as_deref() {
+            Some(key) => H256::from_str(key).map_err(|e| eyre::eyre!("Invalid order key {}: {}", key, e))?,
+            None => {
+                warn!("Pending trade has no order key, cannot monitor keeper execution");
+                return Ok(());
+            }
+        };
+
+        let age = self.db_manager.clock.now() - trade.created_at;
+        let callback_outcome = match &self.settled_requests {
+            Some(settled_requests) => settled_requests.take(order_key).await,
+            None => None,
+        };
+        if callback_outcome == Some(CallbackOutcome::Cancelled) {
+            self.db_manager.update_trade_status(trade.id, TradeStatus::Cancelled, None).await?;
+            warn!(order_key = ?order_key, "GM order cancelled during keeper execution, funds returned");
+            return Ok(());
+        }
+
+        let still_pending = match callback_outcome {
+            Some(_) => false,
+            // No callback yet: still pending until the timeout, when the request list decides (the callback may have been missed)
+            None if self.settled_requests.is_some() && age < self.order_timeout => true,
+            None => match action_type {
+                TradeActionType::GmDeposit => datastore::is_deposit_pending(&self.config, order_key).await?,
+                TradeActionType::GmWithdrawal => datastore::is_withdrawal_pending(&self.config, order_key).await?,
+                TradeActionType::GmShift => datastore::is_shift_pending(&self.config, order_key).await?,
+                // Claims settle in their own transaction and bridge transfers are tracked apart, no keeper involved
+                TradeActionType::ClaimRewards | TradeActionType::ClaimUiFees | TradeActionType::BridgeCollateral => false,
+            },
+        };
+
+        if !still_pending {
+            self.db_manager.update_trade_status(trade.id, TradeStatus::Settled, None).await?;
+            info!(order_key = ?order_key, "GM order executed by keeper");
+            if let Err(e) = self.record_execution_fee_refund(trade, action_type, order_key).await {
+                warn!(order_key = ?order_key, error = ?e, "Failed to record execution fee refund");
+            }
+            return Ok(());
+        }
+
+        if age < self.order_timeout {
+            debug!(order_key = ?order_key, age_secs = age.num_seconds(), "GM order still pending");
+            return Ok(());
+        }
+
+        warn!(
+            order_key = ?order_key,
+            age_secs = age.num_seconds(),
+            timeout_secs = self.order_timeout.num_seconds(),
+            "GM order not executed within timeout, cancelling"
+        );
+        let (cancel_tx_hash, _receipt) = match action_type {
+            TradeActionType::GmDeposit => exchange_router::cancel_deposit(&self.config, &self.wallet_manager, order_key).await?,
+            TradeActionType::GmWithdrawal => exchange_router::cancel_withdrawal(&self.config, &self.wallet_manager, order_key).await?,
+            TradeActionType::GmShift => exchange_router::cancel_shift(&self.config, &self.wallet_manager, order_key).await?,
+            TradeActionType::ClaimRewards | TradeActionType::ClaimUiFees | TradeActionType::BridgeCollateral => unreachable!("Claims and bridge transfers are never pending GM orders"),
+        };
+        self.db_manager.update_trade_status(trade.id, TradeStatus::Cancelled, Some(format!("{:?}", cancel_tx_hash))).await?;
+        info!(order_key = ?order_key, cancel_tx_hash = ?cancel_tx_hash, "GM order cancelled, funds returned");
+
+        Ok(())
+    }
+
+    /// Look up the keeper execution of a settled request and reconcile its execution fee refund: stored in the gas
+    /// profile and on the trade, and netted out of the request's execution cost
+    async fn record_execution_fee_refund(&self, trade: &TradeModel, action_type: TradeActionType, order_key: H256) -> Result<()> {
+        let executed_event_name = match action_type {
+            TradeActionType::GmDeposit => "DepositExecuted",
+            TradeActionType::GmWithdrawal => "WithdrawalExecuted",
+            TradeActionType::GmShift => "ShiftExecuted",
+            TradeActionType::ClaimRewards | TradeActionType::ClaimUiFees | TradeActionType::BridgeCollateral => return Ok(()),
+        };
+        let Some(tx_hash) = trade.tx_hash.as_deref() else {
+            return Ok(());
+        };
+        let created_tx_hash = H256::from_str(tx_hash).map_err(|e| eyre::eyre!("Invalid tx hash {}: {}", tx_hash, e))?;
+        let from_block = self.config.alchemy_provider.get_transaction_receipt(created_tx_hash).await?
+            .and_then(|receipt| receipt.block_number)
+            .ok_or_else(|| eyre::eyre!("Request transaction {} has no mined receipt", tx_hash))?;
+
+        let Some(refund) = exchange_router::get_execution_fee_refund(
+            &self.config,
+            executed_event_name,
+            order_key,
+            self.wallet_manager.address,
+            from_block,
+        ).await? else {
+            debug!(order_key = ?order_key, "Keeper execution not found, execution fee refund not recorded");
+            return Ok(());
+        };
+
+        let refund_amount = u256_to_decimal_scaled_decimals(refund.refund_amount, 18);
+        self.db_manager.update_gas_profile_refund(
+            tx_hash,
+            &format!("{:?}", refund.keeper_tx_hash),
+            u256_to_decimal_scaled_decimals(refund.keeper_gas_price, 0),
+            refund_amount,
+        ).await?;
+        self.db_manager.update_trade_execution_fee_refund(trade.id, refund_amount).await?;
+        if let Some(execution_fee) = trade.execution_fee.filter(|fee| *fee > Decimal::ZERO) {
+            let refunded_fraction = (refund_amount / execution_fee).min(Decimal::ONE);
+            self.db_manager.apply_execution_fee_refund(tx_hash, refunded_fraction).await?;
+        }
+        debug!(keeper_tx_hash = ?refund.keeper_tx_hash, refund_amount = %refund_amount, "Execution fee refund recorded");
+        Ok(())
+    }
+}
@@ -0,0 +1,481 @@
+use eyre::Result;
+use tracing::{debug, info, info_span, warn, error, instrument, Instrument};
+use std::sync::Arc;
+use ethers::prelude::*;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::*;
+use futures::future::join_all;
+use futures::stream::{self, StreamExt};
+
+use crate::config::{Config, dynamic::DynamicConfig};
+use crate::wallet::WalletManager;
+use crate::db::db_manager::DbManager;
+use crate::telemetry;
+use crate::db::models::execution_plans::{self, ExecutionPlanActionModel, NewExecutionPlanActionModel, ExecutionStatus, PlanCreation};
+use crate::db::models::trades::TradeStatus;
+use crate::gmx::callback_receiver::SettledRequests;
+use super::gm_tx_manager::GmTxManager;
+use super::order_monitor::GmOrderMonitor;
+use super::plan_graph::PlanGraph;
+use super::types::{GmTxRequest, GmAmountOutResponse};
+
+/// Fraction of an action's amount the spent balance must have dropped by for an interrupted submission to count as landed
+const SUBMITTED_BALANCE_DROP_FRACTION: f64 = 0.99;
+/// Interval at which a dependency's GM request is checked for keeper execution
+const SETTLEMENT_POLL_INTERVAL_SECS: u64 = 10;
+/// Extra time past the order timeout to wait for the order monitor to cancel a stuck request
+const SETTLEMENT_TIMEOUT_MARGIN_SECS: u64 = 60;
+/// Source of the plans rolling back actions stranded by a failed dependent, these are never compensated themselves
+pub const COMPENSATION_PLAN_SOURCE: &str = "compensation";
+
+/// Outcome of checking an action left in the submitted state by an interrupted run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SubmissionCheck {
+    Landed,      // Trade recorded or spent balance moved, don't send again
+    InFlight,    // Wallet has unmined transactions, outcome not known yet
+    NotLanded,   // Nothing happened on-chain, safe to resubmit
+}
+
+/// Executes GM requests as persisted plans: every action's status is written before and after it is sent,
+/// so a plan interrupted by a crash resumes from its unfinished actions without double-submitting.
+/// Actions run in the order of the plan's dependency graph rather than strictly one after another, up to
+/// PLAN_MAX_CONCURRENT_ACTIONS independent actions at a time. When an action
+/// fails after the actions it depends on executed, those actions are rolled back by a compensation plan (or left for
+/// the next strategy run to replan from, per PLAN_FAILURE_POLICY) rather than leaving the portfolio half rebalanced.
+pub struct GmPlanExecutor {
+    config: Arc<Config>,
+    wallet_manager: Arc<WalletManager>,
+    db_manager: Arc<DbManager>,
+    dynamic_config: Arc<DynamicConfig>,
+    gm_tx_manager: GmTxManager,
+    order_monitor: GmOrderMonitor,
+}
+
+impl GmPlanExecutor {
+    pub fn new(config: Arc<Config>, wallet_manager: Arc<WalletManager>, db_manager: Arc<DbManager>, dynamic_config: Arc<DynamicConfig>) -> Self {
+        let gm_tx_manager = GmTxManager::new(config.clone(), wallet_manager.clone(), db_manager.clone());
+        let order_monitor = GmOrderMonitor::new(config.clone(), wallet_manager.clone(), db_manager.clone());
+        Self {
+            config,
+            wallet_manager,
+            db_manager,
+            dynamic_config,
+            gm_tx_manager,
+            order_monitor,
+        }
+    }
+
+    /// Wait for dependencies on callback events from the callback receiver instead of polling the request lists
+    pub fn with_callbacks(mut self, settled_requests: SettledRequests) -> Self {
+        self.order_monitor = self.order_monitor.with_callbacks(settled_requests);
+        self
+    }
+
+    /// Persist the requests as a new plan of the rebalance in execution order, returning the plan ID.
+    /// The plan records the current trace context so a run resuming it can link back to the run that planned it.
+    /// A plan identical to one created within PLAN_DUPLICATE_WINDOW_SECS is refused, so a misfiring scheduler or a
+    /// second instance can't execute the same moves twice. Compensation plans are exempt, they undo what just ran.
+    #[instrument(skip(self, requests), fields(request_count = requests.len()))]
+    pub async fn create_plan(&self, source: &str, requests: &[GmTxRequest], rebalance_id: Option<i32>) -> Result<i32> {
+        let actions = requests.iter()
+            .map(|request| NewExecutionPlanActionModel::from_request(request, &self.db_manager.market_id_map)
+                .ok_or_else(|| eyre::eyre!("Request cannot be persisted in an execution plan: {:?}", request)))
+            .collect::<Result<Vec<_>>>()?;
+        let plan_hash = execution_plans::plan_hash(source, &actions);
+        let duplicate_since = (source != COMPENSATION_PLAN_SOURCE && self.config.plan_duplicate_window_secs > 0)
+            .then(|| self.db_manager.clock.now() - chrono::Duration::seconds(self.config.plan_duplicate_window_secs as i64));
+        let traceparent = telemetry::traceparent(&tracing::Span::current());
+        match self.db_manager.create_execution_plan(source, traceparent.as_deref(), &plan_hash, duplicate_since, rebalance_id, &actions).await? {
+            PlanCreation::Created(plan_id) => {
+                debug!(plan_id = plan_id, plan_hash = %plan_hash, "Execution plan hashed");
+                Ok(plan_id)
+            }
+            PlanCreation::Duplicate { plan_id, created_at } => Err(eyre::eyre!(
+                "Identical plan {} (hash {}) was already created at {}, refusing to execute it again",
+                plan_id, plan_hash, created_at
+            )),
+        }
+    }
+
+    /// Persist the requests as a plan of the given rebalance and execute it, returning the number of actions confirmed.
+    /// Without a rebalance the plan is a rebalance of its own, so the costs of its legs are reported together.
+    pub async fn execute_requests(&self, source: &str, requests: &[GmTxRequest], rebalance_id: Option<i32>) -> Result<usize> {
+        let (rebalance_id, own_rebalance) = match rebalance_id {
+            Some(rebalance_id) => (rebalance_id, false),
+            None => (self.db_manager.begin_rebalance(source).await?, true),
+        };
+        let result = match self.create_plan(source, requests, Some(rebalance_id)).await {
+            Ok(plan_id) => self.execute_plan(plan_id, Some(rebalance_id)).await,
+            Err(e) => Err(e),
+        };
+        if own_rebalance {
+            self.db_manager.complete_rebalance(rebalance_id).await?;
+        }
+        result
+    }
+
+    /// Resume every plan an earlier run left unfinished, oldest first.
+    /// Returns the number of plans resumed.
+    #[instrument(skip(self), fields(on_close = true))]
+    pub async fn resume_incomplete_plans(&self) -> Result<usize> {
+        let plans = self.db_manager.get_incomplete_execution_plans().await?;
+        for plan in &plans {
+            warn!(plan_id = plan.id, source = %plan.source, created_at = %plan.created_at, "Resuming interrupted execution plan");
+            let resume_span = info_span!("resume_execution_plan", plan_id = plan.id, source = %plan.source);
+            telemetry::add_links(&resume_span, plan.traceparent.as_deref());
+            self.run_plan(plan.id, plan.rebalance_id, plan.source != COMPENSATION_PLAN_SOURCE)
+                .instrument(resume_span)
+                .await?;
+        }
+        Ok(plans.len())
+    }
+
+    /// Run the plan's actions in dependency order, skipping those already confirmed or failed. Actions whose
+    /// dependencies are done run in parallel, and an action waits for keepers to execute the requests it depends on.
+    /// Failures propagate to dependent actions only, independent branches carry on.
+    /// Stops early, leaving the plan incomplete, when an earlier submission is still in flight.
+    /// Legs are recorded against the given rebalance. Returns the number of actions confirmed in this call.
+    pub async fn execute_plan(&self, plan_id: i32, rebalance_id: Option<i32>) -> Result<usize> {
+        self.run_plan(plan_id, rebalance_id, true).await
+    }
+
+    // ==================== Helper/Private methods ====================
+
+    /// Execute the plan, handling actions stranded by a failed dependent when `handle_stranded` is set
+    #[instrument(skip(self), fields(on_close = true))]
+    async fn run_plan(&self, plan_id: i32, rebalance_id: Option<i32>, handle_stranded: bool) -> Result<usize> {
+        let actions = self.db_manager.get_execution_plan_actions(plan_id).await?;
+        let requests = actions.iter()
+            .map(|action| action.to_request(&self.db_manager.market_id_map)
+                .ok_or_else(|| eyre::eyre!("Execution plan action {} cannot be rebuilt into a request", action.id)))
+            .collect::<Result<Vec<_>>>()?;
+        let mut statuses = actions.iter()
+            .map(|action| ExecutionStatus::parse(&action.status)
+                .ok_or_else(|| eyre::eyre!("Unknown execution status: {}", action.status)))
+            .collect::<Result<Vec<_>>>()?;
+        let graph = PlanGraph::build(&requests, &self.wallet_manager);
+        debug!(plan_id = plan_id, action_count = actions.len(), wave_count = graph.waves().len(), "Execution plan dependency graph built");
+
+        let mut settled: Vec<Option<bool>> = vec![None; actions.len()];
+        let mut confirmed = 0;
+        loop {
+            // Fail actions whose dependency failed or was never executed by a keeper (dependencies always come earlier)
+            for i in 0..actions.len() {
+                if statuses[i].is_terminal() {
+                    continue;
+                }
+                let failed_dependency = graph.dependencies(i).iter()
+                    .find(|&&d| statuses[d] == ExecutionStatus::Failed || settled[d] == Some(false));
+                if let Some(&d) = failed_dependency {
+                    let reason = format!("Dependency action {} did not complete", actions[d].id);
+                    warn!(action_id = actions[i].id, dependency_id = actions[d].id, "Skipping action, dependency did not complete");
+                    self.db_manager.update_execution_action_status(actions[i].id, ExecutionStatus::Failed, Some(reason)).await?;
+                    statuses[i] = ExecutionStatus::Failed;
+                }
+            }
+
+            let ready: Vec<usize> = (0..actions.len())
+                .filter(|&i| !statuses[i].is_terminal())
+                .filter(|&i| graph.dependencies(i).iter().all(|&d| statuses[d] == ExecutionStatus::Confirmed))
+                .collect();
+            if ready.is_empty() {
+                break;
+            }
+
+            // The tokens a dependency produces only arrive once a keeper executes it
+            let mut unsettled: Vec<usize> = ready.iter()
+                .flat_map(|&i| graph.dependencies(i).iter().copied())
+                .filter(|&d| settled[d].is_none())
+                .collect();
+            unsettled.sort_unstable();
+            unsettled.dedup();
+            if !unsettled.is_empty() {
+                // Keepers execute independent requests in parallel, so their settlements are awaited together
+                let results = join_all(unsettled.iter().map(|&d| self.wait_for_settlement(plan_id, actions[d].id))).await;
+                for (d, result) in unsettled.into_iter().zip(results) {
+                    settled[d] = Some(result?);
+                }
+                continue;
+            }
+
+            // Safe mode can be switched on while a plan runs, stop before sending the next wave
+            if self.dynamic_config.safe_mode().await {
+                warn!(plan_id = plan_id, "Safe mode enabled, leaving plan to resume later");
+                return Ok(confirmed);
+            }
+
+            // A submitted action may have been sent before the crash, verify on-chain before sending again
+            let mut to_execute = Vec::with_capacity(ready.len());
+            for i in ready {
+                let action = &actions[i];
+                if statuses[i] != ExecutionStatus::Submitted {
+                    to_execute.push(i);
+                    continue;
+                }
+                match self.check_submission(action, &requests[i]).await? {
+                    SubmissionCheck::Landed => {
+                        info!(action_id = action.id, action_type = %action.action_type, "Interrupted action already landed, not resubmitting");
+                        self.db_manager.update_execution_action_status(action.id, ExecutionStatus::Confirmed, None).await?;
+                        statuses[i] = ExecutionStatus::Confirmed;
+                        confirmed += 1;
+                    }
+                    SubmissionCheck::InFlight => {
+                        warn!(action_id = action.id, action_type = %action.action_type, "Earlier submission still in flight, leaving plan to resume later");
+                        return Ok(confirmed);
+                    }
+                    SubmissionCheck::NotLanded => {
+                        info!(action_id = action.id, action_type = %action.action_type, "Interrupted action never landed, resubmitting");
+                        to_execute.push(i);
+                    }
+                }
+            }
+
+            // Independent actions spend different tokens, so the wave is executed concurrently up to the configured bound;
+            // their transactions still go out one at a time through the submission lock, keeping wallet nonces in order
+            let results: Vec<Result<ExecutionStatus>> = stream::iter(to_execute.iter().map(|&i| self.execute_action(&actions[i], &requests[i], rebalance_id)))
+                .buffered(self.config.plan_max_concurrent_actions)
+                .collect()
+                .await;
+            for (i, result) in to_execute.into_iter().zip(results) {
+                statuses[i] = result?;
+                if statuses[i] == ExecutionStatus::Confirmed {
+                    confirmed += 1;
+                }
+            }
+        }
+
+        // An executed action whose output a failed action was meant to spend leaves the portfolio in an intermediate state
+        let stranded: Vec<usize> = (0..actions.len())
+            .filter(|&i| statuses[i] == ExecutionStatus::Confirmed && settled[i] == Some(true))
+            .filter(|&i| {
+                let consumers = graph.consumers(i);
+                consumers.iter().any(|&j| statuses[j] == ExecutionStatus::Failed)
+                    && !consumers.iter().any(|&j| statuses[j] == ExecutionStatus::Confirmed)
+            })
+            .collect();
+        let compensation_plan_id = if handle_stranded && !stranded.is_empty() {
+            self.plan_compensation(plan_id, rebalance_id, &actions, &requests, &stranded).await?
+        } else {
+            None
+        };
+
+        self.db_manager.complete_execution_plan(plan_id).await?;
+        info!(plan_id = plan_id, confirmed = confirmed, action_count = actions.len(), "Execution plan finished");
+
+        if let Some(compensation_plan_id) = compensation_plan_id {
+            // Boxed since the compensation plan runs through this same method, its legs belong to the same rebalance
+            let compensated = Box::pin(self.run_plan(compensation_plan_id, rebalance_id, false)).await?;
+            info!(plan_id = plan_id, compensation_plan_id = compensation_plan_id, compensated = compensated, "Compensation plan finished");
+        }
+        Ok(confirmed)
+    }
+
+    /// Persist a plan rolling back the stranded actions that declare a compensating request, marking them compensated.
+    /// Under the replan policy (or for actions without a compensating request) the intermediate holdings are left for
+    /// the next strategy run, which plans from current holdings. Returns the compensation plan ID, if one was created.
+    #[instrument(skip(self, actions, requests), fields(stranded_count = stranded.len()))]
+    async fn plan_compensation(
+        &self,
+        plan_id: i32,
+        rebalance_id: Option<i32>,
+        actions: &[ExecutionPlanActionModel],
+        requests: &[GmTxRequest],
+        stranded: &[usize],
+    ) -> Result<Option<i32>> {
+        if self.config.plan_failure_policy != "compensate" {
+            for &i in stranded {
+                warn!(action_id = actions[i].id, action_type = %actions[i].action_type, "Dependent action failed, leaving executed action for the next strategy run to replan");
+            }
+            return Ok(None);
+        }
+
+        let mut compensated = Vec::with_capacity(stranded.len());
+        let mut compensating_requests = Vec::with_capacity(stranded.len());
+        for &i in stranded {
+            let compensating_request = match &requests[i] {
+                GmTxRequest::Deposit(_) | GmTxRequest::Withdrawal(_) => {
+                    let amount_out = self.get_realized_amount_out(&requests[i]).await?;
+                    requests[i].compensating_request(&amount_out)
+                        .filter(|request| spent_amount(request) > Decimal::ZERO)
+                }
+                GmTxRequest::Shift(_) | GmTxRequest::ClaimRewards(_) | GmTxRequest::ClaimUiFees(_) => None,
+            };
+            match compensating_request {
+                Some(request) => {
+                    debug!(action_id = actions[i].id, compensating_request = ?request, "Compensating request planned");
+                    compensated.push(i);
+                    compensating_requests.push(request);
+                }
+                None => warn!(action_id = actions[i].id, action_type = %actions[i].action_type, "Dependent action failed and executed action has no compensating request, leaving it to replan"),
+            }
+        }
+        if compensating_requests.is_empty() {
+            return Ok(None);
+        }
+
+        let compensation_plan_id = self.create_plan(COMPENSATION_PLAN_SOURCE, &compensating_requests, rebalance_id).await?;
+        for i in compensated {
+            let reason = format!("Rolled back by compensation plan {}", compensation_plan_id);
+            self.db_manager.update_execution_action_status(actions[i].id, ExecutionStatus::Compensated, Some(reason)).await?;
+        }
+        warn!(
+            plan_id = plan_id,
+            compensation_plan_id = compensation_plan_id,
+            compensating_count = compensating_requests.len(),
+            "Dependent actions failed, rolling back executed actions"
+        );
+        Ok(Some(compensation_plan_id))
+    }
+
+    /// Amounts an executed deposit or withdrawal delivered, estimated by quoting the request again
+    /// and capped by the wallet's balances of the tokens it produced
+    async fn get_realized_amount_out(&self, request: &GmTxRequest) -> Result<GmAmountOutResponse> {
+        let estimate = self.gm_tx_manager.get_transaction_amount_out(request).await?;
+        match (request, estimate) {
+            (GmTxRequest::Deposit(deposit), GmAmountOutResponse::Deposit { amount_out }) => {
+                let market_token_balance = self.wallet_manager.get_token_balance(deposit.market).await?;
+                Ok(GmAmountOutResponse::Deposit { amount_out: amount_out.min(market_token_balance) })
+            }
+            (GmTxRequest::Withdrawal(withdrawal), GmAmountOutResponse::Withdrawal { long_amount_out, short_amount_out }) => {
+                let market_token = self.wallet_manager.market_token(&withdrawal.market)
+                    .ok_or_else(|| eyre::eyre!("Market token not found: {}", withdrawal.market))?;
+                let long_token_balance = self.wallet_manager.get_token_balance(market_token.long_token_address).await?;
+                let short_token_balance = self.wallet_manager.get_token_balance(market_token.short_token_address).await?;
+                Ok(GmAmountOutResponse::Withdrawal {
+                    long_amount_out: long_amount_out.min(long_token_balance),
+                    short_amount_out: short_amount_out.min(short_token_balance),
+                })
+            }
+            (_, estimate) => Ok(estimate),
+        }
+    }
+
+    /// Submit one action, recording its status before and after, returning the final status
+    #[instrument(skip(self, action, request), fields(action_id = action.id))]
+    async fn execute_action(&self, action: &ExecutionPlanActionModel, request: &GmTxRequest, rebalance_id: Option<i32>) -> Result<ExecutionStatus> {
+        let spent_balance = self.get_spent_balance(request).await?;
+        self.db_manager.mark_execution_action_submitted(action.id, spent_balance).await?;
+        match self.gm_tx_manager.execute_transaction(request, rebalance_id).await {
+            Ok(()) => {
+                self.db_manager.update_execution_action_status(action.id, ExecutionStatus::Confirmed, None).await?;
+                Ok(ExecutionStatus::Confirmed)
+            }
+            Err(e) => {
+                error!(error = ?e, action_id = action.id, action_type = %action.action_type, "Execution plan action failed");
+                self.db_manager.update_execution_action_status(action.id, ExecutionStatus::Failed, Some(e.to_string())).await?;
+                Ok(ExecutionStatus::Failed)
+            }
+        }
+    }
+
+    /// Wait until keepers have executed the GM request a confirmed action created, polling pending orders
+    /// (which also cancels timed out ones), or checking them as callbacks arrive when a callback receiver is configured. Returns false if the request was cancelled, failed or cannot be found.
+    #[instrument(skip(self), fields(on_close = true))]
+    async fn wait_for_settlement(&self, plan_id: i32, action_id: i32) -> Result<bool> {
+        let action = self.db_manager.get_execution_plan_actions(plan_id).await?
+            .into_iter()
+            .find(|action| action.id == action_id)
+            .ok_or_else(|| eyre::eyre!("Execution plan action {} not found", action_id))?;
+        let Some(submitted_at) = action.submitted_at else {
+            warn!(action_id = action_id, "Action was never submitted, cannot wait for its execution");
+            return Ok(false);
+        };
+
+        // The order monitor cancels requests after the order timeout, so the wait is bounded by it
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(self.config.gm_order_timeout_secs + SETTLEMENT_TIMEOUT_MARGIN_SECS);
+        loop {
+            let Some(trade) = self.db_manager.get_latest_trade_since(&action.action_type, action.market_id, submitted_at).await? else {
+                warn!(action_id = action_id, "No trade recorded for confirmed action, cannot verify keeper execution");
+                return Ok(false);
+            };
+            match TradeStatus::parse(&trade.status) {
+                Some(TradeStatus::Settled) => {
+                    debug!(action_id = action_id, trade_id = trade.id, "Dependency executed by keeper");
+                    return Ok(true);
+                }
+                Some(TradeStatus::Pending) if std::time::Instant::now() < deadline => {
+                    debug!(action_id = action_id, trade_id = trade.id, "Waiting for keeper to execute dependency");
+                    let poll_interval = std::time::Duration::from_secs(SETTLEMENT_POLL_INTERVAL_SECS);
+                    match self.order_monitor.settled_requests() {
+                        Some(settled_requests) => settled_requests.wait(poll_interval).await,
+                        None => tokio::time::sleep(poll_interval).await,
+                    }
+                    self.order_monitor.check_pending_orders().await?;
+                }
+                _ => {
+                    warn!(action_id = action_id, trade_id = trade.id, status = %trade.status, "Dependency was not executed by a keeper");
+                    return Ok(false);
+                }
+            }
+        }
+    }
+
+    /// Decide whether an action left submitted by an interrupted run reached the chain,
+    /// from the trades table, the wallet's unmined transactions and the spent token balance
+    #[instrument(skip(self, action, request), fields(action_id = action.id))]
+    async fn check_submission(&self, action: &ExecutionPlanActionModel, request: &GmTxRequest) -> Result<SubmissionCheck> {
+        let Some(submitted_at) = action.submitted_at else {
+            return Ok(SubmissionCheck::NotLanded);
+        };
+
+        // Trades are recorded (as pending GM orders) once their creation transaction is mined
+        if let Some(trade) = self.db_manager.get_latest_trade_since(&action.action_type, action.market_id, submitted_at).await? {
+            debug!(trade_id = trade.id, order_key = ?trade.order_key, "Found trade recorded after submission");
+            return Ok(SubmissionCheck::Landed);
+        }
+
+        // A pending nonce ahead of the mined nonce means a transaction is still waiting in the mempool
+        let mined_nonce = self.config.alchemy_provider.get_transaction_count(self.wallet_manager.address, Some(BlockNumber::Latest.into())).await?;
+        let pending_nonce = self.config.alchemy_provider.get_transaction_count(self.wallet_manager.address, Some(BlockNumber::Pending.into())).await?;
+        if pending_nonce > mined_nonce {
+            debug!(mined_nonce = %mined_nonce, pending_nonce = %pending_nonce, "Wallet has unmined transactions");
+            return Ok(SubmissionCheck::InFlight);
+        }
+
+        // The request moves the spent tokens into the GMX vault when it is created, so a landed request shows up as a balance drop
+        let Some(spent_balance_before) = action.spent_balance_before else {
+            return Ok(SubmissionCheck::NotLanded);
+        };
+        let spent_balance = self.get_spent_balance(request).await?;
+        let required_drop = spent_amount(request) * Decimal::from_f64(SUBMITTED_BALANCE_DROP_FRACTION).unwrap();
+        debug!(
+            spent_balance_before = %spent_balance_before,
+            spent_balance = %spent_balance,
+            required_drop = %required_drop,
+            "Compared spent balance against pre-submission balance"
+        );
+        if spent_balance_before - spent_balance >= required_drop {
+            Ok(SubmissionCheck::Landed)
+        } else {
+            Ok(SubmissionCheck::NotLanded)
+        }
+    }
+
+    /// Current balance of the token the request spends
+    async fn get_spent_balance(&self, request: &GmTxRequest) -> Result<Decimal> {
+        let token_address = match request {
+            GmTxRequest::Deposit(deposit) => {
+                let market_token = self.wallet_manager.market_token(&deposit.market)
+                    .ok_or_else(|| eyre::eyre!("Market token not found: {}", deposit.market))?;
+                let (initial_long_token, initial_short_token) = deposit.initial_tokens(market_token.long_token_address, market_token.short_token_address);
+                if deposit.long_amount > Decimal::ZERO { initial_long_token } else { initial_short_token }
+            }
+            GmTxRequest::Withdrawal(withdrawal) => withdrawal.market,
+            GmTxRequest::Shift(shift) => shift.from_market,
+            GmTxRequest::ClaimRewards(_) | GmTxRequest::ClaimUiFees(_) => return Err(eyre::eyre!("Claims are not executed through plans")),
+        };
+        self.wallet_manager.get_token_balance(token_address).await
+    }
+}
+
+/// Amount of the spent token the request moves out of the wallet
+fn spent_amount(request: &GmTxRequest) -> Decimal {
+    match request {
+        GmTxRequest::Deposit(deposit) => {
+            if deposit.long_amount > Decimal::ZERO { deposit.long_amount } else { deposit.short_amount }
+        }
+        GmTxRequest::Withdrawal(withdrawal) => withdrawal.amount,
+        GmTxRequest::Shift(shift) => shift.amount,
+        GmTxRequest::ClaimRewards(_) | GmTxRequest::ClaimUiFees(_) => Decimal::ZERO,
+    }
+}
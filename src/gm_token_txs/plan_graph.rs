@@ -0,0 +1,113 @@
+use ethers::types::Address;
+use rust_decimal::Decimal;
+
+use crate::wallet::WalletManager;
+use super::types::GmTxRequest;
+
+/// Dependencies between the actions of an execution plan, derived from the tokens each action spends and produces.
+/// An action depends on every earlier action that produces a token it spends (a withdrawal's long/short tokens
+/// funding a deposit, a deposit's GM tokens being shifted) and on every earlier action spending the same token,
+/// so competing spends never run concurrently. Edges only point to earlier actions, so the graph is always acyclic
+/// and list order is a valid topological order.
+#[derive(Debug, Clone)]
+pub struct PlanGraph {
+    dependencies: Vec<Vec<usize>>,
+    consumers: Vec<Vec<usize>>,
+}
+
+impl PlanGraph {
+    /// Build the graph for the requests in plan order
+    pub fn build(requests: &[GmTxRequest], wallet_manager: &WalletManager) -> Self {
+        let flows: Vec<(Vec<Address>, Vec<Address>)> = requests.iter()
+            .map(|request| (spent_tokens(request, wallet_manager), produced_tokens(request, wallet_manager)))
+            .collect();
+
+        let dependencies = (0..requests.len())
+            .map(|j| {
+                let (spent_j, _) = &flows[j];
+                (0..j)
+                    .filter(|&i| {
+                        let (spent_i, produced_i) = &flows[i];
+                        spent_j.iter().any(|token| produced_i.contains(token) || spent_i.contains(token))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let consumers = (0..requests.len())
+            .map(|i| {
+                let (_, produced_i) = &flows[i];
+                (i + 1..requests.len())
+                    .filter(|&j| flows[j].0.iter().any(|token| produced_i.contains(token)))
+                    .collect()
+            })
+            .collect();
+
+        Self { dependencies, consumers }
+    }
+
+    /// Indices of the actions this action must wait for
+    pub fn dependencies(&self, index: usize) -> &[usize] {
+        &self.dependencies[index]
+    }
+
+    /// Indices of the later actions spending a token this action produces
+    pub fn consumers(&self, index: usize) -> &[usize] {
+        &self.consumers[index]
+    }
+
+    /// Group actions into waves: every action's dependencies are in earlier waves, so each wave can run in parallel
+    pub fn waves(&self) -> Vec<Vec<usize>> {
+        let mut levels: Vec<usize> = Vec::with_capacity(self.dependencies.len());
+        for dependencies in &self.dependencies {
+            let level = dependencies.iter().map(|&i| levels[i] + 1).max().unwrap_or(0);
+            levels.push(level);
+        }
+
+        let wave_count = levels.iter().max().map_or(0, |max| max + 1);
+        let mut waves = vec![Vec::new(); wave_count];
+        for (index, level) in levels.into_iter().enumerate() {
+            waves[level].push(index);
+        }
+        waves
+    }
+}
+
+/// Tokens the request moves out of the wallet
+fn spent_tokens(request: &GmTxRequest, wallet_manager: &WalletManager) -> Vec<Address> {
+    match request {
+        GmTxRequest::Deposit(deposit) => {
+            let Some(market_token) = wallet_manager.market_token(&deposit.market) else {
+                return Vec::new();
+            };
+            let (initial_long_token, initial_short_token) = deposit.initial_tokens(market_token.long_token_address, market_token.short_token_address);
+            let mut tokens = Vec::new();
+            if deposit.long_amount > Decimal::ZERO {
+                tokens.push(initial_long_token);
+            }
+            if deposit.short_amount > Decimal::ZERO {
+                tokens.push(initial_short_token);
+            }
+            tokens
+        }
+        GmTxRequest::Withdrawal(withdrawal) => vec![withdrawal.market],
+        GmTxRequest::Shift(shift) => vec![shift.from_market],
+        GmTxRequest::ClaimRewards(_) | GmTxRequest::ClaimUiFees(_) => Vec::new(),
+    }
+}
+
+/// Tokens the request delivers to the wallet once executed
+fn produced_tokens(request: &GmTxRequest, wallet_manager: &WalletManager) -> Vec<Address> {
+    match request {
+        GmTxRequest::Deposit(deposit) => vec![deposit.market],
+        GmTxRequest::Withdrawal(withdrawal) => wallet_manager.market_token(&withdrawal.market)
+            .map(|market_token| vec![market_token.long_token_address, market_token.short_token_address])
+            .unwrap_or_default(),
+        GmTxRequest::Shift(shift) => vec![shift.to_market],
+        GmTxRequest::ClaimRewards(claim) => claim.tokens.clone(),
+        GmTxRequest::ClaimUiFees(claim) => claim.markets.iter()
+            .filter_map(|market| wallet_manager.market_token(market))
+            .flat_map(|market_token| [market_token.long_token_address, market_token.short_token_address])
+            .collect(),
+    }
+}
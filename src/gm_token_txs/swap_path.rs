@@ -0,0 +1,58 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use ethers::types::Address;
+
+use crate::wallet::WalletTokens;
+
+/// Most GMX markets a deposit's token is swapped through, each hop adds a single swap gas limit and an oracle price
+pub const MAX_SWAP_PATH_HOPS: usize = 2;
+
+/// Shortest path of GMX markets swapping `from` into `to`. Each market swaps between its long and short tokens;
+/// single-token pools and the excluded market (the one being deposited into, so the swap doesn't skew the pool the
+/// deposit is priced against) are skipped. Empty when the tokens are the same, None when no path exists within
+/// MAX_SWAP_PATH_HOPS.
+pub fn find_swap_path(tokens: &WalletTokens, from: Address, to: Address, exclude_market: Address) -> Option<Vec<Address>> {
+    if from == to {
+        return Some(Vec::new());
+    }
+
+    // Sorted so equally short paths resolve to the same markets every time
+    let mut markets: Vec<_> = tokens.market_tokens.values()
+        .filter(|market| market.address != exclude_market && market.long_token_address != market.short_token_address)
+        .collect();
+    markets.sort_by_key(|market| market.address);
+
+    let mut previous: HashMap<Address, (Address, Address)> = HashMap::new(); // Token -> (token it was swapped from, market)
+    let mut visited = HashSet::from([from]);
+    let mut queue = VecDeque::from([(from, 0)]);
+    while let Some((token, hops)) = queue.pop_front() {
+        if hops == MAX_SWAP_PATH_HOPS {
+            continue;
+        }
+        for market in &markets {
+            let next = if market.long_token_address == token {
+                market.short_token_address
+            } else if market.short_token_address == token {
+                market.long_token_address
+            } else {
+                continue;
+            };
+            if !visited.insert(next) {
+                continue;
+            }
+            previous.insert(next, (token, market.address));
+            if next == to {
+                let mut path = Vec::new();
+                let mut current = to;
+                while current != from {
+                    let (swapped_from, market) = previous[&current];
+                    path.push(market);
+                    current = swapped_from;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            queue.push_back((next, hops + 1));
+        }
+    }
+    None
+}
@@ -6,6 +6,30 @@ pub enum GmTxRequest {
     Deposit(GmDepositRequest),
     Withdrawal(GmWithdrawalRequest),
     Shift(GmShiftRequest),
+    ClaimRewards(GmClaimRewardsRequest),
+    ClaimUiFees(GmClaimUiFeesRequest),
+}
+
+impl GmTxRequest {
+    /// Request undoing this one once keepers have executed it, given the amounts it delivered to the wallet.
+    /// Deposits are undone by withdrawing the GM tokens minted and withdrawals by depositing the tokens returned.
+    /// Shifts (no amount out estimate) and reward and UI fee claims (nothing to undo) have no compensating request.
+    pub fn compensating_request(&self, amount_out: &GmAmountOutResponse) -> Option<GmTxRequest> {
+        match (self, amount_out) {
+            (GmTxRequest::Deposit(deposit), GmAmountOutResponse::Deposit { amount_out }) => Some(GmTxRequest::Withdrawal(GmWithdrawalRequest {
+                market: deposit.market,
+                amount: *amount_out,
+            })),
+            (GmTxRequest::Withdrawal(withdrawal), GmAmountOutResponse::Withdrawal { long_amount_out, short_amount_out }) => Some(GmTxRequest::Deposit(GmDepositRequest {
+                market: withdrawal.market,
+                long_amount: *long_amount_out,
+                short_amount: *short_amount_out,
+                initial_long_token: None,
+                initial_short_token: None,
+            })),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -13,6 +37,15 @@ pub struct GmDepositRequest {
     pub market: Address,
     pub long_amount: Decimal,
     pub short_amount: Decimal,
+    pub initial_long_token: Option<Address>,  // Held token sent in place of the long token, swapped into it through GMX markets (amount in this token)
+    pub initial_short_token: Option<Address>, // Held token sent in place of the short token, swapped the same way
+}
+
+impl GmDepositRequest {
+    /// Tokens sent into the deposit, the market's own long/short tokens unless they are swapped from other held tokens
+    pub fn initial_tokens(&self, long_token: Address, short_token: Address) -> (Address, Address) {
+        (self.initial_long_token.unwrap_or(long_token), self.initial_short_token.unwrap_or(short_token))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -28,6 +61,16 @@ pub struct GmShiftRequest {
     pub amount: Decimal,
 }   
 
+#[derive(Debug, Clone)]
+pub struct GmClaimRewardsRequest {
+    pub tokens: Vec<Address>, // Reward tokens to claim (e.g. ARB incentives)
+}
+
+#[derive(Debug, Clone)]
+pub struct GmClaimUiFeesRequest {
+    pub markets: Vec<Address>, // Markets whose long and short token UI fees are claimed
+}
+
 #[derive(Debug, Clone)]
 pub enum GmAmountOutResponse {
     Deposit { amount_out: Decimal },
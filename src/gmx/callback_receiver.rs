@@ -0,0 +1,162 @@
+use ethers::{
+    types::{Address, Bytes, H256},
+    providers::{Provider, Ws, StreamExt},
+    contract::{abigen, ContractFactory},
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, Notify};
+use eyre::Result;
+use tracing::{info, error, warn, debug, instrument};
+
+use crate::config::Config;
+use crate::wallet::WalletManager;
+
+// Bindings for contracts/GmxCallbackReceiver.sol
+abigen!(
+    GmxCallbackReceiver,
+    r#"[
+        constructor(address _roleStore, address _owner)
+        event RequestSettled(bytes32 indexed key, uint8 indexed requestType, bool executed)
+        function owner() external view returns (address)
+        function roleStore() external view returns (address)
+    ]"#
+);
+
+/// Compiled bytecode written by `solc --bin` (see the build note in the contract source)
+pub const CALLBACK_RECEIVER_BYTECODE_PATH: &str = "./contracts/out/GmxCallbackReceiver.bin";
+
+/// Outcome of a GMX request as reported by its execution or cancellation callback
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallbackOutcome {
+    Executed,
+    Cancelled,
+}
+
+/// Request outcomes received from the callback contract, keyed by request key.
+/// Waiters are woken on every new outcome, so settlement is noticed as soon as the callback log arrives.
+#[derive(Debug, Clone, Default)]
+pub struct SettledRequests {
+    outcomes: Arc<Mutex<HashMap<H256, CallbackOutcome>>>,
+    notify: Arc<Notify>,
+}
+
+impl SettledRequests {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn insert(&self, key: H256, outcome: CallbackOutcome) {
+        self.outcomes.lock().await.insert(key, outcome);
+        self.notify.notify_waiters();
+    }
+
+    /// Outcome of the request, removing it once read, None while no callback has been received
+    pub async fn take(&self, key: H256) -> Option<CallbackOutcome> {
+        self.outcomes.lock().await.remove(&key)
+    }
+
+    /// Wait until a new outcome arrives or the timeout passes, whichever is first
+    pub async fn wait(&self, timeout: Duration) {
+        let _ = tokio::time::timeout(timeout, self.notify.notified()).await;
+    }
+}
+
+/// Deploy the callback receiver with the wallet as owner (receiving forwarded execution fee refunds).
+/// Only GMX contracts holding the CONTROLLER role in `role_store` can trigger its callbacks.
+#[instrument(skip(wallet_manager, bytecode))]
+pub async fn deploy_callback_receiver(wallet_manager: &WalletManager, role_store: Address, bytecode: Bytes) -> Result<Address> {
+    let factory = ContractFactory::new(GMXCALLBACKRECEIVER_ABI.clone(), bytecode, wallet_manager.signer.clone());
+    let deployer = factory.deploy((role_store, wallet_manager.address))
+        .map_err(|e| eyre::eyre!("Failed to encode callback receiver deployment: {}", e))?;
+    let contract = deployer.send().await
+        .map_err(|e| eyre::eyre!("Callback receiver deployment failed: {}", e))?;
+    info!(address = ?contract.address(), "GMX callback receiver deployed");
+    Ok(contract.address())
+}
+
+/// Read compiled bytecode (hex, with or without 0x prefix) from a solc output file
+pub fn load_bytecode(path: &str) -> Result<Bytes> {
+    let hex = std::fs::read_to_string(path)
+        .map_err(|e| eyre::eyre!("Failed to read callback receiver bytecode from {}: {}", path, e))?;
+    hex.trim().parse::<Bytes>()
+        .map_err(|e| eyre::eyre!("Invalid bytecode in {}: {}", path, e))
+}
+
+// --- GMX Callback Listener ---
+pub struct GmxCallbackListener {
+    ws_url: String,
+    callback_contract: Address,
+    settled: SettledRequests,
+}
+
+impl GmxCallbackListener {
+    #[instrument(skip(ws_url, settled))]
+    pub fn init(ws_url: String, callback_contract: Address, settled: SettledRequests) -> Self {
+        info!("Initializing GMX callback listener");
+        GmxCallbackListener {
+            ws_url,
+            callback_contract,
+            settled,
+        }
+    }
+
+    /// Listener for the configured callback contract, None when no callback contract is configured
+    pub fn from_config(config: &Config, settled: SettledRequests) -> Option<Self> {
+        config.gmx_callback_contract
+            .map(|callback_contract| Self::init(config.alchemy_ws_url.clone(), callback_contract, settled))
+    }
+
+    // Start listening for callbacks (to be called in long-running background task)
+    #[instrument(skip(self), fields(callback_contract = %self.callback_contract))]
+    pub async fn start_listening(&self) -> Result<()> {
+        info!("Starting GMX callback listener");
+        loop {
+            let provider = match Provider::<Ws>::connect(&self.ws_url).await {
+                Ok(p) => Arc::new(p),
+                Err(e) => {
+                    error!(?e, "Failed to connect to WebSocket provider");
+                    tokio::time::sleep(Duration::from_secs(10)).await;
+                    continue;
+                }
+            };
+
+            let receiver = GmxCallbackReceiver::new(self.callback_contract, provider.clone());
+            let event_watcher = receiver.event::<RequestSettledFilter>();
+            match event_watcher.subscribe().await {
+                Ok(mut stream) => {
+                    info!("Callback stream subscribed, processing events");
+                    loop {
+                        match stream.next().await {
+                            Some(Ok(event)) => self.process_request_settled_event(event).await,
+                            Some(Err(e)) => {
+                                error!(?e, "Stream error - reconnecting immediately");
+                                break;
+                            }
+                            None => {
+                                warn!("Stream ended - reconnecting");
+                                break;
+                            }
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!(?e, "Failed to subscribe to GMX callback stream, retrying in 10s...");
+                    tokio::time::sleep(Duration::from_secs(10)).await;
+                }
+            }
+
+            // Brief pause before reconnecting
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        }
+    }
+
+    // Process RequestSettled event
+    async fn process_request_settled_event(&self, event: RequestSettledFilter) {
+        let key = H256::from(event.key);
+        let outcome = if event.executed { CallbackOutcome::Executed } else { CallbackOutcome::Cancelled };
+        debug!(key = ?key, request_type = event.request_type, outcome = ?outcome, "GMX request callback received");
+        self.settled.insert(key, outcome).await;
+    }
+}
@@ -3,6 +3,8 @@ use ethers::utils::keccak256;
 use ethers::contract::Multicall;
 use eyre::Result;
 use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
 use tracing::{debug, instrument};
 
 use crate::config::Config;
@@ -20,6 +22,75 @@ async fn get_uint(config: &Config, key: H256) -> Result<U256> {
     Ok(value)
 }
 
+// --- CONSTANT CACHE ---
+// Gas limits, fee receiver factors and price feeds rarely change, so they are cached in-process
+// (keyed by datastore address + hashed key) instead of being re-read on every execution fee calculation.
+
+/// How long cached datastore constants stay valid
+const CONSTANT_CACHE_TTL: Duration = Duration::from_secs(600);
+
+#[derive(Debug, Clone, Copy)]
+enum CachedValue {
+    Uint(U256),
+    Address(Address),
+}
+
+static CONSTANT_CACHE: OnceLock<Mutex<HashMap<(Address, H256), (CachedValue, Instant)>>> = OnceLock::new();
+
+fn constant_cache() -> &'static Mutex<HashMap<(Address, H256), (CachedValue, Instant)>> {
+    CONSTANT_CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn cache_lookup(datastore: Address, key: H256) -> Option<CachedValue> {
+    let cache = constant_cache().lock().unwrap_or_else(|e| e.into_inner());
+    match cache.get(&(datastore, key)) {
+        Some((value, fetched_at)) if fetched_at.elapsed() < CONSTANT_CACHE_TTL => Some(*value),
+        _ => None,
+    }
+}
+
+fn cache_store(datastore: Address, key: H256, value: CachedValue) {
+    let mut cache = constant_cache().lock().unwrap_or_else(|e| e.into_inner());
+    cache.insert((datastore, key), (value, Instant::now()));
+}
+
+/// Read a uint constant from the datastore, served from the cache while fresh
+async fn get_uint_cached(config: &Config, key: H256) -> Result<U256> {
+    if let Some(CachedValue::Uint(value)) = cache_lookup(config.gmx_datastore, key) {
+        debug!(?key, "Datastore uint cache hit");
+        return Ok(value);
+    }
+    let value = get_uint(config, key).await?;
+    cache_store(config.gmx_datastore, key, CachedValue::Uint(value));
+    Ok(value)
+}
+
+/// Read an address constant from the datastore, served from the cache while fresh
+async fn get_address_cached(config: &Config, key: H256) -> Result<Address> {
+    if let Some(CachedValue::Address(value)) = cache_lookup(config.gmx_datastore, key) {
+        debug!(?key, "Datastore address cache hit");
+        return Ok(value);
+    }
+    let value = get_address(config, key).await?;
+    cache_store(config.gmx_datastore, key, CachedValue::Address(value));
+    Ok(value)
+}
+
+/// Drop a single cached datastore constant so the next read goes to the chain
+pub fn invalidate_cached_constant(key: H256) {
+    let mut cache = constant_cache().lock().unwrap_or_else(|e| e.into_inner());
+    cache.retain(|(_, cached_key), _| *cached_key != key);
+    debug!(?key, "Datastore constant cache entry dropped");
+}
+
+/// Datastore key of a global constant such as DEPOSIT_GAS_LIMIT
+pub fn constant_key(name: &str) -> H256 {
+    let encoded = ethers::abi::encode(&[ethers::abi::Token::String(name.to_string())]);
+    H256::from_slice(&keccak256(&encoded))
+}
+
+//----------------------------------------------------------------------------------------------------------------------------------------
+
 pub async fn get_open_interest(config: &Config, market_props: reader_utils::MarketProps, is_long: bool) -> Result<U256> {
     fn get_key(market: Address, collateral_token: Address, is_long: bool) -> H256 {
         let open_interest_encoded = ethers::abi::encode(&[ethers::abi::Token::String("OPEN_INTEREST".to_string())]);
@@ -86,13 +157,37 @@ pub async fn get_lp_fee_pool_factors(config: &Config) -> Result<(U256, U256, U25
     for factor_str in pool_factor_strs.iter() {
         let encoded = ethers::abi::encode(&[ethers::abi::Token::String(factor_str.to_string())]);
         let key = H256::from_slice(&keccak256(&encoded));
-        let value = get_uint(config, key).await?;
+        let value = get_uint_cached(config, key).await?;
         factors.push(gmx_precision - value);
     }
 
     Ok((factors[0], factors[1], factors[2], factors[3]))
 }
 
+/// Per-side factors (30 decimals) bounding how much liquidity can leave a market through withdrawals:
+/// reserved open interest must stay within pool * RESERVE_FACTOR and trader PnL within
+/// pool * MAX_PNL_FACTOR_FOR_WITHDRAWALS, otherwise the withdrawal reverts at execution
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WithdrawalLimitFactors {
+    pub reserve_factor_long: U256,
+    pub reserve_factor_short: U256,
+    pub max_pnl_factor_long: U256,
+    pub max_pnl_factor_short: U256,
+}
+
+pub async fn get_withdrawal_limit_factors(config: &Config, market: Address) -> Result<WithdrawalLimitFactors> {
+    let reserve_factor_long = get_uint_cached(config, get_reserve_factor_key(market, true)).await?;
+    let reserve_factor_short = get_uint_cached(config, get_reserve_factor_key(market, false)).await?;
+    let max_pnl_factor_long = get_uint_cached(config, get_max_pnl_factor_key("MAX_PNL_FACTOR_FOR_WITHDRAWALS", market, true)).await?;
+    let max_pnl_factor_short = get_uint_cached(config, get_max_pnl_factor_key("MAX_PNL_FACTOR_FOR_WITHDRAWALS", market, false)).await?;
+    Ok(WithdrawalLimitFactors {
+        reserve_factor_long,
+        reserve_factor_short,
+        max_pnl_factor_long,
+        max_pnl_factor_short,
+    })
+}
+
 /// Batch version: Get open interest for multiple markets using multicall
 #[instrument(skip(config, markets), fields(market_count = markets.len()))]
 pub async fn get_open_interest_batch(
@@ -235,6 +330,309 @@ pub async fn get_open_interest_in_tokens_batch(
     Ok((long_interests, short_interests))
 }
 
+/// Market status flags read from the datastore
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MarketStatusFlags {
+    pub is_disabled: bool,        // IS_MARKET_DISABLED set by GMX governance
+    pub deposits_disabled: bool,  // MAX_POOL_AMOUNT zeroed for both collateral tokens, no new deposits accepted
+}
+
+impl MarketStatusFlags {
+    /// True if the market is halted or being wound down by GMX
+    pub fn is_deprecated(&self) -> bool {
+        self.is_disabled || self.deposits_disabled
+    }
+}
+
+/// Batch version: Get halt/deprecation flags for multiple markets using multicall
+#[instrument(skip(config, markets), fields(market_count = markets.len()))]
+pub async fn get_market_status_flags_batch(
+    config: &Config,
+    markets: &[reader_utils::MarketProps],
+) -> Result<HashMap<Address, MarketStatusFlags>> {
+    debug!(market_count = markets.len(), "Fetching market status flags batch");
+
+    let datastore = DataStore::new(config.gmx_datastore, config.alchemy_provider.clone());
+
+    // Disabled flags (bool) and max pool amounts (uint) are fetched in separate multicalls since call_array needs a single return type
+    let mut disabled_multicall = Multicall::new(config.alchemy_provider.clone(), None).await?;
+    let mut max_pool_multicall = Multicall::new(config.alchemy_provider.clone(), None).await?;
+    for market_props in markets {
+        disabled_multicall.add_call(datastore.get_bool(get_is_market_disabled_key(market_props.market_token).into()), false);
+        max_pool_multicall.add_call(datastore.get_uint(get_max_pool_amount_key(market_props.market_token, market_props.long_token).into()), false);
+        max_pool_multicall.add_call(datastore.get_uint(get_max_pool_amount_key(market_props.market_token, market_props.short_token).into()), false);
+    }
+
+    debug!(call_count = markets.len() * 3, "Executing market status multicalls");
+    let disabled_results: Vec<bool> = disabled_multicall.call_array().await?;
+    let max_pool_results: Vec<U256> = max_pool_multicall.call_array().await?;
+
+    let mut status_flags = HashMap::new();
+    for (i, market_props) in markets.iter().enumerate() {
+        let is_disabled = disabled_results.get(i).cloned().unwrap_or(false);
+        let max_long_pool = max_pool_results.get(i * 2).cloned().unwrap_or(U256::zero());
+        let max_short_pool = max_pool_results.get(i * 2 + 1).cloned().unwrap_or(U256::zero());
+        status_flags.insert(market_props.market_token, MarketStatusFlags {
+            is_disabled,
+            deposits_disabled: max_long_pool.is_zero() && max_short_pool.is_zero(),
+        });
+    }
+
+    debug!(
+        market_count = markets.len(),
+        deprecated_count = status_flags.values().filter(|f| f.is_deprecated()).count(),
+        "Market status flags batch fetch completed"
+    );
+
+    Ok(status_flags)
+}
+
+/// Pool and open interest caps GMX enforces on a market, deposits or positions taking the market past them revert
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MarketCaps {
+    pub max_pool_amount_long: U256,  // MAX_POOL_AMOUNT of the long token, in long token units
+    pub max_pool_amount_short: U256, // MAX_POOL_AMOUNT of the short token, in short token units
+    pub max_open_interest_long: U256,  // MAX_OPEN_INTEREST for longs, USD scaled by GMX_DECIMALS
+    pub max_open_interest_short: U256, // MAX_OPEN_INTEREST for shorts, USD scaled by GMX_DECIMALS
+}
+
+/// Batch version: Get max pool amount and max open interest caps for multiple markets using multicall
+#[instrument(skip(config, markets), fields(market_count = markets.len()))]
+pub async fn get_market_caps_batch(
+    config: &Config,
+    markets: &[reader_utils::MarketProps],
+) -> Result<HashMap<Address, MarketCaps>> {
+    debug!(market_count = markets.len(), "Fetching market caps batch");
+
+    let mut multicall = Multicall::new(config.alchemy_provider.clone(), None).await?;
+    let datastore = DataStore::new(config.gmx_datastore, config.alchemy_provider.clone());
+    for market_props in markets {
+        multicall.add_call(datastore.get_uint(get_max_pool_amount_key(market_props.market_token, market_props.long_token).into()), false);
+        multicall.add_call(datastore.get_uint(get_max_pool_amount_key(market_props.market_token, market_props.short_token).into()), false);
+        multicall.add_call(datastore.get_uint(get_max_open_interest_key(market_props.market_token, true).into()), false);
+        multicall.add_call(datastore.get_uint(get_max_open_interest_key(market_props.market_token, false).into()), false);
+    }
+
+    debug!(call_count = markets.len() * 4, "Executing market caps multicall");
+    let results: Vec<U256> = multicall.call_array().await?;
+
+    // Each market has 4 results: max long pool, max short pool, max long OI, max short OI
+    let mut caps = HashMap::new();
+    for (i, market_props) in markets.iter().enumerate() {
+        let base_idx = i * 4;
+        caps.insert(market_props.market_token, MarketCaps {
+            max_pool_amount_long: results.get(base_idx).cloned().unwrap_or(U256::zero()),
+            max_pool_amount_short: results.get(base_idx + 1).cloned().unwrap_or(U256::zero()),
+            max_open_interest_long: results.get(base_idx + 2).cloned().unwrap_or(U256::zero()),
+            max_open_interest_short: results.get(base_idx + 3).cloned().unwrap_or(U256::zero()),
+        });
+    }
+
+    debug!(market_count = caps.len(), "Market caps batch fetch completed");
+    Ok(caps)
+}
+
+/// Swap impact and fee factors of a market (30 decimals), the pricing GMX applies to deposits and withdrawals
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SwapPricingFactors {
+    pub positive_impact_factor: U256,
+    pub negative_impact_factor: U256,
+    pub impact_exponent_factor: U256,
+    pub fee_factor_positive_impact: U256,
+    pub fee_factor_negative_impact: U256,
+}
+
+/// Batch version: Get swap impact and fee factors for multiple markets using multicall
+#[instrument(skip(config, markets), fields(market_count = markets.len()))]
+pub async fn get_swap_pricing_factors_batch(
+    config: &Config,
+    markets: &[Address],
+) -> Result<HashMap<Address, SwapPricingFactors>> {
+    debug!(market_count = markets.len(), "Fetching swap pricing factors batch");
+
+    let mut multicall = Multicall::new(config.alchemy_provider.clone(), None).await?;
+    let datastore = DataStore::new(config.gmx_datastore, config.alchemy_provider.clone());
+    for market in markets {
+        multicall.add_call(datastore.get_uint(get_swap_impact_factor_key(*market, true).into()), false);
+        multicall.add_call(datastore.get_uint(get_swap_impact_factor_key(*market, false).into()), false);
+        multicall.add_call(datastore.get_uint(get_swap_impact_exponent_factor_key(*market).into()), false);
+        multicall.add_call(datastore.get_uint(get_swap_fee_factor_key(*market, true).into()), false);
+        multicall.add_call(datastore.get_uint(get_swap_fee_factor_key(*market, false).into()), false);
+    }
+
+    debug!(call_count = markets.len() * 5, "Executing swap pricing factors multicall");
+    let results: Vec<U256> = multicall.call_array().await?;
+
+    // Each market has 5 results: positive and negative impact factor, impact exponent, positive and negative fee factor
+    let mut factors = HashMap::new();
+    for (i, market) in markets.iter().enumerate() {
+        let base_idx = i * 5;
+        let result = |offset: usize| results.get(base_idx + offset).cloned().unwrap_or(U256::zero());
+        factors.insert(*market, SwapPricingFactors {
+            positive_impact_factor: result(0),
+            negative_impact_factor: result(1),
+            impact_exponent_factor: result(2),
+            fee_factor_positive_impact: result(3),
+            fee_factor_negative_impact: result(4),
+        });
+    }
+
+    debug!(market_count = factors.len(), "Swap pricing factors batch fetch completed");
+    Ok(factors)
+}
+
+/// Batch version: Get the UI fees claimable by a UI fee receiver for multiple (market, token) pairs using multicall,
+/// in token units
+#[instrument(skip(config, pairs), fields(pair_count = pairs.len()))]
+pub async fn get_claimable_ui_fees_batch(
+    config: &Config,
+    account: Address,
+    pairs: &[(Address, Address)],
+) -> Result<HashMap<(Address, Address), U256>> {
+    debug!(pair_count = pairs.len(), "Fetching claimable UI fees batch");
+
+    let mut multicall = Multicall::new(config.alchemy_provider.clone(), None).await?;
+    let datastore = DataStore::new(config.gmx_datastore, config.alchemy_provider.clone());
+    for (market, token) in pairs {
+        multicall.add_call(datastore.get_uint(get_claimable_ui_fee_amount_key(*market, *token, account).into()), false);
+    }
+
+    let results: Vec<U256> = multicall.call_array().await?;
+    let claimable = pairs.iter()
+        .enumerate()
+        .map(|(i, pair)| (*pair, results.get(i).cloned().unwrap_or(U256::zero())))
+        .collect();
+    Ok(claimable)
+}
+
+/// UI fee factor (30 decimals) a UI fee receiver has set for itself, the share of the swap fee charged on top as UI fee
+pub async fn get_ui_fee_factor(config: &Config, account: Address) -> Result<U256> {
+    let datastore = DataStore::new(config.gmx_datastore, config.alchemy_provider.clone());
+    let ui_fee_factor: U256 = datastore.get_uint(get_ui_fee_factor_key(account).into()).call().await?;
+    Ok(ui_fee_factor)
+}
+
+/// Helper function to generate claimable UI fee amount key
+fn get_claimable_ui_fee_amount_key(market: Address, token: Address, account: Address) -> H256 {
+    let claimable_ui_fee_amount_encoded = ethers::abi::encode(&[ethers::abi::Token::String("CLAIMABLE_UI_FEE_AMOUNT".to_string())]);
+    let claimable_ui_fee_amount_key = H256::from_slice(&keccak256(&claimable_ui_fee_amount_encoded));
+    let encoded = ethers::abi::encode(&[
+        ethers::abi::Token::FixedBytes(claimable_ui_fee_amount_key.as_bytes().to_vec()),
+        ethers::abi::Token::Address(market),
+        ethers::abi::Token::Address(token),
+        ethers::abi::Token::Address(account),
+    ]);
+    H256::from(keccak256(encoded))
+}
+
+/// Helper function to generate UI fee factor key
+fn get_ui_fee_factor_key(account: Address) -> H256 {
+    let ui_fee_factor_encoded = ethers::abi::encode(&[ethers::abi::Token::String("UI_FEE_FACTOR".to_string())]);
+    let ui_fee_factor_key = H256::from_slice(&keccak256(&ui_fee_factor_encoded));
+    let encoded = ethers::abi::encode(&[
+        ethers::abi::Token::FixedBytes(ui_fee_factor_key.as_bytes().to_vec()),
+        ethers::abi::Token::Address(account),
+    ]);
+    H256::from(keccak256(encoded))
+}
+
+/// Helper function to generate is market disabled key
+fn get_is_market_disabled_key(market: Address) -> H256 {
+    let is_market_disabled_encoded = ethers::abi::encode(&[ethers::abi::Token::String("IS_MARKET_DISABLED".to_string())]);
+    let is_market_disabled_key = H256::from_slice(&keccak256(&is_market_disabled_encoded));
+    let encoded = ethers::abi::encode(&[
+        ethers::abi::Token::FixedBytes(is_market_disabled_key.as_bytes().to_vec()),
+        ethers::abi::Token::Address(market),
+    ]);
+    H256::from(keccak256(encoded))
+}
+
+/// Helper function to generate max pool amount key
+fn get_max_pool_amount_key(market: Address, token: Address) -> H256 {
+    let max_pool_amount_encoded = ethers::abi::encode(&[ethers::abi::Token::String("MAX_POOL_AMOUNT".to_string())]);
+    let max_pool_amount_key = H256::from_slice(&keccak256(&max_pool_amount_encoded));
+    let encoded = ethers::abi::encode(&[
+        ethers::abi::Token::FixedBytes(max_pool_amount_key.as_bytes().to_vec()),
+        ethers::abi::Token::Address(market),
+        ethers::abi::Token::Address(token),
+    ]);
+    H256::from(keccak256(encoded))
+}
+
+/// Helper function to generate swap impact factor key
+fn get_swap_impact_factor_key(market: Address, is_positive: bool) -> H256 {
+    let swap_impact_factor_encoded = ethers::abi::encode(&[ethers::abi::Token::String("SWAP_IMPACT_FACTOR".to_string())]);
+    let swap_impact_factor_key = H256::from_slice(&keccak256(&swap_impact_factor_encoded));
+    let encoded = ethers::abi::encode(&[
+        ethers::abi::Token::FixedBytes(swap_impact_factor_key.as_bytes().to_vec()),
+        ethers::abi::Token::Address(market),
+        ethers::abi::Token::Bool(is_positive),
+    ]);
+    H256::from(keccak256(encoded))
+}
+
+/// Helper function to generate swap impact exponent factor key
+fn get_swap_impact_exponent_factor_key(market: Address) -> H256 {
+    let swap_impact_exponent_encoded = ethers::abi::encode(&[ethers::abi::Token::String("SWAP_IMPACT_EXPONENT_FACTOR".to_string())]);
+    let swap_impact_exponent_key = H256::from_slice(&keccak256(&swap_impact_exponent_encoded));
+    let encoded = ethers::abi::encode(&[
+        ethers::abi::Token::FixedBytes(swap_impact_exponent_key.as_bytes().to_vec()),
+        ethers::abi::Token::Address(market),
+    ]);
+    H256::from(keccak256(encoded))
+}
+
+/// Helper function to generate swap fee factor key
+fn get_swap_fee_factor_key(market: Address, for_positive_impact: bool) -> H256 {
+    let swap_fee_factor_encoded = ethers::abi::encode(&[ethers::abi::Token::String("SWAP_FEE_FACTOR".to_string())]);
+    let swap_fee_factor_key = H256::from_slice(&keccak256(&swap_fee_factor_encoded));
+    let encoded = ethers::abi::encode(&[
+        ethers::abi::Token::FixedBytes(swap_fee_factor_key.as_bytes().to_vec()),
+        ethers::abi::Token::Address(market),
+        ethers::abi::Token::Bool(for_positive_impact),
+    ]);
+    H256::from(keccak256(encoded))
+}
+
+/// Helper function to generate max open interest key
+fn get_max_open_interest_key(market: Address, is_long: bool) -> H256 {
+    let max_open_interest_encoded = ethers::abi::encode(&[ethers::abi::Token::String("MAX_OPEN_INTEREST".to_string())]);
+    let max_open_interest_key = H256::from_slice(&keccak256(&max_open_interest_encoded));
+    let encoded = ethers::abi::encode(&[
+        ethers::abi::Token::FixedBytes(max_open_interest_key.as_bytes().to_vec()),
+        ethers::abi::Token::Address(market),
+        ethers::abi::Token::Bool(is_long),
+    ]);
+    H256::from(keccak256(encoded))
+}
+
+/// Helper function to generate reserve factor key
+fn get_reserve_factor_key(market: Address, is_long: bool) -> H256 {
+    let reserve_factor_encoded = ethers::abi::encode(&[ethers::abi::Token::String("RESERVE_FACTOR".to_string())]);
+    let reserve_factor_key = H256::from_slice(&keccak256(&reserve_factor_encoded));
+    let encoded = ethers::abi::encode(&[
+        ethers::abi::Token::FixedBytes(reserve_factor_key.as_bytes().to_vec()),
+        ethers::abi::Token::Address(market),
+        ethers::abi::Token::Bool(is_long),
+    ]);
+    H256::from(keccak256(encoded))
+}
+
+/// Helper function to generate max pnl factor key for a pnl factor type (e.g. MAX_PNL_FACTOR_FOR_WITHDRAWALS)
+fn get_max_pnl_factor_key(pnl_factor_type: &str, market: Address, is_long: bool) -> H256 {
+    let max_pnl_factor_encoded = ethers::abi::encode(&[ethers::abi::Token::String("MAX_PNL_FACTOR".to_string())]);
+    let max_pnl_factor_key = H256::from_slice(&keccak256(&max_pnl_factor_encoded));
+    let pnl_factor_type_encoded = ethers::abi::encode(&[ethers::abi::Token::String(pnl_factor_type.to_string())]);
+    let pnl_factor_type_key = H256::from_slice(&keccak256(&pnl_factor_type_encoded));
+    let encoded = ethers::abi::encode(&[
+        ethers::abi::Token::FixedBytes(max_pnl_factor_key.as_bytes().to_vec()),
+        ethers::abi::Token::FixedBytes(pnl_factor_type_key.as_bytes().to_vec()),
+        ethers::abi::Token::Address(market),
+        ethers::abi::Token::Bool(is_long),
+    ]);
+    H256::from(keccak256(encoded))
+}
+
 /// Helper function to generate open interest key
 fn get_open_interest_key(market: Address, collateral_token: Address, is_long: bool) -> H256 {
     let open_interest_encoded = ethers::abi::encode(&[ethers::abi::Token::String("OPEN_INTEREST".to_string())]);
@@ -264,7 +662,7 @@ fn get_open_interest_in_tokens_key(market: Address, collateral_token: Address, i
 pub async fn estimate_execute_gas_limit_per_swap(config: &Config) -> Result<U256> {
     let encoded = ethers::abi::encode(&[ethers::abi::Token::String("SINGLE_SWAP_GAS_LIMIT".to_string())]);
     let key = H256::from_slice(&keccak256(&encoded));
-    get_uint(config, key).await
+    get_uint_cached(config, key).await
 }
 
 pub fn estimate_deposit_oracle_price_count(swaps_count: U256) -> U256 {
@@ -280,35 +678,36 @@ pub fn estimate_shift_oracle_price_count(_swaps_count: U256) -> U256 {
 }
 
 pub async fn get_deposit_gas_limit(config: &Config) -> Result<U256> {
-    let encoded = ethers::abi::encode(&[ethers::abi::Token::String("DEPOSIT_GAS_LIMIT".to_string())]);
-    let key = H256::from_slice(&keccak256(&encoded));
-    get_uint(config, key).await
+    get_uint_cached(config, constant_key("DEPOSIT_GAS_LIMIT")).await
 }
 
 pub async fn get_withdrawal_gas_limit(config: &Config) -> Result<U256> {
-    let encoded = ethers::abi::encode(&[ethers::abi::Token::String("WITHDRAWAL_GAS_LIMIT".to_string())]);
-    let key = H256::from_slice(&keccak256(&encoded));
-    get_uint(config, key).await
+    get_uint_cached(config, constant_key("WITHDRAWAL_GAS_LIMIT")).await
 }
 
 pub async fn get_shift_gas_limit(config: &Config) -> Result<U256> {
-    let encoded = ethers::abi::encode(&[ethers::abi::Token::String("SHIFT_GAS_LIMIT".to_string())]);
+    get_uint_cached(config, constant_key("SHIFT_GAS_LIMIT")).await
+}
+
+/// Maximum gas GMX lets a request forward to its callback contract
+pub async fn get_max_callback_gas_limit(config: &Config) -> Result<U256> {
+    let encoded = ethers::abi::encode(&[ethers::abi::Token::String("MAX_CALLBACK_GAS_LIMIT".to_string())]);
     let key = H256::from_slice(&keccak256(&encoded));
-    get_uint(config, key).await
+    get_uint_cached(config, key).await
 }
 
 pub async fn adjust_gas_limit_for_estimate(config: &Config, estimated_gas_limit: U256, oracle_price_count: U256) -> Result<U256> {
     let encoded = ethers::abi::encode(&[ethers::abi::Token::String("ESTIMATED_GAS_FEE_BASE_AMOUNT_V2_1".to_string())]);
     let key = H256::from_slice(&keccak256(&encoded));
-    let mut base_gas_limit = get_uint(config, key).await?;
+    let mut base_gas_limit = get_uint_cached(config, key).await?;
 
     let encoded = ethers::abi::encode(&[ethers::abi::Token::String("ESTIMATED_GAS_FEE_PER_ORACLE_PRICE".to_string())]);
     let key = H256::from_slice(&keccak256(&encoded));
-    base_gas_limit += get_uint(config, key).await? * oracle_price_count;
+    base_gas_limit += get_uint_cached(config, key).await? * oracle_price_count;
 
     let encoded = ethers::abi::encode(&[ethers::abi::Token::String("ESTIMATED_GAS_FEE_MULTIPLIER_FACTOR".to_string())]);
     let key = H256::from_slice(&keccak256(&encoded));
-    let multiplier_factor = get_uint(config, key).await?;
+    let multiplier_factor = get_uint_cached(config, key).await?;
 
     let gmx_precision = U256::from(10).pow(U256::from(GMX_DECIMALS));
     let adjusted_estimated_gas = (estimated_gas_limit * multiplier_factor) / gmx_precision;
@@ -335,6 +734,32 @@ fn get_price_feed_key(token: Address) -> H256 {
 
 pub async fn get_price_feed_for_token(config: &Config, token: Address) -> Result<Address> {
     let key = get_price_feed_key(token);
-    let price_feed = get_address(config, key).await?;
+    let price_feed = get_address_cached(config, key).await?;
     Ok(price_feed)
-}
\ No newline at end of file
+}
+/// Helper function to generate key for a GMX request list (DEPOSIT_LIST, WITHDRAWAL_LIST, SHIFT_LIST)
+fn get_request_list_key(list_name: &str) -> H256 {
+    let encoded = ethers::abi::encode(&[ethers::abi::Token::String(list_name.to_string())]);
+    H256::from_slice(&keccak256(&encoded))
+}
+
+async fn contains_bytes32(config: &Config, set_key: H256, value: H256) -> Result<bool> {
+    let datastore = DataStore::new(config.gmx_datastore, config.alchemy_provider.clone());
+    let contains: bool = datastore.contains_bytes_32(set_key.into(), value.into()).call().await?;
+    Ok(contains)
+}
+
+/// Returns true if the deposit with the given key is still waiting for keeper execution
+pub async fn is_deposit_pending(config: &Config, key: H256) -> Result<bool> {
+    contains_bytes32(config, get_request_list_key("DEPOSIT_LIST"), key).await
+}
+
+/// Returns true if the withdrawal with the given key is still waiting for keeper execution
+pub async fn is_withdrawal_pending(config: &Config, key: H256) -> Result<bool> {
+    contains_bytes32(config, get_request_list_key("WITHDRAWAL_LIST"), key).await
+}
+
+/// Returns true if the shift with the given key is still waiting for keeper execution
+pub async fn is_shift_pending(config: &Config, key: H256) -> Result<bool> {
+    contains_bytes32(config, get_request_list_key("SHIFT_LIST"), key).await
+}
@@ -3,7 +3,7 @@ use ethers::{
     providers::{Provider, Http, Middleware},
     contract::{abigen, EthLogDecode},
 };
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use std::time::Duration;
 use eyre::Result;
@@ -12,32 +12,75 @@ use tracing::{info, error, warn, debug, instrument};
 use super::event_listener_utils::{
     string_to_bytes32,
     MarketFees,
+    LiquidationEvent,
 };
 
+const LOG_BLOCK_RANGE: u64 = 10_000; // Blocks per eth_getLogs request when scanning history
+const LIQUIDATION_ORDER_TYPE: u64 = 7; // Order.OrderType.Liquidation
+const REORG_TRACKING_BLOCKS: u64 = 20_000; // Blocks behind the last fetched block whose hashes are checked for reorgs (~80 minutes on Arbitrum)
+
 abigen!(
     EventEmitter,
     "./abis/EventEmitter.json",
 );   
 
+// --- Event Finality ---
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventFinality {
+    Confirmations(u64), // Blocks behind the chain head
+    Finalized,          // The chain's finalized block (on Arbitrum, once the batch holding it is final on L1)
+}
+
+// --- Ingested Block (a fetched block whose hash is checked for reorgs) ---
+#[derive(Debug, Clone)]
+struct IngestedBlock {
+    hash: H256,
+    fees: HashMap<Address, MarketFees>, // Fees the block's events contributed, empty for range anchors
+}
+
 // --- GMX Event Fetcher ---
 pub struct GmxEventFetcher {
     provider: Arc<Provider<Http>>,
     event_emitter_address: Address,
+    finality: EventFinality,
     last_block_fetched: Option<u64>,
+    ingested_blocks: BTreeMap<u64, IngestedBlock>, // Blocks with fee events plus the last fetched block, by number
+    pending_rollback: HashMap<Address, MarketFees>, // Fees of reorged blocks not yet deducted, by market
 }
 
 impl GmxEventFetcher {
     // Initialize the event fetcher
     #[instrument(skip(provider))]
-    pub fn init(provider: Arc<Provider<Http>>, event_emitter_address: Address) -> Self {
+    pub fn init(provider: Arc<Provider<Http>>, event_emitter_address: Address, finality: EventFinality) -> Self {
         info!("Initializing GMX event fetcher");
         GmxEventFetcher {
             provider,
             event_emitter_address,
+            finality,
             last_block_fetched: None,
+            ingested_blocks: BTreeMap::new(),
+            pending_rollback: HashMap::new(),
+        }
+    }
+
+    // Latest block deep enough to ingest events from, per the finality setting
+    pub async fn safe_block(&self) -> Result<u64> {
+        match self.finality {
+            EventFinality::Confirmations(confirmations) => {
+                Ok(self.provider.get_block_number().await?.as_u64().saturating_sub(confirmations))
+            }
+            EventFinality::Finalized => self.provider.get_block(BlockNumber::Finalized).await?
+                .and_then(|block| block.number)
+                .map(|number| number.as_u64())
+                .ok_or_else(|| eyre::eyre!("Finalized block not available")),
         }
     }
 
+    // Hash of the canonical block at a height, None when the node doesn't have it
+    async fn canonical_hash(&self, block_number: u64) -> Result<Option<H256>> {
+        Ok(self.provider.get_block(BlockNumber::Number(block_number.into())).await?.and_then(|block| block.hash))
+    }
+
     // Helper method to fetch logs with retry logic
     #[instrument(skip(self, filter))]
     async fn fetch_logs_with_retry(&self, filter: &Filter) -> Result<Vec<Log>> {
@@ -76,43 +119,50 @@ impl GmxEventFetcher {
         Err(last_error.unwrap().into())
     }
 
-    // Fetch fees from last block to latest (on-demand)
+    // Fetch fees from the last fetched block to the latest final one (on-demand). Fees of blocks reorged off the chain
+    // since they were fetched are taken back out of the returned fees, and their replacements fetched again.
     #[instrument(skip(self), fields(event_emitter = %self.event_emitter_address))]
     pub async fn fetch_fees(&mut self) -> Result<HashMap<Address, MarketFees>> {
         debug!("Fetching GMX fees");
 
-        // Get current block number
-        let current_block = self.provider.get_block_number().await?;
-        let current_block_u64 = current_block.as_u64();
+        // Get the latest block deep enough to ingest
+        let safe_block = self.safe_block().await?;
 
         // Handle first time call - just set the block and return empty
-        if self.last_block_fetched.is_none() {
-            info!(block = current_block_u64, "First time fetch - setting initial block");
-            self.last_block_fetched = Some(current_block_u64);
+        let Some(mut from_block) = self.last_block_fetched else {
+            info!(block = safe_block, finality = ?self.finality, "First time fetch - setting initial block");
+            self.set_anchor(safe_block).await?;
             return Ok(HashMap::new());
+        };
+
+        // Roll back the blocks a reorg replaced, refetching from the last block still on the chain
+        if let Some(reorged_block) = self.find_reorged_block().await? {
+            from_block = self.roll_back(reorged_block);
         }
 
-        let from_block = self.last_block_fetched.unwrap();
-        
         // If we're already at the latest block, return empty
-        if from_block >= current_block_u64 {
+        if from_block >= safe_block {
             debug!("No new blocks to process");
             return Ok(HashMap::new());
         }
 
         debug!(
             from_block = from_block,
-            to_block = current_block_u64,
-            blocks_to_process = current_block_u64 - from_block,
+            to_block = safe_block,
+            blocks_to_process = safe_block - from_block,
             "Fetching events for block range"
         );
 
-        // Filter for PositionFeesCollected and SwapFeesCollected events
+        // Filter for PositionFeesCollected, SwapFeesCollected and position change events
         let position_fees_collected_hash = string_to_bytes32("PositionFeesCollected");
         let swap_fees_collected_hash = string_to_bytes32("SwapFeesCollected");
+        let position_increase_hash = string_to_bytes32("PositionIncrease");
+        let position_decrease_hash = string_to_bytes32("PositionDecrease");
         let topic1_vec = vec![
             position_fees_collected_hash,
             swap_fees_collected_hash,
+            position_increase_hash,
+            position_decrease_hash,
         ];
 
         // Create filter for events
@@ -120,27 +170,42 @@ impl GmxEventFetcher {
             .address(self.event_emitter_address)
             .topic1(topic1_vec)
             .from_block(BlockNumber::Number((from_block + 1).into()))
-            .to_block(BlockNumber::Number(current_block_u64.into()));
+            .to_block(BlockNumber::Number(safe_block.into()));
 
         // Fetch logs
         let logs = self.fetch_logs_with_retry(&filter).await?;
         debug!(logs_count = logs.len(), "Retrieved logs");
 
-        // Process logs and build fees map
-        let mut fees_map = HashMap::new();
+        // Process logs into each block's fees, keeping the block hashes to check for reorgs later
+        let mut fetched_blocks: BTreeMap<u64, IngestedBlock> = BTreeMap::new();
         let mut events_processed = 0u64;
 
         for log in logs {
+            let (Some(block_number), Some(block_hash), false) = (log.block_number, log.block_hash, log.removed.unwrap_or(false)) else {
+                continue; // Pending or removed logs aren't final
+            };
+            let fees_map = &mut fetched_blocks.entry(block_number.as_u64())
+                .or_insert_with(|| IngestedBlock { hash: block_hash, fees: HashMap::new() })
+                .fees;
+
             // Try to decode as EventLog1Filter (which contains the event_name)
             if let Ok(decoded_log) = event_emitter::EventLog1Filter::decode_log(&log.clone().into()) {
                 let event_name = decoded_log.event_name.as_str();
                 match event_name {
                     "PositionFeesCollected" => {
-                        self.process_position_fees_event(&decoded_log, &mut fees_map);
+                        self.process_position_fees_event(&decoded_log, fees_map);
                         events_processed += 1;
                     },
                     "SwapFeesCollected" => {
-                        self.process_swap_fees_event(&decoded_log, &mut fees_map);
+                        self.process_swap_fees_event(&decoded_log, fees_map);
+                        events_processed += 1;
+                    },
+                    "PositionIncrease" => {
+                        self.process_position_change_event(&decoded_log, true, fees_map);
+                        events_processed += 1;
+                    },
+                    "PositionDecrease" => {
+                        self.process_position_change_event(&decoded_log, false, fees_map);
                         events_processed += 1;
                     },
                     _ => {
@@ -152,8 +217,20 @@ impl GmxEventFetcher {
             }
         }
 
+        // Sum the blocks' fees by market
+        let mut fees_map: HashMap<Address, MarketFees> = HashMap::new();
+        for block in fetched_blocks.values() {
+            for (market, fees) in &block.fees {
+                fees_map.entry(*market).or_insert_with(MarketFees::new).merge(fees);
+            }
+        }
+        self.ingested_blocks.extend(fetched_blocks);
+
         // Update last processed block
-        self.last_block_fetched = Some(current_block_u64);
+        self.set_anchor(safe_block).await?;
+
+        // Take fees of reorged blocks back out
+        self.deduct_rollback(&mut fees_map);
 
         info!(
             events_processed = events_processed,
@@ -164,6 +241,91 @@ impl GmxEventFetcher {
         Ok(fees_map)
     }
 
+    // Record the last fetched block and its hash, the anchor the next fetch checks for a reorg. Blocks further back
+    // than REORG_TRACKING_BLOCKS are treated as final and no longer tracked.
+    async fn set_anchor(&mut self, block_number: u64) -> Result<()> {
+        if !self.ingested_blocks.contains_key(&block_number) {
+            let hash = self.canonical_hash(block_number).await?
+                .ok_or_else(|| eyre::eyre!("Block {} not found", block_number))?;
+            self.ingested_blocks.insert(block_number, IngestedBlock { hash, fees: HashMap::new() });
+        }
+        self.last_block_fetched = Some(block_number);
+        self.ingested_blocks = self.ingested_blocks.split_off(&block_number.saturating_sub(REORG_TRACKING_BLOCKS));
+        Ok(())
+    }
+
+    // First tracked block no longer on the canonical chain, None when the last fetched block still is. A block's hash
+    // commits to all its ancestors, so the tracked blocks are binary searched for the first mismatch.
+    #[instrument(skip(self))]
+    async fn find_reorged_block(&self) -> Result<Option<u64>> {
+        let blocks: Vec<(u64, H256)> = self.ingested_blocks.iter().map(|(number, block)| (*number, block.hash)).collect();
+        let Some((last_block, last_hash)) = blocks.last().copied() else {
+            return Ok(None);
+        };
+        if self.canonical_hash(last_block).await? == Some(last_hash) {
+            return Ok(None);
+        }
+
+        // Blocks before `low` are canonical, the block at `high` is not
+        let (mut low, mut high) = (0, blocks.len() - 1);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.canonical_hash(blocks[mid].0).await? == Some(blocks[mid].1) {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        Ok(Some(blocks[high].0))
+    }
+
+    // Drop the tracked blocks from the reorged one on, queueing the fees they contributed to be deducted. Returns the
+    // block to refetch after: the last tracked block still on the chain, since untracked blocks after it may have
+    // gained events in the reorg.
+    fn roll_back(&mut self, reorged_block: u64) -> u64 {
+        let orphaned_blocks = self.ingested_blocks.split_off(&reorged_block);
+        for block in orphaned_blocks.values() {
+            for (market, fees) in &block.fees {
+                self.pending_rollback.entry(*market).or_insert_with(MarketFees::new).merge(fees);
+            }
+        }
+
+        let resume_block = match self.ingested_blocks.last_key_value() {
+            Some((block_number, _)) => *block_number,
+            None => {
+                warn!(
+                    reorged_block = reorged_block,
+                    tracked_blocks = REORG_TRACKING_BLOCKS,
+                    "Reorg reaches past the tracked blocks, fees of earlier orphaned blocks can't be reconciled"
+                );
+                reorged_block.saturating_sub(1)
+            }
+        };
+        self.last_block_fetched = Some(resume_block);
+        warn!(
+            reorged_block = reorged_block,
+            orphaned_blocks = orphaned_blocks.len(),
+            markets_affected = self.pending_rollback.len(),
+            resume_block = resume_block,
+            "Chain reorg detected, rolling back fees of orphaned blocks"
+        );
+        resume_block
+    }
+
+    // Deduct the fees of reorged blocks from newly fetched fees. Fees already handed out can't be taken back, so what
+    // a market hasn't collected enough to cover is carried into later fetches.
+    fn deduct_rollback(&mut self, fees_map: &mut HashMap<Address, MarketFees>) {
+        for (market, debit) in self.pending_rollback.iter_mut() {
+            if let Some(fees) = fees_map.get_mut(market) {
+                fees.deduct(debit);
+            }
+        }
+        self.pending_rollback.retain(|_, debit| !debit.is_zero());
+        if !self.pending_rollback.is_empty() {
+            warn!(markets = self.pending_rollback.len(), "Fees of reorged blocks not fully reconciled, deducting from later fetches");
+        }
+    }
+
     // Process PositionFeesCollected event
     #[instrument(skip(self, event, fees_map), fields(event_name = "PositionFeesCollected"))]
     fn process_position_fees_event(&self, event: &event_emitter::EventLog1Filter, fees_map: &mut HashMap<Address, MarketFees>) {
@@ -302,6 +464,133 @@ impl GmxEventFetcher {
         );
     }
 
+    // Process PositionIncrease and PositionDecrease events
+    #[instrument(skip(self, event, fees_map), fields(event_name = %event.event_name))]
+    fn process_position_change_event(&self, event: &event_emitter::EventLog1Filter, is_increase: bool, fees_map: &mut HashMap<Address, MarketFees>) {
+        let market_address = match event.event_data.address_items.items.get(1) {
+            Some(item) => Address::from(H256::from(item.value)),
+            None => {
+                error!("Missing market_address at index 1");
+                return;
+            }
+        };
+        let size_delta_usd = match event.event_data.uint_items.items.get(12) {
+            Some(item) => item.value,
+            None => {
+                error!("Missing size_delta_usd at index 12");
+                return;
+            }
+        };
+        let is_long = match event.event_data.bool_items.items.get(0) {
+            Some(item) => item.value,
+            None => {
+                error!("Missing is_long at index 0");
+                return;
+            }
+        };
+
+        // Opening a long or closing a short takes the buy side, the other two the sell side
+        let is_buy = is_long == is_increase;
+
+        // Update fees map
+        let market_fees = fees_map.entry(market_address).or_insert_with(MarketFees::new);
+        if is_buy {
+            market_fees.taker_buy_volume += size_delta_usd;
+        } else {
+            market_fees.taker_sell_volume += size_delta_usd;
+        }
+
+        debug!(
+            market = %market_address,
+            is_long = is_long,
+            is_increase = is_increase,
+            size_delta_usd = %size_delta_usd,
+            "Position change event processed"
+        );
+    }
+
+    // Fetch liquidations in [from_block, to_block]: PositionDecrease events executed by a liquidation order
+    #[instrument(skip(self), fields(event_emitter = %self.event_emitter_address))]
+    pub async fn fetch_liquidations(&self, from_block: u64, to_block: u64) -> Result<Vec<LiquidationEvent>> {
+        let position_decrease_hash = string_to_bytes32("PositionDecrease");
+        let mut liquidations = Vec::new();
+        let mut chunk_start = from_block;
+        while chunk_start <= to_block {
+            let chunk_end = (chunk_start + LOG_BLOCK_RANGE - 1).min(to_block);
+            let filter = Filter::new()
+                .address(self.event_emitter_address)
+                .topic1(position_decrease_hash)
+                .from_block(BlockNumber::Number(chunk_start.into()))
+                .to_block(BlockNumber::Number(chunk_end.into()));
+            let logs = self.fetch_logs_with_retry(&filter).await?;
+
+            for log in logs {
+                let (Some(block_number), Some(tx_hash), Some(log_index)) = (log.block_number, log.transaction_hash, log.log_index) else {
+                    continue; // Pending logs aren't final
+                };
+                let Ok(decoded_log) = event_emitter::EventLog1Filter::decode_log(&log.clone().into()) else {
+                    warn!("Failed to decode event log");
+                    continue;
+                };
+                if let Some(mut liquidation) = self.process_liquidation_event(&decoded_log) {
+                    liquidation.block_number = block_number.as_u64();
+                    liquidation.tx_hash = tx_hash;
+                    liquidation.log_index = log_index.as_u64();
+                    liquidations.push(liquidation);
+                }
+            }
+            debug!(from_block = chunk_start, to_block = chunk_end, liquidations = liquidations.len(), "Scanned block range for liquidations");
+            chunk_start = chunk_end + 1;
+        }
+        Ok(liquidations)
+    }
+
+    // Process a PositionDecrease event, returning it when a liquidation order closed the position
+    #[instrument(skip(self, event), fields(event_name = %event.event_name))]
+    fn process_liquidation_event(&self, event: &event_emitter::EventLog1Filter) -> Option<LiquidationEvent> {
+        let order_type = match event.event_data.uint_items.items.get(16) {
+            Some(item) => item.value,
+            None => {
+                error!("Missing order_type at index 16");
+                return None;
+            }
+        };
+        if order_type != U256::from(LIQUIDATION_ORDER_TYPE) {
+            return None;
+        }
+        let market_address = match event.event_data.address_items.items.get(1) {
+            Some(item) => Address::from(H256::from(item.value)),
+            None => {
+                error!("Missing market_address at index 1");
+                return None;
+            }
+        };
+        let size_delta_usd = match event.event_data.uint_items.items.get(12) {
+            Some(item) => item.value,
+            None => {
+                error!("Missing size_delta_usd at index 12");
+                return None;
+            }
+        };
+        let is_long = match event.event_data.bool_items.items.get(0) {
+            Some(item) => item.value,
+            None => {
+                error!("Missing is_long at index 0");
+                return None;
+            }
+        };
+
+        debug!(market = %market_address, is_long = is_long, size_delta_usd = %size_delta_usd, "Liquidation event processed");
+        Some(LiquidationEvent {
+            market: market_address,
+            block_number: 0,
+            tx_hash: H256::zero(),
+            log_index: 0,
+            is_long,
+            size_delta_usd,
+        })
+    }
+
     // Getter for last block fetched (for persistence/debugging)
     pub fn get_last_block_fetched(&self) -> Option<u64> {
         self.last_block_fetched
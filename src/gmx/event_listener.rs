@@ -7,7 +7,7 @@ use ethers::{
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, mpsc};
 use eyre::Result;
 use tracing::{info, error, warn, debug, instrument};
 
@@ -30,6 +30,8 @@ pub struct GmxEventListener {
     pub fees: CumulativeFeesMap,
     ws_url: String,
     event_emitter_address: Address,
+    event_names: Vec<&'static str>,
+    market_created_tx: Option<mpsc::UnboundedSender<Address>>, // Market tokens of newly created markets
 }
 
 impl GmxEventListener {
@@ -41,6 +43,21 @@ impl GmxEventListener {
             fees: Arc::new(Mutex::new(HashMap::new())),
             ws_url,
             event_emitter_address,
+            event_names: vec!["PositionFeesCollected", "SwapFeesCollected", "PositionIncrease", "PositionDecrease"],
+            market_created_tx: None,
+        }
+    }
+
+    // Initialize a listener for MarketCreated events only, sending each new market token to the channel
+    #[instrument(skip(ws_url, market_created_tx))]
+    pub fn init_market_discovery(ws_url: String, event_emitter_address: Address, market_created_tx: mpsc::UnboundedSender<Address>) -> Self {
+        info!("Initializing GMX market discovery listener");
+        GmxEventListener {
+            fees: Arc::new(Mutex::new(HashMap::new())),
+            ws_url,
+            event_emitter_address,
+            event_names: vec!["MarketCreated"],
+            market_created_tx: Some(market_created_tx),
         }
     }
 
@@ -49,13 +66,10 @@ impl GmxEventListener {
     pub async fn start_listening(&self) -> Result<()> {
         info!("Starting GMX event listener");
 
-        // Filter for PositionFeesCollected and SwapFeesCollected events
-        let position_fees_collected_hash = string_to_bytes32("PositionFeesCollected");
-        let swap_fees_collected_hash = string_to_bytes32("SwapFeesCollected");
-        let topic1_vec = vec![
-            position_fees_collected_hash,
-            swap_fees_collected_hash,
-        ];
+        // Filter for the listener's events (fee and position change events, or MarketCreated for market discovery)
+        let topic1_vec: Vec<H256> = self.event_names.iter()
+            .map(|event_name| string_to_bytes32(event_name))
+            .collect();
 
         // Track ping task handle for cleanup
         let mut ping_handle: Option<tokio::task::JoinHandle<()>> = None;
@@ -127,6 +141,18 @@ impl GmxEventListener {
                                                 self.process_swap_fees_event(event).await;
                                                 events_processed += 1;
                                             },
+                                            "PositionIncrease" => {
+                                                self.process_position_change_event(event, true).await;
+                                                events_processed += 1;
+                                            },
+                                            "PositionDecrease" => {
+                                                self.process_position_change_event(event, false).await;
+                                                events_processed += 1;
+                                            },
+                                            "MarketCreated" => {
+                                                self.process_market_created_event(event);
+                                                events_processed += 1;
+                                            },
                                             _ => {
                                                 warn!(event_name = event_name, "Unknown event type received");
                                             }
@@ -309,4 +335,71 @@ impl GmxEventListener {
             "Swap fees event processed"
         );
     }
+
+    // Process PositionIncrease and PositionDecrease events
+    #[instrument(skip(self, event), fields(event_name = %event.event_name))]
+    async fn process_position_change_event(&self, event: event_emitter::EventLog1Filter, is_increase: bool) {
+        let market_address = match event.event_data.address_items.items.get(1) {
+            Some(item) => Address::from(H256::from(item.value)),
+            None => {
+                error!("Missing market_address at index 1");
+                return;
+            }
+        };
+        let size_delta_usd = match event.event_data.uint_items.items.get(12) {
+            Some(item) => item.value,
+            None => {
+                error!("Missing size_delta_usd at index 12");
+                return;
+            }
+        };
+        let is_long = match event.event_data.bool_items.items.get(0) {
+            Some(item) => item.value,
+            None => {
+                error!("Missing is_long at index 0");
+                return;
+            }
+        };
+
+        // Opening a long or closing a short takes the buy side, the other two the sell side
+        let is_buy = is_long == is_increase;
+
+        // Update fees map
+        let mut fees_map = self.fees.lock().await;
+        let market_fees = fees_map.entry(market_address).or_insert_with(MarketFees::new);
+        if is_buy {
+            market_fees.taker_buy_volume += size_delta_usd;
+        } else {
+            market_fees.taker_sell_volume += size_delta_usd;
+        }
+
+        debug!(
+            market = %market_address,
+            is_long = is_long,
+            is_increase = is_increase,
+            size_delta_usd = %size_delta_usd,
+            "Position change event processed"
+        );
+    }
+
+    // Process MarketCreated event
+    #[instrument(skip(self, event), fields(event_name = "MarketCreated"))]
+    fn process_market_created_event(&self, event: event_emitter::EventLog1Filter) {
+        let market_token = match event.event_data.address_items.items.get(0) {
+            Some(item) => Address::from(H256::from(item.value)),
+            None => {
+                error!("Missing market_token at index 0");
+                return;
+            }
+        };
+        let index_token = event.event_data.address_items.items.get(1)
+            .map(|item| Address::from(H256::from(item.value)));
+
+        info!(market_token = %market_token, index_token = ?index_token, "New GMX market created");
+        if let Some(market_created_tx) = &self.market_created_tx {
+            if market_created_tx.send(market_token).is_err() {
+                warn!(market_token = %market_token, "Market discovery receiver dropped, new market not forwarded");
+            }
+        }
+    }
 }
\ No newline at end of file
@@ -22,6 +22,10 @@ pub struct MarketFees {
 
     pub trading_volume: U256,
     pub swap_volume: HashMap<Address, U256>,
+
+    // Taker flow in USD from position changes: buys are long increases and short decreases, sells the opposite
+    pub taker_buy_volume: U256,
+    pub taker_sell_volume: U256,
 }
 
 impl MarketFees {
@@ -33,8 +37,77 @@ impl MarketFees {
             borrowing_fees: HashMap::new(),
             trading_volume: U256::zero(),
             swap_volume: HashMap::new(),
+            taker_buy_volume: U256::zero(),
+            taker_sell_volume: U256::zero(),
+        }
+    }
+
+    // Add another set of fees (e.g. a later block's) to these
+    pub fn merge(&mut self, other: &MarketFees) {
+        for (fees, other_fees) in [
+            (&mut self.position_fees, &other.position_fees),
+            (&mut self.liquidation_fees, &other.liquidation_fees),
+            (&mut self.swap_fees, &other.swap_fees),
+            (&mut self.borrowing_fees, &other.borrowing_fees),
+            (&mut self.swap_volume, &other.swap_volume),
+        ] {
+            for (token, amount) in other_fees {
+                *fees.entry(*token).or_insert(U256::zero()) += *amount;
+            }
         }
+        self.trading_volume += other.trading_volume;
+        self.taker_buy_volume += other.taker_buy_volume;
+        self.taker_sell_volume += other.taker_sell_volume;
     }
+
+    // Take a debit (fees of orphaned blocks) out of these fees, as much as they hold. What couldn't be taken out is
+    // left in the debit.
+    pub fn deduct(&mut self, debit: &mut MarketFees) {
+        for (fees, debit_fees) in [
+            (&mut self.position_fees, &mut debit.position_fees),
+            (&mut self.liquidation_fees, &mut debit.liquidation_fees),
+            (&mut self.swap_fees, &mut debit.swap_fees),
+            (&mut self.borrowing_fees, &mut debit.borrowing_fees),
+            (&mut self.swap_volume, &mut debit.swap_volume),
+        ] {
+            for (token, debit_amount) in debit_fees.iter_mut() {
+                let amount = fees.entry(*token).or_insert(U256::zero());
+                let taken = (*amount).min(*debit_amount);
+                *amount -= taken;
+                *debit_amount -= taken;
+            }
+            debit_fees.retain(|_, amount| !amount.is_zero());
+        }
+        for (amount, debit_amount) in [
+            (&mut self.trading_volume, &mut debit.trading_volume),
+            (&mut self.taker_buy_volume, &mut debit.taker_buy_volume),
+            (&mut self.taker_sell_volume, &mut debit.taker_sell_volume),
+        ] {
+            let taken = (*amount).min(*debit_amount);
+            *amount -= taken;
+            *debit_amount -= taken;
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        [&self.position_fees, &self.liquidation_fees, &self.swap_fees, &self.borrowing_fees, &self.swap_volume]
+            .iter()
+            .all(|fees| fees.values().all(|amount| amount.is_zero()))
+            && self.trading_volume.is_zero()
+            && self.taker_buy_volume.is_zero()
+            && self.taker_sell_volume.is_zero()
+    }
+}
+
+// --- Liquidation Struct (one liquidated position) ---
+#[derive(Debug, Clone)]
+pub struct LiquidationEvent {
+    pub market: Address,
+    pub block_number: u64,
+    pub tx_hash: H256,
+    pub log_index: u64,
+    pub is_long: bool,
+    pub size_delta_usd: U256, // Position size closed by the liquidation (30 decimals)
 }
 
 // --- Cumulative Fees Map type ---
@@ -2,9 +2,12 @@ use tracing::{debug, instrument};
 use eyre::Result;
 use ethers::prelude::*;
 
+use crate::clock::Clock;
 use crate::config::Config;
 use crate::wallet::WalletManager;
+use crate::permit::{self, Eip2612Permit};
 use super::exchange_router_utils;
+use super::event_fetcher::event_emitter::EventLog1Filter;
 
 abigen!(
     ExchangeRouter,
@@ -20,10 +23,11 @@ abigen!(
 );
 
 /// Create a deposit in the GMX Exchange Router
-#[instrument(skip(config, wallet_manager, params, initial_long_amount, initial_short_amount, gas_limit, gas_price))]
+#[instrument(skip(config, wallet_manager, clock, params, initial_long_amount, initial_short_amount, gas_limit, gas_price))]
 pub async fn create_deposit(
     config: &Config, 
     wallet_manager: &WalletManager,
+    clock: &dyn Clock,
     params: exchange_router_utils::CreateDepositParams,
     initial_long_amount: U256,
     initial_short_amount: U256,
@@ -33,12 +37,14 @@ pub async fn create_deposit(
     let exchange_router = ExchangeRouter::new(config.gmx_exchangerouter, wallet_manager.signer.clone());
     let execution_fee = params.execution_fee;
 
-    // Approve token spending if needed
-    approve_token(wallet_manager, params.addresses.initial_long_token, config.gmx_baserouter, initial_long_amount).await?;
-    approve_token(wallet_manager, params.addresses.initial_short_token, config.gmx_baserouter, initial_short_amount).await?;
+    // Authorize token spending if needed, by permits applied within the multicall where possible
+    let mut permits = Vec::new();
+    permits.extend(authorize_token(config, wallet_manager, clock, params.addresses.initial_long_token, config.gmx_baserouter, initial_long_amount).await?);
+    permits.extend(authorize_token(config, wallet_manager, clock, params.addresses.initial_short_token, config.gmx_baserouter, initial_short_amount).await?);
 
-    // Create token transfer calls
+    // Create token transfer calls, after any permits granting the router its allowance
     let mut encoded_calls = Vec::new();
+    encoded_calls.extend(permit_calldata(&exchange_router, wallet_manager, &permits)?);
 
     if initial_long_amount > U256::zero() {
         let call = exchange_router.send_tokens(
@@ -99,10 +105,11 @@ pub async fn create_deposit(
 }
 
 /// Create a withdrawal in the GMX Exchange Router
-#[instrument(skip(config, wallet_manager, params, market_token_amount, gas_limit, gas_price))]
+#[instrument(skip(config, wallet_manager, clock, params, market_token_amount, gas_limit, gas_price))]
 pub async fn create_withdrawal(
     config: &Config, 
     wallet_manager: &WalletManager,
+    clock: &dyn Clock,
     params: exchange_router_utils::CreateWithdrawalParams,
     market_token_amount: U256,
     gas_limit: U256,
@@ -111,11 +118,14 @@ pub async fn create_withdrawal(
     let exchange_router = ExchangeRouter::new(config.gmx_exchangerouter, wallet_manager.signer.clone());
     let execution_fee = params.execution_fee;
     
-    // Approve token spending if needed
-    approve_token(wallet_manager, params.addresses.market, config.gmx_baserouter, market_token_amount).await?;
+    // Authorize token spending if needed, by permit applied within the multicall where possible
+    let permits: Vec<Eip2612Permit> = authorize_token(config, wallet_manager, clock, params.addresses.market, config.gmx_baserouter, market_token_amount).await?
+        .into_iter()
+        .collect();
 
-    // Create token transfer calls
+    // Create token transfer calls, after any permit granting the router its allowance
     let mut encoded_calls = Vec::new();
+    encoded_calls.extend(permit_calldata(&exchange_router, wallet_manager, &permits)?);
 
     if market_token_amount > U256::zero() {
         let call = exchange_router.send_tokens(
@@ -168,10 +178,11 @@ pub async fn create_withdrawal(
 }
 
 /// Create a shift in the GMX Exchange Router
-#[instrument(skip(config, wallet_manager, params, from_token_amount, gas_limit, gas_price))]
+#[instrument(skip(config, wallet_manager, clock, params, from_token_amount, gas_limit, gas_price))]
 pub async fn create_shift(
     config: &Config, 
     wallet_manager: &WalletManager,
+    clock: &dyn Clock,
     params: exchange_router_utils::CreateShiftParams,
     from_token_amount: U256,
     gas_limit: U256,
@@ -180,11 +191,14 @@ pub async fn create_shift(
     let exchange_router = ExchangeRouter::new(config.gmx_exchangerouter, wallet_manager.signer.clone());
     let execution_fee = params.execution_fee;
 
-    // Approve token spending if needed
-    approve_token(wallet_manager, params.addresses.from_market, config.gmx_baserouter, from_token_amount).await?;
+    // Authorize token spending if needed, by permit applied within the multicall where possible
+    let permits: Vec<Eip2612Permit> = authorize_token(config, wallet_manager, clock, params.addresses.from_market, config.gmx_baserouter, from_token_amount).await?
+        .into_iter()
+        .collect();
 
-    // Create token transfer calls
+    // Create token transfer calls, after any permit granting the router its allowance
     let mut encoded_calls = Vec::new();
+    encoded_calls.extend(permit_calldata(&exchange_router, wallet_manager, &permits)?);
     
     if from_token_amount > U256::zero() {
         let call = exchange_router.send_tokens(
@@ -236,38 +250,216 @@ pub async fn create_shift(
     Ok((tx_hash, receipt))
 }
 
+/// Cancel a pending deposit in the GMX Exchange Router, returning the deposited tokens and remaining execution fee
+#[instrument(skip(config, wallet_manager))]
+pub async fn cancel_deposit(config: &Config, wallet_manager: &WalletManager, key: H256) -> Result<(TxHash, TransactionReceipt)> {
+    let exchange_router = ExchangeRouter::new(config.gmx_exchangerouter, wallet_manager.signer.clone());
+    let call = exchange_router.cancel_deposit(key.into()).from(wallet_manager.address);
+    send_cancellation(call, "Deposit").await
+}
+
+/// Cancel a pending withdrawal in the GMX Exchange Router, returning the market tokens and remaining execution fee
+#[instrument(skip(config, wallet_manager))]
+pub async fn cancel_withdrawal(config: &Config, wallet_manager: &WalletManager, key: H256) -> Result<(TxHash, TransactionReceipt)> {
+    let exchange_router = ExchangeRouter::new(config.gmx_exchangerouter, wallet_manager.signer.clone());
+    let call = exchange_router.cancel_withdrawal(key.into()).from(wallet_manager.address);
+    send_cancellation(call, "Withdrawal").await
+}
+
+/// Cancel a pending shift in the GMX Exchange Router, returning the market tokens and remaining execution fee
+#[instrument(skip(config, wallet_manager))]
+pub async fn cancel_shift(config: &Config, wallet_manager: &WalletManager, key: H256) -> Result<(TxHash, TransactionReceipt)> {
+    let exchange_router = ExchangeRouter::new(config.gmx_exchangerouter, wallet_manager.signer.clone());
+    let call = exchange_router.cancel_shift(key.into()).from(wallet_manager.address);
+    send_cancellation(call, "Shift").await
+}
+
+/// Claim the UI fees accrued to the wallet as UI fee receiver, for each (market, token) pair, to the wallet.
+/// GMX only pays UI fees out to the receiver itself, so this only claims anything when the wallet is the configured receiver.
+#[instrument(skip(config, wallet_manager))]
+pub async fn claim_ui_fees(
+    config: &Config,
+    wallet_manager: &WalletManager,
+    markets: Vec<Address>,
+    tokens: Vec<Address>,
+) -> Result<(TxHash, TransactionReceipt)> {
+    if markets.len() != tokens.len() {
+        return Err(eyre::eyre!("UI fee claim needs one token per market, got {} markets and {} tokens", markets.len(), tokens.len()));
+    }
+    let exchange_router = ExchangeRouter::new(config.gmx_exchangerouter, wallet_manager.signer.clone());
+
+    let call = exchange_router.claim_ui_fees(markets, tokens, wallet_manager.address).from(wallet_manager.address);
+    let pending_tx = call.send().await?;
+    let tx_hash = pending_tx.tx_hash();
+    debug!(tx_hash = ?tx_hash, "UI fee claim transaction sent, waiting for confirmation");
+
+    let receipt = match pending_tx.await? {
+        Some(receipt) => {
+            if receipt.status == Some(1.into()) {
+                receipt
+            } else {
+                return Err(eyre::eyre!("UI fee claim failed with status {:?}: {:?}", receipt.status, receipt));
+            }
+        },
+        None => {
+            return Err(eyre::eyre!("UI fee claim transaction failed: no receipt returned"));
+        }
+    };
+
+    Ok((tx_hash, receipt))
+}
+
+/// Extract the request key of a created deposit/withdrawal/shift from its creation receipt.
+/// GMX emits `EventLog2(msgSender, eventName, eventNameHash, key, account, eventData)` from the EventEmitter,
+/// so the key is the second indexed topic of the log whose event name hash matches.
+pub fn get_request_key_from_receipt(config: &Config, receipt: &TransactionReceipt, event_name: &str) -> Option<H256> {
+    let event_name_hash = H256::from(ethers::utils::keccak256(event_name.as_bytes()));
+    receipt.logs.iter()
+        .filter(|log| log.address == config.gmx_eventemitter)
+        .find(|log| log.topics.len() >= 3 && log.topics[1] == event_name_hash)
+        .map(|log| log.topics[2])
+}
+
+/// Keeper execution of a GM request and the execution fee refunded to the receiver
+#[derive(Debug, Clone)]
+pub struct ExecutionFeeRefund {
+    pub keeper_tx_hash: TxHash,
+    pub keeper_gas_price: U256,
+    pub refund_amount: U256, // Zero when the keeper used the whole execution fee
+}
+
+/// Find the keeper transaction that executed a GM request (its `executed_event_name` event, keyed by request key, emitted
+/// at or after `from_block`) and the `ExecutionFeeRefund` paid to the receiver in that transaction
+#[instrument(skip(config))]
+pub async fn get_execution_fee_refund(
+    config: &Config,
+    executed_event_name: &str,
+    request_key: H256,
+    receiver: Address,
+    from_block: U64,
+) -> Result<Option<ExecutionFeeRefund>> {
+    let executed_event_hash = H256::from(ethers::utils::keccak256(executed_event_name.as_bytes()));
+    let filter = Filter::new()
+        .address(config.gmx_eventemitter)
+        .topic1(executed_event_hash)
+        .topic2(request_key)
+        .from_block(from_block);
+    let executed_logs = config.alchemy_provider.get_logs(&filter).await?;
+    let Some(keeper_tx_hash) = executed_logs.first().and_then(|log| log.transaction_hash) else {
+        debug!("Request execution event not found");
+        return Ok(None);
+    };
+
+    let receipt = config.alchemy_provider.get_transaction_receipt(keeper_tx_hash).await?
+        .ok_or_else(|| eyre::eyre!("Keeper execution transaction {:?} has no receipt", keeper_tx_hash))?;
+    let refund_event_hash = H256::from(ethers::utils::keccak256("ExecutionFeeRefund".as_bytes()));
+    let receiver_topic = H256::from(receiver);
+    let refund_amount = receipt.logs.iter()
+        .filter(|log| log.address == config.gmx_eventemitter)
+        .filter(|log| log.topics.len() >= 3 && log.topics[1] == refund_event_hash && log.topics[2] == receiver_topic)
+        .filter_map(|log| EventLog1Filter::decode_log(&log.clone().into()).ok())
+        .filter_map(|event| event.event_data.uint_items.items.iter()
+            .find(|item| item.key == "refundFeeAmount")
+            .map(|item| item.value))
+        .fold(U256::zero(), |total, amount| total + amount);
+
+    Ok(Some(ExecutionFeeRefund {
+        keeper_tx_hash,
+        keeper_gas_price: receipt.effective_gas_price.unwrap_or_default(),
+        refund_amount,
+    }))
+}
+
 //----------------------------------------------------------------------------------------------------------------------------------------
 
-/// Helper function to approve token spending
-#[instrument(skip(wallet_manager, token_address, spender, amount))]
-async fn approve_token(wallet_manager: &WalletManager, token_address: Address, spender: Address, amount: U256) -> Result<()> {
+/// Helper function to authorize token spending: nothing when the allowance already covers the amount, otherwise a signed
+/// EIP-2612 permit (returned, to be applied within the request's multicall) when permit approvals are enabled and the
+/// token supports them, or else an approve transaction
+#[instrument(skip(config, wallet_manager, clock, token_address, spender, amount))]
+async fn authorize_token(
+    config: &Config,
+    wallet_manager: &WalletManager,
+    clock: &dyn Clock,
+    token_address: Address,
+    spender: Address,
+    amount: U256,
+) -> Result<Option<Eip2612Permit>> {
     if amount.is_zero() {
         debug!(?token_address, ?spender, "No approval needed for zero amount");
-        return Ok(());
+        return Ok(None);
     }
     let amount = amount + (amount / 10); // Add 10% buffer
     let token = ERC20::new(token_address, wallet_manager.signer.clone());
     let allowance = token.allowance(wallet_manager.address, spender).call().await?;
-    if allowance < amount {
-        let approval_call = token.approve(spender, amount);
-        let pending_tx = approval_call.send().await?;
-        let receipt = pending_tx.await?;
-        match receipt {
-            Some(receipt) => {
-                if receipt.status == Some(1.into()) {
-                    debug!(?token_address, ?spender, ?amount, "Token spending approved successfully");
-                } else {
-                    return Err(eyre::eyre!("Token approval failed with status {:?}: {:?}", receipt.status, receipt));
-                }
-            },
-            None => {
-                return Err(eyre::eyre!("Token approval transaction failed: no receipt returned"));
+    if allowance >= amount {
+        debug!(?token_address, ?spender, ?amount, "Token spending already approved");
+        return Ok(None);
+    }
+
+    if config.permit_approvals {
+        if let Some(permit) = permit::sign_eip2612_permit(wallet_manager, clock, token_address, spender, amount).await? {
+            debug!(?token_address, ?spender, ?amount, "Token spending authorized by permit");
+            return Ok(Some(permit));
+        }
+    }
+
+    let approval_call = token.approve(spender, amount);
+    let pending_tx = approval_call.send().await?;
+    let receipt = pending_tx.await?;
+    match receipt {
+        Some(receipt) => {
+            if receipt.status == Some(1.into()) {
+                debug!(?token_address, ?spender, ?amount, "Token spending approved successfully");
+            } else {
+                return Err(eyre::eyre!("Token approval failed with status {:?}: {:?}", receipt.status, receipt));
             }
+        },
+        None => {
+            return Err(eyre::eyre!("Token approval transaction failed: no receipt returned"));
         }
-    } else {
-        debug!(?token_address, ?spender, ?amount, "Token spending already approved");
     }
-    Ok(())
+    Ok(None)
+}
+
+/// Helper function to encode permits as one ExchangeRouter external call batch (GMX's ExternalHandler calls each
+/// token's `permit`, which anyone may submit), None when there are no permits
+fn permit_calldata<M: Middleware + 'static>(
+    exchange_router: &ExchangeRouter<M>,
+    wallet_manager: &WalletManager,
+    permits: &[Eip2612Permit],
+) -> Result<Option<Bytes>> {
+    if permits.is_empty() {
+        return Ok(None);
+    }
+    let targets = permits.iter().map(|p| p.token).collect();
+    let data_list = permits.iter()
+        .map(|p| p.calldata(wallet_manager))
+        .collect::<Result<Vec<Bytes>>>()?;
+    let call = exchange_router.make_external_calls(targets, data_list, Vec::new(), Vec::new());
+    let calldata = call.calldata().ok_or_else(|| eyre::eyre!("Failed to encode calldata"))?;
+    Ok(Some(calldata))
+}
+
+/// Helper function to send a cancellation call and wait for a successful receipt
+async fn send_cancellation<M: Middleware + 'static>(call: ContractCall<M, ()>, request_type: &str) -> Result<(TxHash, TransactionReceipt)> {
+    let pending_tx = call.send().await.map_err(|e| eyre::eyre!("{} cancellation failed to send: {}", request_type, e))?;
+    let tx_hash = pending_tx.tx_hash();
+    debug!(tx_hash = ?tx_hash, "{} cancellation transaction sent, waiting for confirmation", request_type);
+
+    let receipt = match pending_tx.await? {
+        Some(receipt) => {
+            if receipt.status == Some(1.into()) {
+                receipt
+            } else {
+                return Err(eyre::eyre!("{} cancellation failed with status {:?}: {:?}", request_type, receipt.status, receipt));
+            }
+        },
+        None => {
+            return Err(eyre::eyre!("{} cancellation transaction failed: no receipt returned", request_type));
+        }
+    };
+
+    Ok((tx_hash, receipt))
 }
 
 //----------------------------------------------------------------------------------------------------------------------------------------
@@ -0,0 +1,159 @@
+use ethers::types::{Address, U256};
+use eyre::Result;
+use reqwest::Client;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::*;
+use serde::Deserialize;
+use serde_json::json;
+use chrono::{DateTime, Utc};
+use tracing::{debug, warn, instrument};
+
+use crate::constants::GMX_CANDLES_PATH;
+use crate::data_ingestion::market::market_utils::u256_to_decimal_scaled;
+
+const SUBGRAPH_PAGE_SIZE: usize = 1000; // The Graph / Subsquid maximum page size
+const CANDLES_MAX_LIMIT: i64 = 10_000; // Maximum candles returned by one GMX API request
+
+const COLLECTED_FEES_QUERY: &str = r#"
+query CollectedFees($market: String!, $from: Int!, $to: Int!, $first: Int!) {
+  collectedMarketFeesInfos(
+    where: { marketAddress: $market, period: "1h", timestampGroup_gte: $from, timestampGroup_lt: $to }
+    orderBy: timestampGroup
+    orderDirection: asc
+    first: $first
+  ) {
+    timestampGroup
+    feeUsdForPool
+  }
+}
+"#;
+
+#[derive(Debug, Deserialize)]
+struct GraphQlResponse<T> {
+    data: Option<T>,
+    #[serde(default)]
+    errors: Vec<GraphQlError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQlError {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CollectedFeesData {
+    collected_market_fees_infos: Vec<CollectedFeesEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CollectedFeesEntry {
+    timestamp_group: i64,
+    fee_usd_for_pool: String, // 30 decimals
+}
+
+#[derive(Debug, Deserialize)]
+struct CandlesResponse {
+    candles: Vec<(i64, f64, f64, f64, f64)>, // [timestamp, open, high, low, close], newest first
+}
+
+/// Fees a GM pool collected over one hour, from the stats subgraph
+#[derive(Debug, Clone)]
+pub struct HourlyMarketFees {
+    pub timestamp: DateTime<Utc>,    // Start of the hour
+    pub fees_usd: Decimal,        // Fees accrued to the pool over the hour
+}
+
+/// Hourly oracle price candle from the GMX API
+#[derive(Debug, Clone)]
+pub struct PriceCandle {
+    pub timestamp: DateTime<Utc>, // Start of the hour
+    pub close: Decimal,
+}
+
+/// Fetch the hourly fees collected by a GM pool in [start, end) from a GMX synthetics stats subgraph
+/// (The Graph or Subsquid deployment), paging through the results in timestamp order
+#[instrument(skip(client))]
+pub async fn fetch_hourly_market_fees(
+    client: &Client,
+    subgraph_url: &str,
+    market: Address,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Vec<HourlyMarketFees>> {
+    let mut fees = Vec::new();
+    let mut from = start.timestamp();
+    loop {
+        let body = json!({
+            "query": COLLECTED_FEES_QUERY,
+            "variables": {
+                "market": format!("{:?}", market), // Subgraph addresses are lowercase hex
+                "from": from,
+                "to": end.timestamp(),
+                "first": SUBGRAPH_PAGE_SIZE,
+            },
+        });
+        let res: GraphQlResponse<CollectedFeesData> = client
+            .post(subgraph_url)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if let Some(error) = res.errors.first() {
+            return Err(eyre::eyre!("Subgraph query failed: {}", error.message));
+        }
+        let page = res.data.map(|data| data.collected_market_fees_infos).unwrap_or_default();
+        let page_len = page.len();
+
+        for entry in page {
+            from = entry.timestamp_group + 1;
+            let Some(timestamp) = DateTime::from_timestamp(entry.timestamp_group, 0) else {
+                continue;
+            };
+            let Ok(fee_usd) = U256::from_dec_str(&entry.fee_usd_for_pool) else {
+                warn!(market = ?market, timestamp_group = entry.timestamp_group, "Skipping malformed collected fees entry");
+                continue;
+            };
+            fees.push(HourlyMarketFees { timestamp, fees_usd: u256_to_decimal_scaled(fee_usd) });
+        }
+
+        if page_len < SUBGRAPH_PAGE_SIZE {
+            break;
+        }
+    }
+    debug!(market = ?market, count = fees.len(), "Fetched hourly market fees");
+    Ok(fees)
+}
+
+/// Fetch hourly oracle price candles for a token since the given time from the GMX API, oldest first
+#[instrument(skip(client))]
+pub async fn fetch_hourly_price_candles(client: &Client, api_url: &str, token_symbol: &str, since: DateTime<Utc>, now: DateTime<Utc>) -> Result<Vec<PriceCandle>> {
+    let limit = ((now - since).num_hours() + 1).clamp(1, CANDLES_MAX_LIMIT).to_string();
+    let res: CandlesResponse = client
+        .get(format!("{}{}", api_url, GMX_CANDLES_PATH))
+        .query(&[("tokenSymbol", token_symbol), ("period", "1h"), ("limit", limit.as_str())])
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    let mut candles: Vec<PriceCandle> = res.candles.into_iter()
+        .filter_map(|(timestamp, _open, _high, _low, close)| {
+            let timestamp = DateTime::from_timestamp(timestamp, 0)?;
+            if timestamp < since {
+                return None;
+            }
+            Some(PriceCandle {
+                timestamp,
+                close: Decimal::from_f64(close)?,
+            })
+        })
+        .collect();
+    candles.sort_by_key(|candle| candle.timestamp);
+    debug!(token = token_symbol, count = candles.len(), "Fetched hourly price candles");
+    Ok(candles)
+}
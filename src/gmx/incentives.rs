@@ -0,0 +1,137 @@
+use ethers::prelude::*;
+use eyre::Result;
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::str::FromStr;
+use tracing::{debug, warn, instrument};
+
+use crate::config::Config;
+use crate::constants::{GMX_INCENTIVES_PATH, ARB_TOKEN_ADDRESS};
+use crate::wallet::WalletManager;
+
+abigen!(
+    RewardsDistributor,
+    r#"[
+        function claimable(address account, address token) external view returns (uint256)
+        function claim(address[] tokens, address receiver) external returns (uint256[])
+    ]"#
+);
+
+const REWARD_TOKEN_DECIMALS: u32 = 18; // ARB
+
+#[derive(Debug, Deserialize)]
+struct IncentivesResponse {
+    lp: Option<LpIncentivesResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LpIncentivesResponse {
+    is_active: bool,
+    #[serde(default)]
+    token: Option<String>,
+    period: u64, // Distribution period (seconds)
+    #[serde(default)]
+    rewards_per_market: HashMap<String, String>, // Market address -> reward amount for the period (wei)
+}
+
+/// Incentive emission to one GM pool over the current distribution period
+#[derive(Debug, Clone)]
+pub struct MarketIncentive {
+    pub market: Address,
+    pub reward_token: Address,
+    pub reward_amount: Decimal, // Reward token amount distributed over the period
+    pub period_secs: u64,
+}
+
+/// Fetch the active LP incentive emissions per GM pool from the GMX API (none on testnet)
+#[instrument(skip(config))]
+pub async fn fetch_lp_incentives(config: &Config) -> Result<Vec<MarketIncentive>> {
+    if config.network_mode != "prod" {
+        return Ok(Vec::new());
+    }
+
+    let client = Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .build()?;
+    let res: IncentivesResponse = client
+        .get(format!("{}{}", config.gmx_api_url, GMX_INCENTIVES_PATH))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    let Some(lp) = res.lp.filter(|lp| lp.is_active && lp.period > 0) else {
+        debug!("No active LP incentives");
+        return Ok(Vec::new());
+    };
+    let reward_token = Address::from_str(lp.token.as_deref().unwrap_or(ARB_TOKEN_ADDRESS))?;
+
+    let mut incentives = Vec::with_capacity(lp.rewards_per_market.len());
+    for (market, amount) in lp.rewards_per_market {
+        let (Ok(market), Ok(amount)) = (Address::from_str(&market), Decimal::from_str(&amount)) else {
+            warn!(market = %market, amount = %amount, "Skipping malformed LP incentive entry");
+            continue;
+        };
+        incentives.push(MarketIncentive {
+            market,
+            reward_token,
+            reward_amount: amount / Decimal::from(10u64.pow(REWARD_TOKEN_DECIMALS)),
+            period_secs: lp.period,
+        });
+    }
+    debug!(count = incentives.len(), period_secs = lp.period, "Fetched LP incentives");
+    Ok(incentives)
+}
+
+/// Get the rewards accrued to the wallet in the configured rewards distributor, per reward token
+#[instrument(skip(config, wallet_manager))]
+pub async fn get_claimable_rewards(
+    config: &Config,
+    wallet_manager: &WalletManager,
+    tokens: &[Address],
+) -> Result<Vec<U256>> {
+    let distributor_address = config.gmx_rewards_distributor
+        .ok_or_else(|| eyre::eyre!("GMX_REWARDS_DISTRIBUTOR is not configured"))?;
+    let distributor = RewardsDistributor::new(distributor_address, config.alchemy_provider.clone());
+    let mut claimable = Vec::with_capacity(tokens.len());
+    for token in tokens {
+        claimable.push(distributor.claimable(wallet_manager.address, *token).call().await?);
+    }
+    Ok(claimable)
+}
+
+/// Claim accrued rewards for the given tokens to the wallet
+#[instrument(skip(config, wallet_manager))]
+pub async fn claim_rewards(
+    config: &Config,
+    wallet_manager: &WalletManager,
+    tokens: Vec<Address>,
+) -> Result<(TxHash, TransactionReceipt)> {
+    let distributor_address = config.gmx_rewards_distributor
+        .ok_or_else(|| eyre::eyre!("GMX_REWARDS_DISTRIBUTOR is not configured"))?;
+    let distributor = RewardsDistributor::new(distributor_address, wallet_manager.signer.clone());
+
+    let call = distributor.claim(tokens, wallet_manager.address).from(wallet_manager.address);
+    let pending_tx = call.send().await?;
+    let tx_hash = pending_tx.tx_hash();
+    debug!(tx_hash = ?tx_hash, "Reward claim transaction sent, waiting for confirmation");
+
+    let receipt = match pending_tx.await? {
+        Some(receipt) => {
+            if receipt.status == Some(1.into()) {
+                receipt
+            } else {
+                return Err(eyre::eyre!("Reward claim failed with status {:?}: {:?}", receipt.status, receipt));
+            }
+        },
+        None => {
+            return Err(eyre::eyre!("Reward claim transaction failed: no receipt returned"));
+        }
+    };
+
+    Ok((tx_hash, receipt))
+}
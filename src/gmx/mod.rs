@@ -6,4 +6,8 @@ pub mod event_listener_utils;
 pub mod event_fetcher;
 pub mod multicall;
 pub mod exchange_router;
-pub mod exchange_router_utils;
\ No newline at end of file
+pub mod exchange_router_utils;
+pub mod incentives;
+pub mod rpc_batch;
+pub mod history;
+pub mod callback_receiver;
\ No newline at end of file
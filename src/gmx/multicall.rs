@@ -7,7 +7,7 @@ use crate::config::Config;
 use super::reader_utils::{MarketProps, MarketPrices, MarketInfo, MarketPoolValueInfoProps};
 use super::reader::PnlFactorType;
 
-/// Batch data structure containing all market data fetched via multicall
+/// Batch data structure containing all market data fetched via multicall and JSON-RPC batch requests
 #[derive(Debug, Clone)]
 pub struct BatchMarketData {
     pub market_infos: HashMap<Address, MarketInfo>,
@@ -33,7 +33,7 @@ impl BatchMarketData {
     }
 }
 
-/// Fetch all market data using multicall batches
+/// Fetch all market data using batches: reader calls go out as JSON-RPC batch requests, datastore reads through multicall
 #[instrument(skip(config, markets), fields(market_count = markets.len()))]
 pub async fn fetch_all_market_data_batch(
     config: &Config,
@@ -1,12 +1,13 @@
 use ethers::prelude::*;
 use ethers::utils::keccak256;
-use ethers::contract::Multicall;
 use eyre::Result;
 use std::collections::HashMap;
+use std::sync::Arc;
 use tracing::{debug, instrument};
 
 use crate::config::Config;
 use super::reader_utils;
+use super::rpc_batch;
 
 abigen!(
     Reader,
@@ -81,7 +82,33 @@ pub async fn get_market_token_price(config: &Config, market_props: reader_utils:
     Ok((market_token_price, market_pool_value_info_props))
 }
 
-/// Batch version: Fetch market info for multiple markets using multicall
+/// Fetch the market token price as it was at a past block (the provider needs archive state for old blocks)
+pub async fn get_market_token_price_at_block(config: &Config, provider: Arc<Provider<Http>>, market_props: reader_utils::MarketProps,
+            market_prices: reader_utils::MarketPrices, pnl_factor_type: PnlFactorType, maximize: bool, block: u64) -> Result<I256> {
+    let reader = Reader::new(config.gmx_reader, provider);
+
+    let pnl_factor_type_string = match pnl_factor_type {
+        PnlFactorType::Deposit => "MAX_PNL_FACTOR_FOR_DEPOSITS".to_string(),
+        PnlFactorType::Withdrawal => "MAX_PNL_FACTOR_FOR_WITHDRAWALS".to_string(),
+        PnlFactorType::Trader => "MAX_PNL_FACTOR_FOR_TRADERS".to_string(),
+    };
+    let pnl_factor_type_encoded = ethers::abi::encode(&[ethers::abi::Token::String(pnl_factor_type_string)]);
+    let pnl_factor_type = H256::from_slice(&keccak256(&pnl_factor_type_encoded));
+
+    let raw_response = reader.get_market_token_price(
+        config.gmx_datastore,
+        market_props.into(),
+        market_prices.index_token_price.into(),
+        market_prices.long_token_price.into(),
+        market_prices.short_token_price.into(),
+        pnl_factor_type.into(),
+        maximize,
+    ).block(block).call().await?;
+
+    Ok(raw_response.0)
+}
+
+/// Batch version: Fetch market info for multiple markets using a JSON-RPC batch request
 #[instrument(skip(config, markets), fields(market_count = markets.len()))]
 pub async fn get_market_info_batch(
     config: &Config,
@@ -89,23 +116,20 @@ pub async fn get_market_info_batch(
 ) -> Result<HashMap<Address, reader_utils::MarketInfo>> {
     debug!(market_count = markets.len(), "Fetching market info batch");
     
-    // Create multicall instance
-    let mut multicall = Multicall::new(config.alchemy_provider.clone(), None).await?;
     let reader = Reader::new(config.gmx_reader, config.alchemy_provider.clone());
     
-    // Add all market info calls
-    for (market_props, market_prices) in markets {
-        let call = reader.get_market_info(
+    // Build all market info calls
+    let calls: Vec<_> = markets.iter()
+        .map(|(market_props, market_prices)| reader.get_market_info(
             config.gmx_datastore,
             market_prices.clone().into(),
             market_props.market_token,
-        );
-        multicall.add_call(call, false);
-    }
+        ))
+        .collect();
     
-    // Execute the multicall
-    debug!(call_count = markets.len(), "Executing market info multicall");
-    let results: Vec<reader::MarketInfo> = multicall.call_array().await?;
+    // Execute the batch
+    debug!(call_count = calls.len(), "Executing market info batch request");
+    let results: Vec<reader::MarketInfo> = rpc_batch::call_batch(config, &calls).await?;
     
     // Parse results using Into trait like non-batch methods
     let mut market_infos = HashMap::new();
@@ -126,7 +150,8 @@ pub async fn get_market_info_batch(
     Ok(market_infos)
 }
 
-/// Batch version: Fetch market token prices for multiple markets using multicall
+/// Batch version: Fetch market token prices for multiple markets using a JSON-RPC batch request
+/// (the full prices structs make these calls expensive to aggregate through multicall)
 #[instrument(skip(config, markets), fields(market_count = markets.len()))]
 pub async fn get_market_token_price_batch(
     config: &Config,
@@ -135,8 +160,6 @@ pub async fn get_market_token_price_batch(
 ) -> Result<(HashMap<Address, (I256, reader_utils::MarketPoolValueInfoProps)>, HashMap<Address, (I256, reader_utils::MarketPoolValueInfoProps)>)> {
     debug!(market_count = markets.len(), "Fetching market token price batch");
     
-    // Create multicall instance
-    let mut multicall = Multicall::new(config.alchemy_provider.clone(), None).await?;
     let reader = Reader::new(config.gmx_reader, config.alchemy_provider.clone());
     
     // Build PNL factor type 
@@ -148,37 +171,25 @@ pub async fn get_market_token_price_batch(
     let pnl_factor_type_encoded = ethers::abi::encode(&[ethers::abi::Token::String(pnl_factor_type_string)]);
     let pnl_factor_type_hash = H256::from_slice(&keccak256(&pnl_factor_type_encoded));
     
-    // Add min price calls (maximize = false)
-    for (market_props, market_prices) in markets {
-        let call = reader.get_market_token_price(
-            config.gmx_datastore,
-            market_props.clone().into(),
-            market_prices.index_token_price.clone().into(),
-            market_prices.long_token_price.clone().into(),
-            market_prices.short_token_price.clone().into(),
-            pnl_factor_type_hash.into(),
-            false, // minimize for min price
-        );
-        multicall.add_call(call, false);
-    }
-    
-    // Add max price calls (maximize = true)
-    for (market_props, market_prices) in markets {
-        let call = reader.get_market_token_price(
-            config.gmx_datastore,
-            market_props.clone().into(),
-            market_prices.index_token_price.clone().into(),
-            market_prices.long_token_price.clone().into(),
-            market_prices.short_token_price.clone().into(),
-            pnl_factor_type_hash.into(),
-            true, // maximize for max price
-        );
-        multicall.add_call(call, false);
+    // Add min price calls (maximize = false), then max price calls (maximize = true)
+    let mut calls = Vec::with_capacity(markets.len() * 2);
+    for maximize in [false, true] {
+        for (market_props, market_prices) in markets {
+            calls.push(reader.get_market_token_price(
+                config.gmx_datastore,
+                market_props.clone().into(),
+                market_prices.index_token_price.clone().into(),
+                market_prices.long_token_price.clone().into(),
+                market_prices.short_token_price.clone().into(),
+                pnl_factor_type_hash.into(),
+                maximize,
+            ));
+        }
     }
     
-    // Execute the multicall
-    debug!(call_count = markets.len() * 2, "Executing market token price multicall");
-    let results: Vec<(I256, reader::MarketPoolValueInfoProps)> = multicall.call_array().await?;
+    // Execute the batch
+    debug!(call_count = calls.len(), "Executing market token price batch request");
+    let results: Vec<(I256, reader::MarketPoolValueInfoProps)> = rpc_batch::call_batch(config, &calls).await?;
     
     // Parse min prices (first half of results)
     let mut min_prices = HashMap::new();
@@ -258,6 +269,29 @@ pub async fn get_withdrawal_amount_out(
     Ok(raw_response)
 }
 
+/// Expected output of swapping `amount_in` of `token_in` through a GM pool, with the protocol-side price impact and fees
+pub async fn get_swap_amount_out(
+    config: &Config,
+    market_props: reader_utils::MarketProps,
+    market_prices: reader_utils::MarketPrices,
+    token_in: Address,
+    amount_in: U256,
+    ui_fee_receiver: Address,
+) -> Result<reader_utils::SwapAmountOut> {
+    let reader = Reader::new(config.gmx_reader, config.alchemy_provider.clone());
+
+    let raw_response = reader.get_swap_amount_out(
+        config.gmx_datastore,
+        market_props.into(),
+        market_prices.into(),
+        token_in,
+        amount_in,
+        ui_fee_receiver,
+    ).call().await?;
+
+    Ok(raw_response.into())
+}
+
 //----------------------------------------------------------------------------------------------------------------------------------------
 
 impl From<reader_utils::MarketProps> for MarketProps {
@@ -85,6 +85,25 @@ pub struct MarketPoolValueInfoProps {
     pub impact_pool_amount: U256,          // Amound of tokens reserved for price impact smoothing
 }
 
+// Fees component of the Reader.getSwapAmountOut return
+#[derive(Debug, Clone)]
+pub struct SwapFees {
+    pub fee_receiver_amount: U256,      // Swap fee paid to the GMX fee receiver (in raw input token units)
+    pub fee_amount_for_pool: U256,      // Swap fee kept by the pool for liquidity providers
+    pub amount_after_fees: U256,        // Input amount remaining after all fees
+    pub ui_fee_receiver: Address,       // UI fee receiver the fees were computed for
+    pub ui_fee_receiver_factor: U256,   // UI fee factor of the receiver
+    pub ui_fee_amount: U256,            // Fee paid to the UI fee receiver
+}
+
+// Return type for Reader.getSwapAmountOut
+#[derive(Debug, Clone)]
+pub struct SwapAmountOut {
+    pub amount_out: U256,           // Output token amount after fees and price impact (in raw output token units)
+    pub price_impact_usd: I256,     // Price impact of the swap in USD (30 decimals, negative is a cost)
+    pub fees: SwapFees,             // Breakdown of fees charged on the input amount
+}
+
 #[derive(Debug, Clone, Copy)]
 #[repr(u8)]
 pub enum SwapPricingType {
@@ -209,6 +228,29 @@ impl From<reader::MarketPoolValueInfoProps> for MarketPoolValueInfoProps {
     }
 }
 
+impl From<reader::SwapFees> for SwapFees {
+    fn from(f: reader::SwapFees) -> Self {
+        Self {
+            fee_receiver_amount: f.fee_receiver_amount,
+            fee_amount_for_pool: f.fee_amount_for_pool,
+            amount_after_fees: f.amount_after_fees,
+            ui_fee_receiver: f.ui_fee_receiver,
+            ui_fee_receiver_factor: f.ui_fee_receiver_factor,
+            ui_fee_amount: f.ui_fee_amount,
+        }
+    }
+}
+
+impl From<(U256, I256, reader::SwapFees)> for SwapAmountOut {
+    fn from((amount_out, price_impact_usd, fees): (U256, I256, reader::SwapFees)) -> Self {
+        Self {
+            amount_out,
+            price_impact_usd,
+            fees: fees.into(),
+        }
+    }
+}
+
 impl SwapPricingType {
     pub fn as_u8(&self) -> u8 {
         *self as u8
@@ -0,0 +1,88 @@
+use std::borrow::Borrow;
+use ethers::prelude::*;
+use ethers::abi::Detokenize;
+use eyre::Result;
+use serde::Deserialize;
+use serde_json::json;
+use tracing::{debug, instrument};
+
+use crate::config::Config;
+
+/// Maximum number of calls per JSON-RPC batch request (providers reject or throttle larger batches)
+const RPC_BATCH_MAX_SIZE: usize = 50;
+
+#[derive(Debug, Deserialize)]
+struct RpcResponse {
+    id: usize,
+    result: Option<Bytes>,
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+/// Execute contract view calls as JSON-RPC batch `eth_call` requests, so each chunk of calls costs a single HTTP round trip.
+/// For calls too heavy to aggregate cheaply through Multicall3. Results are decoded in call order, any failed call fails the batch.
+#[instrument(skip(config, calls), fields(call_count = calls.len()))]
+pub async fn call_batch<B, M, D>(config: &Config, calls: &[FunctionCall<B, M, D>]) -> Result<Vec<D>>
+where
+    B: Borrow<M>,
+    M: Middleware,
+    D: Detokenize,
+{
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .build()?;
+    let rpc_url = config.alchemy_provider.as_ref().url().clone();
+
+    let mut results = Vec::with_capacity(calls.len());
+    for (chunk_index, chunk) in calls.chunks(RPC_BATCH_MAX_SIZE).enumerate() {
+        let requests: Vec<serde_json::Value> = chunk.iter()
+            .enumerate()
+            .map(|(id, call)| json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": "eth_call",
+                "params": [
+                    {
+                        "to": call.tx.to_addr(),
+                        "data": call.tx.data(),
+                    },
+                    "latest",
+                ],
+            }))
+            .collect();
+
+        debug!(chunk_index = chunk_index, chunk_size = chunk.len(), "Sending JSON-RPC batch request");
+        let mut responses: Vec<RpcResponse> = client
+            .post(rpc_url.clone())
+            .json(&requests)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        // Batch responses may come back in any order
+        responses.sort_by_key(|response| response.id);
+        if responses.len() != chunk.len() {
+            return Err(eyre::eyre!("JSON-RPC batch returned {} responses for {} calls", responses.len(), chunk.len()));
+        }
+
+        for (call, response) in chunk.iter().zip(responses) {
+            if let Some(error) = response.error {
+                return Err(eyre::eyre!("eth_call {} in JSON-RPC batch failed ({}): {}", response.id, error.code, error.message));
+            }
+            let bytes = response.result
+                .ok_or_else(|| eyre::eyre!("eth_call {} in JSON-RPC batch returned no result", response.id))?;
+            let tokens = call.function.decode_output(&bytes)?;
+            results.push(D::from_tokens(tokens)?);
+        }
+    }
+
+    debug!(result_count = results.len(), "JSON-RPC batch calls completed");
+    Ok(results)
+}
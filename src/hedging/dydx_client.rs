@@ -13,11 +13,14 @@ use ethers::types::{
 };
 use tokio::time::{sleep, Duration, Instant};
 use tokio::task::JoinHandle;
+use futures::future::BoxFuture;
+use chrono::{DateTime, Utc};
 use dydx::{
     config::ClientConfig,
     node::{
         NodeClient,
         Wallet,
+        Account,
         OrderBuilder,
         OrderSide,
         OrderTimeInForce,
@@ -26,6 +29,7 @@ use dydx::{
     },
     indexer::{
         IndexerClient,
+        GetHistoricalFundingOpts,
         types::{
             Ticker, 
             PerpetualMarket, 
@@ -39,8 +43,11 @@ use dydx::{
     },
 };
 use dydx_proto::dydxprotocol::{
-    subaccounts::Subaccount as SubaccountInfo,
+    subaccounts::{Subaccount as SubaccountInfo, SubaccountId},
+    clob::MsgPlaceOrder,
+    sending::{MsgDepositToSubaccount, MsgWithdrawFromSubaccount},
 };
+use dydx_proto::ToAny;
 use cosmrs::{
     crypto::secp256k1,
     bip32::{Mnemonic, DerivationPath, Language},
@@ -51,18 +58,23 @@ use prost::Message as ProstMessage;
 
 use crate::config;
 use crate::wallet::WalletManager;
+use crate::db::db_manager::DbManager;
+use crate::db::models::orders::{OrderModel, NewOrderModel, HedgeOrderStatus};
+use crate::db::models::funding_rates::NewFundingRateModel;
+use crate::strategy::compliance;
 use super::hedge_utils;
+use super::hedge_venue::{PerpVenue, HedgeMarket};
 use super::skip_go;
 
 const MAX_FEE_PER_GAS_BUFFER: f64 = 1.05; // 5% above the current gas price
 
-const ARBITRUM_CHAIN_ID: &str = "42161";
-const DYDX_CHAIN_ID: &str = "dydx-mainnet-1";
-const ARBITRUM_USDC_DENOM: &str = "0xaf88d065e77c8cC2239327C5EDb3A432268e5831";
-const DYDX_USDC_DENOM: &str = "ibc/8E27BA2D5493AF5636760E354E46004562C46AB7EC0CC4C1CA14E9E20E2545B5";
 const USDC_DECIMALS: u8 = 6;
+const USDC_ASSET_ID: u32 = 0; // dYdX asset ID of USDC in subaccounts
 
 const DYDX_SUBACCOUNT_NUM: u32 = 0;
+pub const DYDX_VENUE: &str = "dydx";
+const ORDER_GOOD_TIL_BLOCKS: u32 = 40;
+const FUNDING_HISTORY_PAGE_LIMIT: u32 = 100; // Max page size of the indexer historical funding endpoint
 
 // ERC20 ABI for token approvals
 abigen!(
@@ -73,24 +85,76 @@ abigen!(
     ]"#
 );
 
+/// dYdX chain and the Arbitrum end of its USDC route for a network mode
+#[derive(Debug, Clone, Copy)]
+pub struct DydxNetwork {
+    pub client_config_path: &'static str, // Node and indexer endpoints, unless DYDX_CONFIG_PATH overrides it
+    pub dydx_chain_id: &'static str,
+    pub dydx_usdc_denom: &'static str,
+    pub arbitrum_chain_id: &'static str,
+    pub arbitrum_usdc_denom: &'static str,
+}
+
+pub const DYDX_MAINNET: DydxNetwork = DydxNetwork {
+    client_config_path: "src/hedging/dydx_mainnet.toml",
+    dydx_chain_id: "dydx-mainnet-1",
+    dydx_usdc_denom: "ibc/8E27BA2D5493AF5636760E354E46004562C46AB7EC0CC4C1CA14E9E20E2545B5",
+    arbitrum_chain_id: "42161",
+    arbitrum_usdc_denom: "0xaf88d065e77c8cC2239327C5EDb3A432268e5831",
+};
+
+pub const DYDX_TESTNET: DydxNetwork = DydxNetwork {
+    client_config_path: "src/hedging/dydx_testnet.toml",
+    dydx_chain_id: "dydx-testnet-4",
+    dydx_usdc_denom: "ibc/8E27BA2D5493AF5636760E354E46004562C46AB7EC0CC4C1CA14E9E20E2545B5",
+    arbitrum_chain_id: "421614", // Arbitrum Sepolia
+    arbitrum_usdc_denom: "0x75faf114eafb1BDbe2F0316DF893fd58CE46AA4d",
+};
+
+impl DydxNetwork {
+    pub fn for_network_mode(network_mode: &str) -> Result<Self> {
+        match network_mode {
+            "prod" => Ok(DYDX_MAINNET),
+            "test" => Ok(DYDX_TESTNET),
+            _ => Err(eyre::eyre!("Unknown network mode: {}", network_mode)),
+        }
+    }
+}
+
+/// USDC transfer sent through a SkipGo route, tracked by its transactions until the funds arrive
+#[derive(Debug, Clone)]
+pub struct SkipGoTransfer {
+    pub amount: Decimal, // USDC sent on the source chain, including route fees
+    pub expected_amount_out: Decimal, // USDC the route quoted to deliver on the destination chain
+    pub estimated_time_secs: u64,
+    pub submitted_txs: Vec<(String, String)>, // (Tx hash, chain ID) of each transaction sent, in order
+    pub gas_cost_usd: Decimal, // Arbitrum gas of the EVM transactions sent
+}
+
 pub struct DydxClient {
     config: Arc<config::Config>,
     wallet_manager: Arc<WalletManager>,
+    db_manager: Arc<DbManager>,
     node_client: NodeClient,
     indexer_client: IndexerClient,
     dydx_wallet: Wallet,
     dydx_address: String,
+    network: DydxNetwork,
+    client_config_path: String,
     active_transfer_polling_tasks: Arc<tokio::sync::Mutex<Vec<JoinHandle<()>>>>,
 }
 
 impl DydxClient {
-    pub async fn new(cfg: Arc<config::Config>, wallet_manager: Arc<WalletManager>) -> Result<Self> {
+    pub async fn new(cfg: Arc<config::Config>, wallet_manager: Arc<WalletManager>, db_manager: Arc<DbManager>) -> Result<Self> {
         // Initialize crypto provider
         config::init_crypto_provider();
 
-        let config = ClientConfig::from_file("src/hedging/dydx_mainnet.toml")
+        let network = DydxNetwork::for_network_mode(&cfg.network_mode)?;
+        let client_config_path = cfg.dydx_config_path.clone()
+            .unwrap_or_else(|| network.client_config_path.to_string());
+        let config = ClientConfig::from_file(&client_config_path)
             .await
-            .map_err(|e| eyre::eyre!("Failed to load dYdX config: {}", e))?;
+            .map_err(|e| eyre::eyre!("Failed to load dYdX config from {}: {}", client_config_path, e))?;
         let node_client = NodeClient::connect(config.node)
             .await
             .map_err(|e| eyre::eyre!("Failed to connect to dYdX node: {}", e))?;
@@ -99,18 +163,32 @@ impl DydxClient {
             .map_err(|e| eyre::eyre!("Failed to create dYdX wallet from mnemonic: {}", e))?;
         let dydx_address = derive_cosmos_address_from_mnemonic(&cfg, "dydx", None)
             .map_err(|e| eyre::eyre!("Failed to derive dYdX address from mnemonic: {}", e))?;
+        info!(
+            dydx_chain_id = network.dydx_chain_id,
+            client_config_path = %client_config_path,
+            sandbox = cfg.dydx_sandbox,
+            "dYdX client connected"
+        );
         
         Ok(Self {
             config: cfg,
             wallet_manager,
+            db_manager,
             node_client,
             indexer_client,
             dydx_wallet,
             dydx_address,
+            network,
+            client_config_path,
             active_transfer_polling_tasks: Arc::new(tokio::sync::Mutex::new(Vec::new())),
         })
     }
 
+    /// Whether orders and transfers are only signed and simulated, never broadcast (DYDX_SANDBOX)
+    pub fn is_sandbox(&self) -> bool {
+        self.config.dydx_sandbox
+    }
+
     #[instrument(skip(self))]
     pub async fn wait_for_active_tasks(&self) {
         let mut tasks = self.active_transfer_polling_tasks.lock().await;
@@ -149,7 +227,7 @@ impl DydxClient {
 
     #[instrument(skip(self))]
     pub async fn get_token_perp_map(&self) -> Result<HashMap<String, Option<PerpetualMarket>>> {
-        let token_symbols: Vec<String> = self.wallet_manager.asset_tokens.values()
+        let token_symbols: Vec<String> = self.wallet_manager.tokens().asset_tokens.values()
             .map(|token| token.symbol.clone())
             .collect();
 
@@ -217,13 +295,84 @@ impl DydxClient {
         Ok(max_leverage)
     }
 
-    pub async fn submit_perp_order(&mut self, token: &str, size: Decimal, side_is_buy: bool) -> Result<()> {
+    /// Fetch one page of historical hourly funding for a perp ticker, newest first
+    #[instrument(skip(self))]
+    pub async fn get_historical_funding(
+        &self,
+        ticker: &str,
+        effective_before_or_at: Option<DateTime<Utc>>,
+    ) -> Result<Vec<NewFundingRateModel>> {
+        let opts = GetHistoricalFundingOpts {
+            limit: Some(FUNDING_HISTORY_PAGE_LIMIT),
+            effective_before_or_at,
+            ..Default::default()
+        };
+        let history = self.indexer_client.markets()
+            .get_perpetual_market_historical_funding(&ticker.to_string().into(), Some(opts))
+            .await
+            .map_err(|e| eyre::eyre!("Failed to fetch historical funding for {}: {}", ticker, e))?;
+
+        history.into_iter()
+            .map(|funding| Ok(NewFundingRateModel {
+                ticker: ticker.to_string(),
+                effective_at: funding.effective_at,
+                rate: Decimal::from_str(&funding.rate.to_plain_string())?,
+            }))
+            .collect()
+    }
+
+    /// Store historical funding for every perp hedging one of our tokens, paging backwards from now
+    /// until the latest stored rate (or `backfill_since` when nothing newer is stored). Returns the number of rates inserted.
+    #[instrument(skip(self), fields(on_close = true))]
+    pub async fn sync_funding_rates(&self, backfill_since: DateTime<Utc>) -> Result<u64> {
+        let mut tickers: Vec<String> = self.get_token_perp_map().await?
+            .into_iter()
+            .filter(|(_, market)| market.is_some())
+            .map(|(symbol, _)| hedge_utils::get_dydx_perp_ticker(&symbol))
+            .collect();
+        tickers.sort();
+        tickers.dedup();
+
+        let mut total_inserted = 0;
+        for ticker in tickers {
+            let latest_stored = self.db_manager.get_latest_funding_rate_timestamp(&ticker).await?;
+            let since = latest_stored.map_or(backfill_since, |t| t.max(backfill_since));
+
+            let mut before = None;
+            loop {
+                let page = self.get_historical_funding(&ticker, before).await?;
+                let page_len = page.len();
+                let oldest = page.iter().map(|r| r.effective_at).min();
+                let new_rates: Vec<NewFundingRateModel> = page.into_iter()
+                    .filter(|r| r.effective_at > since)
+                    .collect();
+                total_inserted += self.db_manager.insert_funding_rates(&new_rates).await?;
+
+                // Stop once the page reaches already stored history or the indexer has nothing older
+                match oldest {
+                    Some(oldest) if oldest > since && page_len as u32 == FUNDING_HISTORY_PAGE_LIMIT => {
+                        before = Some(oldest - chrono::Duration::seconds(1));
+                    }
+                    _ => break,
+                }
+            }
+            debug!(ticker = %ticker, since = %since, "Funding rates synced");
+        }
+
+        info!(inserted = total_inserted, "Funding rate sync completed");
+        Ok(total_inserted)
+    }
+
+    pub async fn submit_perp_order(&mut self, token: &str, size: Decimal, side_is_buy: bool, rebalance_id: Option<i32>) -> Result<()> {
         let log_string = self.get_perp_order_log_string(&token, size, side_is_buy, false)?;
 
-        self.execute_perp_order(&token, size, side_is_buy, log_string).await
+        // Orders adding to hedges are checked against the compliance rules, reductions only ever lower leverage
+        self.ensure_hedge_order_compliant(token, size, side_is_buy).await?;
+
+        self.execute_perp_order(&token, size, side_is_buy, false, None, rebalance_id, log_string).await
     }
 
-    pub async fn reduce_perp_position(&mut self, token: &str, reduce_by: Option<Decimal>) -> Result<()> {
+    pub async fn reduce_perp_position(&mut self, token: &str, reduce_by: Option<Decimal>, rebalance_id: Option<i32>) -> Result<()> {
         let ticker = hedge_utils::get_dydx_perp_ticker(token);
         let dydx_subaccount_perp_positions_initial = self.get_dydx_subaccount_perp_positions().await?;
         let perp_position_size = dydx_subaccount_perp_positions_initial.get(&ticker)
@@ -235,10 +384,69 @@ impl DydxClient {
         };
         let log_string = self.get_perp_order_log_string(&token, reduce_by, side_is_buy, true)?;
 
-        self.execute_perp_order(token, reduce_by, side_is_buy, log_string).await
+        self.execute_perp_order(token, reduce_by, side_is_buy, true, None, rebalance_id, log_string).await
     }
 
-    async fn execute_perp_order(&mut self, token: &str, size: Decimal, side_is_buy: bool, log_string: String) -> Result<()> {
+    /// Outstanding (submitted, open or partially filled) hedge orders, optionally for a single token,
+    /// so hedging logic can account for adjustments that are already in flight
+    pub async fn get_open_orders(&self, token: Option<&str>) -> Result<Vec<OrderModel>> {
+        let ticker = token.map(hedge_utils::get_dydx_perp_ticker);
+        let orders = self.db_manager.get_open_orders(DYDX_VENUE, ticker.as_deref()).await?;
+        Ok(orders)
+    }
+
+    /// Resubmit the unfilled remainder of every partially filled order as a child order.
+    /// Returns the number of orders retried.
+    #[instrument(skip(self))]
+    pub async fn retry_partially_filled_orders(&mut self) -> Result<usize> {
+        let orders = self.db_manager.get_partially_filled_orders(DYDX_VENUE).await?;
+        let mut retried = 0;
+        for order in orders {
+            let remaining_size = order.size - order.filled_size;
+            let token = order.ticker.trim_end_matches("-USD").to_string();
+            let side_is_buy = order.side == "BUY";
+            if remaining_size <= Decimal::ZERO {
+                self.db_manager.update_order_state(order.id, HedgeOrderStatus::Filled, order.filled_size, None).await?;
+                continue;
+            }
+
+            // Mark the parent first so the open order guard lets the child through
+            self.db_manager.update_order_state(order.id, HedgeOrderStatus::Retried, order.filled_size, None).await?;
+            let log_string = format!(
+                "{} | Retry of order #{}",
+                self.get_perp_order_log_string(&token, remaining_size, side_is_buy, order.reduce_only)?,
+                order.id
+            );
+            if let Err(e) = self.execute_perp_order(&token, remaining_size, side_is_buy, order.reduce_only, Some(order.id), order.rebalance_id, log_string).await {
+                error!(order_id = order.id, error = ?e, "Failed to retry partially filled order");
+                self.db_manager.update_order_state(order.id, HedgeOrderStatus::PartiallyFilled, order.filled_size, None).await?;
+                continue;
+            }
+            retried += 1;
+        }
+        Ok(retried)
+    }
+
+    async fn execute_perp_order(
+        &mut self,
+        token: &str,
+        size: Decimal,
+        side_is_buy: bool,
+        is_position_reduction: bool,
+        parent_order_id: Option<i32>,
+        rebalance_id: Option<i32>,
+        log_string: String,
+    ) -> Result<()> {
+        // Never double-submit a hedge adjustment while another order for the same market is outstanding
+        let ticker = hedge_utils::get_dydx_perp_ticker(token);
+        let open_orders = self.db_manager.get_open_orders(DYDX_VENUE, Some(&ticker)).await?;
+        if let Some(open_order) = open_orders.first() {
+            return Err(eyre::eyre!(
+                "{} | Order #{} for {} is still {}, not submitting another",
+                log_string, open_order.id, ticker, open_order.status
+            ));
+        }
+
         let dydx_usdc_balance_initial = self.get_dydx_usdc_balance().await?;
         let dydx_subaccount_usdc_balance_initial = self.get_dydx_subaccount_usdc_balance().await?;
         let dydx_subaccount_perp_positions_initial = self.get_dydx_subaccount_perp_positions().await?;
@@ -270,7 +478,7 @@ impl DydxClient {
             .market(side, BigDecimal::from_str(&size.to_string())?)
             .short_term()
             .time_in_force(OrderTimeInForce::Unspecified)
-            .until(OrderGoodUntil::Block(current_block_height.ahead(40))) // Order valid for 40 blocks
+            .until(OrderGoodUntil::Block(current_block_height.ahead(ORDER_GOOD_TIL_BLOCKS))) // Order valid for 40 blocks
             .build(ClientId::random())
             .map_err(|e| eyre::eyre!("Failed to build dYdX order: {}", e))?;
         info!(
@@ -288,6 +496,16 @@ impl DydxClient {
         // Set next nonce
         account.set_next_nonce(dydx::node::sequencer::Nonce::Sequence(dydx_account_info.sequence));
 
+        if self.config.dydx_sandbox {
+            let msg = MsgPlaceOrder { order: Some(order) }.to_any();
+            self.sign_and_simulate(&account, vec![msg]).await?;
+            info!(
+                order_id = ?order_id,
+                "{} | Order Validated (sandbox, not broadcast)", log_string
+            );
+            return Ok(());
+        }
+
         let tx_hash = self.node_client.place_order(&mut account, order).await
             .map_err(|e| eyre::eyre!("Failed to place dYdX order: {}", e))?;
 
@@ -296,12 +514,76 @@ impl DydxClient {
             "{} | Order Submitted Successfully", log_string
         );
 
+        // Record the order so its fills can be tracked and it is not submitted twice
+        let good_til_block = current_block_height.ahead(ORDER_GOOD_TIL_BLOCKS);
+        let db_order_id = self.db_manager.insert_order(&NewOrderModel {
+            venue: DYDX_VENUE.to_string(),
+            ticker,
+            side: if side_is_buy { "BUY".to_string() } else { "SELL".to_string() },
+            size,
+            reduce_only: is_position_reduction,
+            client_id: order_id.client_id as i64,
+            good_til_block: Some(good_til_block.0 as i64),
+            tx_hash: Some(tx_hash.to_string()),
+            status: HedgeOrderStatus::Submitted.as_str().to_string(),
+            parent_order_id,
+            reference_price: self.base_asset_price(hedge_utils::get_base_asset(token)),
+        }, rebalance_id).await?;
+
         // Spawn status polling
-        self.spawn_status_polling_perp_order(log_string, false, order_id, current_block_height.ahead(40)).await?;
+        self.spawn_status_polling_perp_order(log_string, is_position_reduction, order_id, good_til_block, db_order_id, size).await?;
 
         Ok(())
     }
 
+    /// Check an order against the account's compliance rules, valuing positions at the wallet's asset token prices.
+    /// Leverage is measured against the subaccount equity (USDC balance plus the value of its positions).
+    async fn ensure_hedge_order_compliant(&self, token: &str, size: Decimal, side_is_buy: bool) -> Result<()> {
+        let rules = compliance::load_rules(&self.db_manager).await?;
+        if rules.is_empty() {
+            return Ok(());
+        }
+
+        let positions = self.get_dydx_subaccount_perp_positions().await?;
+        let order_ticker = hedge_utils::get_dydx_perp_ticker(token);
+        let mut tickers: Vec<&String> = positions.keys().collect();
+        if !positions.contains_key(&order_ticker) {
+            tickers.push(&order_ticker);
+        }
+
+        let mut equity = self.get_dydx_subaccount_usdc_balance().await?;
+        let mut position_notional_after = Decimal::ZERO;
+        let mut order_notional = Decimal::ZERO;
+        for ticker in tickers {
+            let price = self.base_asset_price(ticker.trim_end_matches("-USD"))
+                .ok_or_else(|| eyre::eyre!("No price for {}, cannot check the hedge order against compliance rules", ticker))?;
+            let size_before = positions.get(ticker).copied().unwrap_or(Decimal::ZERO);
+            let size_after = match (*ticker == order_ticker, side_is_buy) {
+                (true, true) => size_before + size,
+                (true, false) => size_before - size,
+                (false, _) => size_before,
+            };
+            if *ticker == order_ticker {
+                order_notional = size * price;
+            }
+            equity += size_before * price;
+            position_notional_after += size_after.abs() * price;
+        }
+
+        if let Some(violation) = compliance::check_hedge_order(&rules, token, order_notional, position_notional_after, equity, self.db_manager.clock.now()) {
+            warn!(rule_id = %violation.rule_id, "{}", violation);
+            return Err(violation.into());
+        }
+        Ok(())
+    }
+
+    /// USD price of a hedged base asset (e.g. ETH for WETH) from the wallet's asset token prices
+    fn base_asset_price(&self, base_asset: &str) -> Option<Decimal> {
+        self.wallet_manager.tokens().asset_tokens.values()
+            .find(|token| hedge_utils::get_base_asset(&token.symbol) == base_asset)
+            .map(|token| token.last_mid_price_usd)
+    }
+
     fn get_perp_order_log_string(
         &self,
         token: &str,
@@ -333,8 +615,10 @@ impl DydxClient {
         is_position_reduction: bool,
         order_id_node: OrderId,
         block_to_wait_until: Height,
+        db_order_id: i32,
+        order_size: Decimal,
     ) -> Result<()> {
-        let config = ClientConfig::from_file("src/hedging/dydx_mainnet.toml").await
+        let config = ClientConfig::from_file(&self.client_config_path).await
             .map_err(|e| eyre::eyre!("Failed to load dYdX config: {}", e))?;
         let mut node_client_clone = NodeClient::connect(config.node).await
             .map_err(|e| eyre::eyre!("Failed to connect to dYdX node: {}", e))?;
@@ -345,14 +629,7 @@ impl DydxClient {
             SubaccountNumber::try_from(DYDX_SUBACCOUNT_NUM)
                 .map_err(|e| eyre::eyre!("Failed to create dYdX subaccount number: {}", e))?,
         );
-        let subaccount_orders = self.indexer_client.accounts().get_subaccount_orders(&subaccount, None).await
-            .map_err(|e| eyre::eyre!("Failed to fetch subaccount orders for order ID string: {}", e))?;
-        let order_id_indexer = subaccount_orders
-            .iter()
-            .find(|order| order.client_id.0 == order_id_node.client_id && order.good_til_block == Some(block_to_wait_until.clone()))
-            .ok_or_else(|| eyre::eyre!("Order not found in subaccount orders"))?
-            .id
-            .clone();
+        let db_manager = self.db_manager.clone();
             
         let handle = tokio::spawn(async move {
             let complete_msg = if is_position_reduction {
@@ -360,97 +637,134 @@ impl DydxClient {
             } else {
                 "Order Executed Successfully"
             };
+            let mut order_id_indexer = None;
+            let mut filled_size = Decimal::ZERO;
             loop {
-                match indexer_client_clone.accounts().get_order(&order_id_indexer).await {
-                    Ok(order) => {
-                        match order.status {
-                            dydx::indexer::ApiOrderStatus::OrderStatus(OrderStatus::Filled) => {
-                                sleep(Duration::from_secs(2)).await; // Small delay to ensure balances are updated
-                                let dydx_usdc_balance_final = match node_client_clone.get_account_balance(
-                                    &dydx_address_clone.clone().into(),
-                                    &Denom::Usdc,
-                                ).await {
-                                    Ok(balance) => Decimal::from_str(&balance.amount.to_string()).unwrap_or(Decimal::ZERO) * Decimal::from_str("0.000001").unwrap(),
-                                    Err(e) => {
-                                        error!(
-                                            error = %e,
-                                            "{} | Failed to fetch dYdX USDC balance: {}", log_string, e
-                                        );
-                                        Decimal::ZERO
+                // Resolve the indexer order ID once the indexer has picked up the order
+                if order_id_indexer.is_none() {
+                    order_id_indexer = match indexer_client_clone.accounts().get_subaccount_orders(&subaccount, None).await {
+                        Ok(orders) => orders.iter()
+                            .find(|order| order.client_id.0 == order_id_node.client_id && order.good_til_block == Some(block_to_wait_until.clone()))
+                            .map(|order| order.id.clone()),
+                        Err(e) => {
+                            error!(
+                                error = %e,
+                                "{} | Failed to fetch subaccount orders during polling", log_string
+                            );
+                            None
+                        }
+                    };
+                }
+                if let Some(order_id_indexer) = order_id_indexer.clone() {
+                    match indexer_client_clone.accounts().get_order(&order_id_indexer).await {
+                        Ok(order) => {
+                            filled_size = Decimal::from_str(&order.total_filled.to_plain_string()).unwrap_or(filled_size);
+                            match order.status {
+                                dydx::indexer::ApiOrderStatus::OrderStatus(OrderStatus::Filled) => {
+                                    if let Err(e) = db_manager.update_order_state(db_order_id, HedgeOrderStatus::Filled, filled_size, Some(order_id_indexer.0.clone())).await {
+                                        error!(error = %e, "{} | Failed to record order fill", log_string);
                                     }
-                                };
-                                let dydx_subaccount_usdc_balance_final = match indexer_client_clone.accounts().get_subaccount_asset_positions(&subaccount).await {
-                                    Ok(positions) => {
-                                        positions.iter().find(|pos| pos.symbol.0 == "USDC")
-                                        .map(|pos| Decimal::from_str(&pos.size.to_plain_string()).unwrap_or(Decimal::ZERO))
-                                        .unwrap_or(Decimal::ZERO)
-                                    },
-                                    Err(e) => {
-                                        error!(
-                                            error = %e,
-                                            "{} | Failed to fetch dYdX subaccount USDC balance: {}", log_string, e
-                                        );
-                                        Decimal::ZERO
+                                    Self::record_average_fill_price(&db_manager, &indexer_client_clone, &subaccount, &order_id_indexer.0, db_order_id, &log_string).await;
+                                    sleep(Duration::from_secs(2)).await; // Small delay to ensure balances are updated
+                                    let dydx_usdc_balance_final = match node_client_clone.get_account_balance(
+                                        &dydx_address_clone.clone().into(),
+                                        &Denom::Usdc,
+                                    ).await {
+                                        Ok(balance) => Decimal::from_str(&balance.amount.to_string()).unwrap_or(Decimal::ZERO) * Decimal::from_str("0.000001").unwrap(),
+                                        Err(e) => {
+                                            error!(
+                                                error = %e,
+                                                "{} | Failed to fetch dYdX USDC balance: {}", log_string, e
+                                            );
+                                            Decimal::ZERO
+                                        }
+                                    };
+                                    let dydx_subaccount_usdc_balance_final = match indexer_client_clone.accounts().get_subaccount_asset_positions(&subaccount).await {
+                                        Ok(positions) => {
+                                            positions.iter().find(|pos| pos.symbol.0 == "USDC")
+                                            .map(|pos| Decimal::from_str(&pos.size.to_plain_string()).unwrap_or(Decimal::ZERO))
+                                            .unwrap_or(Decimal::ZERO)
+                                        },
+                                        Err(e) => {
+                                            error!(
+                                                error = %e,
+                                                "{} | Failed to fetch dYdX subaccount USDC balance: {}", log_string, e
+                                            );
+                                            Decimal::ZERO
+                                        }
+                                    };
+                                    let dydx_subaccount_perp_positions_final = match indexer_client_clone.accounts().get_subaccount_perpetual_positions(&subaccount, None).await {
+                                        Ok(positions) => {
+                                            positions.iter()
+                                            .filter_map(|pos| {
+                                                let ticker = pos.market.0.clone();
+                                                let size_decimal = Decimal::from_str(&pos.size.to_plain_string()).ok()?;
+                                                if size_decimal.is_zero() { return None; }
+                                                Some((ticker, size_decimal))
+                                            })
+                                            .collect::<HashMap<String, Decimal>>()
+                                        },
+                                        Err(e) => {
+                                            error!(
+                                                error = %e,
+                                                "{} | Failed to fetch dYdX subaccount perpetual positions: {}", log_string, e
+                                            );
+                                            HashMap::new()
+                                        }
+                                    };
+                                    info!(
+                                        order_id_indexer = ?order_id_indexer,
+                                        dydx_usdc_balance_final = ?dydx_usdc_balance_final,
+                                        dydx_subaccount_usdc_balance_final = ?dydx_subaccount_usdc_balance_final,
+                                        dydx_subaccount_perp_positions_final = ?dydx_subaccount_perp_positions_final,
+                                        "{} | {}", log_string, complete_msg
+                                    );
+                                    break;
+                                },
+                                dydx::indexer::ApiOrderStatus::OrderStatus(OrderStatus::Open) |
+                                dydx::indexer::ApiOrderStatus::BestEffort(dydx::indexer::types::BestEffortOpenedStatus::BestEffortOpened) => {
+                                    if let Err(e) = db_manager.update_order_state(db_order_id, HedgeOrderStatus::Open, filled_size, Some(order_id_indexer.0.clone())).await {
+                                        error!(error = %e, "{} | Failed to record open order state", log_string);
                                     }
-                                };
-                                let dydx_subaccount_perp_positions_final = match indexer_client_clone.accounts().get_subaccount_perpetual_positions(&subaccount, None).await {
-                                    Ok(positions) => {
-                                        positions.iter()
-                                        .filter_map(|pos| {
-                                            let ticker = pos.market.0.clone();
-                                            let size_decimal = Decimal::from_str(&pos.size.to_plain_string()).ok()?;
-                                            if size_decimal.is_zero() { return None; }
-                                            Some((ticker, size_decimal))
-                                        })
-                                        .collect::<HashMap<String, Decimal>>()
-                                    },
-                                    Err(e) => {
-                                        error!(
-                                            error = %e,
-                                            "{} | Failed to fetch dYdX subaccount perpetual positions: {}", log_string, e
-                                        );
-                                        HashMap::new()
+                                    debug!(
+                                        order_id_indexer = ?order_id_indexer,
+                                        "{} | Order Still Open...", log_string
+                                    );
+                                }
+                                dydx::indexer::ApiOrderStatus::OrderStatus(OrderStatus::Canceled) |
+                                dydx::indexer::ApiOrderStatus::OrderStatus(OrderStatus::BestEffortCanceled) => {
+                                    // A cancelled order with partial fills leaves a remainder to retry
+                                    let status = if filled_size > Decimal::ZERO { HedgeOrderStatus::PartiallyFilled } else { HedgeOrderStatus::Cancelled };
+                                    if let Err(e) = db_manager.update_order_state(db_order_id, status, filled_size, Some(order_id_indexer.0.clone())).await {
+                                        error!(error = %e, "{} | Failed to record order cancellation", log_string);
                                     }
-                                };
-                                info!(
-                                    order_id_indexer = ?order_id_indexer,
-                                    dydx_usdc_balance_final = ?dydx_usdc_balance_final,
-                                    dydx_subaccount_usdc_balance_final = ?dydx_subaccount_usdc_balance_final,
-                                    dydx_subaccount_perp_positions_final = ?dydx_subaccount_perp_positions_final,
-                                    "{} | {}", log_string, complete_msg
-                                );
-                                break;
-                            },
-                            dydx::indexer::ApiOrderStatus::OrderStatus(OrderStatus::Open) |
-                            dydx::indexer::ApiOrderStatus::BestEffort(dydx::indexer::types::BestEffortOpenedStatus::BestEffortOpened) => {
-                                debug!(
-                                    order_id_indexer = ?order_id_indexer,
-                                    "{} | Order Still Open...", log_string
-                                );
-                            }
-                            dydx::indexer::ApiOrderStatus::OrderStatus(OrderStatus::Canceled) |
-                            dydx::indexer::ApiOrderStatus::OrderStatus(OrderStatus::BestEffortCanceled) => {
-                                warn!(
-                                    order_id_indexer = ?order_id_indexer,
-                                    "{} | Order Cancelled", log_string
-                                );
-                                break;
-                            }
-                            _ => {
-                                warn!(
-                                    order_id_indexer = ?order_id_indexer,
-                                    order_status = ?order.status,
-                                    "{} | Order In Unexpected State", log_string
-                                );
-                            }
-                        };
-                    }
-                    Err(e) => {
-                        error!(
-                            error = %e,
-                            "{} | Failed to fetch account during polling", log_string
-                        );
-                        continue;
+                                    if filled_size > Decimal::ZERO {
+                                        Self::record_average_fill_price(&db_manager, &indexer_client_clone, &subaccount, &order_id_indexer.0, db_order_id, &log_string).await;
+                                    }
+                                    warn!(
+                                        order_id_indexer = ?order_id_indexer,
+                                        filled_size = %filled_size,
+                                        order_size = %order_size,
+                                        "{} | Order Cancelled", log_string
+                                    );
+                                    break;
+                                }
+                                _ => {
+                                    warn!(
+                                        order_id_indexer = ?order_id_indexer,
+                                        order_status = ?order.status,
+                                        "{} | Order In Unexpected State", log_string
+                                    );
+                                }
+                            };
+                        }
+                        Err(e) => {
+                            error!(
+                                error = %e,
+                                "{} | Failed to fetch account during polling", log_string
+                            );
+                            continue;
+                        }
                     }
                 }
                 
@@ -465,7 +779,18 @@ impl DydxClient {
                     }
                 };
                 if current_block_height > block_to_wait_until {
-                    warn!("{} | Order Timed Out After Reaching Target Block Height", log_string);
+                    let status = if filled_size > Decimal::ZERO { HedgeOrderStatus::PartiallyFilled } else { HedgeOrderStatus::Expired };
+                    if let Err(e) = db_manager.update_order_state(db_order_id, status, filled_size, None).await {
+                        error!(error = %e, "{} | Failed to record order expiry", log_string);
+                    }
+                    if let (Some(order_id_indexer), true) = (&order_id_indexer, filled_size > Decimal::ZERO) {
+                        Self::record_average_fill_price(&db_manager, &indexer_client_clone, &subaccount, &order_id_indexer.0, db_order_id, &log_string).await;
+                    }
+                    warn!(
+                        filled_size = %filled_size,
+                        order_size = %order_size,
+                        "{} | Order Timed Out After Reaching Target Block Height", log_string
+                    );
                     break;
                 }
                 sleep(Duration::from_secs(5)).await;
@@ -477,6 +802,40 @@ impl DydxClient {
         Ok(())
     }
 
+    /// Record the size weighted average price of an order's fills, so its slippage from the reference price can be
+    /// attributed. Failures are logged rather than returned since the order has already been recorded.
+    async fn record_average_fill_price(
+        db_manager: &DbManager,
+        indexer_client: &IndexerClient,
+        subaccount: &Subaccount,
+        order_id_indexer: &str,
+        db_order_id: i32,
+        log_string: &str,
+    ) {
+        let fills = match indexer_client.accounts().get_subaccount_fills(subaccount, None).await {
+            Ok(fills) => fills,
+            Err(e) => {
+                error!(error = %e, "{} | Failed to fetch order fills", log_string);
+                return;
+            }
+        };
+        let (filled_size, filled_notional) = fills.iter()
+            .filter(|fill| fill.order_id.as_ref().is_some_and(|order_id| order_id.0 == order_id_indexer))
+            .filter_map(|fill| {
+                let price = Decimal::from_str(&fill.price.to_plain_string()).ok()?;
+                let size = Decimal::from_str(&fill.size.to_plain_string()).ok()?;
+                Some((size, size * price))
+            })
+            .fold((Decimal::ZERO, Decimal::ZERO), |(size, notional), (fill_size, fill_notional)| (size + fill_size, notional + fill_notional));
+        if filled_size <= Decimal::ZERO {
+            warn!(order_id_indexer = order_id_indexer, "{} | No fills found for order, fill price not recorded", log_string);
+            return;
+        }
+        if let Err(e) = db_manager.update_order_fill_price(db_order_id, filled_notional / filled_size).await {
+            error!(error = %e, "{} | Failed to record order fill price", log_string);
+        }
+    }
+
     pub async fn get_subaccount(&mut self) -> Result<SubaccountInfo> {
         let subaccount = Subaccount::new(
             self.dydx_address.clone().into(),
@@ -508,6 +867,23 @@ impl DydxClient {
         let dydx_usdc_balance_initial = self.get_dydx_usdc_balance().await?;
         let dydx_subaccount_usdc_balance_initial = self.get_dydx_subaccount_usdc_balance().await?;
 
+        if self.config.dydx_sandbox {
+            let msg = MsgDepositToSubaccount {
+                sender: self.dydx_address.clone(),
+                recipient: Some(SubaccountId { owner: self.dydx_address.clone(), number: DYDX_SUBACCOUNT_NUM }),
+                asset_id: USDC_ASSET_ID,
+                quantums: decimal_to_u256(amount, USDC_DECIMALS)?.as_u64(),
+            }.to_any();
+            self.sign_and_simulate(&account, vec![msg]).await?;
+            info!(
+                amount = ?amount,
+                dydx_usdc_balance_initial = ?dydx_usdc_balance_initial,
+                dydx_subaccount_usdc_balance_initial = ?dydx_subaccount_usdc_balance_initial,
+                "Deposit to dYdX subaccount validated (sandbox, not broadcast)"
+            );
+            return Ok(());
+        }
+
         let tx_hash = self.node_client.deposit(
             &mut account,
             self.dydx_address.clone().into(),
@@ -561,6 +937,23 @@ impl DydxClient {
         let dydx_usdc_balance_initial = self.get_dydx_usdc_balance().await?;
         let dydx_subaccount_usdc_balance_initial = self.get_dydx_subaccount_usdc_balance().await?;
 
+        if self.config.dydx_sandbox {
+            let msg = MsgWithdrawFromSubaccount {
+                sender: Some(SubaccountId { owner: self.dydx_address.clone(), number: DYDX_SUBACCOUNT_NUM }),
+                recipient: self.dydx_address.clone(),
+                asset_id: USDC_ASSET_ID,
+                quantums: decimal_to_u256(amount, USDC_DECIMALS)?.as_u64(),
+            }.to_any();
+            self.sign_and_simulate(&account, vec![msg]).await?;
+            info!(
+                amount = ?amount,
+                dydx_usdc_balance_initial = ?dydx_usdc_balance_initial,
+                dydx_subaccount_usdc_balance_initial = ?dydx_subaccount_usdc_balance_initial,
+                "Withdrawal from dYdX subaccount validated (sandbox, not broadcast)"
+            );
+            return Ok(());
+        }
+
         let tx_hash = self.node_client.withdraw(
             &mut account,
             subaccount,
@@ -632,7 +1025,7 @@ impl DydxClient {
         amount_out: Option<Decimal>, 
         go_fast: bool,
         slippage_tolerance_percent: Option<Decimal>,
-    ) -> Result<()> {
+    ) -> Result<SkipGoTransfer> {
         // Get USDC + ETH balances
         let initial_arbitrum_usdc_balance = self.get_arbitrum_usdc_balance().await?;
         let initial_dydx_usdc_balance = self.get_dydx_usdc_balance().await?;
@@ -654,15 +1047,15 @@ impl DydxClient {
         );
 
         // Get SkipGo route and msgs
-        let (amount, estimated_time_secs, msgs) = self.skip_go_get_route_and_msgs(
+        let (amount, expected_amount_out, estimated_time_secs, msgs) = self.skip_go_get_route_and_msgs(
             amount_in,
             amount_out,
             go_fast,
             slippage_tolerance_percent,
-            ARBITRUM_USDC_DENOM,
-            ARBITRUM_CHAIN_ID,
-            DYDX_USDC_DENOM,
-            DYDX_CHAIN_ID,
+            self.network.arbitrum_usdc_denom,
+            self.network.arbitrum_chain_id,
+            self.network.dydx_usdc_denom,
+            self.network.dydx_chain_id,
         ).await?;
 
         // Validate requested transfer amount and estimated fees against balances
@@ -693,7 +1086,7 @@ impl DydxClient {
         }
 
         // Execute SkipGo transfer
-        self.execute_skip_go_transfer(
+        let (submitted_txs, gas_cost_usd) = self.execute_skip_go_transfer(
             msgs.txs, 
             initial_dydx_usdc_balance,
             initial_arbitrum_usdc_balance,
@@ -708,7 +1101,7 @@ impl DydxClient {
             log_string, 
         );
 
-        Ok(())
+        Ok(SkipGoTransfer { amount, expected_amount_out, estimated_time_secs, submitted_txs, gas_cost_usd })
     }
 
     #[instrument(skip(self))]
@@ -718,7 +1111,7 @@ impl DydxClient {
         amount_out: Option<Decimal>, 
         go_fast: bool,
         slippage_tolerance_percent: Option<Decimal>,
-    ) -> Result<()> {
+    ) -> Result<SkipGoTransfer> {
         // Get USDC balances
         let initial_arbitrum_usdc_balance = self.get_arbitrum_usdc_balance().await?;
         let initial_dydx_usdc_balance = self.get_dydx_usdc_balance().await?;
@@ -738,15 +1131,15 @@ impl DydxClient {
             log_string
         );
 
-        let (amount, estimated_time_secs, msgs) = self.skip_go_get_route_and_msgs(
+        let (amount, expected_amount_out, estimated_time_secs, msgs) = self.skip_go_get_route_and_msgs(
             amount_in,
             amount_out,
             go_fast,
             slippage_tolerance_percent,
-            DYDX_USDC_DENOM,
-            DYDX_CHAIN_ID,
-            ARBITRUM_USDC_DENOM,
-            ARBITRUM_CHAIN_ID,
+            self.network.dydx_usdc_denom,
+            self.network.dydx_chain_id,
+            self.network.arbitrum_usdc_denom,
+            self.network.arbitrum_chain_id,
         ).await?;
 
         // Validate requested transfer amount and estimated fees against balances
@@ -767,7 +1160,7 @@ impl DydxClient {
         }
 
         // Execute SkipGo transfer
-        self.execute_skip_go_transfer(
+        let (submitted_txs, gas_cost_usd) = self.execute_skip_go_transfer(
             msgs.txs, 
             initial_dydx_usdc_balance,
             initial_arbitrum_usdc_balance,
@@ -782,17 +1175,18 @@ impl DydxClient {
             log_string, 
         );
 
-        Ok(())
+        Ok(SkipGoTransfer { amount, expected_amount_out, estimated_time_secs, submitted_txs, gas_cost_usd })
     }
 
     async fn get_arbitrum_usdc_balance(&self) -> Result<Decimal> {
         let balance = self.wallet_manager.get_token_balance(
-            Address::from_str(ARBITRUM_USDC_DENOM)?
+            Address::from_str(self.network.arbitrum_usdc_denom)?
         ).await?;
         Ok(balance)
     }
 
-    async fn get_dydx_usdc_balance(&mut self) -> Result<Decimal> {
+    /// USDC held by the dYdX chain account itself (outside the trading subaccount), where bridged funds arrive
+    pub async fn get_dydx_usdc_balance(&mut self) -> Result<Decimal> {
         let balance = self.node_client.get_account_balance(
             &self.dydx_address.clone().into(),
             &Denom::Usdc
@@ -909,7 +1303,7 @@ impl DydxClient {
         source_asset_chain_id: &str,
         dest_asset_denom: &str,
         dest_asset_chain_id: &str,
-    ) -> Result<(Decimal, u64, skip_go::SkipGoGetMsgsResponse)> {
+    ) -> Result<(Decimal, Decimal, u64, skip_go::SkipGoGetMsgsResponse)> {
         let amount_in: Option<String> = amount_in.map(|d| {
             let amount_u256 = decimal_to_u256(d, USDC_DECIMALS).unwrap();
             amount_u256.to_string()
@@ -973,7 +1367,12 @@ impl DydxClient {
             USDC_DECIMALS,
         )?;
 
-        Ok((amount, estimated_time_secs, msgs))
+        let expected_amount_out = u256_to_decimal(
+            U256::from_dec_str(true_amount_out).unwrap(),
+            USDC_DECIMALS,
+        )?;
+
+        Ok((amount, expected_amount_out, estimated_time_secs, msgs))
     }
 
     async fn execute_skip_go_transfer(
@@ -984,13 +1383,18 @@ impl DydxClient {
         arbitrum_native_balance_initial: Decimal,
         log_string: String,
         expected_time_to_complete_secs: u64,
-    ) -> Result<()> {
+    ) -> Result<(Vec<(String, String)>, Decimal)> {
+        let mut submitted_txs = Vec::new();
+        let mut gas_cost_usd = Decimal::ZERO;
         for tx in txs {
             match tx {
                 skip_go::SkipGoTx::CosmosTx(cosmos_tx) => {
                     debug!("Executing Cosmos Tx: {:#?}", cosmos_tx);
 
-                    let tx_hash = self.construct_and_execute4_cosmos_tx(cosmos_tx).await?;
+                    let Some(tx_hash) = self.construct_and_execute4_cosmos_tx(cosmos_tx).await? else {
+                        info!("{} Cosmos Transaction Validated (sandbox, not broadcast)", log_string);
+                        continue;
+                    };
                     info!(
                         tx_hash = ?tx_hash,
                         "{} Cosmos Transaction Submitted Successfully", 
@@ -1000,7 +1404,7 @@ impl DydxClient {
                     // Track transaction 
                     let track_transaction_request = skip_go::SkipGoTrackTransactionRequest {
                         tx_hash: tx_hash.clone(),
-                        chain_id: DYDX_CHAIN_ID.to_string(),
+                        chain_id: self.network.dydx_chain_id.to_string(),
                     };
                     skip_go::track_transaction(track_transaction_request).await?;
                     info!(
@@ -1010,9 +1414,10 @@ impl DydxClient {
                     );
 
                     // Spawn status polling
+                    submitted_txs.push((tx_hash.clone(), self.network.dydx_chain_id.to_string()));
                     self.spawn_status_polling_skipgo(
                         tx_hash,
-                        DYDX_CHAIN_ID.to_string(), 
+                        self.network.dydx_chain_id.to_string(), 
                         expected_time_to_complete_secs,
                         dydx_usdc_balance_initial,
                         arbitrum_usdc_balance_initial,
@@ -1023,6 +1428,22 @@ impl DydxClient {
                 skip_go::SkipGoTx::EvmTx(evm_tx) => {
                     debug!("Executing EVM Tx: {:#?}", evm_tx);
 
+                    // Approvals would have to be sent for the transfer to simulate, so in sandbox mode a transaction
+                    // that still needs one is only built
+                    if self.config.dydx_sandbox {
+                        let needs_approval = !evm_tx.evm_tx.required_erc20_approvals.is_empty();
+                        let evm_transaction = self.build_evm_transaction(evm_tx).await?;
+                        if !needs_approval {
+                            self.simulate_evm_transaction(&evm_transaction, arbitrum_native_balance_initial).await?;
+                        }
+                        info!(
+                            transaction = ?evm_transaction,
+                            simulated = !needs_approval,
+                            "{} EVM Transaction Validated (sandbox, not sent)", log_string
+                        );
+                        continue;
+                    }
+
                     for required_approval in &evm_tx.evm_tx.required_erc20_approvals {
                         self.approve_erc20(required_approval.clone()).await?;
                     }
@@ -1037,20 +1458,22 @@ impl DydxClient {
                     let gas_used = u256_to_decimal(receipt.gas_used.unwrap_or(U256::zero()), 0)?;
                     let gas_price = u256_to_decimal(receipt.effective_gas_price.unwrap_or(U256::zero()), 18)?;
                     let total_gas_cost = gas_used * gas_price;
+                    gas_cost_usd += total_gas_cost * self.wallet_manager.native_token().last_mid_price_usd;
                     info!(
                         tx_hash = ?tx_hash,
                         gas_used = ?gas_used,
                         gas_price = ?gas_price,
                         total_gas_cost = ?total_gas_cost,
-                        total_gas_cost_usd = ?(total_gas_cost * self.wallet_manager.native_token.last_mid_price_usd),
+                        total_gas_cost_usd = ?(total_gas_cost * self.wallet_manager.native_token().last_mid_price_usd),
                         "{} Transaction Executed Successfully", 
                         log_string
                     );
 
                     // Spawn status polling
+                    submitted_txs.push((format!("{:#x}", tx_hash), self.network.arbitrum_chain_id.to_string()));
                     self.spawn_status_polling_skipgo(
                         format!("{:#x}", tx_hash),
-                        ARBITRUM_CHAIN_ID.to_string(), 
+                        self.network.arbitrum_chain_id.to_string(), 
                         expected_time_to_complete_secs,
                         dydx_usdc_balance_initial,
                         arbitrum_usdc_balance_initial,
@@ -1063,7 +1486,7 @@ impl DydxClient {
                 }
             }
         }
-        Ok(())
+        Ok((submitted_txs, gas_cost_usd))
     }
 
     async fn approve_erc20(&self, approval: skip_go::EvmRequiredErc20Approval) -> Result<()> {
@@ -1211,10 +1634,12 @@ impl DydxClient {
         }
     }
 
+    /// Build, simulate and broadcast a SkipGo Cosmos transaction. Returns its hash, None in sandbox mode where it is
+    /// only built and simulated.
     async fn construct_and_execute4_cosmos_tx(
         &mut self,
         cosmos_tx_wrapper: skip_go::TxsCosmosTx,
-    ) -> Result<String> {
+    ) -> Result<Option<String>> {
         // Construct messages
         let mut tx_msgs: Vec<Any> = Vec::new();
         for m in cosmos_tx_wrapper.cosmos_tx.msgs {
@@ -1290,12 +1715,33 @@ impl DydxClient {
             .builder
             .build_transaction(&account, tx_msgs, Some(fee), None)
             .map_err(|e| eyre::eyre!("Failed to build final Cosmos transaction: {}", e))?;
+        if self.config.dydx_sandbox {
+            return Ok(None);
+        }
 
         // Broadcast transaction
         let tx_hash = self.node_client.broadcast_transaction(final_tx_raw).await
             .map_err(|e| eyre::eyre!("Failed to broadcast Cosmos transaction: {}", e))?;
 
-        Ok(tx_hash)
+        Ok(Some(tx_hash))
+    }
+
+    /// Sandbox stand-in for broadcasting: sign the messages into a transaction and simulate it against the node, which
+    /// runs the same checks as delivery (signature, sequence, balances, order validity) without committing anything
+    async fn sign_and_simulate(&mut self, account: &Account, msgs: Vec<Any>) -> Result<()> {
+        let tx_raw = self.node_client
+            .builder
+            .build_transaction(account, msgs, None, None)
+            .map_err(|e| eyre::eyre!("Failed to build dYdX transaction: {}", e))?;
+        let gas_info = self.node_client.simulate(&tx_raw).await
+            .map_err(|e| eyre::eyre!("dYdX transaction failed validation: {}", e))?;
+        info!(
+            dydx_chain_id = self.network.dydx_chain_id,
+            gas_used = ?gas_info.gas_used,
+            gas_wanted = ?gas_info.gas_wanted,
+            "dYdX transaction signed and simulated (sandbox)"
+        );
+        Ok(())
     }
 
     async fn spawn_status_polling_skipgo(
@@ -1308,12 +1754,13 @@ impl DydxClient {
         arbitrum_native_balance_initial: Decimal,
         log_string: String,
     ) -> Result<()> {
-        let config = ClientConfig::from_file("src/hedging/dydx_mainnet.toml").await
+        let config = ClientConfig::from_file(&self.client_config_path).await
             .map_err(|e| eyre::eyre!("Failed to load dYdX config: {}", e))?;
         let mut node_client_clone = NodeClient::connect(config.node).await
             .map_err(|e| eyre::eyre!("Failed to connect to dYdX node: {}", e))?;
         let dydx_address_clone = self.dydx_address.clone();
         let wallet_manager_clone = self.wallet_manager.clone();
+        let arbitrum_usdc_denom = self.network.arbitrum_usdc_denom;
 
         let handle = tokio::spawn(async move {
             let interval = Duration::from_secs(
@@ -1360,7 +1807,7 @@ impl DydxClient {
                                     }
                                 };
                                 let arbitrum_usdc_balance_final = match wallet_manager_clone.get_token_balance(
-                                    Address::from_str(ARBITRUM_USDC_DENOM).unwrap(),
+                                    Address::from_str(arbitrum_usdc_denom).unwrap(),
                                 ).await {
                                     Ok(balance) => balance,
                                     Err(e) => {
@@ -1464,6 +1911,52 @@ impl DydxClient {
     }
 }
 
+impl PerpVenue for DydxClient {
+    fn name(&self) -> &'static str {
+        DYDX_VENUE
+    }
+
+    fn get_ticker(&self, token: &str) -> Option<String> {
+        (!hedge_utils::STABLE_COINS.contains(&token)).then(|| hedge_utils::get_dydx_perp_ticker(token))
+    }
+
+    fn get_hedge_markets(&self) -> BoxFuture<'_, Result<HashMap<String, HedgeMarket>>> {
+        Box::pin(async move {
+            let mut hedge_markets = HashMap::new();
+            for (token_symbol, market) in self.get_token_perp_map().await? {
+                let Some(market) = market else {
+                    continue;
+                };
+                let hedge_market = HedgeMarket {
+                    venue: DYDX_VENUE,
+                    ticker: hedge_utils::get_dydx_perp_ticker(&token_symbol),
+                    funding_rate: Decimal::from_str(&market.next_funding_rate.to_plain_string())?,
+                    volume_24h_usd: Decimal::from_str(&market.volume_24h.to_string()).unwrap_or(Decimal::ZERO),
+                    max_leverage: self.calculate_max_leverage(market).await?,
+                };
+                hedge_markets.insert(token_symbol, hedge_market);
+            }
+            Ok(hedge_markets)
+        })
+    }
+
+    fn get_funding_rate<'a>(&'a self, token: &'a str) -> BoxFuture<'a, Result<Decimal>> {
+        Box::pin(DydxClient::get_funding_rate(self, token))
+    }
+
+    fn submit_perp_order<'a>(&'a mut self, token: &'a str, size: Decimal, side_is_buy: bool, rebalance_id: Option<i32>) -> BoxFuture<'a, Result<()>> {
+        Box::pin(DydxClient::submit_perp_order(self, token, size, side_is_buy, rebalance_id))
+    }
+
+    fn reduce_perp_position<'a>(&'a mut self, token: &'a str, reduce_by: Option<Decimal>, rebalance_id: Option<i32>) -> BoxFuture<'a, Result<()>> {
+        Box::pin(DydxClient::reduce_perp_position(self, token, reduce_by, rebalance_id))
+    }
+
+    fn get_perp_positions(&self) -> BoxFuture<'_, Result<HashMap<String, Decimal>>> {
+        Box::pin(self.get_dydx_subaccount_perp_positions())
+    }
+}
+
 // ==================== Utility methods ====================
 
 /// Helper to convert Decimal to U256
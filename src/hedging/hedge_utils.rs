@@ -13,10 +13,19 @@ const TOKEN_MAP: [(&str, &str); 4] = [
     ("tBTC", "BTC"),
 ];
 
-pub fn get_dydx_perp_ticker(token_symbol: &str) -> String {
-    let base = TOKEN_MAP.iter()
+/// Asset a token is hedged as, wrapped and bridged variants map to their underlying (e.g. WETH -> ETH)
+pub fn get_base_asset(token_symbol: &str) -> &str {
+    TOKEN_MAP.iter()
         .find(|(key, _)| *key == token_symbol)
         .map(|(_, value)| *value)
-        .unwrap_or(token_symbol);
-    format!("{}-USD", base)
+        .unwrap_or(token_symbol)
+}
+
+pub fn get_dydx_perp_ticker(token_symbol: &str) -> String {
+    format!("{}-USD", get_base_asset(token_symbol))
+}
+
+/// Hyperliquid perps are named by their base asset (e.g. "ETH")
+pub fn get_hyperliquid_coin(token_symbol: &str) -> String {
+    get_base_asset(token_symbol).to_string()
 }
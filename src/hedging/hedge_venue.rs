@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+use rust_decimal::Decimal;
+use tracing::{debug, info, warn, instrument};
+use eyre::Result;
+use futures::future::BoxFuture;
+
+use crate::config::Config;
+use super::hedge_utils;
+
+/// A perp market a token can be hedged on
+#[derive(Debug, Clone)]
+pub struct HedgeMarket {
+    pub venue: &'static str,
+    pub ticker: String,            // Venue's name for the perp (e.g. "ETH-USD" on dYdX, "ETH" on Hyperliquid)
+    pub funding_rate: Decimal,     // Next hourly funding rate, positive when longs pay shorts
+    pub max_leverage: Decimal,     // Leverage we are willing to use (half the venue maximum)
+    pub volume_24h_usd: Decimal,   // Notional traded over the last 24h, used as the liquidity measure
+}
+
+/// A perp venue hedges can be placed on. Object safe so venues (and paper or test doubles) can be used as
+/// `Box<dyn PerpVenue>` without threading concrete client types through the strategy and execution code.
+pub trait PerpVenue: Send + Sync {
+    /// Name of the venue, matches the venue recorded with its orders
+    fn name(&self) -> &'static str;
+
+    /// Venue ticker of the perp hedging the token, None for stablecoins
+    fn get_ticker(&self, token: &str) -> Option<String>;
+
+    /// Perp markets for the wallet's asset tokens, keyed by token symbol. Stablecoins and unlisted tokens are absent.
+    fn get_hedge_markets(&self) -> BoxFuture<'_, Result<HashMap<String, HedgeMarket>>>;
+
+    /// Next hourly funding rate of the perp hedging the token
+    fn get_funding_rate<'a>(&'a self, token: &'a str) -> BoxFuture<'a, Result<Decimal>>;
+
+    /// Open or increase a perp position with a market order, recorded as a leg of the given rebalance
+    fn submit_perp_order<'a>(&'a mut self, token: &'a str, size: Decimal, side_is_buy: bool, rebalance_id: Option<i32>) -> BoxFuture<'a, Result<()>>;
+
+    /// Reduce a perp position by the given size, or close it entirely, recorded as a leg of the given rebalance
+    fn reduce_perp_position<'a>(&'a mut self, token: &'a str, reduce_by: Option<Decimal>, rebalance_id: Option<i32>) -> BoxFuture<'a, Result<()>>;
+
+    /// Open perp position sizes keyed by ticker, negative for shorts
+    fn get_perp_positions(&self) -> BoxFuture<'_, Result<HashMap<String, Decimal>>>;
+}
+
+/// Pick the venue to hedge each token on from the markets of each venue, given in order of preference.
+/// The first venue listing the token with at least `min_volume_usd` of daily volume wins; when no venue is
+/// liquid enough the most liquid listing is used.
+pub fn select_hedge_venues(venue_markets: &[HashMap<String, HedgeMarket>], min_volume_usd: Decimal) -> HashMap<String, HedgeMarket> {
+    let mut tokens: Vec<&String> = venue_markets.iter().flat_map(|markets| markets.keys()).collect();
+    tokens.sort();
+    tokens.dedup();
+
+    tokens.into_iter()
+        .filter_map(|token| {
+            let listings: Vec<&HedgeMarket> = venue_markets.iter().filter_map(|markets| markets.get(token)).collect();
+            let selected = listings.iter()
+                .find(|market| market.volume_24h_usd >= min_volume_usd)
+                .or_else(|| listings.iter().max_by_key(|market| market.volume_24h_usd))?;
+            Some((token.clone(), (*selected).clone()))
+        })
+        .collect()
+}
+
+/// Load the perp market each token is hedged on from the venues, given in order of preference (dYdX first, then
+/// Hyperliquid when enabled). A venue failing to respond other than the first only loses it as a fallback.
+#[instrument(skip(config, venues), fields(venue_count = venues.len()))]
+pub async fn load_hedge_markets(config: &Config, venues: &[Box<dyn PerpVenue>]) -> Result<HashMap<String, HedgeMarket>> {
+    let mut venue_markets = Vec::with_capacity(venues.len());
+    for (i, venue) in venues.iter().enumerate() {
+        match venue.get_hedge_markets().await {
+            Ok(markets) => venue_markets.push(markets),
+            Err(e) if i == 0 => return Err(e),
+            Err(e) => warn!(venue = venue.name(), error = ?e, "Failed to fetch hedge markets, venue skipped"),
+        }
+    }
+
+    let hedge_markets = select_hedge_venues(&venue_markets, config.hedge_min_volume_usd);
+    for (token, market) in &hedge_markets {
+        debug!(token = %token, venue = market.venue, ticker = %market.ticker, volume_24h_usd = %market.volume_24h_usd.round_dp(0), "Hedge venue selected");
+    }
+    Ok(hedge_markets)
+}
+
+/// Open perp positions on every venue, consolidated by base asset
+pub async fn get_consolidated_positions(venues: &[Box<dyn PerpVenue>]) -> Result<HashMap<String, Vec<VenuePosition>>> {
+    let mut venue_positions = Vec::with_capacity(venues.len());
+    for venue in venues {
+        venue_positions.push((venue.name(), venue.get_perp_positions().await?));
+    }
+    Ok(consolidate_positions(&venue_positions))
+}
+
+/// Open perp position on one venue
+#[derive(Debug, Clone)]
+pub struct VenuePosition {
+    pub venue: &'static str,
+    pub ticker: String,
+    pub size: Decimal,
+}
+
+/// Group open positions from every venue by base asset, so the total hedge per asset can be read across venues
+pub fn consolidate_positions(venue_positions: &[(&'static str, HashMap<String, Decimal>)]) -> HashMap<String, Vec<VenuePosition>> {
+    let mut consolidated: HashMap<String, Vec<VenuePosition>> = HashMap::new();
+    for (venue, positions) in venue_positions {
+        for (ticker, size) in positions {
+            let base_asset = ticker.trim_end_matches("-USD");
+            consolidated.entry(hedge_utils::get_base_asset(base_asset).to_string())
+                .or_default()
+                .push(VenuePosition { venue: *venue, ticker: ticker.clone(), size: *size });
+        }
+    }
+    consolidated
+}
+
+/// Log open positions per base asset with their net size across venues
+pub fn log_consolidated_positions(consolidated: &HashMap<String, Vec<VenuePosition>>) {
+    if consolidated.is_empty() {
+        info!("No open perp positions");
+        return;
+    }
+
+    let mut base_assets: Vec<&String> = consolidated.keys().collect();
+    base_assets.sort();
+    let summary = base_assets.iter()
+        .map(|base_asset| {
+            let positions = &consolidated[*base_asset];
+            let net_size: Decimal = positions.iter().map(|p| p.size).sum();
+            let venues = positions.iter()
+                .map(|p| format!("{} {} {}", p.venue, p.ticker, p.size))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("{}: Net={} ({})", base_asset, net_size, venues)
+        })
+        .collect::<Vec<_>>()
+        .join("\n  ");
+    info!("Open perp positions across venues:\n  {}", summary);
+}
+
+/// Signed size of the perp order re-targeting a hedge (positive buys), None while the hedge is within its band.
+/// Bands are fractions of the hedged position's notional at `price`, so cheap tokens don't trade dust and expensive ones
+/// don't drift far. Once past the rebalance band the hedge is only moved to the edge of the target band, the gap
+/// between the two bands keeps a hedge hovering around the trigger from flipping between buys and sells each run.
+/// A hedge being closed (zero target) has no notional to band against and is always closed out in full.
+/// With HEDGE_BAND_VOL_MULTIPLE set, both bands widen to that many `volatility`s (the standard deviation of the index
+/// price move until the hedge is next checked, as a fraction) when that is wider, keeping the gap between them.
+pub fn hedge_adjustment(config: &Config, target_size: Decimal, current_size: Decimal, price: Decimal, volatility: Option<Decimal>) -> Option<Decimal> {
+    let delta = target_size - current_size;
+    if delta.is_zero() || price <= Decimal::ZERO {
+        return None;
+    }
+    let notional = target_size.abs() * price;
+    if notional.is_zero() {
+        return Some(delta);
+    }
+    let band_scale = match (config.hedge_band_vol_multiple, volatility) {
+        (Some(multiple), Some(volatility)) if config.hedge_rebalance_band_pct > Decimal::ZERO => (multiple * volatility / config.hedge_rebalance_band_pct).max(Decimal::ONE),
+        _ => Decimal::ONE,
+    };
+    if delta.abs() * price <= config.hedge_rebalance_band_pct * band_scale * notional {
+        debug!(target_size = %target_size, current_size = %current_size, band_scale = %band_scale, "Hedge within rebalance band, not re-targeted");
+        return None;
+    }
+    let tolerance = config.hedge_rebalance_target_band_pct * band_scale * notional / price;
+    Some(delta - tolerance * delta.signum())
+}
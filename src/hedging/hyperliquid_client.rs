@@ -0,0 +1,509 @@
+use eyre::Result;
+use std::sync::Arc;
+use std::collections::HashMap;
+use std::str::FromStr;
+use tracing::{instrument, debug, info, error};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use futures::future::BoxFuture;
+use ethers::prelude::*;
+use ethers::types::transaction::eip712::TypedData;
+
+use crate::config;
+use crate::wallet::WalletManager;
+use crate::db::db_manager::DbManager;
+use crate::db::models::orders::{NewOrderModel, HedgeOrderStatus};
+use super::hedge_utils;
+use super::hedge_venue::{PerpVenue, HedgeMarket};
+
+pub const HYPERLIQUID_VENUE: &str = "hyperliquid";
+const MARKET_ORDER_SLIPPAGE: f64 = 0.05; // Market orders are IOC limit orders this far through the mid price
+const MAX_PRICE_SIGNIFICANT_FIGURES: u32 = 5;
+const MAX_PERP_PRICE_DECIMALS: u32 = 6; // Perp prices allow 6 - szDecimals decimals
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HyperliquidMeta {
+    universe: Vec<HyperliquidAssetMeta>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HyperliquidAssetMeta {
+    name: String,
+    sz_decimals: u32,
+    max_leverage: u32,
+    #[serde(default)]
+    is_delisted: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HyperliquidAssetCtx {
+    funding: String,
+    day_ntl_vlm: String,
+    mark_px: String,
+    mid_px: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HyperliquidClearinghouseState {
+    asset_positions: Vec<HyperliquidAssetPosition>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HyperliquidAssetPosition {
+    position: HyperliquidPosition,
+}
+
+#[derive(Debug, Deserialize)]
+struct HyperliquidPosition {
+    coin: String,
+    szi: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct HyperliquidExchangeResponse {
+    status: String,
+    response: serde_json::Value,
+}
+
+/// Perp listed on Hyperliquid, with its current market context
+#[derive(Debug, Clone)]
+pub struct HyperliquidAsset {
+    pub index: u32, // Asset ID used in order actions
+    pub coin: String,
+    pub sz_decimals: u32,
+    pub max_leverage: u32,
+    pub funding_rate: Decimal, // Hourly
+    pub mid_price: Decimal,
+    pub volume_24h_usd: Decimal,
+}
+
+// Exchange actions, field order matters since actions are hashed as MessagePack
+#[derive(Debug, Serialize)]
+struct OrderAction {
+    #[serde(rename = "type")]
+    action_type: &'static str,
+    orders: Vec<OrderWire>,
+    grouping: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct OrderWire {
+    a: u32,    // Asset
+    b: bool,   // Is buy
+    p: String, // Limit price
+    s: String, // Size
+    r: bool,   // Reduce only
+    t: OrderTypeWire,
+}
+
+#[derive(Debug, Serialize)]
+struct OrderTypeWire {
+    limit: LimitOrderWire,
+}
+
+#[derive(Debug, Serialize)]
+struct LimitOrderWire {
+    tif: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdateLeverageAction {
+    #[serde(rename = "type")]
+    action_type: &'static str,
+    asset: u32,
+    is_cross: bool,
+    leverage: u32,
+}
+
+/// Hyperliquid perps client, for hedging tokens dYdX does not list. Orders are signed with the wallet key;
+/// USDC collateral has to be bridged to the Hyperliquid account separately.
+pub struct HyperliquidClient {
+    config: Arc<config::Config>,
+    wallet_manager: Arc<WalletManager>,
+    db_manager: Arc<DbManager>,
+    http_client: reqwest::Client,
+}
+
+impl HyperliquidClient {
+    pub fn new(cfg: Arc<config::Config>, wallet_manager: Arc<WalletManager>, db_manager: Arc<DbManager>) -> Result<Self> {
+        let http_client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(10))
+            .build()?;
+        Ok(Self {
+            config: cfg,
+            wallet_manager,
+            db_manager,
+            http_client,
+        })
+    }
+
+    /// Every listed perp with its funding, mid price and volume
+    #[instrument(skip(self))]
+    pub async fn get_assets(&self) -> Result<Vec<HyperliquidAsset>> {
+        let (meta, ctxs): (HyperliquidMeta, Vec<HyperliquidAssetCtx>) = self.post_info(json!({ "type": "metaAndAssetCtxs" })).await?;
+
+        meta.universe.into_iter()
+            .zip(ctxs)
+            .enumerate()
+            .filter(|(_, (asset_meta, _))| !asset_meta.is_delisted)
+            .map(|(index, (asset_meta, ctx))| Ok(HyperliquidAsset {
+                index: index as u32,
+                coin: asset_meta.name,
+                sz_decimals: asset_meta.sz_decimals,
+                max_leverage: asset_meta.max_leverage,
+                funding_rate: Decimal::from_str(&ctx.funding)?,
+                mid_price: Decimal::from_str(ctx.mid_px.as_deref().unwrap_or(&ctx.mark_px))?,
+                volume_24h_usd: Decimal::from_str(&ctx.day_ntl_vlm)?,
+            }))
+            .collect()
+    }
+
+    /// The perp hedging the given token, if Hyperliquid lists one
+    pub async fn get_asset(&self, token: &str) -> Result<Option<HyperliquidAsset>> {
+        if hedge_utils::STABLE_COINS.contains(&token) {
+            return Ok(None);
+        }
+        let coin = hedge_utils::get_hyperliquid_coin(token);
+        Ok(self.get_assets().await?.into_iter().find(|asset| asset.coin == coin))
+    }
+
+    pub async fn get_max_leverage(&self, token: &str) -> Result<Decimal> {
+        let asset = self.get_asset(token).await?
+            .ok_or_else(|| eyre::eyre!("No Hyperliquid perp found for token {}", token))?;
+        Ok(Self::usable_leverage(&asset))
+    }
+
+    /// Resubmit the unfilled remainder of every partially filled order as a child order.
+    /// Returns the number of orders retried.
+    #[instrument(skip(self))]
+    pub async fn retry_partially_filled_orders(&mut self) -> Result<usize> {
+        let orders = self.db_manager.get_partially_filled_orders(HYPERLIQUID_VENUE).await?;
+        let mut retried = 0;
+        for order in orders {
+            let remaining_size = order.size - order.filled_size;
+            let side_is_buy = order.side == "BUY";
+            if remaining_size <= Decimal::ZERO {
+                self.db_manager.update_order_state(order.id, HedgeOrderStatus::Filled, order.filled_size, None).await?;
+                continue;
+            }
+
+            // Mark the parent first so the open order guard lets the child through
+            self.db_manager.update_order_state(order.id, HedgeOrderStatus::Retried, order.filled_size, None).await?;
+            let log_string = format!(
+                "{} | Retry of order #{}",
+                Self::get_perp_order_log_string(&order.ticker, remaining_size, side_is_buy, order.reduce_only),
+                order.id
+            );
+            if let Err(e) = self.execute_perp_order(&order.ticker, remaining_size, side_is_buy, order.reduce_only, Some(order.id), order.rebalance_id, log_string).await {
+                error!(order_id = order.id, error = ?e, "Failed to retry partially filled order");
+                self.db_manager.update_order_state(order.id, HedgeOrderStatus::PartiallyFilled, order.filled_size, None).await?;
+                continue;
+            }
+            retried += 1;
+        }
+        Ok(retried)
+    }
+
+    /// Place an IOC order through the mid price, recording it with its fill
+    async fn execute_perp_order(
+        &mut self,
+        token: &str,
+        size: Decimal,
+        side_is_buy: bool,
+        is_position_reduction: bool,
+        parent_order_id: Option<i32>,
+        rebalance_id: Option<i32>,
+        log_string: String,
+    ) -> Result<()> {
+        let asset = self.get_asset(token).await?
+            .ok_or_else(|| eyre::eyre!("No Hyperliquid perp found for token {}", token))?;
+
+        // Never double-submit a hedge adjustment while another order for the same market is outstanding
+        let open_orders = self.db_manager.get_open_orders(HYPERLIQUID_VENUE, Some(&asset.coin)).await?;
+        if let Some(open_order) = open_orders.first() {
+            return Err(eyre::eyre!(
+                "{} | Order #{} for {} is still {}, not submitting another",
+                log_string, open_order.id, asset.coin, open_order.status
+            ));
+        }
+
+        // Cap the position's leverage at what the strategy models before adding to it
+        if !is_position_reduction {
+            let leverage = Self::usable_leverage(&asset).floor().to_u32().unwrap_or(1).max(1);
+            self.post_action(&UpdateLeverageAction {
+                action_type: "updateLeverage",
+                asset: asset.index,
+                is_cross: true,
+                leverage,
+            }).await?;
+        }
+
+        let slippage = Decimal::from_f64(MARKET_ORDER_SLIPPAGE).unwrap();
+        let limit_price = if side_is_buy {
+            asset.mid_price * (Decimal::ONE + slippage)
+        } else {
+            asset.mid_price * (Decimal::ONE - slippage)
+        };
+        let size = size.round_dp_with_strategy(asset.sz_decimals, RoundingStrategy::ToZero);
+        if size <= Decimal::ZERO {
+            return Err(eyre::eyre!("{} | Order size rounds to zero at {} size decimals", log_string, asset.sz_decimals));
+        }
+        let action = OrderAction {
+            action_type: "order",
+            orders: vec![OrderWire {
+                a: asset.index,
+                b: side_is_buy,
+                p: format_price(limit_price, asset.sz_decimals),
+                s: size.normalize().to_string(),
+                r: is_position_reduction,
+                t: OrderTypeWire { limit: LimitOrderWire { tif: "Ioc" } },
+            }],
+            grouping: "na",
+        };
+        info!(asset = asset.index, limit_price = %action.orders[0].p, "{} | Order Initiated", log_string);
+
+        // Record the order before submitting so it is never submitted twice
+        let client_id = self.db_manager.clock.now().timestamp_millis();
+        let db_order_id = self.db_manager.insert_order(&NewOrderModel {
+            venue: HYPERLIQUID_VENUE.to_string(),
+            ticker: asset.coin.clone(),
+            side: if side_is_buy { "BUY".to_string() } else { "SELL".to_string() },
+            size,
+            reduce_only: is_position_reduction,
+            client_id,
+            good_til_block: None,
+            tx_hash: None,
+            status: HedgeOrderStatus::Submitted.as_str().to_string(),
+            parent_order_id,
+            reference_price: Some(asset.mid_price),
+        }, rebalance_id).await?;
+
+        let response = match self.post_action(&action).await {
+            Ok(response) => response,
+            Err(e) => {
+                self.db_manager.update_order_state(db_order_id, HedgeOrderStatus::Cancelled, Decimal::ZERO, None).await?;
+                return Err(e);
+            }
+        };
+
+        // IOC orders are either (partially) filled or cancelled immediately
+        let order_status = &response["data"]["statuses"][0];
+        if let Some(filled) = order_status.get("filled") {
+            let filled_size = filled["totalSz"].as_str().map(Decimal::from_str).transpose()?.unwrap_or(Decimal::ZERO);
+            let venue_order_id = filled["oid"].as_u64().map(|oid| oid.to_string());
+            let status = if filled_size >= size { HedgeOrderStatus::Filled } else { HedgeOrderStatus::PartiallyFilled };
+            self.db_manager.update_order_state(db_order_id, status, filled_size, venue_order_id).await?;
+            if let Some(average_fill_price) = filled["avgPx"].as_str().and_then(|px| Decimal::from_str(px).ok()) {
+                self.db_manager.update_order_fill_price(db_order_id, average_fill_price).await?;
+            }
+            info!(
+                filled_size = %filled_size,
+                avg_price = ?filled["avgPx"].as_str(),
+                status = status.as_str(),
+                "{} | Order Filled", log_string
+            );
+            Ok(())
+        } else {
+            self.db_manager.update_order_state(db_order_id, HedgeOrderStatus::Cancelled, Decimal::ZERO, None).await?;
+            Err(eyre::eyre!("{} | Order not filled: {}", log_string, order_status))
+        }
+    }
+
+    /// Usable leverage for a perp, half the venue maximum to leave a liquidation buffer (as on dYdX)
+    fn usable_leverage(asset: &HyperliquidAsset) -> Decimal {
+        Decimal::from(asset.max_leverage) / Decimal::from(2)
+    }
+
+    fn get_perp_order_log_string(token: &str, size: Decimal, side_is_buy: bool, is_position_reduction: bool) -> String {
+        let side_str = if side_is_buy { "Long" } else { "Short" };
+        if is_position_reduction {
+            format!("HYPERLIQUID PERP POSITION REDUCTION REQUEST | {} {:.5} {}", side_str, size, token)
+        } else {
+            format!("HYPERLIQUID PERP ORDER REQUEST | {} {:.5} {}", side_str, size, token)
+        }
+    }
+
+    /// Query the info endpoint
+    async fn post_info<T: serde::de::DeserializeOwned>(&self, request: serde_json::Value) -> Result<T> {
+        let response = self.http_client
+            .post(format!("{}/info", self.config.hyperliquid_api_url))
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(response)
+    }
+
+    /// Sign an action and send it to the exchange endpoint, returning the response payload
+    async fn post_action<A: Serialize>(&self, action: &A) -> Result<serde_json::Value> {
+        let nonce = self.db_manager.clock.now().timestamp_millis() as u64;
+        let signature = self.sign_action(action, nonce).await?;
+        let request = json!({
+            "action": action,
+            "nonce": nonce,
+            "signature": {
+                "r": format!("0x{:064x}", signature.r),
+                "s": format!("0x{:064x}", signature.s),
+                "v": signature.v,
+            },
+            "vaultAddress": null,
+        });
+
+        let response: HyperliquidExchangeResponse = self.http_client
+            .post(format!("{}/exchange", self.config.hyperliquid_api_url))
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if response.status != "ok" {
+            return Err(eyre::eyre!("Hyperliquid rejected action: {}", response.response));
+        }
+        debug!(response = %response.response, "Hyperliquid action accepted");
+        Ok(response.response)
+    }
+
+    /// Sign an action as an L1 action: the MessagePack-encoded action and nonce are hashed into the
+    /// connection ID of a phantom agent, which is signed as EIP-712 typed data
+    async fn sign_action<A: Serialize>(&self, action: &A, nonce: u64) -> Result<Signature> {
+        let mut encoded = rmp_serde::to_vec_named(action)?;
+        encoded.extend_from_slice(&nonce.to_be_bytes());
+        encoded.push(0); // No vault address
+        let connection_id = H256::from(ethers::utils::keccak256(&encoded));
+
+        let typed_data: TypedData = serde_json::from_value(json!({
+            "types": {
+                "EIP712Domain": [
+                    { "name": "name", "type": "string" },
+                    { "name": "version", "type": "string" },
+                    { "name": "chainId", "type": "uint256" },
+                    { "name": "verifyingContract", "type": "address" },
+                ],
+                "Agent": [
+                    { "name": "source", "type": "string" },
+                    { "name": "connectionId", "type": "bytes32" },
+                ],
+            },
+            "primaryType": "Agent",
+            "domain": {
+                "name": "Exchange",
+                "version": "1",
+                "chainId": 1337,
+                "verifyingContract": "0x0000000000000000000000000000000000000000",
+            },
+            "message": {
+                "source": if self.config.network_mode == "prod" { "a" } else { "b" },
+                "connectionId": connection_id,
+            },
+        }))?;
+        let signature = self.wallet_manager.signer.inner().signer().sign_typed_data(&typed_data).await?;
+        Ok(signature)
+    }
+}
+
+impl PerpVenue for HyperliquidClient {
+    fn name(&self) -> &'static str {
+        HYPERLIQUID_VENUE
+    }
+
+    fn get_ticker(&self, token: &str) -> Option<String> {
+        (!hedge_utils::STABLE_COINS.contains(&token)).then(|| hedge_utils::get_hyperliquid_coin(token))
+    }
+
+    fn get_hedge_markets(&self) -> BoxFuture<'_, Result<HashMap<String, HedgeMarket>>> {
+        Box::pin(async move {
+            let assets: HashMap<String, HyperliquidAsset> = self.get_assets().await?
+                .into_iter()
+                .map(|asset| (asset.coin.clone(), asset))
+                .collect();
+
+            let hedge_markets = self.wallet_manager.tokens().asset_tokens.values()
+                .filter(|token| !hedge_utils::STABLE_COINS.contains(&token.symbol.as_str()))
+                .filter_map(|token| {
+                    let asset = assets.get(&hedge_utils::get_hyperliquid_coin(&token.symbol))?;
+                    Some((token.symbol.clone(), HedgeMarket {
+                        venue: HYPERLIQUID_VENUE,
+                        ticker: asset.coin.clone(),
+                        funding_rate: asset.funding_rate,
+                        max_leverage: Self::usable_leverage(asset),
+                        volume_24h_usd: asset.volume_24h_usd,
+                    }))
+                })
+                .collect();
+            Ok(hedge_markets)
+        })
+    }
+
+    fn get_funding_rate<'a>(&'a self, token: &'a str) -> BoxFuture<'a, Result<Decimal>> {
+        Box::pin(async move {
+            let asset = self.get_asset(token).await?
+                .ok_or_else(|| eyre::eyre!("No Hyperliquid perp found for token {}", token))?;
+            Ok(asset.funding_rate)
+        })
+    }
+
+    fn submit_perp_order<'a>(&'a mut self, token: &'a str, size: Decimal, side_is_buy: bool, rebalance_id: Option<i32>) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let log_string = Self::get_perp_order_log_string(token, size, side_is_buy, false);
+            self.execute_perp_order(token, size, side_is_buy, false, None, rebalance_id, log_string).await
+        })
+    }
+
+    fn reduce_perp_position<'a>(&'a mut self, token: &'a str, reduce_by: Option<Decimal>, rebalance_id: Option<i32>) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let coin = hedge_utils::get_hyperliquid_coin(token);
+            let positions = self.get_perp_positions().await?;
+            let perp_position_size = positions.get(&coin)
+                .ok_or_else(|| eyre::eyre!("No existing Hyperliquid perp position for token {}", token))?;
+            let side_is_buy = !perp_position_size.is_sign_positive();
+            let reduce_by = reduce_by.unwrap_or_else(|| perp_position_size.abs());
+            let log_string = Self::get_perp_order_log_string(token, reduce_by, side_is_buy, true);
+
+            self.execute_perp_order(token, reduce_by, side_is_buy, true, None, rebalance_id, log_string).await
+        })
+    }
+
+    fn get_perp_positions(&self) -> BoxFuture<'_, Result<HashMap<String, Decimal>>> {
+        Box::pin(async move {
+            let state: HyperliquidClearinghouseState = self.post_info(json!({
+                "type": "clearinghouseState",
+                "user": format!("{:?}", self.wallet_manager.address),
+            })).await?;
+
+            let mut positions = HashMap::new();
+            for asset_position in state.asset_positions {
+                let size = Decimal::from_str(&asset_position.position.szi)?;
+                if size != Decimal::ZERO {
+                    positions.insert(asset_position.position.coin, size);
+                }
+            }
+            Ok(positions)
+        })
+    }
+}
+
+/// Format a perp price the way Hyperliquid accepts it: at most 5 significant figures (integers are always
+/// allowed) and at most 6 - szDecimals decimals
+fn format_price(price: Decimal, sz_decimals: u32) -> String {
+    let integer_digits = price.trunc().to_string().trim_start_matches('-').trim_start_matches('0').len() as u32;
+    let significant_decimals = MAX_PRICE_SIGNIFICANT_FIGURES.saturating_sub(integer_digits);
+    let significant_decimals = if integer_digits == 0 {
+        // Below 1, leading zeros after the decimal point don't count as significant
+        let leading_zeros = price.fract().to_string().trim_start_matches("0.").chars().take_while(|c| *c == '0').count() as u32;
+        leading_zeros + MAX_PRICE_SIGNIFICANT_FIGURES
+    } else {
+        significant_decimals
+    };
+    let decimals = significant_decimals.min(MAX_PERP_PRICE_DECIMALS.saturating_sub(sz_decimals));
+    price.round_dp(decimals).normalize().to_string()
+}
@@ -1,3 +1,5 @@
 pub mod dydx_client;
 pub mod hedge_utils;
-pub mod skip_go;
\ No newline at end of file
+pub mod skip_go;
+pub mod hedge_venue;
+pub mod hyperliquid_client;
\ No newline at end of file
@@ -9,4 +9,17 @@ pub mod db;
 pub mod strategy;
 pub mod spot_swap;
 pub mod gm_token_txs;
-pub mod hedging;
\ No newline at end of file
+pub mod hedging;
+pub mod reporting_currency;
+pub mod clock;
+pub mod redis_client;
+pub mod accounting;
+pub mod monitor;
+pub mod telemetry;
+pub mod tx_registry;
+pub mod wallet_watchdog;
+pub mod permit;
+pub mod stress;
+pub mod bridging;
+pub mod pipeline_watchdog;
+pub mod signal_sink;
\ No newline at end of file
@@ -7,10 +7,15 @@ use tracing_subscriber::{
     layer::{SubscriberExt, Layer, Context}, 
     util::SubscriberInitExt
 };
-use tracing::{Id, Subscriber, span, field::Field, field::Visit, debug, error};
+use tracing::{Id, Subscriber, Event, Metadata, Dispatch, span, subscriber::Interest, field::Field, field::Value, field::Visit, field::DisplayValue, debug, error};
+use std::any::TypeId;
 use std::time::{Instant, Duration};
-use std::sync::OnceLock; // For global file guard
+use std::sync::{Arc, OnceLock, RwLock}; // OnceLock for global file guard
 use tracing_loki::url::Url;
+use opentelemetry::global;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
 
 static FILE_GUARD: OnceLock<tracing_appender::non_blocking::WorkerGuard> = OnceLock::new();
 
@@ -37,6 +42,21 @@ pub fn init_logging(bin_name: String) -> Result<(), tracing_loki::Error> {
     let console_log_level = env::var("CONSOLE_LOG_LEVEL").unwrap_or_else(|_| "INFO".to_string());
     let file_log_level = env::var("FILE_LOG_LEVEL").unwrap_or_else(|_| "INFO".to_string());
     let loki_log_level = env::var("LOKI_LOG_LEVEL").unwrap_or_else(|_| "TRACE".to_string());
+    let otel_trace_level = env::var("OTEL_TRACE_LEVEL").unwrap_or_else(|_| "INFO".to_string());
+
+    // Load redaction policies per sink: "off" logs full detail, "mask" masks sensitive fields and shortens addresses.
+    // Registered secrets (key material, API keys) are masked on every sink regardless of policy.
+    let redact_fields: Vec<String> = env::var("LOG_REDACT_FIELDS")
+        .unwrap_or_else(|_| DEFAULT_REDACT_FIELDS.to_string())
+        .split(',')
+        .map(|field| field.trim().to_lowercase())
+        .filter(|field| !field.is_empty())
+        .collect();
+    let redactor = |var: &str, default: RedactionPolicy| Redactor::new(RedactionPolicy::from_env(var, default), redact_fields.clone());
+    let console_redactor = redactor("CONSOLE_LOG_REDACTION", RedactionPolicy::Off);
+    let file_redactor = redactor("FILE_LOG_REDACTION", RedactionPolicy::Mask);
+    let loki_redactor = redactor("LOKI_LOG_REDACTION", RedactionPolicy::Mask);
+    let otel_redactor = redactor("OTEL_TRACE_REDACTION", RedactionPolicy::Mask);
 
     // Load file log flag from env
     let log_to_file = env::var("LOG_TO_FILE").unwrap_or_else(|_| "false".to_string()) == "true";
@@ -53,6 +73,9 @@ pub fn init_logging(bin_name: String) -> Result<(), tracing_loki::Error> {
     };
     let loki_url = Url::parse(&loki_url_str).unwrap();
 
+    // Load OTLP endpoint for span export (e.g. http://localhost:4317), traces are only exported when set
+    let otlp_endpoint = env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok().filter(|endpoint| !endpoint.is_empty());
+
     // Determine service name: use container name in Docker, otherwise binary name
     let service_name = if deployment == "docker" {
         // In Docker, use the explicitly set container name
@@ -68,39 +91,62 @@ pub fn init_logging(bin_name: String) -> Result<(), tracing_loki::Error> {
     // Set up EnvFilter for runtime log levels, filter globally to "warn", filter our own crate and binaries to the specified levels in .env
     let env_filter_console = EnvFilter::try_new(
         &format!(
-            "warn,crypto_yield_farming_bot={0},data_collector={0},data_recorder={0},main={0},see_balances={0},spot_swap={0},trading_bot={0},transact_gm_tokens={0},dydx_transfer={0},dydx_trade_perps={0}", 
+            "warn,crypto_yield_farming_bot={0},data_collector={0},data_recorder={0},main={0},see_balances={0},spot_swap={0},trading_bot={0},transact_gm_tokens={0},dydx_transfer={0},dydx_trade_perps={0},plan={0},testnet_bootstrap={0},evaluate_returns={0},data_retention={0},funding_collector={0},strategy={0},export={0},gas_profile={0},backfill={0},deploy_gmx_callback={0},wallet_watchdog={0},stress={0},gm_price_verifier={0},bridge={0},whatif={0},price_stream={0},pipeline_watchdog={0},signal_sink={0},raise_cash={0},rebalance_costs={0}", 
             console_log_level
         )
     ).unwrap_or_else(|_| EnvFilter::new(
-        "warn,crypto_yield_farming_bot=info,data_collector=info,data_recorder=info,main=info,see_balances=info,spot_swap=info,trading_bot=info,transact_gm_tokens=info,dydx_transfer=info,dydx_trade_perps=info"
+        "warn,crypto_yield_farming_bot=info,data_collector=info,data_recorder=info,main=info,see_balances=info,spot_swap=info,trading_bot=info,transact_gm_tokens=info,dydx_transfer=info,dydx_trade_perps=info,plan=info,testnet_bootstrap=info,evaluate_returns=info,data_retention=info,funding_collector=info,strategy=info,export=info,gas_profile=info,backfill=info,deploy_gmx_callback=info,wallet_watchdog=info,stress=info,gm_price_verifier=info,bridge=info,whatif=info,price_stream=info,pipeline_watchdog=info,signal_sink=info,raise_cash=info,rebalance_costs=info"
     ));
 
     let env_filter_file = EnvFilter::try_new(
         &format!(
-            "warn,crypto_yield_farming_bot={0},data_collector={0},data_recorder={0},main={0},see_balances={0},spot_swap={0},trading_bot={0},transact_gm_tokens={0},dydx_transfer={0},dydx_trade_perps={0}",
+            "warn,crypto_yield_farming_bot={0},data_collector={0},data_recorder={0},main={0},see_balances={0},spot_swap={0},trading_bot={0},transact_gm_tokens={0},dydx_transfer={0},dydx_trade_perps={0},plan={0},testnet_bootstrap={0},evaluate_returns={0},data_retention={0},funding_collector={0},strategy={0},export={0},gas_profile={0},backfill={0},deploy_gmx_callback={0},wallet_watchdog={0},stress={0},gm_price_verifier={0},bridge={0},whatif={0},price_stream={0},pipeline_watchdog={0},signal_sink={0},raise_cash={0},rebalance_costs={0}",
             file_log_level
         )
     ).unwrap_or_else(|_| EnvFilter::new(
-        "warn,crypto_yield_farming_bot=info,data_collector=info,data_recorder=info,main=info,see_balances=info,spot_swap=info,trading_bot=info,transact_gm_tokens=info,dydx_transfer=info,dydx_trade_perps=info"
+        "warn,crypto_yield_farming_bot=info,data_collector=info,data_recorder=info,main=info,see_balances=info,spot_swap=info,trading_bot=info,transact_gm_tokens=info,dydx_transfer=info,dydx_trade_perps=info,plan=info,testnet_bootstrap=info,evaluate_returns=info,data_retention=info,funding_collector=info,strategy=info,export=info,gas_profile=info,backfill=info,deploy_gmx_callback=info,wallet_watchdog=info,stress=info,gm_price_verifier=info,bridge=info,whatif=info,price_stream=info,pipeline_watchdog=info,signal_sink=info,raise_cash=info,rebalance_costs=info"
     ));
 
     let env_filter_loki = EnvFilter::try_new(
         &format!(
-            "warn,crypto_yield_farming_bot={0},data_collector={0},data_recorder={0},main={0},see_balances={0},spot_swap={0},trading_bot={0},transact_gm_tokens={0},dydx_transfer={0},dydx_trade_perps={0}",
+            "warn,crypto_yield_farming_bot={0},data_collector={0},data_recorder={0},main={0},see_balances={0},spot_swap={0},trading_bot={0},transact_gm_tokens={0},dydx_transfer={0},dydx_trade_perps={0},plan={0},testnet_bootstrap={0},evaluate_returns={0},data_retention={0},funding_collector={0},strategy={0},export={0},gas_profile={0},backfill={0},deploy_gmx_callback={0},wallet_watchdog={0},stress={0},gm_price_verifier={0},bridge={0},whatif={0},price_stream={0},pipeline_watchdog={0},signal_sink={0},raise_cash={0},rebalance_costs={0}",
             loki_log_level
         )
     ).unwrap_or_else(|_| EnvFilter::new(
-        "warn,crypto_yield_farming_bot=info,data_collector=info,data_recorder=info,main=info,see_balances=info,spot_swap=info,trading_bot=info,transact_gm_tokens=info,dydx_transfer=info,dydx_trade_perps=info"
+        "warn,crypto_yield_farming_bot=info,data_collector=info,data_recorder=info,main=info,see_balances=info,spot_swap=info,trading_bot=info,transact_gm_tokens=info,dydx_transfer=info,dydx_trade_perps=info,plan=info,testnet_bootstrap=info,evaluate_returns=info,data_retention=info,funding_collector=info,strategy=info,export=info,gas_profile=info,backfill=info,deploy_gmx_callback=info,wallet_watchdog=info,stress=info,gm_price_verifier=info,bridge=info,whatif=info,price_stream=info,pipeline_watchdog=info,signal_sink=info,raise_cash=info,rebalance_costs=info"
+    ));
+
+    let env_filter_otel = EnvFilter::try_new(
+        &format!(
+            "warn,crypto_yield_farming_bot={0},data_collector={0},data_recorder={0},main={0},see_balances={0},spot_swap={0},trading_bot={0},transact_gm_tokens={0},dydx_transfer={0},dydx_trade_perps={0},plan={0},testnet_bootstrap={0},evaluate_returns={0},data_retention={0},funding_collector={0},strategy={0},export={0},gas_profile={0},backfill={0},deploy_gmx_callback={0},wallet_watchdog={0},stress={0},gm_price_verifier={0},bridge={0},whatif={0},price_stream={0},pipeline_watchdog={0},signal_sink={0},raise_cash={0},rebalance_costs={0}",
+            otel_trace_level
+        )
+    ).unwrap_or_else(|_| EnvFilter::new(
+        "warn,crypto_yield_farming_bot=info,data_collector=info,data_recorder=info,main=info,see_balances=info,spot_swap=info,trading_bot=info,transact_gm_tokens=info,dydx_transfer=info,dydx_trade_perps=info,plan=info,testnet_bootstrap=info,evaluate_returns=info,data_retention=info,funding_collector=info,strategy=info,export=info,gas_profile=info,backfill=info,deploy_gmx_callback=info,wallet_watchdog=info,stress=info,gm_price_verifier=info,bridge=info,whatif=info,price_stream=info,pipeline_watchdog=info,signal_sink=info,raise_cash=info,rebalance_costs=info"
     ));
 
     // Console layer: always enabled, pretty human-readable logs
-    let console_layer = fmt::Layer::new()
-        .pretty()
+    let console_layer = RedactingLayer::new(fmt::Layer::new().pretty(), console_redactor)
         .with_filter(env_filter_console);
     
     // Timing layer: always enabled, tracks span timing
     let timing_layer = SpanTimingLayer;
 
+    // OpenTelemetry layer: spans exported over OTLP so a trace can follow data across processes.
+    // The trace context propagator is installed either way, without an exporter there is simply no context to propagate.
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    let otel_tracer = otlp_endpoint.and_then(|endpoint| match init_otel_tracer(&endpoint, &service_name) {
+        Ok(tracer) => Some(tracer),
+        Err(e) => {
+            eprintln!("Failed to initialize OTLP span export to {}, traces disabled: {}", endpoint, e);
+            None
+        }
+    });
+    let otel_layer = otel_tracer.map(|tracer| {
+        RedactingLayer::new(tracing_opentelemetry::layer().with_tracer(tracer), otel_redactor)
+            .with_filter(env_filter_otel)
+    });
+
     // Loki layer: structured JSON logs via local Alloy container
     let (loki_layer, loki_task) = tracing_loki::builder()
         .label("job", "crypto-yield-farming-bot")?
@@ -108,7 +154,7 @@ pub fn init_logging(bin_name: String) -> Result<(), tracing_loki::Error> {
         .label("deployment", deployment)?
         .label("stage", stage)?
         .build_url(loki_url)?;
-    let loki_layer = loki_layer
+    let loki_layer = RedactingLayer::new(loki_layer, loki_redactor)
         .with_filter(env_filter_loki);
 
     if log_to_file {   
@@ -127,13 +173,15 @@ pub fn init_logging(bin_name: String) -> Result<(), tracing_loki::Error> {
         let file_layer = fmt::Layer::new()
             .json()
             .with_writer(non_blocking)
-            .with_timer(fmt::time::UtcTime::rfc_3339())
+            .with_timer(fmt::time::UtcTime::rfc_3339());
+        let file_layer = RedactingLayer::new(file_layer, file_redactor)
             .with_filter(env_filter_file);
 
         tracing_subscriber::registry()
             .with(console_layer)
             .with(loki_layer)
             .with(file_layer)
+            .with(otel_layer)
             .with(timing_layer)
             .init();
     } else {
@@ -141,6 +189,7 @@ pub fn init_logging(bin_name: String) -> Result<(), tracing_loki::Error> {
         tracing_subscriber::registry()
             .with(console_layer)
             .with(loki_layer)
+            .with(otel_layer)
             .with(timing_layer)
             .init();
     }
@@ -152,6 +201,358 @@ pub fn init_logging(bin_name: String) -> Result<(), tracing_loki::Error> {
     Ok(())
 }
 
+/// Build a tracer exporting spans over OTLP/gRPC in batches, installed as the global tracer provider
+fn init_otel_tracer(endpoint: &str, service_name: &str) -> Result<opentelemetry_sdk::trace::Tracer, opentelemetry::trace::TraceError> {
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(endpoint)
+        .build()?;
+    let provider = opentelemetry_sdk::trace::TracerProvider::builder()
+        .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+        .with_resource(opentelemetry_sdk::Resource::new(vec![
+            opentelemetry::KeyValue::new("service.name", service_name.to_string()),
+        ]))
+        .build();
+    let tracer = provider.tracer("crypto-yield-farming-bot");
+    global::set_tracer_provider(provider);
+    Ok(tracer)
+}
+
+/// Flush spans still queued for export, call before a short-lived binary exits
+pub fn shutdown_tracing() {
+    global::shutdown_tracer_provider();
+}
+
+// --- REDACTION ---
+// Sinks are wrapped in a redacting layer that rebuilds each event and span with sensitive values masked before the
+// sink sees them, so the policy applies whatever format the sink writes (pretty, JSON, Loki, OTLP).
+
+/// Fields masked under the mask policy when LOG_REDACT_FIELDS is unset, matched as substrings of the field name
+const DEFAULT_REDACT_FIELDS: &str = "balance,private_key,mnemonic,password,secret,api_key";
+/// Replacement for masked values
+const REDACTED: &str = "[redacted]";
+/// Shortest value registered as a secret, shorter values would mask unrelated text
+const MIN_SECRET_LEN: usize = 8;
+/// Most fields an event or span is rebuilt with, tracing callsites can't declare more
+const MAX_REDACTED_FIELDS: usize = 32;
+
+static SECRETS: OnceLock<RwLock<Vec<String>>> = OnceLock::new();
+
+/// Register a secret value (private key, mnemonic, API key) to be masked wherever it appears in any sink's logs
+pub fn register_secret(secret: &str) {
+    let secret = secret.trim().trim_start_matches("0x");
+    if secret.len() < MIN_SECRET_LEN {
+        return;
+    }
+    let mut secrets = SECRETS.get_or_init(|| RwLock::new(Vec::new())).write().unwrap_or_else(|e| e.into_inner());
+    if !secrets.iter().any(|known| known == secret) {
+        secrets.push(secret.to_string());
+    }
+}
+
+/// How a sink's logs are redacted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RedactionPolicy {
+    Off,  // Full detail, only registered secrets are masked
+    Mask, // Sensitive fields masked and addresses shortened
+}
+
+impl RedactionPolicy {
+    fn from_env(var: &str, default: RedactionPolicy) -> Self {
+        match env::var(var).map(|v| v.to_lowercase()) {
+            Ok(v) if v == "off" => RedactionPolicy::Off,
+            Ok(v) if v == "mask" => RedactionPolicy::Mask,
+            Ok(v) => panic!("{} must be off or mask, got {}", var, v),
+            Err(_) => default,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Redactor {
+    policy: RedactionPolicy,
+    fields: Arc<Vec<String>>,
+}
+
+impl Redactor {
+    fn new(policy: RedactionPolicy, fields: Vec<String>) -> Self {
+        Self { policy, fields: Arc::new(fields) }
+    }
+
+    fn is_sensitive(&self, field_name: &str) -> bool {
+        self.policy == RedactionPolicy::Mask && self.fields.iter().any(|field| field_name.to_lowercase().contains(field))
+    }
+
+    /// Redacted form of a string value, None when nothing in it needs masking
+    fn redact_str(&self, field_name: &str, value: &str) -> Option<String> {
+        if self.is_sensitive(field_name) {
+            return Some(REDACTED.to_string());
+        }
+        let mut redacted = mask_secrets(value);
+        if self.policy == RedactionPolicy::Mask {
+            redacted = mask_addresses(&redacted);
+        }
+        (redacted != value).then_some(redacted)
+    }
+
+    /// Record the fields with `record`, returning them redacted, or None when no value changed
+    fn redacted_fields(&self, record: impl FnOnce(&mut dyn Visit)) -> Option<Vec<(Field, RedactedValue)>> {
+        let mut visitor = RedactingVisitor { redactor: self, fields: Vec::new(), changed: false };
+        record(&mut visitor);
+        (visitor.changed && !visitor.fields.is_empty()).then_some(visitor.fields)
+    }
+}
+
+/// A recorded field value, kept in its original type unless it was redacted
+enum RedactedValue {
+    I64(i64),
+    U64(u64),
+    I128(i128),
+    U128(u128),
+    F64(f64),
+    Bool(bool),
+    Str(String),
+    Display(DisplayValue<String>), // Debug-formatted or redacted values, written without quotes as the original was
+}
+
+impl RedactedValue {
+    fn as_value(&self) -> &dyn Value {
+        match self {
+            RedactedValue::I64(v) => v,
+            RedactedValue::U64(v) => v,
+            RedactedValue::I128(v) => v,
+            RedactedValue::U128(v) => v,
+            RedactedValue::F64(v) => v,
+            RedactedValue::Bool(v) => v,
+            RedactedValue::Str(v) => v,
+            RedactedValue::Display(v) => v,
+        }
+    }
+}
+
+struct RedactingVisitor<'a> {
+    redactor: &'a Redactor,
+    fields: Vec<(Field, RedactedValue)>,
+    changed: bool,
+}
+
+impl RedactingVisitor<'_> {
+    fn push_scalar(&mut self, field: &Field, value: RedactedValue) {
+        if self.redactor.is_sensitive(field.name()) {
+            self.changed = true;
+            self.fields.push((field.clone(), RedactedValue::Display(tracing::field::display(REDACTED.to_string()))));
+        } else {
+            self.fields.push((field.clone(), value));
+        }
+    }
+}
+
+impl Visit for RedactingVisitor<'_> {
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.push_scalar(field, RedactedValue::I64(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.push_scalar(field, RedactedValue::U64(value));
+    }
+
+    fn record_i128(&mut self, field: &Field, value: i128) {
+        self.push_scalar(field, RedactedValue::I128(value));
+    }
+
+    fn record_u128(&mut self, field: &Field, value: u128) {
+        self.push_scalar(field, RedactedValue::U128(value));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.push_scalar(field, RedactedValue::F64(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.push_scalar(field, RedactedValue::Bool(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        let value = match self.redactor.redact_str(field.name(), value) {
+            Some(redacted) => {
+                self.changed = true;
+                RedactedValue::Display(tracing::field::display(redacted))
+            }
+            None => RedactedValue::Str(value.to_string()),
+        };
+        self.fields.push((field.clone(), value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        let formatted = format!("{:?}", value);
+        let value = match self.redactor.redact_str(field.name(), &formatted) {
+            Some(redacted) => {
+                self.changed = true;
+                redacted
+            }
+            None => formatted,
+        };
+        self.fields.push((field.clone(), RedactedValue::Display(tracing::field::display(value))));
+    }
+}
+
+/// Values to rebuild a value set from, padded with empty entries (skipped when recorded) to a fixed length
+fn value_array(fields: &[(Field, RedactedValue)]) -> [(&Field, Option<&dyn Value>); MAX_REDACTED_FIELDS] {
+    std::array::from_fn(|i| match fields.get(i) {
+        Some((field, value)) => (field, Some(value.as_value())),
+        None => (&fields[0].0, None),
+    })
+}
+
+/// Replace registered secrets in a value
+fn mask_secrets(value: &str) -> String {
+    let Some(secrets) = SECRETS.get() else {
+        return value.to_string();
+    };
+    let secrets = secrets.read().unwrap_or_else(|e| e.into_inner());
+    let mut masked = value.to_string();
+    for secret in secrets.iter().filter(|secret| value.contains(secret.as_str())) {
+        masked = masked.replace(secret.as_str(), REDACTED);
+    }
+    masked
+}
+
+/// Shorten 0x-prefixed 20 byte addresses to their first and last hex digits (0x1234…abcd), longer hex strings
+/// such as transaction hashes are left as they are
+fn mask_addresses(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut masked = String::with_capacity(value.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let is_address = bytes[i] == b'0'
+            && bytes.get(i + 1) == Some(&b'x')
+            && i + 42 <= bytes.len()
+            && bytes[i + 2..i + 42].iter().all(u8::is_ascii_hexdigit)
+            && !bytes.get(i + 42).is_some_and(u8::is_ascii_hexdigit)
+            && (i == 0 || !bytes[i - 1].is_ascii_alphanumeric());
+        if is_address {
+            masked.push_str(&value[i..i + 6]);
+            masked.push('…');
+            masked.push_str(&value[i + 38..i + 42]);
+            i += 42;
+        } else {
+            let ch = value[i..].chars().next().unwrap();
+            masked.push(ch);
+            i += ch.len_utf8();
+        }
+    }
+    masked
+}
+
+/// Wraps a sink layer, handing it events and span fields with sensitive values redacted under its policy
+struct RedactingLayer<L> {
+    inner: L,
+    redactor: Redactor,
+}
+
+impl<L> RedactingLayer<L> {
+    fn new(inner: L, redactor: Redactor) -> Self {
+        Self { inner, redactor }
+    }
+}
+
+impl<S, L> Layer<S> for RedactingLayer<L>
+where
+    S: Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    L: Layer<S>,
+{
+    fn on_register_dispatch(&self, subscriber: &Dispatch) {
+        self.inner.on_register_dispatch(subscriber);
+    }
+
+    fn on_layer(&mut self, subscriber: &mut S) {
+        self.inner.on_layer(subscriber);
+    }
+
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        self.inner.register_callsite(metadata)
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>, ctx: Context<'_, S>) -> bool {
+        self.inner.enabled(metadata, ctx)
+    }
+
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(fields) = self.redactor.redacted_fields(|visitor| attrs.record(visitor)) else {
+            return self.inner.on_new_span(attrs, id, ctx);
+        };
+        let metadata = attrs.metadata();
+        let values = value_array(&fields);
+        let value_set = metadata.fields().value_set(&values);
+        let redacted = if attrs.is_root() {
+            span::Attributes::new_root(metadata, &value_set)
+        } else if attrs.is_contextual() {
+            span::Attributes::new(metadata, &value_set)
+        } else {
+            span::Attributes::child_of(attrs.parent().cloned().unwrap(), metadata, &value_set)
+        };
+        self.inner.on_new_span(&redacted, id, ctx);
+    }
+
+    fn on_record(&self, id: &Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        let metadata = ctx.metadata(id);
+        let (Some(metadata), Some(fields)) = (metadata, self.redactor.redacted_fields(|visitor| values.record(visitor))) else {
+            return self.inner.on_record(id, values, ctx);
+        };
+        let redacted_values = value_array(&fields);
+        let value_set = metadata.fields().value_set(&redacted_values);
+        self.inner.on_record(id, &span::Record::new(&value_set), ctx);
+    }
+
+    fn on_follows_from(&self, id: &Id, follows: &Id, ctx: Context<'_, S>) {
+        self.inner.on_follows_from(id, follows, ctx);
+    }
+
+    fn event_enabled(&self, event: &Event<'_>, ctx: Context<'_, S>) -> bool {
+        self.inner.event_enabled(event, ctx)
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let Some(fields) = self.redactor.redacted_fields(|visitor| event.record(visitor)) else {
+            return self.inner.on_event(event, ctx);
+        };
+        let metadata = event.metadata();
+        let values = value_array(&fields);
+        let value_set = metadata.fields().value_set(&values);
+        let redacted = if event.is_contextual() {
+            Event::new(metadata, &value_set)
+        } else {
+            Event::new_child_of(event.parent().cloned(), metadata, &value_set)
+        };
+        self.inner.on_event(&redacted, ctx);
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        self.inner.on_enter(id, ctx);
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        self.inner.on_exit(id, ctx);
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        self.inner.on_close(id, ctx);
+    }
+
+    fn on_id_change(&self, old: &Id, new: &Id, ctx: Context<'_, S>) {
+        self.inner.on_id_change(old, new, ctx);
+    }
+
+    // Lets callers reach the wrapped layer (e.g. the OpenTelemetry layer's span context)
+    unsafe fn downcast_raw(&self, id: TypeId) -> Option<*const ()> {
+        if id == TypeId::of::<Self>() {
+            Some(self as *const Self as *const ())
+        } else {
+            unsafe { self.inner.downcast_raw(id) }
+        }
+    }
+}
+
 // Custom layer to track span timing for specific spans with "on_close" field = true
 struct SpanTimingLayer;
 
@@ -229,3 +630,98 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    const ADDRESS: &str = "0x1234567890abcdef1234567890abcdef12345678";
+
+    /// Sink layer capturing each event's and new span's fields as `name=value` pairs
+    struct CaptureLayer(Arc<Mutex<Vec<String>>>);
+
+    struct CaptureVisitor<'a>(&'a mut Vec<String>);
+
+    impl Visit for CaptureVisitor<'_> {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.push(format!("{}={}", field.name(), value));
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0.push(format!("{}={:?}", field.name(), value));
+        }
+    }
+
+    impl<S: Subscriber> Layer<S> for CaptureLayer {
+        fn on_new_span(&self, attrs: &span::Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+            let mut fields = Vec::new();
+            attrs.record(&mut CaptureVisitor(&mut fields));
+            self.0.lock().unwrap().push(fields.join(" "));
+        }
+
+        fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+            let mut fields = Vec::new();
+            event.record(&mut CaptureVisitor(&mut fields));
+            self.0.lock().unwrap().push(fields.join(" "));
+        }
+    }
+
+    fn capture(policy: RedactionPolicy, log: impl FnOnce()) -> Vec<String> {
+        let captured = Arc::new(Mutex::new(Vec::new()));
+        let redactor = Redactor::new(policy, DEFAULT_REDACT_FIELDS.split(',').map(str::to_string).collect());
+        let subscriber = tracing_subscriber::registry().with(RedactingLayer::new(CaptureLayer(captured.clone()), redactor));
+        tracing::subscriber::with_default(subscriber, log);
+        captured.lock().unwrap().clone()
+    }
+
+    #[test]
+    fn mask_addresses_shortens_standalone_addresses() {
+        assert_eq!(mask_addresses(&format!("to {} done", ADDRESS)), "to 0x1234…5678 done");
+        assert_eq!(mask_addresses(&format!("{},{}", ADDRESS, ADDRESS)), "0x1234…5678,0x1234…5678");
+        assert_eq!(mask_addresses(&format!("→ {} ✓", ADDRESS)), "→ 0x1234…5678 ✓");
+    }
+
+    #[test]
+    fn mask_addresses_leaves_hashes_and_embedded_hex_alone() {
+        let tx_hash = format!("{}abcdef1234567890abcdef1234567890", ADDRESS);
+        assert_eq!(mask_addresses(&tx_hash), tx_hash);
+        let embedded = format!("id{}", ADDRESS);
+        assert_eq!(mask_addresses(&embedded), embedded);
+        let truncated = &ADDRESS[..30];
+        assert_eq!(mask_addresses(truncated), truncated);
+    }
+
+    #[test]
+    fn mask_policy_redacts_sensitive_fields_and_shortens_addresses() {
+        let events = capture(RedactionPolicy::Mask, || {
+            tracing::info!(balances = %"1.5 WETH", balance_wei = 5u64, wallet = %ADDRESS, count = 3, "Balances logged");
+        });
+        assert_eq!(events.len(), 1);
+        assert!(events[0].contains("balances=[redacted]"));
+        assert!(events[0].contains("balance_wei=[redacted]"));
+        assert!(events[0].contains("wallet=0x1234…5678"));
+        assert!(events[0].contains("count=3"));
+        assert!(events[0].contains("message=Balances logged"));
+    }
+
+    #[test]
+    fn mask_policy_redacts_span_fields() {
+        let events = capture(RedactionPolicy::Mask, || {
+            let _span = tracing::info_span!("transfer", balance = %"3 USDC", amount = 1u64).entered();
+        });
+        assert_eq!(events, vec!["balance=[redacted] amount=1".to_string()]);
+    }
+
+    #[test]
+    fn off_policy_only_masks_registered_secrets() {
+        register_secret("0xdeadbeefcafebabe0123");
+        let events = capture(RedactionPolicy::Off, || {
+            tracing::info!(balance = %"2 WETH", wallet = %ADDRESS, "key deadbeefcafebabe0123 loaded");
+        });
+        assert_eq!(events.len(), 1);
+        assert!(events[0].contains("balance=2 WETH"));
+        assert!(events[0].contains(&format!("wallet={}", ADDRESS)));
+        assert!(events[0].contains("message=key [redacted] loaded"));
+    }
+}
@@ -0,0 +1,304 @@
+use chrono::{DateTime, Utc};
+use ethers::types::Address;
+use redis::aio::MultiplexedConnection;
+use rust_decimal::Decimal;
+use std::collections::{BTreeSet, HashMap};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Cell, Paragraph, Row, Table},
+};
+
+use crate::db::db_manager::DbManager;
+use crate::db::models::{
+    orders::OrderModel,
+    strategy_runs::{StrategyRunModel, ReturnModelMetricsModel},
+    trades::TradeModel,
+};
+use crate::hedging::dydx_client::DYDX_VENUE;
+use crate::redis_client::{self, ErrorSource};
+use crate::strategy::types::PortfolioSnapshot;
+use crate::wallet::WalletManager;
+
+const RECENT_TRADE_LIMIT: i64 = 10;
+const ERROR_RATE_WINDOW_MINUTES: i64 = 60;
+const INGEST_STALE_AFTER_SECS: i64 = 15 * 60; // Data collection runs every 5 minutes
+
+/// Current and target weight of one market
+#[derive(Debug, Clone)]
+pub struct WeightRow {
+    pub market: String,
+    pub current_weight: Decimal,
+    pub target_weight: Decimal,
+}
+
+/// Everything shown on one monitor frame. Each section loads independently,
+/// a failing source is reported in `load_errors` instead of blanking the whole screen.
+#[derive(Debug, Clone, Default)]
+pub struct MonitorSnapshot {
+    pub refreshed_at: DateTime<Utc>,
+    pub weights: Vec<WeightRow>,
+    pub open_hedges: Vec<OrderModel>,
+    pub last_run: Option<StrategyRunModel>,
+    pub last_metrics: Option<ReturnModelMetricsModel>,
+    pub recent_trades: Vec<TradeModel>,
+    pub trade_markets: HashMap<i32, String>,
+    pub last_market_state_at: Option<DateTime<Utc>>,
+    pub last_token_price_at: Option<DateTime<Utc>>,
+    pub error_counts: Vec<(ErrorSource, Option<u64>)>, // Failures over the error rate window, None when Redis is unavailable
+    pub load_errors: Vec<String>,
+}
+
+impl MonitorSnapshot {
+    /// Read the latest bot state from the database, the wallet and the Redis error counters
+    pub async fn load(
+        db_manager: &DbManager,
+        wallet_manager: &WalletManager,
+        mut redis_connection: Option<&mut MultiplexedConnection>,
+    ) -> Self {
+        let mut snapshot = Self {
+            refreshed_at: db_manager.clock.now(),
+            ..Self::default()
+        };
+
+        let display_names = db_manager.get_market_display_names().await.unwrap_or_default();
+        let names_by_id: HashMap<i32, String> = db_manager.market_id_map.iter()
+            .map(|(address, id)| (*id, display_names.get(address).cloned().unwrap_or_else(|| format!("{:?}", address))))
+            .collect();
+
+        // Current holdings against the targets of the latest strategy run
+        match db_manager.get_latest_strategy_run().await {
+            Ok(run) => snapshot.last_run = run,
+            Err(e) => snapshot.load_errors.push(format!("Strategy run: {}", e)),
+        }
+        let targets: HashMap<i32, Decimal> = match &snapshot.last_run {
+            Some(run) => match db_manager.get_strategy_run_markets(run.id).await {
+                Ok(markets) => markets.into_iter().map(|m| (m.market_id, m.target_weight)).collect(),
+                Err(e) => {
+                    snapshot.load_errors.push(format!("Strategy run targets: {}", e));
+                    HashMap::new()
+                }
+            },
+            None => HashMap::new(),
+        };
+        let current = match PortfolioSnapshot::load(wallet_manager, db_manager).await {
+            Ok(portfolio) => portfolio.weights,
+            Err(e) => {
+                snapshot.load_errors.push(format!("Wallet balances: {}", e));
+                HashMap::new()
+            }
+        };
+        let market_ids: BTreeSet<i32> = targets.keys().copied()
+            .chain(current.keys().filter_map(|address| db_manager.market_id_map.get(address).copied()))
+            .collect();
+        let address_by_id: HashMap<i32, Address> = db_manager.market_id_map.iter().map(|(address, id)| (*id, *address)).collect();
+        snapshot.weights = market_ids.into_iter()
+            .map(|id| WeightRow {
+                market: names_by_id.get(&id).cloned().unwrap_or_else(|| id.to_string()),
+                current_weight: address_by_id.get(&id).and_then(|address| current.get(address)).copied().unwrap_or(Decimal::ZERO),
+                target_weight: targets.get(&id).copied().unwrap_or(Decimal::ZERO),
+            })
+            .filter(|row| !row.current_weight.is_zero() || !row.target_weight.is_zero())
+            .collect();
+        snapshot.weights.sort_by(|a, b| b.target_weight.cmp(&a.target_weight).then(b.current_weight.cmp(&a.current_weight)));
+
+        match db_manager.get_open_orders(DYDX_VENUE, None).await {
+            Ok(orders) => snapshot.open_hedges = orders,
+            Err(e) => snapshot.load_errors.push(format!("Open hedges: {}", e)),
+        }
+        match db_manager.get_recent_return_model_metrics(1).await {
+            Ok(metrics) => snapshot.last_metrics = metrics.into_iter().next(),
+            Err(e) => snapshot.load_errors.push(format!("Return model metrics: {}", e)),
+        }
+        match db_manager.get_recent_trades(RECENT_TRADE_LIMIT).await {
+            Ok(trades) => snapshot.recent_trades = trades,
+            Err(e) => snapshot.load_errors.push(format!("Recent trades: {}", e)),
+        }
+        snapshot.trade_markets = names_by_id;
+        match db_manager.get_latest_ingest_timestamps().await {
+            Ok((market_state_at, token_price_at)) => {
+                snapshot.last_market_state_at = market_state_at;
+                snapshot.last_token_price_at = token_price_at;
+            }
+            Err(e) => snapshot.load_errors.push(format!("Ingest timestamps: {}", e)),
+        }
+
+        for source in [ErrorSource::Rpc, ErrorSource::Db] {
+            let count = match redis_connection.as_deref_mut() {
+                Some(connection) => match redis_client::get_error_count(connection, source, ERROR_RATE_WINDOW_MINUTES, snapshot.refreshed_at).await {
+                    Ok(count) => Some(count),
+                    Err(e) => {
+                        snapshot.load_errors.push(format!("{} error counts: {}", source.as_str(), e));
+                        None
+                    }
+                },
+                None => None,
+            };
+            snapshot.error_counts.push((source, count));
+        }
+
+        snapshot
+    }
+}
+
+/// Draw the snapshot: weights and hedges on top, strategy and pipeline health in the middle, recent trades at the bottom
+pub fn render(frame: &mut Frame, snapshot: &MonitorSnapshot) {
+    let [header_area, top_area, middle_area, trades_area] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Percentage(40),
+        Constraint::Length(8),
+        Constraint::Min(5),
+    ]).areas(frame.area());
+    let [weights_area, hedges_area] = Layout::horizontal([Constraint::Percentage(55), Constraint::Percentage(45)]).areas(top_area);
+    let [run_area, health_area] = Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(middle_area);
+
+    let header_style = Style::default().add_modifier(Modifier::BOLD);
+    let header = if snapshot.load_errors.is_empty() {
+        Line::from(format!("Bot monitor | refreshed {} | q to quit", snapshot.refreshed_at.format("%Y-%m-%d %H:%M:%S UTC")))
+    } else {
+        Line::styled(
+            format!("Bot monitor | refreshed {} | {}", snapshot.refreshed_at.format("%H:%M:%S UTC"), snapshot.load_errors.join(" | ")),
+            Style::default().fg(Color::Red),
+        )
+    };
+    frame.render_widget(Paragraph::new(header), header_area);
+
+    // Portfolio weights vs targets
+    let weight_rows = snapshot.weights.iter().map(|row| {
+        let drift = row.current_weight - row.target_weight;
+        Row::new(vec![
+            Cell::from(row.market.clone()),
+            Cell::from(format_percent(row.current_weight)),
+            Cell::from(format_percent(row.target_weight)),
+            Cell::from(format_percent(drift)).style(drift_style(drift)),
+        ])
+    });
+    let weights = Table::new(weight_rows, [Constraint::Min(20), Constraint::Length(9), Constraint::Length(9), Constraint::Length(9)])
+        .header(Row::new(vec!["Market", "Current", "Target", "Drift"]).style(header_style))
+        .block(Block::bordered().title("Portfolio weights"));
+    frame.render_widget(weights, weights_area);
+
+    // Open hedges
+    let hedge_rows = snapshot.open_hedges.iter().map(|order| Row::new(vec![
+        order.ticker.clone(),
+        order.side.clone(),
+        format!("{}/{}", order.filled_size.normalize(), order.size.normalize()),
+        order.status.clone(),
+    ]));
+    let hedges = Table::new(hedge_rows, [Constraint::Min(10), Constraint::Length(5), Constraint::Min(12), Constraint::Length(15)])
+        .header(Row::new(vec!["Ticker", "Side", "Filled/Size", "Status"]).style(header_style))
+        .block(Block::bordered().title(format!("Open hedges ({})", snapshot.open_hedges.len())));
+    frame.render_widget(hedges, hedges_area);
+
+    // Last strategy run and its evaluated return model metrics
+    let mut run_lines = vec![match &snapshot.last_run {
+        Some(run) => Line::from(format!("Run {} at {} ({})", run.id, run.created_at.format("%Y-%m-%d %H:%M"), format_age(snapshot.refreshed_at, run.created_at))),
+        None => Line::from("No strategy runs recorded"),
+    }];
+    if let Some(relaxed) = snapshot.last_run.as_ref().and_then(|run| run.relaxed_constraints.as_ref()) {
+        run_lines.push(Line::from(format!("Relaxed constraints: {}", relaxed)));
+    }
+    match &snapshot.last_metrics {
+        Some(metrics) => {
+            run_lines.push(Line::from(format!("Evaluated run {} over {}h, {} markets", metrics.run_id, metrics.horizon_hours, metrics.market_count)));
+            run_lines.push(Line::from(format!("Bias {:.2} bps | MAE {:.2} bps", metrics.bias_bps, metrics.mae_bps)));
+            run_lines.push(Line::from(format!(
+                "Rank correlation {} ({})",
+                metrics.rank_correlation.map(|c| format!("{:.3}", c)).unwrap_or_else(|| "N/A".to_string()),
+                metrics.reporting_currency
+            )));
+        }
+        None => run_lines.push(Line::from("No return model metrics yet")),
+    }
+    frame.render_widget(Paragraph::new(run_lines).block(Block::bordered().title("Last strategy run")), run_area);
+
+    // Pipeline health and error rates
+    let mut health_lines = vec![
+        ingest_line("Market states", snapshot.refreshed_at, snapshot.last_market_state_at),
+        ingest_line("Token prices", snapshot.refreshed_at, snapshot.last_token_price_at),
+    ];
+    for (source, count) in &snapshot.error_counts {
+        let label = format!("{} errors (last {}m)", source.as_str().to_uppercase(), ERROR_RATE_WINDOW_MINUTES);
+        health_lines.push(match count {
+            Some(0) => Line::from(format!("{}: 0", label)),
+            Some(count) => Line::styled(
+                format!("{}: {} ({:.2}/min)", label, count, *count as f64 / ERROR_RATE_WINDOW_MINUTES as f64),
+                Style::default().fg(Color::Yellow),
+            ),
+            None => Line::from(format!("{}: N/A", label)),
+        });
+    }
+    frame.render_widget(Paragraph::new(health_lines).block(Block::bordered().title("Pipeline health")), health_area);
+
+    // Recent trades
+    let trade_rows = snapshot.recent_trades.iter().map(|trade| {
+        let market = trade.market_id
+            .and_then(|id| snapshot.trade_markets.get(&id).cloned())
+            .unwrap_or_else(|| "N/A".to_string());
+        let amount = trade.market_token_amount
+            .map(|amount| format!("{} GM", amount.normalize()))
+            .or_else(|| match (trade.long_token_amount, trade.short_token_amount) {
+                (None, None) => None,
+                (long, short) => Some(format!("{} / {}", long.unwrap_or_default().normalize(), short.unwrap_or_default().normalize())),
+            })
+            .unwrap_or_else(|| "N/A".to_string());
+        let status_style = match trade.status.as_str() {
+            "Failed" | "Cancelled" => Style::default().fg(Color::Red),
+            "Pending" => Style::default().fg(Color::Yellow),
+            _ => Style::default(),
+        };
+        Row::new(vec![
+            Cell::from(trade.created_at.format("%m-%d %H:%M").to_string()),
+            Cell::from(trade.action_type.clone()),
+            Cell::from(market),
+            Cell::from(amount),
+            Cell::from(trade.status.clone()).style(status_style),
+        ])
+    });
+    let trades = Table::new(trade_rows, [
+        Constraint::Length(11),
+        Constraint::Length(13),
+        Constraint::Min(20),
+        Constraint::Min(16),
+        Constraint::Length(10),
+    ])
+        .header(Row::new(vec!["Created", "Action", "Market", "Amount", "Status"]).style(header_style))
+        .block(Block::bordered().title("Recent trades"));
+    frame.render_widget(trades, trades_area);
+}
+
+// --- HELPERS ---
+
+fn format_percent(weight: Decimal) -> String {
+    format!("{:.2}%", weight * Decimal::from(100))
+}
+
+fn drift_style(drift: Decimal) -> Style {
+    if drift.abs() >= Decimal::new(5, 2) {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default()
+    }
+}
+
+fn format_age(now: DateTime<Utc>, at: DateTime<Utc>) -> String {
+    let age = now - at;
+    if age.num_hours() >= 1 {
+        format!("{}h {}m ago", age.num_hours(), age.num_minutes() % 60)
+    } else {
+        format!("{}m {}s ago", age.num_minutes(), age.num_seconds() % 60)
+    }
+}
+
+fn ingest_line(label: &str, now: DateTime<Utc>, last: Option<DateTime<Utc>>) -> Line<'static> {
+    match last {
+        Some(at) if (now - at).num_seconds() > INGEST_STALE_AFTER_SECS => Line::styled(
+            format!("{}: {} (stale)", label, format_age(now, at)),
+            Style::default().fg(Color::Red),
+        ),
+        Some(at) => Line::from(format!("{}: {}", label, format_age(now, at))),
+        None => Line::styled(format!("{}: never", label), Style::default().fg(Color::Red)),
+    }
+}
@@ -0,0 +1,156 @@
+// Signature-based token allowances, so spending can be authorized inside the transaction that spends the tokens
+// instead of by a separate approve transaction.
+// - EIP-2612: tokens implementing `permit` take a signed allowance that anyone can submit (GM flows bundle it into the
+//   ExchangeRouter multicall).
+// - Permit2: the canonical Permit2 contract holds one standing approval per token and grants signed, single-use
+//   transfers from it (0x swaps carry the signature appended to their calldata).
+use ethers::prelude::*;
+use ethers::types::transaction::eip712::{EIP712Domain, TypedData};
+use serde_json::json;
+use tracing::{debug, instrument};
+use eyre::Result;
+
+use crate::clock::Clock;
+use crate::wallet::WalletManager;
+
+abigen!(
+    IERC20Permit,
+    r#"[
+        function name() external view returns (string)
+        function nonces(address owner) external view returns (uint256)
+        function DOMAIN_SEPARATOR() external view returns (bytes32)
+        function permit(address owner, address spender, uint256 value, uint256 deadline, uint8 v, bytes32 r, bytes32 s) external
+    ]"#
+);
+
+/// Permits are valid for this long after signing, they are used within the same transaction flow
+pub const PERMIT_DEADLINE_SECS: i64 = 30 * 60;
+
+// EIP-712 domain versions used by EIP-2612 tokens (OpenZeppelin ERC20Permit uses "1", Circle's USDC "2")
+const EIP2612_DOMAIN_VERSIONS: [&str; 2] = ["1", "2"];
+
+/// Signed EIP-2612 allowance, ready to be submitted to the token's `permit`
+#[derive(Debug, Clone)]
+pub struct Eip2612Permit {
+    pub token: Address,
+    pub owner: Address,
+    pub spender: Address,
+    pub value: U256,
+    pub deadline: U256,
+    pub signature: Signature,
+}
+
+impl Eip2612Permit {
+    /// Calldata of the token's `permit` call applying this allowance
+    pub fn calldata(&self, wallet_manager: &WalletManager) -> Result<Bytes> {
+        let token = IERC20Permit::new(self.token, wallet_manager.signer.clone());
+        let mut r = [0u8; 32];
+        let mut s = [0u8; 32];
+        self.signature.r.to_big_endian(&mut r);
+        self.signature.s.to_big_endian(&mut s);
+        token.permit(self.owner, self.spender, self.value, self.deadline, self.signature.v as u8, r, s)
+            .calldata()
+            .ok_or_else(|| eyre::eyre!("Failed to encode permit calldata"))
+    }
+}
+
+/// Sign an EIP-2612 allowance of `value` for `spender`, None if the token doesn't support permits.
+/// Support is detected by matching the token's DOMAIN_SEPARATOR against the known domain versions,
+/// so a token whose domain can't be reproduced falls back to a regular approval rather than a failing permit.
+#[instrument(skip(wallet_manager, clock))]
+pub async fn sign_eip2612_permit(
+    wallet_manager: &WalletManager,
+    clock: &dyn Clock,
+    token_address: Address,
+    spender: Address,
+    value: U256,
+) -> Result<Option<Eip2612Permit>> {
+    let token = IERC20Permit::new(token_address, wallet_manager.signer.clone());
+    let (Ok(domain_separator), Ok(nonce), Ok(name)) = tokio::join!(
+        token.domain_separator().call(),
+        token.nonces(wallet_manager.address).call(),
+        token.name().call(),
+    ) else {
+        debug!("Token does not implement EIP-2612 permits");
+        return Ok(None);
+    };
+
+    let chain_id = wallet_manager.signer.get_chainid().await?;
+    let Some(version) = EIP2612_DOMAIN_VERSIONS.iter().find(|version| {
+        let domain = EIP712Domain {
+            name: Some(name.clone()),
+            version: Some(version.to_string()),
+            chain_id: Some(chain_id),
+            verifying_contract: Some(token_address),
+            salt: None,
+        };
+        domain.separator() == domain_separator
+    }) else {
+        debug!("Token permit domain not recognized, falling back to approval");
+        return Ok(None);
+    };
+
+    let deadline = U256::from(clock.now().timestamp() + PERMIT_DEADLINE_SECS);
+    let typed_data: TypedData = serde_json::from_value(json!({
+        "types": {
+            "EIP712Domain": [
+                { "name": "name", "type": "string" },
+                { "name": "version", "type": "string" },
+                { "name": "chainId", "type": "uint256" },
+                { "name": "verifyingContract", "type": "address" },
+            ],
+            "Permit": [
+                { "name": "owner", "type": "address" },
+                { "name": "spender", "type": "address" },
+                { "name": "value", "type": "uint256" },
+                { "name": "nonce", "type": "uint256" },
+                { "name": "deadline", "type": "uint256" },
+            ],
+        },
+        "primaryType": "Permit",
+        "domain": {
+            "name": name,
+            "version": version,
+            "chainId": chain_id,
+            "verifyingContract": token_address,
+        },
+        "message": {
+            "owner": wallet_manager.address,
+            "spender": spender,
+            "value": value,
+            "nonce": nonce,
+            "deadline": deadline,
+        },
+    }))?;
+    let signature = wallet_manager.signer.inner().signer().sign_typed_data(&typed_data).await?;
+    debug!(version = version, nonce = %nonce, deadline = %deadline, "EIP-2612 permit signed");
+
+    Ok(Some(Eip2612Permit {
+        token: token_address,
+        owner: wallet_manager.address,
+        spender,
+        value,
+        deadline,
+        signature,
+    }))
+}
+
+/// Sign a Permit2 transfer message (EIP-712 typed data as returned by the swap API)
+#[instrument(skip(wallet_manager, typed_data))]
+pub async fn sign_permit2(wallet_manager: &WalletManager, typed_data: &TypedData) -> Result<Signature> {
+    let signature = wallet_manager.signer.inner().signer().sign_typed_data(typed_data).await?;
+    debug!("Permit2 transfer signed");
+    Ok(signature)
+}
+
+/// Append a Permit2 signature to 0x swap calldata: the signature length as a 32-byte word, then the signature
+pub fn append_permit2_signature(transaction_data: &Bytes, signature: &Signature) -> Bytes {
+    let signature_bytes = signature.to_vec();
+    let mut length_word = [0u8; 32];
+    U256::from(signature_bytes.len()).to_big_endian(&mut length_word);
+
+    let mut data = transaction_data.to_vec();
+    data.extend_from_slice(&length_word);
+    data.extend_from_slice(&signature_bytes);
+    data.into()
+}
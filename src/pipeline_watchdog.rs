@@ -0,0 +1,148 @@
+// Watches the Redis data streams the collector publishes to, so a dead collector is noticed without reading logs.
+// Each stream has its own staleness threshold; entry IDs carry the time they were published, so the watchdog
+// measures staleness from the collector's side even when it starts long after the last entry.
+use chrono::{DateTime, Utc};
+use redis::AsyncCommands;
+use redis::aio::MultiplexedConnection;
+use redis::streams::{StreamRangeReply, StreamReadOptions, StreamReadReply};
+use std::sync::Arc;
+use eyre::Result;
+use tracing::{info, debug, error, instrument};
+
+use crate::config::Config;
+use crate::db::db_manager::DbManager;
+use crate::db::models::pipeline_health::NewPipelineHealthModel;
+
+const CHECK_INTERVAL_MS: usize = 30_000; // Longest a stream read blocks before the thresholds are checked again
+const STALE_STATUS: &str = "stale";
+const RECOVERED_STATUS: &str = "recovered";
+
+/// Watch state of one data stream
+#[derive(Debug, Clone)]
+struct StreamWatch {
+    stream: &'static str,
+    stale_after: chrono::Duration,
+    last_id: String,
+    last_entry_at: Option<DateTime<Utc>>,
+    stale: bool,
+}
+
+pub struct PipelineWatchdog {
+    db: Arc<DbManager>,
+    redis_connection: MultiplexedConnection,
+    watches: Vec<StreamWatch>,
+    started_at: DateTime<Utc>, // Stands in for the last entry of a stream that has none yet
+}
+
+impl PipelineWatchdog {
+    /// Start watching token_prices and market_states from their latest entries. The connection is used for
+    /// blocking stream reads and should not be shared.
+    #[instrument(skip(config, db, redis_connection))]
+    pub async fn init(config: &Config, db: Arc<DbManager>, mut redis_connection: MultiplexedConnection) -> Result<Self> {
+        let mut watches = Vec::new();
+        for (stream, stale_after_mins) in [
+            ("token_prices", config.token_prices_stale_after_mins),
+            ("market_states", config.market_states_stale_after_mins),
+        ] {
+            let latest: StreamRangeReply = redis_connection.xrevrange_count(stream, "+", "-", 1).await?;
+            let last_id = latest.ids.first().map(|entry| entry.id.clone()).unwrap_or_else(|| "0".to_string());
+            let last_entry_at = entry_time(&last_id);
+            info!(stream = stream, stale_after_mins = stale_after_mins, last_entry_at = ?last_entry_at, "Watching data stream");
+            watches.push(StreamWatch {
+                stream,
+                stale_after: chrono::Duration::minutes(stale_after_mins as i64),
+                last_id,
+                last_entry_at,
+                stale: false,
+            });
+        }
+
+        Ok(Self {
+            started_at: db.clock.now(),
+            db,
+            redis_connection,
+            watches,
+        })
+    }
+
+    // Follow the streams forever (to be called in long-running background task)
+    #[instrument(skip(self))]
+    pub async fn run(&mut self) {
+        info!(check_interval_ms = CHECK_INTERVAL_MS, "Starting pipeline watchdog");
+        loop {
+            if let Err(e) = self.check().await {
+                error!(?e, "Pipeline watchdog check failed");
+                tokio::time::sleep(std::time::Duration::from_millis(CHECK_INTERVAL_MS as u64)).await;
+            }
+        }
+    }
+
+    /// Wait up to the check interval for new entries, then alert on every stream past its threshold and record
+    /// streams that resumed. Returns the number of stale streams.
+    pub async fn check(&mut self) -> Result<usize> {
+        let streams: Vec<&str> = self.watches.iter().map(|watch| watch.stream).collect();
+        let last_ids: Vec<String> = self.watches.iter().map(|watch| watch.last_id.clone()).collect();
+        let stream_options = StreamReadOptions::default().block(CHECK_INTERVAL_MS).count(100);
+        let reply: Option<StreamReadReply> = self.redis_connection.xread_options(&streams, &last_ids, &stream_options).await?;
+        for stream_key in reply.map(|reply| reply.keys).unwrap_or_default() {
+            let Some(watch) = self.watches.iter_mut().find(|watch| watch.stream == stream_key.key) else {
+                continue;
+            };
+            if let Some(entry) = stream_key.ids.last() {
+                watch.last_id = entry.id.clone();
+                watch.last_entry_at = entry_time(&entry.id).or(watch.last_entry_at);
+                debug!(stream = watch.stream, entries = stream_key.ids.len(), last_entry_at = ?watch.last_entry_at, "New stream entries");
+            }
+        }
+
+        let now = self.db.clock.now();
+        let mut stale_count = 0;
+        for i in 0..self.watches.len() {
+            let watch = &self.watches[i];
+            let since = watch.last_entry_at.unwrap_or(self.started_at);
+            let is_stale = now - since > watch.stale_after;
+            if is_stale {
+                stale_count += 1;
+            }
+            if is_stale == watch.stale {
+                continue;
+            }
+
+            if is_stale {
+                error!(
+                    stream = watch.stream,
+                    last_entry_at = ?watch.last_entry_at,
+                    stale_after_mins = watch.stale_after.num_minutes(),
+                    "ALERT: no {} entry for {} minutes, check the data collector",
+                    watch.stream,
+                    (now - since).num_minutes()
+                );
+            } else {
+                info!(stream = watch.stream, last_entry_at = ?watch.last_entry_at, "Data stream recovered");
+            }
+            self.record(i, if is_stale { STALE_STATUS } else { RECOVERED_STATUS }).await?;
+            self.watches[i].stale = is_stale;
+        }
+        Ok(stale_count)
+    }
+
+    async fn record(&self, index: usize, status: &str) -> Result<()> {
+        let watch = &self.watches[index];
+        self.db.insert_pipeline_health(&NewPipelineHealthModel {
+            stream: watch.stream.to_string(),
+            status: status.to_string(),
+            last_entry_at: watch.last_entry_at,
+            stale_after_secs: watch.stale_after.num_seconds() as i32,
+        }).await?;
+        Ok(())
+    }
+}
+
+/// Publish time encoded in a stream entry ID (milliseconds-sequence), None for the empty stream placeholder
+fn entry_time(id: &str) -> Option<DateTime<Utc>> {
+    let millis: i64 = id.split('-').next()?.parse().ok()?;
+    if millis == 0 {
+        return None;
+    }
+    DateTime::<Utc>::from_timestamp_millis(millis)
+}
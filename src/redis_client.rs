@@ -0,0 +1,140 @@
+use redis::{AsyncCommands, Client, ConnectionAddr, IntoConnectionInfo};
+use redis::aio::MultiplexedConnection;
+use redis::streams::StreamMaxlen;
+use eyre::Result;
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+use tracing::{info, warn, instrument};
+
+use crate::config::{self, Config};
+use crate::telemetry::TRACEPARENT_FIELD;
+
+const REDIS_CONNECT_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const REDIS_CONNECT_MAX_BACKOFF: Duration = Duration::from_secs(30);
+const ERROR_COUNTER_TTL_SECS: i64 = 24 * 60 * 60;
+const STREAM_MAX_LEN: usize = 1000; // Approximate cap on entries kept per data stream
+
+/// Component whose failures are counted in per-minute Redis buckets for monitoring
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorSource {
+    Rpc,
+    Db,
+}
+
+impl ErrorSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorSource::Rpc => "rpc",
+            ErrorSource::Db => "db",
+        }
+    }
+}
+
+/// Build a Redis client from the configured URL (`redis://` or `rediss://` for TLS),
+/// applying any configured credentials, database index and TLS options on top of the URL
+pub fn create_client(config: &Config) -> Result<Client> {
+    let mut connection_info = config.redis_url.as_str().into_connection_info()?;
+
+    if let Some(username) = &config.redis_username {
+        connection_info.redis.username = Some(username.clone());
+    }
+    if let Some(password) = &config.redis_password {
+        connection_info.redis.password = Some(password.clone());
+    }
+    if let Some(db) = config.redis_db {
+        connection_info.redis.db = db;
+    }
+    if let ConnectionAddr::TcpTls { insecure, .. } = &mut connection_info.addr {
+        config::init_crypto_provider();
+        if config.redis_tls_insecure {
+            warn!("Redis TLS certificate verification disabled");
+            *insecure = true;
+        }
+    }
+
+    Ok(Client::open(connection_info)?)
+}
+
+/// Open a multiplexed connection, retrying with exponential backoff while Redis is not up yet
+#[instrument(skip(client, config), fields(on_close = true))]
+pub async fn connect_with_retry(client: &Client, config: &Config) -> Result<MultiplexedConnection> {
+    let mut backoff = REDIS_CONNECT_INITIAL_BACKOFF;
+    let mut attempt = 1;
+    loop {
+        match client.get_multiplexed_async_connection().await {
+            Ok(connection) => {
+                info!(attempt = attempt, "Redis connection established");
+                return Ok(connection);
+            }
+            Err(e) if attempt < config.redis_connect_max_retries => {
+                warn!(
+                    error = %e,
+                    attempt = attempt,
+                    max_retries = config.redis_connect_max_retries,
+                    backoff_secs = backoff.as_secs(),
+                    "Redis not reachable, retrying"
+                );
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(REDIS_CONNECT_MAX_BACKOFF);
+                attempt += 1;
+            }
+            Err(e) => {
+                return Err(eyre::eyre!("Failed to connect to Redis after {} attempts: {}", attempt, e));
+            }
+        }
+    }
+}
+
+/// Append a serialized model to a data stream under the `data` field, with the trace context of the span that
+/// produced it under `traceparent` (when traces are exported) so the consumer can continue the trace
+pub async fn publish_stream_entry(
+    connection: &mut MultiplexedConnection,
+    stream: &str,
+    data: String,
+    traceparent: Option<&str>,
+) -> Result<()> {
+    let mut fields = vec![("data", data)];
+    if let Some(traceparent) = traceparent {
+        fields.push((TRACEPARENT_FIELD, traceparent.to_string()));
+    }
+    let _: () = connection.xadd_maxlen(stream, StreamMaxlen::Approx(STREAM_MAX_LEN), "*", &fields).await?;
+    Ok(())
+}
+
+fn error_counter_key(source: ErrorSource, minute: i64) -> String {
+    format!("errors:{}:{}", source.as_str(), minute)
+}
+
+/// Count one failure of the given component in the current minute's bucket.
+/// Failing to count is only logged, error accounting must never take down the caller.
+pub async fn record_error(connection: &mut MultiplexedConnection, source: ErrorSource) {
+    let key = error_counter_key(source, Utc::now().timestamp() / 60);
+    let result: redis::RedisResult<()> = redis::pipe()
+        .incr(&key, 1)
+        .ignore()
+        .expire(&key, ERROR_COUNTER_TTL_SECS)
+        .ignore()
+        .query_async(connection)
+        .await;
+    if let Err(e) = result {
+        warn!(error = %e, source = source.as_str(), "Failed to record error count");
+    }
+}
+
+/// Total failures of the given component over the last `window_minutes` minutes up to `now`
+pub async fn get_error_count(
+    connection: &mut MultiplexedConnection,
+    source: ErrorSource,
+    window_minutes: i64,
+    now: DateTime<Utc>,
+) -> Result<u64> {
+    let current_minute = now.timestamp() / 60;
+    let keys: Vec<String> = (0..window_minutes)
+        .map(|offset| error_counter_key(source, current_minute - offset))
+        .collect();
+    if keys.is_empty() {
+        return Ok(0);
+    }
+    let counts: Vec<Option<u64>> = connection.mget(keys).await?;
+    Ok(counts.into_iter().flatten().sum())
+}
\ No newline at end of file
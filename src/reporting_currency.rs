@@ -0,0 +1,83 @@
+use eyre::Result;
+use rust_decimal::Decimal;
+use chrono::{DateTime, Utc};
+use tracing::{info, instrument};
+
+use crate::config::Config;
+use crate::db::db_manager::DbManager;
+
+const USD: &str = "USD";
+
+// Reporting currencies whose prices are stored under a wrapped token symbol
+const CURRENCY_TOKEN_ALIASES: [(&str, &str); 1] = [
+    ("ETH", "WETH"),
+];
+
+/// Unit that valuations and performance metrics are reported in.
+/// USD values are converted at the stored price of the currency token at (or just before) the valuation time.
+#[derive(Debug, Clone)]
+pub struct ReportingCurrency {
+    pub symbol: String,
+    token_id: Option<i32>, // None for USD
+}
+
+impl ReportingCurrency {
+    pub fn usd() -> Self {
+        Self {
+            symbol: USD.to_string(),
+            token_id: None,
+        }
+    }
+
+    /// Resolve the configured reporting currency to a tracked token.
+    /// Currencies without stored prices (e.g. fiat other than USD) are rejected.
+    #[instrument(skip(config, db_manager), fields(reporting_currency = %config.reporting_currency))]
+    pub async fn load(config: &Config, db_manager: &DbManager) -> Result<Self> {
+        let symbol = config.reporting_currency.to_uppercase();
+        if symbol == USD {
+            return Ok(Self::usd());
+        }
+
+        let token_symbol = CURRENCY_TOKEN_ALIASES.iter()
+            .find(|(currency, _)| *currency == symbol)
+            .map(|(_, token)| token.to_string())
+            .unwrap_or_else(|| symbol.clone());
+        let tokens = db_manager.get_all_tokens().await?;
+        let token = tokens.iter()
+            .find(|t| t.symbol.to_uppercase() == token_symbol)
+            .ok_or_else(|| eyre::eyre!(
+                "Reporting currency {} has no stored prices, use USD or the symbol of a tracked token", symbol
+            ))?;
+
+        info!(token_id = token.id, token_symbol = %token.symbol, "Reporting currency resolved");
+        Ok(Self {
+            symbol,
+            token_id: Some(token.id),
+        })
+    }
+
+    pub fn is_usd(&self) -> bool {
+        self.token_id.is_none()
+    }
+
+    /// USD price of one unit of the reporting currency at the given time
+    pub async fn usd_price_at(&self, db_manager: &DbManager, at: DateTime<Utc>) -> Result<Decimal> {
+        let Some(token_id) = self.token_id else {
+            return Ok(Decimal::ONE);
+        };
+        let price = db_manager.get_token_price_at_or_before(token_id, at).await?
+            .ok_or_else(|| eyre::eyre!("No {} price stored at or before {}", self.symbol, at))?;
+        if price <= Decimal::ZERO {
+            return Err(eyre::eyre!("Invalid {} price {} at {}", self.symbol, price, at));
+        }
+        Ok(price)
+    }
+
+    /// Convert a USD value to the reporting currency at the given time
+    pub async fn convert_usd_at(&self, db_manager: &DbManager, usd_value: Decimal, at: DateTime<Utc>) -> Result<Decimal> {
+        if self.is_usd() {
+            return Ok(usd_value);
+        }
+        Ok(usd_value / self.usd_price_at(db_manager, at).await?)
+    }
+}
@@ -0,0 +1,131 @@
+// Inbound side of the external signal integration. An external model (or a person) posts per-market expected
+// returns and vetoes as a JSON payload signed with an Ethereum key; only signers listed in EXTERNAL_SIGNAL_TRUST
+// are accepted. Accepted signals are queued in the external_signals table, which the strategy engine reads on
+// its next run, so signals can be added or withdrawn without code changes or restarts.
+use chrono::{DateTime, Utc};
+use ethers::types::{Address, Signature};
+use ethers::utils::keccak256;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::str::FromStr;
+use tracing::{info, instrument};
+
+use crate::config::Config;
+use crate::db::db_manager::DbManager;
+use crate::db::models::external_signals::NewExternalSignalModel;
+
+/// Oldest a payload may be when it arrives (and how far ahead of the sink's clock it may be dated)
+pub const MAX_PAYLOAD_AGE_SECS: i64 = 5 * 60;
+/// Longest a signal may stay active, a signer has to re-post to keep a view in force
+pub const MAX_SIGNAL_TTL_HOURS: i64 = 7 * 24;
+
+/// Signed payload body, e.g.
+/// `{"issued_at": 1760000000, "expires_at": 1760086400, "signals": [{"market": "0x70d9...", "expected_return_apr": "0.18"}]}`
+#[derive(Debug, Clone, Deserialize)]
+pub struct SignalPayload {
+    pub issued_at: i64,  // Unix seconds
+    pub expires_at: i64, // Unix seconds
+    pub signals: Vec<MarketSignal>,
+}
+
+/// The signer's view of one market
+#[derive(Debug, Clone, Deserialize)]
+pub struct MarketSignal {
+    pub market: Address,
+    #[serde(default)]
+    pub expected_return_apr: Option<Decimal>, // Annualized LP return, blended with the internal estimate by the signer's trust
+    #[serde(default)]
+    pub veto: bool,                           // Keep the market out of the allocation while the signal is active
+}
+
+/// Why a payload was refused, mapped to the HTTP status returned to the poster
+#[derive(Debug, thiserror::Error)]
+pub enum SignalRejection {
+    #[error("invalid payload: {0}")]
+    Invalid(String),
+    #[error("signer {0:?} is not trusted")]
+    UntrustedSigner(Address),
+    #[error("payload already received")]
+    Replayed,
+    #[error("failed to store signals: {0}")]
+    Storage(#[from] sqlx::Error),
+}
+
+impl SignalRejection {
+    pub fn status(&self) -> &'static str {
+        match self {
+            SignalRejection::Invalid(_) => "400 Bad Request",
+            SignalRejection::UntrustedSigner(_) => "403 Forbidden",
+            SignalRejection::Replayed => "409 Conflict",
+            SignalRejection::Storage(_) => "500 Internal Server Error",
+        }
+    }
+}
+
+/// Recover the signer of a payload from its EIP-191 (personal_sign) signature over the raw body, and check the
+/// signer is trusted
+pub fn verify_signer(config: &Config, body: &[u8], signature: &str) -> Result<Address, SignalRejection> {
+    let signature = Signature::from_str(signature.trim())
+        .map_err(|e| SignalRejection::Invalid(format!("malformed signature: {}", e)))?;
+    let signer = signature.recover(body)
+        .map_err(|e| SignalRejection::Invalid(format!("signature recovery failed: {}", e)))?;
+    if !config.external_signal_trust.contains_key(&signer) {
+        return Err(SignalRejection::UntrustedSigner(signer));
+    }
+    Ok(signer)
+}
+
+/// Verify and queue one posted payload for the strategy engine. Returns the signer and the number of signals queued.
+#[instrument(skip(config, db_manager, body, signature), fields(on_close = true))]
+pub async fn accept_payload(config: &Config, db_manager: &DbManager, body: &[u8], signature: &str) -> Result<(Address, usize), SignalRejection> {
+    let signer = verify_signer(config, body, signature)?;
+    let payload: SignalPayload = serde_json::from_slice(body)
+        .map_err(|e| SignalRejection::Invalid(format!("malformed JSON: {}", e)))?;
+
+    let now = db_manager.clock.now();
+    let timestamp = |secs: i64, field: &str| DateTime::<Utc>::from_timestamp(secs, 0)
+        .ok_or_else(|| SignalRejection::Invalid(format!("{} out of range", field)));
+    let issued_at = timestamp(payload.issued_at, "issued_at")?;
+    let expires_at = timestamp(payload.expires_at, "expires_at")?;
+    if (now - issued_at).num_seconds().abs() > MAX_PAYLOAD_AGE_SECS {
+        return Err(SignalRejection::Invalid(format!("issued_at must be within {}s of the current time", MAX_PAYLOAD_AGE_SECS)));
+    }
+    if expires_at <= issued_at || expires_at - issued_at > chrono::Duration::hours(MAX_SIGNAL_TTL_HOURS) {
+        return Err(SignalRejection::Invalid(format!("expires_at must be after issued_at and within {}h of it", MAX_SIGNAL_TTL_HOURS)));
+    }
+    if payload.signals.is_empty() {
+        return Err(SignalRejection::Invalid("no signals".to_string()));
+    }
+
+    let mut models = Vec::with_capacity(payload.signals.len());
+    for signal in &payload.signals {
+        let Some(market_id) = db_manager.market_id_map.get(&signal.market).copied() else {
+            return Err(SignalRejection::Invalid(format!("unknown market {:?}", signal.market)));
+        };
+        if signal.expected_return_apr.is_none() && !signal.veto {
+            return Err(SignalRejection::Invalid(format!("signal for {:?} has neither an expected return nor a veto", signal.market)));
+        }
+        models.push(NewExternalSignalModel {
+            signer: format!("{:?}", signer),
+            market_id,
+            expected_return_apr: signal.expected_return_apr,
+            veto: signal.veto,
+            issued_at,
+            expires_at,
+            payload_hash: hex::encode(keccak256(body)),
+        });
+    }
+
+    let inserted = db_manager.insert_external_signals(&models).await?;
+    if !inserted {
+        return Err(SignalRejection::Replayed);
+    }
+    info!(
+        signer = ?signer,
+        signals = models.len(),
+        vetoes = models.iter().filter(|model| model.veto).count(),
+        expires_at = %expires_at,
+        "External signals queued"
+    );
+    Ok((signer, models.len()))
+}
@@ -0,0 +1,67 @@
+use eyre::Result;
+use rust_decimal::Decimal;
+use tracing::{debug, info, warn, instrument};
+
+use crate::config::Config;
+use crate::wallet::WalletManager;
+use super::swap_manager::SwapManager;
+use super::types::SwapRequest;
+
+/// Build the swaps that sweep dust asset balances into the base stablecoin.
+/// Dust is any asset balance worth less than the dust threshold but at least the minimum swap size (smaller balances
+/// aren't worth the gas). The base stablecoin and WETH, which backs the gas reserve, are never swept.
+#[instrument(skip(config, wallet_manager), fields(on_close = true))]
+pub async fn plan_dust_consolidation(config: &Config, wallet_manager: &WalletManager) -> Result<Vec<SwapRequest>> {
+    let Some(base_stablecoin) = config.base_stablecoin else {
+        debug!("No base stablecoin configured, skipping dust consolidation");
+        return Ok(Vec::new());
+    };
+    if wallet_manager.asset_token(&base_stablecoin).is_none() {
+        return Err(eyre::eyre!("Base stablecoin not found: {}", base_stablecoin));
+    }
+
+    let balances = wallet_manager.get_asset_token_balances().await?;
+    let mut swap_requests = Vec::new();
+    for (address, balance) in balances {
+        if address == base_stablecoin || address == config.wnt_address || balance <= Decimal::ZERO {
+            continue;
+        }
+        let Some(token) = wallet_manager.asset_token(&address) else {
+            continue;
+        };
+        let value_usd = balance * token.last_mid_price_usd;
+        if value_usd < config.min_swap_size_usd || value_usd >= config.dust_threshold_usd {
+            continue;
+        }
+
+        debug!(token = %token.symbol, balance = %balance, value_usd = %value_usd, "Found dust balance");
+        swap_requests.push(SwapRequest {
+            from_token_address: address,
+            to_token_address: base_stablecoin,
+            amount: balance,
+            side: "SELL".to_string(), // Sell the whole balance
+            rebalance_id: config.rebalance_id,
+        });
+    }
+    Ok(swap_requests)
+}
+
+/// Sweep dust asset balances into the base stablecoin in a single swap session, continuing past individual failures.
+/// Returns the number of balances swept.
+#[instrument(skip(config, wallet_manager, swap_manager), fields(on_close = true))]
+pub async fn consolidate_dust(config: &Config, wallet_manager: &WalletManager, swap_manager: &SwapManager) -> Result<usize> {
+    let swap_requests = plan_dust_consolidation(config, wallet_manager).await?;
+    if swap_requests.is_empty() {
+        return Ok(0);
+    }
+    info!(swap_count = swap_requests.len(), threshold = %config.dust_threshold_usd, "Consolidating dust balances into base stablecoin");
+
+    let mut swept = 0;
+    for swap_request in &swap_requests {
+        match swap_manager.execute_swap(swap_request).await {
+            Ok(_) => swept += 1,
+            Err(e) => warn!(error = ?e, from_token = ?swap_request.from_token_address, amount = %swap_request.amount, "Failed to sweep dust balance"),
+        }
+    }
+    Ok(swept)
+}
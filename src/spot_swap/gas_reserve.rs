@@ -0,0 +1,78 @@
+use eyre::Result;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::*;
+use tracing::{debug, info, instrument};
+
+use crate::config::Config;
+use crate::wallet::WalletManager;
+use super::swap_manager::SwapManager;
+use super::types::SwapRequest;
+
+/// Buffer on the source token amount needed to buy native ETH, covering price moves and swap fees
+const SOURCE_TOKEN_BUFFER: f64 = 1.05;
+
+/// Build the swap that tops the native balance back up to the target, or None while it is above the minimum.
+/// WETH is unwrapped when enough is held, otherwise native ETH is bought with the configured source token.
+#[instrument(skip(config, wallet_manager), fields(on_close = true))]
+pub async fn plan_gas_reserve_top_up(config: &Config, wallet_manager: &WalletManager) -> Result<Option<SwapRequest>> {
+    let native_balance = wallet_manager.get_native_balance().await?;
+    if native_balance >= config.gas_reserve_min_native {
+        debug!(native_balance = %native_balance, minimum = %config.gas_reserve_min_native, "Gas reserve sufficient");
+        return Ok(None);
+    }
+    let needed = config.gas_reserve_target_native - native_balance;
+    let native_token = wallet_manager.native_token();
+
+    let weth_balance = wallet_manager.get_token_balance(config.wnt_address).await?;
+    if weth_balance >= needed {
+        return Ok(Some(SwapRequest {
+            from_token_address: config.wnt_address,
+            to_token_address: native_token.address,
+            amount: needed,
+            side: "SELL".to_string(), // Unwrap WETH to native ETH
+            rebalance_id: config.rebalance_id,
+        }));
+    }
+
+    let source_token_address = config.gas_reserve_source_token.ok_or_else(|| eyre::eyre!(
+        "Native balance {} below gas reserve minimum {} and WETH balance {} cannot cover {}, no GAS_RESERVE_SOURCE_TOKEN configured",
+        native_balance, config.gas_reserve_min_native, weth_balance, needed
+    ))?;
+    let source_token = wallet_manager.asset_token(&source_token_address)
+        .ok_or_else(|| eyre::eyre!("Gas reserve source token not found: {}", source_token_address))?;
+    let source_balance = wallet_manager.get_token_balance(source_token_address).await?;
+    if source_token.last_mid_price_usd > Decimal::ZERO {
+        let source_needed = needed * native_token.last_mid_price_usd / source_token.last_mid_price_usd
+            * Decimal::from_f64(SOURCE_TOKEN_BUFFER).unwrap();
+        if source_balance < source_needed {
+            return Err(eyre::eyre!(
+                "Insufficient {} balance to replenish gas reserve: need ~{} but have {}",
+                source_token.symbol, source_needed.round_dp(source_token.decimals as u32), source_balance
+            ));
+        }
+    }
+
+    Ok(Some(SwapRequest {
+        from_token_address: source_token_address,
+        to_token_address: native_token.address,
+        amount: needed,
+        side: "BUY".to_string(), // Exact native ETH out
+        rebalance_id: config.rebalance_id,
+    }))
+}
+
+/// Replenish the native gas reserve if it has dropped below the minimum, returns true if a top-up was executed
+#[instrument(skip(config, wallet_manager, swap_manager), fields(on_close = true))]
+pub async fn ensure_gas_reserve(config: &Config, wallet_manager: &WalletManager, swap_manager: &SwapManager) -> Result<bool> {
+    let Some(swap_request) = plan_gas_reserve_top_up(config, wallet_manager).await? else {
+        return Ok(false);
+    };
+    info!(
+        amount = %swap_request.amount,
+        from_token = ?swap_request.from_token_address,
+        target = %config.gas_reserve_target_native,
+        "Native balance below gas reserve minimum, replenishing"
+    );
+    swap_manager.execute_swap(&swap_request).await?;
+    Ok(true)
+}
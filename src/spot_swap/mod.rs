@@ -1,3 +1,10 @@
 pub mod paraswap_api_client;
 pub mod types;
 pub mod swap_manager;
+pub mod quoter;
+pub mod zerox_api_client;
+pub mod oneinch_api_client;
+pub mod gas_reserve;
+pub mod dust;
+pub mod twap;
+pub mod screening;
\ No newline at end of file
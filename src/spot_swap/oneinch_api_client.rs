@@ -0,0 +1,93 @@
+use reqwest_middleware::ClientWithMiddleware;
+use std::str::FromStr;
+use tracing::{debug, instrument};
+use url::Url;
+use ethers::types::{Address, Bytes, U256};
+use rust_decimal::Decimal;
+use eyre::Result;
+
+use super::types::{QuoteRequest, QuoteResponse, OneInchSwapResponse};
+use super::quoter::{self, SwapQuoter};
+use crate::config::Config;
+
+const ONEINCH_BASE_URL: &str = "https://api.1inch.dev/swap/v6.0";
+const ONEINCH_REQUESTS_PER_SEC: u32 = 1;
+const ONEINCH_FALLBACK_GAS_LIMIT: u64 = 500_000; // Used when the API skips gas estimation
+
+#[derive(Debug, Clone)]
+pub struct OneInchClient {
+    http_client: ClientWithMiddleware,
+    base_url: String,
+    chain_id: u64,
+    taker_address: Address,
+}
+
+impl OneInchClient {
+    pub fn new(taker_address: Address, api_key: &str, config: &Config) -> Self {
+        let authorization = format!("Bearer {}", api_key);
+        let http_client = quoter::build_http_client(&[("authorization", authorization.as_str())], ONEINCH_REQUESTS_PER_SEC);
+
+        Self {
+            http_client,
+            base_url: ONEINCH_BASE_URL.to_string(),
+            chain_id: config.chain_id,
+            taker_address,
+        }
+    }
+}
+
+impl SwapQuoter for OneInchClient {
+    fn name(&self) -> &'static str {
+        "1inch"
+    }
+
+    /// Get a swap quote from the 1inch router (sell side only)
+    #[instrument(skip(self))]
+    async fn get_quote(&self, request: &QuoteRequest) -> Result<QuoteResponse> {
+        if request.side == "BUY" {
+            return Err(eyre::eyre!("1inch does not support BUY (exact output) quotes"));
+        }
+        let url = Url::parse(&format!("{}/{}/swap", self.base_url, self.chain_id))?;
+        let params = [
+            ("src", format!("{:?}", request.from_token)),
+            ("dst", format!("{:?}", request.to_token)),
+            ("amount", quoter::decimal_to_u256_str(request.amount, request.from_token_decimals)),
+            ("from", format!("{:?}", self.taker_address)),
+            ("origin", format!("{:?}", self.taker_address)),
+            ("slippage", request.slippage_tolerance.to_string()),
+            ("disableEstimate", "true".to_string()), // Allowance is ensured (and the tx simulated) after quote selection
+        ];
+
+        let response = self.http_client.get(url).query(&params).send().await?.error_for_status()?;
+
+        let swap_response: OneInchSwapResponse = response.json().await?;
+        debug!(?swap_response, "Received swap response from 1inch");
+
+        let to_amount = quoter::u256_str_to_decimal(&swap_response.dst_amount, request.to_token_decimals);
+        let transaction = swap_response.tx;
+        let gas_limit = if transaction.gas > 0 { transaction.gas } else { ONEINCH_FALLBACK_GAS_LIMIT };
+        let gas_price = U256::from_dec_str(&transaction.gas_price)?;
+        let gas_cost = quoter::u256_str_to_decimal(&(U256::from(gas_limit) * gas_price).to_string(), 18);
+        let to_contract = Address::from_str(&transaction.to)?;
+
+        Ok(QuoteResponse {
+            source: self.name(),
+            from_token: request.from_token,
+            to_token: request.to_token,
+            from_amount: request.amount,
+            to_amount,
+            from_amount_usd: request.amount * request.from_token_price_usd,
+            to_amount_usd: to_amount * request.to_token_price_usd,
+            to_contract,
+            allowance_target: to_contract, // The aggregation router pulls tokens itself
+            transaction_data: Bytes::from_str(&transaction.data)?,
+            value: U256::from_dec_str(&transaction.value)?,
+            partner_fee_bps: 0,
+            partner_fee_amount: Decimal::ZERO,
+            gas_cost_usd: gas_cost * request.native_token_price_usd,
+            block_number: None,
+            permit2: None,
+            route_contracts: Vec::new(), // Route isn't requested from 1inch
+        })
+    }
+}
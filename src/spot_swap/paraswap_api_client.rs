@@ -1,11 +1,6 @@
-use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
-use reqwest_retry::{RetryTransientMiddleware, policies::ExponentialBackoff};
-use governor::{Quota, DefaultDirectRateLimiter};
-use nonzero_ext::*;
-use std::time::Duration;
+use reqwest_middleware::ClientWithMiddleware;
 use std::str::FromStr;
-use std::sync::Arc;
-use tracing::{warn, instrument};
+use tracing::{debug, instrument};
 use url::Url;
 use ethers::types::{Address, Bytes, U256};
 use rust_decimal::Decimal;
@@ -13,19 +8,11 @@ use rust_decimal::prelude::*;
 use eyre::Result;
 
 use super::types::{QuoteRequest, QuoteResponse, ParaSwapQuoteResponse};
+use super::quoter::{self, SwapQuoter};
 use crate::config::Config;
 
 const PARASWAP_BASE_URL: &str = "https://api.paraswap.io";
-
-struct ParaswapRateLimiter {
-    rate_limiter: Arc<DefaultDirectRateLimiter>,
-}
-
-impl reqwest_ratelimit::RateLimiter for ParaswapRateLimiter {
-    async fn acquire_permit(&self) {
-        self.rate_limiter.until_ready().await;
-    }
-}
+const PARASWAP_API_KEY_HEADER: &str = "x-api-key";
 
 #[derive(Debug, Clone)]
 pub struct ParaSwapClient {
@@ -33,46 +20,46 @@ pub struct ParaSwapClient {
     base_url: String,
     chain_id: u64,
     taker_address: Address,
+    partner: Option<String>,
+    partner_address: Option<Address>,
+    partner_fee_bps: u32,
 }
 
 impl ParaSwapClient {
     pub fn new(taker_address: Address, config: &Config) -> Self {
-        let reqwest_client = reqwest_middleware::reqwest::Client::builder()
-            .timeout(Duration::from_secs(10))
-            .build()
-            .expect("Failed to create HTTP client");
-
-        let retry_policy = ExponentialBackoff::builder()
-            .retry_bounds(Duration::from_millis(500), Duration::from_millis(1000))
-            .build_with_max_retries(3);
-
-        let rate_limiter = ParaswapRateLimiter {
-            rate_limiter: Arc::new(DefaultDirectRateLimiter::direct(Quota::per_second(nonzero!(1u32)))),
-        };
-
-        let http_client = ClientBuilder::new(reqwest_client)
-            .with(RetryTransientMiddleware::new_with_policy(retry_policy))
-            .with(reqwest_ratelimit::all(rate_limiter))
-            .build();
+        // Authenticated (Pro API) requests carry the API key on every call
+        let headers: Vec<(&str, &str)> = config.paraswap_api_key.iter()
+            .map(|api_key| (PARASWAP_API_KEY_HEADER, api_key.as_str()))
+            .collect();
+        let http_client = quoter::build_http_client(&headers, config.paraswap_requests_per_sec);
 
         Self {
             http_client,
-            base_url: PARASWAP_BASE_URL.to_string(),
+            base_url: config.paraswap_api_url.clone().unwrap_or_else(|| PARASWAP_BASE_URL.to_string()),
             chain_id: config.chain_id,
             taker_address,
+            partner: config.paraswap_partner.clone(),
+            partner_address: config.paraswap_partner_address,
+            partner_fee_bps: config.paraswap_partner_fee_bps,
         }
     }
+}
+
+impl SwapQuoter for ParaSwapClient {
+    fn name(&self) -> &'static str {
+        "paraswap"
+    }
 
     /// Get quote for specific buy amount (ParaSwap's native feature)
     #[instrument(skip(self))]
-    pub async fn get_quote(&self, request: &QuoteRequest) -> Result<QuoteResponse> {
+    async fn get_quote(&self, request: &QuoteRequest) -> Result<QuoteResponse> {
         let amount = if request.side == "BUY" {
-            self.decimal_to_u256_str(request.amount, request.to_token_decimals)
+            quoter::decimal_to_u256_str(request.amount, request.to_token_decimals)
         } else {
-            self.decimal_to_u256_str(request.amount, request.from_token_decimals)
+            quoter::decimal_to_u256_str(request.amount, request.from_token_decimals)
         };
         let url = Url::parse(&format!("{}/swap", self.base_url))?;
-        let params = [
+        let mut params = vec![
             ("srcToken", format!("{:?}", request.from_token)),
             ("srcDecimals", request.from_token_decimals.to_string()),
             ("destToken", format!("{:?}", request.to_token)),
@@ -84,47 +71,67 @@ impl ParaSwapClient {
             ("network", self.chain_id.to_string()),
             ("version", "6.2".to_string()), 
         ];
+        if let Some(partner) = &self.partner {
+            params.push(("partner", partner.clone()));
+        }
+        if let (Some(partner_address), true) = (self.partner_address, self.partner_fee_bps > 0) {
+            params.push(("partnerAddress", format!("{:?}", partner_address)));
+            params.push(("partnerFeeBps", self.partner_fee_bps.to_string()));
+        }
 
         let response = self.http_client.get(url).query(&params).send().await?.error_for_status()?;
 
         let quote_response: ParaSwapQuoteResponse = response.json().await?;
-        tracing::debug!(?quote_response, "Received quote response from ParaSwap");
+        debug!(?quote_response, "Received quote response from ParaSwap");
         let price_route = quote_response.price_route;
 
+        // Route amounts are quoted before the partner fee, which is taken from the received amount on SELL
+        // and added to the paid amount on BUY, so adjust them to what actually leaves / arrives in the wallet
+        let src_amount = quoter::u256_str_to_decimal(&price_route.src_amount, request.from_token_decimals);
+        let dest_amount = quoter::u256_str_to_decimal(&price_route.dest_amount, request.to_token_decimals);
+        let fee_rate = Decimal::from(self.partner_fee_bps) / Decimal::from(10000);
+        let (from_amount, to_amount, partner_fee_amount) = if self.partner_fee_bps == 0 {
+            (src_amount, dest_amount, Decimal::ZERO)
+        } else if request.side == "BUY" {
+            let fee = src_amount * fee_rate;
+            (src_amount + fee, dest_amount, fee)
+        } else {
+            let fee = dest_amount * fee_rate;
+            (src_amount, dest_amount - fee, fee)
+        };
+        if self.partner_fee_bps > 0 {
+            debug!(
+                partner_fee_bps = self.partner_fee_bps,
+                partner_fee_amount = %partner_fee_amount,
+                route_partner_fee = price_route.partner_fee,
+                "Applied partner fee to quote"
+            );
+        }
+
+        let to_contract = Address::from_str(&quote_response.tx_params.to)?;
         Ok(QuoteResponse {
+            source: self.name(),
             from_token: Address::from_str(&price_route.src_token)?,
             to_token: Address::from_str(&price_route.dest_token)?,
-            from_amount: self.u256_str_to_decimal(&price_route.src_amount, request.from_token_decimals),
-            to_amount: self.u256_str_to_decimal(&price_route.dest_amount, request.to_token_decimals),
+            from_amount,
+            to_amount,
             from_amount_usd: Decimal::from_str(&price_route.src_usd)?,
             to_amount_usd: Decimal::from_str(&price_route.dest_usd)?,
-            to_contract: Address::from_str(&quote_response.tx_params.to)?,
+            to_contract,
+            allowance_target: to_contract, // Augustus v6 pulls tokens itself
             transaction_data: Bytes::from_str(&quote_response.tx_params.data)?,
             value: U256::from_dec_str(&quote_response.tx_params.value)?,
+            partner_fee_bps: self.partner_fee_bps,
+            partner_fee_amount,
+            gas_cost_usd: Decimal::from_str(&price_route.gas_cost_usd)?,
+            block_number: Some(price_route.block_number),
+            permit2: None,
+            route_contracts: price_route.best_route.iter()
+                .flat_map(|route| &route.swaps)
+                .flat_map(|swap| &swap.swap_exchanges)
+                .flat_map(|exchange| &exchange.pool_addresses)
+                .filter_map(|address| Address::from_str(address).ok())
+                .collect(),
         })
     }
-       
-
-    /// Convert decimal amount to wei (U256)
-    fn decimal_to_u256_str(&self, amount: Decimal, decimals: u8) -> String {
-        let amount_str = amount.to_string();
-        let formatted = ethers::utils::parse_units(&amount_str, decimals as usize).unwrap_or_else(|_| {
-            warn!("Failed to parse decimal value: {}", amount_str);
-            ethers::utils::ParseUnits::U256(U256::zero())
-        });
-        formatted.to_string()
-    }
-
-    /// Convert wei string to decimal
-    fn u256_str_to_decimal(&self, u256_str: &str, decimals: u8) -> Decimal {
-        let u256 = U256::from_dec_str(u256_str).unwrap_or_else(|_| {
-            warn!("Failed to parse U256 value: {}", u256_str);
-            U256::zero()
-        });
-        let formatted = ethers::utils::format_units(u256, decimals as usize).unwrap_or_else(|_| {
-            warn!("Failed to format U256 value: {}", u256);
-            "0".to_string()
-        });
-        Decimal::from_str(&formatted).unwrap_or(Decimal::ZERO)
-    }
-}
\ No newline at end of file
+}
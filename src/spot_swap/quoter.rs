@@ -0,0 +1,87 @@
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
+use reqwest_middleware::reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest_retry::{RetryTransientMiddleware, policies::ExponentialBackoff};
+use governor::{Quota, DefaultDirectRateLimiter};
+use std::future::Future;
+use std::num::NonZeroU32;
+use std::time::Duration;
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::warn;
+use ethers::types::U256;
+use rust_decimal::Decimal;
+use eyre::Result;
+
+use super::types::{QuoteRequest, QuoteResponse};
+
+/// A swap aggregator API that can quote (and build the transaction for) a swap
+pub trait SwapQuoter {
+    /// Name of the aggregator, used for logging and to tag quotes
+    fn name(&self) -> &'static str;
+
+    /// Fetch an executable quote for the request
+    fn get_quote(&self, request: &QuoteRequest) -> impl Future<Output = Result<QuoteResponse>> + Send;
+}
+
+struct ApiRateLimiter {
+    rate_limiter: Arc<DefaultDirectRateLimiter>,
+}
+
+impl reqwest_ratelimit::RateLimiter for ApiRateLimiter {
+    async fn acquire_permit(&self) {
+        self.rate_limiter.until_ready().await;
+    }
+}
+
+/// Build an HTTP client with retries, a client-side rate limit and the given default headers (e.g. API keys)
+pub fn build_http_client(headers: &[(&str, &str)], requests_per_sec: u32) -> ClientWithMiddleware {
+    let mut header_map = HeaderMap::new();
+    for (name, value) in headers {
+        let mut header_value = HeaderValue::from_str(value).expect("Invalid API header value");
+        header_value.set_sensitive(true);
+        header_map.insert(HeaderName::from_bytes(name.as_bytes()).expect("Invalid API header name"), header_value);
+    }
+    let reqwest_client = reqwest_middleware::reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .default_headers(header_map)
+        .build()
+        .expect("Failed to create HTTP client");
+
+    let retry_policy = ExponentialBackoff::builder()
+        .retry_bounds(Duration::from_millis(500), Duration::from_millis(1000))
+        .build_with_max_retries(3);
+
+    let rate_limiter = ApiRateLimiter {
+        rate_limiter: Arc::new(DefaultDirectRateLimiter::direct(Quota::per_second(
+            NonZeroU32::new(requests_per_sec).expect("API requests per second must be positive")
+        ))),
+    };
+
+    ClientBuilder::new(reqwest_client)
+        .with(RetryTransientMiddleware::new_with_policy(retry_policy))
+        .with(reqwest_ratelimit::all(rate_limiter))
+        .build()
+}
+
+/// Convert decimal amount to wei string
+pub fn decimal_to_u256_str(amount: Decimal, decimals: u8) -> String {
+    let amount_str = amount.to_string();
+    let formatted = ethers::utils::parse_units(&amount_str, decimals as usize).unwrap_or_else(|_| {
+        warn!("Failed to parse decimal value: {}", amount_str);
+        ethers::utils::ParseUnits::U256(U256::zero())
+    });
+    formatted.to_string()
+}
+
+/// Convert wei string to decimal
+pub fn u256_str_to_decimal(u256_str: &str, decimals: u8) -> Decimal {
+    let u256 = U256::from_dec_str(u256_str).unwrap_or_else(|_| {
+        warn!("Failed to parse U256 value: {}", u256_str);
+        U256::zero()
+    });
+    let formatted = ethers::utils::format_units(u256, decimals as usize).unwrap_or_else(|_| {
+        warn!("Failed to format U256 value: {}", u256);
+        "0".to_string()
+    });
+    Decimal::from_str(&formatted).unwrap_or(Decimal::ZERO)
+}
@@ -0,0 +1,56 @@
+use ethers::prelude::*;
+use std::collections::HashSet;
+use std::sync::Arc;
+use eyre::Result;
+use tracing::{debug, warn, instrument};
+
+use crate::config::Config;
+use crate::constants::CHAINALYSIS_SANCTIONS_ORACLE_ADDRESS;
+
+abigen!(
+    ChainalysisSanctionsOracle,
+    r#"[
+        function isSanctioned(address addr) external view returns (bool)
+    ]"#
+);
+
+/// Screens swap counterparties against the configured deny-list and, when enabled, the Chainalysis sanctions oracle
+pub struct AddressScreener {
+    deny_list: HashSet<Address>,
+    oracle: Option<ChainalysisSanctionsOracle<Provider<Http>>>,
+}
+
+impl AddressScreener {
+    pub fn new(config: &Config) -> Self {
+        let oracle = config.sanctions_oracle_screening.then(|| {
+            let address = CHAINALYSIS_SANCTIONS_ORACLE_ADDRESS.parse().expect("Invalid sanctions oracle address");
+            ChainalysisSanctionsOracle::new(address, Arc::clone(&config.alchemy_provider))
+        });
+        Self {
+            deny_list: config.sanctions_deny_list.clone(),
+            oracle,
+        }
+    }
+
+    /// Addresses among `addresses` flagged by the deny-list or the oracle. An oracle call failing is an error rather
+    /// than a pass, so an unavailable oracle blocks swaps instead of silently skipping the screen.
+    #[instrument(skip(self, addresses), fields(count = addresses.len()))]
+    pub async fn flagged(&self, addresses: &[Address]) -> Result<Vec<Address>> {
+        let mut flagged = Vec::new();
+        for address in addresses {
+            if self.deny_list.contains(address) {
+                warn!(address = ?address, source = "deny_list", "Sanctioned address found");
+                flagged.push(*address);
+                continue;
+            }
+            if let Some(oracle) = &self.oracle {
+                if oracle.is_sanctioned(*address).call().await? {
+                    warn!(address = ?address, source = "chainalysis_oracle", "Sanctioned address found");
+                    flagged.push(*address);
+                }
+            }
+        }
+        debug!(flagged = flagged.len(), "Addresses screened");
+        Ok(flagged)
+    }
+}
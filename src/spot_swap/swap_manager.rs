@@ -4,17 +4,24 @@ use ethers::types::{
     transaction::eip2718::TypedTransaction,
 };
 use ethers::prelude::*;
-use tracing::{debug, info, warn, instrument};
+use tracing::{debug, info, warn, error, instrument};
 use eyre::Result;
 use rust_decimal::Decimal;
 use rust_decimal::prelude::*;
 use std::sync::Arc;
 
-use crate::config::Config;
+use crate::config::{Config, dynamic::DynamicConfig};
 use crate::wallet::WalletManager;
-use crate::constants::WNT_ADDRESS;
-use super::types::{SwapRequest, QuoteRequest, QuoteResponse};
+use crate::permit;
+use crate::db::db_manager::DbManager;
+use crate::db::models::execution_costs::{NewExecutionCostModel, ExecutionVenue};
+use crate::strategy::compliance;
+use super::types::{SwapRequest, QuoteRequest, QuoteResponse, SwapFill};
 use super::paraswap_api_client::ParaSwapClient;
+use super::zerox_api_client::ZeroXClient;
+use super::oneinch_api_client::OneInchClient;
+use super::quoter::SwapQuoter;
+use super::screening::AddressScreener;
 
 // Add ERC20 ABI for approve function
 abigen!(
@@ -35,50 +42,77 @@ abigen!(
 );
 
 const MAX_FEE_PER_GAS_BUFFER: f64 = 1.05; // 5% above the current gas price
+const MAX_QUOTE_BLOCK_LAG: u64 = 240; // Quotes priced more than ~1 minute of Arbitrum blocks ago are stale
 
 pub struct SwapManager {
     paraswap_client: ParaSwapClient,
+    zerox_client: ZeroXClient,
+    oneinch_client: Option<OneInchClient>,
+    screener: AddressScreener,
     wallet_manager: Arc<WalletManager>,
+    db_manager: Arc<DbManager>,
+    dynamic_config: Arc<DynamicConfig>,
     chain_id: u64,
+    wnt_address: Address,
     max_fee_per_gas_buffer: Decimal,
 }
 
 impl SwapManager {
-    pub fn new(config: &Config, wallet_manager: Arc<WalletManager>) -> Self {
-        let paraswap_client = ParaSwapClient::new(wallet_manager.address.clone(), config);
+    pub fn new(config: &Config, wallet_manager: Arc<WalletManager>, db_manager: Arc<DbManager>, dynamic_config: Arc<DynamicConfig>) -> Self {
+        let paraswap_client = ParaSwapClient::new(wallet_manager.address, config);
+        let zerox_client = ZeroXClient::new(wallet_manager.address, config);
+        let oneinch_client = config.oneinch_api_key.as_ref()
+            .map(|api_key| OneInchClient::new(wallet_manager.address, api_key, config));
         let chain_id = config.chain_id;
         let max_fee_per_gas_buffer = Decimal::from_f64(MAX_FEE_PER_GAS_BUFFER).unwrap();
         Self {
             paraswap_client,
+            zerox_client,
+            oneinch_client,
+            screener: AddressScreener::new(config),
             wallet_manager,
+            db_manager,
+            dynamic_config,
             chain_id,
+            wnt_address: config.wnt_address,
             max_fee_per_gas_buffer,
         }
     }
 
-    /// Executes a swap request using the ParaSwap API and smart contract - assumes wallet manager tokens have been loaded
+    /// Executes a swap request using the best quote across the ParaSwap, 0x and 1inch APIs - assumes wallet manager tokens have been loaded
+    pub async fn execute_swap(&self, swap_request: &SwapRequest) -> Result<SwapFill> {
+        self.execute_swap_within_limit(swap_request, None).await?
+            .ok_or_else(|| eyre::eyre!("Swap not executed"))
+    }
+
+    /// Executes a swap request unless the best quote's rate (to token received per from token paid) is below `min_rate`,
+    /// returning None without sending anything in that case
     #[instrument(skip(self, swap_request), fields(on_close = true))]
-    pub async fn execute_swap(&self, swap_request: &SwapRequest) -> Result<()> {
+    pub async fn execute_swap_within_limit(&self, swap_request: &SwapRequest, min_rate: Option<Decimal>) -> Result<Option<SwapFill>> {
+        if self.dynamic_config.safe_mode().await {
+            return Err(eyre::eyre!("Safe mode enabled, swaps are halted"));
+        }
         let (swap_log_string, quote_request) = self.validate_swap_request(swap_request).await?;
+        self.ensure_swap_compliant(&quote_request).await?;
 
         // Check if this is an ETH/WETH swap
-        let weth_address = Address::from_str(WNT_ADDRESS).unwrap();
+        let weth_address = self.wnt_address;
         if let Some(is_wrap) = self.is_eth_weth_swap(
             quote_request.from_token,
             quote_request.to_token,
             weth_address,
         ) {
-            return self.execute_eth_weth_swap(swap_request, is_wrap, &swap_log_string).await;
+            return self.execute_eth_weth_swap(swap_request, is_wrap, &swap_log_string).await.map(Some);
         }
 
         // Get initial balances
         let initial_native_balance = self.wallet_manager.get_native_balance().await?;
-        let initial_from_balance = if quote_request.from_token == self.wallet_manager.native_token.address {
+        let initial_from_balance = if quote_request.from_token == self.wallet_manager.native_token().address {
             initial_native_balance
         } else {
             self.wallet_manager.get_token_balance(quote_request.from_token).await?
         };
-        let initial_to_balance = if quote_request.to_token == self.wallet_manager.native_token.address {
+        let initial_to_balance = if quote_request.to_token == self.wallet_manager.native_token().address {
             initial_native_balance
         } else {
             self.wallet_manager.get_token_balance(quote_request.to_token).await?
@@ -92,20 +126,36 @@ impl SwapManager {
             swap_log_string
         );
 
-        // Fetch the swap quote from ParaSwap
-        let mut quote = self.paraswap_client.get_quote(&quote_request).await?;
-        debug!(quote = ?quote, "{} Quote Received", swap_log_string);
+        // Fetch the best swap quote across aggregators
+        let mut quote = self.get_best_quote(&quote_request).await?;
+        debug!(quote = ?quote, source = quote.source, "{} Quote Received", swap_log_string);
+
+        // Skip the swap if the price has moved past the caller's limit
+        if let Some(min_rate) = min_rate {
+            let rate = if quote.from_amount > Decimal::ZERO { quote.to_amount / quote.from_amount } else { Decimal::ZERO };
+            if rate < min_rate {
+                warn!(rate = %rate, min_rate = %min_rate, source = quote.source, "{} Quote Beyond Price Limit, Swap Skipped", swap_log_string);
+                return Ok(None);
+            }
+        }
 
         // Validate the transaction
         self.validate_transaction(&quote, initial_from_balance).await?;
         debug!("{} Transaction Validated", swap_log_string);
 
         // Handle token approval if needed (for ERC20 tokens)
-        if quote.from_token != self.wallet_manager.native_token.address {
+        if quote.from_token != self.wallet_manager.native_token().address {
             self.ensure_token_approval(&quote, quote_request.from_token_decimals).await?;
         }
         debug!("{} Token Approval Ensured", swap_log_string);
 
+        // Permit2 quotes move the tokens with a signed transfer, its signature travels with the swap calldata
+        if let Some(permit2) = &quote.permit2 {
+            let signature = permit::sign_permit2(&self.wallet_manager, permit2).await?;
+            quote.transaction_data = permit::append_permit2_signature(&quote.transaction_data, &signature);
+            debug!("{} Permit2 Transfer Signed", swap_log_string);
+        }
+
         // Build the transaction
         let tx = self.build_transaction(&mut quote).await?;
         debug!(transaction = ?tx, "{} Transaction Built", swap_log_string);
@@ -124,19 +174,20 @@ impl SwapManager {
             gas_used = ?gas_used,
             gas_price = ?gas_price,
             gas_cost = ?gas_used * gas_price,
-            gas_cost_usd = ?gas_used * gas_price * self.wallet_manager.native_token.last_mid_price_usd,
+            gas_cost_usd = ?gas_used * gas_price * self.wallet_manager.native_token().last_mid_price_usd,
             "{} Swap Executed Successfully",
             swap_log_string,
         );
+        self.record_execution_cost(ExecutionVenue::ParaSwap, "SpotSwap", tx_hash, gas_used * gas_price, swap_request.rebalance_id).await;
 
         // Get final balances
         let final_native_balance = self.wallet_manager.get_native_balance().await?;
-        let final_from_balance = if quote.from_token == self.wallet_manager.native_token.address {
+        let final_from_balance = if quote.from_token == self.wallet_manager.native_token().address {
             final_native_balance
         } else {
             self.wallet_manager.get_token_balance(quote.from_token).await?
         };
-        let final_to_balance = if quote.to_token == self.wallet_manager.native_token.address {
+        let final_to_balance = if quote.to_token == self.wallet_manager.native_token().address {
             final_native_balance
         } else {
             self.wallet_manager.get_token_balance(quote.to_token).await?
@@ -145,24 +196,59 @@ impl SwapManager {
         let from_token_delta = final_from_balance - initial_from_balance;
         let to_token_delta = final_to_balance - initial_to_balance;
         let native_token_delta = final_native_balance - initial_native_balance;
-        let from_token_info = self.wallet_manager.all_tokens.get(&quote.from_token).unwrap();
-        let to_token_info = self.wallet_manager.all_tokens.get(&quote.to_token).unwrap();
+        let from_token_info = self.wallet_manager.token(&quote.from_token).unwrap();
+        let to_token_info = self.wallet_manager.token(&quote.to_token).unwrap();
 
         info!(
             final_from_balance = %final_from_balance,
             final_to_balance = %final_to_balance,
             final_native_balance = %final_native_balance,
-            "{} Swap Completed \n {}{} {} ({:.2} USD) | {}{} {} ({:.2} USD) | {}{} NATIVE ({:.4} USD)",
-            swap_log_string,
-            if from_token_delta.is_sign_positive() { "+" } else { "" }, from_token_delta,
-            from_token_info.symbol, from_token_delta * from_token_info.last_mid_price_usd,
-            if to_token_delta.is_sign_positive() { "+" } else { "" }, to_token_delta,
-            to_token_info.symbol, to_token_delta * to_token_info.last_mid_price_usd,
-            if native_token_delta.is_sign_positive() { "+" } else { "" }, native_token_delta,
-            native_token_delta * self.wallet_manager.native_token.last_mid_price_usd
+            balance_changes = %format!(
+                "{}{} {} ({:.2} USD) | {}{} {} ({:.2} USD) | {}{} NATIVE ({:.4} USD)",
+                if from_token_delta.is_sign_positive() { "+" } else { "" }, from_token_delta,
+                from_token_info.symbol, from_token_delta * from_token_info.last_mid_price_usd,
+                if to_token_delta.is_sign_positive() { "+" } else { "" }, to_token_delta,
+                to_token_info.symbol, to_token_delta * to_token_info.last_mid_price_usd,
+                if native_token_delta.is_sign_positive() { "+" } else { "" }, native_token_delta,
+                native_token_delta * self.wallet_manager.native_token().last_mid_price_usd
+            ),
+            "{} Swap Completed", swap_log_string
         );
 
-        Ok(())
+        // Value given up versus mid prices, with the gas paid in native ETH taken back out of a native leg's delta
+        let gas_cost = gas_used * gas_price;
+        let native_address = self.wallet_manager.native_token().address;
+        let from_amount = if quote.from_token == native_address { -from_token_delta - gas_cost } else { -from_token_delta };
+        let to_amount = if quote.to_token == native_address { to_token_delta + gas_cost } else { to_token_delta };
+        let slippage_usd = from_amount * from_token_info.last_mid_price_usd - to_amount * to_token_info.last_mid_price_usd;
+        if let Err(e) = self.db_manager.update_execution_slippage(&format!("{:?}", tx_hash), slippage_usd).await {
+            error!(error = ?e, tx_hash = ?tx_hash, "Failed to record swap slippage");
+        }
+
+        // Reconcile the quoted amount (net of partner fee) with the amount actually received
+        let received_vs_quoted_bps = if quote.to_amount > Decimal::ZERO {
+            (to_token_delta / quote.to_amount - Decimal::ONE) * Decimal::from(10000)
+        } else {
+            Decimal::ZERO
+        };
+        info!(
+            quoted_to_amount = %quote.to_amount,
+            received_to_amount = %to_token_delta,
+            partner_fee_bps = quote.partner_fee_bps,
+            partner_fee_amount = %quote.partner_fee_amount,
+            received_vs_quoted_bps = %received_vs_quoted_bps.round_dp(2),
+            slippage_usd = %slippage_usd.round_dp(4),
+            "{} Swap Reconciled",
+            swap_log_string
+        );
+
+        Ok(Some(SwapFill {
+            tx_hash,
+            from_amount: -from_token_delta,
+            to_amount: to_token_delta,
+            gas_cost_usd: gas_used * gas_price * self.wallet_manager.native_token().last_mid_price_usd,
+            screened_contracts: quote.counterparty_contracts(),
+        }))
     }
 
     /// Validate swap request, create log string and quote request
@@ -171,17 +257,17 @@ impl SwapManager {
         if swap_request.side != "BUY" && swap_request.side != "SELL" {
             return Err(eyre::eyre!("Invalid swap side: {}", swap_request.side));
         }
-        let from_token = if swap_request.from_token_address == self.wallet_manager.native_token.address {
-            &self.wallet_manager.native_token
+        let from_token = if swap_request.from_token_address == self.wallet_manager.native_token().address {
+            self.wallet_manager.native_token()
         } else {
-            self.wallet_manager.all_tokens.get(&swap_request.from_token_address).ok_or_else(|| {
+            self.wallet_manager.token(&swap_request.from_token_address).ok_or_else(|| {
                 eyre::eyre!("From token not found in wallet manager: {:?}", swap_request.from_token_address)
             })?
         };
-        let to_token = if swap_request.to_token_address == self.wallet_manager.native_token.address {
-            &self.wallet_manager.native_token
+        let to_token = if swap_request.to_token_address == self.wallet_manager.native_token().address {
+            self.wallet_manager.native_token()
         } else {
-            self.wallet_manager.all_tokens.get(&swap_request.to_token_address).ok_or_else(|| {
+            self.wallet_manager.token(&swap_request.to_token_address).ok_or_else(|| {
                 eyre::eyre!("To token not found in wallet manager: {:?}", swap_request.to_token_address)
             })?
         };
@@ -199,12 +285,108 @@ impl SwapManager {
             to_token_decimals: to_token.decimals,
             amount: swap_request.amount,
             side: swap_request.side.clone(),
-            slippage_tolerance: Decimal::from_f64(0.5).unwrap(), // Default 0.5% slippage
+            slippage_tolerance: self.dynamic_config.params().await.swap_slippage_tolerance_pct,
+            from_token_price_usd: from_token.last_mid_price_usd,
+            to_token_price_usd: to_token.last_mid_price_usd,
+            native_token_price_usd: self.wallet_manager.native_token().last_mid_price_usd,
         };
 
         Ok((swap_log_string, request))
     }
 
+    /// Check the swap against the account's compliance rules, valued at the wallet's mid prices of the side's amount
+    async fn ensure_swap_compliant(&self, request: &QuoteRequest) -> Result<()> {
+        let native_token = self.wallet_manager.native_token();
+        let symbol = |address: Address| if address == native_token.address {
+            Some(native_token.symbol.clone())
+        } else {
+            self.wallet_manager.token(&address).map(|info| info.symbol)
+        };
+        let (Some(from_symbol), Some(to_symbol)) = (symbol(request.from_token), symbol(request.to_token)) else {
+            return Err(eyre::eyre!("Swap tokens not found in wallet manager, cannot check the swap against compliance rules"));
+        };
+        let notional_usd = if request.side == "BUY" {
+            request.amount * request.to_token_price_usd
+        } else {
+            request.amount * request.from_token_price_usd
+        };
+
+        if let Some(violation) = compliance::check_spot_swap(&self.db_manager, &from_symbol, &to_symbol, notional_usd).await? {
+            warn!(rule_id = %violation.rule_id, "{}", violation);
+            return Err(violation.into());
+        }
+        Ok(())
+    }
+
+    /// Query all aggregators concurrently and pick the quote with the best value net of gas.
+    /// Aggregators that error or return a stale quote are skipped, so any single API can fail.
+    #[instrument(skip(self, request))]
+    async fn get_best_quote(&self, request: &QuoteRequest) -> Result<QuoteResponse> {
+        let oneinch_quote = async {
+            match &self.oneinch_client {
+                Some(client) => Some(client.get_quote(request).await),
+                None => None,
+            }
+        };
+        let (paraswap_quote, zerox_quote, oneinch_quote) = tokio::join!(
+            self.paraswap_client.get_quote(request),
+            self.zerox_client.get_quote(request),
+            oneinch_quote,
+        );
+        let current_block = self.wallet_manager.signer.provider().get_block_number().await?.as_u64();
+
+        let mut candidates = Vec::new();
+        for (source, result) in [
+            (self.paraswap_client.name(), Some(paraswap_quote)),
+            (self.zerox_client.name(), Some(zerox_quote)),
+            ("1inch", oneinch_quote),
+        ] {
+            match result {
+                Some(Ok(quote)) => {
+                    let block_lag = quote.block_number.map(|b| current_block.saturating_sub(b)).unwrap_or(0);
+                    if block_lag > MAX_QUOTE_BLOCK_LAG {
+                        warn!(source = source, block_lag = block_lag, "Skipping stale swap quote");
+                        continue;
+                    }
+                    // Value received minus value paid minus gas, all in USD at the wallet's last mid prices
+                    let net_value_usd = quote.to_amount * request.to_token_price_usd
+                        - quote.from_amount * request.from_token_price_usd
+                        - quote.gas_cost_usd;
+                    debug!(
+                        source = source,
+                        from_amount = %quote.from_amount,
+                        to_amount = %quote.to_amount,
+                        gas_cost_usd = %quote.gas_cost_usd,
+                        net_value_usd = %net_value_usd,
+                        "Swap quote received"
+                    );
+                    candidates.push((net_value_usd, quote));
+                }
+                Some(Err(e)) => warn!(source = source, error = ?e, "Swap quote failed, falling back to other aggregators"),
+                None => {}
+            }
+        }
+
+        // Best quote first, routes through sanctioned contracts are refused in favour of the next best
+        candidates.sort_by(|a, b| b.0.cmp(&a.0));
+        for (net_value_usd, quote) in candidates {
+            let contracts = quote.counterparty_contracts();
+            let flagged = self.screener.flagged(&contracts).await?;
+            if !flagged.is_empty() {
+                warn!(source = quote.source, flagged = ?flagged, "Swap quote routes through sanctioned addresses, refused");
+                continue;
+            }
+            info!(
+                source = quote.source,
+                net_value_usd = %net_value_usd.round_dp(4),
+                screened_contracts = contracts.len(),
+                "Selected best swap quote"
+            );
+            return Ok(quote);
+        }
+        Err(eyre::eyre!("No aggregator returned a usable swap quote"))
+    }
+
     /// Validate that we have sufficient balance for the swap
     #[instrument(skip(self, quote))]
     async fn validate_transaction(&self, quote: &QuoteResponse, from_token_balance: Decimal) -> Result<()> {
@@ -237,13 +419,13 @@ impl SwapManager {
     /// Ensure the ParaSwap contract has sufficient allowance to spend our tokens
     #[instrument(skip(self, quote, from_token_decimals))]
     async fn ensure_token_approval(&self, quote: &QuoteResponse, from_token_decimals: u8) -> Result<()> {
-        debug!("Checking token approval for ParaSwap contract");
+        debug!(spender = ?quote.allowance_target, source = quote.source, "Checking token approval for aggregator contract");
         
         let token_contract = IERC20Approve::new(quote.from_token, self.wallet_manager.signer.clone());
         
         // Check current allowance
         let current_allowance = token_contract
-            .allowance(self.wallet_manager.address, quote.to_contract)
+            .allowance(self.wallet_manager.address, quote.allowance_target)
             .call()
             .await?;
 
@@ -258,7 +440,7 @@ impl SwapManager {
             
             // Approve maximum amount to avoid repeated approvals
             let max_approval = U256::MAX;
-            let approve_tx = token_contract.approve(quote.to_contract, max_approval);
+            let approve_tx = token_contract.approve(quote.allowance_target, max_approval);
             
             let pending_tx = approve_tx.send().await?;
             let receipt = pending_tx.await?;
@@ -300,7 +482,7 @@ impl SwapManager {
             .from(self.wallet_manager.address)
             .data(quote.transaction_data.clone())
             .value(
-                if quote.from_token == self.wallet_manager.native_token.address {
+                if quote.from_token == self.wallet_manager.native_token().address {
                     quote.value
                 } else {
                     U256::zero()
@@ -313,7 +495,7 @@ impl SwapManager {
             to_token = ?quote.to_token,
             from_amount = %quote.from_amount,
             to_amount = %quote.to_amount,
-            native_token = ?self.wallet_manager.native_token.address,
+            native_token = ?self.wallet_manager.native_token().address,
             tx_value = %tx.value.unwrap_or(U256::zero()),
             tx = ?tx,
             "Transaction value calculation"
@@ -413,6 +595,22 @@ impl SwapManager {
         Ok((tx_hash, receipt))
     }
 
+    /// Record the gas paid for a swap against the fee budget.
+    /// Failures are logged rather than returned since the swap has already been executed.
+    async fn record_execution_cost(&self, venue: ExecutionVenue, action_type: &str, tx_hash: TxHash, gas_cost: Decimal, rebalance_id: Option<i32>) {
+        let execution_cost = NewExecutionCostModel {
+            venue: venue.as_str().to_string(),
+            action_type: action_type.to_string(),
+            tx_hash: Some(format!("{:?}", tx_hash)),
+            gas_cost_usd: gas_cost * self.wallet_manager.native_token().last_mid_price_usd,
+            execution_fee_usd: Decimal::ZERO,
+            slippage_usd: Decimal::ZERO,
+        };
+        if let Err(e) = self.db_manager.insert_execution_cost(&execution_cost, rebalance_id).await {
+            error!(error = ?e, tx_hash = ?tx_hash, "Failed to record execution cost");
+        }
+    }
+
     /// Helper to convert Decimal to U256
     fn decimal_to_u256(&self, value: Decimal, decimals: u8) -> Result<U256> {
         let value_str = value.to_string();
@@ -439,8 +637,8 @@ impl SwapManager {
         swap_request: &SwapRequest,
         is_wrap: bool,
         swap_log_string: &str,
-    ) -> Result<()> {
-        let weth_address = Address::from_str(WNT_ADDRESS).unwrap();
+    ) -> Result<SwapFill> {
+        let weth_address = self.wnt_address;
 
         // Get initial balances
         let initial_native_balance = self.wallet_manager.get_native_balance().await?;
@@ -491,10 +689,12 @@ impl SwapManager {
             gas_used = ?gas_used,
             gas_price = ?gas_price,
             gas_cost = ?gas_used * gas_price,
-            gas_cost_usd = ?gas_used * gas_price * self.wallet_manager.native_token.last_mid_price_usd,
+            gas_cost_usd = ?gas_used * gas_price * self.wallet_manager.native_token().last_mid_price_usd,
             "{} ETH/WETH Operation Executed Successfully",
             swap_log_string,
         );
+        let action_type = if is_wrap { "Wrap" } else { "Unwrap" };
+        self.record_execution_cost(ExecutionVenue::Weth, action_type, tx_hash, gas_used * gas_price, swap_request.rebalance_id).await;
 
         // Get final balances
         let final_native_balance = self.wallet_manager.get_native_balance().await?;
@@ -505,15 +705,24 @@ impl SwapManager {
         info!(
             final_eth_balance = %final_native_balance,
             final_weth_balance = %final_weth_balance,
-            "{} ETH/WETH Operation Completed \n {}{} ETH ({:.4} USD) | {}{} WETH ({:.2} USD)",
-            swap_log_string,
-            if is_wrap { "" } else { "+" }, native_delta,
-            native_delta * self.wallet_manager.native_token.last_mid_price_usd,
-            if is_wrap { "+" } else { "" }, weth_delta,
-            weth_delta * self.wallet_manager.all_tokens.get(&weth_address).unwrap().last_mid_price_usd
+            balance_changes = %format!(
+                "{}{} ETH ({:.4} USD) | {}{} WETH ({:.2} USD)",
+                if is_wrap { "" } else { "+" }, native_delta,
+                native_delta * self.wallet_manager.native_token().last_mid_price_usd,
+                if is_wrap { "+" } else { "" }, weth_delta,
+                weth_delta * self.wallet_manager.token(&weth_address).unwrap().last_mid_price_usd
+            ),
+            "{} ETH/WETH Operation Completed", swap_log_string
         );
 
-        Ok(())
+        let (from_amount, to_amount) = if is_wrap { (-native_delta, weth_delta) } else { (-weth_delta, native_delta) };
+        Ok(SwapFill {
+            tx_hash,
+            from_amount,
+            to_amount,
+            gas_cost_usd: gas_used * gas_price * self.wallet_manager.native_token().last_mid_price_usd,
+            screened_contracts: Vec::new(), // Wrapping only calls the WETH contract
+        })
     }
 
     /// Check if a swap is between ETH and WETH
@@ -523,9 +732,9 @@ impl SwapManager {
         to_token: Address,
         weth_address: Address,
     ) -> Option<bool> {
-        if from_token == self.wallet_manager.native_token.address && to_token == weth_address {
+        if from_token == self.wallet_manager.native_token().address && to_token == weth_address {
             Some(true) // wrap ETH to WETH
-        } else if from_token == weth_address && to_token == self.wallet_manager.native_token.address {
+        } else if from_token == weth_address && to_token == self.wallet_manager.native_token().address {
             Some(false) // unwrap WETH to ETH
         } else {
             None // not an ETH/WETH swap
@@ -0,0 +1,129 @@
+use eyre::Result;
+use ethers::types::Address;
+use rust_decimal::Decimal;
+use std::collections::BTreeSet;
+use std::time::Duration;
+use tracing::{info, warn, error, instrument};
+
+use crate::config::Config;
+use crate::constants::NATIVE_ADDRESS;
+use crate::db::db_manager::DbManager;
+use crate::db::models::spot_swaps::{NewSpotSwapModel, SpotSwapStatus};
+use crate::wallet::WalletManager;
+use super::swap_manager::SwapManager;
+use super::types::{SwapRequest, SwapFill};
+
+/// Execute a swap as a TWAP when its notional is at least the TWAP minimum, otherwise in one go.
+/// The swap is split into equal slices spread evenly over the TWAP duration, each re-quoted right before it is sent.
+/// The first slice's fill rate is the reference price: a later slice quoted more than the max price move below it
+/// aborts the remaining slices. All fills are recorded as one spot swap, also when a slice fails.
+#[instrument(skip(config, wallet_manager, swap_manager, db), fields(on_close = true))]
+pub async fn execute_twap_swap(
+    config: &Config,
+    wallet_manager: &WalletManager,
+    swap_manager: &SwapManager,
+    db: &DbManager,
+    swap_request: &SwapRequest,
+) -> Result<SpotSwapStatus> {
+    let slices = slice_count(config, wallet_manager, swap_request);
+    let slice_amount = swap_request.amount / Decimal::from(slices);
+    let interval = Duration::from_secs(config.twap_duration_mins * 60 / slices as u64);
+    if slices > 1 {
+        info!(
+            slices = slices,
+            slice_amount = %slice_amount,
+            interval_secs = interval.as_secs(),
+            max_price_move_pct = %config.twap_max_price_move_pct,
+            "Executing swap as TWAP"
+        );
+    }
+
+    let mut fills: Vec<SwapFill> = Vec::new();
+    let mut min_rate = None;
+    let mut status = SpotSwapStatus::Completed;
+    let mut failure = None;
+    for slice in 0..slices {
+        if slice > 0 {
+            tokio::time::sleep(interval).await;
+        }
+        // The last slice takes the rounding remainder
+        let amount = if slice == slices - 1 { swap_request.amount - slice_amount * Decimal::from(slices - 1) } else { slice_amount };
+        let slice_request = SwapRequest { amount, ..swap_request.clone() };
+
+        match swap_manager.execute_swap_within_limit(&slice_request, min_rate).await {
+            Ok(Some(fill)) => {
+                if min_rate.is_none() && fill.from_amount > Decimal::ZERO {
+                    let reference_rate = fill.to_amount / fill.from_amount;
+                    min_rate = Some(reference_rate * (Decimal::ONE - config.twap_max_price_move_pct / Decimal::ONE_HUNDRED));
+                }
+                fills.push(fill);
+            }
+            Ok(None) => {
+                warn!(slice = slice + 1, slices = slices, "Price moved past the TWAP limit, aborting remaining slices");
+                status = SpotSwapStatus::Aborted;
+                break;
+            }
+            Err(e) => {
+                error!(error = ?e, slice = slice + 1, slices = slices, "TWAP slice failed, aborting remaining slices");
+                status = SpotSwapStatus::Failed;
+                failure = Some(e);
+                break;
+            }
+        }
+    }
+
+    let record = NewSpotSwapModel {
+        from_token_address: format!("{:?}", swap_request.from_token_address),
+        to_token_address: format!("{:?}", swap_request.to_token_address),
+        side: swap_request.side.clone(),
+        requested_amount: swap_request.amount,
+        from_amount: fills.iter().map(|fill| fill.from_amount).sum(),
+        to_amount: fills.iter().map(|fill| fill.to_amount).sum(),
+        slices_planned: slices as i32,
+        slices_filled: fills.len() as i32,
+        status: status.as_str().to_string(),
+        gas_cost_usd: fills.iter().map(|fill| fill.gas_cost_usd).sum(),
+        tx_hashes: fills.iter().map(|fill| format!("{:?}", fill.tx_hash)).collect(),
+        screened_contracts: fills.iter()
+            .flat_map(|fill| &fill.screened_contracts)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|address| format!("{:?}", address))
+            .collect(),
+    };
+    info!(
+        status = %record.status,
+        slices_filled = record.slices_filled,
+        from_amount = %record.from_amount,
+        to_amount = %record.to_amount,
+        gas_cost_usd = %record.gas_cost_usd,
+        "Spot swap finished"
+    );
+    if let Err(e) = db.insert_spot_swap(&record, swap_request.rebalance_id).await {
+        error!(error = ?e, "Failed to record spot swap");
+    }
+
+    match failure {
+        Some(e) => Err(e),
+        None => Ok(status),
+    }
+}
+
+/// Number of slices to split a swap into, one unless its notional reaches the TWAP minimum
+fn slice_count(config: &Config, wallet_manager: &WalletManager, swap_request: &SwapRequest) -> u32 {
+    // The amount is denominated in the token bought when buying and the token sold when selling
+    let amount_token = if swap_request.side == "BUY" { swap_request.to_token_address } else { swap_request.from_token_address };
+    let price = if amount_token == NATIVE_ADDRESS.parse::<Address>().expect("Invalid native address") {
+        Some(wallet_manager.native_token().last_mid_price_usd)
+    } else {
+        wallet_manager.token(&amount_token).map(|token| token.last_mid_price_usd)
+    };
+    match price {
+        Some(price) if swap_request.amount * price >= config.twap_min_notional_usd => config.twap_slices,
+        Some(_) => 1,
+        None => {
+            warn!(token = ?amount_token, "No price for swap amount token, executing without TWAP");
+            1
+        }
+    }
+}
@@ -1,4 +1,5 @@
-use ethers::types::{Address, Bytes, U256};
+use ethers::types::{Address, Bytes, TxHash, U256};
+use ethers::types::transaction::eip712::TypedData;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
@@ -8,6 +9,7 @@ pub struct SwapRequest {
     pub to_token_address: Address,
     pub amount: Decimal, // Amount to swap (in to_token if side is "BUY", in from_token if side is "SELL")
     pub side: String, // "BUY" or "SELL"
+    pub rebalance_id: Option<i32>, // Rebalance the swap is a leg of, None for a standalone swap
 }
 
 #[derive(Debug, Clone)]
@@ -19,19 +21,51 @@ pub struct QuoteRequest {
     pub amount: Decimal, // Amount to swap (in to_token if side is "BUY", in from_token if side is "SELL")
     pub side: String, // "BUY" or "SELL"
     pub slippage_tolerance: Decimal, // in percentage (e.g., 1 for 1%)
+    pub from_token_price_usd: Decimal, // Used to value quotes from APIs that don't return USD amounts
+    pub to_token_price_usd: Decimal,
+    pub native_token_price_usd: Decimal, // Used to value gas costs
 }
 
 #[derive(Debug, Clone)]
 pub struct QuoteResponse {
+    pub source: &'static str, // Aggregator the quote came from
     pub from_token: Address,
     pub to_token: Address,
     pub from_amount: Decimal,
-    pub to_amount: Decimal, // Amount received after swap
+    pub to_amount: Decimal, // Amount received after swap (net of partner fee)
     pub from_amount_usd: Decimal, // USD value of the from amount
     pub to_amount_usd: Decimal, // USD value of the to amount
     pub to_contract: Address, // The address of the contract to send the transaction data for execution
+    pub allowance_target: Address, // The address that must be approved to spend the from token
     pub transaction_data: Bytes, // The transaction data to execute the swap
     pub value: U256,
+    pub partner_fee_bps: u32, // Partner fee charged on this quote (0 if disabled)
+    pub partner_fee_amount: Decimal, // Partner fee, in to_token for SELL quotes and from_token for BUY quotes
+    pub gas_cost_usd: Decimal, // Estimated gas cost of executing the swap
+    pub block_number: Option<u64>, // Block the quote was priced at, if reported
+    pub permit2: Option<TypedData>, // Permit2 transfer to sign, its signature is appended to the transaction data
+    pub route_contracts: Vec<Address>, // Pools and exchange contracts the route passes through, when the aggregator reports them
+}
+
+impl QuoteResponse {
+    /// Every contract executing the swap would interact with: the swap contract, the spender and the route
+    pub fn counterparty_contracts(&self) -> Vec<Address> {
+        let mut contracts = vec![self.to_contract, self.allowance_target];
+        contracts.extend(self.route_contracts.iter().copied());
+        contracts.sort();
+        contracts.dedup();
+        contracts
+    }
+}
+
+/// Outcome of an executed swap, amounts from the wallet's balance changes
+#[derive(Debug, Clone)]
+pub struct SwapFill {
+    pub tx_hash: TxHash,
+    pub from_amount: Decimal, // Amount of the from token spent
+    pub to_amount: Decimal, // Amount of the to token received
+    pub gas_cost_usd: Decimal,
+    pub screened_contracts: Vec<Address>, // Counterparty contracts screened clean before the swap was sent
 }
 
 // ParaSwap API Response structures
@@ -131,3 +165,70 @@ pub struct SwapExchange {
     pub pool_addresses: Vec<String>,
     pub data: serde_json::Value, 
 }
+
+// 0x API Response structures
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZeroXQuoteResponse {
+    #[serde(rename = "blockNumber")]
+    pub block_number: String,
+    #[serde(rename = "buyAmount")]
+    pub buy_amount: String,
+    #[serde(rename = "sellAmount")]
+    pub sell_amount: String,
+    #[serde(rename = "buyToken")]
+    pub buy_token: String,
+    #[serde(rename = "sellToken")]
+    pub sell_token: String,
+    #[serde(rename = "liquidityAvailable")]
+    pub liquidity_available: bool,
+    pub issues: ZeroXIssues,
+    pub transaction: ZeroXTransaction,
+    pub permit2: Option<ZeroXPermit2>, // Only on Permit2 quotes
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZeroXPermit2 {
+    pub eip712: TypedData,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZeroXIssues {
+    pub allowance: Option<ZeroXAllowanceIssue>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZeroXAllowanceIssue {
+    pub actual: String,
+    pub spender: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZeroXTransaction {
+    pub to: String,
+    pub data: String,
+    pub gas: Option<String>,
+    #[serde(rename = "gasPrice")]
+    pub gas_price: String,
+    pub value: String,
+}
+
+// 1inch API Response structures
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OneInchSwapResponse {
+    #[serde(rename = "dstAmount")]
+    pub dst_amount: String,
+    pub tx: OneInchTransaction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OneInchTransaction {
+    pub from: String,
+    pub to: String,
+    pub data: String,
+    pub value: String,
+    pub gas: u64,
+    #[serde(rename = "gasPrice")]
+    pub gas_price: String,
+}
@@ -0,0 +1,109 @@
+use reqwest_middleware::ClientWithMiddleware;
+use std::str::FromStr;
+use tracing::{debug, instrument};
+use url::Url;
+use ethers::types::{Address, Bytes, U256};
+use rust_decimal::Decimal;
+use eyre::Result;
+
+use super::types::{QuoteRequest, QuoteResponse, ZeroXQuoteResponse};
+use super::quoter::{self, SwapQuoter};
+use crate::config::Config;
+use crate::constants::PERMIT2_ADDRESS;
+
+const ZEROX_BASE_URL: &str = "https://api.0x.org";
+const ZEROX_API_KEY_HEADER: &str = "0x-api-key";
+const ZEROX_VERSION_HEADER: &str = "0x-version";
+const ZEROX_REQUESTS_PER_SEC: u32 = 5;
+
+#[derive(Debug, Clone)]
+pub struct ZeroXClient {
+    http_client: ClientWithMiddleware,
+    base_url: String,
+    chain_id: u64,
+    taker_address: Address,
+    use_permit2: bool, // Quote Permit2 swaps (one standing approval to Permit2, signed transfers) instead of AllowanceHolder
+}
+
+impl ZeroXClient {
+    pub fn new(taker_address: Address, config: &Config) -> Self {
+        let headers = [
+            (ZEROX_API_KEY_HEADER, config.zerox_api_key.as_str()),
+            (ZEROX_VERSION_HEADER, "v2"),
+        ];
+        let http_client = quoter::build_http_client(&headers, ZEROX_REQUESTS_PER_SEC);
+
+        Self {
+            http_client,
+            base_url: ZEROX_BASE_URL.to_string(),
+            chain_id: config.chain_id,
+            taker_address,
+            use_permit2: config.permit_approvals,
+        }
+    }
+}
+
+impl SwapQuoter for ZeroXClient {
+    fn name(&self) -> &'static str {
+        "0x"
+    }
+
+    /// Get an AllowanceHolder or Permit2 quote (sell side only, 0x v2 has no exact output quotes)
+    #[instrument(skip(self))]
+    async fn get_quote(&self, request: &QuoteRequest) -> Result<QuoteResponse> {
+        if request.side == "BUY" {
+            return Err(eyre::eyre!("0x does not support BUY (exact output) quotes"));
+        }
+        let endpoint = if self.use_permit2 { "permit2" } else { "allowance-holder" };
+        let url = Url::parse(&format!("{}/swap/{}/quote", self.base_url, endpoint))?;
+        let params = [
+            ("chainId", self.chain_id.to_string()),
+            ("sellToken", format!("{:?}", request.from_token)),
+            ("buyToken", format!("{:?}", request.to_token)),
+            ("sellAmount", quoter::decimal_to_u256_str(request.amount, request.from_token_decimals)),
+            ("taker", format!("{:?}", self.taker_address)),
+            ("slippageBps", (request.slippage_tolerance * Decimal::from(100)).round().to_string()),
+        ];
+
+        let response = self.http_client.get(url).query(&params).send().await?.error_for_status()?;
+
+        let quote_response: ZeroXQuoteResponse = response.json().await?;
+        debug!(?quote_response, "Received quote response from 0x");
+        if !quote_response.liquidity_available {
+            return Err(eyre::eyre!("0x has no liquidity available for this swap"));
+        }
+
+        let from_amount = quoter::u256_str_to_decimal(&quote_response.sell_amount, request.from_token_decimals);
+        let to_amount = quoter::u256_str_to_decimal(&quote_response.buy_amount, request.to_token_decimals);
+        let transaction = quote_response.transaction;
+        let gas_limit = U256::from_dec_str(transaction.gas.as_deref().unwrap_or("0"))?;
+        let gas_price = U256::from_dec_str(&transaction.gas_price)?;
+        let gas_cost = quoter::u256_str_to_decimal(&(gas_limit * gas_price).to_string(), 18);
+        let to_contract = Address::from_str(&transaction.to)?;
+        let allowance_target = match (&quote_response.issues.allowance, self.use_permit2) {
+            (Some(allowance), _) => Address::from_str(&allowance.spender)?,
+            (None, true) => Address::from_str(PERMIT2_ADDRESS)?,
+            (None, false) => to_contract,
+        };
+
+        Ok(QuoteResponse {
+            source: self.name(),
+            from_token: request.from_token,
+            to_token: request.to_token,
+            from_amount,
+            to_amount,
+            from_amount_usd: from_amount * request.from_token_price_usd,
+            to_amount_usd: to_amount * request.to_token_price_usd,
+            to_contract,
+            allowance_target,
+            transaction_data: Bytes::from_str(&transaction.data)?,
+            value: U256::from_dec_str(&transaction.value)?,
+            partner_fee_bps: 0,
+            partner_fee_amount: Decimal::ZERO,
+            gas_cost_usd: gas_cost * request.native_token_price_usd,
+            block_number: quote_response.block_number.parse().ok(),
+            permit2: quote_response.permit2.map(|permit2| permit2.eip712),
+            route_contracts: Vec::new(), // 0x reports liquidity sources by name only
+        })
+    }
+}
@@ -6,14 +6,241 @@ use argmin::solver::gradientdescent::SteepestDescent;
 use argmin::solver::linesearch::{BacktrackingLineSearch, condition::ArmijoCondition};
 use eyre::Result;
 
-/// Maximize Sharpe ratio subject to weights summing to 1 and being non-negative
+use super::strategy_constants::{
+    OPTIMIZER_MAX_ITERS,
+    OPTIMIZER_TARGET_COST,
+    OPTIMIZER_ARMIJO_C,
+    OPTIMIZER_CONSTRAINT_PENALTY,
+    OPTIMIZER_EPSILON,
+    WEIGHT_ADJUSTMENT_MAX_ITERS,
+    WEIGHT_DECIMAL_PLACES,
+    TURNOVER_SMOOTHING,
+};
+use super::feasibility::AllocationConstraints;
+
+/// Objective the allocator optimizes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AllocatorMode {
+    MeanVariance, // Expected LP return against variance, and turnover when holdings are known
+    HedgedCarry,  // Market-neutral: only fully hedged markets, net carry per unit of residual basis risk
+}
+
+impl AllocatorMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AllocatorMode::MeanVariance => "mean_variance",
+            AllocatorMode::HedgedCarry => "hedged_carry",
+        }
+    }
+}
+
+// Numeric path:
+//   1. Inputs are validated and converted from Decimal to f64 exactly once (inputs_to_f64).
+//   2. Heuristic initialization, argmin refinement, projection, min weight filter, position
+//      and cluster limits all run in f64. Every loop is bounded (OPTIMIZER_MAX_ITERS, WEIGHT_ADJUSTMENT_MAX_ITERS)
+//      and iterates in index order, so identical inputs always produce identical outputs.
+//   3. Weights are converted back to Decimal exactly once (weights_to_decimal), rounded to
+//      WEIGHT_DECIMAL_PLACES, with the rounding residual assigned to a single deterministic index
+//      so the returned weights sum to exactly 1 (or are all zero).
+
+/// Maximize Sharpe ratio subject to weights summing to 1 and being non-negative,
+/// with the combined weight of each correlation cluster capped (`cluster_ids` gives each asset's cluster).
+/// `constraints` should come from the feasibility check so the position and cluster caps can hold the full portfolio.
 pub fn maximize_sharpe(
     expected_returns: Array1<Decimal>,
     covariance_matrix: Array2<Decimal>,
+    cluster_ids: &[usize],
+    constraints: &AllocationConstraints,
+) -> Result<Array1<Decimal>> {
+    validate_inputs(&expected_returns, &covariance_matrix, cluster_ids)?;
+
+    let (expected_returns_f64, covariance_matrix_f64) = inputs_to_f64(&expected_returns, &covariance_matrix)?;
+
+    // Use a simple analytical solution for the unconstrained case, then project
+    let optimal_weights = solve_unconstrained_mpt(&expected_returns_f64, &covariance_matrix_f64, cluster_ids, constraints)?;
+
+    Ok(weights_to_decimal(&optimal_weights, constraints.max_position_weight))
+}
+
+/// Maximize expected return − λ·variance − κ·|Δweights| relative to the current weights,
+/// subject to weights summing to 1 and being non-negative and to the cluster caps, so the optimizer avoids churn on its own
+pub fn maximize_utility_with_turnover(
+    expected_returns: Array1<Decimal>,
+    covariance_matrix: Array2<Decimal>,
+    current_weights: Array1<Decimal>,
+    cluster_ids: &[usize],
+    constraints: &AllocationConstraints,
+    risk_aversion: f64,
+    turnover_penalty: f64,
 ) -> Result<Array1<Decimal>> {
+    validate_inputs(&expected_returns, &covariance_matrix, cluster_ids)?;
+
+    if current_weights.len() != expected_returns.len() {
+        return Err(eyre::eyre!("Current weights dimensions don't match expected returns"));
+    }
+    if risk_aversion < 0.0 || turnover_penalty < 0.0 {
+        return Err(eyre::eyre!("Risk aversion and turnover penalty must be non-negative"));
+    }
+
+    let (expected_returns_f64, covariance_matrix_f64) = inputs_to_f64(&expected_returns, &covariance_matrix)?;
+    let current_weights_f64 = current_weights.mapv(|w| w.to_f64().unwrap_or(0.0).max(0.0));
+
+    // Start from current holdings when there are any, otherwise from the Sharpe heuristic
+    let initial_weights = if current_weights_f64.sum() > OPTIMIZER_EPSILON {
+        current_weights_f64.mapv(|w| w / current_weights_f64.sum())
+    } else {
+        sharpe_heuristic_weights(&expected_returns_f64, &covariance_matrix_f64)
+    };
+
+    let problem = TurnoverPenalizedProblem {
+        expected_returns: expected_returns_f64,
+        covariance_matrix: covariance_matrix_f64,
+        current_weights: current_weights_f64,
+        risk_aversion,
+        turnover_penalty,
+    };
+
+    let linesearch = BacktrackingLineSearch::new(
+        ArmijoCondition::new(OPTIMIZER_ARMIJO_C).map_err(|e| eyre::eyre!("Failed to create Armijo condition: {}", e))?
+    );
+    let solver = SteepestDescent::new(linesearch);
+
+    // No target cost, the utility is naturally negative so the bounded iteration count is the stopping rule
+    let result = Executor::new(problem, solver)
+        .configure(|state| {
+            state
+                .param(initial_weights.to_vec())
+                .max_iters(OPTIMIZER_MAX_ITERS)
+        })
+        .run()
+        .map_err(|e| eyre::eyre!("Optimization failed: {}", e))?;
+
+    let optimal_weights = match result.state().get_best_param() {
+        Some(best) if best.iter().all(|w| w.is_finite()) => Array1::from_vec(best.clone()),
+        _ => initial_weights,
+    };
+
+    Ok(weights_to_decimal(&project_to_valid_weights(optimal_weights, cluster_ids, constraints), constraints.max_position_weight))
+}
+
+/// Market-neutral allocation: maximize net carry (fees, borrowing and funding) per unit of residual basis risk over
+/// the markets whose collateral is fully hedged, `eligible` marking them. Other markets get zero weight. The covariance
+/// is the pool's residual exposure to trader PnL, which the collateral hedge leaves in place. The position cap is
+/// raised to 1/eligible count when too few markets are eligible to hold the full portfolio under it.
+pub fn maximize_carry_to_risk(
+    carry_returns: Array1<Decimal>,
+    covariance_matrix: Array2<Decimal>,
+    eligible: &[bool],
+    cluster_ids: &[usize],
+    constraints: &AllocationConstraints,
+) -> Result<Array1<Decimal>> {
+    validate_inputs(&carry_returns, &covariance_matrix, cluster_ids)?;
+
+    if eligible.len() != carry_returns.len() {
+        return Err(eyre::eyre!("Eligibility flags don't match expected returns"));
+    }
+
+    let n = carry_returns.len();
+    let indices: Vec<usize> = (0..n).filter(|&i| eligible[i]).collect();
+    if indices.is_empty() {
+        return Ok(Array1::from_elem(n, Decimal::ZERO));
+    }
+
+    let (carry_returns_f64, covariance_matrix_f64) = inputs_to_f64(&carry_returns, &covariance_matrix)?;
+    let sub_returns = Array1::from_iter(indices.iter().map(|&i| carry_returns_f64[i]));
+    let sub_covariance = Array2::from_shape_fn((indices.len(), indices.len()), |(a, b)| covariance_matrix_f64[[indices[a], indices[b]]]);
+
+    // Renumber the eligible markets' clusters so excluded markets don't count toward the cluster count
+    let mut cluster_map: Vec<usize> = Vec::new();
+    let sub_cluster_ids: Vec<usize> = indices.iter()
+        .map(|&i| match cluster_map.iter().position(|&cluster| cluster == cluster_ids[i]) {
+            Some(id) => id,
+            None => {
+                cluster_map.push(cluster_ids[i]);
+                cluster_map.len() - 1
+            }
+        })
+        .collect();
+    let sub_constraints = AllocationConstraints {
+        max_position_weight: constraints.max_position_weight.max(1.0 / indices.len() as f64),
+        ..*constraints
+    };
+
+    let sub_weights = solve_unconstrained_mpt(&sub_returns, &sub_covariance, &sub_cluster_ids, &sub_constraints)?;
+    let mut weights = Array1::zeros(n);
+    for (a, &i) in indices.iter().enumerate() {
+        weights[i] = sub_weights[a];
+    }
+
+    Ok(weights_to_decimal(&weights, sub_constraints.max_position_weight))
+}
+
+/// Fractional-Kelly sizing: cap each weight at `kelly_fraction` times its market's Kelly bet (expected return over
+/// variance, zero for a non-positive edge). The capped excess is left undeployed rather than redistributed, so the
+/// weights sum to less than 1 when edges are small relative to their uncertainty. Capped weights that fall below
+/// the minimum position weight are zeroed.
+pub fn apply_kelly_scaling(
+    weights: &Array1<Decimal>,
+    expected_returns: &Array1<Decimal>,
+    covariance_matrix: &Array2<Decimal>,
+    kelly_fraction: f64,
+    min_position_weight: f64,
+) -> Array1<Decimal> {
+    let min_weight = Decimal::from_f64(min_position_weight).unwrap_or(Decimal::ZERO);
+    let mut scaled = weights.clone();
+    for i in 0..weights.len() {
+        let expected_return = expected_returns[i].to_f64().unwrap_or(0.0);
+        let variance = covariance_matrix[[i, i]].to_f64().unwrap_or(0.0);
+        let kelly_weight = if expected_return > 0.0 && variance > OPTIMIZER_EPSILON {
+            kelly_fraction * expected_return / variance
+        } else {
+            0.0
+        };
+        // Round down so the cap is never exceeded through rounding
+        let cap = Decimal::from_f64(kelly_weight.min(1.0))
+            .unwrap_or(Decimal::ZERO)
+            .round_dp_with_strategy(WEIGHT_DECIMAL_PLACES, RoundingStrategy::ToZero);
+        if scaled[i] > cap {
+            scaled[i] = if cap < min_weight { Decimal::ZERO } else { cap };
+        }
+    }
+    scaled
+}
+
+/// Discount markets by their data quality score q in (0, 1]: expected returns shrink toward zero (μ·q) and
+/// variance is inflated (σ²/q). Covariances are scaled by 1/√(q_i·q_j), keeping correlations and a positive
+/// semi-definite matrix, so a low-quality market looks both less rewarding and riskier to the optimizer.
+pub fn apply_data_quality(
+    expected_returns: &Array1<Decimal>,
+    covariance_matrix: &Array2<Decimal>,
+    quality_scores: &[f64],
+) -> (Array1<Decimal>, Array2<Decimal>) {
+    let scale: Vec<Decimal> = quality_scores.iter()
+        .map(|q| Decimal::from_f64(q.clamp(OPTIMIZER_EPSILON, 1.0)).unwrap_or(Decimal::ONE))
+        .collect();
+    let inflation: Vec<Decimal> = scale.iter()
+        .map(|q| Decimal::ONE / q.sqrt().unwrap_or(Decimal::ONE))
+        .collect();
+
+    let mut adjusted_returns = expected_returns.clone();
+    let mut adjusted_covariance = covariance_matrix.clone();
+    for i in 0..expected_returns.len() {
+        adjusted_returns[i] *= scale[i];
+        for j in 0..expected_returns.len() {
+            adjusted_covariance[[i, j]] *= inflation[i] * inflation[j];
+        }
+    }
+    (adjusted_returns, adjusted_covariance)
+}
+
+/// Validate optimizer inputs: non-empty, matching dimensions and a positive covariance diagonal
+fn validate_inputs(
+    expected_returns: &Array1<Decimal>,
+    covariance_matrix: &Array2<Decimal>,
+    cluster_ids: &[usize],
+) -> Result<()> {
     let n_assets = expected_returns.len();
     
-    // Validate inputs
     if n_assets == 0 {
         return Err(eyre::eyre!("Empty expected returns vector"));
     }
@@ -22,6 +249,10 @@ pub fn maximize_sharpe(
         return Err(eyre::eyre!("Covariance matrix dimensions don't match expected returns"));
     }
 
+    if cluster_ids.len() != n_assets {
+        return Err(eyre::eyre!("Cluster assignments don't match expected returns"));
+    }
+
     // Check if covariance matrix is positive definite by ensuring all diagonal elements are positive
     for i in 0..n_assets {
         if covariance_matrix[[i, i]] <= Decimal::ZERO {
@@ -29,85 +260,143 @@ pub fn maximize_sharpe(
         }
     }
 
-    // Use a simple analytical solution for the unconstrained case, then project
-    let optimal_weights = solve_unconstrained_mpt(&expected_returns, &covariance_matrix)?;
-    
-    Ok(optimal_weights)
+    Ok(())
 }
 
-/// Solve the unconstrained MPT problem analytically and project to valid weights
-fn solve_unconstrained_mpt(
+/// Convert Decimal inputs to f64, rejecting values that do not map to a finite float
+fn inputs_to_f64(
     expected_returns: &Array1<Decimal>,
     covariance_matrix: &Array2<Decimal>,
-) -> Result<Array1<Decimal>> {
+) -> Result<(Array1<f64>, Array2<f64>)> {
+    let to_finite = |d: &Decimal| -> Result<f64> {
+        d.to_f64()
+            .filter(|f| f.is_finite())
+            .ok_or_else(|| eyre::eyre!("Value {} cannot be represented as a finite f64", d))
+    };
+
+    let expected_returns_f64 = expected_returns.iter()
+        .map(to_finite)
+        .collect::<Result<Vec<f64>>>()?;
+    let covariance_matrix_f64 = covariance_matrix.iter()
+        .map(to_finite)
+        .collect::<Result<Vec<f64>>>()?;
+
     let n = expected_returns.len();
-    
+    let covariance_matrix_f64 = Array2::from_shape_vec((n, n), covariance_matrix_f64)
+        .map_err(|e| eyre::eyre!("Failed to reshape covariance matrix: {}", e))?;
+
+    Ok((Array1::from_vec(expected_returns_f64), covariance_matrix_f64))
+}
+
+/// Convert f64 weights back to Decimal, rounded to WEIGHT_DECIMAL_PLACES.
+/// The rounding residual is added to the largest weight that is still below the position cap
+/// (lowest index on ties) so the result sums to exactly 1.
+fn weights_to_decimal(weights: &Array1<f64>, max_position_weight: f64) -> Array1<Decimal> {
+    let mut weights_decimal: Array1<Decimal> = weights.mapv(|w| {
+        Decimal::from_f64(w)
+            .unwrap_or(Decimal::ZERO)
+            .round_dp(WEIGHT_DECIMAL_PLACES)
+            .max(Decimal::ZERO)
+    });
+
+    let weight_sum = weights_decimal.sum();
+    if weight_sum.is_zero() {
+        return weights_decimal;
+    }
+
+    let residual = Decimal::ONE - weight_sum;
+    if !residual.is_zero() {
+        let max_weight = Decimal::from_f64(max_position_weight).unwrap_or(Decimal::ONE);
+        let pick_largest = |below_cap: bool| {
+            weights_decimal.iter()
+                .enumerate()
+                .filter(|(_, w)| **w > Decimal::ZERO && (!below_cap || **w < max_weight))
+                .fold(None, |best: Option<(usize, Decimal)>, (i, &w)| match best {
+                    Some((_, best_w)) if best_w >= w => best,
+                    _ => Some((i, w)),
+                })
+                .map(|(i, _)| i)
+        };
+        if let Some(i) = pick_largest(true).or_else(|| pick_largest(false)) {
+            weights_decimal[i] = (weights_decimal[i] + residual).max(Decimal::ZERO);
+        }
+    }
+
+    weights_decimal
+}
+
+/// Solve the unconstrained MPT problem analytically and project to valid weights
+fn solve_unconstrained_mpt(
+    expected_returns: &Array1<f64>,
+    covariance_matrix: &Array2<f64>,
+    cluster_ids: &[usize],
+    constraints: &AllocationConstraints,
+) -> Result<Array1<f64>> {
     // For the mean-variance optimization problem, we want to maximize:
     // w^T * μ - λ/2 * w^T * Σ * w
     // subject to w^T * 1 = 1 (weights sum to 1)
     
     // The analytical solution is: w = (Σ^-1 * μ) / (1^T * Σ^-1 * μ)
     // But since matrix inversion is complex, we'll use a simpler heuristic approach
-    
-    // Simple heuristic: set initial weights by normalized Sharpe ratios prior to optimization
+    let weights = sharpe_heuristic_weights(expected_returns, covariance_matrix);
+
+    // Apply minimum variance optimization as a refinement
+    let refined_weights = refine_with_minimum_variance(&weights, expected_returns, covariance_matrix, cluster_ids, constraints)?;
+
+    Ok(refined_weights)
+}
+
+/// Simple heuristic: weights proportional to each asset's (non-negative) individual Sharpe ratio
+fn sharpe_heuristic_weights(
+    expected_returns: &Array1<f64>,
+    covariance_matrix: &Array2<f64>,
+) -> Array1<f64> {
+    let n = expected_returns.len();
     let mut weights = Array1::zeros(n);
-    let mut total_score = Decimal::ZERO;
-    
+    let mut total_score = 0.0;
+
     for i in 0..n {
         let variance = covariance_matrix[[i, i]];
         let expected_return = expected_returns[i];
         
         // Calculate individual Sharpe ratio (assuming zero risk-free rate)
-        let std_dev = variance.sqrt().unwrap_or(Decimal::ZERO);
-        let sharpe_ratio = if std_dev > Decimal::ZERO { expected_return / std_dev } else { Decimal::ZERO };
-        
+        let std_dev = variance.max(0.0).sqrt();
+        let sharpe_ratio = if std_dev > OPTIMIZER_EPSILON { expected_return / std_dev } else { 0.0 };
+
         // Use max(0, sharpe_ratio) to ensure non-negative weights
-        let score = sharpe_ratio.max(Decimal::ZERO);
+        let score = sharpe_ratio.max(0.0);
         weights[i] = score;
         total_score += score;
     }
     
     // Normalize weights to sum to 1
-    if total_score > Decimal::from_f64(1e-10).unwrap() {
-        for weight in weights.iter_mut() {
-            *weight = *weight / total_score;
-        }
+    if total_score > OPTIMIZER_EPSILON {
+        weights.mapv_inplace(|w| w / total_score);
     } else {
         // Fallback to zero weights if all Sharpe ratios are non-positive
-        weights.fill(Decimal::ZERO);
+        weights.fill(0.0);
     }
     
-    // Apply minimum variance optimization as a refinement
-    let refined_weights = refine_with_minimum_variance(&weights, expected_returns, covariance_matrix)?;
-    
-    Ok(refined_weights)
+    weights
 }
 
 /// Refine weights using a simple gradient descent approach
 fn refine_with_minimum_variance(
-    initial_weights: &Array1<Decimal>,
-    expected_returns: &Array1<Decimal>,
-    covariance_matrix: &Array2<Decimal>,
-) -> Result<Array1<Decimal>> {
-    let n = initial_weights.len();
-    
-    // Convert Decimal to f64 for optimization (argmin doesn't support Decimal)
-    let initial_weights_f64: Vec<f64> = initial_weights.iter()
-        .map(|d| d.to_f64().unwrap_or(0.0))
-        .collect();
-    
-    let expected_returns_f64 = expected_returns.mapv(|d| d.to_f64().unwrap_or(0.0));
-    let covariance_matrix_f64 = covariance_matrix.mapv(|d| d.to_f64().unwrap_or(0.0));
-    
+    initial_weights: &Array1<f64>,
+    expected_returns: &Array1<f64>,
+    covariance_matrix: &Array2<f64>,
+    cluster_ids: &[usize],
+    constraints: &AllocationConstraints,
+) -> Result<Array1<f64>> {
     // Define the optimization problem
     let problem = SharpeRatioProblem {
-        expected_returns: expected_returns_f64,
-        covariance_matrix: covariance_matrix_f64,
+        expected_returns: expected_returns.clone(),
+        covariance_matrix: covariance_matrix.clone(),
     };
 
     // Use steepest descent with backtracking line search
     let linesearch = BacktrackingLineSearch::new(
-        ArmijoCondition::new(0.0001).map_err(|e| eyre::eyre!("Failed to create Armijo condition: {}", e))?
+        ArmijoCondition::new(OPTIMIZER_ARMIJO_C).map_err(|e| eyre::eyre!("Failed to create Armijo condition: {}", e))?
     );
     let solver = SteepestDescent::new(linesearch);
 
@@ -115,96 +404,159 @@ fn refine_with_minimum_variance(
     let result = Executor::new(problem, solver)
         .configure(|state| {
             state
-                .param(initial_weights_f64)
-                .max_iters(1000)
-                .target_cost(1e-6)
+                .param(initial_weights.to_vec())
+                .max_iters(OPTIMIZER_MAX_ITERS)
+                .target_cost(OPTIMIZER_TARGET_COST)
         })
         .run()
         .map_err(|e| eyre::eyre!("Optimization failed: {}", e))?;
 
-    // Get the optimal weights and convert back to Decimal
-    let optimal_weights_vec = result.state().get_best_param().unwrap().clone();
-    let mut optimal_weights: Array1<Decimal> = Array1::from_vec(
-        optimal_weights_vec.iter()
-            .map(|&f| Decimal::from_f64(f).unwrap_or(Decimal::ZERO))
-            .collect()
-    );
+    // Get the optimal weights, falling back to the initial weights if the solver produced nothing usable
+    let optimal_weights = match result.state().get_best_param() {
+        Some(best) if best.iter().all(|w| w.is_finite()) => Array1::from_vec(best.clone()),
+        _ => initial_weights.clone(),
+    };
+
+    Ok(project_to_valid_weights(optimal_weights, cluster_ids, constraints))
+}
+
+/// Project raw optimizer output onto valid weights: non-negative, summing to 1 (or all zero),
+/// with tiny positions removed and position and cluster limits applied
+fn project_to_valid_weights(mut optimal_weights: Array1<f64>, cluster_ids: &[usize], constraints: &AllocationConstraints) -> Array1<f64> {
+    let n = optimal_weights.len();
 
     // Ensure weights are non-negative (project negative weights to zero)
-    optimal_weights.mapv_inplace(|w| w.max(Decimal::ZERO));
+    optimal_weights.mapv_inplace(|w| w.max(0.0));
 
     // Normalize weights to sum to 1
     let weight_sum = optimal_weights.sum();
-    if weight_sum > Decimal::from_f64(1e-10).unwrap() {
-        for weight in optimal_weights.iter_mut() {
-            *weight = *weight / weight_sum;
-        }
+    if weight_sum > OPTIMIZER_EPSILON {
+        optimal_weights.mapv_inplace(|w| w / weight_sum);
     } else {
         // Fallback to zero weights if all weights are near zero
-        optimal_weights = Array1::from_vec(vec![Decimal::ZERO; n]);
+        optimal_weights = Array1::zeros(n);
     }
 
     // Apply minimum weight filter first (eliminate tiny positions)
-    optimal_weights = apply_minimum_weight_filter(optimal_weights, Decimal::from_f64(0.01).unwrap()); // 1% min weight or zero
+    optimal_weights = apply_minimum_weight_filter(optimal_weights, constraints.min_position_weight);
 
     // Then apply maximum position size limits
-    optimal_weights = apply_position_limits(optimal_weights, Decimal::from_f64(0.25).unwrap()); // 25% max weight per asset
+    optimal_weights = apply_position_limits(optimal_weights, constraints.max_position_weight);
 
-    Ok(optimal_weights)
+    // Cap correlated clusters, then re-apply position limits as their excess lands on other clusters' assets
+    optimal_weights = apply_cluster_limits(optimal_weights, cluster_ids, constraints.max_cluster_weight);
+    apply_position_limits(optimal_weights, constraints.max_position_weight)
 }
 
-/// Apply position size limits by capping weights and redistributing excess
-fn apply_position_limits(mut weights: Array1<Decimal>, max_weight: Decimal) -> Array1<Decimal> {
+/// Apply cluster weight limits by scaling down overweight clusters and redistributing excess to assets in other clusters.
+/// The cap is raised to 1/cluster count when there are too few clusters to hold the full portfolio under it.
+fn apply_cluster_limits(mut weights: Array1<f64>, cluster_ids: &[usize], max_cluster_weight: f64) -> Array1<f64> {
     let n = weights.len();
-    let mut iterations = 0;
-    const MAX_ITERATIONS: usize = 100; // Prevent infinite loops
-    let epsilon = Decimal::from_f64(1e-10).unwrap();
-    
-    loop {
-        iterations += 1;
-        if iterations > MAX_ITERATIONS {
+    let n_clusters = cluster_ids.iter().max().map_or(0, |max_id| max_id + 1);
+    if n_clusters == 0 {
+        return weights;
+    }
+    let max_cluster_weight = max_cluster_weight.max(1.0 / n_clusters as f64);
+
+    for _ in 0..WEIGHT_ADJUSTMENT_MAX_ITERS {
+        let mut cluster_weights = vec![0.0; n_clusters];
+        for (i, &weight) in weights.iter().enumerate() {
+            cluster_weights[cluster_ids[i]] += weight;
+        }
+
+        // Find clusters that exceed the limit
+        let capped: Vec<bool> = cluster_weights.iter().map(|&w| w > max_cluster_weight).collect();
+        let total_excess: f64 = cluster_weights.iter()
+            .map(|&w| (w - max_cluster_weight).max(0.0))
+            .sum();
+
+        // If no clusters exceed the limit, we're done
+        if total_excess <= OPTIMIZER_EPSILON {
             break;
         }
-        
+
+        // Scale the overweight clusters down to the cap
+        let mut uncapped_weight_sum = 0.0;
+        for i in 0..n {
+            let cluster = cluster_ids[i];
+            if capped[cluster] {
+                weights[i] *= max_cluster_weight / cluster_weights[cluster];
+            } else {
+                uncapped_weight_sum += weights[i];
+            }
+        }
+
+        // Redistribute excess proportionally to assets in uncapped clusters
+        if uncapped_weight_sum > OPTIMIZER_EPSILON {
+            for i in 0..n {
+                if !capped[cluster_ids[i]] {
+                    let proportion = weights[i] / uncapped_weight_sum;
+                    weights[i] += total_excess * proportion;
+                }
+            }
+        } else {
+            // If all uncapped clusters have zero weight, distribute equally among their assets
+            let uncapped_count = cluster_ids.iter().filter(|&&c| !capped[c]).count();
+            if uncapped_count > 0 {
+                let equal_share = total_excess / uncapped_count as f64;
+                for i in 0..n {
+                    if !capped[cluster_ids[i]] {
+                        weights[i] += equal_share;
+                    }
+                }
+            }
+        }
+    }
+    
+    weights
+}
+
+/// Apply position size limits by capping weights and redistributing excess
+fn apply_position_limits(mut weights: Array1<f64>, max_weight: f64) -> Array1<f64> {
+    let n = weights.len();
+
+    for _ in 0..WEIGHT_ADJUSTMENT_MAX_ITERS {
         // Find assets that exceed the limit
-        let mut total_excess = Decimal::ZERO;
-        let mut capped_indices = Vec::new();
-        let mut uncapped_weight_sum = Decimal::ZERO;
-        
+        let mut total_excess = 0.0;
+        let mut capped = vec![false; n];
+        let mut uncapped_weight_sum = 0.0;
+
         for (i, &weight) in weights.iter().enumerate() {
             if weight > max_weight {
                 total_excess += weight - max_weight;
-                capped_indices.push(i);
+                capped[i] = true;
             } else {
                 uncapped_weight_sum += weight;
             }
         }
         
         // If no assets exceed the limit, we're done
-        if total_excess <= epsilon {
+        if total_excess <= OPTIMIZER_EPSILON {
             break;
         }
         
         // Cap the overweight assets
-        for &i in &capped_indices {
-            weights[i] = max_weight;
+        for i in 0..n {
+            if capped[i] {
+                weights[i] = max_weight;
+            }
         }
         
         // Redistribute excess proportionally to uncapped assets
-        if uncapped_weight_sum > epsilon {
+        if uncapped_weight_sum > OPTIMIZER_EPSILON {
             for i in 0..n {
-                if !capped_indices.contains(&i) {
+                if !capped[i] {
                     let proportion = weights[i] / uncapped_weight_sum;
                     weights[i] += total_excess * proportion;
                 }
             }
         } else {
             // If all uncapped assets have zero weight, distribute equally among them
-            let uncapped_count = n - capped_indices.len();
+            let uncapped_count = capped.iter().filter(|&&c| !c).count();
             if uncapped_count > 0 {
-                let equal_share = total_excess / Decimal::from(uncapped_count);
+                let equal_share = total_excess / uncapped_count as f64;
                 for i in 0..n {
-                    if !capped_indices.contains(&i) {
+                    if !capped[i] {
                         weights[i] += equal_share;
                     }
                 }
@@ -216,44 +568,38 @@ fn apply_position_limits(mut weights: Array1<Decimal>, max_weight: Decimal) -> A
 }
 
 /// Apply minimum weight filter by zeroing out tiny positions and redistributing their weight
-fn apply_minimum_weight_filter(mut weights: Array1<Decimal>, min_weight: Decimal) -> Array1<Decimal> {
+fn apply_minimum_weight_filter(mut weights: Array1<f64>, min_weight: f64) -> Array1<f64> {
     let n = weights.len();
-    let mut iterations = 0;
-    const MAX_ITERATIONS: usize = 100; // Prevent infinite loops
-    let epsilon = Decimal::from_f64(1e-10).unwrap();
-    
-    loop {
-        iterations += 1;
-        if iterations > MAX_ITERATIONS {
-            break;
-        }
-        
+
+    for _ in 0..WEIGHT_ADJUSTMENT_MAX_ITERS {
         // Find assets below the minimum threshold (but not already zero)
-        let mut total_to_redistribute = Decimal::ZERO;
-        let mut zeroed_indices = Vec::new();
-        let mut remaining_weight_sum = Decimal::ZERO;
-        
+        let mut total_to_redistribute = 0.0;
+        let mut zeroed = vec![false; n];
+        let mut remaining_weight_sum = 0.0;
+
         for (i, &weight) in weights.iter().enumerate() {
-            if weight > Decimal::ZERO && weight < min_weight {
+            if weight > 0.0 && weight < min_weight {
                 total_to_redistribute += weight;
-                zeroed_indices.push(i);
+                zeroed[i] = true;
             } else if weight >= min_weight {
                 remaining_weight_sum += weight;
             }
         }
 
         // Zero out the tiny positions
-        for &i in &zeroed_indices {
-            weights[i] = Decimal::ZERO;
+        for i in 0..n {
+            if zeroed[i] {
+                weights[i] = 0.0;
+            }
         }
         
         // If no assets are below the threshold, we're done
-        if total_to_redistribute <= epsilon {
+        if total_to_redistribute <= OPTIMIZER_EPSILON {
             break;
         }
         
         // Redistribute their weight proportionally to assets above the threshold
-        if remaining_weight_sum > epsilon {
+        if remaining_weight_sum > OPTIMIZER_EPSILON {
             for i in 0..n {
                 if weights[i] >= min_weight {
                     let proportion = weights[i] / remaining_weight_sum;
@@ -263,14 +609,10 @@ fn apply_minimum_weight_filter(mut weights: Array1<Decimal>, min_weight: Decimal
         } else {
             // If no assets are above the threshold, this shouldn't happen after normalization
             // But as a safety, distribute equally among all non-zero positions
-            let non_zero_count = weights.iter().filter(|&&w| w > Decimal::ZERO).count();
+            let non_zero_count = weights.iter().filter(|&&w| w > 0.0).count();
             if non_zero_count > 0 {
-                let equal_share = total_to_redistribute / Decimal::from(non_zero_count);
-                for weight in weights.iter_mut() {
-                    if *weight > Decimal::ZERO {
-                        *weight += equal_share;
-                    }
-                }
+                let equal_share = total_to_redistribute / non_zero_count as f64;
+                weights.mapv_inplace(|w| if w > 0.0 { w + equal_share } else { w });
             }
         }
     }
@@ -294,10 +636,10 @@ impl CostFunction for SharpeRatioProblem {
         
         // Ensure weights sum to 1 (soft constraint via penalty)
         let weight_sum = w.sum();
-        let weight_constraint_penalty = 1000.0 * (weight_sum - 1.0).powi(2);
-        
+        let weight_constraint_penalty = OPTIMIZER_CONSTRAINT_PENALTY * (weight_sum - 1.0).powi(2);
+
         // Ensure weights are non-negative (soft constraint via penalty)
-        let negative_weight_penalty = 1000.0 * w.iter()
+        let negative_weight_penalty = OPTIMIZER_CONSTRAINT_PENALTY * w.iter()
             .map(|&weight| if weight < 0.0 { weight.powi(2) } else { 0.0 })
             .sum::<f64>();
         
@@ -308,7 +650,7 @@ impl CostFunction for SharpeRatioProblem {
         let portfolio_variance = w.dot(&self.covariance_matrix.dot(&w));
         
         // Calculate Sharpe ratio (assuming risk-free rate = 0)
-        let sharpe_ratio = if portfolio_variance > 0.0 {
+        let sharpe_ratio = if portfolio_variance > OPTIMIZER_EPSILON {
             portfolio_return / portfolio_variance.sqrt()
         } else {
             0.0
@@ -335,15 +677,15 @@ impl Gradient for SharpeRatioProblem {
         let portfolio_std = portfolio_variance.sqrt();
         
         let mut gradient = vec![0.0; n];
-        
-        if portfolio_variance > 1e-10 {
+
+        if portfolio_variance > OPTIMIZER_EPSILON {
             // Gradient of negative Sharpe ratio
             for i in 0..n {
                 let d_return_d_wi = self.expected_returns[i];
                 let d_variance_d_wi = 2.0 * self.covariance_matrix.row(i).dot(&w);
                 let d_std_d_wi = d_variance_d_wi / (2.0 * portfolio_std);
                 
-                let d_sharpe_d_wi = (d_return_d_wi * portfolio_std - portfolio_return * d_std_d_wi) 
+                let d_sharpe_d_wi = (d_return_d_wi * portfolio_std - portfolio_return * d_std_d_wi)
                     / portfolio_variance;
                 
                 gradient[i] = -d_sharpe_d_wi;
@@ -354,14 +696,166 @@ impl Gradient for SharpeRatioProblem {
         let weight_sum = w.sum();
         for i in 0..n {
             // Weight sum constraint gradient
-            gradient[i] += 2000.0 * (weight_sum - 1.0);
-            
+            gradient[i] += 2.0 * OPTIMIZER_CONSTRAINT_PENALTY * (weight_sum - 1.0);
+
             // Non-negativity constraint gradient
             if w[i] < 0.0 {
-                gradient[i] += 2000.0 * w[i];
+                gradient[i] += 2.0 * OPTIMIZER_CONSTRAINT_PENALTY * w[i];
             }
         }
         
         Ok(gradient)
     }
 }
+
+/// Problem definition for the turnover-penalized mean-variance objective.
+/// We minimize −(w·μ − λ·wᵀΣw − κ·Σ|w − w₀|), with |x| smoothed as √(x² + s²) so the gradient is defined at zero turnover.
+struct TurnoverPenalizedProblem {
+    expected_returns: Array1<f64>,
+    covariance_matrix: Array2<f64>,
+    current_weights: Array1<f64>,
+    risk_aversion: f64,
+    turnover_penalty: f64,
+}
+
+impl CostFunction for TurnoverPenalizedProblem {
+    type Param = Vec<f64>;
+    type Output = f64;
+
+    fn cost(&self, weights: &Self::Param) -> Result<Self::Output, argmin::core::Error> {
+        let w = Array1::from_vec(weights.clone());
+
+        // Soft constraints: weights sum to 1 and are non-negative
+        let weight_constraint_penalty = OPTIMIZER_CONSTRAINT_PENALTY * (w.sum() - 1.0).powi(2);
+        let negative_weight_penalty = OPTIMIZER_CONSTRAINT_PENALTY * w.iter()
+            .map(|&weight| if weight < 0.0 { weight.powi(2) } else { 0.0 })
+            .sum::<f64>();
+
+        let portfolio_return = w.dot(&self.expected_returns);
+        let portfolio_variance = w.dot(&self.covariance_matrix.dot(&w));
+        let turnover = w.iter()
+            .zip(self.current_weights.iter())
+            .map(|(&wi, &w0)| ((wi - w0).powi(2) + TURNOVER_SMOOTHING.powi(2)).sqrt())
+            .sum::<f64>();
+
+        let utility = portfolio_return - self.risk_aversion * portfolio_variance - self.turnover_penalty * turnover;
+
+        Ok(-utility + weight_constraint_penalty + negative_weight_penalty)
+    }
+}
+
+impl Gradient for TurnoverPenalizedProblem {
+    type Param = Vec<f64>;
+    type Gradient = Vec<f64>;
+
+    fn gradient(&self, weights: &Self::Param) -> Result<Self::Gradient, argmin::core::Error> {
+        let w = Array1::from_vec(weights.clone());
+        let n = w.len();
+        let sigma_w = self.covariance_matrix.dot(&w);
+        let weight_sum = w.sum();
+
+        let mut gradient = vec![0.0; n];
+        for i in 0..n {
+            let delta = w[i] - self.current_weights[i];
+            let d_turnover_d_wi = delta / (delta.powi(2) + TURNOVER_SMOOTHING.powi(2)).sqrt();
+
+            gradient[i] = -self.expected_returns[i]
+                + 2.0 * self.risk_aversion * sigma_w[i]
+                + self.turnover_penalty * d_turnover_d_wi;
+
+            // Constraint penalty gradients
+            gradient[i] += 2.0 * OPTIMIZER_CONSTRAINT_PENALTY * (weight_sum - 1.0);
+            if w[i] < 0.0 {
+                gradient[i] += 2.0 * OPTIMIZER_CONSTRAINT_PENALTY * w[i];
+            }
+        }
+        
+        Ok(gradient)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use crate::strategy::strategy_constants::{MIN_POSITION_WEIGHT, MAX_POSITION_WEIGHT, MAX_CLUSTER_WEIGHT};
+
+    const CASES: u64 = 50;
+    const BOUND_TOLERANCE: f64 = 1e-6; // Rounding to WEIGHT_DECIMAL_PLACES and the residual can move a weight by ~1e-8
+
+    fn constraints() -> AllocationConstraints {
+        AllocationConstraints {
+            min_position_weight: MIN_POSITION_WEIGHT,
+            max_position_weight: MAX_POSITION_WEIGHT,
+            max_cluster_weight: MAX_CLUSTER_WEIGHT,
+            min_trade_size_usd: Decimal::ZERO,
+        }
+    }
+
+    /// Random allocation problem with positive expected returns (per 5 minute step, like the engine's) and a positive
+    /// definite covariance (A·Aᵀ plus a diagonal floor), over enough markets for the position cap to hold the portfolio.
+    /// Markets are paired into clusters, which the cluster cap admits at the position cap.
+    fn random_problem(seed: u64) -> (Array1<Decimal>, Array2<Decimal>, Vec<usize>) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let n = rng.random_range(6..=12);
+        let expected_returns = Array1::from_shape_fn(n, |_| rng.random_range(1e-6..1e-4));
+        let factors = Array2::from_shape_fn((n, n), |_| rng.random_range(-1e-3..1e-3));
+        let covariance = factors.dot(&factors.t()) + Array2::from_diag(&Array1::from_elem(n, 1e-7));
+        let to_decimal = |x: f64| Decimal::from_f64(x).unwrap();
+        let cluster_ids = (0..n).map(|i| i / 2).collect();
+        (expected_returns.mapv(to_decimal), covariance.mapv(to_decimal), cluster_ids)
+    }
+
+    fn assert_valid_weights(weights: &Array1<Decimal>, seed: u64) {
+        if weights.iter().all(|w| w.is_zero()) {
+            return;
+        }
+        assert_eq!(weights.sum(), Decimal::ONE, "weights don't sum to 1 (seed {})", seed);
+        for w in weights.iter().map(|w| w.to_f64().unwrap()) {
+            assert!(w >= 0.0, "negative weight {} (seed {})", w, seed);
+            assert!(w == 0.0 || w >= MIN_POSITION_WEIGHT - BOUND_TOLERANCE, "weight {} below the minimum (seed {})", w, seed);
+            assert!(w <= MAX_POSITION_WEIGHT + BOUND_TOLERANCE, "weight {} above the cap (seed {})", w, seed);
+        }
+    }
+
+    /// Bit-level comparison, Decimal equality ignores the scale
+    fn same_bits(a: &Array1<Decimal>, b: &Array1<Decimal>) -> bool {
+        a.len() == b.len() && a.iter().zip(b.iter()).all(|(x, y)| x.serialize() == y.serialize())
+    }
+
+    #[test]
+    fn sharpe_weights_sum_to_one_within_bounds() {
+        for seed in 0..CASES {
+            let (expected_returns, covariance, cluster_ids) = random_problem(seed);
+            let weights = maximize_sharpe(expected_returns, covariance, &cluster_ids, &constraints()).unwrap();
+            assert_valid_weights(&weights, seed);
+        }
+    }
+
+    #[test]
+    fn turnover_weights_sum_to_one_within_bounds() {
+        for seed in 0..CASES {
+            let (expected_returns, covariance, cluster_ids) = random_problem(seed);
+            let n = expected_returns.len();
+            let current_weights = Array1::from_elem(n, Decimal::ONE / Decimal::from(n));
+            let weights = maximize_utility_with_turnover(expected_returns, covariance, current_weights, &cluster_ids, &constraints(), 1.0, 1e-4).unwrap();
+            assert_valid_weights(&weights, seed);
+        }
+    }
+
+    #[test]
+    fn repeated_runs_are_bit_identical() {
+        for seed in 0..CASES {
+            let (expected_returns, covariance, cluster_ids) = random_problem(seed);
+            let first = maximize_sharpe(expected_returns.clone(), covariance.clone(), &cluster_ids, &constraints()).unwrap();
+            let second = maximize_sharpe(expected_returns.clone(), covariance.clone(), &cluster_ids, &constraints()).unwrap();
+            assert!(same_bits(&first, &second), "maximize_sharpe differs between runs (seed {})", seed);
+
+            let current_weights = Array1::from_elem(expected_returns.len(), Decimal::ZERO);
+            let first = maximize_utility_with_turnover(expected_returns.clone(), covariance.clone(), current_weights.clone(), &cluster_ids, &constraints(), 1.0, 1e-4).unwrap();
+            let second = maximize_utility_with_turnover(expected_returns, covariance, current_weights, &cluster_ids, &constraints(), 1.0, 1e-4).unwrap();
+            assert!(same_bits(&first, &second), "maximize_utility_with_turnover differs between runs (seed {})", seed);
+        }
+    }
+}
\ No newline at end of file
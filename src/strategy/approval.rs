@@ -0,0 +1,72 @@
+use eyre::Result;
+use tracing::{info, debug, warn, instrument};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::db::db_manager::DbManager;
+use crate::db::models::pending_plans::{PendingPlanActionModel, NewPendingPlanActionModel, PlanStatus};
+use super::types::PortfolioData;
+
+/// Write the strategy output to the pending plans table, returning the plan ID
+#[instrument(skip(config, db_manager, portfolio_data), fields(on_close = true))]
+pub async fn submit_plan_for_approval(
+    config: &Config,
+    db_manager: Arc<DbManager>,
+    portfolio_data: &PortfolioData,
+) -> Result<i32> {
+    let actions = NewPendingPlanActionModel::from_portfolio_data(portfolio_data, &db_manager.market_id_map);
+    if actions.len() < portfolio_data.market_addresses.len() {
+        warn!(
+            proposed = portfolio_data.market_addresses.len(),
+            recorded = actions.len(),
+            "Some markets are missing from the market ID map and were left out of the plan"
+        );
+    }
+    let expires_at = db_manager.clock.now() + chrono::Duration::seconds(config.plan_approval_ttl_secs as i64);
+    let plan_id = db_manager.create_pending_plan(&actions, expires_at).await?;
+    info!(
+        plan_id = plan_id,
+        action_count = actions.len(),
+        expires_at = %expires_at,
+        "Plan submitted for approval, approve with `plan approve {}`",
+        plan_id
+    );
+    Ok(plan_id)
+}
+
+/// Block until the plan is decided or expires.
+/// Returns the approved actions, or None if the plan was rejected or expired.
+#[instrument(skip(db_manager), fields(on_close = true))]
+pub async fn wait_for_plan_approval(
+    db_manager: Arc<DbManager>,
+    plan_id: i32,
+    poll_interval: Duration,
+) -> Result<Option<Vec<PendingPlanActionModel>>> {
+    loop {
+        db_manager.expire_stale_plans().await?;
+
+        let (plan, actions) = db_manager.get_pending_plan(plan_id).await?
+            .ok_or_else(|| eyre::eyre!("Pending plan {} not found", plan_id))?;
+        let status = PlanStatus::parse(&plan.status)
+            .ok_or_else(|| eyre::eyre!("Unknown plan status: {}", plan.status))?;
+
+        match status {
+            PlanStatus::Pending => {
+                debug!(plan_id = plan_id, expires_at = %plan.expires_at, "Plan still awaiting approval");
+                tokio::time::sleep(poll_interval).await;
+            }
+            PlanStatus::Approved => {
+                let approved: Vec<PendingPlanActionModel> = actions.into_iter()
+                    .filter(|a| a.status == PlanStatus::Approved.as_str())
+                    .collect();
+                info!(plan_id = plan_id, approved_actions = approved.len(), "Plan approved");
+                return Ok(Some(approved));
+            }
+            PlanStatus::Rejected | PlanStatus::Expired => {
+                info!(plan_id = plan_id, status = status.as_str(), "Plan not approved, skipping execution");
+                return Ok(None);
+            }
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use eyre::Result;
+use ethers::prelude::*;
+use rust_decimal::Decimal;
+use tracing::{debug, warn, instrument};
+
+use crate::config::Config;
+
+// Aave v3 Pool.getReserveData, the ReserveData struct is all static fields so it decodes as a flat tuple
+abigen!(
+    AavePool,
+    r#"[
+        function getReserveData(address asset) external view returns (uint256, uint128, uint128, uint128, uint128, uint128, uint40, uint16, address, address, address, address, uint128, uint128, uint128)
+    ]"#
+);
+
+/// Aave rates are rays (27 decimals)
+const RAY_DECIMALS: u32 = 27;
+
+/// Current risk-free (hurdle) rate as an APR fraction: the configured fixed rate if set,
+/// otherwise the Aave supply APR of the base stablecoin ("hold USDC in Aave"), otherwise zero
+#[instrument(skip(config), fields(on_close = true))]
+pub async fn load_risk_free_rate_apr(config: &Config) -> Decimal {
+    if let Some(rate) = config.risk_free_rate_apr {
+        return rate;
+    }
+    let (Some(pool_address), Some(asset)) = (config.aave_pool_address, config.base_stablecoin) else {
+        debug!("No fixed rate or Aave pool configured, using zero risk-free rate");
+        return Decimal::ZERO;
+    };
+    match get_aave_supply_apr(config, pool_address, asset).await {
+        Ok(rate) => {
+            debug!(rate = %rate, "Fetched Aave supply APR as risk-free rate");
+            rate
+        }
+        Err(e) => {
+            warn!(error = ?e, "Failed to fetch Aave supply APR, using zero risk-free rate");
+            Decimal::ZERO
+        }
+    }
+}
+
+/// Supply APR of an asset on Aave v3 (current liquidity rate), as a fraction
+pub async fn get_aave_supply_apr(config: &Config, pool_address: Address, asset: Address) -> Result<Decimal> {
+    let pool = AavePool::new(pool_address, config.alchemy_provider.clone());
+    let reserve_data = pool.get_reserve_data(asset).call().await?;
+    let current_liquidity_rate = reserve_data.2;
+    Ok(Decimal::from_i128_with_scale(current_liquidity_rate as i128, RAY_DECIMALS).normalize())
+}
@@ -0,0 +1,293 @@
+// Pre-trade compliance rules, evaluated before every GM request, hedge order, spot swap (each TWAP slice) and bridge
+// transfer. Rules are rows of the compliance_rules table (per account), a violation blocks the action and GM requests
+// are recorded as Blocked trades carrying the violated rule's ID.
+use chrono::{DateTime, Timelike, Utc};
+use ethers::types::Address;
+use eyre::Result;
+use rust_decimal::Decimal;
+use std::fmt;
+use tracing::{debug, instrument};
+
+use crate::db::db_manager::DbManager;
+use crate::db::models::compliance_rules::ComplianceRuleModel;
+use crate::gm_token_txs::types::GmTxRequest;
+use crate::hedging::hedge_utils;
+use crate::wallet::WalletManager;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ComplianceRuleKind {
+    MaxTradeNotional(Decimal),          // USD value of a single action
+    MaxDailyTurnover(Decimal),          // USD value of GM requests created in the current UTC day plus the action, for GM requests and spot swaps
+    BannedToken(String),                // Token no action may send, receive or hedge, by symbol or base asset (ETH bans WETH)
+    MaxHedgeLeverage(Decimal),          // Hedge position notional over the venue collateral after the order
+    TradingWindow { start_hour: u32, end_hour: u32 }, // UTC hours actions may run in, start inclusive, end exclusive
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComplianceRule {
+    pub rule_id: String,
+    pub kind: ComplianceRuleKind,
+}
+
+impl ComplianceRule {
+    /// Parse a rule row, an invalid rule is an error rather than skipped so a typo can't silently lift a restriction
+    pub fn parse(model: &ComplianceRuleModel) -> Result<Self> {
+        let value = model.value.trim();
+        let invalid = |expected: &str| eyre::eyre!("Invalid compliance rule {}: expected {}, got {:?}", model.rule_id, expected, value);
+        let positive_decimal = || value.parse::<Decimal>().ok().filter(|v| *v > Decimal::ZERO);
+        let kind = match model.rule_type.as_str() {
+            "MaxTradeNotional" => ComplianceRuleKind::MaxTradeNotional(positive_decimal().ok_or_else(|| invalid("a positive USD amount"))?),
+            "MaxDailyTurnover" => ComplianceRuleKind::MaxDailyTurnover(positive_decimal().ok_or_else(|| invalid("a positive USD amount"))?),
+            "BannedToken" if !value.is_empty() => ComplianceRuleKind::BannedToken(value.to_uppercase()),
+            "BannedToken" => return Err(invalid("a token symbol")),
+            "MaxHedgeLeverage" => ComplianceRuleKind::MaxHedgeLeverage(positive_decimal().ok_or_else(|| invalid("a positive leverage"))?),
+            "TradingWindow" => {
+                let (start, end) = value.split_once('-').ok_or_else(|| invalid("UTC hours as start-end, e.g. 8-20"))?;
+                let hour = |h: &str| h.trim().parse::<u32>().ok().filter(|h| *h <= 24);
+                match (hour(start), hour(end)) {
+                    (Some(start_hour), Some(end_hour)) if start_hour != end_hour => ComplianceRuleKind::TradingWindow { start_hour, end_hour },
+                    _ => return Err(invalid("two different UTC hours within 0-24")),
+                }
+            }
+            other => return Err(eyre::eyre!("Unknown compliance rule type {} for rule {}", other, model.rule_id)),
+        };
+        Ok(Self { rule_id: model.rule_id.clone(), kind })
+    }
+}
+
+/// An action about to be taken, as seen by the rules
+#[derive(Debug, Clone)]
+pub struct ProposedAction {
+    pub description: String,
+    pub notional_usd: Decimal,
+    pub token_symbols: Vec<String>,
+    pub daily_turnover_usd: Option<Decimal>, // Turnover already done today, None for actions not counted as turnover
+    pub hedge_leverage: Option<Decimal>,     // Hedge leverage after the action, None for non-hedge actions
+}
+
+/// A rule the action would break
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComplianceViolation {
+    pub rule_id: String,
+    pub reason: String,
+}
+
+impl fmt::Display for ComplianceViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Blocked by compliance rule {}: {}", self.rule_id, self.reason)
+    }
+}
+
+impl std::error::Error for ComplianceViolation {}
+
+/// Load and parse the account's enabled rules
+pub async fn load_rules(db_manager: &DbManager) -> Result<Vec<ComplianceRule>> {
+    db_manager.get_compliance_rules().await?
+        .iter()
+        .map(ComplianceRule::parse)
+        .collect()
+}
+
+/// First rule the action breaks at the given time, None when it complies with all of them
+pub fn evaluate_rules(rules: &[ComplianceRule], action: &ProposedAction, now: DateTime<Utc>) -> Option<ComplianceViolation> {
+    rules.iter().find_map(|rule| {
+        let reason = match &rule.kind {
+            ComplianceRuleKind::MaxTradeNotional(max) if action.notional_usd > *max => {
+                format!("{} notional {:.2} USD exceeds the {:.2} USD limit", action.description, action.notional_usd, max)
+            }
+            ComplianceRuleKind::MaxDailyTurnover(max) => {
+                let turnover = action.daily_turnover_usd? + action.notional_usd;
+                if turnover <= *max {
+                    return None;
+                }
+                format!("{} would bring today's turnover to {:.2} USD, over the {:.2} USD limit", action.description, turnover, max)
+            }
+            ComplianceRuleKind::BannedToken(symbol) if action.token_symbols.iter().any(|s| {
+                s.eq_ignore_ascii_case(symbol) || hedge_utils::get_base_asset(s).eq_ignore_ascii_case(symbol)
+            }) => {
+                format!("{} involves banned token {}", action.description, symbol)
+            }
+            ComplianceRuleKind::MaxHedgeLeverage(max) => {
+                let leverage = action.hedge_leverage?;
+                if leverage <= *max {
+                    return None;
+                }
+                format!("{} would take hedge leverage to {:.2}x, over the {:.2}x limit", action.description, leverage, max)
+            }
+            ComplianceRuleKind::TradingWindow { start_hour, end_hour } if !in_window(now.hour(), *start_hour, *end_hour) => {
+                format!("{} outside the {:02}:00-{:02}:00 UTC trading window", action.description, start_hour, end_hour)
+            }
+            _ => return None,
+        };
+        Some(ComplianceViolation { rule_id: rule.rule_id.clone(), reason })
+    })
+}
+
+/// Whether the hour is within a window, windows with start after end wrap past midnight
+fn in_window(hour: u32, start_hour: u32, end_hour: u32) -> bool {
+    if start_hour < end_hour {
+        hour >= start_hour && hour < end_hour
+    } else {
+        hour >= start_hour || hour < end_hour
+    }
+}
+
+/// USD value of a GM request at the wallet's last prices: tokens sent for deposits, GM tokens for withdrawals and
+/// shifts. Reward claims have no notional.
+pub fn request_notional_usd(wallet_manager: &WalletManager, request: &GmTxRequest) -> Result<Decimal> {
+    let market_price = |market: &Address| wallet_manager.market_token(market)
+        .map(|info| info.last_mid_price_usd)
+        .ok_or_else(|| eyre::eyre!("Market token not found: {}", market));
+    match request {
+        GmTxRequest::Deposit(deposit) => {
+            let market_info = wallet_manager.market_token(&deposit.market)
+                .ok_or_else(|| eyre::eyre!("Market token not found: {}", deposit.market))?;
+            let (long_token, short_token) = deposit.initial_tokens(market_info.long_token_address, market_info.short_token_address);
+            let price = |token: &Address| wallet_manager.token(token)
+                .map(|info| info.last_mid_price_usd)
+                .ok_or_else(|| eyre::eyre!("Token not found: {}", token));
+            Ok(deposit.long_amount * price(&long_token)? + deposit.short_amount * price(&short_token)?)
+        }
+        GmTxRequest::Withdrawal(withdrawal) => Ok(withdrawal.amount * market_price(&withdrawal.market)?),
+        GmTxRequest::Shift(shift) => Ok(shift.amount * market_price(&shift.from_market)?),
+        GmTxRequest::ClaimRewards(_) | GmTxRequest::ClaimUiFees(_) => Ok(Decimal::ZERO),
+    }
+}
+
+/// Symbols of every token a GM request touches: the GM tokens and the market's long and short tokens (and the
+/// tokens swapped into them), or the reward or UI fee tokens claimed
+fn request_token_symbols(wallet_manager: &WalletManager, request: &GmTxRequest) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut add_market = |market: &Address| {
+        if let Some(info) = wallet_manager.market_token(market) {
+            tokens.extend([info.address, info.long_token_address, info.short_token_address]);
+        }
+    };
+    match request {
+        GmTxRequest::Deposit(deposit) => add_market(&deposit.market),
+        GmTxRequest::Withdrawal(withdrawal) => add_market(&withdrawal.market),
+        GmTxRequest::Shift(shift) => {
+            add_market(&shift.from_market);
+            add_market(&shift.to_market);
+        }
+        GmTxRequest::ClaimRewards(_) | GmTxRequest::ClaimUiFees(_) => {}
+    }
+    match request {
+        GmTxRequest::Deposit(deposit) => tokens.extend(deposit.initial_long_token.into_iter().chain(deposit.initial_short_token)),
+        GmTxRequest::ClaimRewards(claim) => tokens.extend(claim.tokens.iter().copied()),
+        GmTxRequest::ClaimUiFees(claim) => tokens.extend(claim.markets.iter()
+            .filter_map(|market| wallet_manager.market_token(market))
+            .flat_map(|info| [info.long_token_address, info.short_token_address])),
+        _ => {}
+    }
+    tokens.iter()
+        .filter_map(|token| wallet_manager.token(token).map(|info| info.symbol))
+        .collect()
+}
+
+/// Check a GM request against the account's rules, returning the violation blocking it, if any
+#[instrument(skip(db_manager, wallet_manager, request), fields(on_close = true))]
+pub async fn check_gm_request(db_manager: &DbManager, wallet_manager: &WalletManager, request: &GmTxRequest) -> Result<Option<ComplianceViolation>> {
+    let rules = load_rules(db_manager).await?;
+    if rules.is_empty() {
+        return Ok(None);
+    }
+
+    let now = db_manager.clock.now();
+    let action = ProposedAction {
+        description: match request {
+            GmTxRequest::Deposit(_) => "GM deposit",
+            GmTxRequest::Withdrawal(_) => "GM withdrawal",
+            GmTxRequest::Shift(_) => "GM shift",
+            GmTxRequest::ClaimRewards(_) => "Reward claim",
+            GmTxRequest::ClaimUiFees(_) => "UI fee claim",
+        }.to_string(),
+        notional_usd: request_notional_usd(wallet_manager, request)?,
+        token_symbols: request_token_symbols(wallet_manager, request),
+        daily_turnover_usd: Some(daily_turnover_usd(db_manager, now).await?),
+        hedge_leverage: None,
+    };
+    let violation = evaluate_rules(&rules, &action, now);
+    debug!(rule_count = rules.len(), notional_usd = %action.notional_usd.round_dp(2), blocked = violation.is_some(), "GM request checked against compliance rules");
+    Ok(violation)
+}
+
+/// Check a spot swap (or one TWAP slice of it) against the account's rules, returning the violation blocking it, if any
+#[instrument(skip(db_manager), fields(on_close = true))]
+pub async fn check_spot_swap(db_manager: &DbManager, from_symbol: &str, to_symbol: &str, notional_usd: Decimal) -> Result<Option<ComplianceViolation>> {
+    let rules = load_rules(db_manager).await?;
+    if rules.is_empty() {
+        return Ok(None);
+    }
+
+    let now = db_manager.clock.now();
+    let action = ProposedAction {
+        description: format!("{} -> {} swap", from_symbol, to_symbol),
+        notional_usd,
+        token_symbols: vec![from_symbol.to_string(), to_symbol.to_string()],
+        daily_turnover_usd: Some(daily_turnover_usd(db_manager, now).await?),
+        hedge_leverage: None,
+    };
+    let violation = evaluate_rules(&rules, &action, now);
+    debug!(rule_count = rules.len(), blocked = violation.is_some(), "Spot swap checked against compliance rules");
+    Ok(violation)
+}
+
+/// Check a bridge transfer against the account's rules, returning the violation blocking it, if any. Moving collateral
+/// between the account's own venues isn't counted as turnover.
+#[instrument(skip(db_manager), fields(on_close = true))]
+pub async fn check_bridge_collateral(db_manager: &DbManager, token: &str, notional_usd: Decimal) -> Result<Option<ComplianceViolation>> {
+    let rules = load_rules(db_manager).await?;
+    if rules.is_empty() {
+        return Ok(None);
+    }
+
+    let now = db_manager.clock.now();
+    let action = ProposedAction {
+        description: format!("{} bridge transfer", token),
+        notional_usd,
+        token_symbols: vec![token.to_string()],
+        daily_turnover_usd: None,
+        hedge_leverage: None,
+    };
+    let violation = evaluate_rules(&rules, &action, now);
+    debug!(rule_count = rules.len(), blocked = violation.is_some(), "Bridge transfer checked against compliance rules");
+    Ok(violation)
+}
+
+/// USD value of the GM requests created so far in the UTC day of `now`
+async fn daily_turnover_usd(db_manager: &DbManager, now: DateTime<Utc>) -> Result<Decimal> {
+    let day_start = now.date_naive().and_hms_opt(0, 0, 0).unwrap().and_utc();
+    Ok(db_manager.get_trade_notional_since(day_start).await?)
+}
+
+/// Check a hedge order against the given rules, from the venue's open position notional after the order and its
+/// collateral, returning the violation blocking it, if any
+pub fn check_hedge_order(
+    rules: &[ComplianceRule],
+    token: &str,
+    notional_usd: Decimal,
+    position_notional_after_usd: Decimal,
+    collateral_usd: Decimal,
+    now: DateTime<Utc>,
+) -> Option<ComplianceViolation> {
+    let action = ProposedAction {
+        description: format!("{} hedge order", token),
+        notional_usd,
+        token_symbols: vec![token.to_string()],
+        daily_turnover_usd: None,
+        hedge_leverage: Some(hedge_leverage(position_notional_after_usd, collateral_usd)),
+    };
+    evaluate_rules(rules, &action, now)
+}
+
+/// Position notional over collateral, unbounded when positions are held without collateral
+fn hedge_leverage(position_notional_usd: Decimal, collateral_usd: Decimal) -> Decimal {
+    if position_notional_usd.is_zero() {
+        Decimal::ZERO
+    } else if collateral_usd > Decimal::ZERO {
+        position_notional_usd / collateral_usd
+    } else {
+        Decimal::MAX
+    }
+}
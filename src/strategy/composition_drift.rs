@@ -0,0 +1,168 @@
+use eyre::Result;
+use ethers::types::Address;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use tracing::{debug, info, warn, instrument};
+
+use crate::config::Config;
+use crate::db::db_manager::DbManager;
+use crate::db::models::market_states::MarketStateModel;
+use crate::db::models::position_exposures::NewPositionExposureModel;
+use super::strategy_constants::WEIGHT_DECIMAL_PLACES;
+use super::types::{PortfolioData, PortfolioSnapshot};
+
+/// A held GM position's implicit collateral exposure. GM is backed by the pool's long and short tokens in the
+/// proportion of their USD value, so the split moves with prices (and swaps into the pool) while the GM held doesn't.
+#[derive(Debug, Clone, Copy)]
+pub struct PositionExposure {
+    pub market: Address,
+    pub gm_value_usd: Decimal,
+    pub long_share: Decimal,       // Long token share of the pool's token value
+    pub entry_long_share: Decimal, // Long token share when the position was last traded
+}
+
+impl PositionExposure {
+    pub fn long_token_usd(&self) -> Decimal {
+        self.gm_value_usd * self.long_share
+    }
+
+    pub fn short_token_usd(&self) -> Decimal {
+        self.gm_value_usd - self.long_token_usd()
+    }
+
+    /// Change in the long token share since the position was last traded, positive when the position got longer
+    pub fn drift(&self) -> Decimal {
+        self.long_share - self.entry_long_share
+    }
+}
+
+/// Long token share of a pool's token value, None when the state has no pool composition
+fn long_share(state: &MarketStateModel) -> Option<Decimal> {
+    let long_usd = state.pool_long_token_usd?;
+    let pool_usd = long_usd + state.pool_short_token_usd?;
+    if pool_usd <= Decimal::ZERO {
+        return None;
+    }
+    Some(long_usd / pool_usd)
+}
+
+/// Work out the collateral exposure of every held market from the latest recorded pool compositions and record it in
+/// the position exposure ledger. The entry split is carried over from the market's previous entry until a trade in
+/// the market settles, which resets it to the current split.
+#[instrument(skip(db_manager, current_portfolio), fields(on_close = true))]
+pub async fn track_position_exposures(db_manager: &DbManager, current_portfolio: &PortfolioSnapshot) -> Result<Vec<PositionExposure>> {
+    let market_states: HashMap<i32, MarketStateModel> = db_manager.get_latest_market_states().await?
+        .into_iter()
+        .map(|state| (state.market_id, state))
+        .collect();
+    let previous = db_manager.get_latest_position_exposures().await?;
+
+    let mut exposures = Vec::new();
+    let mut records = Vec::new();
+    for (market, weight) in &current_portfolio.weights {
+        let Some(market_id) = db_manager.market_id_map.get(market).copied() else {
+            continue;
+        };
+        let Some(long_share) = market_states.get(&market_id).and_then(long_share) else {
+            warn!(market = ?market, "No pool composition recorded for held market, exposure not tracked");
+            continue;
+        };
+        let entry_long_share = match previous.get(&market_id) {
+            Some(entry) => {
+                let last_trade = db_manager.get_last_settled_trade_time(market_id).await?;
+                if last_trade.is_some_and(|traded_at| traded_at > entry.recorded_at) { long_share } else { entry.entry_long_share }
+            }
+            None => long_share,
+        };
+
+        let exposure = PositionExposure {
+            market: *market,
+            gm_value_usd: *weight * current_portfolio.total_value_usd,
+            long_share,
+            entry_long_share,
+        };
+        debug!(market = ?market, long_share = %long_share.round_dp(4), drift = %exposure.drift().round_dp(4), "Position exposure tracked");
+        records.push(NewPositionExposureModel {
+            market_id,
+            gm_value_usd: exposure.gm_value_usd,
+            long_token_usd: exposure.long_token_usd(),
+            short_token_usd: exposure.short_token_usd(),
+            long_share,
+            entry_long_share,
+        });
+        exposures.push(exposure);
+    }
+
+    if !records.is_empty() {
+        db_manager.insert_position_exposures(&records).await?;
+    }
+    Ok(exposures)
+}
+
+/// When a held market's collateral split has drifted past the configured threshold, rebalance it. A position gone
+/// long heavy is trimmed until its long token exposure is back at its entry level, the withdrawal leaving the pool's
+/// overweight side behind. A position gone short heavy has lost long exposure a withdrawal can't restore, so the
+/// long hedge covering it is oversized: the hedge reduction is recorded in the plan notes instead.
+/// Targets already withdrawing more than the trim are left alone. Returns the number of markets trimmed.
+#[instrument(skip(config, exposures, portfolio_data, current_portfolio), fields(on_close = true))]
+pub fn apply_collateral_drift_rebalance(
+    config: &Config,
+    exposures: &[PositionExposure],
+    portfolio_data: &mut PortfolioData,
+    current_portfolio: &PortfolioSnapshot,
+) -> usize {
+    let Some(threshold) = config.collateral_drift_threshold else {
+        return 0;
+    };
+    let total_value = current_portfolio.total_value_usd;
+    if total_value <= Decimal::ZERO {
+        return 0;
+    }
+
+    let mut trimmed = 0;
+    for exposure in exposures.iter().filter(|exposure| exposure.drift().abs() > threshold) {
+        let Some(i) = portfolio_data.get_market_index(exposure.market) else {
+            continue;
+        };
+        let name = portfolio_data.display_names[i].clone();
+        let drift_points = exposure.drift() * Decimal::from(100);
+
+        if exposure.drift() < Decimal::ZERO {
+            let hedge_excess_usd = -exposure.drift() * exposure.gm_value_usd;
+            let note = format!(
+                "Collateral split drifted {:.2} points short (long share {:.2}% from {:.2}%), long hedge oversized by {:.2} USD",
+                -drift_points,
+                exposure.long_share * Decimal::from(100),
+                exposure.entry_long_share * Decimal::from(100),
+                hedge_excess_usd
+            );
+            warn!(market = %name, "{}", note);
+            portfolio_data.add_note(exposure.market, note);
+            continue;
+        }
+
+        // Long exposure L * (V - w) back at the entry level E * V
+        let withdrawal_usd = exposure.gm_value_usd * (Decimal::ONE - exposure.entry_long_share / exposure.long_share);
+        let current_weight = current_portfolio.weights.get(&exposure.market).copied().unwrap_or(Decimal::ZERO);
+        let trimmed_weight = ((current_weight - withdrawal_usd / total_value).max(Decimal::ZERO)).round_dp(WEIGHT_DECIMAL_PLACES);
+        let target_weight = portfolio_data.weights[i];
+        if target_weight <= trimmed_weight {
+            continue;
+        }
+
+        portfolio_data.weights[i] = trimmed_weight;
+        trimmed += 1;
+        let note = format!(
+            "Collateral split drifted {:.2} points long (long share {:.2}% from {:.2}%), withdrawing {:.2} USD to restore long exposure, target weight {:.2}% -> {:.2}%",
+            drift_points,
+            exposure.long_share * Decimal::from(100),
+            exposure.entry_long_share * Decimal::from(100),
+            withdrawal_usd,
+            target_weight * Decimal::from(100),
+            trimmed_weight * Decimal::from(100)
+        );
+        info!(market = %name, "{}", note);
+        portfolio_data.add_note(exposure.market, note);
+    }
+    trimmed
+}
@@ -1,118 +1,190 @@
 use rust_decimal::Decimal;
 use rust_decimal::prelude::*;
-use ndarray::Array2;
+use ndarray::{Array1, Array2, ArrayView1, Axis};
+use chrono::{DateTime, Duration, Utc};
 
 use super::types::{
     MarketStateSlice, 
 };
+use super::return_calculation_utils::{self, FillMethod};
+use super::strategy_constants::{RETURN_RESAMPLE_INTERVAL_MINUTES, RETURN_RESAMPLE_MAX_GAP_MINUTES};
+
+/// Index returns of every market over the same resampled grid steps, one row per market in slice order. Built once
+/// per run and shared by the covariance and clustering, so the Decimal history is converted a single time and the
+/// pairwise statistics are matrix products. Rebuilding reuses the buffers while the shape is unchanged.
+#[derive(Debug, Clone)]
+pub struct ReturnMatrix {
+    returns: Array2<f64>,
+    centered: Array2<f64>, // Returns less each market's mean return
+}
 
-/// Calculate covariance matrix from market slices with consistent ordering
-pub fn calculate_covariance_matrix(market_slices: &[MarketStateSlice]) -> Option<Array2<Decimal>> {
-    if market_slices.is_empty() {
-        return None;
+impl ReturnMatrix {
+    /// Aligned returns of the slices. None when a market has no pool value or there are fewer than two common steps.
+    pub fn from_slices(market_slices: &[MarketStateSlice]) -> Option<Self> {
+        let mut matrix = Self { returns: Array2::zeros((0, 0)), centered: Array2::zeros((0, 0)) };
+        matrix.rebuild(market_slices).then_some(matrix)
     }
 
-    // Calculate historical covariance matrix
-    let historical_cov = calculate_historical_covariance(market_slices)?;
-
-    Some(historical_cov)
-}
-
-/// Calculate historical covariance matrix from PnL returns
-fn calculate_historical_covariance(market_slices: &[MarketStateSlice]) -> Option<Array2<Decimal>> {
-        let n_markets = market_slices.len();
-        
-        // Extract PnL returns for each market
-        let mut returns_matrix: Vec<Vec<Decimal>> = Vec::with_capacity(n_markets);
-        let mut min_length = usize::MAX;
-
-        for slice in market_slices {
-            let returns = calculate_returns(slice)?;
-            min_length = min_length.min(returns.len());
-            returns_matrix.push(returns);
+    /// Refill the matrix from this run's slices, reallocating only when the number of markets or steps changed.
+    /// Returns false, leaving the matrix empty, when the slices have no usable aligned returns.
+    pub fn rebuild(&mut self, market_slices: &[MarketStateSlice]) -> bool {
+        let Some(returns) = aligned_returns(market_slices) else {
+            self.returns = Array2::zeros((0, 0));
+            self.centered = Array2::zeros((0, 0));
+            return false;
+        };
+        let shape = (returns.len(), returns.first().map_or(0, Vec::len));
+        if self.returns.dim() != shape {
+            self.returns = Array2::zeros(shape);
+            self.centered = Array2::zeros(shape);
         }
 
-        if min_length < 2 {
-            return None;
+        for (mut row, market_returns) in self.returns.rows_mut().into_iter().zip(&returns) {
+            for (cell, value) in row.iter_mut().zip(market_returns) {
+                *cell = value.to_f64().unwrap_or(0.0);
+            }
         }
+        let means = self.returns.mean_axis(Axis(1)).unwrap_or_else(|| Array1::zeros(shape.0));
+        self.centered.assign(&self.returns);
+        self.centered -= &means.insert_axis(Axis(1));
+        true
+    }
 
-        // Truncate all return series to the same length
-        for returns in &mut returns_matrix {
-            returns.truncate(min_length);
-        }
+    pub fn n_markets(&self) -> usize {
+        self.returns.nrows()
+    }
 
-        // Calculate covariance matrix
-        let mut cov_matrix: Array2<Decimal> = Array2::zeros((n_markets, n_markets));
-        
-        for i in 0..n_markets {
-            for j in 0..n_markets {
-                let covariance = calculate_covariance(&returns_matrix[i], &returns_matrix[j]);
-                let net_oi_i = market_slices[i].oi_long_via_tokens - market_slices[i].oi_short_via_tokens;
-                let net_oi_j = market_slices[j].oi_long_via_tokens - market_slices[j].oi_short_via_tokens;
-                let pool_value_i = market_slices[i].pool_long_collateral_usd + market_slices[i].pool_short_collateral_usd - market_slices[i].impact_pool_usd;
-                let pool_value_j = market_slices[j].pool_long_collateral_usd + market_slices[j].pool_short_collateral_usd - market_slices[j].impact_pool_usd;
-                let net_oi_exposure_i = if pool_value_i > Decimal::ZERO {
-                    net_oi_i / pool_value_i
-                } else {
-                    Decimal::ZERO
-                };
-                let net_oi_exposure_j = if pool_value_j > Decimal::ZERO {
-                    net_oi_j / pool_value_j
-                } else {
-                    Decimal::ZERO
-                };
-                cov_matrix[[i, j]] = covariance * net_oi_exposure_i * net_oi_exposure_j;
-            }
-        }
+    pub fn n_steps(&self) -> usize {
+        self.returns.ncols()
+    }
 
-        Some(cov_matrix)
+    /// Returns of one market, in grid step order
+    pub fn market_returns(&self, market: usize) -> ArrayView1<'_, f64> {
+        self.returns.row(market)
     }
 
-/// Calculate PnL returns for a single market from historical data
-fn calculate_returns(slice: &MarketStateSlice) -> Option<Vec<Decimal>> {
-        if slice.index_prices.len() < 2 {
-            return None;
+    /// Sample covariance of index returns between every pair of markets (divide by n-1)
+    pub fn covariance(&self) -> Array2<f64> {
+        if self.n_steps() < 2 {
+            return Array2::zeros((self.n_markets(), self.n_markets()));
         }
+        self.centered.dot(&self.centered.t()) / (self.n_steps() - 1) as f64
+    }
 
-        let current_pool_value = slice.pool_long_collateral_usd + slice.pool_short_collateral_usd - slice.impact_pool_usd;
-        
-        if current_pool_value <= Decimal::ZERO {
-            return None;
+    /// Pearson correlation of index returns between every pair of markets, zero against markets with flat returns
+    pub fn correlation(&self) -> Array2<f64> {
+        let covariance = self.covariance();
+        let std_devs = covariance.diag().mapv(f64::sqrt);
+        let mut correlation = covariance;
+        for ((i, j), value) in correlation.indexed_iter_mut() {
+            let denominator = std_devs[i] * std_devs[j];
+            *value = if i == j {
+                1.0
+            } else if denominator > 0.0 {
+                *value / denominator
+            } else {
+                0.0
+            };
         }
+        correlation
+    }
+}
+
+/// Calculate covariance matrix from market slices with consistent ordering
+pub fn calculate_covariance_matrix(market_slices: &[MarketStateSlice], return_matrix: &ReturnMatrix) -> Option<Array2<Decimal>> {
+    if market_slices.is_empty() || return_matrix.n_markets() != market_slices.len() {
+        return None;
+    }
 
-        let mut returns = Vec::new();
+    // Calculate historical covariance matrix
+    let historical_cov = calculate_historical_covariance(market_slices, return_matrix);
+
+    Some(historical_cov)
+}
 
-        for i in 1..slice.index_prices.len() {
-            let p0 = slice.index_prices[i - 1];
-            let p1 = slice.index_prices[i];
-            
-            if p0 > Decimal::ZERO && p1 > Decimal::ZERO {
-                let token_return = (p1 - p0) / p0;
-                returns.push(token_return);
+/// Group markets by agglomerative (average-linkage) clustering of index-return correlation.
+/// Clusters are merged, most correlated pair first, while their average pairwise correlation is at least the threshold.
+/// Returns a cluster ID per market in slice order, IDs numbered by each cluster's first market.
+pub fn cluster_markets_by_correlation(return_matrix: &ReturnMatrix, correlation_threshold: f64) -> Vec<usize> {
+    let n_markets = return_matrix.n_markets();
+
+    // Pairwise correlation of index returns
+    let correlation = return_matrix.correlation();
+
+    // Merge the most correlated pair of clusters until none reaches the threshold (lowest indices win ties)
+    let mut clusters: Vec<Vec<usize>> = (0..n_markets).map(|i| vec![i]).collect();
+    loop {
+        let mut best: Option<(usize, usize, f64)> = None;
+        for a in 0..clusters.len() {
+            for b in (a + 1)..clusters.len() {
+                let linkage = clusters[a].iter()
+                    .flat_map(|&i| clusters[b].iter().map(move |&j| (i, j)))
+                    .map(|(i, j)| correlation[[i, j]])
+                    .sum::<f64>() / (clusters[a].len() * clusters[b].len()) as f64;
+                if linkage >= correlation_threshold && best.is_none_or(|(_, _, best_linkage)| linkage > best_linkage) {
+                    best = Some((a, b, linkage));
+                }
             }
         }
+        let Some((a, b, _)) = best else {
+            break;
+        };
+        let merged = clusters.remove(b);
+        clusters[a].extend(merged);
+    }
 
-        if returns.is_empty() {
-            None
-        } else {
-            Some(returns)
+    let mut cluster_ids = vec![0; n_markets];
+    for (id, cluster) in clusters.iter().enumerate() {
+        for &i in cluster {
+            cluster_ids[i] = id;
         }
     }
+    cluster_ids
+}
 
-/// Calculate sample covariance between two return series
-fn calculate_covariance(returns_x: &[Decimal], returns_y: &[Decimal]) -> Decimal {
-    if returns_x.len() != returns_y.len() || returns_x.len() < 2 {
-        return Decimal::ZERO;
+/// Index returns for each market over the same time steps, resampled onto a common grid
+fn aligned_returns(market_slices: &[MarketStateSlice]) -> Option<Vec<Vec<Decimal>>> {
+    // Markets without pool value carry no PnL exposure to model
+    let has_pool_value = market_slices.iter()
+        .all(|slice| slice.pool_long_collateral_usd + slice.pool_short_collateral_usd - slice.impact_pool_usd > Decimal::ZERO);
+    if !has_pool_value {
+        return None;
     }
 
-    let n = Decimal::from_i64(returns_x.len() as i64).unwrap();
-    let mean_x = returns_x.iter().sum::<Decimal>() / n;
-    let mean_y = returns_y.iter().sum::<Decimal>() / n;
-
-    let covariance = returns_x.iter()
-        .zip(returns_y.iter())
-        .map(|(x, y)| (x - mean_x) * (y - mean_y))
-        .sum::<Decimal>() / (n - Decimal::ONE); // Sample covariance (divide by n-1)
+    let series: Vec<(&[DateTime<Utc>], &[Decimal])> = market_slices.iter()
+        .map(|slice| (slice.index_token_timestamps.as_slice(), slice.index_prices.as_slice()))
+        .collect();
+    let returns_matrix = return_calculation_utils::aligned_returns(
+        &series,
+        Duration::minutes(RETURN_RESAMPLE_INTERVAL_MINUTES),
+        FillMethod::ForwardFill,
+        Duration::minutes(RETURN_RESAMPLE_MAX_GAP_MINUTES),
+    )?;
+
+    if returns_matrix.first().map_or(0, Vec::len) < 2 {
+        return None;
+    }
 
-    covariance
+    Some(returns_matrix)
 }
+
+/// Calculate historical covariance matrix from PnL returns: index return covariance scaled by each market's net OI
+/// exposure (net trader OI as a fraction of pool value), which is what the pool's PnL is exposed to
+fn calculate_historical_covariance(market_slices: &[MarketStateSlice], return_matrix: &ReturnMatrix) -> Array2<Decimal> {
+    let net_oi_exposure: Array1<f64> = market_slices.iter()
+        .map(|slice| {
+            let net_oi = slice.oi_long_via_tokens - slice.oi_short_via_tokens;
+            let pool_value = slice.pool_long_collateral_usd + slice.pool_short_collateral_usd - slice.impact_pool_usd;
+            if pool_value > Decimal::ZERO {
+                (net_oi / pool_value).to_f64().unwrap_or(0.0)
+            } else {
+                0.0
+            }
+        })
+        .collect();
+
+    let exposure_column = net_oi_exposure.view().insert_axis(Axis(1));
+    let exposure_row = net_oi_exposure.view().insert_axis(Axis(0));
+    let cov_matrix = return_matrix.covariance() * &exposure_column * &exposure_row;
+    cov_matrix.mapv(|covariance| Decimal::from_f64(covariance).unwrap_or(Decimal::ZERO))
+}
\ No newline at end of file
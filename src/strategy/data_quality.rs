@@ -0,0 +1,95 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::*;
+
+use super::types::MarketStateSlice;
+use super::strategy_constants::{
+    RETURN_RESAMPLE_INTERVAL_MINUTES,
+    RETURN_RESAMPLE_MAX_GAP_MINUTES,
+    DATA_QUALITY_ANOMALY_MOVE,
+    DATA_QUALITY_ANOMALY_PENALTY,
+    DATA_QUALITY_MIN_SCORE,
+};
+
+/// How far one market's history can be trusted, components in [0, 1]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DataQuality {
+    pub coverage: f64, // Observations over those expected at the collection interval, worst of the market and index series
+    pub gap_fraction: f64, // Share of the history spent in gaps longer than the resampling bridges, worst of both series
+    pub anomaly_fraction: f64, // Share of GM and index price steps that are non-positive or jump past the anomaly threshold
+    pub score: f64, // Combined score the allocator scales returns and variance by, 1 for clean data
+}
+
+impl std::fmt::Display for DataQuality {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:.2} (coverage {:.1}%, gaps {:.1}%, anomalies {:.2}%)",
+            self.score,
+            self.coverage * 100.0,
+            self.gap_fraction * 100.0,
+            self.anomaly_fraction * 100.0
+        )
+    }
+}
+
+/// Score a market's history by coverage, gaps and price anomalies.
+/// score = coverage × (1 − gap fraction) × (1 − penalty × anomaly fraction), floored at DATA_QUALITY_MIN_SCORE.
+/// Long gaps count twice (as missing rows and as gap time), a gappy history is less trustworthy than one
+/// missing the same rows spread out, as the resampling bridges short gaps but not long ones.
+pub fn score_slice(slice: &MarketStateSlice) -> DataQuality {
+    let (market_coverage, market_gaps) = timeline_quality(&slice.timestamps);
+    let (index_coverage, index_gaps) = timeline_quality(&slice.index_token_timestamps);
+    let coverage = market_coverage.min(index_coverage);
+    let gap_fraction = market_gaps.max(index_gaps);
+
+    let (gm_anomalies, gm_steps) = price_anomalies(&slice.gm_prices);
+    let (index_anomalies, index_steps) = price_anomalies(&slice.index_prices);
+    let steps = gm_steps + index_steps;
+    let anomaly_fraction = if steps > 0 { (gm_anomalies + index_anomalies) as f64 / steps as f64 } else { 0.0 };
+
+    let score = coverage
+        * (1.0 - gap_fraction)
+        * (1.0 - DATA_QUALITY_ANOMALY_PENALTY * anomaly_fraction).max(0.0);
+    DataQuality {
+        coverage,
+        gap_fraction,
+        anomaly_fraction,
+        score: score.clamp(DATA_QUALITY_MIN_SCORE, 1.0),
+    }
+}
+
+/// Coverage and gap fraction of a series of observation timestamps over its own span
+fn timeline_quality(timestamps: &[DateTime<Utc>]) -> (f64, f64) {
+    let (Some(first), Some(last)) = (timestamps.first(), timestamps.last()) else {
+        return (0.0, 1.0);
+    };
+    let span_secs = (*last - *first).num_seconds() as f64;
+    if span_secs <= 0.0 {
+        return (0.0, 1.0);
+    }
+    let interval_secs = (RETURN_RESAMPLE_INTERVAL_MINUTES * 60) as f64;
+    let max_gap_secs = (RETURN_RESAMPLE_MAX_GAP_MINUTES * 60) as f64;
+
+    let expected_rows = span_secs / interval_secs + 1.0;
+    let coverage = (timestamps.len() as f64 / expected_rows).min(1.0);
+
+    // Time beyond the first interval of each gap the resampling won't bridge
+    let gap_secs: f64 = timestamps.windows(2)
+        .map(|pair| (pair[1] - pair[0]).num_seconds() as f64)
+        .filter(|step| *step > max_gap_secs)
+        .map(|step| step - interval_secs)
+        .sum();
+    (coverage, (gap_secs / span_secs).min(1.0))
+}
+
+/// Anomalous steps and total steps of a price series
+fn price_anomalies(prices: &[Decimal]) -> (usize, usize) {
+    let anomalies = prices.windows(2)
+        .filter(|pair| {
+            let (previous, current) = (pair[0].to_f64().unwrap_or(0.0), pair[1].to_f64().unwrap_or(0.0));
+            previous <= 0.0 || current <= 0.0 || ((current - previous) / previous).abs() > DATA_QUALITY_ANOMALY_MOVE
+        })
+        .count();
+    (anomalies, prices.len().saturating_sub(1))
+}
@@ -0,0 +1,152 @@
+use eyre::Result;
+use ethers::types::Address;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::*;
+use tracing::{debug, warn, instrument};
+
+use crate::db::db_manager::DbManager;
+use crate::db::models::market_states::MarketCapsModel;
+use crate::gm_token_txs::types::GmDepositRequest;
+use super::strategy_constants::{DEPOSIT_CAPACITY_BUFFER, WEIGHT_DECIMAL_PLACES};
+use super::types::{PortfolioData, PortfolioSnapshot};
+
+/// How much can currently be deposited into a market before a pool amount passes its MAX_POOL_AMOUNT and the
+/// deposit reverts. Deposits are sized pro rata to the pool's USD composition, so the side closest to its cap
+/// (relative to its share of the pool) bounds the whole deposit.
+#[derive(Debug, Clone, Copy)]
+pub struct DepositCapacity {
+    pub remaining_long_amount: Decimal,  // Long tokens the pool can still take
+    pub remaining_short_amount: Decimal, // Short tokens the pool can still take
+    pub max_usd: Decimal,                // Depositable value in USD
+}
+
+impl DepositCapacity {
+    /// None when the recorded state has no pool composition to size a deposit with
+    pub fn from_market_caps(caps: &MarketCapsModel) -> Option<Self> {
+        let pool_long_amount = caps.pool_long_amount?;
+        let pool_short_amount = caps.pool_short_amount?;
+        let pool_long_usd = caps.pool_long_token_usd?;
+        let pool_short_usd = caps.pool_short_token_usd?;
+        let pool_usd = pool_long_usd + pool_short_usd;
+        if pool_usd <= Decimal::ZERO {
+            return None;
+        }
+
+        let remaining_long_amount = (caps.max_pool_amount_long - pool_long_amount).max(Decimal::ZERO);
+        let remaining_short_amount = (caps.max_pool_amount_short - pool_short_amount).max(Decimal::ZERO);
+        let max_usd = side_capacity_usd(remaining_long_amount, pool_long_amount, pool_long_usd, pool_usd)
+            .min(side_capacity_usd(remaining_short_amount, pool_short_amount, pool_short_usd, pool_usd));
+        Some(Self {
+            remaining_long_amount,
+            remaining_short_amount,
+            max_usd,
+        })
+    }
+
+    /// Depositable USD after the planning buffer
+    pub fn buffered_usd(&self) -> Decimal {
+        self.max_usd * deposit_buffer()
+    }
+}
+
+/// Deposit capacity of a market from its latest state recorded with pool caps.
+/// None when no caps have been recorded for the market.
+#[instrument(skip(db_manager))]
+pub async fn get_deposit_capacity(db_manager: &DbManager, market: Address) -> Result<Option<DepositCapacity>> {
+    let Some(market_id) = db_manager.market_id_map.get(&market) else {
+        return Ok(None);
+    };
+    let capacity = db_manager.get_latest_market_caps().await?
+        .get(market_id)
+        .and_then(DepositCapacity::from_market_caps);
+    debug!(market = ?market, capacity = ?capacity, "Deposit capacity computed");
+    Ok(capacity)
+}
+
+/// Cap target weights so no planned deposit exceeds the market's remaining pool capacity (less the planning
+/// buffer); a deposit above it reverts at execution and only burns the execution fee. The freed weight is left
+/// undeployed. Each cap is recorded in the plan notes. Returns the number of markets capped.
+#[instrument(skip(db_manager, portfolio_data, current_portfolio), fields(on_close = true))]
+pub async fn apply_deposit_capacity_cap(
+    db_manager: &DbManager,
+    portfolio_data: &mut PortfolioData,
+    current_portfolio: &PortfolioSnapshot,
+) -> Result<usize> {
+    let total_value = current_portfolio.total_value_usd;
+    if total_value <= Decimal::ZERO {
+        return Ok(0);
+    }
+    let latest_caps = db_manager.get_latest_market_caps().await?;
+
+    let mut capped = 0;
+    for i in 0..portfolio_data.market_addresses.len() {
+        let address = portfolio_data.market_addresses[i];
+        let current_weight = current_portfolio.weights.get(&address).copied().unwrap_or(Decimal::ZERO);
+        let target_weight = portfolio_data.weights[i];
+        if target_weight <= current_weight {
+            continue;
+        }
+        let Some(capacity) = db_manager.market_id_map.get(&address)
+            .and_then(|id| latest_caps.get(id))
+            .and_then(DepositCapacity::from_market_caps) else {
+            continue;
+        };
+
+        let deposit_usd = (target_weight - current_weight) * total_value;
+        let max_deposit_usd = capacity.buffered_usd();
+        if deposit_usd <= max_deposit_usd {
+            continue;
+        }
+
+        let capped_weight = (current_weight + max_deposit_usd / total_value).round_dp(WEIGHT_DECIMAL_PLACES);
+        portfolio_data.weights[i] = capped_weight;
+        capped += 1;
+        let note = format!(
+            "Deposit capped by GMX pool caps: {:.2} USD planned, {:.2} USD depositable, target weight {:.2}% -> {:.2}%",
+            deposit_usd,
+            max_deposit_usd,
+            target_weight * Decimal::from(100),
+            capped_weight * Decimal::from(100)
+        );
+        warn!(market = %portfolio_data.display_names[i], "{}", note);
+        portfolio_data.add_note(address, note);
+    }
+    Ok(capped)
+}
+
+/// Refuse a deposit that would take a pool amount past its cap, it would revert at execution.
+/// Sides sent as another token (swapped into the pool token on the way in) are not checked, their amount is in
+/// the token sent.
+pub async fn ensure_deposit_within_capacity(db_manager: &DbManager, request: &GmDepositRequest) -> Result<()> {
+    let Some(capacity) = get_deposit_capacity(db_manager, request.market).await? else {
+        return Ok(());
+    };
+    let sides = [
+        ("long", request.initial_long_token.is_none(), request.long_amount, capacity.remaining_long_amount),
+        ("short", request.initial_short_token.is_none(), request.short_amount, capacity.remaining_short_amount),
+    ];
+    for (side, is_pool_token, amount, remaining) in sides {
+        if is_pool_token && amount > remaining {
+            return Err(eyre::eyre!(
+                "Deposit of {} {} tokens into market {:?} exceeds the {} left under its max pool amount",
+                amount, side, request.market, remaining.round_dp(6)
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Deposit value at which a side's pool amount reaches its cap, given the side takes its USD share of the deposit.
+/// Unconstrained (MAX) when the side holds no value, a pro rata deposit adds nothing to it.
+fn side_capacity_usd(remaining_amount: Decimal, pool_amount: Decimal, pool_side_usd: Decimal, pool_usd: Decimal) -> Decimal {
+    if pool_side_usd <= Decimal::ZERO || pool_amount <= Decimal::ZERO {
+        return Decimal::MAX;
+    }
+    let token_price = pool_side_usd / pool_amount;
+    let side_share = pool_side_usd / pool_usd;
+    remaining_amount.checked_mul(token_price).map(|usd| usd / side_share).unwrap_or(Decimal::MAX)
+}
+
+fn deposit_buffer() -> Decimal {
+    Decimal::from_f64(DEPOSIT_CAPACITY_BUFFER).unwrap_or(Decimal::ONE)
+}
@@ -1,27 +1,57 @@
-use tracing::{instrument, debug, info, error};
+use tracing::{instrument, debug, info, warn, error};
 use eyre::Result;
 use std::sync::Arc;
+use std::collections::HashMap;
 use rust_decimal::Decimal;
 use rust_decimal::prelude::*;
 use ndarray::Array1;
 
 use super::{
-    fee_model, allocator, covariance,
+    allocator::{self, AllocatorMode},
+    covariance, feasibility, data_quality, pnl_model, liquidation_risk, external_signals, volatility,
+    pnl_model::ReturnEnsemble,
+    market_universe::MarketUniverse,
     types::{
         MarketStateSlice, 
         PortfolioData,
+        PortfolioSnapshot,
+        HOURS_PER_YEAR,
     },
 };
+use crate::config::dynamic::DynamicParams;
 use crate::db::db_manager::DbManager;
-use crate::hedging::dydx_client::DydxClient;
+use crate::hedging::hedge_utils::STABLE_COINS;
+use crate::hedging::hedge_venue::HedgeMarket;
+use super::strategy_constants::{
+    FUNDING_RATE_LOOKBACK_HOURS,
+    INCENTIVE_STALENESS_HOURS,
+    LIQUIDATION_LOOKBACK_HOURS,
+    CLUSTER_CORRELATION_THRESHOLD,
+};
 
-/// Entry point for the strategy engine — run on each data refresh
-#[instrument(name = "strategy_engine", skip(db_manager, dydx_client))]
-pub async fn run_strategy_engine(db_manager: Arc<DbManager>, dydx_client: Arc<DydxClient>) -> Result<PortfolioData> {
+/// Entry point for the strategy engine — run on each data refresh. With a market universe only its markets are
+/// allocated, and `current_portfolio` should be scoped to the universe's bucket.
+#[instrument(name = "strategy_engine", skip(db_manager, hedge_markets, ensemble, params, universe), fields(universe = universe.map(|u| u.name.as_str())))]
+pub async fn run_strategy_engine(
+    db_manager: Arc<DbManager>,
+    hedge_markets: &HashMap<String, HedgeMarket>,
+    ensemble: &ReturnEnsemble,
+    params: &DynamicParams,
+    current_portfolio: Option<&PortfolioSnapshot>,
+    universe: Option<&MarketUniverse>,
+) -> Result<PortfolioData> {
     info!("Starting strategy engine...");
 
     // Fetch all data from DB
-    let market_slices = fetch_market_state_slices(db_manager).await?;
+    let now = db_manager.clock.now();
+    let market_slices = fetch_market_state_slices(db_manager.clone()).await?;
+
+    // Expected return overrides and vetoes posted through the signal sink, only read when a signer is trusted
+    let active_signals = if ensemble.external_signal_trust().is_empty() {
+        HashMap::new()
+    } else {
+        db_manager.get_active_external_signals().await?
+    };
 
     if market_slices.is_empty() {
         error!("No market slices fetched from database");
@@ -35,6 +65,19 @@ pub async fn run_strategy_engine(db_manager: Arc<DbManager>, dydx_client: Arc<Dy
         .filter(|slice| {
 
             let name = &slice.display_name;
+            // Filter out slices outside the run's market universe
+            if let Some(universe) = universe.filter(|universe| !universe.contains(&slice.market_address)) {
+                filtered_markets.push_str(&format!("{} --> not in market universe {}\n", name, universe.name));
+                return false;
+            }
+            // Filter out slices vetoed by a trusted external signal
+            if let Some(signals) = active_signals.get(&slice.market_address) {
+                let vetoing = external_signals::vetoing_signers(signals, ensemble.external_signal_trust());
+                if !vetoing.is_empty() {
+                    filtered_markets.push_str(&format!("{} --> vetoed by external signal ({})\n", name, vetoing.join(", ")));
+                    return false;
+                }
+            }
             // Filter out slices without at least one day of market observations
             if slice.timestamps.len() < 288 {
                 filtered_markets.push_str(&format!("{} --> insufficient market timestamps ({} < 288)\n", name, slice.timestamps.len()));
@@ -46,22 +89,22 @@ pub async fn run_strategy_engine(db_manager: Arc<DbManager>, dydx_client: Arc<Dy
                 return false;
             }
             // Filter out slices where the oldest market timestamp is too recent 
-            if !slice.timestamps.first().map_or(false, |t| *t < (chrono::Utc::now() - chrono::Duration::days(1))) {
+            if !slice.timestamps.first().map_or(false, |t| *t < (now - chrono::Duration::days(1))) {
                 filtered_markets.push_str(&format!("{} --> oldest market timestamp too recent ({:?})\n", name, slice.timestamps.first()));
                 return false;
             }
             // Filter out slices where the oldest index token timestamp is too recent
-            if !slice.index_token_timestamps.first().map_or(false, |t| *t < (chrono::Utc::now() - chrono::Duration::days(1))) {
+            if !slice.index_token_timestamps.first().map_or(false, |t| *t < (now - chrono::Duration::days(1))) {
                 filtered_markets.push_str(&format!("{} --> oldest index token timestamp too recent ({:?})\n", name, slice.index_token_timestamps.first()));
                 return false;
             }
             // Filter out slices where the newest market timestamp is too old
-            if !slice.timestamps.last().map_or(false, |t| *t > (chrono::Utc::now() - chrono::Duration::hours(1))) {
+            if !slice.timestamps.last().map_or(false, |t| *t > (now - chrono::Duration::hours(1))) {
                 filtered_markets.push_str(&format!("{} --> newest market timestamp too old ({:?})\n", name, slice.timestamps.last()));
                 return false;
             }
             // Filter out slices where the newest index token timestamp is too old
-            if !slice.index_token_timestamps.last().map_or(false, |t| *t > (chrono::Utc::now() - chrono::Duration::hours(1))) {
+            if !slice.index_token_timestamps.last().map_or(false, |t| *t > (now - chrono::Duration::hours(1))) {
                 filtered_markets.push_str(&format!("{} --> newest index token timestamp too old ({:?})\n", name, slice.index_token_timestamps.last()));
                 return false;
             }
@@ -82,8 +125,15 @@ pub async fn run_strategy_engine(db_manager: Arc<DbManager>, dydx_client: Arc<Dy
         debug!("Filtered out markets: {} available\nRemoved markets:\n{}", market_slices.len(), filtered_markets);
     }
 
+    // Align index returns once for the covariance and the clustering, rows in the same ordering as market_slices
+    let Some(return_matrix) = covariance::ReturnMatrix::from_slices(&market_slices) else {
+        error!("Failed to calculate covariance matrix, no aligned index returns");
+        return Err(eyre::eyre!("Failed to calculate covariance matrix"));
+    };
+    debug!(markets = return_matrix.n_markets(), steps = return_matrix.n_steps(), "Return matrix built");
+
     // Calculate covariance matrix using the same ordering as market_slices
-    let covariance_matrix = match covariance::calculate_covariance_matrix(&market_slices) {
+    let covariance_matrix = match covariance::calculate_covariance_matrix(&market_slices, &return_matrix) {
         Some(matrix) => matrix,
         None => {
             error!("Failed to calculate covariance matrix");
@@ -92,36 +142,100 @@ pub async fn run_strategy_engine(db_manager: Arc<DbManager>, dydx_client: Arc<Dy
     };
     debug!("Covariance matrix calculated");
 
+    // Scale each market's risk to the holding period from variance measured at several horizons (and implied
+    // volatility for the majors when enabled) rather than from 5 minute returns treated as independent
+    let implied_volatilities = match params.implied_vol_weight {
+        Some(_) => volatility::fetch_implied_volatilities(now).await.unwrap_or_else(|e| {
+            warn!(error = ?e, "Failed to fetch implied volatilities, using realized volatility");
+            HashMap::new()
+        }),
+        None => HashMap::new(),
+    };
+    let term_structures = volatility::term_structures(&market_slices, &return_matrix, &implied_volatilities, params.implied_vol_weight);
+    for (slice, term_structure) in market_slices.iter().zip(&term_structures) {
+        debug!(
+            market = %slice.display_name,
+            horizon_variances = ?term_structure.horizon_variances,
+            implied_volatility = ?term_structure.implied_volatility,
+            variance_ratio = term_structure.variance_ratio(params.risk_horizon_hours),
+            "Volatility term structure"
+        );
+    }
+    let covariance_matrix = volatility::apply_horizon_scaling(&covariance_matrix, &term_structures, params.risk_horizon_hours);
+
+    // Group markets moving together (e.g. ETH, LSTs and ETH-beta memecoins) so no single factor dominates the portfolio
+    let cluster_ids = covariance::cluster_markets_by_correlation(&return_matrix, CLUSTER_CORRELATION_THRESHOLD);
+    log_clusters(&market_slices, &cluster_ids);
+
+    // Score each market's history so sparse, gappy or anomalous data gets less confidence than clean data
+    let market_quality: Vec<data_quality::DataQuality> = market_slices.iter().map(data_quality::score_slice).collect();
+    for (slice, quality) in market_slices.iter().zip(market_quality.iter()) {
+        if quality.score < 1.0 {
+            debug!(market = %slice.display_name, data_quality = %quality, "Market data quality below full confidence");
+        }
+    }
+
     let n_markets = market_slices.len();
     let mut market_addresses = Vec::with_capacity(n_markets);
     let mut display_names = Vec::with_capacity(n_markets);
+    let mut input_digests = Vec::with_capacity(n_markets);
     let mut expected_returns = Array1::zeros(n_markets);
+    let mut fully_hedged = Vec::with_capacity(n_markets);
+
+    // Mean recorded funding over the lookback window models hedge carry better than the single next funding rate
+    let funding_since = now - chrono::Duration::hours(FUNDING_RATE_LOOKBACK_HOURS);
+    let historical_funding_rates = db_manager.get_average_funding_rates_since(funding_since).await?;
+
+    // Pool incentive emissions (e.g. ARB) add to the hourly yield on top of trading fees
+    let incentive_rates = db_manager.get_market_incentive_rates_since(now - chrono::Duration::hours(INCENTIVE_STALENESS_HOURS)).await?;
 
-    let token_hedgeinfo_map = dydx_client.get_token_hedgeinfo_map().await?;
+    // Expected LP return of each market from the ensemble of fee, borrowing, trader PnL and momentum signals, or in
+    // hedged carry mode the fee and borrowing carry alone, the directional signals being hedged away
+    let expected_lp_returns = match params.allocator_mode {
+        AllocatorMode::MeanVariance => ensemble.expected_returns(&market_slices),
+        AllocatorMode::HedgedCarry => pnl_model::carry_returns(&market_slices),
+    };
+    let hedgeable = |symbol: &str| STABLE_COINS.contains(&symbol) || hedge_markets.contains_key(symbol);
 
     // Run models on each market sequentially to respect rate limits
     for (i, slice) in market_slices.iter().enumerate() {
         market_addresses.push(slice.market_address);
         display_names.push(slice.display_name.clone());
+        input_digests.push(slice.input_digest(covariance_matrix[[i, i]].sqrt().unwrap_or(Decimal::ZERO), market_quality[i].score));
         
-        let fee_return = fee_model::simulate_fee_return(&slice).unwrap_or(Decimal::ZERO);
+        let pool_value_usd = slice.pool_long_collateral_usd + slice.pool_short_collateral_usd;
+        let incentive_return = match incentive_rates.get(&slice.market_address) {
+            Some(rate) if pool_value_usd > Decimal::ZERO => *rate / pool_value_usd,
+            _ => Decimal::ZERO,
+        };
+        if incentive_return > Decimal::ZERO {
+            debug!(
+                market = %slice.display_name,
+                incentive_return = %incentive_return,
+                incentive_return_annualized = %((incentive_return * Decimal::from_f64(24.0 * 365.0).unwrap())),
+                "Added pool incentives to fee return"
+            );
+        }
+        let fee_return = expected_lp_returns[i] + incentive_return;
         
-        let (long_token_symbol, short_token_symbol) = get_collateral_tokens_from_display_name(slice.display_name.clone())?;
-        let long_token_hedgeinfo_opt = token_hedgeinfo_map.get(&long_token_symbol);
-        let short_token_hedgeinfo_opt = token_hedgeinfo_map.get(&short_token_symbol);
-        if let Some(Some(long_token_hedgeinfo)) = long_token_hedgeinfo_opt {
-            let exposed_capital_frac = if short_token_hedgeinfo_opt.unwrap_or(&None).is_some() {
+        let (long_token_symbol, short_token_symbol) = slice.descriptor.collateral_symbols();
+        fully_hedged.push(hedgeable(long_token_symbol) && hedgeable(short_token_symbol));
+        if let Some(long_token_hedge) = hedge_markets.get(long_token_symbol) {
+            let exposed_capital_frac = if hedge_markets.contains_key(short_token_symbol) {
                 Decimal::ONE // short token is stablecoin
             } else {
                 Decimal::from_str("0.5").unwrap() // short token is not stablecoin
             };
-            let funding_rate = long_token_hedgeinfo.0;
-            let leverage = long_token_hedgeinfo.1;
+            let funding_rate = historical_funding_rates.get(&long_token_hedge.ticker)
+                .copied()
+                .unwrap_or(long_token_hedge.funding_rate);
+            let leverage = long_token_hedge.max_leverage;
             let funding_cost = exposed_capital_frac * (- funding_rate); // funding rate > 0 ==> longs pay shorts ==> income for our short position
             let opportunity_cost = (exposed_capital_frac / leverage) * fee_return;
             let total_return = fee_return - funding_cost - opportunity_cost;
             debug!(
                 market = %slice.display_name,
+                hedge_venue = long_token_hedge.venue,
                 fee_return = %fee_return,
                 fee_return_annualized = %((fee_return * Decimal::from_f64(24.0 * 365.0).unwrap())),
                 exposed_capital_frac = %exposed_capital_frac,
@@ -137,18 +251,118 @@ pub async fn run_strategy_engine(db_manager: Arc<DbManager>, dydx_client: Arc<Dy
             );
             expected_returns[i] = total_return;
         } else {
-            expected_returns[i] = fee_return; // No hedge info for long token indicates stablecoin or unsupported by dYdX
+            expected_returns[i] = fee_return; // No hedge market for long token indicates stablecoin or unsupported by every hedge venue
             continue;
         }
     }
     debug!("Market returns calculated");
 
+    // Shrink returns toward zero and inflate variance by data quality before allocating (and Kelly sizing)
+    let quality_scores: Vec<f64> = market_quality.iter().map(|quality| quality.score).collect();
+    let (mut expected_returns, covariance_matrix) = allocator::apply_data_quality(&expected_returns, &covariance_matrix, &quality_scores);
+
+    // Blend in trusted external expected returns, after the data quality shrinkage which only applies to our own estimates
+    let mut external_notes = Vec::new();
+    for (i, slice) in market_slices.iter().enumerate() {
+        let Some(signals) = active_signals.get(&slice.market_address) else {
+            continue;
+        };
+        let (blended_return, external_weight) = external_signals::blend_expected_return(expected_returns[i], signals, ensemble.external_signal_trust());
+        if external_weight > Decimal::ZERO {
+            debug!(
+                market = %slice.display_name,
+                internal_return = %expected_returns[i],
+                blended_return = %blended_return,
+                external_weight = %external_weight,
+                "Blended external expected return"
+            );
+            external_notes.push((slice.market_address, format!(
+                "Expected return blended with external signals {:.2}% -> {:.2}% APR ({:.0}% external weight)",
+                expected_returns[i] * Decimal::from(HOURS_PER_YEAR * 100),
+                blended_return * Decimal::from(HOURS_PER_YEAR * 100),
+                external_weight * Decimal::from(100)
+            )));
+            expected_returns[i] = blended_return;
+        }
+    }
+
+    // Inflate the variance of markets prone to liquidation cascades, their return history understates the tail risk
+    let liquidation_totals = db_manager.get_liquidation_totals_since(now - chrono::Duration::hours(LIQUIDATION_LOOKBACK_HOURS)).await?;
+    let liquidation_intensities = liquidation_risk::liquidation_intensities(&market_slices, &liquidation_totals);
+    for (slice, intensity) in market_slices.iter().zip(liquidation_intensities.iter()) {
+        if intensity.liquidation_count > 0 {
+            debug!(market = %slice.display_name, liquidations = %intensity, variance_uplift = %intensity.variance_uplift(), "Liquidation variance uplift");
+        }
+    }
+    let covariance_matrix = liquidation_risk::apply_liquidation_uplift(&covariance_matrix, &liquidation_intensities);
+
+    // Relax conflicting position, cluster and trade size constraints up front instead of letting the projection produce corner solutions
+    let (constraints, relaxed_constraints) = feasibility::check_feasibility(
+        params,
+        &cluster_ids,
+        current_portfolio.map(|snapshot| snapshot.total_value_usd),
+    );
+
     // Create PortfolioData with consistent ordering
-    let weights = allocator::maximize_sharpe(expected_returns.clone(), covariance_matrix.clone())?;
+    // With current holdings known, penalize turnover away from them so small return differences don't cause churn
+    let weights = match (params.allocator_mode, current_portfolio) {
+        (AllocatorMode::HedgedCarry, _) => allocator::maximize_carry_to_risk(
+            expected_returns.clone(),
+            covariance_matrix.clone(),
+            &fully_hedged,
+            &cluster_ids,
+            &constraints,
+        )?,
+        (AllocatorMode::MeanVariance, Some(snapshot)) => allocator::maximize_utility_with_turnover(
+            expected_returns.clone(),
+            covariance_matrix.clone(),
+            snapshot.weights_for(&market_addresses),
+            &cluster_ids,
+            &constraints,
+            params.allocator_risk_aversion,
+            params.allocator_turnover_penalty,
+        )?,
+        (AllocatorMode::MeanVariance, None) => allocator::maximize_sharpe(expected_returns.clone(), covariance_matrix.clone(), &cluster_ids, &constraints)?,
+    };
 
     debug!("Optimal portfolio weights calculated");
 
-    let portfolio_data = PortfolioData::new(market_addresses, display_names, expected_returns, covariance_matrix, weights);
+    // In fractional-Kelly mode, leave capital undeployed where the edge is small relative to its variance
+    let kelly_weights = params.allocator_kelly_fraction.map(|kelly_fraction| allocator::apply_kelly_scaling(
+        &weights,
+        &expected_returns,
+        &covariance_matrix,
+        kelly_fraction,
+        constraints.min_position_weight,
+    ));
+
+    let mut portfolio_data = PortfolioData::new(market_addresses, display_names, expected_returns, covariance_matrix, weights.clone(), input_digests, constraints, relaxed_constraints);
+    portfolio_data.index_volatilities = volatility::by_base_asset(&market_slices, &term_structures);
+    portfolio_data.universe = universe.map(|universe| universe.name.clone());
+    for (market, note) in external_notes {
+        portfolio_data.add_note(market, note);
+    }
+
+    if params.allocator_mode == AllocatorMode::HedgedCarry {
+        for i in (0..n_markets).filter(|&i| !fully_hedged[i]) {
+            portfolio_data.add_note(portfolio_data.market_addresses[i], "Excluded in hedged carry mode, collateral can't be fully hedged".to_string());
+        }
+        info!(eligible_markets = fully_hedged.iter().filter(|hedged| **hedged).count(), "Hedged carry allocation applied");
+    }
+
+    if let Some(kelly_weights) = kelly_weights {
+        for i in 0..n_markets {
+            if kelly_weights[i] < weights[i] {
+                portfolio_data.add_note(portfolio_data.market_addresses[i], format!(
+                    "Kelly sizing capped weight {:.2}% -> {:.2}%",
+                    weights[i] * Decimal::from(100),
+                    kelly_weights[i] * Decimal::from(100)
+                ));
+            }
+        }
+        info!(deployed_weight = %kelly_weights.sum(), "Fractional-Kelly sizing applied");
+        portfolio_data.weights = kelly_weights;
+    }
 
     Ok(portfolio_data)
 }
@@ -156,8 +370,8 @@ pub async fn run_strategy_engine(db_manager: Arc<DbManager>, dydx_client: Arc<Dy
 /// Fetch market state slices from the database
 #[instrument(name = "fetch_market_state_slices", skip(db_manager))]
 async fn fetch_market_state_slices(db_manager: Arc<DbManager>) -> Result<Vec<MarketStateSlice>> {
-    let start = chrono::Utc::now() - chrono::Duration::days(30); // 30 days for now, to be adjusted later
-    let end = chrono::Utc::now();
+    let end = db_manager.clock.now();
+    let start = end - chrono::Duration::days(30); // 30 days for now, to be adjusted later
     
     let slices = db_manager.get_market_state_slices(start, end).await?;
     Ok(slices)
@@ -165,11 +379,18 @@ async fn fetch_market_state_slices(db_manager: Arc<DbManager>) -> Result<Vec<Mar
 
 // --- HELPERS ---
 
-fn get_collateral_tokens_from_display_name(display_name: String) -> Result<(String, String)> {
-    let collateral_tokens_start_idx = display_name.find('[')
-        .ok_or_else(|| eyre::eyre!("Invalid display name format: {}", display_name))? + 1;
-    let collateral_tokens_substr = &display_name[collateral_tokens_start_idx..display_name.len()-1];
-    let (long_token, short_token) = collateral_tokens_substr.split_once('-')
-        .ok_or_else(|| eyre::eyre!("Invalid collateral token format in display name: {}", display_name))?;
-    Ok((long_token[..long_token.len()-1].to_string(), short_token[1..].to_string()))
+fn log_clusters(market_slices: &[MarketStateSlice], cluster_ids: &[usize]) {
+    let n_clusters = cluster_ids.iter().max().map_or(0, |max_id| max_id + 1);
+    let cluster_summary = (0..n_clusters)
+        .map(|cluster| {
+            let names = market_slices.iter()
+                .zip(cluster_ids)
+                .filter(|(_, id)| **id == cluster)
+                .map(|(slice, _)| slice.display_name.as_str())
+                .collect::<Vec<_>>();
+            format!("Cluster {}: {}", cluster, names.join(", "))
+        })
+        .collect::<Vec<_>>()
+        .join("\n  ");
+    debug!(cluster_count = n_clusters, "Market correlation clusters:\n  {}", cluster_summary);
 }
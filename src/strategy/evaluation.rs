@@ -0,0 +1,227 @@
+use eyre::Result;
+use tracing::{debug, info, warn, instrument};
+use std::sync::Arc;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::*;
+
+use crate::db::db_manager::DbManager;
+use crate::db::models::strategy_runs::{NewReturnModelMetricsModel, ReturnModelMetricsModel, StrategyRunModel};
+use crate::reporting_currency::ReportingCurrency;
+use super::strategy_constants::RETURN_EVALUATION_HORIZON_HOURS;
+use super::types::HOURS_PER_YEAR;
+
+/// Compare the expected returns of every strategy run old enough to have a full holding horizon
+/// against the realized GM token returns over that horizon (measured in the reporting currency),
+/// and store the prediction error metrics along with the portfolio's alpha over holding the base
+/// stablecoin in Aave at the run's risk-free rate. Returns the number of runs evaluated.
+#[instrument(name = "evaluate_expected_returns", skip(db_manager, reporting_currency), fields(reporting_currency = %reporting_currency.symbol, on_close = true))]
+pub async fn evaluate_expected_returns(db_manager: Arc<DbManager>, reporting_currency: &ReportingCurrency) -> Result<usize> {
+    let horizon = chrono::Duration::hours(RETURN_EVALUATION_HORIZON_HOURS);
+    let runs = db_manager.get_unevaluated_strategy_runs(db_manager.clock.now() - horizon).await?;
+    let mut evaluated = 0;
+
+    for run in runs {
+        let run_markets = db_manager.get_strategy_run_markets(run.id).await?;
+
+        // Pair (expected, realized) returns in bps over the horizon
+        let mut predicted = Vec::with_capacity(run_markets.len());
+        let mut realized = Vec::with_capacity(run_markets.len());
+        let mut weights = Vec::with_capacity(run_markets.len());
+        for market in &run_markets {
+            let start = db_manager.get_gm_price_at(market.market_id, run.created_at).await?;
+            let end = db_manager.get_gm_price_at(market.market_id, run.created_at + horizon).await?;
+            let (Some((start_ts, start_price)), Some((end_ts, end_price))) = (start, end) else {
+                debug!(run_id = run.id, market_id = market.market_id, "Missing GM prices for horizon, skipping market");
+                continue;
+            };
+            if end_ts <= start_ts || start_price <= Decimal::ZERO {
+                debug!(run_id = run.id, market_id = market.market_id, "No GM price observed within horizon, skipping market");
+                continue;
+            }
+            let (start_currency_price, end_currency_price) = match (
+                reporting_currency.usd_price_at(&db_manager, start_ts).await,
+                reporting_currency.usd_price_at(&db_manager, end_ts).await,
+            ) {
+                (Ok(start), Ok(end)) => (start, end),
+                (Err(e), _) | (_, Err(e)) => {
+                    debug!(run_id = run.id, market_id = market.market_id, error = ?e, "Missing reporting currency price, skipping market");
+                    continue;
+                }
+            };
+            let start_value = start_price / start_currency_price;
+            let end_value = end_price / end_currency_price;
+            predicted.push(market.expected_return_bps * Decimal::from(RETURN_EVALUATION_HORIZON_HOURS));
+            realized.push((end_value / start_value - Decimal::ONE) * Decimal::from(10000));
+            weights.push(market.target_weight);
+        }
+
+        if predicted.is_empty() {
+            warn!(run_id = run.id, "No markets with realized returns, skipping run evaluation");
+            continue;
+        }
+
+        // Target-weighted realized return of the evaluated markets against the stablecoin benchmark
+        let total_weight: Decimal = weights.iter().sum();
+        let portfolio_return_bps = (total_weight > Decimal::ZERO)
+            .then(|| weights.iter().zip(&realized).map(|(w, r)| w * r).sum::<Decimal>() / total_weight);
+        let benchmark_return_bps = benchmark_return_bps(&db_manager, reporting_currency, &run, horizon).await;
+
+        let mut metrics = compute_metrics(run.id, &predicted, &realized, &reporting_currency.symbol);
+        metrics.portfolio_return_bps = portfolio_return_bps;
+        metrics.benchmark_return_bps = benchmark_return_bps;
+        metrics.alpha_bps = portfolio_return_bps.zip(benchmark_return_bps).map(|(portfolio, benchmark)| portfolio - benchmark);
+        debug!(
+            run_id = run.id,
+            market_count = metrics.market_count,
+            bias_bps = %metrics.bias_bps,
+            mae_bps = %metrics.mae_bps,
+            rank_correlation = ?metrics.rank_correlation,
+            alpha_bps = ?metrics.alpha_bps,
+            "Strategy run evaluated"
+        );
+        db_manager.insert_return_model_metrics(&metrics).await?;
+        evaluated += 1;
+    }
+
+    info!(evaluated = evaluated, "Expected return evaluation completed");
+    Ok(evaluated)
+}
+
+/// Log a calibration report of the given metrics (newest first) so model drift is visible
+pub fn log_metrics_report(metrics: &[ReturnModelMetricsModel]) {
+    if metrics.is_empty() {
+        info!("No evaluated strategy runs to report");
+        return;
+    }
+
+    let run_summary = metrics.iter()
+        .map(|m| format!(
+            "Run #{}: Markets={}, Bias={:.2}bps, MAE={:.2}bps, RankCorr={}, Alpha={}",
+            m.run_id,
+            m.market_count,
+            m.bias_bps,
+            m.mae_bps,
+            m.rank_correlation.map(|r| format!("{:.3}", r)).unwrap_or_else(|| "n/a".to_string()),
+            m.alpha_bps.map(|a| format!("{:.2}bps", a)).unwrap_or_else(|| "n/a".to_string())
+        ))
+        .collect::<Vec<_>>()
+        .join("\n  ");
+
+    let n = Decimal::from(metrics.len());
+    let avg_bias = metrics.iter().map(|m| m.bias_bps).sum::<Decimal>() / n;
+    let avg_mae = metrics.iter().map(|m| m.mae_bps).sum::<Decimal>() / n;
+    let rank_correlations: Vec<Decimal> = metrics.iter().filter_map(|m| m.rank_correlation).collect();
+    let avg_rank_correlation = if rank_correlations.is_empty() {
+        "n/a".to_string()
+    } else {
+        format!("{:.3}", rank_correlations.iter().sum::<Decimal>() / Decimal::from(rank_correlations.len()))
+    };
+    let alphas: Vec<Decimal> = metrics.iter().filter_map(|m| m.alpha_bps).collect();
+    let avg_alpha = if alphas.is_empty() {
+        "n/a".to_string()
+    } else {
+        format!("{:.2}bps", alphas.iter().sum::<Decimal>() / Decimal::from(alphas.len()))
+    };
+
+    info!(
+        "Expected Return Model Calibration (newest first, {}h horizon, realized in {}):\n  {}\n\nSummary over {} runs:\n  Avg Bias: {:.2}bps\n  Avg MAE: {:.2}bps\n  Avg Rank Correlation: {}\n  Avg Alpha vs Benchmark: {}",
+        metrics[0].horizon_hours,
+        metrics[0].reporting_currency,
+        run_summary,
+        metrics.len(),
+        avg_bias,
+        avg_mae,
+        avg_rank_correlation,
+        avg_alpha
+    );
+}
+
+// --- HELPERS ---
+
+/// Bias (mean predicted - realized), mean absolute error and Spearman rank correlation
+fn compute_metrics(run_id: i32, predicted: &[Decimal], realized: &[Decimal], reporting_currency: &str) -> NewReturnModelMetricsModel {
+    let n = Decimal::from(predicted.len());
+    let errors: Vec<Decimal> = predicted.iter().zip(realized).map(|(p, r)| p - r).collect();
+    let bias_bps = errors.iter().sum::<Decimal>() / n;
+    let mae_bps = errors.iter().map(|e| e.abs()).sum::<Decimal>() / n;
+
+    let predicted_f64: Vec<f64> = predicted.iter().map(|d| d.to_f64().unwrap_or(0.0)).collect();
+    let realized_f64: Vec<f64> = realized.iter().map(|d| d.to_f64().unwrap_or(0.0)).collect();
+    let rank_correlation = spearman_correlation(&predicted_f64, &realized_f64).and_then(Decimal::from_f64);
+
+    NewReturnModelMetricsModel {
+        run_id,
+        horizon_hours: RETURN_EVALUATION_HORIZON_HOURS as i32,
+        market_count: predicted.len() as i32,
+        bias_bps,
+        mae_bps,
+        rank_correlation,
+        reporting_currency: reporting_currency.to_string(),
+        portfolio_return_bps: None,
+        benchmark_return_bps: None,
+        alpha_bps: None,
+    }
+}
+
+/// Return in bps of holding the base stablecoin in Aave at the run's risk-free rate over the horizon, in the reporting currency.
+/// None for runs recorded before the rate was stored or when reporting currency prices are missing.
+async fn benchmark_return_bps(
+    db_manager: &DbManager,
+    reporting_currency: &ReportingCurrency,
+    run: &StrategyRunModel,
+    horizon: chrono::Duration,
+) -> Option<Decimal> {
+    let risk_free_rate_apr = run.risk_free_rate_apr?;
+    let start_currency_price = reporting_currency.usd_price_at(db_manager, run.created_at).await.ok()?;
+    let end_currency_price = reporting_currency.usd_price_at(db_manager, run.created_at + horizon).await.ok()?;
+    if end_currency_price <= Decimal::ZERO {
+        return None;
+    }
+    let usd_growth = Decimal::ONE + risk_free_rate_apr * Decimal::from(horizon.num_hours()) / Decimal::from(HOURS_PER_YEAR);
+    Some((usd_growth * start_currency_price / end_currency_price - Decimal::ONE) * Decimal::from(10000))
+}
+
+/// Spearman rank correlation (Pearson correlation of average ranks), None if undefined
+fn spearman_correlation(a: &[f64], b: &[f64]) -> Option<f64> {
+    if a.len() < 2 || a.len() != b.len() {
+        return None;
+    }
+    let rank_a = average_ranks(a);
+    let rank_b = average_ranks(b);
+
+    let mean_a = rank_a.iter().sum::<f64>() / rank_a.len() as f64;
+    let mean_b = rank_b.iter().sum::<f64>() / rank_b.len() as f64;
+    let mut cov = 0.0;
+    let mut var_a = 0.0;
+    let mut var_b = 0.0;
+    for (x, y) in rank_a.iter().zip(&rank_b) {
+        cov += (x - mean_a) * (y - mean_b);
+        var_a += (x - mean_a).powi(2);
+        var_b += (y - mean_b).powi(2);
+    }
+    if var_a <= 0.0 || var_b <= 0.0 {
+        return None;
+    }
+    Some(cov / (var_a * var_b).sqrt())
+}
+
+/// Ranks starting at 1, ties get the average of their ranks
+fn average_ranks(values: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&i, &j| values[i].partial_cmp(&values[j]).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut ranks = vec![0.0; values.len()];
+    let mut i = 0;
+    while i < order.len() {
+        let mut j = i;
+        while j + 1 < order.len() && values[order[j + 1]] == values[order[i]] {
+            j += 1;
+        }
+        let avg_rank = (i + j) as f64 / 2.0 + 1.0;
+        for &idx in &order[i..=j] {
+            ranks[idx] = avg_rank;
+        }
+        i = j + 1;
+    }
+    ranks
+}
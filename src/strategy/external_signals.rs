@@ -0,0 +1,43 @@
+use ethers::types::Address;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+use crate::db::models::external_signals::ExternalSignalModel;
+use super::types::HOURS_PER_YEAR;
+
+/// Trust weight of a stored signal's signer, zero for signers no longer trusted
+fn signer_trust(trust: &HashMap<Address, Decimal>, signal: &ExternalSignalModel) -> Decimal {
+    signal.signer.parse::<Address>().ok()
+        .and_then(|signer| trust.get(&signer).copied())
+        .unwrap_or(Decimal::ZERO)
+}
+
+/// Signers currently vetoing a market, among those still trusted
+pub fn vetoing_signers(signals: &[ExternalSignalModel], trust: &HashMap<Address, Decimal>) -> Vec<String> {
+    signals.iter()
+        .filter(|signal| signal.veto && signer_trust(trust, signal) > Decimal::ZERO)
+        .map(|signal| signal.signer.clone())
+        .collect()
+}
+
+/// Blend a market's internal expected hourly return with the external expected returns posted for it. Each signer
+/// takes its trust weight of the blend (scaled down together when they add up past 1), the internal estimate keeps
+/// the rest. Returns the blended return and the total external weight, zero when no trusted signer has a view.
+pub fn blend_expected_return(internal_return: Decimal, signals: &[ExternalSignalModel], trust: &HashMap<Address, Decimal>) -> (Decimal, Decimal) {
+    let views: Vec<(Decimal, Decimal)> = signals.iter()
+        .filter_map(|signal| {
+            let weight = signer_trust(trust, signal);
+            let apr = signal.expected_return_apr?;
+            (weight > Decimal::ZERO).then(|| (weight, apr / Decimal::from(HOURS_PER_YEAR)))
+        })
+        .collect();
+    let total_weight: Decimal = views.iter().map(|(weight, _)| *weight).sum();
+    if total_weight <= Decimal::ZERO {
+        return (internal_return, Decimal::ZERO);
+    }
+
+    let scale = if total_weight > Decimal::ONE { Decimal::ONE / total_weight } else { Decimal::ONE };
+    let external_weight = total_weight * scale;
+    let external_return: Decimal = views.iter().map(|(weight, view)| *weight * scale * *view).sum();
+    ((Decimal::ONE - external_weight) * internal_return + external_return, external_weight)
+}
@@ -0,0 +1,160 @@
+use rust_decimal::Decimal;
+use rust_decimal::prelude::*;
+use tracing::{debug, warn};
+
+use crate::config::dynamic::DynamicParams;
+use super::strategy_constants::{
+    MIN_POSITION_WEIGHT,
+    MAX_POSITION_WEIGHT,
+    MAX_CLUSTER_WEIGHT,
+    OPTIMIZER_EPSILON,
+};
+
+/// Limits the allocator projects its weights onto, and the minimum trade size the plan is filtered with
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AllocationConstraints {
+    pub min_position_weight: f64,
+    pub max_position_weight: f64,
+    pub max_cluster_weight: f64,
+    pub min_trade_size_usd: Decimal,
+}
+
+impl AllocationConstraints {
+    /// Strategy constant limits with the configured minimum trade size
+    pub fn from_params(params: &DynamicParams) -> Self {
+        Self {
+            min_position_weight: MIN_POSITION_WEIGHT,
+            max_position_weight: MAX_POSITION_WEIGHT,
+            max_cluster_weight: MAX_CLUSTER_WEIGHT,
+            min_trade_size_usd: params.min_trade_size_usd,
+        }
+    }
+}
+
+/// A constraint loosened so the allocation problem has a solution, with the conflict that forced it
+#[derive(Debug, Clone)]
+pub struct RelaxedConstraint {
+    pub constraint: &'static str,
+    pub from: String,
+    pub to: String,
+    pub reason: String,
+}
+
+impl std::fmt::Display for RelaxedConstraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} -> {} ({})", self.constraint, self.from, self.to, self.reason)
+    }
+}
+
+/// Check that the constraints admit fully invested weights before optimizing, relaxing the conflicting ones.
+/// Without this the projection steps fight each other (e.g. a position cap that can't sum to one) and the
+/// allocator returns corner solutions that the later plan guards then partly undo.
+///
+/// Constraints are relaxed in priority order, least protective first, each only as far as needed:
+///   1. Position cap: raised to 1/market count when too few markets can hold the portfolio under it, and to the
+///      minimum trade weight (minimum trade size over portfolio value) when no capped position is worth a trade.
+///   2. Cluster cap: raised to the smallest value at which the clusters, each holding at most its markets' position
+///      caps, can hold the full portfolio.
+///   3. Minimum trade size: lowered to the portfolio value when the whole portfolio is worth less than one trade.
+/// The minimum position weight is never relaxed, it stays below the position cap as the cap only rises.
+pub fn check_feasibility(
+    params: &DynamicParams,
+    cluster_ids: &[usize],
+    portfolio_value_usd: Option<Decimal>,
+) -> (AllocationConstraints, Vec<RelaxedConstraint>) {
+    let mut constraints = AllocationConstraints::from_params(params);
+    let mut relaxed = Vec::new();
+    let n_assets = cluster_ids.len();
+    if n_assets == 0 {
+        return (constraints, relaxed);
+    }
+
+    // 1. Position cap
+    let min_full_cap = 1.0 / n_assets as f64;
+    if constraints.max_position_weight < min_full_cap {
+        relaxed.push(weight_relaxation(
+            "max_position_weight",
+            constraints.max_position_weight,
+            min_full_cap,
+            format!("{} markets can't hold the portfolio under the cap", n_assets),
+        ));
+        constraints.max_position_weight = min_full_cap;
+    }
+    let min_trade_weight = match portfolio_value_usd {
+        Some(value) if value > Decimal::ZERO => (constraints.min_trade_size_usd / value).to_f64().unwrap_or(0.0),
+        _ => 0.0,
+    };
+    if min_trade_weight > constraints.max_position_weight && constraints.max_position_weight < 1.0 {
+        let raised = min_trade_weight.min(1.0);
+        relaxed.push(weight_relaxation(
+            "max_position_weight",
+            constraints.max_position_weight,
+            raised,
+            format!("a capped position is worth less than the ${:.2} minimum trade size", constraints.min_trade_size_usd),
+        ));
+        constraints.max_position_weight = raised;
+    }
+
+    // 2. Cluster cap
+    let n_clusters = cluster_ids.iter().max().map_or(0, |max_id| max_id + 1);
+    let mut cluster_capacities = vec![0.0; n_clusters];
+    for &cluster in cluster_ids {
+        cluster_capacities[cluster] += constraints.max_position_weight;
+    }
+    let cluster_cap = min_feasible_cluster_cap(&cluster_capacities);
+    if cluster_cap > constraints.max_cluster_weight + OPTIMIZER_EPSILON {
+        relaxed.push(weight_relaxation(
+            "max_cluster_weight",
+            constraints.max_cluster_weight,
+            cluster_cap,
+            format!("{} correlation clusters can't hold the portfolio under the cap", n_clusters),
+        ));
+        constraints.max_cluster_weight = cluster_cap;
+    }
+
+    // 3. Minimum trade size
+    if let Some(value) = portfolio_value_usd {
+        if value > Decimal::ZERO && constraints.min_trade_size_usd > value {
+            relaxed.push(RelaxedConstraint {
+                constraint: "min_trade_size_usd",
+                from: format!("${:.2}", constraints.min_trade_size_usd),
+                to: format!("${:.2}", value),
+                reason: "portfolio is worth less than one trade".to_string(),
+            });
+            constraints.min_trade_size_usd = value;
+        }
+    }
+
+    for relaxation in &relaxed {
+        warn!("Infeasible allocation constraint relaxed: {}", relaxation);
+    }
+    debug!(constraints = ?constraints, "Allocation constraints checked for feasibility");
+    (constraints, relaxed)
+}
+
+/// Smallest cluster cap c with Σ min(c, capacity) ≥ 1, filling the smallest clusters first.
+/// Returns 1 when even uncapped clusters can't hold the portfolio.
+fn min_feasible_cluster_cap(cluster_capacities: &[f64]) -> f64 {
+    let mut capacities = cluster_capacities.to_vec();
+    capacities.sort_by(|a, b| a.total_cmp(b));
+
+    let mut filled = 0.0;
+    for (i, &capacity) in capacities.iter().enumerate() {
+        // Clusters from i on all sit at the cap, so they share what the smaller clusters can't hold
+        let cap = (1.0 - filled) / (capacities.len() - i) as f64;
+        if cap <= capacity {
+            return cap;
+        }
+        filled += capacity;
+    }
+    1.0
+}
+
+fn weight_relaxation(constraint: &'static str, from: f64, to: f64, reason: String) -> RelaxedConstraint {
+    RelaxedConstraint {
+        constraint,
+        from: format!("{:.2}%", from * 100.0),
+        to: format!("{:.2}%", to * 100.0),
+        reason,
+    }
+}
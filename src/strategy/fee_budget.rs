@@ -0,0 +1,113 @@
+use eyre::Result;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::*;
+use chrono::Datelike;
+use tracing::{info, warn, instrument};
+
+use crate::config::Config;
+use crate::db::db_manager::DbManager;
+use crate::db::models::execution_costs::ExecutionSpendModel;
+use super::strategy_constants::FEE_BUDGET_ALERT_THRESHOLD;
+
+/// Gas + execution fee spend for the current UTC day and month against the configured budgets
+#[derive(Debug, Clone)]
+pub struct FeeBudgetStatus {
+    pub daily_spent_usd: Decimal,
+    pub monthly_spent_usd: Decimal,
+    pub daily_budget_usd: Option<Decimal>,
+    pub monthly_budget_usd: Option<Decimal>,
+    pub monthly_spend_by_action: Vec<ExecutionSpendModel>,
+}
+
+impl FeeBudgetStatus {
+    /// Load the current spend from the execution costs ledger
+    #[instrument(skip(config, db_manager), fields(on_close = true))]
+    pub async fn load(config: &Config, db_manager: &DbManager) -> Result<Self> {
+        let now = db_manager.clock.now();
+        let day_start = now.date_naive().and_hms_opt(0, 0, 0).unwrap().and_utc();
+        let month_start = now.date_naive().with_day(1).unwrap().and_hms_opt(0, 0, 0).unwrap().and_utc();
+
+        let monthly_spend_by_action = db_manager.get_execution_spend_since(month_start).await?;
+        let daily_spend_by_action = db_manager.get_execution_spend_since(day_start).await?;
+
+        Ok(Self {
+            daily_spent_usd: total_spend(&daily_spend_by_action),
+            monthly_spent_usd: total_spend(&monthly_spend_by_action),
+            daily_budget_usd: config.daily_fee_budget_usd,
+            monthly_budget_usd: config.monthly_fee_budget_usd,
+            monthly_spend_by_action,
+        })
+    }
+
+    /// True once either budget is fully spent, discretionary actions should be deferred
+    pub fn is_exhausted(&self) -> bool {
+        exceeds(self.daily_spent_usd, self.daily_budget_usd, Decimal::ONE)
+            || exceeds(self.monthly_spent_usd, self.monthly_budget_usd, Decimal::ONE)
+    }
+
+    /// Log remaining budget and the monthly spend breakdown, alerting once the alert threshold is consumed
+    pub fn log_remaining(&self) {
+        let breakdown = self.monthly_spend_by_action.iter()
+            .map(|s| format!("{} {}: {:.2} USD ({} txs)", s.venue, s.action_type, s.total_cost_usd, s.tx_count))
+            .collect::<Vec<_>>();
+        let breakdown = if breakdown.is_empty() { "N/A".to_string() } else { breakdown.join("\n  ") };
+        info!(
+            daily_spent_usd = %self.daily_spent_usd.round_dp(2),
+            daily_remaining_usd = %remaining(self.daily_spent_usd, self.daily_budget_usd),
+            monthly_spent_usd = %self.monthly_spent_usd.round_dp(2),
+            monthly_remaining_usd = %remaining(self.monthly_spent_usd, self.monthly_budget_usd),
+            "Execution fee budget:\n  {}",
+            breakdown
+        );
+
+        let alert_threshold = Decimal::from_f64(FEE_BUDGET_ALERT_THRESHOLD).unwrap();
+        for (period, spent, budget) in [
+            ("daily", self.daily_spent_usd, self.daily_budget_usd),
+            ("monthly", self.monthly_spent_usd, self.monthly_budget_usd),
+        ] {
+            let Some(budget) = budget.filter(|b| *b > Decimal::ZERO) else {
+                continue;
+            };
+            if spent >= budget * alert_threshold {
+                warn!(
+                    period = period,
+                    spent_usd = %spent.round_dp(2),
+                    budget_usd = %budget,
+                    "Execution fee budget {:.0}% consumed",
+                    spent / budget * Decimal::from(100)
+                );
+            }
+        }
+    }
+}
+
+/// Refuse discretionary actions once the fee budget is spent
+pub async fn ensure_fee_budget_available(config: &Config, db_manager: &DbManager, action: &str) -> Result<()> {
+    let status = FeeBudgetStatus::load(config, db_manager).await?;
+    if status.is_exhausted() {
+        return Err(eyre::eyre!(
+            "Execution fee budget exhausted (daily {:.2} USD, monthly {:.2} USD spent), deferring {}",
+            status.daily_spent_usd, status.monthly_spent_usd, action
+        ));
+    }
+    Ok(())
+}
+
+fn total_spend(spend: &[ExecutionSpendModel]) -> Decimal {
+    spend.iter().map(|s| s.total_cost_usd).sum()
+}
+
+fn exceeds(spent: Decimal, budget: Option<Decimal>, fraction: Decimal) -> bool {
+    match budget {
+        Some(budget) if budget > Decimal::ZERO => spent >= budget * fraction,
+        Some(_) => true, // Zero budget allows no discretionary spend
+        None => false,
+    }
+}
+
+fn remaining(spent: Decimal, budget: Option<Decimal>) -> String {
+    match budget {
+        Some(budget) => format!("{:.2}", (budget - spent).max(Decimal::ZERO)),
+        None => "unlimited".to_string(),
+    }
+}
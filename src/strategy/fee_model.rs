@@ -1,10 +1,12 @@
 use rust_decimal::Decimal;
 use rust_decimal::prelude::*;
-use chrono::{DateTime, Utc, Timelike};
-use std::collections::BTreeMap;
+use chrono::{DateTime, Duration, Utc};
 
+use crate::data_ingestion::market::market_utils::u256_to_decimal_scaled;
+use crate::gmx::datastore::SwapPricingFactors;
 use super::types::MarketStateSlice;
-use super::strategy_constants::EWMA_ALPHA;
+use super::return_calculation_utils::{self, FillMethod};
+use super::strategy_constants::{EWMA_ALPHA, RETURN_RESAMPLE_MAX_GAP_MINUTES};
 
 /// Returns expected return over the time horizon (as % of pool value)
 pub fn simulate_fee_return(slice: &MarketStateSlice) -> Option<Decimal> {
@@ -24,25 +26,35 @@ pub fn simulate_fee_return(slice: &MarketStateSlice) -> Option<Decimal> {
     Some(expected_return)
 }
 
-/// Aggregates ~5-min fee data into hourly fee buckets
-fn standardize_to_hourly(timestamps: &[DateTime<Utc>], fees_usd: &[Decimal]) -> Option<Vec<Decimal>> {
+/// Aggregates ~5-min fee data into hourly fees. Cumulative fees are resampled onto the hour boundaries and differenced,
+/// so the partial first and last hours and hours with a boundary inside a collection gap are dropped rather than
+/// counted as low fee hours. None if no complete hour is covered.
+pub fn standardize_to_hourly(timestamps: &[DateTime<Utc>], fees_usd: &[Decimal]) -> Option<Vec<Decimal>> {
     if timestamps.len() != fees_usd.len() || timestamps.is_empty() {
         return None;
     }
 
-    let mut hourly_buckets: BTreeMap<DateTime<Utc>, Decimal> = BTreeMap::new();
+    let cumulative_fees: Vec<Decimal> = fees_usd.iter()
+        .scan(Decimal::ZERO, |total, fee| {
+            *total += *fee;
+            Some(*total)
+        })
+        .collect();
+    let (_, resampled) = return_calculation_utils::align_series(
+        &[(timestamps, cumulative_fees.as_slice())],
+        Duration::hours(1),
+        FillMethod::ForwardFill,
+        Duration::minutes(RETURN_RESAMPLE_MAX_GAP_MINUTES),
+    )?;
 
-    for (ts, fee) in timestamps.iter().zip(fees_usd.iter()) {
-        // Truncate timestamp to the hour
-        let hour_key = ts.with_minute(0).unwrap().with_second(0).unwrap().with_nanosecond(0).unwrap();
-        *hourly_buckets.entry(hour_key).or_insert(Decimal::ZERO) += *fee;
-    }
-
-    Some(hourly_buckets.values().cloned().collect())
+    let hourly_fees: Vec<Decimal> = resampled[0].windows(2)
+        .filter_map(|hour| Some(hour[1]? - hour[0]?))
+        .collect();
+    (!hourly_fees.is_empty()).then_some(hourly_fees)
 }
 
 /// Standard EWMA computation over a Decimal vector
-fn compute_ewma(values: &[Decimal]) -> Option<Decimal> {
+pub fn compute_ewma(values: &[Decimal]) -> Option<Decimal> {
     if values.is_empty() {
         return None;
     }
@@ -55,4 +67,134 @@ fn compute_ewma(values: &[Decimal]) -> Option<Decimal> {
     }
 
     Some(ewma)
+}
+
+// GMX deposit and withdrawal pricing, ported from SwapPricingUtils so cost estimates follow the protocol math.
+// Amounts are USD and factors are scaled down from GMX's 30 decimals (a 2e30 exponent factor is 2).
+
+/// A market's swap impact and fee factors, as read from the datastore
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SwapPricingParams {
+    pub positive_impact_factor: Decimal,
+    pub negative_impact_factor: Decimal,
+    pub impact_exponent: Decimal,
+    pub fee_factor_positive_impact: Decimal, // Fee factor when the action improves the pool balance
+    pub fee_factor_negative_impact: Decimal, // Fee factor when the action worsens the pool balance
+}
+
+impl SwapPricingParams {
+    /// Scale the raw datastore factors down from 30 decimals
+    pub fn from_factors(factors: &SwapPricingFactors) -> Self {
+        Self {
+            positive_impact_factor: u256_to_decimal_scaled(factors.positive_impact_factor),
+            negative_impact_factor: u256_to_decimal_scaled(factors.negative_impact_factor),
+            impact_exponent: u256_to_decimal_scaled(factors.impact_exponent_factor),
+            fee_factor_positive_impact: u256_to_decimal_scaled(factors.fee_factor_positive_impact),
+            fee_factor_negative_impact: u256_to_decimal_scaled(factors.fee_factor_negative_impact),
+        }
+    }
+}
+
+/// USD value of a pool's long and short tokens (or of its virtual inventory)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PoolBalance {
+    pub long_usd: Decimal,
+    pub short_usd: Decimal,
+}
+
+/// Cost of a GM action: fees paid and price impact (positive is a rebate, negative a cost)
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct GmActionCost {
+    pub fees_usd: Decimal,
+    pub price_impact_usd: Decimal,
+}
+
+impl GmActionCost {
+    /// Net cost in USD, negative when the impact rebate exceeds the fees
+    pub fn total_usd(&self) -> Decimal {
+        self.fees_usd - self.price_impact_usd
+    }
+}
+
+/// Impact factors as GMX applies them: a positive factor above the negative one is capped at it, otherwise moving
+/// the pool back and forth would earn more positive impact than it pays
+pub fn adjusted_impact_factors(params: &SwapPricingParams) -> (Decimal, Decimal) {
+    (params.positive_impact_factor.min(params.negative_impact_factor), params.negative_impact_factor)
+}
+
+/// diff^exponent * factor. None when the result overflows.
+pub fn apply_impact_factor(diff_usd: Decimal, factor: Decimal, exponent: Decimal) -> Option<Decimal> {
+    let scaled = if exponent.fract().is_zero() {
+        diff_usd.checked_powu(exponent.to_u64()?)?
+    } else {
+        diff_usd.checked_powd(exponent)?
+    };
+    scaled.checked_mul(factor)
+}
+
+/// Price impact of changing the pool's long and short token value by the given deltas. An action keeping the same
+/// side of the pool heavier pays (or earns) the change in the imbalance penalty; one crossing the balance point earns
+/// positive impact for removing the initial imbalance and pays negative impact for the new one.
+/// None when a delta takes a side below zero or the impact overflows.
+pub fn swap_price_impact_usd(pool: PoolBalance, delta_long_usd: Decimal, delta_short_usd: Decimal, params: &SwapPricingParams) -> Option<Decimal> {
+    let next_long_usd = pool.long_usd + delta_long_usd;
+    let next_short_usd = pool.short_usd + delta_short_usd;
+    if next_long_usd < Decimal::ZERO || next_short_usd < Decimal::ZERO {
+        return None;
+    }
+
+    let initial_diff_usd = (pool.long_usd - pool.short_usd).abs();
+    let next_diff_usd = (next_long_usd - next_short_usd).abs();
+    let (positive_factor, negative_factor) = adjusted_impact_factors(params);
+    let exponent = params.impact_exponent;
+
+    let is_same_side_rebalance = (pool.long_usd <= pool.short_usd) == (next_long_usd <= next_short_usd);
+    if is_same_side_rebalance {
+        let has_positive_impact = next_diff_usd < initial_diff_usd;
+        let factor = if has_positive_impact { positive_factor } else { negative_factor };
+        let delta_usd = (apply_impact_factor(initial_diff_usd, factor, exponent)? - apply_impact_factor(next_diff_usd, factor, exponent)?).abs();
+        Some(if has_positive_impact { delta_usd } else { -delta_usd })
+    } else {
+        Some(apply_impact_factor(initial_diff_usd, positive_factor, exponent)? - apply_impact_factor(next_diff_usd, negative_factor, exponent)?)
+    }
+}
+
+/// Fee charged on an amount, at the positive or negative impact fee factor
+pub fn swap_fee_usd(amount_usd: Decimal, for_positive_impact: bool, params: &SwapPricingParams) -> Decimal {
+    let fee_factor = if for_positive_impact { params.fee_factor_positive_impact } else { params.fee_factor_negative_impact };
+    amount_usd * fee_factor
+}
+
+/// Cost of depositing long and short token value into a pool. Negative impact is the worse of the pool and virtual
+/// inventory impacts, positive impact is capped at what the impact pool holds. The fee factor follows the uncapped
+/// impact, as GMX picks it before applying the cap. None when the impact overflows.
+pub fn deposit_cost(
+    pool: PoolBalance,
+    virtual_inventory: Option<PoolBalance>,
+    impact_pool_usd: Decimal,
+    long_usd: Decimal,
+    short_usd: Decimal,
+    params: &SwapPricingParams,
+) -> Option<GmActionCost> {
+    let mut price_impact_usd = swap_price_impact_usd(pool, long_usd, short_usd, params)?;
+    if price_impact_usd < Decimal::ZERO {
+        if let Some(virtual_inventory) = virtual_inventory {
+            price_impact_usd = price_impact_usd.min(swap_price_impact_usd(virtual_inventory, long_usd, short_usd, params)?);
+        }
+    }
+    let fees_usd = swap_fee_usd(long_usd + short_usd, price_impact_usd > Decimal::ZERO, params);
+
+    Some(GmActionCost {
+        fees_usd,
+        price_impact_usd: price_impact_usd.min(impact_pool_usd.max(Decimal::ZERO)),
+    })
+}
+
+/// Cost of a withdrawal: GMX pays withdrawals out pro rata to the pool composition without price impact, charging
+/// the negative impact fee factor
+pub fn withdrawal_cost(value_usd: Decimal, params: &SwapPricingParams) -> GmActionCost {
+    GmActionCost {
+        fees_usd: swap_fee_usd(value_usd, false, params),
+        price_impact_usd: Decimal::ZERO,
+    }
 }
\ No newline at end of file
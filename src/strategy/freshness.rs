@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use eyre::Result;
+use tracing::{debug, error, instrument};
+
+use crate::config::Config;
+use crate::db::db_manager::DbManager;
+use crate::hedging::dydx_client::DYDX_VENUE;
+use crate::hedging::hedge_venue::HedgeMarket;
+
+/// Pre-flight check that the data a plan is built from is fresh: the newest market state and token price must be
+/// younger than MAX_MARKET_DATA_AGE_SECS and the newest funding rate of every dYdX hedge ticker younger than
+/// MAX_FUNDING_DATA_AGE_SECS. A stalled pipeline otherwise goes unnoticed and the plan executes against stale data.
+/// Every violation is logged as an error and the run is aborted with all of them in the returned error.
+#[instrument(skip(config, db_manager, hedge_markets), fields(on_close = true))]
+pub async fn ensure_data_fresh(
+    config: &Config,
+    db_manager: &DbManager,
+    hedge_markets: &HashMap<String, HedgeMarket>,
+) -> Result<()> {
+    let now = db_manager.clock.now();
+    let mut violations = Vec::new();
+
+    let (market_state_at, token_price_at) = db_manager.get_latest_ingest_timestamps().await?;
+    for (source, timestamp) in [("market state", market_state_at), ("token price", token_price_at)] {
+        if let Some(violation) = check_age(source, timestamp, now, config.max_market_data_age_secs) {
+            violations.push(violation);
+        }
+    }
+
+    let mut tickers: Vec<&str> = hedge_markets.values()
+        .filter(|market| market.venue == DYDX_VENUE)
+        .map(|market| market.ticker.as_str())
+        .collect();
+    tickers.sort_unstable();
+    tickers.dedup();
+    for ticker in tickers {
+        let timestamp = db_manager.get_latest_funding_rate_timestamp(ticker).await?;
+        if let Some(violation) = check_age(&format!("{} funding rate", ticker), timestamp, now, config.max_funding_data_age_secs) {
+            violations.push(violation);
+        }
+    }
+
+    if !violations.is_empty() {
+        for violation in &violations {
+            error!(violation = %violation, "Data freshness check failed");
+        }
+        return Err(eyre::eyre!("Stale input data, aborting run: {}", violations.join("; ")));
+    }
+    debug!("Data freshness checks passed");
+    Ok(())
+}
+
+/// Describe the violation when the newest observation is missing or older than the limit
+fn check_age(source: &str, timestamp: Option<DateTime<Utc>>, now: DateTime<Utc>, max_age_secs: i64) -> Option<String> {
+    match timestamp {
+        None => Some(format!("no {} recorded", source)),
+        Some(timestamp) => {
+            let age_secs = (now - timestamp).num_seconds();
+            (age_secs > max_age_secs).then(|| format!("newest {} is {}s old (limit {}s)", source, age_secs, max_age_secs))
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use eyre::Result;
+use ethers::types::Address;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use tracing::{debug, warn, instrument};
+
+use crate::config::Config;
+use crate::db::db_manager::DbManager;
+use crate::gmx::datastore;
+use super::fee_model::{self, GmActionCost, PoolBalance, SwapPricingParams};
+use super::rebalance::RebalancePlan;
+use super::types::PortfolioData;
+
+/// Estimated GMX fees and price impact of a rebalance plan's deposits and withdrawals
+#[derive(Debug, Clone, Default)]
+pub struct RebalanceCostEstimate {
+    pub deposits: GmActionCost,
+    pub withdrawals: GmActionCost,
+    pub unestimated_markets: Vec<Address>, // Markets without pool data or pricing factors, left out
+}
+
+impl RebalanceCostEstimate {
+    pub fn fees_usd(&self) -> Decimal {
+        self.deposits.fees_usd + self.withdrawals.fees_usd
+    }
+
+    pub fn price_impact_usd(&self) -> Decimal {
+        self.deposits.price_impact_usd + self.withdrawals.price_impact_usd
+    }
+
+    /// Net cost in USD, fees plus negative impact less any positive impact
+    pub fn total_usd(&self) -> Decimal {
+        self.deposits.total_usd() + self.withdrawals.total_usd()
+    }
+}
+
+/// Estimate what GMX will charge for the plan's deposits and withdrawals with the protocol's own swap pricing, from
+/// the latest recorded pool balances and the markets' current impact and fee factors. Deposits are assumed to be made
+/// in the pool's long/short proportion, as they are sized at execution. Swap impact pools and virtual inventories
+/// aren't collected, so positive impact isn't credited and only pool impact is charged. Shifts move capital between
+/// same-collateral markets without fees or impact and are not estimated.
+/// Each estimated move's cost is recorded in the plan notes.
+#[instrument(skip(config, db_manager, plan, portfolio_data), fields(on_close = true))]
+pub async fn estimate_rebalance_costs(
+    config: &Config,
+    db_manager: &DbManager,
+    plan: &RebalancePlan,
+    portfolio_data: &mut PortfolioData,
+) -> Result<RebalanceCostEstimate> {
+    let mut estimate = RebalanceCostEstimate::default();
+    let mut markets: Vec<Address> = plan.withdrawals.iter().map(|w| w.market)
+        .chain(plan.deposits.iter().map(|d| d.market))
+        .collect();
+    markets.sort();
+    markets.dedup();
+    if markets.is_empty() {
+        return Ok(estimate);
+    }
+
+    let factors = datastore::get_swap_pricing_factors_batch(config, &markets).await?;
+    let gm_prices = db_manager.get_latest_gm_prices_as_of(db_manager.clock.now()).await?;
+    let mut pools: HashMap<Address, PoolBalance> = HashMap::new();
+    for market in &markets {
+        if let Some(state) = db_manager.get_latest_market_state(*market).await? {
+            if let (Some(long_usd), Some(short_usd)) = (state.pool_long_token_usd, state.pool_short_token_usd) {
+                pools.insert(*market, PoolBalance { long_usd, short_usd });
+            }
+        }
+    }
+    let params = |market: &Address| factors.get(market).map(SwapPricingParams::from_factors);
+
+    for withdrawal in &plan.withdrawals {
+        let (Some(params), Some((gm_price, _))) = (params(&withdrawal.market), gm_prices.get(&withdrawal.market)) else {
+            estimate.unestimated_markets.push(withdrawal.market);
+            continue;
+        };
+        let cost = fee_model::withdrawal_cost(withdrawal.amount * gm_price, &params);
+        estimate.withdrawals.fees_usd += cost.fees_usd;
+        portfolio_data.add_note(withdrawal.market, format!("Withdrawal fee estimated at ${:.2}", cost.fees_usd));
+    }
+
+    for deposit in &plan.deposits {
+        let (Some(params), Some(pool)) = (params(&deposit.market), pools.get(&deposit.market)) else {
+            estimate.unestimated_markets.push(deposit.market);
+            continue;
+        };
+        let pool_value_usd = pool.long_usd + pool.short_usd;
+        let long_share = if pool_value_usd > Decimal::ZERO { pool.long_usd / pool_value_usd } else { Decimal::new(5, 1) };
+        let long_usd = deposit.value_usd * long_share;
+        let Some(cost) = fee_model::deposit_cost(*pool, None, Decimal::ZERO, long_usd, deposit.value_usd - long_usd, &params) else {
+            estimate.unestimated_markets.push(deposit.market);
+            continue;
+        };
+        estimate.deposits.fees_usd += cost.fees_usd;
+        estimate.deposits.price_impact_usd += cost.price_impact_usd;
+        portfolio_data.add_note(deposit.market, format!(
+            "Deposit fee estimated at ${:.2}, price impact ${:.2}",
+            cost.fees_usd,
+            cost.price_impact_usd
+        ));
+    }
+
+    if !estimate.unestimated_markets.is_empty() {
+        warn!(markets = ?estimate.unestimated_markets, "No pool data or pricing factors, these moves are left out of the cost estimate");
+    }
+    debug!(
+        fees_usd = %estimate.fees_usd(),
+        price_impact_usd = %estimate.price_impact_usd(),
+        "Rebalance costs estimated"
+    );
+    Ok(estimate)
+}
@@ -0,0 +1,111 @@
+use chrono::{DateTime, Utc};
+use eyre::Result;
+use ethers::types::Address;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use tracing::{debug, instrument};
+
+use crate::config::Config;
+use crate::db::db_manager::DbManager;
+use crate::db::models::market_holding_periods::MarketHoldingPeriodModel;
+use super::types::{PortfolioData, PortfolioSnapshot};
+
+/// Update the holding period state from the current holdings: a market held now but not at the last update was
+/// entered now, and a market no longer held was exited now. Returns the state of every market, keyed by address.
+#[instrument(skip(db_manager, current_portfolio), fields(on_close = true))]
+pub async fn track_holding_periods(db_manager: &DbManager, current_portfolio: &PortfolioSnapshot) -> Result<HashMap<Address, MarketHoldingPeriodModel>> {
+    let now = db_manager.clock.now();
+    let mut periods = db_manager.get_market_holding_periods().await?;
+
+    let mut changed = Vec::new();
+    for (market, market_id) in &db_manager.market_id_map {
+        let is_held = current_portfolio.weights.get(market).is_some_and(|weight| *weight > Decimal::ZERO);
+        let period = periods.entry(*market_id).or_insert_with(|| MarketHoldingPeriodModel {
+            market_id: *market_id,
+            entered_at: None,
+            exited_at: None,
+        });
+        if is_held == period.is_held() {
+            continue;
+        }
+        if is_held {
+            period.entered_at = Some(now);
+        } else {
+            period.exited_at = Some(now);
+        }
+        debug!(market = ?market, held = is_held, "Market holding changed");
+        changed.push(period.clone());
+    }
+    if !changed.is_empty() {
+        db_manager.upsert_market_holding_periods(&changed).await?;
+    }
+
+    let address_by_id: HashMap<i32, Address> = db_manager.market_id_map.iter().map(|(address, id)| (*id, *address)).collect();
+    Ok(periods.into_iter()
+        .filter_map(|(market_id, period)| Some((*address_by_id.get(&market_id)?, period)))
+        .collect())
+}
+
+/// Hold back churn that would burn GMX fees: a target below the current weight of a position entered within its
+/// minimum holding period keeps the current weight, and a target re-entering a market exited within its cooldown
+/// is reset to zero. Markets the engine dropped are still exited, and later risk guards (e.g. the collateral drift
+/// trim) can still reduce held positions. Each reset is recorded in the plan notes. Returns the number of targets reset.
+pub fn apply_holding_rules(
+    config: &Config,
+    periods: &HashMap<Address, MarketHoldingPeriodModel>,
+    now: DateTime<Utc>,
+    portfolio_data: &mut PortfolioData,
+    current_portfolio: &PortfolioSnapshot,
+) -> usize {
+    let mut reset = 0;
+    for i in 0..portfolio_data.market_addresses.len() {
+        let address = portfolio_data.market_addresses[i];
+        let Some(period) = periods.get(&address) else {
+            continue;
+        };
+        let target_weight = portfolio_data.weights[i];
+        let current_weight = current_portfolio.weights.get(&address).copied().unwrap_or(Decimal::ZERO);
+
+        let note = if target_weight < current_weight && period.is_held() {
+            let min_holding_hours = config.min_holding_hours_by_market.get(&address).copied().unwrap_or(config.min_holding_hours);
+            let Some(entered_at) = period.entered_at else {
+                continue;
+            };
+            let held_for = now - entered_at;
+            if held_for >= chrono::Duration::hours(min_holding_hours) {
+                continue;
+            }
+            portfolio_data.weights[i] = current_weight;
+            format!(
+                "Target weight {:.2}% kept at the current {:.2}%, position entered {}h ago is within its {}h minimum holding period",
+                target_weight * Decimal::from(100),
+                current_weight * Decimal::from(100),
+                held_for.num_hours(),
+                min_holding_hours
+            )
+        } else if target_weight > Decimal::ZERO && current_weight == Decimal::ZERO && !period.is_held() {
+            let cooldown_hours = config.reentry_cooldown_hours_by_market.get(&address).copied().unwrap_or(config.reentry_cooldown_hours);
+            let Some(exited_at) = period.exited_at else {
+                continue;
+            };
+            let exited_for = now - exited_at;
+            if exited_for >= chrono::Duration::hours(cooldown_hours) {
+                continue;
+            }
+            portfolio_data.weights[i] = Decimal::ZERO;
+            format!(
+                "Target weight {:.2}% not entered, market exited {}h ago is within its {}h re-entry cooldown",
+                target_weight * Decimal::from(100),
+                exited_for.num_hours(),
+                cooldown_hours
+            )
+        } else {
+            continue;
+        };
+
+        reset += 1;
+        debug!(market = %portfolio_data.display_names[i], "{}", note);
+        portfolio_data.add_note(address, note);
+    }
+    reset
+}
@@ -0,0 +1,83 @@
+use ethers::types::Address;
+use ndarray::Array2;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::*;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::db::models::liquidations::LiquidationTotalsModel;
+use super::types::MarketStateSlice;
+use super::strategy_constants::{LIQUIDATION_VARIANCE_SENSITIVITY, LIQUIDATION_MAX_VARIANCE_UPLIFT};
+
+/// Liquidation features of one market over the lookback window. Cascades are when LPs earn the most fees but also
+/// absorb the most toxic flow, so markets liquidating a large share of their pool carry more tail risk than their
+/// return variance shows.
+#[derive(Debug, Clone, Default)]
+pub struct LiquidationIntensity {
+    pub liquidation_count: i64,
+    pub liquidated_usd: Decimal,
+    pub long_share: Decimal,          // Share of the liquidated size that was long positions
+    pub intensity: Decimal,           // Liquidated size over the window as a fraction of pool value
+    pub peak_hour_intensity: Decimal, // Largest single-hour liquidated size as a fraction of pool value
+}
+
+impl LiquidationIntensity {
+    /// Features from the recorded totals, relative to the market's latest pool value
+    pub fn from_totals(totals: &LiquidationTotalsModel, pool_value_usd: Decimal) -> Self {
+        let fraction_of_pool = |usd: Decimal| if pool_value_usd > Decimal::ZERO { usd / pool_value_usd } else { Decimal::ZERO };
+        Self {
+            liquidation_count: totals.liquidation_count,
+            liquidated_usd: totals.liquidated_usd,
+            long_share: if totals.liquidated_usd > Decimal::ZERO { totals.long_liquidated_usd / totals.liquidated_usd } else { Decimal::ZERO },
+            intensity: fraction_of_pool(totals.liquidated_usd),
+            peak_hour_intensity: fraction_of_pool(totals.max_hourly_liquidated_usd),
+        }
+    }
+
+    /// Multiplier on the market's return variance, 1 without liquidations and capped for the most violent markets
+    pub fn variance_uplift(&self) -> Decimal {
+        let sensitivity = Decimal::from_f64(LIQUIDATION_VARIANCE_SENSITIVITY).unwrap();
+        let max_uplift = Decimal::from_f64(LIQUIDATION_MAX_VARIANCE_UPLIFT).unwrap();
+        (Decimal::ONE + sensitivity * (self.intensity + self.peak_hour_intensity)).min(max_uplift)
+    }
+}
+
+impl fmt::Display for LiquidationIntensity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} liquidations, ${:.0} ({:.2}% of pool, peak hour {:.2}%, {:.0}% long)",
+            self.liquidation_count,
+            self.liquidated_usd,
+            self.intensity * Decimal::from(100),
+            self.peak_hour_intensity * Decimal::from(100),
+            self.long_share * Decimal::from(100)
+        )
+    }
+}
+
+/// Liquidation features per market in slice order, markets without recorded liquidations get the default
+pub fn liquidation_intensities(market_slices: &[MarketStateSlice], totals: &HashMap<Address, LiquidationTotalsModel>) -> Vec<LiquidationIntensity> {
+    market_slices.iter()
+        .map(|slice| match totals.get(&slice.market_address) {
+            Some(totals) => LiquidationIntensity::from_totals(totals, slice.pool_long_collateral_usd + slice.pool_short_collateral_usd),
+            None => LiquidationIntensity::default(),
+        })
+        .collect()
+}
+
+/// Scale each market's variance by its liquidation uplift (and covariances by the square roots of both uplifts,
+/// keeping correlations unchanged)
+pub fn apply_liquidation_uplift(covariance_matrix: &Array2<Decimal>, intensities: &[LiquidationIntensity]) -> Array2<Decimal> {
+    let scale: Vec<Decimal> = intensities.iter()
+        .map(|intensity| intensity.variance_uplift().sqrt().unwrap_or(Decimal::ONE))
+        .collect();
+
+    let mut adjusted_covariance = covariance_matrix.clone();
+    for i in 0..scale.len() {
+        for j in 0..scale.len() {
+            adjusted_covariance[[i, j]] *= scale[i] * scale[j];
+        }
+    }
+    adjusted_covariance
+}
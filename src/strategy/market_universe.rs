@@ -0,0 +1,136 @@
+// Named market universes (e.g. core = BTC/ETH/SOL pools, experimental = newly listed markets), rows of the
+// market_universes table per account. A trading run given a universe allocates only its markets, with its capital
+// fraction of the GM holdings as its bucket, so markets can be promoted from an experimental bucket to the core one by
+// moving them between universes.
+use ethers::types::Address;
+use eyre::Result;
+use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet};
+use tracing::{debug, info, instrument};
+
+use crate::db::db_manager::DbManager;
+use crate::db::models::market_universes::MarketUniverseModel;
+use crate::hedging::hedge_utils;
+use crate::wallet::WalletManager;
+use super::types::PortfolioSnapshot;
+
+/// How a universe selects markets
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarketSelector {
+    IndexSymbol(String), // Every market on the index token, by symbol or base asset (ETH selects WETH markets)
+    Market(Address),     // A single market, by market token address
+}
+
+#[derive(Debug, Clone)]
+pub struct MarketUniverse {
+    pub name: String,
+    pub selectors: Vec<MarketSelector>,
+    pub capital_fraction: Decimal, // Share of the GM holdings the universe allocates, in (0, 1]
+    pub markets: HashSet<Address>, // Market tokens the selectors resolve to
+}
+
+impl MarketUniverse {
+    /// Parse a universe row, an invalid universe is an error rather than skipped so a typo can't widen a bucket
+    pub fn parse(model: &MarketUniverseModel) -> Result<Self> {
+        if model.capital_fraction <= Decimal::ZERO || model.capital_fraction > Decimal::ONE {
+            return Err(eyre::eyre!("Invalid market universe {}: capital fraction must be in (0, 1], got {}", model.universe, model.capital_fraction));
+        }
+        let selectors: Vec<MarketSelector> = model.markets.iter()
+            .map(|market| market.trim())
+            .filter(|market| !market.is_empty())
+            .map(|market| match market.parse::<Address>() {
+                Ok(address) => MarketSelector::Market(address),
+                Err(_) => MarketSelector::IndexSymbol(market.to_uppercase()),
+            })
+            .collect();
+        if selectors.is_empty() {
+            return Err(eyre::eyre!("Invalid market universe {}: no markets", model.universe));
+        }
+        Ok(Self {
+            name: model.universe.clone(),
+            selectors,
+            capital_fraction: model.capital_fraction,
+            markets: HashSet::new(),
+        })
+    }
+
+    /// Resolve the selectors against the wallet's market catalog
+    pub fn resolve(&mut self, wallet_manager: &WalletManager) {
+        let tokens = wallet_manager.tokens();
+        self.markets = tokens.market_tokens.values()
+            .filter(|market| {
+                let index_symbol = tokens.all_tokens.get(&market.index_token_address).map(|token| token.symbol.to_uppercase());
+                self.selectors.iter().any(|selector| match selector {
+                    MarketSelector::Market(address) => *address == market.address,
+                    MarketSelector::IndexSymbol(symbol) => index_symbol.as_deref().is_some_and(|index_symbol| {
+                        index_symbol == symbol || hedge_utils::get_base_asset(index_symbol) == symbol
+                    }),
+                })
+            })
+            .map(|market| market.address)
+            .collect();
+    }
+
+    pub fn contains(&self, market: &Address) -> bool {
+        self.markets.contains(market)
+    }
+
+    /// The holdings as the universe's bucket: its capital fraction of the total GM value, with weights of the
+    /// universe's positions relative to the bucket. Positions of other universes are left out, so the run neither
+    /// counts nor trades them.
+    pub fn scope_portfolio(&self, portfolio: &PortfolioSnapshot) -> PortfolioSnapshot {
+        let bucket_value_usd = portfolio.total_value_usd * self.capital_fraction;
+        let weights = if bucket_value_usd > Decimal::ZERO {
+            portfolio.weights.iter()
+                .filter(|(market, _)| self.contains(market))
+                .map(|(market, weight)| (*market, *weight / self.capital_fraction))
+                .collect()
+        } else {
+            HashMap::new()
+        };
+        PortfolioSnapshot {
+            weights,
+            total_value_usd: bucket_value_usd,
+            ..portfolio.clone()
+        }
+    }
+}
+
+/// Load the account's enabled universes and return the named one, resolved against the wallet's markets. The
+/// universes are checked together: their capital fractions may not add up past 1 and no market may be in two of
+/// them, as both runs would then trade the same position.
+#[instrument(skip(db_manager, wallet_manager), fields(on_close = true))]
+pub async fn load_universe(db_manager: &DbManager, wallet_manager: &WalletManager, name: &str) -> Result<MarketUniverse> {
+    let mut universes = db_manager.get_market_universes().await?
+        .iter()
+        .map(MarketUniverse::parse)
+        .collect::<Result<Vec<_>>>()?;
+    for universe in universes.iter_mut() {
+        universe.resolve(wallet_manager);
+    }
+
+    let total_fraction: Decimal = universes.iter().map(|universe| universe.capital_fraction).sum();
+    if total_fraction > Decimal::ONE {
+        return Err(eyre::eyre!("Market universe capital fractions add up to {}, more than the holdings", total_fraction));
+    }
+    let mut owners: HashMap<Address, &str> = HashMap::new();
+    for universe in &universes {
+        for market in &universe.markets {
+            if let Some(other) = owners.insert(*market, &universe.name) {
+                return Err(eyre::eyre!("Market {:?} is in both market universes {} and {}", market, other, universe.name));
+            }
+        }
+    }
+
+    let universe = universes.into_iter()
+        .find(|universe| universe.name == name)
+        .ok_or_else(|| eyre::eyre!("Market universe {} is not defined or not enabled", name))?;
+    debug!(selectors = ?universe.selectors, "Market universe selectors");
+    info!(
+        universe = %universe.name,
+        markets = universe.markets.len(),
+        capital_fraction = %universe.capital_fraction,
+        "Market universe loaded"
+    );
+    Ok(universe)
+}
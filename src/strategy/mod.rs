@@ -3,4 +3,29 @@ pub mod types;
 pub mod fee_model;
 pub mod allocator;
 pub mod covariance;
-pub mod strategy_constants;
\ No newline at end of file
+pub mod strategy_constants;
+pub mod approval;
+pub mod evaluation;
+pub mod fee_budget;
+pub mod wind_down;
+pub mod utilization_guard;
+pub mod trade_size;
+pub mod benchmark;
+pub mod return_calculation_utils;
+pub mod pnl_model;
+pub mod freshness;
+pub mod withdrawal_liquidity;
+pub mod rebalance;
+pub mod feasibility;
+pub mod data_quality;
+pub mod deposit_capacity;
+pub mod composition_drift;
+pub mod compliance;
+pub mod weight_smoothing;
+pub mod gm_costs;
+pub mod liquidation_risk;
+pub mod holding_period;
+pub mod external_signals;
+pub mod raise_cash;
+pub mod volatility;
+pub mod market_universe;
\ No newline at end of file
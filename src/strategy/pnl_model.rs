@@ -0,0 +1,355 @@
+use std::collections::HashMap;
+use ethers::types::Address;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::*;
+use chrono::Duration;
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2};
+use tracing::{debug, info, warn};
+
+use crate::config::Config;
+use super::fee_model;
+use super::types::MarketStateSlice;
+use super::strategy_constants::{
+    SIGNAL_FULL_CONFIDENCE_HOURS,
+    BORROWING_RECENT_HOURS,
+    TRADER_PNL_LOOKBACK_HOURS,
+    MOMENTUM_LOOKBACK_HOURS,
+    STACKING_HORIZON_HOURS,
+    STACKING_STEP_HOURS,
+    STACKING_LOOKBACK_HOURS,
+    STACKING_MIN_SAMPLES,
+    STACKING_ITERATIONS,
+};
+
+/// Expected hourly return of a market (fraction of pool value) from one signal
+#[derive(Debug, Clone, Copy)]
+pub struct SignalEstimate {
+    pub expected_return: Decimal,
+    pub confidence: Decimal, // 0 (no information) to 1 (full history behind the estimate)
+}
+
+/// A signal estimating a market's expected hourly LP return from its history
+pub trait ReturnSignal: Send + Sync {
+    /// Name the signal is weighted and logged under
+    fn name(&self) -> &'static str;
+
+    /// Estimate from the first `end` observations of the slice only, so the signal can be evaluated walk-forward.
+    /// None when the window holds too little data.
+    fn estimate(&self, slice: &MarketStateSlice, end: usize) -> Option<SignalEstimate>;
+}
+
+/// Trailing fee APR: EWMA of hourly fees over the pool value (the original fee model)
+pub struct TrailingFeeSignal;
+
+impl ReturnSignal for TrailingFeeSignal {
+    fn name(&self) -> &'static str {
+        "trailing_fees"
+    }
+
+    fn estimate(&self, slice: &MarketStateSlice, end: usize) -> Option<SignalEstimate> {
+        let hourly_fees = fee_model::standardize_to_hourly(&slice.timestamps[..end], &slice.fees_usd[..end])?;
+        let pool_value = pool_value(slice)?;
+        Some(SignalEstimate {
+            expected_return: fee_model::compute_ewma(&hourly_fees)? / pool_value,
+            confidence: coverage_confidence(hourly_fees.len() as i64),
+        })
+    }
+}
+
+/// Predicted borrowing fees: trailing non-borrowing fees plus the recent borrowing fee rate projected forward,
+/// since borrowing accrues at the current utilization rather than the trailing average
+pub struct BorrowingFeeSignal;
+
+impl ReturnSignal for BorrowingFeeSignal {
+    fn name(&self) -> &'static str {
+        "borrowing_fees"
+    }
+
+    fn estimate(&self, slice: &MarketStateSlice, end: usize) -> Option<SignalEstimate> {
+        let timestamps = &slice.timestamps[..end];
+        let other_fees: Vec<Decimal> = slice.fees_usd[..end].iter()
+            .zip(&slice.borrowing_fees_usd[..end])
+            .map(|(total, borrowing)| *total - *borrowing)
+            .collect();
+        let hourly_other_fees = fee_model::standardize_to_hourly(timestamps, &other_fees)?;
+        let hourly_borrowing_fees = fee_model::standardize_to_hourly(timestamps, &slice.borrowing_fees_usd[..end])?;
+        let recent = &hourly_borrowing_fees[hourly_borrowing_fees.len().saturating_sub(BORROWING_RECENT_HOURS as usize)..];
+        let recent_borrowing_rate = recent.iter().sum::<Decimal>() / Decimal::from(recent.len());
+
+        let pool_value = pool_value(slice)?;
+        Some(SignalEstimate {
+            expected_return: (fee_model::compute_ewma(&hourly_other_fees)? + recent_borrowing_rate) / pool_value,
+            confidence: coverage_confidence(hourly_other_fees.len() as i64),
+        })
+    }
+}
+
+/// Trader PnL drag: trailing fees net of the drift in open trader PnL, which the pool pays out when positions close
+pub struct TraderPnlSignal;
+
+impl ReturnSignal for TraderPnlSignal {
+    fn name(&self) -> &'static str {
+        "trader_pnl"
+    }
+
+    fn estimate(&self, slice: &MarketStateSlice, end: usize) -> Option<SignalEstimate> {
+        let fee_estimate = TrailingFeeSignal.estimate(slice, end)?;
+        let start = window_start(slice, end, TRADER_PNL_LOOKBACK_HOURS)?;
+        let hours = window_hours(slice, start, end)?;
+        let pnl_drift = (slice.trader_pnl_usd[end - 1] - slice.trader_pnl_usd[start]) / hours;
+        Some(SignalEstimate {
+            expected_return: fee_estimate.expected_return - pnl_drift / pool_value(slice)?,
+            confidence: fee_estimate.confidence.min(coverage_confidence(hours.to_i64().unwrap_or(0))),
+        })
+    }
+}
+
+/// GM price momentum: hourly GM price return over the lookback, capturing fees, trader PnL and price impact together
+/// (and the unhedged index exposure, so it is the noisiest signal)
+pub struct MomentumSignal;
+
+impl ReturnSignal for MomentumSignal {
+    fn name(&self) -> &'static str {
+        "momentum"
+    }
+
+    fn estimate(&self, slice: &MarketStateSlice, end: usize) -> Option<SignalEstimate> {
+        let start = window_start(slice, end, MOMENTUM_LOOKBACK_HOURS)?;
+        let hours = window_hours(slice, start, end)?;
+        let (first_price, last_price) = (slice.gm_prices[start], slice.gm_prices[end - 1]);
+        if first_price <= Decimal::ZERO || last_price <= Decimal::ZERO {
+            return None;
+        }
+        Some(SignalEstimate {
+            expected_return: (last_price / first_price - Decimal::ONE) / hours,
+            confidence: coverage_confidence(hours.to_i64().unwrap_or(0)),
+        })
+    }
+}
+
+/// How the signal estimates of a market are combined into its expected return
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Combiner {
+    WeightedAverage, // Configured weights scaled by each estimate's confidence
+    Stacking,        // Non-negative weights fitted walk-forward against realized returns
+}
+
+/// Net LP carry of each market in slice order: trailing trading fees plus the projected borrowing fees, leaving out the
+/// directional trader PnL and momentum signals. Markets without enough history get zero.
+pub fn carry_returns(slices: &[MarketStateSlice]) -> Array1<Decimal> {
+    Array1::from_iter(slices.iter().map(|slice| {
+        BorrowingFeeSignal.estimate(slice, slice.timestamps.len())
+            .map_or(Decimal::ZERO, |estimate| estimate.expected_return)
+    }))
+}
+
+/// Ensemble of return signals producing the expected return vector fed to the allocator
+pub struct ReturnEnsemble {
+    signals: Vec<Box<dyn ReturnSignal>>,
+    weights: HashMap<String, Decimal>,
+    combiner: Combiner,
+    external_signal_trust: HashMap<Address, Decimal>, // Signers whose posted expected returns are blended in, by trust
+}
+
+impl ReturnEnsemble {
+    pub fn new(signals: Vec<Box<dyn ReturnSignal>>, weights: HashMap<String, Decimal>, combiner: Combiner) -> Self {
+        Self { signals, weights, combiner, external_signal_trust: HashMap::new() }
+    }
+
+    /// Blend in the external signals posted by these signers, weighted by their trust
+    pub fn with_external_signals(mut self, trust: HashMap<Address, Decimal>) -> Self {
+        self.external_signal_trust = trust;
+        self
+    }
+
+    pub fn external_signal_trust(&self) -> &HashMap<Address, Decimal> {
+        &self.external_signal_trust
+    }
+
+    /// Every built-in signal with the configured weights and combiner
+    pub fn from_config(config: &Config) -> Self {
+        let signals: Vec<Box<dyn ReturnSignal>> = vec![
+            Box::new(TrailingFeeSignal),
+            Box::new(BorrowingFeeSignal),
+            Box::new(TraderPnlSignal),
+            Box::new(MomentumSignal),
+        ];
+        let combiner = match config.return_ensemble_combiner.as_str() {
+            "stacking" => Combiner::Stacking,
+            _ => Combiner::WeightedAverage,
+        };
+        Self::new(signals, config.return_signal_weights.clone(), combiner)
+            .with_external_signals(config.external_signal_trust.clone())
+    }
+
+    /// Expected hourly return of each market in slice order. Markets no signal can estimate get zero.
+    pub fn expected_returns(&self, slices: &[MarketStateSlice]) -> Array1<Decimal> {
+        let estimates: Vec<Vec<Option<SignalEstimate>>> = slices.iter()
+            .map(|slice| self.signals.iter().map(|signal| signal.estimate(slice, slice.timestamps.len())).collect())
+            .collect();
+
+        let stacked_weights = match self.combiner {
+            Combiner::Stacking => self.fit_stacking_weights(slices),
+            Combiner::WeightedAverage => None,
+        };
+
+        Array1::from_iter(slices.iter().zip(&estimates).map(|(slice, market_estimates)| {
+            let expected_return = match &stacked_weights {
+                Some(weights) => combine_stacked(market_estimates, weights),
+                None => self.combine_weighted(market_estimates),
+            };
+            debug!(
+                market = %slice.display_name,
+                expected_return = %expected_return,
+                signals = %self.describe(market_estimates),
+                "Combined return signals"
+            );
+            expected_return
+        }))
+    }
+
+    // ==================== Helper/Private methods ====================
+
+    /// Average of the estimates weighted by configured weight times confidence
+    fn combine_weighted(&self, estimates: &[Option<SignalEstimate>]) -> Decimal {
+        let (weighted_sum, total_weight) = self.signals.iter()
+            .zip(estimates)
+            .filter_map(|(signal, estimate)| {
+                let estimate = (*estimate)?;
+                let weight = self.weights.get(signal.name()).copied().unwrap_or(Decimal::ZERO) * estimate.confidence;
+                Some((weight * estimate.expected_return, weight))
+            })
+            .fold((Decimal::ZERO, Decimal::ZERO), |(sum, total), (value, weight)| (sum + value, total + weight));
+        if total_weight > Decimal::ZERO { weighted_sum / total_weight } else { Decimal::ZERO }
+    }
+
+    /// Fit non-negative signal weights by least squares against the realized return over the following horizon,
+    /// sampling every market at cut-offs through the recent history. None when too few complete samples exist.
+    fn fit_stacking_weights(&self, slices: &[MarketStateSlice]) -> Option<Vec<f64>> {
+        // Samples are appended row by row into one buffer, viewed as a samples × signals matrix for the fit
+        let mut features: Vec<f64> = Vec::new();
+        let mut targets: Vec<f64> = Vec::new();
+        for slice in slices {
+            let Some(last_timestamp) = slice.timestamps.last().copied() else {
+                continue;
+            };
+            let latest_cutoff = last_timestamp - Duration::hours(STACKING_HORIZON_HOURS);
+            let mut cutoff = latest_cutoff - Duration::hours(STACKING_LOOKBACK_HOURS);
+            while cutoff <= latest_cutoff {
+                let end = slice.timestamps.partition_point(|t| *t <= cutoff);
+                let horizon_end = slice.timestamps.partition_point(|t| *t <= cutoff + Duration::hours(STACKING_HORIZON_HOURS));
+                cutoff += Duration::hours(STACKING_STEP_HOURS);
+
+                let Some(target) = realized_return(slice, end, horizon_end) else {
+                    continue;
+                };
+                let sample: Option<Vec<f64>> = self.signals.iter()
+                    .map(|signal| signal.estimate(slice, end).and_then(|estimate| estimate.expected_return.to_f64()))
+                    .collect();
+                if let (Some(sample), Some(target)) = (sample, target.to_f64()) {
+                    features.extend(sample);
+                    targets.push(target);
+                }
+            }
+        }
+
+        if targets.len() < STACKING_MIN_SAMPLES {
+            warn!(samples = targets.len(), "Too few walk-forward samples to fit stacking weights, using weighted average");
+            return None;
+        }
+        let features = Array2::from_shape_vec((targets.len(), self.signals.len()), features).ok()?;
+        let weights = fit_non_negative_least_squares(features.view(), ArrayView1::from(&targets)).to_vec();
+        if weights.iter().all(|w| *w <= 0.0) {
+            warn!("Stacking fit zero weight to every signal, using weighted average");
+            return None;
+        }
+        info!(
+            samples = targets.len(),
+            "Stacking weights fitted: {}",
+            self.signals.iter().zip(&weights).map(|(signal, w)| format!("{}={:.4}", signal.name(), w)).collect::<Vec<_>>().join(", ")
+        );
+        Some(weights)
+    }
+
+    fn describe(&self, estimates: &[Option<SignalEstimate>]) -> String {
+        self.signals.iter()
+            .zip(estimates)
+            .map(|(signal, estimate)| match estimate {
+                Some(estimate) => format!("{}={:.8} (confidence {:.2})", signal.name(), estimate.expected_return, estimate.confidence),
+                None => format!("{}=N/A", signal.name()),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Stacked combination, rescaled over the signals available for the market so a missing signal doesn't shrink it
+fn combine_stacked(estimates: &[Option<SignalEstimate>], weights: &[f64]) -> Decimal {
+    let total_weight: f64 = weights.iter().sum();
+    let (weighted_sum, available_weight) = estimates.iter()
+        .zip(weights)
+        .filter_map(|(estimate, weight)| Some((estimate.as_ref()?.expected_return.to_f64()? * weight, *weight)))
+        .fold((0.0, 0.0), |(sum, total), (value, weight)| (sum + value, total + weight));
+    if available_weight <= 0.0 {
+        return Decimal::ZERO;
+    }
+    Decimal::from_f64(weighted_sum * total_weight / available_weight).unwrap_or(Decimal::ZERO)
+}
+
+/// Hedged LP return realized over observations [end, horizon_end): fees earned net of the change in open trader PnL,
+/// per hour and relative to the current pool value
+fn realized_return(slice: &MarketStateSlice, end: usize, horizon_end: usize) -> Option<Decimal> {
+    if end == 0 || horizon_end <= end || horizon_end > slice.timestamps.len() {
+        return None;
+    }
+    let hours = window_hours(slice, end - 1, horizon_end)?;
+    let fees: Decimal = slice.fees_usd[end..horizon_end].iter().sum();
+    let pnl_change = slice.trader_pnl_usd[horizon_end - 1] - slice.trader_pnl_usd[end - 1];
+    Some((fees - pnl_change) / hours / pool_value(slice)?)
+}
+
+/// Minimize ||Xw - y||² subject to w >= 0 by projected gradient descent
+fn fit_non_negative_least_squares(features: ArrayView2<f64>, targets: ArrayView1<f64>) -> Array1<f64> {
+    // Normal equations: gradient is 2(Gw - b) with G = XᵀX and b = Xᵀy
+    let n = features.ncols();
+    let gram = features.t().dot(&features);
+    let moment = features.t().dot(&targets);
+
+    // Step size from the trace, an upper bound on the largest eigenvalue of G
+    let trace = gram.diag().sum();
+    if trace <= 0.0 {
+        return Array1::zeros(n);
+    }
+    let step = 1.0 / (2.0 * trace);
+    let mut weights = Array1::from_elem(n, 1.0 / n as f64);
+    for _ in 0..STACKING_ITERATIONS {
+        let gradient = (gram.dot(&weights) - &moment) * 2.0;
+        weights.scaled_add(-step, &gradient);
+        weights.mapv_inplace(|w| w.max(0.0));
+    }
+    weights
+}
+
+/// Pool value returns are measured against, as in the fee model
+fn pool_value(slice: &MarketStateSlice) -> Option<Decimal> {
+    let pool_value = slice.pool_long_collateral_usd + slice.pool_short_collateral_usd - slice.impact_pool_usd;
+    (pool_value > Decimal::ZERO).then_some(pool_value)
+}
+
+/// First observation within the lookback before observation `end`
+fn window_start(slice: &MarketStateSlice, end: usize, lookback_hours: i64) -> Option<usize> {
+    let last_timestamp = *slice.timestamps.get(end.checked_sub(1)?)?;
+    let start = slice.timestamps[..end].partition_point(|t| *t < last_timestamp - Duration::hours(lookback_hours));
+    (start < end - 1).then_some(start)
+}
+
+/// Hours between observation `start` and the last observation before `end`
+fn window_hours(slice: &MarketStateSlice, start: usize, end: usize) -> Option<Decimal> {
+    let seconds = (slice.timestamps[end - 1] - slice.timestamps[start]).num_seconds();
+    (seconds > 0).then(|| Decimal::from(seconds) / Decimal::from(3600))
+}
+
+/// Confidence from the hours of history behind an estimate, full at SIGNAL_FULL_CONFIDENCE_HOURS
+fn coverage_confidence(hours: i64) -> Decimal {
+    (Decimal::from(hours.max(0)) / Decimal::from(SIGNAL_FULL_CONFIDENCE_HOURS)).min(Decimal::ONE)
+}
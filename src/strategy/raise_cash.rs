@@ -0,0 +1,233 @@
+use eyre::Result;
+use ethers::types::Address;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use tracing::{debug, info, warn, error, instrument};
+
+use crate::config::Config;
+use crate::config::dynamic::DynamicParams;
+use crate::db::db_manager::DbManager;
+use crate::gmx::datastore;
+use crate::wallet::WalletManager;
+use crate::gm_token_txs::{
+    plan_executor::GmPlanExecutor,
+    types::{GmTxRequest, GmWithdrawalRequest},
+};
+use super::fee_model::{self, SwapPricingParams};
+use super::withdrawal_liquidity;
+
+const RAISE_CASH_PLAN_SOURCE: &str = "raise_cash";
+
+/// Part of one GM position withdrawn to raise cash
+#[derive(Debug, Clone)]
+pub struct PlannedTrim {
+    pub market: Address,
+    pub display_name: String,
+    pub gm_amount: Decimal,
+    pub value_usd: Decimal,                   // Value withdrawn, before the exit cost
+    pub exit_cost_usd: Decimal,               // Estimated GMX withdrawal fee
+    pub expected_return_bps: Option<Decimal>, // Latest strategy run's expected return, None when the run left the market out
+    pub full_exit: bool,
+}
+
+/// Withdrawals raising a cash amount, with what they are expected to deliver
+#[derive(Debug, Clone, Default)]
+pub struct CashRaisePlan {
+    pub amount_usd: Decimal,        // Amount requested
+    pub idle_cash_usd: Decimal,     // Base stablecoin already held, counted towards the amount
+    pub trims: Vec<PlannedTrim>,    // In trim order
+    pub shortfall_usd: Decimal,     // Left unraised when holdings (or pool liquidity) run out
+}
+
+impl CashRaisePlan {
+    /// Withdrawal proceeds net of the estimated exit costs
+    pub fn raised_usd(&self) -> Decimal {
+        self.trims.iter().map(|trim| trim.value_usd - trim.exit_cost_usd).sum()
+    }
+
+    pub fn exit_cost_usd(&self) -> Decimal {
+        self.trims.iter().map(|trim| trim.exit_cost_usd).sum()
+    }
+
+    pub fn withdrawals(&self) -> Vec<GmWithdrawalRequest> {
+        self.trims.iter()
+            .map(|trim| GmWithdrawalRequest { market: trim.market, amount: trim.gm_amount })
+            .collect()
+    }
+}
+
+/// A held position that can be trimmed, with its ranking inputs
+#[derive(Debug, Clone)]
+struct TrimCandidate {
+    market: Address,
+    balance: Decimal,
+    gm_price: Decimal,
+    withdrawable_usd: Decimal,
+    exit_cost_rate: Decimal,
+    expected_return_bps: Option<Decimal>,
+}
+
+impl TrimCandidate {
+    /// What trimming a dollar gives up: the expected return it would have earned plus the cost of getting it out.
+    /// Markets the latest run left out have no expected return to give up, the engine is already exiting them.
+    fn score_bps(&self) -> Decimal {
+        self.expected_return_bps.unwrap_or(Decimal::ZERO) + self.exit_cost_rate * Decimal::from(10_000)
+    }
+}
+
+/// Select the GM positions to trim to raise `amount_usd` in cash, without running the strategy engine: positions
+/// are ranked by the latest strategy run's expected return plus their estimated exit cost, and trimmed cheapest first
+/// until the withdrawals' proceeds net of fees (plus any base stablecoin already held) cover the amount. Trims are
+/// capped at what the pool can currently pay out, and a position left with less than the minimum trade size is
+/// exited in full. Proceeds arrive as the pools' long and short tokens, and hedges sized to the trimmed positions
+/// are re-targeted by the next trading run.
+#[instrument(skip(config, db_manager, params, wallet_manager), fields(on_close = true))]
+pub async fn plan_raise_cash(
+    config: &Config,
+    db_manager: &DbManager,
+    params: &DynamicParams,
+    wallet_manager: &WalletManager,
+    amount_usd: Decimal,
+) -> Result<CashRaisePlan> {
+    if amount_usd <= Decimal::ZERO {
+        return Err(eyre::eyre!("Cash amount to raise must be positive, got {}", amount_usd));
+    }
+    let mut plan = CashRaisePlan { amount_usd, ..Default::default() };
+
+    let idle_cash_usd = match config.base_stablecoin {
+        Some(base_stablecoin) => wallet_manager.get_token_balance(base_stablecoin).await?,
+        None => Decimal::ZERO,
+    };
+    plan.idle_cash_usd = idle_cash_usd.min(amount_usd);
+    let mut remaining_usd = amount_usd - plan.idle_cash_usd;
+    if remaining_usd <= Decimal::ZERO {
+        info!(idle_cash_usd = %idle_cash_usd, "Base stablecoin held already covers the amount, nothing to trim");
+        return Ok(plan);
+    }
+
+    let balances = wallet_manager.get_market_token_balances().await?;
+    let mut markets: Vec<Address> = balances.iter()
+        .filter(|(_, balance)| **balance > Decimal::ZERO)
+        .map(|(market, _)| *market)
+        .collect();
+    markets.sort();
+    let gm_prices = db_manager.get_latest_gm_prices_as_of(db_manager.clock.now()).await?;
+    let factors = datastore::get_swap_pricing_factors_batch(config, &markets).await?;
+    let display_names = db_manager.get_market_display_names().await?;
+
+    // Expected returns of the latest run, keyed by market address
+    let mut expected_returns: HashMap<Address, Decimal> = HashMap::new();
+    if let Some(run) = db_manager.get_latest_strategy_run().await? {
+        let address_by_id: HashMap<i32, Address> = db_manager.market_id_map.iter().map(|(address, id)| (*id, *address)).collect();
+        for run_market in db_manager.get_strategy_run_markets(run.id).await? {
+            if let Some(address) = address_by_id.get(&run_market.market_id) {
+                expected_returns.insert(*address, run_market.expected_return_bps);
+            }
+        }
+    } else {
+        warn!("No strategy run recorded, positions are ranked by exit cost only");
+    }
+
+    let mut candidates: Vec<TrimCandidate> = Vec::new();
+    for market in markets {
+        let balance = balances[&market];
+        let (Some((gm_price, _)), Some(factors)) = (gm_prices.get(&market).copied(), factors.get(&market)) else {
+            warn!(market = ?market, "No GM price or pricing factors, position left out");
+            continue;
+        };
+        let withdrawable_gm = match withdrawal_liquidity::get_withdrawal_capacity(config, db_manager, market).await {
+            Ok(Some(capacity)) => capacity.buffered_gm_amount().min(balance),
+            Ok(None) => balance,
+            Err(e) => {
+                warn!(market = ?market, error = ?e, "Failed to read withdrawal limits, trim left uncapped");
+                balance
+            }
+        };
+        let exit_cost_rate = fee_model::withdrawal_cost(Decimal::ONE, &SwapPricingParams::from_factors(factors)).total_usd();
+        candidates.push(TrimCandidate {
+            market,
+            balance,
+            gm_price,
+            withdrawable_usd: withdrawable_gm * gm_price,
+            exit_cost_rate,
+            expected_return_bps: expected_returns.get(&market).copied(),
+        });
+    }
+    candidates.sort_by(|a, b| a.score_bps().cmp(&b.score_bps()).then(a.market.cmp(&b.market)));
+
+    let min_trade_size = params.min_trade_size_usd;
+    for candidate in candidates {
+        if remaining_usd <= Decimal::ZERO {
+            break;
+        }
+        // Gross up so the proceeds net of the exit cost cover what is left
+        let needed_usd = (remaining_usd / (Decimal::ONE - candidate.exit_cost_rate)).max(min_trade_size);
+        let position_usd = candidate.balance * candidate.gm_price;
+        let mut value_usd = needed_usd.min(candidate.withdrawable_usd);
+        let full_exit = position_usd - value_usd < min_trade_size && candidate.withdrawable_usd >= position_usd;
+        if full_exit {
+            value_usd = position_usd;
+        }
+        if value_usd < min_trade_size {
+            continue;
+        }
+
+        let exit_cost_usd = value_usd * candidate.exit_cost_rate;
+        let gm_amount = if full_exit { candidate.balance } else { value_usd / candidate.gm_price };
+        remaining_usd -= value_usd - exit_cost_usd;
+        plan.trims.push(PlannedTrim {
+            market: candidate.market,
+            display_name: display_names.get(&candidate.market).cloned().unwrap_or_else(|| format!("{:?}", candidate.market)),
+            gm_amount,
+            value_usd,
+            exit_cost_usd,
+            expected_return_bps: candidate.expected_return_bps,
+            full_exit,
+        });
+    }
+    plan.shortfall_usd = remaining_usd.max(Decimal::ZERO);
+
+    let trim_summary = plan.trims.iter()
+        .map(|trim| format!(
+            "{}: ${:.2}{} (exit cost ${:.2}, expected return {})",
+            trim.display_name,
+            trim.value_usd,
+            if trim.full_exit { " full exit" } else { "" },
+            trim.exit_cost_usd,
+            trim.expected_return_bps.map(|bps| format!("{:.2}bps", bps)).unwrap_or_else(|| "N/A".to_string())
+        ))
+        .collect::<Vec<_>>()
+        .join("\n  ");
+    info!(
+        amount_usd = %plan.amount_usd,
+        idle_cash_usd = %plan.idle_cash_usd,
+        raised_usd = %plan.raised_usd(),
+        exit_cost_usd = %plan.exit_cost_usd(),
+        shortfall_usd = %plan.shortfall_usd,
+        "Cash raise planned:\n  {}",
+        if plan.trims.is_empty() { "N/A".to_string() } else { trim_summary }
+    );
+    if plan.shortfall_usd > Decimal::ZERO {
+        warn!(shortfall_usd = %plan.shortfall_usd, "Holdings and pool liquidity can't cover the full amount");
+    }
+    debug!(trims = plan.trims.len(), "Cash raise trims selected");
+    Ok(plan)
+}
+
+/// Submit the cash raise withdrawals as a persisted plan of the given rebalance. Returns the number of withdrawals confirmed.
+#[instrument(skip(plan_executor, plan), fields(trim_count = plan.trims.len(), on_close = true))]
+pub async fn execute_cash_raise(plan_executor: &GmPlanExecutor, plan: &CashRaisePlan, rebalance_id: Option<i32>) -> usize {
+    let requests: Vec<GmTxRequest> = plan.withdrawals().into_iter().map(GmTxRequest::Withdrawal).collect();
+    if requests.is_empty() {
+        return 0;
+    }
+    let submitted = match plan_executor.execute_requests(RAISE_CASH_PLAN_SOURCE, &requests, rebalance_id).await {
+        Ok(submitted) => submitted,
+        Err(e) => {
+            error!(error = ?e, "Failed to execute cash raise plan");
+            0
+        }
+    };
+    info!(submitted = submitted, planned = requests.len(), "Cash raise withdrawals submitted");
+    submitted
+}
@@ -0,0 +1,195 @@
+use eyre::Result;
+use ethers::types::Address;
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use tracing::{debug, info, error, instrument};
+
+use crate::config::dynamic::DynamicParams;
+use crate::wallet::WalletManager;
+use crate::gm_token_txs::{
+    plan_executor::GmPlanExecutor,
+    types::{GmTxRequest, GmShiftRequest, GmWithdrawalRequest},
+};
+use super::types::{PortfolioData, PortfolioSnapshot};
+
+const REBALANCE_PLAN_SOURCE: &str = "rebalance";
+
+/// Capital to add to a market, sized into long/short token amounts when the deposit is executed
+#[derive(Debug, Clone)]
+pub struct PlannedDeposit {
+    pub market: Address,
+    pub value_usd: Decimal,
+}
+
+/// GM moves taking the current holdings to the target weights. Capital leaving one market for another with the same
+/// long and short tokens moves as a single shift, paying one execution fee and no deposit/withdrawal price impact
+/// instead of a withdraw, swap and deposit round trip; only what can't be shifted is withdrawn or deposited.
+#[derive(Debug, Clone, Default)]
+pub struct RebalancePlan {
+    pub shifts: Vec<GmShiftRequest>,
+    pub withdrawals: Vec<GmWithdrawalRequest>,
+    pub deposits: Vec<PlannedDeposit>,
+}
+
+impl RebalancePlan {
+    pub fn is_empty(&self) -> bool {
+        self.shifts.is_empty() && self.withdrawals.is_empty() && self.deposits.is_empty()
+    }
+
+    /// Shift and withdrawal requests, shifts first. Deposits are not included, they are sized from the tokens held
+    /// once the withdrawals have settled.
+    pub fn gm_requests(&self) -> Vec<GmTxRequest> {
+        self.shifts.iter().cloned().map(GmTxRequest::Shift)
+            .chain(self.withdrawals.iter().cloned().map(GmTxRequest::Withdrawal))
+            .collect()
+    }
+
+    /// Shift and withdrawal requests touching only the given markets, the part of the plan approved when a plan is
+    /// approved action by action. A shift needs both of its markets approved.
+    pub fn gm_requests_within(&self, markets: &HashSet<Address>) -> Vec<GmTxRequest> {
+        self.gm_requests().into_iter()
+            .filter(|request| match request {
+                GmTxRequest::Shift(shift) => markets.contains(&shift.from_market) && markets.contains(&shift.to_market),
+                GmTxRequest::Withdrawal(withdrawal) => markets.contains(&withdrawal.market),
+                _ => false,
+            })
+            .collect()
+    }
+}
+
+/// Excess or shortfall of one market against its target, in USD and (for held markets) GM tokens per USD
+#[derive(Debug, Clone)]
+struct MarketImbalance {
+    market: Address,
+    amount_usd: Decimal,
+    gm_per_usd: Decimal,
+}
+
+/// Turn the target weights into GM moves. Markets are grouped by their (long, short) token pair, GMX only shifts
+/// between markets sharing both, and within a group the largest over-weight market is matched with the largest
+/// under-weight one until either side runs out. Leftover excess is withdrawn and leftover shortfall deposited.
+/// GM amounts are converted at the snapshot's own valuation (balance over value held), so a full exit moves the
+/// whole balance. Moves worth less than the minimum trade size are left out.
+/// Each move is recorded in the plan notes of the markets involved.
+#[instrument(skip(params, portfolio_data, current_portfolio, wallet_manager), fields(on_close = true))]
+pub async fn plan_rebalance(
+    params: &DynamicParams,
+    portfolio_data: &mut PortfolioData,
+    current_portfolio: &PortfolioSnapshot,
+    wallet_manager: &WalletManager,
+) -> Result<RebalancePlan> {
+    let total_value = current_portfolio.total_value_usd;
+    if total_value <= Decimal::ZERO {
+        return Ok(RebalancePlan::default());
+    }
+    let balances = wallet_manager.get_market_token_balances().await?;
+
+    // Held markets missing from the target are exited completely
+    let mut targets: HashMap<Address, Decimal> = current_portfolio.weights.keys().map(|address| (*address, Decimal::ZERO)).collect();
+    for (i, address) in portfolio_data.market_addresses.iter().enumerate() {
+        targets.insert(*address, portfolio_data.weights[i]);
+    }
+
+    let mut groups: BTreeMap<(Address, Address), (Vec<MarketImbalance>, Vec<MarketImbalance>)> = BTreeMap::new();
+    for (market, target_weight) in targets {
+        let current_weight = current_portfolio.weights.get(&market).copied().unwrap_or(Decimal::ZERO);
+        let delta_usd = (target_weight - current_weight) * total_value;
+        if delta_usd.is_zero() {
+            continue;
+        }
+        // Markets missing from the token catalog get their own group, they can't be matched for a shift
+        let pair = wallet_manager.market_token(&market)
+            .map(|token| (token.long_token_address, token.short_token_address))
+            .unwrap_or((market, market));
+        let held_usd = current_weight * total_value;
+        let gm_per_usd = if held_usd > Decimal::ZERO {
+            balances.get(&market).copied().unwrap_or(Decimal::ZERO) / held_usd
+        } else {
+            Decimal::ZERO
+        };
+        let imbalance = MarketImbalance { market, amount_usd: delta_usd.abs(), gm_per_usd };
+        let (excess, shortfall) = groups.entry(pair).or_default();
+        if delta_usd < Decimal::ZERO {
+            excess.push(imbalance);
+        } else {
+            shortfall.push(imbalance);
+        }
+    }
+
+    let min_trade_size = params.min_trade_size_usd;
+    let mut plan = RebalancePlan::default();
+    let mut notes: Vec<(Address, String)> = Vec::new();
+    for (_, (mut excess, mut shortfall)) in groups {
+        excess.sort_by(|a, b| b.amount_usd.cmp(&a.amount_usd).then(a.market.cmp(&b.market)));
+        shortfall.sort_by(|a, b| b.amount_usd.cmp(&a.amount_usd).then(a.market.cmp(&b.market)));
+
+        let (mut i, mut j) = (0, 0);
+        while i < excess.len() && j < shortfall.len() {
+            let value_usd = excess[i].amount_usd.min(shortfall[j].amount_usd);
+            if value_usd >= min_trade_size {
+                plan.shifts.push(GmShiftRequest {
+                    from_market: excess[i].market,
+                    to_market: shortfall[j].market,
+                    amount: value_usd * excess[i].gm_per_usd,
+                });
+                notes.push((excess[i].market, format!("Shift ${:.2} out to {}", value_usd, display_name(portfolio_data, wallet_manager, shortfall[j].market))));
+                notes.push((shortfall[j].market, format!("Shift ${:.2} in from {}", value_usd, display_name(portfolio_data, wallet_manager, excess[i].market))));
+            }
+            excess[i].amount_usd -= value_usd;
+            shortfall[j].amount_usd -= value_usd;
+            if excess[i].amount_usd.is_zero() {
+                i += 1;
+            }
+            if shortfall[j].amount_usd.is_zero() {
+                j += 1;
+            }
+        }
+
+        for remaining in excess.iter().filter(|imbalance| imbalance.amount_usd >= min_trade_size) {
+            plan.withdrawals.push(GmWithdrawalRequest {
+                market: remaining.market,
+                amount: remaining.amount_usd * remaining.gm_per_usd,
+            });
+            notes.push((remaining.market, format!("Withdraw ${:.2}, no market with the same collateral to shift into", remaining.amount_usd)));
+        }
+        for remaining in shortfall.iter().filter(|imbalance| imbalance.amount_usd >= min_trade_size) {
+            plan.deposits.push(PlannedDeposit { market: remaining.market, value_usd: remaining.amount_usd });
+            notes.push((remaining.market, format!("Deposit ${:.2}, no market with the same collateral to shift from", remaining.amount_usd)));
+        }
+    }
+
+    for (market, note) in notes {
+        portfolio_data.add_note(market, note);
+    }
+    debug!(
+        shifts = plan.shifts.len(),
+        withdrawals = plan.withdrawals.len(),
+        deposits = plan.deposits.len(),
+        "Rebalance planned"
+    );
+    Ok(plan)
+}
+
+fn display_name(portfolio_data: &PortfolioData, wallet_manager: &WalletManager, market: Address) -> String {
+    portfolio_data.get_market_index(market)
+        .map(|i| portfolio_data.display_names[i].clone())
+        .or_else(|| wallet_manager.market_token(&market).map(|token| token.symbol))
+        .unwrap_or_else(|| format!("{:?}", market))
+}
+
+/// Submit the rebalance's shifts and withdrawals as a persisted plan of the given rebalance. Returns the number of requests confirmed.
+#[instrument(skip(plan_executor, requests), fields(request_count = requests.len(), on_close = true))]
+pub async fn execute_rebalance(plan_executor: &GmPlanExecutor, requests: &[GmTxRequest], rebalance_id: Option<i32>) -> usize {
+    if requests.is_empty() {
+        return 0;
+    }
+    let submitted = match plan_executor.execute_requests(REBALANCE_PLAN_SOURCE, requests, rebalance_id).await {
+        Ok(submitted) => submitted,
+        Err(e) => {
+            error!(error = ?e, "Failed to execute rebalance plan");
+            0
+        }
+    };
+    info!(submitted = submitted, planned = requests.len(), "Rebalance requests submitted");
+    submitted
+}
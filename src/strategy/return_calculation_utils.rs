@@ -0,0 +1,212 @@
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+
+/// How grid points between observations are filled when resampling an irregular series
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillMethod {
+    ForwardFill, // Last observation at or before the grid point
+    Linear,      // Linear interpolation between the observations around the grid point
+}
+
+/// Regular grid of timestamps from start to end (inclusive), starting at the first interval boundary at or after start
+pub fn time_grid(start: DateTime<Utc>, end: DateTime<Utc>, interval: Duration) -> Vec<DateTime<Utc>> {
+    let interval_ms = interval.num_milliseconds();
+    if interval_ms <= 0 || end < start {
+        return Vec::new();
+    }
+
+    let start_ms = start.timestamp_millis();
+    let first_ms = start_ms + (interval_ms - start_ms.rem_euclid(interval_ms)) % interval_ms;
+    let mut grid = Vec::new();
+    let mut t = DateTime::<Utc>::from_timestamp_millis(first_ms);
+    while let Some(ts) = t.filter(|ts| *ts <= end) {
+        grid.push(ts);
+        t = ts.checked_add_signed(interval);
+    }
+    grid
+}
+
+/// Resample a series with ascending timestamps onto the grid. A grid point is None before the first observation, and
+/// when filling it would bridge more than `max_gap`: the last observation is older than that (forward fill) or the
+/// observations around it are further apart than that (linear, which also leaves points after the last observation empty).
+/// Returns None if timestamps and values have different lengths.
+pub fn resample(
+    timestamps: &[DateTime<Utc>],
+    values: &[Decimal],
+    grid: &[DateTime<Utc>],
+    method: FillMethod,
+    max_gap: Duration,
+) -> Option<Vec<Option<Decimal>>> {
+    if timestamps.len() != values.len() {
+        return None;
+    }
+
+    let mut resampled = Vec::with_capacity(grid.len());
+    let mut next = 0; // Index of the first observation after the current grid point
+    for &t in grid {
+        while next < timestamps.len() && timestamps[next] <= t {
+            next += 1;
+        }
+        let Some(prev) = next.checked_sub(1) else {
+            resampled.push(None);
+            continue;
+        };
+
+        let value = if timestamps[prev] == t {
+            Some(values[prev])
+        } else {
+            match method {
+                FillMethod::ForwardFill => (t - timestamps[prev] <= max_gap).then_some(values[prev]),
+                FillMethod::Linear => timestamps.get(next)
+                    .filter(|&&t1| t1 - timestamps[prev] <= max_gap)
+                    .map(|&t1| {
+                        let elapsed = Decimal::from((t - timestamps[prev]).num_milliseconds());
+                        let span = Decimal::from((t1 - timestamps[prev]).num_milliseconds());
+                        values[prev] + (values[next] - values[prev]) * elapsed / span
+                    }),
+            }
+        };
+        resampled.push(value);
+    }
+    Some(resampled)
+}
+
+/// Resample several series onto a common grid covering the period all of them span. Returns the grid and the resampled
+/// values per series in input order, or None if any series is empty or malformed.
+pub fn align_series(
+    series: &[(&[DateTime<Utc>], &[Decimal])],
+    interval: Duration,
+    method: FillMethod,
+    max_gap: Duration,
+) -> Option<(Vec<DateTime<Utc>>, Vec<Vec<Option<Decimal>>>)> {
+    let start = series.iter().map(|(timestamps, _)| timestamps.first().copied()).collect::<Option<Vec<_>>>()?.into_iter().max()?;
+    let end = series.iter().map(|(timestamps, _)| timestamps.last().copied()).collect::<Option<Vec<_>>>()?.into_iter().min()?;
+    let grid = time_grid(start, end, interval);
+
+    let resampled = series.iter()
+        .map(|(timestamps, values)| resample(timestamps, values, &grid, method, max_gap))
+        .collect::<Option<Vec<_>>>()?;
+    Some((grid, resampled))
+}
+
+/// Simple returns of several price series over the same grid steps. Series are aligned onto a common grid and only
+/// steps where every series has a positive price at both ends are kept, so returns never span a gap and the i-th return
+/// of each series covers the same period.
+pub fn aligned_returns(
+    series: &[(&[DateTime<Utc>], &[Decimal])],
+    interval: Duration,
+    method: FillMethod,
+    max_gap: Duration,
+) -> Option<Vec<Vec<Decimal>>> {
+    let (grid, prices) = align_series(series, interval, method, max_gap)?;
+
+    let mut returns = vec![Vec::new(); series.len()];
+    for step in 1..grid.len() {
+        let step_prices: Option<Vec<(Decimal, Decimal)>> = prices.iter()
+            .map(|p| match (p[step - 1], p[step]) {
+                (Some(p0), Some(p1)) if p0 > Decimal::ZERO && p1 > Decimal::ZERO => Some((p0, p1)),
+                _ => None,
+            })
+            .collect();
+        if let Some(step_prices) = step_prices {
+            for (series_returns, (p0, p1)) in returns.iter_mut().zip(step_prices) {
+                series_returns.push((p1 - p0) / p0);
+            }
+        }
+    }
+    Some(returns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(minutes: i64) -> DateTime<Utc> {
+        DateTime::<Utc>::from_timestamp(minutes * 60, 0).unwrap()
+    }
+
+    fn decimals(values: &[i64]) -> Vec<Decimal> {
+        values.iter().map(|v| Decimal::from(*v)).collect()
+    }
+
+    fn some(values: &[i64]) -> Vec<Option<Decimal>> {
+        values.iter().map(|v| Some(Decimal::from(*v))).collect()
+    }
+
+    #[test]
+    fn forward_fill_stops_after_max_gap() {
+        let timestamps = [at(0), at(5), at(30)];
+        let grid: Vec<_> = (-1..=6).map(|i| at(i * 5)).collect();
+
+        let resampled = resample(&timestamps, &decimals(&[1, 2, 3]), &grid, FillMethod::ForwardFill, Duration::minutes(15)).unwrap();
+
+        // Nothing before the first observation, the 5 minute observation fills up to 15 minutes after it (inclusive)
+        let d = |v: i64| Some(Decimal::from(v));
+        assert_eq!(resampled, vec![None, d(1), d(2), d(2), d(2), d(2), None, d(3)]);
+    }
+
+    #[test]
+    fn linear_interpolates_within_max_gap() {
+        let timestamps = [at(0), at(10), at(40)];
+        let values = decimals(&[1, 3, 9]);
+        let grid = time_grid(at(0), at(45), Duration::minutes(5));
+
+        // The 10 to 40 minute span is wider than the gap and nothing follows the last observation
+        let resampled = resample(&timestamps, &values, &grid, FillMethod::Linear, Duration::minutes(15)).unwrap();
+        let d = |v: i64| Some(Decimal::from(v));
+        assert_eq!(resampled, vec![d(1), d(2), d(3), None, None, None, None, None, d(9), None]);
+
+        let resampled = resample(&timestamps, &values, &grid, FillMethod::Linear, Duration::minutes(30)).unwrap();
+        assert_eq!(resampled, vec![d(1), d(2), d(3), d(4), d(5), d(6), d(7), d(8), d(9), None]);
+    }
+
+    #[test]
+    fn resample_rejects_mismatched_lengths() {
+        assert!(resample(&[at(0), at(5)], &decimals(&[1]), &[at(0)], FillMethod::ForwardFill, Duration::minutes(15)).is_none());
+    }
+
+    #[test]
+    fn align_series_covers_common_span_of_misaligned_series() {
+        // Series a starts before and ends before series b, off the grid boundaries
+        let a_timestamps: Vec<_> = (0..7).map(|i| at(2 + i * 5)).collect();
+        let a_values = decimals(&[1, 2, 3, 4, 5, 6, 7]);
+        let b_timestamps: Vec<_> = (0..5).map(|i| at(10 + i * 10)).collect();
+        let b_values = decimals(&[10, 20, 30, 40, 50]);
+        let series = [(a_timestamps.as_slice(), a_values.as_slice()), (b_timestamps.as_slice(), b_values.as_slice())];
+
+        let (grid, resampled) = align_series(&series, Duration::minutes(5), FillMethod::ForwardFill, Duration::minutes(15)).unwrap();
+
+        assert_eq!(grid, vec![at(10), at(15), at(20), at(25), at(30)]);
+        assert_eq!(resampled[0], some(&[2, 3, 4, 5, 6]));
+        assert_eq!(resampled[1], some(&[10, 10, 20, 20, 30]));
+
+        // Every step is covered by both series, so the returns line up step for step
+        let returns = aligned_returns(&series, Duration::minutes(5), FillMethod::ForwardFill, Duration::minutes(15)).unwrap();
+        assert_eq!(returns[0].len(), 4);
+        assert_eq!(returns[1], vec![Decimal::ZERO, Decimal::ONE, Decimal::ZERO, Decimal::from(1) / Decimal::from(2)]);
+    }
+
+    #[test]
+    fn aligned_returns_skip_steps_with_a_gap_in_any_series() {
+        let a_timestamps: Vec<_> = (0..=8).map(|i| at(i * 5)).collect();
+        let a_values = decimals(&[1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        let b_timestamps = [at(0), at(5), at(30), at(35), at(40)];
+        let b_values = decimals(&[1, 1, 1, 1, 1]);
+        let series = [(a_timestamps.as_slice(), a_values.as_slice()), (b_timestamps.as_slice(), b_values.as_slice())];
+
+        // b can't be filled at 25 minutes, dropping the 20-25 and 25-30 steps from both series
+        let returns = aligned_returns(&series, Duration::minutes(5), FillMethod::ForwardFill, Duration::minutes(15)).unwrap();
+        assert_eq!(returns[0].len(), 6);
+        assert_eq!(returns[1].len(), 6);
+        assert_eq!(returns[0][3], Decimal::ONE / Decimal::from(4)); // 4 -> 5 at 15-20 minutes
+        assert_eq!(returns[0][4], Decimal::ONE / Decimal::from(7)); // 7 -> 8 at 30-35 minutes
+    }
+
+    #[test]
+    fn align_series_rejects_empty_series() {
+        let timestamps = [at(0)];
+        let values = decimals(&[1]);
+        let series = [(timestamps.as_slice(), values.as_slice()), (&[][..], &[][..])];
+        assert!(align_series(&series, Duration::minutes(5), FillMethod::ForwardFill, Duration::minutes(15)).is_none());
+    }
+}
@@ -1,3 +1,119 @@
 // --- FEE MODEL CONSTANTS ---
 /// EWMA smoothing factor
 pub const EWMA_ALPHA: f64 = 0.0286; // Corresponds to half life of ~24 hours for hourly data
+
+// --- RETURN ENSEMBLE CONSTANTS ---
+/// Hours of history at which a signal estimate gets full confidence, shorter windows are scaled down linearly
+pub const SIGNAL_FULL_CONFIDENCE_HOURS: i64 = 7 * 24;
+/// Recent window whose borrowing fee rate is projected forward (borrowing accrues at the current utilization)
+pub const BORROWING_RECENT_HOURS: i64 = 6;
+/// Window over which the drift of open trader PnL is measured
+pub const TRADER_PNL_LOOKBACK_HOURS: i64 = 72;
+/// Window over which GM price momentum is measured
+pub const MOMENTUM_LOOKBACK_HOURS: i64 = 72;
+/// Signal weights used when RETURN_SIGNAL_WEIGHTS is unset
+pub const DEFAULT_RETURN_SIGNAL_WEIGHTS: &str = "trailing_fees=1.0,borrowing_fees=0.5,trader_pnl=0.5,momentum=0.25";
+/// Realized return horizon signals are fitted against when stacking
+pub const STACKING_HORIZON_HOURS: i64 = 24;
+/// Spacing of the walk-forward cut-offs stacking samples signals at
+pub const STACKING_STEP_HOURS: i64 = 12;
+/// History stacking samples cut-offs from, the most recent horizon excluded
+pub const STACKING_LOOKBACK_HOURS: i64 = 7 * 24;
+/// Minimum walk-forward samples to fit stacking weights, fewer falls back to the weighted average
+pub const STACKING_MIN_SAMPLES: usize = 30;
+/// Projected gradient iterations of the non-negative least squares fit
+pub const STACKING_ITERATIONS: usize = 500;
+
+// --- RETURN SERIES ALIGNMENT CONSTANTS ---
+/// Interval of the common grid index price series are resampled onto before computing returns
+pub const RETURN_RESAMPLE_INTERVAL_MINUTES: i64 = 5; // Matches the data collection interval
+/// Maximum age of the observation a grid point is filled from, larger gaps are left empty rather than bridged
+pub const RETURN_RESAMPLE_MAX_GAP_MINUTES: i64 = 15;
+
+// --- DATA QUALITY CONSTANTS ---
+/// Relative move between consecutive GM or index price observations counted as a price anomaly
+pub const DATA_QUALITY_ANOMALY_MOVE: f64 = 0.1; // 10% in one 5 minute step
+/// Score lost per unit of anomalous price steps (share of steps), e.g. 1% anomalous steps cost 10% of the score
+pub const DATA_QUALITY_ANOMALY_PENALTY: f64 = 10.0;
+/// Lowest data quality score, bounding how far returns are shrunk and variance inflated
+pub const DATA_QUALITY_MIN_SCORE: f64 = 0.1;
+
+// --- HEDGE CARRY CONSTANTS ---
+/// Window over which recorded dYdX funding rates are averaged to model hedge carry
+pub const FUNDING_RATE_LOOKBACK_HOURS: i64 = 72;
+/// Incentive emissions recorded longer ago than this are treated as ended
+pub const INCENTIVE_STALENESS_HOURS: i64 = 24;
+
+// --- LIQUIDATION RISK CONSTANTS ---
+/// Window over which recorded liquidations are measured against pool value
+pub const LIQUIDATION_LOOKBACK_HOURS: i64 = 7 * 24;
+/// Variance uplift per unit of liquidation intensity (window plus peak hour liquidations as a fraction of pool value)
+pub const LIQUIDATION_VARIANCE_SENSITIVITY: f64 = 5.0;
+/// Cap on the variance multiplier, so one cascade can't push a market out of the portfolio on its own
+pub const LIQUIDATION_MAX_VARIANCE_UPLIFT: f64 = 2.0;
+
+// --- VOLATILITY TERM STRUCTURE CONSTANTS ---
+/// Horizons realized index variance is measured at, shortest first
+pub const VOLATILITY_HORIZONS_HOURS: [i64; 5] = [1, 4, 24, 72, 7 * 24];
+/// Minimum history, in non-overlapping windows, for a horizon's variance to be measured
+pub const VOLATILITY_MIN_WINDOWS: usize = 4;
+/// Bounds on the ratio of a horizon's measured variance to the sampling-interval variance scaled up to it, so a
+/// few windows of trend or mean reversion can't blow up or zero out a market's risk
+pub const VOLATILITY_MIN_VARIANCE_RATIO: f64 = 0.25;
+pub const VOLATILITY_MAX_VARIANCE_RATIO: f64 = 4.0;
+/// Base assets with a Deribit DVOL implied volatility index
+pub const DERIBIT_DVOL_CURRENCIES: [&str; 2] = ["BTC", "ETH"];
+/// Age of the latest DVOL print past which it is ignored
+pub const DERIBIT_DVOL_MAX_AGE_HOURS: i64 = 2;
+
+// --- ALLOCATOR CONSTANTS ---
+/// Maximum number of steepest descent iterations in the Sharpe refinement step
+pub const OPTIMIZER_MAX_ITERS: u64 = 1000;
+/// Cost at which the optimizer stops early
+pub const OPTIMIZER_TARGET_COST: f64 = 1e-6;
+/// Sufficient decrease parameter for the Armijo backtracking line search
+pub const OPTIMIZER_ARMIJO_C: f64 = 1e-4;
+/// Weight of the quadratic penalties enforcing sum-to-one and non-negativity
+pub const OPTIMIZER_CONSTRAINT_PENALTY: f64 = 1000.0;
+/// Values with magnitude below this are treated as zero (weight sums, variances)
+pub const OPTIMIZER_EPSILON: f64 = 1e-10;
+/// Maximum number of passes for the min weight filter and position limit redistribution
+pub const WEIGHT_ADJUSTMENT_MAX_ITERS: usize = 100;
+/// Positions below this weight are zeroed and redistributed
+pub const MIN_POSITION_WEIGHT: f64 = 0.01; // 1% min weight or zero
+/// Maximum weight per asset
+pub const MAX_POSITION_WEIGHT: f64 = 0.25; // 25% max weight per asset
+/// Maximum combined weight of a correlation cluster (raised to 1/cluster count when fewer clusters can't hold the full portfolio)
+pub const MAX_CLUSTER_WEIGHT: f64 = 0.5; // 50% max weight per cluster
+/// Average index-return correlation at or above which clusters are merged
+pub const CLUSTER_CORRELATION_THRESHOLD: f64 = 0.7;
+/// Decimal places weights are rounded to when converted back to Decimal
+pub const WEIGHT_DECIMAL_PLACES: u32 = 8;
+/// Risk aversion λ in the turnover-penalized objective (expected return − λ·variance − κ·turnover)
+pub const ALLOCATOR_RISK_AVERSION: f64 = 1.0;
+/// Turnover penalty κ per unit of weight traded, in hourly return terms (round-trip cost amortized over the holding period)
+pub const ALLOCATOR_TURNOVER_PENALTY: f64 = 5e-6;
+/// Smoothing of |Δweight| in the turnover penalty so its gradient is defined at zero turnover
+pub const TURNOVER_SMOOTHING: f64 = 1e-4;
+
+// --- RETURN MODEL EVALUATION CONSTANTS ---
+/// Holding horizon over which expected (hourly) returns are compared to realized GM token returns
+pub const RETURN_EVALUATION_HORIZON_HOURS: i64 = 24;
+/// Number of most recent evaluated runs included in the calibration report
+pub const RETURN_EVALUATION_REPORT_RUNS: i64 = 30;
+
+// --- EXECUTION FEE BUDGET CONSTANTS ---
+/// Fraction of a fee budget consumed at which an alert is raised
+pub const FEE_BUDGET_ALERT_THRESHOLD: f64 = 0.8;
+
+// --- PORTFOLIO SNAPSHOT CONSTANTS ---
+/// Age of the oldest GM price a portfolio snapshot is valued at past which the snapshot is flagged as stale
+pub const SNAPSHOT_MAX_PRICE_AGE_SECS: i64 = 15 * 60;
+
+// --- WITHDRAWAL LIQUIDITY CONSTANTS ---
+/// Share of a market's withdrawable liquidity the planner may use, headroom for pool moves between the last recorded state and execution
+pub const WITHDRAWAL_LIQUIDITY_BUFFER: f64 = 0.9;
+
+// --- DEPOSIT CAPACITY CONSTANTS ---
+/// Share of a market's remaining pool capacity the planner may deposit, headroom for other deposits landing between the last recorded state and execution
+pub const DEPOSIT_CAPACITY_BUFFER: f64 = 0.9;
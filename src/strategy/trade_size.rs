@@ -0,0 +1,47 @@
+use rust_decimal::Decimal;
+use tracing::{debug, instrument};
+
+use crate::config::dynamic::DynamicParams;
+use super::types::{PortfolioData, PortfolioSnapshot};
+
+/// Reset target weights whose implied trade is worth less than the minimum trade size back to the current holding,
+/// so the plan doesn't pay execution fees on deposits or withdrawals that cost more than they move.
+/// Trade size is the weight change valued at the current GM holdings; nothing is filtered while nothing is held.
+/// Each dropped trade is recorded in the plan notes. Returns the number of trades dropped.
+#[instrument(skip(params, portfolio_data, current_portfolio), fields(on_close = true))]
+pub fn apply_min_trade_size(
+    params: &DynamicParams,
+    portfolio_data: &mut PortfolioData,
+    current_portfolio: &PortfolioSnapshot,
+) -> usize {
+    let min_trade_size = params.min_trade_size_usd;
+    let total_value = current_portfolio.total_value_usd;
+    if min_trade_size <= Decimal::ZERO || total_value <= Decimal::ZERO {
+        return 0;
+    }
+
+    let mut dropped = 0;
+    for i in 0..portfolio_data.market_addresses.len() {
+        let address = portfolio_data.market_addresses[i];
+        let current_weight = current_portfolio.weights.get(&address).copied().unwrap_or(Decimal::ZERO);
+        let target_weight = portfolio_data.weights[i];
+        let trade_size = (target_weight - current_weight).abs() * total_value;
+        if trade_size.is_zero() || trade_size >= min_trade_size {
+            continue;
+        }
+
+        portfolio_data.weights[i] = current_weight;
+        dropped += 1;
+        let note = format!(
+            "{} of ${:.2} dropped below the ${:.2} minimum trade size, target weight {:.2}% -> {:.2}%",
+            if target_weight > current_weight { "Deposit" } else { "Withdrawal" },
+            trade_size,
+            min_trade_size,
+            target_weight * Decimal::from(100),
+            current_weight * Decimal::from(100)
+        );
+        debug!(market = %portfolio_data.display_names[i], "{}", note);
+        portfolio_data.add_note(address, note);
+    }
+    dropped
+}
@@ -1,19 +1,36 @@
 use chrono::{DateTime, Utc};
-use ethers::types::Address;
+use ethers::types::{Address, BlockNumber};
+use std::collections::HashMap;
 use rust_decimal::Decimal;
 use rust_decimal::prelude::*;
 use ndarray::{Array1, Array2};
-use tracing::info;
+use tracing::{debug, info, warn, instrument};
+use eyre::Result;
+use ethers::providers::Middleware;
+
+use crate::wallet::WalletManager;
+use crate::db::db_manager::DbManager;
+use crate::data_ingestion::market::market_descriptor::MarketDescriptor;
+use super::strategy_constants::SNAPSHOT_MAX_PRICE_AGE_SECS;
+use super::feasibility::{AllocationConstraints, RelaxedConstraint};
+use super::volatility::VolatilityTermStructure;
+
+/// Hours per year, converts annual rates to the hourly timestep of the return model
+pub const HOURS_PER_YEAR: i64 = 24 * 365;
 
 /// Historical slice of market data for one GMX market
 #[derive(Debug, Clone)]
 pub struct MarketStateSlice {
     pub market_address: Address,
     pub display_name: String, // e.g. "ETH/USD [WETH - USDC]"
+    pub descriptor: MarketDescriptor,
 
     // --- Historical data ---
     pub timestamps: Vec<DateTime<Utc>>,
     pub fees_usd: Vec<Decimal>,       // Total fees collected per timestep
+    pub borrowing_fees_usd: Vec<Decimal>, // Borrowing fees collected per timestep (included in fees_usd)
+    pub trader_pnl_usd: Vec<Decimal>, // Net PnL of open positions at each timestep, a liability of the pool
+    pub gm_prices: Vec<Decimal>,      // GM token mid price at each timestep
 
     pub index_token_address: Address, 
     pub index_token_symbol: String,   
@@ -43,6 +60,44 @@ pub struct MarketStateSlice {
     pub impact_pool_token_amount: Decimal, // Total impact pool value in index token
 }
 
+impl MarketStateSlice {
+    /// Summarize the data this slice feeds the optimizer, given the volatility derived for it from the covariance matrix
+    /// and its data quality score
+    pub fn input_digest(&self, volatility: Decimal, data_quality: f64) -> MarketInputDigest {
+        let pool_value_usd = self.pool_long_collateral_usd + self.pool_short_collateral_usd;
+        let mean_fees_usd = if self.fees_usd.is_empty() {
+            Decimal::ZERO
+        } else {
+            self.fees_usd.iter().sum::<Decimal>() / Decimal::from(self.fees_usd.len())
+        };
+        MarketInputDigest {
+            market_row_count: self.timestamps.len(),
+            index_price_row_count: self.index_prices.len(),
+            first_timestamp: self.timestamps.first().copied(),
+            last_timestamp: self.timestamps.last().copied(),
+            mean_fee_rate: if pool_value_usd > Decimal::ZERO { mean_fees_usd / pool_value_usd } else { Decimal::ZERO },
+            volatility,
+            last_index_price: self.index_prices.last().copied(),
+            pool_value_usd,
+            data_quality: Decimal::from_f64(data_quality).unwrap_or(Decimal::ZERO),
+        }
+    }
+}
+
+/// Compact digest of the inputs the optimizer saw for one market, persisted with each strategy run
+#[derive(Debug, Clone)]
+pub struct MarketInputDigest {
+    pub market_row_count: usize,
+    pub index_price_row_count: usize,
+    pub first_timestamp: Option<DateTime<Utc>>,
+    pub last_timestamp: Option<DateTime<Utc>>,
+    pub mean_fee_rate: Decimal, // Mean fees per timestep relative to the current pool value
+    pub volatility: Decimal, // Standard deviation from the covariance matrix diagonal
+    pub last_index_price: Option<Decimal>,
+    pub pool_value_usd: Decimal,
+    pub data_quality: Decimal, // Score the market's returns and variance were discounted by, 1 for clean data
+}
+
 /// Portfolio data containing returns and covariance matrix with consistent ordering
 #[derive(Debug, Clone)]
 pub struct PortfolioData {
@@ -51,15 +106,25 @@ pub struct PortfolioData {
     pub expected_returns: Array1<Decimal>,
     pub covariance_matrix: Array2<Decimal>,
     pub weights: Array1<Decimal>,
+    pub raw_weights: Option<Array1<Decimal>>,      // Optimizer output, set by the weight smoothing layer
+    pub smoothed_weights: Option<Array1<Decimal>>, // Weights after cross-run smoothing, before the planning guards
+    pub input_digests: Vec<MarketInputDigest>,
+    pub notes: HashMap<Address, Vec<String>>, // Constraints applied to the plan per market (e.g. blocked deposits)
+    pub risk_free_rate_apr: Decimal, // Hurdle rate (e.g. USDC lending APR) Sharpe ratios are measured in excess of
+    pub constraints: AllocationConstraints, // Limits the weights were allocated under, after feasibility relaxation
+    pub relaxed_constraints: Vec<RelaxedConstraint>, // Constraints loosened because they conflicted, in relaxation order
+    pub index_volatilities: HashMap<String, VolatilityTermStructure>, // Index volatility term structure by base asset, for hedge bands
+    pub universe: Option<String>, // Market universe the weights were allocated over, None for every market
 }
 
 impl PortfolioData {
-    pub fn new(market_addresses: Vec<Address>, display_names: Vec<String>, expected_returns: Array1<Decimal>, covariance_matrix: Array2<Decimal>, weights: Array1<Decimal>) -> Self {
+    pub fn new(market_addresses: Vec<Address>, display_names: Vec<String>, expected_returns: Array1<Decimal>, covariance_matrix: Array2<Decimal>, weights: Array1<Decimal>, input_digests: Vec<MarketInputDigest>, constraints: AllocationConstraints, relaxed_constraints: Vec<RelaxedConstraint>) -> Self {
         assert_eq!(market_addresses.len(), display_names.len());
         assert_eq!(market_addresses.len(), expected_returns.len());
         assert_eq!(market_addresses.len(), covariance_matrix.nrows());
         assert_eq!(market_addresses.len(), covariance_matrix.ncols());
         assert_eq!(market_addresses.len(), weights.len());
+        assert_eq!(market_addresses.len(), input_digests.len());
         
         Self {
             market_addresses,
@@ -67,9 +132,50 @@ impl PortfolioData {
             expected_returns,
             covariance_matrix,
             weights,
+            raw_weights: None,
+            smoothed_weights: None,
+            input_digests,
+            notes: HashMap::new(),
+            risk_free_rate_apr: Decimal::ZERO,
+            constraints,
+            relaxed_constraints,
+            index_volatilities: HashMap::new(),
+            universe: None,
         }
     }
+
+    /// Risk-free rate per timestep (hourly), in the same units as the expected returns
+    pub fn risk_free_rate(&self) -> Decimal {
+        self.risk_free_rate_apr / Decimal::from(HOURS_PER_YEAR)
+    }
+
+    /// Portfolio expected return, volatility and excess-return Sharpe ratio (per timestep) at the current weights
+    pub fn portfolio_metrics(&self) -> (Decimal, Decimal, Decimal) {
+        let portfolio_return = self.weights.dot(&self.expected_returns);
+        let portfolio_variance = self.weights.dot(&self.covariance_matrix.dot(&self.weights));
+        let portfolio_volatility = portfolio_variance.sqrt().unwrap_or(Decimal::ZERO);
+        let portfolio_sharpe = excess_sharpe(portfolio_return, portfolio_volatility, self.risk_free_rate());
+        (portfolio_return, portfolio_volatility, portfolio_sharpe)
+    }
+
+    /// Attach a plan note to a market
+    pub fn add_note(&mut self, address: Address, note: String) {
+        self.notes.entry(address).or_default().push(note);
+    }
+
+    /// All notes for a market joined into one line, if any
+    pub fn get_notes(&self, address: Address) -> Option<String> {
+        self.notes.get(&address).map(|notes| notes.join("; "))
+    }
     
+    /// Relaxed constraints joined into one line, if any were relaxed
+    pub fn relaxed_constraints_summary(&self) -> Option<String> {
+        if self.relaxed_constraints.is_empty() {
+            return None;
+        }
+        Some(self.relaxed_constraints.iter().map(|r| r.to_string()).collect::<Vec<_>>().join("; "))
+    }
+
     pub fn get_market_index(&self, address: Address) -> Option<usize> {
         self.market_addresses.iter().position(|&addr| addr == address)
     }
@@ -104,7 +210,7 @@ impl PortfolioData {
                 let expected_return = self.expected_returns[i];
                 let variance = self.get_variance(addr).unwrap_or(Decimal::ZERO);
                 let std_dev = variance.sqrt().unwrap_or(Decimal::ZERO);
-                let sharpe = if std_dev > Decimal::ZERO { expected_return / std_dev } else { Decimal::ZERO };
+                let sharpe = excess_sharpe(expected_return, std_dev, self.risk_free_rate());
                 let weight = self.weights[i];
                 
                 (i, self.display_names[i].clone(), expected_return * Decimal::from_f64(10000.0).unwrap(), std_dev * Decimal::from_f64(10000.0).unwrap(), sharpe, weight)
@@ -134,18 +240,110 @@ impl PortfolioData {
         
         // Calculate portfolio metrics
         let total_weight = self.weights.sum();
-        let portfolio_return = self.weights.dot(&self.expected_returns);
-        let portfolio_variance = self.weights.dot(&self.covariance_matrix.dot(&self.weights));
-        let portfolio_volatility = portfolio_variance.sqrt().unwrap_or(Decimal::ZERO);
-        let portfolio_sharpe = if portfolio_volatility > Decimal::ZERO { portfolio_return / portfolio_volatility } else { Decimal::ZERO };
+        let (portfolio_return, portfolio_volatility, portfolio_sharpe) = self.portfolio_metrics();
         
         info!(
-            "Optimal Portfolio (sorted by weight):\n  {}\n\nPortfolio Summary:\n  Total Weight: {:.2}%\n  Expected Return: {:.5}bps\n  Volatility: {:.5}bps\n  Sharpe Ratio: {:.3}",
+            "Optimal Portfolio (sorted by weight):\n  {}\n\nPortfolio Summary:\n  Total Weight: {:.2}%\n  Expected Return: {:.5}bps\n  Volatility: {:.5}bps\n  Risk-Free Rate: {:.2}% APR\n  Sharpe Ratio (excess): {:.3}",
             market_summary,
             total_weight * Decimal::from_f64(100.0).unwrap(),
             portfolio_return * Decimal::from_f64(10000.0).unwrap(),
             portfolio_volatility * Decimal::from_f64(10000.0).unwrap(),
+            self.risk_free_rate_apr * Decimal::from_f64(100.0).unwrap(),
             portfolio_sharpe
         );
     }
 }
+
+/// How stale the data behind a portfolio snapshot was when it was taken
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotStaleness {
+    pub block_age_secs: i64,          // Snapshot time minus the timestamp of the block balances were read at
+    pub max_price_age_secs: i64,      // Age of the oldest GM price a holding was valued at
+    pub unpriced_market_count: usize, // Held markets with no GM price recorded before the snapshot time, left out
+}
+
+/// Current GM holdings expressed as portfolio weights (share of total GM value per market)
+#[derive(Debug, Clone, Default)]
+pub struct PortfolioSnapshot {
+    pub weights: HashMap<Address, Decimal>,
+    pub total_value_usd: Decimal,
+    pub taken_at: DateTime<Utc>,
+    pub block_number: u64, // Block every balance was read at
+    pub staleness: SnapshotStaleness,
+}
+
+impl PortfolioSnapshot {
+    /// Build the snapshot atomically: every GM balance is read in one multicall pinned to a single block and valued
+    /// at the latest GM prices recorded in the database as of the same moment, so balances and prices are consistent
+    /// with each other rather than mixing prices from whenever the token catalog was last refreshed
+    #[instrument(skip(wallet_manager, db_manager))]
+    pub async fn load(wallet_manager: &WalletManager, db_manager: &DbManager) -> Result<Self> {
+        let taken_at = db_manager.clock.now();
+        let block = wallet_manager.signer.provider().get_block(BlockNumber::Latest).await?
+            .ok_or_else(|| eyre::eyre!("Latest block not found"))?;
+        let block_number = block.number.ok_or_else(|| eyre::eyre!("Latest block has no number"))?.as_u64();
+        let block_time = DateTime::<Utc>::from_timestamp(block.timestamp.as_u64() as i64, 0).unwrap_or(taken_at);
+
+        let (balances, prices) = tokio::try_join!(
+            wallet_manager.get_market_token_balances_at(Some(block_number)),
+            async { db_manager.get_latest_gm_prices_as_of(taken_at).await.map_err(eyre::Report::from) },
+        )?;
+
+        let mut staleness = SnapshotStaleness {
+            block_age_secs: (taken_at - block_time).num_seconds().max(0),
+            ..SnapshotStaleness::default()
+        };
+        let mut values: HashMap<Address, Decimal> = HashMap::new();
+        for (address, balance) in balances.into_iter().filter(|(_, balance)| *balance > Decimal::ZERO) {
+            let Some((price, priced_at)) = prices.get(&address) else {
+                staleness.unpriced_market_count += 1;
+                continue;
+            };
+            staleness.max_price_age_secs = staleness.max_price_age_secs.max((taken_at - *priced_at).num_seconds());
+            let value = balance * *price;
+            if value > Decimal::ZERO {
+                values.insert(address, value);
+            }
+        }
+        if staleness.max_price_age_secs > SNAPSHOT_MAX_PRICE_AGE_SECS || staleness.unpriced_market_count > 0 {
+            warn!(
+                block_number = block_number,
+                block_age_secs = staleness.block_age_secs,
+                max_price_age_secs = staleness.max_price_age_secs,
+                unpriced_market_count = staleness.unpriced_market_count,
+                "Portfolio snapshot valued with stale or missing GM prices"
+            );
+        } else {
+            debug!(
+                block_number = block_number,
+                block_age_secs = staleness.block_age_secs,
+                max_price_age_secs = staleness.max_price_age_secs,
+                "Portfolio snapshot taken"
+            );
+        }
+
+        let total_value: Decimal = values.values().sum();
+        let weights = if total_value > Decimal::ZERO {
+            values.into_iter().map(|(address, value)| (address, value / total_value)).collect()
+        } else {
+            HashMap::new()
+        };
+        Ok(Self {
+            weights,
+            total_value_usd: total_value,
+            taken_at,
+            block_number,
+            staleness,
+        })
+    }
+
+    /// Current weights aligned to the given market order, markets not held get zero weight
+    pub fn weights_for(&self, market_addresses: &[Address]) -> Array1<Decimal> {
+        Array1::from_iter(market_addresses.iter().map(|address| self.weights.get(address).copied().unwrap_or(Decimal::ZERO)))
+    }
+}
+
+/// Sharpe ratio of a return in excess of the risk-free rate, zero when volatility is zero
+pub fn excess_sharpe(expected_return: Decimal, volatility: Decimal, risk_free_rate: Decimal) -> Decimal {
+    if volatility > Decimal::ZERO { (expected_return - risk_free_rate) / volatility } else { Decimal::ZERO }
+}
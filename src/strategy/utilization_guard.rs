@@ -0,0 +1,118 @@
+use eyre::Result;
+use ethers::types::Address;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use tracing::{debug, warn, instrument};
+
+use crate::config::Config;
+use crate::db::db_manager::DbManager;
+use crate::db::models::market_states::MarketStateModel;
+use super::types::{PortfolioData, PortfolioSnapshot};
+
+/// Current pool utilization and the share of each collateral side reserved for open positions
+#[derive(Debug, Clone, Copy)]
+pub struct MarketUtilization {
+    pub utilization: Decimal, // Total open interest over total pool liquidity
+    pub long_reserve_ratio: Decimal, // Long OI (valued via index tokens) over the long collateral pool
+    pub short_reserve_ratio: Decimal, // Short OI over the short collateral pool
+}
+
+impl MarketUtilization {
+    pub fn from_market_state(state: &MarketStateModel) -> Self {
+        let pool_long_usd = state.pool_long_token_usd.unwrap_or_default();
+        let pool_short_usd = state.pool_short_token_usd.unwrap_or_default();
+        let total_open_interest = state.open_interest_long.unwrap_or_default() + state.open_interest_short.unwrap_or_default();
+        Self {
+            utilization: state.utilization.unwrap_or_else(|| ratio(total_open_interest, pool_long_usd + pool_short_usd)),
+            long_reserve_ratio: ratio(state.open_interest_long_via_tokens.unwrap_or_default(), pool_long_usd),
+            short_reserve_ratio: ratio(state.open_interest_short.unwrap_or_default(), pool_short_usd),
+        }
+    }
+
+    /// The binding ratio for withdrawals: the most constrained of the whole pool and its two sides
+    pub fn max_ratio(&self) -> Decimal {
+        self.utilization.max(self.long_reserve_ratio).max(self.short_reserve_ratio)
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "utilization {:.2}% (long reserve {:.2}%, short reserve {:.2}%)",
+            self.utilization * Decimal::from(100),
+            self.long_reserve_ratio * Decimal::from(100),
+            self.short_reserve_ratio * Decimal::from(100)
+        )
+    }
+}
+
+/// Cap target weights of markets above the utilization ceiling at their current holding,
+/// so the plan never adds to a pool we may not be able to withdraw from promptly.
+/// Markets not held are blocked (weight zero), held markets are downsized to their current weight;
+/// the freed weight is left undeployed. Each constraint is recorded in the plan notes.
+/// Returns the number of markets constrained.
+#[instrument(skip(config, db_manager, portfolio_data, current_portfolio), fields(on_close = true))]
+pub async fn apply_utilization_ceiling(
+    config: &Config,
+    db_manager: &DbManager,
+    portfolio_data: &mut PortfolioData,
+    current_portfolio: Option<&PortfolioSnapshot>,
+) -> Result<usize> {
+    let ceiling = config.max_deposit_utilization;
+    let latest_states: HashMap<i32, MarketStateModel> = db_manager.get_latest_market_states().await?
+        .into_iter()
+        .map(|state| (state.market_id, state))
+        .collect();
+
+    let mut constrained = 0;
+    for i in 0..portfolio_data.market_addresses.len() {
+        let address = portfolio_data.market_addresses[i];
+        let Some(state) = db_manager.market_id_map.get(&address).and_then(|id| latest_states.get(id)) else {
+            continue;
+        };
+        let utilization = MarketUtilization::from_market_state(state);
+        if utilization.max_ratio() <= ceiling {
+            continue;
+        }
+
+        let current_weight = current_portfolio
+            .and_then(|snapshot| snapshot.weights.get(&address).copied())
+            .unwrap_or(Decimal::ZERO);
+        let target_weight = portfolio_data.weights[i];
+        if target_weight <= current_weight {
+            debug!(market = %portfolio_data.display_names[i], "{} above ceiling but no deposit planned", utilization.describe());
+            continue;
+        }
+
+        portfolio_data.weights[i] = current_weight;
+        constrained += 1;
+        let note = format!(
+            "Deposit {} by utilization ceiling: {} above {:.2}%, target weight {:.2}% -> {:.2}%",
+            if current_weight.is_zero() { "blocked" } else { "downsized to current holding" },
+            utilization.describe(),
+            ceiling * Decimal::from(100),
+            target_weight * Decimal::from(100),
+            current_weight * Decimal::from(100)
+        );
+        warn!(market = %portfolio_data.display_names[i], "{}", note);
+        portfolio_data.add_note(address, note);
+    }
+    Ok(constrained)
+}
+
+/// Refuse a deposit into a market whose latest recorded utilization is above the ceiling
+pub async fn ensure_deposit_within_utilization(config: &Config, db_manager: &DbManager, market: Address) -> Result<()> {
+    let Some(state) = db_manager.get_latest_market_state(market).await? else {
+        return Ok(());
+    };
+    let utilization = MarketUtilization::from_market_state(&state);
+    if utilization.max_ratio() > config.max_deposit_utilization {
+        return Err(eyre::eyre!(
+            "Market {:?} {} is above the {:.2}% deposit ceiling, refusing GM deposit",
+            market, utilization.describe(), config.max_deposit_utilization * Decimal::from(100)
+        ));
+    }
+    Ok(())
+}
+
+fn ratio(numerator: Decimal, denominator: Decimal) -> Decimal {
+    if denominator > Decimal::ZERO { numerator / denominator } else { Decimal::ZERO }
+}
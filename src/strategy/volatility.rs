@@ -0,0 +1,204 @@
+use chrono::{DateTime, Duration, Utc};
+use eyre::Result;
+use ndarray::{Array2, ArrayView1};
+use reqwest::Client;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use tracing::{debug, warn, instrument};
+
+use crate::constants::{DERIBIT_API_URL, DERIBIT_VOLATILITY_INDEX_PATH};
+use crate::hedging::hedge_utils;
+use super::covariance::ReturnMatrix;
+use super::types::{MarketStateSlice, HOURS_PER_YEAR};
+use super::strategy_constants::{
+    RETURN_RESAMPLE_INTERVAL_MINUTES,
+    VOLATILITY_HORIZONS_HOURS,
+    VOLATILITY_MIN_WINDOWS,
+    VOLATILITY_MIN_VARIANCE_RATIO,
+    VOLATILITY_MAX_VARIANCE_RATIO,
+    DERIBIT_DVOL_CURRENCIES,
+    DERIBIT_DVOL_MAX_AGE_HOURS,
+};
+
+const STEPS_PER_HOUR: f64 = 60.0 / RETURN_RESAMPLE_INTERVAL_MINUTES as f64;
+
+/// Variance of a market's index returns measured at several horizons, optionally blended with implied volatility.
+/// Index returns are not independent across 5 minute steps (trends, mean reversion after wicks), so the variance over
+/// a holding period is read from the nearest measured horizon rather than the step variance scaled up.
+#[derive(Debug, Clone)]
+pub struct VolatilityTermStructure {
+    pub step_variance: f64,                 // Variance of one grid step's return
+    pub horizon_variances: Vec<(i64, f64)>, // (Horizon hours, variance over the horizon), shortest first, horizons without enough history left out
+    pub implied_volatility: Option<f64>,    // Annualized implied volatility (e.g. 0.55 for 55%), majors only
+    pub implied_weight: f64,                // Weight of the implied variance in the blend
+}
+
+impl VolatilityTermStructure {
+    /// Expected variance of the index return over the horizon. The realized part is scaled linearly in time from the
+    /// longest measured horizon not beyond it (the shortest measured one for shorter horizons), and blended with the
+    /// implied variance over the horizon when an implied volatility is known.
+    pub fn variance_at(&self, horizon_hours: i64) -> f64 {
+        let horizon = horizon_hours as f64;
+        let realized = match self.horizon_variances.iter().rev()
+            .find(|(hours, _)| *hours <= horizon_hours)
+            .or(self.horizon_variances.first())
+        {
+            Some((hours, variance)) => variance * horizon / *hours as f64,
+            None => self.naive_variance_at(horizon_hours),
+        };
+        match self.implied_volatility {
+            Some(implied_volatility) => {
+                let implied_variance = implied_volatility.powi(2) * horizon / HOURS_PER_YEAR as f64;
+                (1.0 - self.implied_weight) * realized + self.implied_weight * implied_variance
+            }
+            None => realized,
+        }
+    }
+
+    /// Standard deviation of the index return over the horizon, as a fraction of the price
+    pub fn volatility_at(&self, horizon_hours: i64) -> f64 {
+        self.variance_at(horizon_hours).sqrt()
+    }
+
+    /// Step variance times the steps in the horizon, the variance if returns were independent
+    pub fn naive_variance_at(&self, horizon_hours: i64) -> f64 {
+        self.step_variance * horizon_hours as f64 * STEPS_PER_HOUR
+    }
+
+    /// Expected over naive variance at the horizon, within the configured bounds. 1 for flat markets.
+    pub fn variance_ratio(&self, horizon_hours: i64) -> f64 {
+        let naive = self.naive_variance_at(horizon_hours);
+        if naive <= 0.0 {
+            return 1.0;
+        }
+        (self.variance_at(horizon_hours) / naive).clamp(VOLATILITY_MIN_VARIANCE_RATIO, VOLATILITY_MAX_VARIANCE_RATIO)
+    }
+}
+
+/// Term structure of every market in the return matrix, in slice order. Implied volatilities are keyed by base asset
+/// and blended in with `implied_weight`, None leaves every market on realized volatility.
+pub fn term_structures(
+    market_slices: &[MarketStateSlice],
+    return_matrix: &ReturnMatrix,
+    implied_volatilities: &HashMap<String, f64>,
+    implied_weight: Option<f64>,
+) -> Vec<VolatilityTermStructure> {
+    (0..return_matrix.n_markets())
+        .map(|i| {
+            let returns = return_matrix.market_returns(i);
+            let horizon_variances = VOLATILITY_HORIZONS_HOURS.iter()
+                .filter_map(|hours| {
+                    let steps = (*hours as f64 * STEPS_PER_HOUR) as usize;
+                    horizon_variance(returns, steps).map(|variance| (*hours, variance))
+                })
+                .collect();
+            let base_asset = hedge_utils::get_base_asset(&market_slices[i].index_token_symbol);
+            VolatilityTermStructure {
+                step_variance: horizon_variance(returns, 1).unwrap_or(0.0),
+                horizon_variances,
+                implied_volatility: implied_weight.and(implied_volatilities.get(base_asset).copied()),
+                implied_weight: implied_weight.unwrap_or(0.0),
+            }
+        })
+        .collect()
+}
+
+/// Scale the covariance to the risk horizon: each market's variance by its variance ratio at the horizon, and
+/// covariances by the square roots of both ratios, keeping correlations unchanged. The matrix stays in grid step
+/// units the allocator's risk aversion is calibrated to, only the relative risk of the markets changes.
+pub fn apply_horizon_scaling(covariance_matrix: &Array2<Decimal>, term_structures: &[VolatilityTermStructure], horizon_hours: i64) -> Array2<Decimal> {
+    let scale: Vec<Decimal> = term_structures.iter()
+        .map(|term_structure| Decimal::from_f64(term_structure.variance_ratio(horizon_hours).sqrt()).unwrap_or(Decimal::ONE))
+        .collect();
+
+    let mut adjusted_covariance = covariance_matrix.clone();
+    for i in 0..scale.len() {
+        for j in 0..scale.len() {
+            adjusted_covariance[[i, j]] *= scale[i] * scale[j];
+        }
+    }
+    adjusted_covariance
+}
+
+/// Term structures keyed by the base asset of the market's index token, for sizing hedge bands. Markets sharing an
+/// index token share its price series, the first one is kept.
+pub fn by_base_asset(market_slices: &[MarketStateSlice], term_structures: &[VolatilityTermStructure]) -> HashMap<String, VolatilityTermStructure> {
+    let mut by_base_asset = HashMap::new();
+    for (slice, term_structure) in market_slices.iter().zip(term_structures) {
+        by_base_asset.entry(hedge_utils::get_base_asset(&slice.index_token_symbol).to_string())
+            .or_insert_with(|| term_structure.clone());
+    }
+    by_base_asset
+}
+
+/// Unbiased variance of the sums of `steps` consecutive returns, over every overlapping window (Lo-MacKinlay).
+/// None when the history holds fewer than VOLATILITY_MIN_WINDOWS non-overlapping windows.
+fn horizon_variance(returns: ArrayView1<f64>, steps: usize) -> Option<f64> {
+    let n = returns.len();
+    if steps == 0 || n < steps * VOLATILITY_MIN_WINDOWS.max(2) {
+        return None;
+    }
+    let mean = returns.mean()?;
+    let mut cumulative = Vec::with_capacity(n + 1);
+    cumulative.push(0.0);
+    for r in returns.iter() {
+        cumulative.push(cumulative[cumulative.len() - 1] + r);
+    }
+    let sum_of_squares: f64 = (0..=n - steps)
+        .map(|t| (cumulative[t + steps] - cumulative[t] - steps as f64 * mean).powi(2))
+        .sum();
+    let divisor = (n - steps + 1) as f64 * (1.0 - steps as f64 / n as f64);
+    (divisor > 0.0).then(|| sum_of_squares / divisor)
+}
+
+#[derive(Debug, Deserialize)]
+struct VolatilityIndexResponse {
+    result: VolatilityIndexData,
+}
+
+#[derive(Debug, Deserialize)]
+struct VolatilityIndexData {
+    data: Vec<[f64; 5]>, // [Timestamp (ms), open, high, low, close], DVOL in percent
+}
+
+/// Latest Deribit DVOL (30 day implied volatility, annualized) of the majors, by base asset as a fraction.
+/// Currencies whose index can't be fetched or has no print within DERIBIT_DVOL_MAX_AGE_HOURS are left out.
+#[instrument]
+pub async fn fetch_implied_volatilities(now: DateTime<Utc>) -> Result<HashMap<String, f64>> {
+    let client = Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .build()?;
+    let start = now - Duration::hours(DERIBIT_DVOL_MAX_AGE_HOURS);
+
+    let mut implied_volatilities = HashMap::new();
+    for currency in DERIBIT_DVOL_CURRENCIES {
+        let response = client
+            .get(format!("{}{}", DERIBIT_API_URL, DERIBIT_VOLATILITY_INDEX_PATH))
+            .query(&[
+                ("currency", currency.to_string()),
+                ("start_timestamp", start.timestamp_millis().to_string()),
+                ("end_timestamp", now.timestamp_millis().to_string()),
+                ("resolution", "60".to_string()),
+            ])
+            .send()
+            .await
+            .and_then(|res| res.error_for_status());
+        let index = match response {
+            Ok(res) => res.json::<VolatilityIndexResponse>().await,
+            Err(e) => Err(e),
+        };
+        match index {
+            Ok(index) => match index.result.data.iter().max_by(|a, b| a[0].total_cmp(&b[0])) {
+                Some(latest) if latest[4] > 0.0 => {
+                    debug!(currency = currency, dvol = latest[4], "Fetched implied volatility");
+                    implied_volatilities.insert(currency.to_string(), latest[4] / 100.0);
+                }
+                _ => warn!(currency = currency, "No recent implied volatility print, using realized volatility"),
+            },
+            Err(e) => warn!(currency = currency, error = %e, "Failed to fetch implied volatility, using realized volatility"),
+        }
+    }
+    Ok(implied_volatilities)
+}
@@ -0,0 +1,87 @@
+use eyre::Result;
+use ethers::types::Address;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use tracing::{debug, instrument};
+
+use crate::config::Config;
+use crate::db::db_manager::DbManager;
+use super::strategy_constants::WEIGHT_DECIMAL_PLACES;
+use super::types::{PortfolioData, PortfolioSnapshot};
+
+/// Smooth the optimizer's target weights across runs before the planning guards see them, so a large jump between
+/// runs doesn't whipsaw the portfolio. With an EMA weight configured, each target moves only that fraction of the
+/// way from the previous run's smoothed target (the current weight for markets the previous run didn't target).
+/// A smoothed target within the market's drift band of the current weight is then reset to the current weight,
+/// leaving a no-trade region around the holdings. The raw and smoothed weights are kept for the run record.
+/// Each changed target is recorded in the plan notes. Returns the number of targets changed.
+#[instrument(skip(config, db_manager, portfolio_data, current_portfolio), fields(on_close = true))]
+pub async fn apply_weight_smoothing(
+    config: &Config,
+    db_manager: &DbManager,
+    portfolio_data: &mut PortfolioData,
+    current_portfolio: &PortfolioSnapshot,
+) -> Result<usize> {
+    let raw_weights = portfolio_data.weights.clone();
+    portfolio_data.raw_weights = Some(raw_weights.clone());
+
+    // Smoothed targets of the previous run over the same universe, falling back to its final targets for runs recorded
+    // before smoothing
+    let previous_run = db_manager.get_latest_universe_strategy_run(portfolio_data.universe.as_deref()).await?;
+    let previous_targets: Option<HashMap<Address, Decimal>> = match (config.weight_smoothing_alpha, previous_run) {
+        (Some(_), Some(run)) => {
+            let address_by_id: HashMap<i32, Address> = db_manager.market_id_map.iter().map(|(address, id)| (*id, *address)).collect();
+            Some(db_manager.get_strategy_run_markets(run.id).await?
+                .into_iter()
+                .filter_map(|market| Some((*address_by_id.get(&market.market_id)?, market.smoothed_target_weight.unwrap_or(market.target_weight))))
+                .collect())
+        }
+        _ => None,
+    };
+
+    let mut changed = 0;
+    for i in 0..portfolio_data.market_addresses.len() {
+        let address = portfolio_data.market_addresses[i];
+        let raw_weight = raw_weights[i];
+        let current_weight = current_portfolio.weights.get(&address).copied().unwrap_or(Decimal::ZERO);
+        let mut notes = Vec::new();
+
+        let mut weight = raw_weight;
+        if let (Some(alpha), Some(previous_targets)) = (config.weight_smoothing_alpha, &previous_targets) {
+            let previous_weight = previous_targets.get(&address).copied().unwrap_or(current_weight);
+            weight = (alpha * raw_weight + (Decimal::ONE - alpha) * previous_weight).round_dp(WEIGHT_DECIMAL_PLACES);
+            if weight != raw_weight {
+                notes.push(format!(
+                    "Target weight smoothed {:.2}% -> {:.2}% toward the previous target {:.2}%",
+                    raw_weight * Decimal::from(100),
+                    weight * Decimal::from(100),
+                    previous_weight * Decimal::from(100)
+                ));
+            }
+        }
+
+        // Nothing held yet means there is no position to leave alone
+        let band = config.weight_drift_bands.get(&address).copied().unwrap_or(config.weight_drift_band);
+        if band > Decimal::ZERO && current_portfolio.total_value_usd > Decimal::ZERO && weight != current_weight && (weight - current_weight).abs() <= band {
+            notes.push(format!(
+                "Target weight {:.2}% within the {:.2}% drift band, kept at the current {:.2}%",
+                weight * Decimal::from(100),
+                band * Decimal::from(100),
+                current_weight * Decimal::from(100)
+            ));
+            weight = current_weight;
+        }
+
+        portfolio_data.weights[i] = weight;
+        if weight != raw_weight {
+            changed += 1;
+        }
+        for note in notes {
+            debug!(market = %portfolio_data.display_names[i], "{}", note);
+            portfolio_data.add_note(address, note);
+        }
+    }
+
+    portfolio_data.smoothed_weights = Some(portfolio_data.weights.clone());
+    Ok(changed)
+}
@@ -0,0 +1,93 @@
+use eyre::Result;
+use rust_decimal::Decimal;
+use tracing::{info, warn, error, instrument};
+
+use crate::config::Config;
+use crate::db::db_manager::DbManager;
+use crate::wallet::WalletManager;
+use super::withdrawal_liquidity;
+use crate::gm_token_txs::{
+    plan_executor::GmPlanExecutor,
+    types::{GmTxRequest, GmWithdrawalRequest},
+};
+
+const WIND_DOWN_PLAN_SOURCE: &str = "deprecated_market_exits";
+
+/// Build full withdrawals for every GM position held in a market flagged as deprecated, capped at what the
+/// pool can currently pay out so the exit doesn't revert; the remainder is exited by later runs
+#[instrument(skip(config, db_manager, wallet_manager), fields(on_close = true))]
+pub async fn plan_deprecated_market_exits(
+    config: &Config,
+    db_manager: &DbManager,
+    wallet_manager: &WalletManager,
+) -> Result<Vec<GmWithdrawalRequest>> {
+    let deprecated_markets = db_manager.get_deprecated_markets().await?;
+    if deprecated_markets.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let balances = wallet_manager.get_market_token_balances().await?;
+    let mut exits: Vec<GmWithdrawalRequest> = Vec::new();
+    for market in &deprecated_markets {
+        let balance = balances.get(market).copied().unwrap_or(Decimal::ZERO);
+        if balance <= Decimal::ZERO {
+            continue;
+        }
+        let amount = match withdrawal_liquidity::get_withdrawal_capacity(config, db_manager, *market).await {
+            Ok(Some(capacity)) if capacity.buffered_gm_amount() < balance => {
+                warn!(
+                    market = ?market,
+                    balance = %balance,
+                    withdrawable = %capacity.buffered_gm_amount(),
+                    "Deprecated market exit capped by pool liquidity"
+                );
+                capacity.buffered_gm_amount()
+            }
+            Ok(_) => balance,
+            Err(e) => {
+                warn!(market = ?market, error = ?e, "Failed to read withdrawal limits, exit left uncapped");
+                balance
+            }
+        };
+        if amount > Decimal::ZERO {
+            exits.push(GmWithdrawalRequest { market: *market, amount });
+        }
+    }
+
+    let exit_summary = exits.iter()
+        .map(|exit| {
+            let symbol = wallet_manager.market_token(&exit.market)
+                .map(|t| t.symbol.clone())
+                .unwrap_or_else(|| format!("{:?}", exit.market));
+            format!("{}: {} GM", symbol, exit.amount)
+        })
+        .collect::<Vec<_>>()
+        .join("\n  ");
+    info!(
+        deprecated_market_count = deprecated_markets.len(),
+        exit_count = exits.len(),
+        "Deprecated market exits planned:\n  {}",
+        if exits.is_empty() { "N/A".to_string() } else { exit_summary }
+    );
+    Ok(exits)
+}
+
+/// Submit the planned exits one at a time as a persisted plan of the given rebalance, continuing past individual failures.
+/// Returns the number of withdrawals submitted.
+#[instrument(skip(plan_executor, exits), fields(exit_count = exits.len(), on_close = true))]
+pub async fn execute_deprecated_market_exits(
+    plan_executor: &GmPlanExecutor,
+    exits: &[GmWithdrawalRequest],
+    rebalance_id: Option<i32>,
+) -> usize {
+    let requests: Vec<GmTxRequest> = exits.iter().cloned().map(GmTxRequest::Withdrawal).collect();
+    let submitted = match plan_executor.execute_requests(WIND_DOWN_PLAN_SOURCE, &requests, rebalance_id).await {
+        Ok(submitted) => submitted,
+        Err(e) => {
+            error!(error = ?e, "Failed to execute deprecated market exit plan");
+            0
+        }
+    };
+    info!(submitted = submitted, planned = exits.len(), "Deprecated market exits submitted");
+    submitted
+}
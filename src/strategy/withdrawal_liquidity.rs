@@ -0,0 +1,175 @@
+use eyre::Result;
+use ethers::types::Address;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::*;
+use tracing::{debug, warn, instrument};
+
+use crate::config::Config;
+use crate::db::db_manager::DbManager;
+use crate::db::models::market_states::MarketStateModel;
+use crate::data_ingestion::market::market_utils::u256_to_decimal_scaled;
+use crate::gmx::datastore::{self, WithdrawalLimitFactors};
+use super::strategy_constants::{WITHDRAWAL_LIQUIDITY_BUFFER, WEIGHT_DECIMAL_PLACES};
+use super::types::{PortfolioData, PortfolioSnapshot};
+
+/// How much liquidity can currently leave a market through a GM withdrawal before the reserve or max PnL
+/// validation reverts it. A withdrawal takes each collateral side out pro rata to its USD share of the pool,
+/// so every side shrinks by the same fraction of its pool value.
+#[derive(Debug, Clone, Copy)]
+pub struct WithdrawalCapacity {
+    pub max_fraction: Decimal, // Share of the pool that can be withdrawn, set by the most binding side and limit
+    pub max_usd: Decimal,      // Withdrawable pool value in USD
+    pub max_gm_amount: Decimal, // Withdrawable GM tokens at the recorded GM price
+}
+
+impl WithdrawalCapacity {
+    /// None when the state has no pool composition or GM price to size a withdrawal with
+    pub fn from_market_state(state: &MarketStateModel, factors: &WithdrawalLimitFactors) -> Option<Self> {
+        let pool_long_usd = state.pool_long_token_usd.unwrap_or_default();
+        let pool_short_usd = state.pool_short_token_usd.unwrap_or_default();
+        let gm_price = state.gm_price_mid.unwrap_or_default();
+        if pool_long_usd + pool_short_usd <= Decimal::ZERO || gm_price <= Decimal::ZERO {
+            return None;
+        }
+
+        let sides = [
+            (
+                pool_long_usd,
+                state.open_interest_long_via_tokens.unwrap_or_default(),
+                state.pnl_long.unwrap_or_default(),
+                factors.reserve_factor_long,
+                factors.max_pnl_factor_long,
+            ),
+            (
+                pool_short_usd,
+                state.open_interest_short.unwrap_or_default(),
+                state.pnl_short.unwrap_or_default(),
+                factors.reserve_factor_short,
+                factors.max_pnl_factor_short,
+            ),
+        ];
+        let mut max_fraction = Decimal::ONE;
+        for (pool_usd, reserved_usd, pnl_usd, reserve_factor, max_pnl_factor) in sides {
+            // Unset factors are skipped rather than read as "nothing may be reserved"
+            max_fraction = max_fraction
+                .min(headroom(reserved_usd, pool_usd, u256_to_decimal_scaled(reserve_factor)))
+                .min(headroom(pnl_usd, pool_usd, u256_to_decimal_scaled(max_pnl_factor)));
+        }
+
+        let max_usd = max_fraction * (pool_long_usd + pool_short_usd);
+        Some(Self {
+            max_fraction,
+            max_usd,
+            max_gm_amount: max_usd / gm_price,
+        })
+    }
+
+    /// Withdrawable GM tokens after the planning buffer
+    pub fn buffered_gm_amount(&self) -> Decimal {
+        self.max_gm_amount * withdrawal_buffer()
+    }
+
+    /// Withdrawable USD after the planning buffer
+    pub fn buffered_usd(&self) -> Decimal {
+        self.max_usd * withdrawal_buffer()
+    }
+}
+
+/// Withdrawal capacity of a market from its latest recorded state and the datastore reserve and max PnL factors.
+/// None when no usable state has been recorded for the market.
+#[instrument(skip(config, db_manager))]
+pub async fn get_withdrawal_capacity(config: &Config, db_manager: &DbManager, market: Address) -> Result<Option<WithdrawalCapacity>> {
+    let Some(state) = db_manager.get_latest_market_state(market).await? else {
+        return Ok(None);
+    };
+    let factors = datastore::get_withdrawal_limit_factors(config, market).await?;
+    let capacity = WithdrawalCapacity::from_market_state(&state, &factors);
+    debug!(market = ?market, capacity = ?capacity, "Withdrawal capacity computed");
+    Ok(capacity)
+}
+
+/// Raise target weights so no planned withdrawal exceeds what its market can currently pay out (less the
+/// planning buffer); a withdrawal above that reverts at execution and only burns the execution fee.
+/// The remainder is picked up by later runs as reserves free up. Each cap is recorded in the plan notes.
+/// Returns the number of markets capped.
+#[instrument(skip(config, db_manager, portfolio_data, current_portfolio), fields(on_close = true))]
+pub async fn apply_withdrawal_liquidity_cap(
+    config: &Config,
+    db_manager: &DbManager,
+    portfolio_data: &mut PortfolioData,
+    current_portfolio: &PortfolioSnapshot,
+) -> Result<usize> {
+    let total_value = current_portfolio.total_value_usd;
+    if total_value <= Decimal::ZERO {
+        return Ok(0);
+    }
+
+    let mut capped = 0;
+    for i in 0..portfolio_data.market_addresses.len() {
+        let address = portfolio_data.market_addresses[i];
+        let current_weight = current_portfolio.weights.get(&address).copied().unwrap_or(Decimal::ZERO);
+        let target_weight = portfolio_data.weights[i];
+        if target_weight >= current_weight {
+            continue;
+        }
+
+        let withdrawal_usd = (current_weight - target_weight) * total_value;
+        let capacity = match get_withdrawal_capacity(config, db_manager, address).await {
+            Ok(Some(capacity)) => capacity,
+            Ok(None) => continue,
+            Err(e) => {
+                warn!(market = %portfolio_data.display_names[i], error = ?e, "Failed to read withdrawal limits, withdrawal left uncapped");
+                continue;
+            }
+        };
+        let max_withdrawal_usd = capacity.buffered_usd();
+        if withdrawal_usd <= max_withdrawal_usd {
+            continue;
+        }
+
+        let capped_weight = (current_weight - max_withdrawal_usd / total_value).round_dp(WEIGHT_DECIMAL_PLACES);
+        portfolio_data.weights[i] = capped_weight;
+        capped += 1;
+        let note = format!(
+            "Withdrawal capped by pool liquidity: {:.2} USD planned, {:.2} USD withdrawable ({:.2}% of pool), target weight {:.2}% -> {:.2}%",
+            withdrawal_usd,
+            max_withdrawal_usd,
+            capacity.max_fraction * Decimal::from(100),
+            target_weight * Decimal::from(100),
+            capped_weight * Decimal::from(100)
+        );
+        warn!(market = %portfolio_data.display_names[i], "{}", note);
+        portfolio_data.add_note(address, note);
+    }
+    Ok(capped)
+}
+
+/// Refuse a withdrawal larger than the market can currently pay out, it would revert at execution
+pub async fn ensure_withdrawal_within_liquidity(config: &Config, db_manager: &DbManager, market: Address, amount: Decimal) -> Result<()> {
+    let Some(capacity) = get_withdrawal_capacity(config, db_manager, market).await? else {
+        return Ok(());
+    };
+    if amount > capacity.max_gm_amount {
+        return Err(eyre::eyre!(
+            "Withdrawal of {} GM from market {:?} exceeds the {} GM withdrawable ({:.2}% of pool) under its reserve and PnL limits",
+            amount, market, capacity.max_gm_amount.round_dp(6), capacity.max_fraction * Decimal::from(100)
+        ));
+    }
+    Ok(())
+}
+
+/// Share of the pool that can be withdrawn before `amount` exceeds `pool * factor`, in [0, 1].
+/// Unconstrained (one) when the factor is unset or nothing counts against it.
+fn headroom(amount: Decimal, pool_usd: Decimal, factor: Decimal) -> Decimal {
+    if factor <= Decimal::ZERO || amount <= Decimal::ZERO {
+        return Decimal::ONE;
+    }
+    if pool_usd <= Decimal::ZERO {
+        return Decimal::ZERO;
+    }
+    (Decimal::ONE - amount / (pool_usd * factor)).clamp(Decimal::ZERO, Decimal::ONE)
+}
+
+fn withdrawal_buffer() -> Decimal {
+    Decimal::from_f64(WITHDRAWAL_LIQUIDITY_BUFFER).unwrap_or(Decimal::ONE)
+}
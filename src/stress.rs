@@ -0,0 +1,295 @@
+// Stress scenarios: shock index prices and revalue the current portfolio (GM holdings, spot balances and perp hedges)
+// from the latest recorded pool compositions, answering "what happens to us in a crash" without waiting for one.
+use ethers::types::Address;
+use eyre::Result;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use tracing::{info, warn, instrument};
+
+use crate::db::db_manager::DbManager;
+use crate::db::models::market_states::MarketStateModel;
+use crate::hedging::hedge_utils::{self, STABLE_COINS};
+use crate::hedging::hedge_venue::{self, PerpVenue};
+use crate::wallet::WalletManager;
+
+/// Key for the shock applied to every asset not listed explicitly
+const OTHER_ASSETS_KEY: &str = "*";
+
+/// Index price shocks by base asset (e.g. ETH), as fractions (-0.3 for -30%).
+/// Assets without an explicit shock take the default one, except stablecoins which are left unshocked.
+#[derive(Debug, Clone)]
+pub struct StressScenario {
+    pub shocks: HashMap<String, Decimal>,
+    pub default_shock: Decimal,
+}
+
+impl StressScenario {
+    /// Market-wide crash: ETH and BTC -30%, everything else -50%
+    pub fn crash() -> Self {
+        Self {
+            shocks: HashMap::from([
+                ("ETH".to_string(), Decimal::new(-30, 2)),
+                ("BTC".to_string(), Decimal::new(-30, 2)),
+            ]),
+            default_shock: Decimal::new(-50, 2),
+        }
+    }
+
+    /// Parse shocks given as percentages per base asset (e.g. `ETH=-30 BTC=-25 *=-50`), `*` setting the default
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let mut scenario = Self { shocks: HashMap::new(), default_shock: Decimal::ZERO };
+        for arg in args {
+            let (asset, pct) = arg.split_once('=')
+                .ok_or_else(|| eyre::eyre!("Invalid shock '{}', expected ASSET=PERCENT", arg))?;
+            let pct: Decimal = pct.parse().map_err(|_| eyre::eyre!("Invalid shock percentage in '{}'", arg))?;
+            if pct <= -Decimal::ONE_HUNDRED {
+                return Err(eyre::eyre!("Shock '{}' would take the price to zero or below", arg));
+            }
+            let shock = pct / Decimal::ONE_HUNDRED;
+            if asset == OTHER_ASSETS_KEY {
+                scenario.default_shock = shock;
+            } else {
+                scenario.shocks.insert(asset.to_uppercase(), shock);
+            }
+        }
+        Ok(scenario)
+    }
+
+    /// Shock applied to a token, by the asset it tracks (WETH is shocked as ETH)
+    pub fn shock(&self, token_symbol: &str) -> Decimal {
+        let base_asset = hedge_utils::get_base_asset(token_symbol);
+        if let Some(shock) = self.shocks.get(&base_asset.to_uppercase()) {
+            return *shock;
+        }
+        if STABLE_COINS.contains(&token_symbol) {
+            return Decimal::ZERO;
+        }
+        self.default_shock
+    }
+}
+
+impl std::fmt::Display for StressScenario {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut assets: Vec<&String> = self.shocks.keys().collect();
+        assets.sort();
+        for asset in assets {
+            write!(f, "{} {}%, ", asset, (self.shocks[asset] * Decimal::ONE_HUNDRED).normalize())?;
+        }
+        write!(f, "others {}%", (self.default_shock * Decimal::ONE_HUNDRED).normalize())
+    }
+}
+
+/// GM price after the pool's tokens are shocked, None when the recorded state can't be revalued.
+/// Pool value = long and short token value − net trader PnL − impact pool. Under the shock the pool's tokens reprice
+/// and open interest PnL moves against the pool (longs gain as the index rises, shorts as it falls); the GM price
+/// scales with the pool value as the supply is unchanged. Trader PnL caps and pending borrowing fees are ignored,
+/// so pool losses are overstated for pools whose traders hit the PnL cap.
+pub fn stressed_gm_price(state: &MarketStateModel, index_shock: Decimal, long_shock: Decimal, short_shock: Decimal) -> Option<Decimal> {
+    let gm_price = state.gm_price_mid?;
+    let long_usd = state.pool_long_token_usd?;
+    let short_usd = state.pool_short_token_usd?;
+    let pnl_net = state.pnl_net.unwrap_or_default();
+    let impact_usd = state.pool_impact_token_usd.unwrap_or_default();
+    let pool_value = long_usd + short_usd - pnl_net - impact_usd;
+    if pool_value <= Decimal::ZERO {
+        return None;
+    }
+
+    let open_interest_net = state.open_interest_long_via_tokens.unwrap_or_default() - state.open_interest_short_via_tokens.unwrap_or_default();
+    let stressed_pool_value = long_usd * (Decimal::ONE + long_shock)
+        + short_usd * (Decimal::ONE + short_shock)
+        - (pnl_net + open_interest_net * index_shock)
+        - impact_usd * (Decimal::ONE + index_shock);
+    Some((gm_price * stressed_pool_value / pool_value).max(Decimal::ZERO))
+}
+
+/// Kind of holding a stress contribution comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HoldingKind {
+    Gm,
+    Spot,
+    Hedge, // Value is the PnL of the position under the shock, its collateral is held on the venue
+}
+
+impl HoldingKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HoldingKind::Gm => "GM",
+            HoldingKind::Spot => "Spot",
+            HoldingKind::Hedge => "Hedge",
+        }
+    }
+}
+
+/// One holding's value before and after the shock
+#[derive(Debug, Clone)]
+pub struct StressContribution {
+    pub kind: HoldingKind,
+    pub name: String,
+    pub value_before_usd: Decimal,
+    pub value_after_usd: Decimal,
+}
+
+impl StressContribution {
+    pub fn change_usd(&self) -> Decimal {
+        self.value_after_usd - self.value_before_usd
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StressReport {
+    pub scenario: StressScenario,
+    pub contributions: Vec<StressContribution>, // Largest loss first
+    pub unvalued: Vec<String>, // Holdings that couldn't be revalued (missing prices or market state)
+}
+
+impl StressReport {
+    pub fn value_before_usd(&self) -> Decimal {
+        self.contributions.iter().map(|c| c.value_before_usd).sum()
+    }
+
+    pub fn value_after_usd(&self) -> Decimal {
+        self.contributions.iter().map(|c| c.value_after_usd).sum()
+    }
+}
+
+/// Apply a stress scenario to the wallet's current GM and spot holdings and the open perp positions on every venue,
+/// valued at the latest recorded token prices and market states
+#[instrument(skip(wallet_manager, db_manager, venues), fields(scenario = %scenario))]
+pub async fn stress_portfolio(
+    wallet_manager: &WalletManager,
+    db_manager: &DbManager,
+    venues: &[Box<dyn PerpVenue>],
+    scenario: StressScenario,
+) -> Result<StressReport> {
+    let tokens = db_manager.get_all_tokens().await?;
+    let token_prices: HashMap<i32, Decimal> = db_manager.get_latest_token_prices().await?
+        .into_iter()
+        .map(|price| (price.token_id, price.mid_price))
+        .collect();
+    let symbols: HashMap<i32, String> = tokens.iter().map(|token| (token.id, token.symbol.clone())).collect();
+    let market_states: HashMap<i32, MarketStateModel> = db_manager.get_latest_market_states().await?
+        .into_iter()
+        .map(|state| (state.market_id, state))
+        .collect();
+    let markets = db_manager.get_all_markets().await?;
+    let display_names = db_manager.get_market_display_names().await?;
+
+    let mut contributions = Vec::new();
+    let mut unvalued = Vec::new();
+
+    // GM holdings, revalued from their pool composition
+    let gm_balances = wallet_manager.get_market_token_balances().await?;
+    for market in &markets {
+        let Ok(address) = market.address.parse::<Address>() else {
+            continue;
+        };
+        let balance = gm_balances.get(&address).copied().unwrap_or_default();
+        if balance <= Decimal::ZERO {
+            continue;
+        }
+        let name = display_names.get(&address).cloned().unwrap_or_else(|| market.address.clone());
+        let shock_of = |token_id: i32| symbols.get(&token_id).map(|symbol| scenario.shock(symbol));
+        let (Some(state), Some(index_shock), Some(long_shock), Some(short_shock)) = (
+            market_states.get(&market.id),
+            shock_of(market.index_token_id),
+            shock_of(market.long_token_id),
+            shock_of(market.short_token_id),
+        ) else {
+            unvalued.push(name);
+            continue;
+        };
+        let (Some(gm_price), Some(stressed_price)) = (state.gm_price_mid, stressed_gm_price(state, index_shock, long_shock, short_shock)) else {
+            unvalued.push(name);
+            continue;
+        };
+        contributions.push(StressContribution {
+            kind: HoldingKind::Gm,
+            name,
+            value_before_usd: balance * gm_price,
+            value_after_usd: balance * stressed_price,
+        });
+    }
+
+    // Spot balances, including native ETH
+    let mut spot_balances: Vec<(String, Decimal, Decimal)> = Vec::new();
+    let native_token = wallet_manager.native_token();
+    spot_balances.push((native_token.symbol.clone(), wallet_manager.get_native_balance().await?, native_token.last_mid_price_usd));
+    for (address, balance) in wallet_manager.get_asset_token_balances().await? {
+        if let Some(token) = wallet_manager.asset_token(&address) {
+            spot_balances.push((token.symbol, balance, token.last_mid_price_usd));
+        }
+    }
+    for (symbol, balance, price) in spot_balances.into_iter().filter(|(_, balance, _)| *balance > Decimal::ZERO) {
+        let value = balance * price;
+        contributions.push(StressContribution {
+            kind: HoldingKind::Spot,
+            value_after_usd: value * (Decimal::ONE + scenario.shock(&symbol)),
+            value_before_usd: value,
+            name: symbol,
+        });
+    }
+
+    // Perp hedges, priced at the latest price of a token tracking their base asset
+    let mut base_asset_prices: HashMap<String, Decimal> = HashMap::new();
+    for token in &tokens {
+        if let Some(price) = token_prices.get(&token.id) {
+            base_asset_prices.entry(hedge_utils::get_base_asset(&token.symbol).to_string()).or_insert(*price);
+        }
+    }
+    let positions = hedge_venue::get_consolidated_positions(venues).await?;
+    for (base_asset, venue_positions) in positions {
+        for position in venue_positions.into_iter().filter(|position| !position.size.is_zero()) {
+            let name = format!("{} {}", position.venue, position.ticker);
+            let Some(price) = base_asset_prices.get(&base_asset) else {
+                unvalued.push(name);
+                continue;
+            };
+            contributions.push(StressContribution {
+                kind: HoldingKind::Hedge,
+                name,
+                value_before_usd: Decimal::ZERO,
+                value_after_usd: position.size * *price * scenario.shock(&base_asset),
+            });
+        }
+    }
+
+    contributions.sort_by(|a, b| a.change_usd().cmp(&b.change_usd()));
+    if !unvalued.is_empty() {
+        warn!(unvalued = ?unvalued, "Some holdings could not be revalued and are left out of the stress report");
+    }
+    Ok(StressReport { scenario, contributions, unvalued })
+}
+
+/// Log a stress report with each holding's contribution to the change in portfolio value
+pub fn log_stress_report(report: &StressReport) {
+    if report.contributions.is_empty() {
+        info!(scenario = %report.scenario, "No holdings to stress");
+        return;
+    }
+
+    let before = report.value_before_usd();
+    let after = report.value_after_usd();
+    let change_pct = if before > Decimal::ZERO { (after - before) / before * Decimal::ONE_HUNDRED } else { Decimal::ZERO };
+    let contributions = report.contributions.iter()
+        .map(|c| format!(
+            "{} {}: Before=${}, After=${}, Change=${}",
+            c.kind.as_str(),
+            c.name,
+            c.value_before_usd.round_dp(2),
+            c.value_after_usd.round_dp(2),
+            c.change_usd().round_dp(2),
+        ))
+        .collect::<Vec<_>>()
+        .join("\n  ");
+
+    info!(
+        "Stress Scenario ({}):\n  {}\n\nPortfolio: Before=${}, After=${}, Change=${} ({}%)",
+        report.scenario,
+        contributions,
+        before.round_dp(2),
+        after.round_dp(2),
+        (after - before).round_dp(2),
+        change_pct.round_dp(2)
+    );
+}
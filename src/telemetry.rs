@@ -0,0 +1,41 @@
+// Trace context propagation between processes, so one trace follows a data tick from collection through
+// recording to the strategy and execution runs that consume it
+use std::collections::HashMap;
+use opentelemetry::global;
+use opentelemetry::trace::TraceContextExt;
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// W3C trace context header, also used as the Redis stream field and database column carrying it
+pub const TRACEPARENT_FIELD: &str = "traceparent";
+
+/// W3C traceparent of the span, None when spans are not exported (no OTLP endpoint configured)
+pub fn traceparent(span: &Span) -> Option<String> {
+    let mut carrier: HashMap<String, String> = HashMap::new();
+    global::get_text_map_propagator(|propagator| propagator.inject_context(&span.context(), &mut carrier));
+    carrier.remove(TRACEPARENT_FIELD)
+}
+
+/// Continue the trace a traceparent belongs to, making `span` a child of the remote span
+pub fn set_parent(span: &Span, traceparent: &str) {
+    span.set_parent(extract(traceparent));
+}
+
+/// Link `span` to the spans of other traces it consumed the output of. Returns the number of links added.
+pub fn add_links<'a>(span: &Span, traceparents: impl IntoIterator<Item = &'a str>) -> usize {
+    let mut linked = 0;
+    for traceparent in traceparents {
+        let context = extract(traceparent);
+        let span_context = context.span().span_context().clone();
+        if span_context.is_valid() {
+            span.add_link(span_context);
+            linked += 1;
+        }
+    }
+    linked
+}
+
+fn extract(traceparent: &str) -> opentelemetry::Context {
+    let carrier = HashMap::from([(TRACEPARENT_FIELD.to_string(), traceparent.to_string())]);
+    global::get_text_map_propagator(|propagator| propagator.extract(&carrier))
+}
@@ -0,0 +1,101 @@
+// Registry of the transactions the bot itself sends from its wallet. Every send goes through the wallet signer,
+// so wrapping it in a middleware records them all (contract calls, approvals, wraps, swaps, deployments) in one place.
+// The wallet watchdog compares the wallet's on-chain nonce against this registry to spot transactions sent by
+// anyone else holding the key.
+use async_trait::async_trait;
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use sqlx::PgPool;
+use std::sync::OnceLock;
+use thiserror::Error;
+use tracing::{debug, error};
+
+use crate::db::models::wallet_transactions::NewWalletTransactionModel;
+use crate::db::queries::wallet_transactions as wallet_transactions_queries;
+
+/// Middleware registering every transaction sent through it (hash and nonce) in the wallet_transactions table.
+/// Registration starts once a database pool is attached; sends before that, or whose registration fails, are
+/// flagged by the watchdog as external.
+#[derive(Debug)]
+pub struct TxRegistryMiddleware<M> {
+    inner: M,
+    wallet_address: Address,
+    pool: OnceLock<PgPool>,
+}
+
+#[derive(Error, Debug)]
+pub enum TxRegistryError<M: Middleware> {
+    #[error("{0}")]
+    MiddlewareError(M::Error),
+}
+
+impl<M: Middleware> MiddlewareError for TxRegistryError<M> {
+    type Inner = M::Error;
+
+    fn from_err(src: M::Error) -> Self {
+        TxRegistryError::MiddlewareError(src)
+    }
+
+    fn as_inner(&self) -> Option<&Self::Inner> {
+        match self {
+            TxRegistryError::MiddlewareError(e) => Some(e),
+        }
+    }
+}
+
+impl<M: Middleware> TxRegistryMiddleware<M> {
+    pub fn new(inner: M, wallet_address: Address) -> Self {
+        Self {
+            inner,
+            wallet_address,
+            pool: OnceLock::new(),
+        }
+    }
+
+    /// Start registering sent transactions in the database (later calls keep the first pool)
+    pub fn attach(&self, pool: PgPool) {
+        let _ = self.pool.set(pool);
+    }
+
+    async fn register(&self, tx_hash: H256, nonce: Option<U256>) {
+        let (Some(pool), Some(nonce)) = (self.pool.get(), nonce) else {
+            error!(tx_hash = ?tx_hash, "Sent transaction not registered (no database attached or unknown nonce), the wallet watchdog will flag it");
+            return;
+        };
+        let tx = NewWalletTransactionModel {
+            wallet_address: format!("{:?}", self.wallet_address),
+            tx_hash: format!("{:?}", tx_hash),
+            nonce: nonce.as_u64() as i64,
+        };
+        match wallet_transactions_queries::insert_wallet_transaction(pool, &tx).await {
+            Ok(()) => debug!(tx_hash = ?tx_hash, nonce = tx.nonce, "Sent transaction registered"),
+            Err(e) => error!(?e, tx_hash = ?tx_hash, "Failed to register sent transaction, the wallet watchdog will flag it"),
+        }
+    }
+}
+
+#[async_trait]
+impl<M: Middleware> Middleware for TxRegistryMiddleware<M> {
+    type Error = TxRegistryError<M>;
+    type Provider = M::Provider;
+    type Inner = M;
+
+    fn inner(&self) -> &M {
+        &self.inner
+    }
+
+    /// Fill the transaction first so the nonce it is signed with is known, then send and register it.
+    /// A failed registration doesn't fail the send, the transaction is already broadcast.
+    async fn send_transaction<T: Into<TypedTransaction> + Send + Sync>(
+        &self,
+        tx: T,
+        block: Option<BlockId>,
+    ) -> Result<PendingTransaction<'_, Self::Provider>, Self::Error> {
+        let mut tx: TypedTransaction = tx.into();
+        self.inner.fill_transaction(&mut tx, block).await.map_err(MiddlewareError::from_err)?;
+        let nonce = tx.nonce().copied();
+        let pending = self.inner.send_transaction(tx, block).await.map_err(MiddlewareError::from_err)?;
+        self.register(pending.tx_hash(), nonce).await;
+        Ok(pending)
+    }
+}
@@ -1,14 +1,16 @@
 use ethers::prelude::*;
 use ethers::contract::Multicall;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use std::collections::HashMap;
 use eyre::Result;
 use rust_decimal::Decimal;
-use tracing::{debug, info, warn, instrument};
+use tracing::{debug, info, warn, error, instrument};
 
 use crate::config::Config;
+use crate::data_ingestion::token::token_registry::check_token_decimals;
 use crate::db::db_manager::DbManager;
+use crate::tx_registry::TxRegistryMiddleware;
 
 abigen!(
     IERC20,
@@ -36,21 +38,18 @@ pub struct MarketTokenInfo {
     pub short_token_address: Address,
 }
 
-pub struct WalletManager {
-    pub signer: Arc<SignerMiddleware<Arc<Provider<Http>>, Wallet<k256::ecdsa::SigningKey>>>,
-    pub address: Address,
+/// Token catalog loaded from the database, swapped out as a whole on refresh
+#[derive(Debug, Clone)]
+pub struct WalletTokens {
     pub native_token: TokenInfo,
     pub all_tokens: HashMap<Address, TokenInfo>,
     pub asset_tokens: HashMap<Address, TokenInfo>,
     pub market_tokens: HashMap<Address, MarketTokenInfo>,
 }
 
-impl WalletManager {
-    pub fn new(config: &Config) -> Result<Self> {
-        let signer = Self::get_wallet_signer(config)?;
-        Ok(Self {
-            signer: Arc::new(signer.clone()),
-            address: signer.address(),
+impl WalletTokens {
+    fn empty() -> Self {
+        Self {
             native_token: TokenInfo {
                 address: Address::from_str("0xEeeeeEeeeEeEeeEeEeEeeEEEeeeeEeeeeeeeEEeE").unwrap(), 
                 symbol: "NATIVE".to_string(),
@@ -60,6 +59,24 @@ impl WalletManager {
             all_tokens: HashMap::new(),
             asset_tokens: HashMap::new(),
             market_tokens: HashMap::new(),
+        }
+    }
+}
+
+pub struct WalletManager {
+    pub signer: Arc<TxRegistryMiddleware<SignerMiddleware<Arc<Provider<Http>>, Wallet<k256::ecdsa::SigningKey>>>>,
+    pub address: Address,
+    tokens: RwLock<Arc<WalletTokens>>,
+}
+
+impl WalletManager {
+    pub fn new(config: &Config) -> Result<Self> {
+        let signer = Self::get_wallet_signer(config)?;
+        let address = signer.address();
+        Ok(Self {
+            signer: Arc::new(TxRegistryMiddleware::new(signer, address)),
+            address,
+            tokens: RwLock::new(Arc::new(WalletTokens::empty())),
         })
     }
 
@@ -67,9 +84,9 @@ impl WalletManager {
     fn get_wallet_signer(config: &Config) -> Result<
         SignerMiddleware<Arc<Provider<Http>>, Wallet<k256::ecdsa::SigningKey>>
     > {
-        // Load wallet from private key
-        let wallet = Wallet::from_str(&config.wallet_private_key)?
-            .with_chain_id(config.chain_id); 
+        // Load wallet from the private key, or unlock the encrypted keystore
+        let wallet = config.wallet_key.wallet()?
+            .with_chain_id(config.chain_id);
 
         // Use already-built provider (already Arc-wrapped)
         let provider = config.alchemy_provider.clone();
@@ -79,17 +96,70 @@ impl WalletManager {
         Ok(client)
     }
 
-    // Load all tokens from the database
+    /// Snapshot of the current token catalog
+    pub fn tokens(&self) -> Arc<WalletTokens> {
+        self.tokens.read().unwrap().clone()
+    }
+
+    /// Native token info (price as of the last refresh)
+    pub fn native_token(&self) -> TokenInfo {
+        self.tokens().native_token.clone()
+    }
+
+    /// Asset or market token info by address
+    pub fn token(&self, address: &Address) -> Option<TokenInfo> {
+        self.tokens().all_tokens.get(address).cloned()
+    }
+
+    /// Asset token info by address
+    pub fn asset_token(&self, address: &Address) -> Option<TokenInfo> {
+        self.tokens().asset_tokens.get(address).cloned()
+    }
+
+    /// Market token info by address
+    pub fn market_token(&self, address: &Address) -> Option<MarketTokenInfo> {
+        self.tokens().market_tokens.get(address).cloned()
+    }
+
+    // Load all tokens from the database (and register sent transactions in it from now on)
+    #[instrument(skip(self, db))]
+    pub async fn load_tokens(&self, db: &DbManager) -> Result<()> {
+        self.register_transactions(db);
+        self.refresh(db).await
+    }
+
+    /// Register every transaction sent from the wallet in the database, for the wallet watchdog
+    pub fn register_transactions(&self, db: &DbManager) {
+        self.signer.attach(db.pool.clone());
+    }
+
+    /// Re-pull asset tokens, market tokens and their latest prices from the database.
+    /// The new catalog replaces the old one in a single swap, so readers never see a partial load.
     #[instrument(skip(self, db))]
-    pub async fn load_tokens(&mut self, db: &DbManager) -> Result<()> {
-        self.load_asset_tokens(db).await?;
-        self.load_market_tokens(db).await?;
+    pub async fn refresh(&self, db: &DbManager) -> Result<()> {
+        let mut tokens = WalletTokens::empty();
+        Self::load_asset_tokens(&mut tokens, db).await?;
+        Self::load_market_tokens(&mut tokens, db).await?;
+
+        let previous = self.tokens();
+        self.verify_asset_tokens(&mut tokens, &previous).await?;
+        let new_markets = tokens.market_tokens.keys()
+            .filter(|address| !previous.market_tokens.contains_key(address))
+            .count();
+        debug!(
+            asset_tokens = tokens.asset_tokens.len(),
+            market_tokens = tokens.market_tokens.len(),
+            new_markets = new_markets,
+            "Wallet tokens refreshed"
+        );
+
+        *self.tokens.write().unwrap() = Arc::new(tokens);
         Ok(())
     }
 
     /// Load all asset tokens from the database
-    #[instrument(skip(self, db))]
-    pub async fn load_asset_tokens(&mut self, db: &DbManager) -> Result<()> {
+    #[instrument(skip(tokens, db))]
+    async fn load_asset_tokens(tokens: &mut WalletTokens, db: &DbManager) -> Result<()> {
         let asset_tokens = db.get_all_asset_tokens().await?;
         for token in asset_tokens {
             let token_info = TokenInfo {
@@ -99,17 +169,50 @@ impl WalletManager {
                 last_mid_price_usd: token.3,
             };     
             if token_info.address == Address::from_str("0x82aF49447D8a07e3bd95BD0d56f35241523fBab1").unwrap() { // WETH
-                self.native_token.last_mid_price_usd = token_info.last_mid_price_usd;
+                tokens.native_token.last_mid_price_usd = token_info.last_mid_price_usd;
             }
-            self.all_tokens.insert(token_info.address, token_info.clone());
-            self.asset_tokens.insert(token_info.address, token_info);
+            tokens.all_tokens.insert(token_info.address, token_info.clone());
+            tokens.asset_tokens.insert(token_info.address, token_info);
+        }
+        Ok(())
+    }
+
+    /// Check the decimals of asset tokens new to the catalog against the chain. On the first load a mismatch (or a
+    /// failed check) is an error, the token's balances and USD values would all be mis-scaled. Tokens appearing on
+    /// later refreshes are quarantined instead: left out of the catalog until their decimals are fixed.
+    #[instrument(skip(self, tokens, previous))]
+    async fn verify_asset_tokens(&self, tokens: &mut WalletTokens, previous: &WalletTokens) -> Result<()> {
+        let first_load = previous.asset_tokens.is_empty();
+        let new_tokens: Vec<(Address, String, u8)> = tokens.asset_tokens.values()
+            .filter(|token| !previous.asset_tokens.contains_key(&token.address))
+            .map(|token| (token.address, token.symbol.clone(), token.decimals))
+            .collect();
+        let mismatches = match check_token_decimals(self.signer.provider().clone().into(), &new_tokens).await {
+            Ok(mismatches) => mismatches,
+            Err(e) if first_load => return Err(e),
+            Err(e) => {
+                warn!(error = ?e, new_tokens = new_tokens.len(), "Failed to check token decimals on-chain, new tokens loaded unverified");
+                return Ok(());
+            }
+        };
+        if mismatches.is_empty() {
+            return Ok(());
+        }
+        if first_load {
+            let details = mismatches.iter().map(|mismatch| mismatch.to_string()).collect::<Vec<_>>().join("; ");
+            return Err(eyre::eyre!("Asset token decimals don't match the chain: {}", details));
+        }
+        for mismatch in mismatches {
+            error!(token = %mismatch, "Token decimals don't match the chain, token quarantined");
+            tokens.asset_tokens.remove(&mismatch.address);
+            tokens.all_tokens.remove(&mismatch.address);
         }
         Ok(())
     }
 
     /// Load all market tokens from the database
-    #[instrument(skip(self, db))]
-    async fn load_market_tokens(&mut self, db: &DbManager) -> Result<()> {
+    #[instrument(skip(tokens, db))]
+    async fn load_market_tokens(tokens: &mut WalletTokens, db: &DbManager) -> Result<()> {
         let market_tokens = db.get_all_market_tokens().await?;
         for token in market_tokens {
             let token_info = TokenInfo {
@@ -118,7 +221,7 @@ impl WalletManager {
                 decimals: 18, // Market tokens are always 18 decimals
                 last_mid_price_usd: token.2,
             };
-            self.all_tokens.insert(token_info.address, token_info);
+            tokens.all_tokens.insert(token_info.address, token_info);
             let market_token_info = MarketTokenInfo {
                 address: token.0,
                 symbol: token.1,
@@ -128,7 +231,7 @@ impl WalletManager {
                 long_token_address: token.4,
                 short_token_address: token.5,
             };
-            self.market_tokens.insert(market_token_info.address, market_token_info);
+            tokens.market_tokens.insert(market_token_info.address, market_token_info);
         }
         Ok(())
     }
@@ -148,7 +251,7 @@ impl WalletManager {
     #[instrument(skip(self))]
     pub async fn get_native_balance(&self) -> Result<Decimal> {
         let balance = self.signer.get_balance(self.address, None).await?;
-        let balance = Self::u256_to_decimal(balance, self.native_token.decimals);
+        let balance = Self::u256_to_decimal(balance, self.native_token().decimals);
         debug!(
             balance = %balance,
             "Retrieved native balance as Decimal"
@@ -159,11 +262,11 @@ impl WalletManager {
     /// Get ERC20 token balance as U256
     #[instrument(skip(self, token_address))]
     pub async fn get_token_balance_u256(&self, token_address: Address) -> Result<U256> {
-        if token_address == self.native_token.address {
+        if token_address == self.native_token().address {
             return self.get_native_balance_u256().await;
         }
 
-        let token_info = self.all_tokens.get(&token_address).ok_or_else(|| eyre::eyre!("Token not found: {}", token_address))?;
+        let token_info = self.token(&token_address).ok_or_else(|| eyre::eyre!("Token not found: {}", token_address))?;
 
         // Create ERC20 contract instance
         let contract = IERC20::new(token_address, self.signer.clone());
@@ -184,10 +287,10 @@ impl WalletManager {
     /// Get ERC20 token balance for a specific token as Decimal
     #[instrument(skip(self, token_address))]
     pub async fn get_token_balance(&self, token_address: Address) -> Result<Decimal> {
-        if token_address == self.native_token.address {
+        if token_address == self.native_token().address {
             return self.get_native_balance().await;
         }
-        let token_info = self.all_tokens.get(&token_address).ok_or_else(|| eyre::eyre!("Token not found: {}", token_address))?;
+        let token_info = self.token(&token_address).ok_or_else(|| eyre::eyre!("Token not found: {}", token_address))?;
 
         // Create ERC20 contract instance
         let contract = IERC20::new(token_address, self.signer.clone());
@@ -210,8 +313,9 @@ impl WalletManager {
     #[instrument(skip(self))]
     pub async fn get_all_token_balances(&self) -> Result<HashMap<Address, Decimal>> {
         debug!("Fetching all token balances using multicall");
+        let tokens = self.tokens();
         let mut multicall = Multicall::new(self.signer.provider().clone(), None).await?;
-        for token in self.all_tokens.values() {
+        for token in tokens.all_tokens.values() {
             let contract = IERC20::new(token.address, self.signer.provider().clone().into());
             let call = contract.balance_of(self.address);
             multicall.add_call(call, false);
@@ -222,7 +326,7 @@ impl WalletManager {
 
         // Parse results into a map
         let mut balances = HashMap::new();
-        for (i, token) in self.all_tokens.values().enumerate() {
+        for (i, token) in tokens.all_tokens.values().enumerate() {
             let balance = Self::u256_to_decimal(results[i], token.decimals);
             balances.insert(token.address, balance);
         }
@@ -234,8 +338,9 @@ impl WalletManager {
     #[instrument(skip(self))]
     pub async fn get_asset_token_balances(&self) -> Result<HashMap<Address, Decimal>> {
         debug!("Fetching all asset token balances");
+        let tokens = self.tokens();
         let mut multicall = Multicall::new(self.signer.provider().clone(), None).await?;
-        for asset_token in self.asset_tokens.values() {
+        for asset_token in tokens.asset_tokens.values() {
             let contract = IERC20::new(asset_token.address, self.signer.provider().clone().into());
             let call = contract.balance_of(self.address);
             multicall.add_call(call, false);
@@ -246,7 +351,7 @@ impl WalletManager {
 
         // Parse results into a map
         let mut balances = HashMap::new();
-        for (i, asset_token) in self.asset_tokens.values().enumerate() {
+        for (i, asset_token) in tokens.asset_tokens.values().enumerate() {
             let balance = Self::u256_to_decimal(results[i], asset_token.decimals);
             balances.insert(asset_token.address, balance);
         }
@@ -257,9 +362,19 @@ impl WalletManager {
     /// Get all market token balances
     #[instrument(skip(self))]
     pub async fn get_market_token_balances(&self) -> Result<HashMap<Address, Decimal>> {
+        self.get_market_token_balances_at(None).await
+    }
+
+    /// Get all market token balances as of the given block (latest when None), read in a single multicall
+    #[instrument(skip(self))]
+    pub async fn get_market_token_balances_at(&self, block: Option<u64>) -> Result<HashMap<Address, Decimal>> {
         debug!("Fetching all market token balances");
+        let tokens = self.tokens();
         let mut multicall = Multicall::new(self.signer.provider().clone(), None).await?;
-        for market_token in self.market_tokens.values() {
+        if let Some(block) = block {
+            multicall = multicall.block(block);
+        }
+        for market_token in tokens.market_tokens.values() {
             let contract = IERC20::new(market_token.address, self.signer.provider().clone().into());
             let call = contract.balance_of(self.address);
             multicall.add_call(call, false);
@@ -270,7 +385,7 @@ impl WalletManager {
 
         // Parse results into a map
         let mut balances = HashMap::new();
-        for (i, market_token) in self.market_tokens.values().enumerate() {
+        for (i, market_token) in tokens.market_tokens.values().enumerate() {
             let balance = Self::u256_to_decimal(results[i], market_token.decimals);
             balances.insert(market_token.address, balance);
         }
@@ -278,6 +393,20 @@ impl WalletManager {
         Ok(balances)
     }
 
+    /// Get total wallet value in USD (native balance plus all token balances at their last mid prices)
+    #[instrument(skip(self))]
+    pub async fn get_total_value_usd(&self) -> Result<Decimal> {
+        let tokens = self.tokens();
+        let native_value = self.get_native_balance().await? * tokens.native_token.last_mid_price_usd;
+        let token_balances = self.get_all_token_balances().await?;
+        let token_value: Decimal = token_balances.iter()
+            .filter_map(|(address, balance)| {
+                tokens.all_tokens.get(address).map(|token| *balance * token.last_mid_price_usd)
+            })
+            .sum();
+        Ok(native_value + token_value)
+    }
+
     /// Print comprehensive wallet balances including native, all ERC20 tokens, and all market tokens
     #[instrument(skip(self, include_zero_balances))]
     pub async fn log_all_balances(&self, include_zero_balances: bool) -> Result<()> {
@@ -299,7 +428,7 @@ impl WalletManager {
             balance_strings.join("\n")
         };
 
-        info!("All token balances: \n{}", output);
+        info!(balances = %output, "All token balances");
         Ok(())
     }
 
@@ -307,19 +436,19 @@ impl WalletManager {
     #[instrument(skip(self))]
     pub async fn log_native_balance(&self) -> Result<()> {
         let balance_string = self.get_native_balance_string().await?;
-        info!("Native token balance: \n{}", balance_string);
+        info!(balance = %balance_string, "Native token balance");
         Ok(())
     }
 
     /// Log single token balance only
     #[instrument(skip(self, token_address))]
     pub async fn log_token_balance(&self, token_address: Address) -> Result<()> {
-        if token_address == self.native_token.address {
+        if token_address == self.native_token().address {
             return self.log_native_balance().await;
         }
         let balance_string = self.get_token_balance_string(token_address).await?;
-        let token_info = self.all_tokens.get(&token_address).ok_or_else(|| eyre::eyre!("Token not found: {}", token_address))?;
-        info!("{} token balance: \n{}", token_info.symbol, balance_string);
+        let token_info = self.token(&token_address).ok_or_else(|| eyre::eyre!("Token not found: {}", token_address))?;
+        info!(symbol = %token_info.symbol, balance = %balance_string, "Token balance");
         Ok(())
     }
 
@@ -333,7 +462,7 @@ impl WalletManager {
             balance_strings.join("\n")
         };
 
-        info!("Asset token balances: \n{}", output);
+        info!(balances = %output, "Asset token balances");
         Ok(())
     }
 
@@ -347,7 +476,7 @@ impl WalletManager {
             balance_strings.join("\n")
         };
 
-        info!("Market token balances: \n{}", output);
+        info!(balances = %output, "Market token balances");
         Ok(())
     }
 
@@ -365,23 +494,24 @@ impl WalletManager {
     #[instrument(skip(self))]
     async fn get_native_balance_string(&self) -> Result<String> {
         let balance = self.get_native_balance().await?;
+        let native_token = self.native_token();
         Ok(format!(
             "{} ({:?}): {} ({:.2} USD)",
-            self.native_token.symbol,
-            self.native_token.address,
+            native_token.symbol,
+            native_token.address,
             balance,
-            balance * self.native_token.last_mid_price_usd
+            balance * native_token.last_mid_price_usd
         ))
     }
 
     /// Get single token balance string
     #[instrument(skip(self, token_address))]
     async fn get_token_balance_string(&self, token_address: Address) -> Result<String> {
-        if token_address == self.native_token.address {
+        if token_address == self.native_token().address {
             return self.get_native_balance_string().await;
         }
         let balance = self.get_token_balance(token_address).await?;
-        let token_info = self.all_tokens.get(&token_address).ok_or_else(|| eyre::eyre!("Token not found: {}", token_address))?;
+        let token_info = self.token(&token_address).ok_or_else(|| eyre::eyre!("Token not found: {}", token_address))?;
         Ok(format!(
             "{} ({:?}): {} ({:.2} USD)",
             token_info.symbol,
@@ -395,7 +525,8 @@ impl WalletManager {
     #[instrument(skip(self, include_zero_balances))]
     async fn get_asset_token_balance_strings(&self, include_zero_balances: bool) -> Result<Vec<String>> {
         let balances = self.get_asset_token_balances().await?;
-        let balance_strings = self.asset_tokens.values().filter_map(|token| {
+        let tokens = self.tokens();
+        let balance_strings = tokens.asset_tokens.values().filter_map(|token| {
             let balance = balances.get(&token.address).unwrap_or(&Decimal::ZERO);
             if !include_zero_balances && *balance == Decimal::ZERO {
                 return None;
@@ -415,7 +546,8 @@ impl WalletManager {
     #[instrument(skip(self, include_zero_balances))]
     async fn get_market_token_balance_strings(&self, include_zero_balances: bool) -> Result<Vec<String>> {
         let balances = self.get_market_token_balances().await?;
-        let balance_strings = self.market_tokens.values().filter_map(|token| {
+        let tokens = self.tokens();
+        let balance_strings = tokens.market_tokens.values().filter_map(|token| {
             let balance = balances.get(&token.address).unwrap_or(&Decimal::ZERO);
             if !include_zero_balances && *balance == Decimal::ZERO {
                 return None;
@@ -0,0 +1,114 @@
+// Watches the execution wallet for transactions the bot didn't send (a leaked key or an operator sending by hand).
+// Every outgoing transaction consumes one wallet nonce, so each nonce the wallet has used must belong to a
+// transaction in the bot's registry (see tx_registry) that was actually mined.
+use ethers::prelude::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use eyre::Result;
+use tracing::{info, debug, error, instrument};
+
+use crate::config::Config;
+use crate::db::db_manager::DbManager;
+
+/// Dynamic parameter switched on when an external transaction is found (and safe mode on alert is enabled)
+const SAFE_MODE_KEY: &str = "safe_mode";
+
+pub struct WalletWatchdog {
+    config: Arc<Config>,
+    db: Arc<DbManager>,
+    wallet_address: Address,
+    checked_nonce: u64, // Nonces below this have been checked
+    observed_nonce: u64, // Wallet nonce seen at the previous poll, its new nonces are checked on the next poll
+}
+
+impl WalletWatchdog {
+    /// Start watching from the wallet's current nonce, earlier transactions are not checked
+    #[instrument(skip(config, db))]
+    pub async fn init(config: Arc<Config>, db: Arc<DbManager>, wallet_address: Address) -> Result<Self> {
+        let nonce = Self::mined_nonce(&config, wallet_address).await?;
+        info!(nonce = nonce, "Initializing wallet watchdog");
+        Ok(Self {
+            config,
+            db,
+            wallet_address,
+            checked_nonce: nonce,
+            observed_nonce: nonce,
+        })
+    }
+
+    // Poll the wallet nonce forever (to be called in long-running background task)
+    #[instrument(skip(self), fields(wallet = ?self.wallet_address))]
+    pub async fn run(&mut self) {
+        let mut ticker = tokio::time::interval(Duration::from_secs(self.config.wallet_watchdog_poll_secs));
+        info!(interval_secs = self.config.wallet_watchdog_poll_secs, "Starting wallet watchdog");
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.check().await {
+                error!(?e, "Wallet watchdog check failed");
+            }
+        }
+    }
+
+    /// Check the nonces used since the previous poll, returning how many belong to transactions the bot didn't send.
+    /// Nonces are checked one poll after they were first seen, giving the bot time to register what it just sent.
+    pub async fn check(&mut self) -> Result<usize> {
+        let nonce = Self::mined_nonce(&self.config, self.wallet_address).await?;
+        let (from, to) = (self.checked_nonce, self.observed_nonce);
+        self.observed_nonce = nonce.max(self.observed_nonce);
+        if to <= from {
+            return Ok(0);
+        }
+
+        let mut registered: HashMap<u64, Vec<H256>> = HashMap::new();
+        for tx in self.db.get_wallet_transactions_by_nonce(self.wallet_address, from, to).await? {
+            if let Ok(tx_hash) = tx.tx_hash.parse::<H256>() {
+                registered.entry(tx.nonce as u64).or_default().push(tx_hash);
+            }
+        }
+
+        // A registered nonce can still have been taken by someone else's transaction replacing the bot's,
+        // so one of its registered hashes has to have been mined
+        let provider = &self.config.alchemy_provider;
+        let mut external = Vec::new();
+        for n in from..to {
+            let mut mined = false;
+            for tx_hash in registered.get(&n).map(Vec::as_slice).unwrap_or_default() {
+                if provider.get_transaction_receipt(*tx_hash).await?.is_some() {
+                    mined = true;
+                    break;
+                }
+            }
+            if !mined {
+                external.push(n);
+            }
+        }
+        self.checked_nonce = to;
+        debug!(from_nonce = from, to_nonce = to, external = external.len(), "Wallet nonces checked");
+
+        if !external.is_empty() {
+            self.alert(&external).await?;
+        }
+        Ok(external.len())
+    }
+
+    async fn alert(&self, nonces: &[u64]) -> Result<()> {
+        error!(
+            wallet = ?self.wallet_address,
+            nonces = ?nonces,
+            "ALERT: wallet sent transactions the bot did not initiate, check for a leaked key or manual sends"
+        );
+        if self.config.wallet_watchdog_safe_mode {
+            self.db.set_config_override(SAFE_MODE_KEY, "true").await?;
+            error!("Safe mode enabled by the wallet watchdog, clear the safe_mode override once the wallet is secured");
+        }
+        Ok(())
+    }
+
+    async fn mined_nonce(config: &Config, wallet_address: Address) -> Result<u64> {
+        let nonce = config.alchemy_provider
+            .get_transaction_count(wallet_address, Some(BlockNumber::Latest.into()))
+            .await?;
+        Ok(nonce.as_u64())
+    }
+}
@@ -0,0 +1,177 @@
+// GMX deposit/withdrawal pricing in strategy::fee_model, checked against hand-computed values and, with a forked
+// chain, against the GMX Reader itself. The on-chain comparison needs anvil on PATH and an Arbitrum RPC in E2E_FORK_URL:
+//     E2E_FORK_URL=https://arb-mainnet.g.alchemy.com/v2/<key> cargo test --test fee_model -- --ignored --nocapture
+use crypto_yield_farming_bot::constants::{GMX_DATASTORE_ADDRESS_MAINNET, GMX_READER_ADDRESS_MAINNET};
+use crypto_yield_farming_bot::data_ingestion::market::market_utils::u256_to_decimal_scaled;
+use crypto_yield_farming_bot::gmx::datastore::{DataStore, SwapPricingFactors};
+use crypto_yield_farming_bot::gmx::reader::{MarketPrices, MarketProps, PriceProps, Reader};
+use crypto_yield_farming_bot::strategy::fee_model::{self, GmActionCost, PoolBalance, SwapPricingParams};
+use ethers::abi::Token;
+use ethers::prelude::*;
+use ethers::utils::{keccak256, Anvil};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+use std::sync::Arc;
+
+const FORK_URL_ENV: &str = "E2E_FORK_URL";
+const ETH_USD_MARKET: &str = "0x70d95587d40A2caf56bd97485aB3Eec10Bee6336"; // ETH/USD [WETH-USDC]
+const WETH: &str = "0x82aF49447D8a07e3bd95BD0d56f35241523fBab1";
+const USDC: &str = "0xaf88d065e77c8cC2239327C5EDb3A432268e5831";
+
+fn dec(value: &str) -> Decimal {
+    Decimal::from_str(value).unwrap()
+}
+
+fn params() -> SwapPricingParams {
+    SwapPricingParams {
+        positive_impact_factor: dec("0.000000005"),
+        negative_impact_factor: dec("0.00000001"),
+        impact_exponent: dec("2"),
+        fee_factor_positive_impact: dec("0.0005"),
+        fee_factor_negative_impact: dec("0.0007"),
+    }
+}
+
+// Long side heavier by $200k
+fn pool() -> PoolBalance {
+    PoolBalance { long_usd: dec("600000"), short_usd: dec("400000") }
+}
+
+#[test]
+fn same_side_deposit_worsening_balance_pays_negative_impact() {
+    // 1e-8 * (300k^2 - 200k^2)
+    let impact = fee_model::swap_price_impact_usd(pool(), dec("100000"), Decimal::ZERO, &params()).unwrap();
+    assert_eq!(impact, dec("-500"));
+}
+
+#[test]
+fn same_side_deposit_improving_balance_earns_positive_impact() {
+    // 5e-9 * (200k^2 - 100k^2)
+    let impact = fee_model::swap_price_impact_usd(pool(), Decimal::ZERO, dec("100000"), &params()).unwrap();
+    assert_eq!(impact, dec("150"));
+}
+
+#[test]
+fn crossover_deposit_nets_positive_and_negative_impact() {
+    // 5e-9 * 200k^2 for removing the imbalance less 1e-8 * 100k^2 for the new one on the short side
+    let impact = fee_model::swap_price_impact_usd(pool(), Decimal::ZERO, dec("300000"), &params()).unwrap();
+    assert_eq!(impact, dec("100"));
+}
+
+#[test]
+fn positive_impact_factor_is_capped_at_negative() {
+    let params = SwapPricingParams { positive_impact_factor: dec("0.00000002"), ..params() };
+    let impact = fee_model::swap_price_impact_usd(pool(), Decimal::ZERO, dec("100000"), &params).unwrap();
+    assert_eq!(impact, dec("300"));
+}
+
+#[test]
+fn delta_taking_a_side_below_zero_is_rejected() {
+    assert_eq!(fee_model::swap_price_impact_usd(pool(), Decimal::ZERO, dec("-500000"), &params()), None);
+}
+
+#[test]
+fn deposit_positive_impact_is_capped_at_impact_pool() {
+    let cost = fee_model::deposit_cost(pool(), None, dec("100"), Decimal::ZERO, dec("100000"), &params()).unwrap();
+    assert_eq!(cost, GmActionCost { fees_usd: dec("50"), price_impact_usd: dec("100") });
+    assert_eq!(cost.total_usd(), dec("-50"));
+}
+
+#[test]
+fn deposit_negative_impact_takes_worse_of_pool_and_virtual_inventory() {
+    // Virtual inventory long side heavier by $400k: 1e-8 * (500k^2 - 400k^2)
+    let virtual_inventory = PoolBalance { long_usd: dec("700000"), short_usd: dec("300000") };
+    let cost = fee_model::deposit_cost(pool(), Some(virtual_inventory), dec("1000"), dec("100000"), Decimal::ZERO, &params()).unwrap();
+    assert_eq!(cost, GmActionCost { fees_usd: dec("70"), price_impact_usd: dec("-900") });
+    assert_eq!(cost.total_usd(), dec("970"));
+}
+
+#[test]
+fn withdrawal_pays_negative_impact_fee_without_impact() {
+    let cost = fee_model::withdrawal_cost(dec("10000"), &params());
+    assert_eq!(cost, GmActionCost { fees_usd: dec("7"), price_impact_usd: Decimal::ZERO });
+}
+
+/// keccak(abi.encode(keccak(abi.encode(name)), ...)), as the GMX Keys library builds datastore keys
+fn datastore_key(name: &str, args: Vec<Token>) -> [u8; 32] {
+    let name_key = keccak256(ethers::abi::encode(&[Token::String(name.to_string())]));
+    let mut tokens = vec![Token::FixedBytes(name_key.to_vec())];
+    tokens.extend(args);
+    keccak256(ethers::abi::encode(&tokens))
+}
+
+#[tokio::test]
+#[ignore = "needs anvil and E2E_FORK_URL"]
+async fn swap_price_impact_matches_gmx_reader() {
+    let Ok(fork_url) = std::env::var(FORK_URL_ENV) else {
+        eprintln!("{} not set, skipping on-chain fee model test", FORK_URL_ENV);
+        return;
+    };
+    let anvil = Anvil::new().fork(fork_url).spawn();
+    let provider = Arc::new(Provider::<Http>::try_from(anvil.endpoint()).unwrap());
+    let datastore_address = Address::from_str(GMX_DATASTORE_ADDRESS_MAINNET).unwrap();
+    let datastore = DataStore::new(datastore_address, provider.clone());
+    let reader = Reader::new(Address::from_str(GMX_READER_ADDRESS_MAINNET).unwrap(), provider.clone());
+    let market = Address::from_str(ETH_USD_MARKET).unwrap();
+    let (weth, usdc) = (Address::from_str(WETH).unwrap(), Address::from_str(USDC).unwrap());
+
+    let get_uint = |name: &'static str, args: Vec<Token>| {
+        let call = datastore.get_uint(datastore_key(name, args));
+        async move { call.call().await.expect("Failed to read datastore") }
+    };
+    let factors = SwapPricingFactors {
+        positive_impact_factor: get_uint("SWAP_IMPACT_FACTOR", vec![Token::Address(market), Token::Bool(true)]).await,
+        negative_impact_factor: get_uint("SWAP_IMPACT_FACTOR", vec![Token::Address(market), Token::Bool(false)]).await,
+        impact_exponent_factor: get_uint("SWAP_IMPACT_EXPONENT_FACTOR", vec![Token::Address(market)]).await,
+        fee_factor_positive_impact: get_uint("SWAP_FEE_FACTOR", vec![Token::Address(market), Token::Bool(true)]).await,
+        fee_factor_negative_impact: get_uint("SWAP_FEE_FACTOR", vec![Token::Address(market), Token::Bool(false)]).await,
+    };
+    let params = SwapPricingParams::from_factors(&factors);
+
+    // Fixed prices (per raw token unit, 30 decimals) so the USD values on both sides are exact
+    let weth_price = U256::from(3000u64) * U256::exp10(12);
+    let usdc_price = U256::exp10(24);
+    let price = |value: U256| PriceProps { min: value, max: value };
+    let prices = MarketPrices { index_token_price: price(weth_price), long_token_price: price(weth_price), short_token_price: price(usdc_price) };
+    let market_props = MarketProps { market_token: market, index_token: weth, long_token: weth, short_token: usdc };
+
+    let long_amount = get_uint("POOL_AMOUNT", vec![Token::Address(market), Token::Address(weth)]).await;
+    let short_amount = get_uint("POOL_AMOUNT", vec![Token::Address(market), Token::Address(usdc)]).await;
+    let pool = PoolBalance {
+        long_usd: u256_to_decimal_scaled(long_amount * weth_price),
+        short_usd: u256_to_decimal_scaled(short_amount * usdc_price),
+    };
+
+    // $10k swaps in each direction: the one into the lighter side improves the balance
+    for (token_in, amount_in, token_in_price) in [
+        (weth, U256::from(10000u64) * U256::exp10(18) / U256::from(3000u64), weth_price),
+        (usdc, U256::from(10000u64) * U256::exp10(6), usdc_price),
+    ] {
+        let delta_usd = u256_to_decimal_scaled(amount_in * token_in_price);
+        let delta_long_usd = if token_in == weth { delta_usd } else { -delta_usd };
+        let modeled = fee_model::swap_price_impact_usd(pool, delta_long_usd, -delta_long_usd, &params).unwrap();
+
+        let (_, on_chain_impact, fees) = reader
+            .get_swap_amount_out(datastore_address, market_props.clone(), prices.clone(), token_in, amount_in, Address::zero())
+            .call()
+            .await
+            .expect("Failed to call Reader.getSwapAmountOut");
+        let on_chain_impact = if on_chain_impact.is_negative() {
+            -u256_to_decimal_scaled(on_chain_impact.unsigned_abs())
+        } else {
+            u256_to_decimal_scaled(on_chain_impact.into_raw())
+        };
+        let tolerance = Decimal::new(1, 2);
+        if modeled > Decimal::ZERO {
+            // Positive impact only considers the pool
+            assert!((modeled - on_chain_impact).abs() <= tolerance, "modeled {} vs on-chain {}", modeled, on_chain_impact);
+        } else {
+            // Negative impact can only be made worse by the virtual inventory
+            assert!(on_chain_impact <= modeled + tolerance, "modeled {} vs on-chain {}", modeled, on_chain_impact);
+        }
+
+        let on_chain_fee_usd = u256_to_decimal_scaled((fees.fee_receiver_amount + fees.fee_amount_for_pool) * token_in_price);
+        let modeled_fee_usd = fee_model::swap_fee_usd(delta_usd, modeled > Decimal::ZERO, &params);
+        assert!((modeled_fee_usd - on_chain_fee_usd).abs() <= tolerance, "modeled fee {} vs on-chain {}", modeled_fee_usd, on_chain_fee_usd);
+    }
+}
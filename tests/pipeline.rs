@@ -0,0 +1,178 @@
+// End-to-end run of the pipeline glue: collect → record → funding → strategy → paper execution (approval mode) on the fork.
+// Needs docker (Postgres/Redis containers), anvil on PATH and an Arbitrum archive RPC in E2E_FORK_URL:
+//     E2E_FORK_URL=https://arb-mainnet.g.alchemy.com/v2/<key> cargo test --test pipeline -- --ignored --nocapture
+mod common;
+
+use common::TestEnv;
+use ethers::types::{Address, U256};
+use std::time::Duration;
+
+const DATA_COLLECTOR: &str = env!("CARGO_BIN_EXE_data_collector");
+const DATA_RECORDER: &str = env!("CARGO_BIN_EXE_data_recorder");
+const FUNDING_COLLECTOR: &str = env!("CARGO_BIN_EXE_funding_collector");
+const TRADING_BOT: &str = env!("CARGO_BIN_EXE_trading_bot");
+const PLAN: &str = env!("CARGO_BIN_EXE_plan");
+
+const COLLECTION_TIMEOUT: Duration = Duration::from_secs(300);
+const TRADING_BOT_TIMEOUT: Duration = Duration::from_secs(600);
+const SEED_HISTORY_POINTS: i32 = 300; // 25 hours of 5 minute rows, enough for the return and volatility windows
+const SEED_GM_BALANCE: u64 = 1_000; // GM tokens held in a single market, more than its max position weight allows
+
+#[tokio::test]
+#[ignore = "needs docker, anvil and E2E_FORK_URL"]
+async fn full_cycle_records_every_stage() {
+    let Some(env) = TestEnv::start().await else {
+        return;
+    };
+
+    // Collect → record: the recorder subscribes to the collector's start signal, so it has to be up first
+    let recorder = env.spawn(DATA_RECORDER, &[]);
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    let collector = env.spawn(DATA_COLLECTOR, &[]);
+    for table in ["tokens", "markets", "token_prices", "market_states"] {
+        env.wait_for_rows(table, 1, COLLECTION_TIMEOUT).await;
+    }
+    common::stop(collector);
+    common::stop(recorder);
+
+    // The strategy needs a day of history, seed it from the collected snapshot
+    seed_history(&env).await;
+
+    // Funding rates for the hedge markets
+    env.run(FUNDING_COLLECTOR, &["backfill", "1"]);
+    env.wait_for_rows("funding_rates", 1, Duration::from_secs(10)).await;
+
+    // Hold the whole portfolio in one market, so the plan has to move capital out of it
+    let market: String = sqlx::query_scalar(
+        r#"
+        SELECT m.address FROM markets m
+        JOIN tokens t ON t.id = m.index_token_id
+        WHERE t.symbol IN ('ETH', 'WETH')
+        ORDER BY m.id
+        LIMIT 1
+        "#,
+    )
+    .fetch_one(&env.pool)
+    .await
+    .expect("No ETH market collected");
+    let market: Address = market.parse().expect("Invalid market address");
+    env.set_erc20_balance(market, env.wallet_address(), U256::from(SEED_GM_BALANCE) * U256::exp10(18)).await;
+
+    // Strategy → paper execution: the bot submits its plan, waits for approval and executes the approved moves
+    let mut trading_bot = env.spawn(TRADING_BOT, &[]);
+    env.wait_for_rows("pending_plans", 1, TRADING_BOT_TIMEOUT).await;
+    let plan_id: i32 = sqlx::query_scalar("SELECT id FROM pending_plans ORDER BY id DESC LIMIT 1")
+        .fetch_one(&env.pool)
+        .await
+        .expect("Failed to read pending plan");
+    env.run(PLAN, &["approve", &plan_id.to_string()]);
+
+    let deadline = tokio::time::Instant::now() + TRADING_BOT_TIMEOUT;
+    let status = loop {
+        if let Some(status) = trading_bot.try_wait().expect("Failed to poll trading bot") {
+            break status;
+        }
+        if tokio::time::Instant::now() >= deadline {
+            common::stop(trading_bot);
+            panic!("Timed out waiting for the trading bot to finish");
+        }
+        tokio::time::sleep(Duration::from_secs(2)).await;
+    };
+    assert!(status.success(), "trading_bot exited with {}", status);
+
+    for table in [
+        "strategy_runs",
+        "strategy_run_markets",
+        "strategy_run_inputs",
+        "pending_plans",
+        "pending_plan_actions",
+        "execution_plans",
+        "execution_plan_actions",
+        "trades",
+        "execution_costs",
+        "rebalances",
+    ] {
+        assert!(env.count_rows(table).await > 0, "No rows recorded in {}", table);
+    }
+    let plan_status: String = sqlx::query_scalar("SELECT status FROM pending_plans WHERE id = $1")
+        .bind(plan_id)
+        .fetch_one(&env.pool)
+        .await
+        .expect("Failed to read plan status");
+    assert_eq!(plan_status, "Approved");
+
+    // The approved moves out of the seeded market were submitted on the fork, each with its execution cost
+    let submitted: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*) FROM trades t
+        JOIN execution_costs c ON c.tx_hash = t.tx_hash
+        WHERE t.action_type IN ('GmWithdrawal', 'GmShift') AND t.tx_hash IS NOT NULL
+        "#,
+    )
+    .fetch_one(&env.pool)
+    .await
+    .expect("Failed to read submitted trades");
+    assert!(submitted > 0, "No GM withdrawal or shift submitted with an execution cost");
+}
+
+/// Copy the latest collected market state and token price back in time at the collection interval,
+/// with prices gently oscillating so returns and volatility are non-degenerate
+async fn seed_history(env: &TestEnv) {
+    sqlx::query(
+        r#"
+        INSERT INTO market_states (
+            market_id, timestamp,
+            borrowing_factor_long, borrowing_factor_short,
+            pnl_long, pnl_short, pnl_net,
+            gm_price_min, gm_price_max, gm_price_mid,
+            pool_long_amount, pool_short_amount, pool_impact_amount,
+            pool_long_token_usd, pool_short_token_usd, pool_impact_token_usd,
+            open_interest_long, open_interest_short,
+            open_interest_long_amount, open_interest_short_amount,
+            open_interest_long_via_tokens, open_interest_short_via_tokens,
+            utilization, swap_volume, trading_volume,
+            fees_position, fees_liquidation, fees_swap, fees_borrowing, fees_total,
+            source
+        )
+        SELECT
+            s.market_id, s.timestamp - n * INTERVAL '5 minutes',
+            s.borrowing_factor_long, s.borrowing_factor_short,
+            s.pnl_long, s.pnl_short, s.pnl_net,
+            s.gm_price_min * (1 + 0.002 * sin(n)::NUMERIC),
+            s.gm_price_max * (1 + 0.002 * sin(n)::NUMERIC),
+            s.gm_price_mid * (1 + 0.002 * sin(n)::NUMERIC),
+            s.pool_long_amount, s.pool_short_amount, s.pool_impact_amount,
+            s.pool_long_token_usd, s.pool_short_token_usd, s.pool_impact_token_usd,
+            s.open_interest_long, s.open_interest_short,
+            s.open_interest_long_amount, s.open_interest_short_amount,
+            s.open_interest_long_via_tokens, s.open_interest_short_via_tokens,
+            s.utilization, s.swap_volume, s.trading_volume,
+            s.fees_position, s.fees_liquidation, s.fees_swap, s.fees_borrowing, s.fees_total,
+            'e2e_seed'
+        FROM (SELECT DISTINCT ON (market_id) * FROM market_states ORDER BY market_id, timestamp DESC) s
+        CROSS JOIN generate_series(1, $1) AS n
+        "#,
+    )
+    .bind(SEED_HISTORY_POINTS)
+    .execute(&env.pool)
+    .await
+    .expect("Failed to seed market state history");
+
+    sqlx::query(
+        r#"
+        INSERT INTO token_prices (token_id, timestamp, min_price, max_price, mid_price, source)
+        SELECT
+            p.token_id, p.timestamp - n * INTERVAL '5 minutes',
+            p.min_price * (1 + 0.003 * cos(n)::NUMERIC),
+            p.max_price * (1 + 0.003 * cos(n)::NUMERIC),
+            p.mid_price * (1 + 0.003 * cos(n)::NUMERIC),
+            'e2e_seed'
+        FROM (SELECT DISTINCT ON (token_id) * FROM token_prices ORDER BY token_id, timestamp DESC) p
+        CROSS JOIN generate_series(1, $1) AS n
+        "#,
+    )
+    .bind(SEED_HISTORY_POINTS)
+    .execute(&env.pool)
+    .await
+    .expect("Failed to seed token price history");
+}